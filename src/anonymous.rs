@@ -0,0 +1,60 @@
+use anyhow::Result;
+use serenity::all::{ChannelId, CommandDataOptionValue, CommandInteraction, Http, UserId};
+
+use crate::config::Project;
+use crate::store::Store;
+
+/// `anonymous` option of `/issue create`
+pub fn requested(command: &CommandInteraction) -> bool {
+    let Some(CommandDataOptionValue::SubCommand(options)) =
+        command.data.options.first().map(|o| &o.value)
+    else {
+        return false;
+    };
+    options
+        .iter()
+        .find(|o| o.name == "anonymous")
+        .and_then(|o| o.value.as_bool())
+        .unwrap_or(false)
+}
+
+/// The reporter to leave out of the thread's GitHub issue: whoever was
+/// recorded when the thread was filed anonymously, or the thread owner when
+/// the project hides every reporter
+pub async fn hidden_reporter(
+    discord: &Http,
+    store: &Store,
+    project: &Project,
+    thread_id: u64,
+) -> Result<Option<UserId>> {
+    if let Some(user_id) = store.anonymous_reporter(thread_id)? {
+        return Ok(Some(UserId::new(user_id)));
+    }
+    if !project.anonymous_reports() {
+        return Ok(None);
+    }
+    let thread = crate::discord_cache::channel(discord, ChannelId::new(thread_id)).await?;
+    Ok(thread.guild().and_then(|t| t.owner_id))
+}
+
+/// How an author appears on GitHub
+pub fn display_name(author_id: UserId, name: &str, hidden: Option<UserId>) -> &str {
+    if hidden == Some(author_id) {
+        crate::constants::ANONYMOUS_REPORTER_NAME
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_name() {
+        let reporter = UserId::new(1);
+        assert_eq!(display_name(reporter, "ana", Some(reporter)), "reporter");
+        assert_eq!(display_name(UserId::new(2), "bo", Some(reporter)), "bo");
+        assert_eq!(display_name(reporter, "ana", None), "ana");
+    }
+}
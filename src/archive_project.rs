@@ -0,0 +1,179 @@
+use anyhow::Result;
+use octocrab::params::State;
+use octocrab::Octocrab;
+use serenity::http::Http;
+use serenity::model::id::{ChannelId, GuildId};
+use tracing::info;
+
+use crate::audit::{AuditAction, AuditEntry, AuditLog};
+use crate::config::{Config, Project};
+use crate::store::Store;
+
+/// Final sweep of a project marked `archived = true`: lock and archive its
+/// forum threads and label its open linked issues, so both sides show the
+/// program is over. The links stay in the store.
+pub async fn archive_project(project_filter: &str, dry_run: bool) -> Result<()> {
+    let config = Config::load()?;
+    let projects = crate::threads::selected_projects(&config, Some(project_filter));
+    let Some(project) = projects.first() else {
+        anyhow::bail!("No project named '{project_filter}'");
+    };
+    if project.staging() {
+        anyhow::bail!("'{project_filter}' is a staging project, whose threads are never locked");
+    }
+    // A running bot would unlock threads of open issues right after the sweep
+    if !project.archived() {
+        anyhow::bail!(
+            "Set archived = true for '{project_filter}' and restart the bot before the final sweep"
+        );
+    }
+
+    println!(
+        "🗄️  Archiving project {}...",
+        project.name.as_deref().unwrap_or(&project.github_repo)
+    );
+    if dry_run {
+        println!("   (dry run - no changes will be made)");
+    }
+    println!();
+
+    let store = Store::open(&config)?;
+    let clients = crate::clients::Clients::new_standalone().await?;
+    let audit = crate::threads::cli_audit_log(&config, &clients)?;
+
+    for route in &project.routes() {
+        println!("  - GitHub: {}/{}", route.github_owner, route.github_repo);
+        let github = clients.github_for(&config, route).await?;
+        let count = label_open_issues(&github, &store, &audit, route, dry_run).await?;
+        let verb = if dry_run { "Would label" } else { "Labeled" };
+        println!("  ✅ {verb} {count} issues");
+    }
+
+    let discord = clients.discord_for(&config, project)?;
+    let count = lock_forum_threads(&discord, &audit, project, dry_run).await?;
+    let verb = if dry_run { "Would lock" } else { "Locked" };
+    println!("  ✅ {verb} {count} threads");
+
+    Ok(())
+}
+
+async fn label_open_issues(
+    github: &Octocrab,
+    store: &Store,
+    audit: &AuditLog,
+    project: &Project,
+    dry_run: bool,
+) -> Result<usize> {
+    let issues = github.issues(&project.github_owner, &project.github_repo);
+    let label = crate::constants::ARCHIVED_ISSUE_LABEL.to_string();
+    let mut linked = Vec::new();
+    let mut page: u32 = 1;
+    loop {
+        let batch = issues
+            .list()
+            .state(State::Open)
+            .per_page(100)
+            .page(page)
+            .send()
+            .await?;
+        for issue in &batch.items {
+            if issue.pull_request.is_some() || issue.labels.iter().any(|l| l.name == label) {
+                continue;
+            }
+            if let Some(thread_id) = crate::mappings::thread_for(store, project, issue) {
+                linked.push((issue.number, issue.title.clone(), thread_id));
+            }
+        }
+        if batch.next.is_none() {
+            break;
+        }
+        page += 1;
+    }
+
+    for (number, title, thread_id) in &linked {
+        if dry_run {
+            println!("  - Would label: #{number} {title}");
+            continue;
+        }
+        println!("  - Labeling: #{number} {title}");
+        issues
+            .add_labels(*number, std::slice::from_ref(&label))
+            .await?;
+        info!("Labeled issue #{} '{}'", number, label);
+        audit
+            .record(
+                AuditEntry::new(project, AuditAction::IssueUpdated)
+                    .thread(*thread_id)
+                    .issue(*number)
+                    .actor(crate::constants::AUDIT_ACTOR_CLI)
+                    .details(format!("labeled '{label}', project archived")),
+            )
+            .await;
+    }
+
+    Ok(linked.len())
+}
+
+async fn lock_forum_threads(
+    discord: &Http,
+    audit: &AuditLog,
+    project: &Project,
+    dry_run: bool,
+) -> Result<usize> {
+    let guild_id = GuildId::new(project.discord_guild_id.parse()?);
+    let forum_id = ChannelId::new(project.discord_forum_id.parse()?);
+
+    let threads = guild_id.get_active_threads(discord).await?;
+    let mut locked_count = 0;
+
+    for thread in threads
+        .threads
+        .iter()
+        .filter(|t| t.parent_id == Some(forum_id))
+    {
+        let is_locked = thread
+            .thread_metadata
+            .as_ref()
+            .map(|m| m.locked)
+            .unwrap_or(false);
+        if is_locked {
+            continue;
+        }
+
+        if dry_run {
+            println!("  - Would lock: {} ({})", thread.name, thread.id);
+        } else {
+            println!("  - Locking: {} ({})", thread.name, thread.id);
+            thread
+                .id
+                .send_message(
+                    discord,
+                    serenity::builder::CreateMessage::new()
+                        .content(crate::constants::MSG_PROJECT_ARCHIVED),
+                )
+                .await?;
+            thread
+                .id
+                .edit_thread(
+                    discord,
+                    serenity::builder::EditThread::new()
+                        .locked(true)
+                        .archived(true),
+                )
+                .await?;
+            info!("Locked thread {} of archived project", thread.id);
+            audit
+                .record(
+                    AuditEntry::new(project, AuditAction::ThreadLocked)
+                        .thread(thread.id.get())
+                        .actor(crate::constants::AUDIT_ACTOR_CLI)
+                        .details("project archived"),
+                )
+                .await;
+        }
+
+        locked_count += 1;
+    }
+
+    Ok(locked_count)
+}
@@ -3,7 +3,7 @@ use serenity::http::Http;
 use serenity::model::id::{ChannelId, GuildId};
 use tracing::info;
 
-use crate::config::Config;
+use crate::config::{ChatPlatform, Config};
 
 pub async fn archive_locked_threads() -> Result<()> {
     println!("🧹 Archiving locked threads with configured prefixes...\n");
@@ -11,18 +11,18 @@ pub async fn archive_locked_threads() -> Result<()> {
     // Load configuration
     let config = Config::load()?;
 
-    println!(
-        "Thread prefixes to check: {:?}",
-        crate::constants::THREAD_PREFIXES
-    );
-    println!();
-
     // Use shared clients
     let clients = crate::clients::Clients::new_standalone().await?;
-    let discord = &clients.discord_http;
+    let audit = crate::threads::cli_audit_log(&config, &clients)?;
 
     // Process each project
-    for (idx, project) in config.projects.iter().enumerate() {
+    // Matrix threads can't be archived
+    for (idx, project) in config
+        .projects
+        .iter()
+        .filter(|p| p.platform() == ChatPlatform::Discord)
+        .enumerate()
+    {
         println!(
             "Project {}: {}",
             idx + 1,
@@ -30,8 +30,14 @@ pub async fn archive_locked_threads() -> Result<()> {
         );
         println!("  - Discord Guild: {}", project.discord_guild_id);
         println!("  - Discord Forum: {}", project.discord_forum_id);
+        println!("  - Thread prefixes: {}", project.prefix_list());
+        if project.staging() {
+            println!("  ⏭️  Skipped: staging projects never archive threads\n");
+            continue;
+        }
 
-        match archive_project_threads(discord, project).await {
+        let discord = clients.discord_for(&config, project)?;
+        match archive_project_threads(&discord, &audit, project).await {
             Ok(count) => {
                 println!("  ✅ Archived {count} locked threads");
             }
@@ -47,6 +53,7 @@ pub async fn archive_locked_threads() -> Result<()> {
 
 async fn archive_project_threads(
     discord: &Http,
+    audit: &crate::audit::AuditLog,
     project: &crate::config::Project,
 ) -> Result<usize> {
     let guild_id = GuildId::new(project.discord_guild_id.parse()?);
@@ -64,9 +71,7 @@ async fn archive_project_threads(
 
         // Check if thread has valid prefix
         let thread_name = &thread.name;
-        let has_valid_prefix = crate::constants::THREAD_PREFIXES
-            .iter()
-            .any(|prefix| thread_name.starts_with(prefix));
+        let has_valid_prefix = project.has_valid_prefix(thread_name);
 
         if !has_valid_prefix {
             continue;
@@ -91,6 +96,16 @@ async fn archive_project_threads(
 
             archived_count += 1;
             info!("Archived locked thread {} ({})", thread.id, thread_name);
+            audit
+                .record(
+                    crate::audit::AuditEntry::new(
+                        project,
+                        crate::audit::AuditAction::ThreadArchived,
+                    )
+                    .thread(thread.id.get())
+                    .actor(crate::constants::AUDIT_ACTOR_CLI),
+                )
+                .await;
         }
     }
 
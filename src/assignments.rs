@@ -0,0 +1,139 @@
+use anyhow::Result;
+use octocrab::models::issues::Issue;
+use octocrab::models::Author;
+use serenity::builder::{CreateAllowedMentions, CreateEmbed, CreateEmbedAuthor, CreateMessage};
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+
+use crate::store::Store;
+
+/// Tell a thread who picked up its issue, comparing the issue's assignees
+/// with the ones seen on the previous sync. The first look at an issue only
+/// records them, so turning the option on doesn't announce old assignments.
+pub async fn check_assignees(
+    discord: &Http,
+    store: &Store,
+    issue: &Issue,
+    thread_id: u64,
+) -> Result<()> {
+    let mut current: Vec<String> = issue.assignees.iter().map(|a| a.login.clone()).collect();
+    current.sort();
+
+    let Some(previous) = store.issue_assignees(thread_id)? else {
+        store.set_issue_assignees(thread_id, &current)?;
+        return Ok(());
+    };
+    let Some(text) = describe(&previous, &current) else {
+        return Ok(());
+    };
+
+    // Show whoever the change is about: the first new assignee, else the first removed
+    let face = match issue
+        .assignees
+        .iter()
+        .find(|a| !previous.contains(&a.login))
+    {
+        Some(assignee) => Some(author(assignee)),
+        None => previous
+            .iter()
+            .find(|l| !current.contains(l))
+            .map(|login| CreateEmbedAuthor::new(format!("@{login}"))),
+    };
+    let mut embed = CreateEmbed::new()
+        .description(text)
+        .color(crate::constants::COLOR_SUCCESS);
+    if let Some(face) = face {
+        embed = embed.author(face);
+    }
+
+    ChannelId::new(thread_id)
+        .send_message(
+            discord,
+            CreateMessage::new()
+                .embed(embed)
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
+    store.set_issue_assignees(thread_id, &current)?;
+    Ok(())
+}
+
+fn author(assignee: &Author) -> CreateEmbedAuthor {
+    CreateEmbedAuthor::new(format!("@{}", assignee.login))
+        .icon_url(assignee.avatar_url.as_str())
+        .url(assignee.html_url.as_str())
+}
+
+/// Message for a change of assignees, None when nothing changed
+fn describe(previous: &[String], current: &[String]) -> Option<String> {
+    let added: Vec<&String> = current.iter().filter(|l| !previous.contains(l)).collect();
+    let removed: Vec<&String> = previous.iter().filter(|l| !current.contains(l)).collect();
+
+    match (added.is_empty(), removed.is_empty()) {
+        (true, true) => None,
+        (false, true) => Some(format!(
+            "🔧 {} {} now working on this",
+            logins(&added),
+            if added.len() == 1 { "is" } else { "are" }
+        )),
+        (true, false) if current.is_empty() => Some(format!(
+            "⏸️ {} {} no longer working on this; it's waiting for someone to pick it up",
+            logins(&removed),
+            if removed.len() == 1 { "is" } else { "are" }
+        )),
+        (true, false) => Some(format!(
+            "🔧 {} stepped off; {} still on it",
+            logins(&removed),
+            logins(&current.iter().collect::<Vec<_>>())
+        )),
+        (false, false) => Some(format!(
+            "🔁 Reassigned from {} to {}",
+            logins(&removed),
+            logins(&added)
+        )),
+    }
+}
+
+/// "@a", "@a and @b", "@a, @b and @c"
+fn logins(logins: &[&String]) -> String {
+    let names: Vec<String> = logins.iter().map(|l| format!("**@{l}**")).collect();
+    match names.split_last() {
+        None => String::new(),
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} and {last}", rest.join(", ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(logins: &[&str]) -> Vec<String> {
+        logins.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(describe(&v(&["a"]), &v(&["a"])), None);
+        assert_eq!(
+            describe(&v(&[]), &v(&["alice"])).unwrap(),
+            "🔧 **@alice** is now working on this"
+        );
+        assert_eq!(
+            describe(&v(&["alice"]), &v(&["alice", "bob", "carol"])).unwrap(),
+            "🔧 **@bob** and **@carol** are now working on this"
+        );
+        assert_eq!(
+            describe(&v(&["alice"]), &v(&[])).unwrap(),
+            "⏸️ **@alice** is no longer working on this; it's waiting for someone to pick it up"
+        );
+        assert_eq!(
+            describe(&v(&["alice", "bob"]), &v(&["bob"])).unwrap(),
+            "🔧 **@alice** stepped off; **@bob** still on it"
+        );
+        assert_eq!(
+            describe(&v(&["alice"]), &v(&["bob"])).unwrap(),
+            "🔁 Reassigned from **@alice** to **@bob**"
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use anyhow::{Context, Result};
+use serenity::builder::GetMessages;
+use serenity::http::Http;
+use serenity::model::channel::{Attachment, GuildChannel, Message};
+use serenity::model::id::ChannelId;
+use tracing::{info, warn};
+
+use crate::config::{AttachmentStoreConfig, Config, Project};
+use crate::object_store::ObjectStore;
+use crate::store::Store;
+
+/// List the thread's attachments for the issue body, or None without any.
+///
+/// Attachments of at least `min_size_mb` are copied to the object store and
+/// linked from there, unless the project opted out; smaller ones link to
+/// Discord. Individual failures are logged and listed by name only.
+pub async fn section(
+    config: &AttachmentStoreConfig,
+    project: &Project,
+    discord: &Http,
+    thread: &GuildChannel,
+    store: &Store,
+) -> Option<String> {
+    let messages = match thread
+        .messages(
+            discord,
+            GetMessages::new().limit(crate::constants::GITHUB_THREAD_CONTENT_LIMIT),
+        )
+        .await
+    {
+        Ok(messages) => messages,
+        Err(e) => {
+            warn!(
+                "Failed to fetch messages for attachments in {}: {}",
+                thread.id, e
+            );
+            return None;
+        }
+    };
+
+    messages_section(config, project, store, thread.id, &messages).await
+}
+
+/// The attachments section for messages fetched elsewhere, newest first
+pub async fn messages_section(
+    config: &AttachmentStoreConfig,
+    project: &Project,
+    store: &Store,
+    channel_id: ChannelId,
+    messages: &[Message],
+) -> Option<String> {
+    let attachments: Vec<&Attachment> = messages
+        .iter()
+        .rev()
+        .flat_map(|m| m.attachments.iter())
+        .take(crate::constants::MAX_ISSUE_ATTACHMENTS)
+        .collect();
+    if attachments.is_empty() {
+        return None;
+    }
+
+    let object_store = if project.rehost_attachments() {
+        match ObjectStore::from_env(config) {
+            Ok(object_store) => Some(object_store),
+            Err(e) => {
+                warn!("Attachments can't be re-hosted: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let mut lines = Vec::new();
+    for attachment in attachments {
+        let name = attachment.filename.replace('[', "\\[").replace(']', "\\]");
+        let bytes = u64::from(attachment.size);
+        let size = format_size(bytes);
+        if bytes < config.min_size_bytes() || bytes > config.max_size_bytes() {
+            lines.push(format!("- [{name}]({}) ({size})", attachment.url));
+            continue;
+        }
+        let Some(object_store) = &object_store else {
+            lines.push(format!("- {name} ({size}, too large to include)"));
+            continue;
+        };
+        match rehost(object_store, config, store, channel_id, attachment).await {
+            Ok((url, expires_at)) => {
+                let until = chrono::DateTime::from_timestamp(expires_at, 0)
+                    .map(|t| t.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default();
+                lines.push(format!(
+                    "- [{name}]({url}) ({size}, available until {until})"
+                ));
+            }
+            Err(e) => {
+                warn!("Failed to re-host {}: {}", attachment.filename, e);
+                lines.push(format!("- {name} ({size}, too large to include)"));
+            }
+        }
+    }
+
+    Some(format!(
+        "<details>\n<summary>Attachments</summary>\n\n{}\n\n</details>",
+        lines.join("\n")
+    ))
+}
+
+/// Upload an attachment once and return its URL and expiry; refreshing the
+/// issue reuses the copy until it expires
+async fn rehost(
+    object_store: &ObjectStore<'_>,
+    config: &AttachmentStoreConfig,
+    store: &Store,
+    channel_id: ChannelId,
+    attachment: &Attachment,
+) -> Result<(String, i64)> {
+    if let Some(hosted) = store.rehosted_attachment(attachment.id.get())? {
+        return Ok(hosted);
+    }
+
+    let key = object_key(channel_id.get(), attachment.id.get(), &attachment.filename);
+    let body = object_store
+        .download(&attachment.url)
+        .await
+        .context("Failed to download attachment")?;
+    object_store
+        .put(
+            &key,
+            attachment
+                .content_type
+                .as_deref()
+                .unwrap_or("application/octet-stream"),
+            body,
+        )
+        .await?;
+
+    let url = object_store.public_url(&key);
+    let expires_at = chrono::Utc::now().timestamp() + (config.expiry_days() * 24 * 60 * 60) as i64;
+    store.record_rehosted_attachment(attachment.id.get(), &key, &url, expires_at)?;
+    info!(
+        "Re-hosted {} ({} bytes) from channel {}",
+        attachment.filename, attachment.size, channel_id
+    );
+    Ok((url, expires_at))
+}
+
+/// Delete re-hosted attachments past their expiry. Failed deletions stay in
+/// the store and are retried on the next sync cycle.
+pub async fn delete_expired(config: &Config, store: &Store) {
+    let Some(attachment_config) = &config.attachment_store else {
+        return;
+    };
+    let expired = match store.expired_attachments() {
+        Ok(expired) if !expired.is_empty() => expired,
+        Ok(_) => return,
+        Err(e) => {
+            warn!("Failed to read expired attachments: {}", e);
+            return;
+        }
+    };
+    let object_store = match ObjectStore::from_env(attachment_config) {
+        Ok(object_store) => object_store,
+        Err(e) => {
+            warn!("Expired attachments can't be deleted: {}", e);
+            return;
+        }
+    };
+
+    for (attachment_id, key) in expired {
+        match object_store.delete(&key).await {
+            Ok(()) => {
+                if let Err(e) = store.forget_rehosted_attachment(attachment_id) {
+                    warn!("Failed to forget attachment {}: {}", attachment_id, e);
+                }
+                info!("Deleted expired attachment {}", key);
+            }
+            Err(e) => warn!("Failed to delete expired attachment {}: {}", key, e),
+        }
+    }
+}
+
+/// Object key for an attachment, with the filename reduced to characters
+/// that need no URL encoding
+fn object_key(thread_id: u64, attachment_id: u64, filename: &str) -> String {
+    let name: String = filename
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{thread_id}/{attachment_id}/{name}")
+}
+
+fn format_size(bytes: u64) -> String {
+    const MB: u64 = 1024 * 1024;
+    if bytes >= MB {
+        format!("{:.1} MB", bytes as f64 / MB as f64)
+    } else {
+        format!("{} KB", bytes.div_ceil(1024))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_object_key() {
+        assert_eq!(
+            object_key(1, 2, "crash log (1).txt"),
+            "1/2/crash_log__1_.txt"
+        );
+        assert_eq!(object_key(1, 2, "écran.png"), "1/2/_cran.png");
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(500), "1 KB");
+        assert_eq!(format_size(20 * 1024 * 1024 + 512 * 1024), "20.5 MB");
+    }
+}
@@ -0,0 +1,253 @@
+use anyhow::Result;
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use std::fmt;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::config::{Config, Project};
+use crate::store::Store;
+
+/// Kinds of changes the bot makes on GitHub or Discord
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    IssueCreated,
+    IssueUpdated,
+    IssueClosed,
+    IssueReopened,
+    IssueRenamed,
+    IssuePrioritized,
+    ThreadLocked,
+    ThreadUnlocked,
+    ThreadArchived,
+    ThreadPinned,
+    TriageDecision,
+    SyncSuspended,
+    ThreadMerged,
+    /// A child issue filed by `/issue split`
+    IssueSplit,
+    /// The thread's issue moved to another repository by `/issue move`
+    IssueMoved,
+    ThreadRenamed,
+    ThreadCreated,
+    /// A mismatch the syncer can't fix itself, such as an issue whose thread is gone
+    DriftDetected,
+    DriftEscalated,
+    DriftResolved,
+    /// A linked issue went past its SLA target without a maintainer response
+    SlaBreached,
+    /// First maintainer response to an issue with an SLA target
+    SlaResponded,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::IssueCreated => "issue_created",
+            Self::IssueUpdated => "issue_updated",
+            Self::IssueClosed => "issue_closed",
+            Self::IssueReopened => "issue_reopened",
+            Self::IssueRenamed => "issue_renamed",
+            Self::IssuePrioritized => "issue_prioritized",
+            Self::ThreadLocked => "thread_locked",
+            Self::ThreadUnlocked => "thread_unlocked",
+            Self::ThreadArchived => "thread_archived",
+            Self::ThreadPinned => "thread_pinned",
+            Self::TriageDecision => "triage_decision",
+            Self::SyncSuspended => "sync_suspended",
+            Self::ThreadMerged => "thread_merged",
+            Self::IssueSplit => "issue_split",
+            Self::IssueMoved => "issue_moved",
+            Self::ThreadRenamed => "thread_renamed",
+            Self::ThreadCreated => "thread_created",
+            Self::DriftDetected => "drift_detected",
+            Self::DriftEscalated => "drift_escalated",
+            Self::DriftResolved => "drift_resolved",
+            Self::SlaBreached => "sla_breached",
+            Self::SlaResponded => "sla_responded",
+        }
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One recorded mutation
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: i64,
+    pub project: String,
+    pub action: String,
+    pub thread_id: Option<u64>,
+    pub issue_number: Option<u64>,
+    pub actor: String,
+    pub details: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(project: &Project, action: AuditAction) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp(),
+            project: project_key(project),
+            action: action.to_string(),
+            thread_id: None,
+            issue_number: None,
+            actor: crate::constants::AUDIT_ACTOR_BOT.to_string(),
+            details: None,
+        }
+    }
+
+    pub fn thread(mut self, thread_id: u64) -> Self {
+        self.thread_id = Some(thread_id);
+        self
+    }
+
+    pub fn issue(mut self, issue_number: u64) -> Self {
+        self.issue_number = Some(issue_number);
+        self
+    }
+
+    pub fn actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = actor.into();
+        self
+    }
+
+    pub fn details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    pub fn summary(&self) -> String {
+        let mut parts = vec![format!("[{}] {}", self.project, self.action)];
+        if let Some(issue) = self.issue_number {
+            parts.push(format!("issue #{issue}"));
+        }
+        if let Some(thread) = self.thread_id {
+            parts.push(format!("thread {thread}"));
+        }
+        parts.push(format!("by {}", self.actor));
+        if let Some(details) = &self.details {
+            parts.push(format!("- {details}"));
+        }
+        parts.join(" ")
+    }
+}
+
+/// Filters for querying the audit log
+#[derive(Debug, Default)]
+pub struct AuditQuery {
+    pub project: Option<String>,
+    pub thread_id: Option<u64>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub limit: usize,
+}
+
+/// Stable identifier for a project in audit records
+pub fn project_key(project: &Project) -> String {
+    format!("{}/{}", project.github_owner, project.github_repo)
+}
+
+/// Records mutations to the store and, optionally, a Discord audit channel
+pub struct AuditLog {
+    store: Arc<Store>,
+    channel: Option<(Arc<Http>, ChannelId)>,
+}
+
+impl AuditLog {
+    pub fn new(store: Arc<Store>, discord: Option<Arc<Http>>, config: &Config) -> Self {
+        let channel = config
+            .audit_channel_id
+            .as_deref()
+            .and_then(|id| id.parse::<u64>().ok())
+            .zip(discord)
+            .map(|(id, http)| (http, ChannelId::new(id)));
+
+        Self { store, channel }
+    }
+
+    /// Record an entry; failures are logged rather than interrupting the mutation flow
+    pub async fn record(&self, entry: AuditEntry) {
+        if let Err(e) = self.store.insert_audit(&entry) {
+            warn!("Failed to write audit entry '{}': {}", entry.summary(), e);
+        }
+
+        if let Some((http, channel_id)) = &self.channel {
+            let message = serenity::builder::CreateMessage::new()
+                .content(format!("📝 {}", entry.summary()))
+                .allowed_mentions(serenity::builder::CreateAllowedMentions::new());
+            if let Err(e) = channel_id.send_message(http, message).await {
+                warn!(
+                    "Failed to post audit entry to channel {}: {}",
+                    channel_id, e
+                );
+            }
+        }
+    }
+}
+
+/// CLI entry point: print audit entries matching the filters
+pub async fn print_audit_log(
+    project: Option<String>,
+    thread_id: Option<u64>,
+    since: Option<String>,
+    until: Option<String>,
+    limit: usize,
+) -> Result<()> {
+    let config = Config::load()?;
+    let store = Store::open(&config)?;
+
+    let project = project.map(|filter| filter_key(&config, filter));
+
+    let query = AuditQuery {
+        project,
+        thread_id,
+        since: since.as_deref().map(parse_time).transpose()?,
+        until: until.as_deref().map(parse_time).transpose()?,
+        limit,
+    };
+
+    let entries = store.query_audit(&query)?;
+    if entries.is_empty() {
+        println!("No audit entries found");
+        return Ok(());
+    }
+
+    for entry in entries {
+        let time = chrono::DateTime::from_timestamp(entry.timestamp, 0)
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+        println!("{time}  {}", entry.summary());
+    }
+
+    Ok(())
+}
+
+/// Audit key for a `--project` filter, which accepts either a project name
+/// or its repository
+pub fn filter_key(config: &Config, filter: String) -> String {
+    config
+        .projects
+        .iter()
+        .find(|p| p.name.as_deref() == Some(filter.as_str()) || p.github_repo == filter)
+        .map(project_key)
+        .unwrap_or(filter)
+}
+
+/// Parse an RFC 3339 timestamp or a plain YYYY-MM-DD date (UTC midnight)
+pub fn parse_time(value: &str) -> Result<i64> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(time.timestamp());
+    }
+
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("Invalid time '{value}', expected YYYY-MM-DD or RFC 3339"))?;
+    Ok(date
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc()
+        .timestamp())
+}
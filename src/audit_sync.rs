@@ -4,31 +4,33 @@ use serenity::http::Http;
 use serenity::model::id::{ChannelId, GuildId};
 use std::collections::HashSet;
 
-use crate::config::Config;
-use crate::sync::extract_thread_id;
+use crate::config::{ChatPlatform, Config};
+use crate::mappings::Mapping;
+use crate::store::Store;
 
 pub async fn audit_sync_status() -> Result<()> {
     println!("🔍 Auditing sync status between GitHub and Discord...\n");
 
     // Load configuration
     let config = Config::load()?;
+    let store = Store::open(&config)?;
     let sync_config = config.sync_config();
 
     println!("Sync Configuration:");
     println!("  - Enabled: {}", sync_config.enabled);
-    println!(
-        "  - Thread prefixes: {:?}",
-        crate::constants::THREAD_PREFIXES
-    );
     println!();
 
     // Use shared clients
     let clients = crate::clients::Clients::new_standalone().await?;
-    let github = &clients.github;
-    let discord = &clients.discord_http;
 
     // Audit each project
-    for (idx, project) in config.projects.iter().enumerate() {
+    // Matrix threads have no forum to compare against
+    for (idx, project) in config
+        .projects
+        .iter()
+        .filter(|p| p.platform() == ChatPlatform::Discord)
+        .enumerate()
+    {
         println!(
             "Project {}: {}",
             idx + 1,
@@ -40,9 +42,13 @@ pub async fn audit_sync_status() -> Result<()> {
         );
         println!("  - Discord Guild: {}", project.discord_guild_id);
         println!("  - Discord Forum: {}", project.discord_forum_id);
+        println!("  - Thread prefixes: {}", project.prefix_list());
+        println!("  - Mapping mode: {:?}", project.mapping_mode());
         println!();
 
-        match audit_project(github, discord, project).await {
+        let github = clients.github_for(&config, project).await?;
+        let discord = clients.discord_for(&config, project)?;
+        match audit_project(&github, &discord, &store, project).await {
             Ok(()) => {}
             Err(e) => {
                 eprintln!("  ❌ Error auditing project: {e}");
@@ -57,6 +63,7 @@ pub async fn audit_sync_status() -> Result<()> {
 async fn audit_project(
     github: &Octocrab,
     discord: &Http,
+    store: &Store,
     project: &crate::config::Project,
 ) -> Result<()> {
     // Get all open GitHub issues with thread IDs
@@ -71,11 +78,16 @@ async fn audit_project(
         .send()
         .await?;
 
+    // Links as read from both the issues and the database
+    let mut mappings = Vec::new();
+    for issue in &search_result.items {
+        mappings.push((issue, Mapping::lookup(store, project, issue)?));
+    }
+
     // Build set of open issue thread IDs
-    let github_open_threads: HashSet<u64> = search_result
-        .items
+    let github_open_threads: HashSet<u64> = mappings
         .iter()
-        .filter_map(|issue| extract_thread_id(&issue.title))
+        .filter_map(|(_, mapping)| mapping.thread_id(project.mapping_mode()))
         .collect();
 
     println!("  📊 GitHub Status:");
@@ -161,10 +173,9 @@ async fn audit_project(
             println!("    (These threads may have been deleted or archived)");
             if missing_threads.len() <= 5 {
                 for &&thread_id in &missing_threads {
-                    if let Some(issue) = search_result
-                        .items
+                    if let Some((issue, _)) = mappings
                         .iter()
-                        .find(|i| extract_thread_id(&i.title) == Some(thread_id))
+                        .find(|(_, m)| m.thread_id(project.mapping_mode()) == Some(thread_id))
                     {
                         println!("      - Issue #{}: {}", issue.number, issue.title);
                     }
@@ -173,6 +184,24 @@ async fn audit_project(
         }
     }
 
+    let discrepancies: Vec<_> = mappings
+        .iter()
+        .filter_map(|(issue, mapping)| Some((issue, mapping.discrepancy()?)))
+        .collect();
+    println!("\n  🔗 Mapping Analysis:");
+    if discrepancies.is_empty() {
+        println!("    ✅ Issue markers and the database agree");
+    } else {
+        println!(
+            "    ⚠️  {} open issues disagree between markers and the database:",
+            discrepancies.len()
+        );
+        for (issue, discrepancy) in &discrepancies {
+            println!("      - Issue #{}: {}", issue.number, discrepancy);
+        }
+        println!("    (`cardibot migrate-mappings` backfills missing database links)");
+    }
+
     println!("\n  📝 Summary:");
     println!("    - Sync only manages threads where CardiBot created a GitHub issue");
     println!("    - Other Discord threads (even with [BUG] prefix) are ignored");
@@ -0,0 +1,177 @@
+use anyhow::Result;
+use serenity::builder::CreateMessage;
+use serenity::http::Http;
+use serenity::model::channel::{GuildChannel, Message, ReactionType};
+use std::collections::HashSet;
+use tracing::{debug, info};
+
+use crate::audit::AuditLog;
+use crate::config::{AutoCreateConfig, Config, Project};
+use crate::store::Store;
+
+/// How much engagement a thread has drawn
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ThreadActivity {
+    pub messages: usize,
+    pub participants: usize,
+    /// 👍 reactions on the starter message
+    pub reactions: usize,
+}
+
+pub fn thread_activity(thread: &GuildChannel, messages: &[Message]) -> ThreadActivity {
+    let human: Vec<&Message> = messages.iter().filter(|m| !m.author.bot).collect();
+    let participants: HashSet<_> = human.iter().map(|m| m.author.id).collect();
+
+    let reactions = messages
+        .iter()
+        .find(|m| m.id == crate::discord_cache::starter_id(thread.id))
+        .and_then(|starter| {
+            starter
+                .reactions
+                .iter()
+                .find(|r| matches!(&r.reaction_type, ReactionType::Unicode(emoji) if emoji == "👍"))
+        })
+        .map(|r| r.count as usize)
+        .unwrap_or(0);
+
+    ThreadActivity {
+        messages: human.len(),
+        participants: participants.len(),
+        reactions,
+    }
+}
+
+/// Any configured threshold being reached is enough
+pub fn meets_threshold(config: &AutoCreateConfig, activity: &ThreadActivity) -> bool {
+    [
+        config.min_messages.map(|min| activity.messages >= min),
+        config
+            .min_participants
+            .map(|min| activity.participants >= min),
+        config.min_reactions.map(|min| activity.reactions >= min),
+    ]
+    .into_iter()
+    .flatten()
+    .any(|met| met)
+}
+
+/// File an issue for a prefixed thread without one once it draws enough
+/// activity, or nudge the reporter if it stays quiet
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_unlinked_thread(
+    discord: &Http,
+    config: &Config,
+    project: &Project,
+    thread: &GuildChannel,
+    messages: &[Message],
+    store: &Store,
+    audit: &AuditLog,
+    paused: bool,
+) -> Result<()> {
+    let Some(auto_create) = &project.auto_create else {
+        return Ok(());
+    };
+    // Linked already, filed as a discussion or project item, or a triage
+    // decision is pending or was made
+    if store.thread_issue(thread.id.get())?.is_some()
+        || crate::filing::filed_elsewhere_url(messages).is_some()
+        || store
+            .triage_status(thread.id.get())?
+            .is_some_and(|s| s != crate::triage::STATUS_PENDING)
+    {
+        return Ok(());
+    }
+
+    let activity = thread_activity(thread, messages);
+    if meets_threshold(auto_create, &activity) {
+        if paused {
+            info!("Would auto-create issue for thread {} (paused)", thread.id);
+            return Ok(());
+        }
+        if project.triage_queue() {
+            return crate::triage::queue_candidate(discord, project, thread, &activity, store)
+                .await;
+        }
+        if !store.try_lock_thread(thread.id.get(), crate::constants::THREAD_LOCK_TTL_SECS)? {
+            return Ok(());
+        }
+
+        info!(
+            "Thread {} reached auto-create threshold ({:?})",
+            thread.id, activity
+        );
+        let result = crate::filing::file_thread(
+            discord,
+            config,
+            project,
+            thread,
+            store,
+            audit,
+            crate::constants::AUDIT_ACTOR_BOT,
+            &Default::default(),
+            crate::progress::Progress::Silent,
+        )
+        .await;
+        store.unlock_thread(thread.id.get())?;
+        result?;
+        return Ok(());
+    }
+
+    // Below threshold: nudge once the thread has had time to gather replies
+    let age_hours =
+        (chrono::Utc::now().timestamp() - thread.id.created_at().unix_timestamp()) / 3600;
+    let nudge_after = auto_create
+        .nudge_after_hours
+        .unwrap_or(crate::constants::DEFAULT_AUTO_CREATE_NUDGE_HOURS);
+    let already_nudged = messages.iter().any(|m| {
+        m.author.bot
+            && m.content
+                .starts_with(crate::constants::MSG_AUTO_CREATE_NUDGE)
+    });
+
+    if age_hours >= nudge_after as i64 && !already_nudged && !paused && !project.staging() {
+        thread
+            .id
+            .send_message(
+                discord,
+                CreateMessage::new().content(format!(
+                    "{} Add steps to reproduce, screenshots or logs, and react with 👍 if this affects you too.",
+                    crate::constants::MSG_AUTO_CREATE_NUDGE
+                )),
+            )
+            .await?;
+        debug!("Nudged quiet thread {}", thread.id);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meets_threshold() {
+        let config = AutoCreateConfig {
+            min_messages: Some(5),
+            min_participants: Some(3),
+            min_reactions: None,
+            nudge_after_hours: None,
+        };
+        let quiet = ThreadActivity {
+            messages: 4,
+            participants: 1,
+            reactions: 10,
+        };
+        assert!(!meets_threshold(&config, &quiet));
+
+        let busy = ThreadActivity {
+            messages: 2,
+            participants: 3,
+            reactions: 0,
+        };
+        assert!(meets_threshold(&config, &busy));
+
+        assert!(!meets_threshold(&AutoCreateConfig::default(), &busy));
+    }
+}
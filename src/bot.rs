@@ -1,39 +1,477 @@
 use serenity::{all::*, async_trait, model::gateway::Ready};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
+use crate::constants::INTERACTION_QUICK_TIMEOUT_SECS;
+use crate::jobs::Job;
+use crate::router::Router;
+
+#[derive(Clone)]
 pub struct Bot {
     pub config: Arc<crate::config::Config>,
+    pub store: Arc<crate::store::Store>,
+    pub cooldowns: Arc<crate::cooldown::CommandCooldowns>,
+    pub audit: Arc<crate::audit::AuditLog>,
+    pub gateway: Arc<crate::gateway::GatewayHealth>,
+    /// Gateway events whose handling calls GitHub, run by worker tasks
+    pub jobs: Arc<crate::jobs::JobQueue>,
+    /// This bot's own user, known once the gateway is ready
+    pub user_id: Arc<OnceLock<UserId>>,
 }
 
 #[async_trait]
 impl EventHandler for Bot {
     async fn ready(&self, ctx: Context, ready: Ready) {
         tracing::info!("Bot is ready as {}", ready.user.name);
+        let _ = self.user_id.set(ready.user.id);
+
+        let guilds: Vec<GuildId> = ready.guilds.iter().map(|g| g.id).collect();
+        self.register_commands(&ctx.http, &guilds).await;
+    }
 
-        // Register slash commands
-        let commands = vec![crate::commands::create_issue_command()];
+    async fn resume(&self, _ctx: Context, _event: ResumedEvent) {
+        self.gateway.record_resume();
+    }
+
+    async fn shard_stage_update(&self, _ctx: Context, event: ShardStageUpdateEvent) {
+        self.gateway.record_stage(event.old, event.new);
+    }
 
-        for guild in &ready.guilds {
-            let commands_builder = guild.id.set_commands(&ctx.http, commands.clone()).await;
+    async fn thread_create(&self, _ctx: Context, thread: GuildChannel) {
+        if crate::thread_events::project_for_thread(&self.config, &thread).is_some() {
+            self.queue(Job::ThreadCreate {
+                thread_id: thread.id.get(),
+            });
+        }
+    }
+
+    async fn thread_update(&self, _ctx: Context, old: Option<GuildChannel>, new: GuildChannel) {
+        crate::discord_cache::invalidate_channel(new.id);
+        // Tag edits and the like leave nothing to carry over
+        let state = |thread: &GuildChannel| {
+            let metadata = thread.thread_metadata.as_ref();
+            (
+                thread.name.clone(),
+                metadata.is_some_and(|m| m.locked),
+                metadata.is_some_and(|m| m.archived),
+            )
+        };
+        if old.is_some_and(|old| state(&old) == state(&new)) || !self.has_issue(new.id) {
+            return;
+        }
+        self.queue(Job::ThreadUpdate {
+            thread_id: new.id.get(),
+        });
+    }
 
-            if let Err(e) = commands_builder {
-                tracing::error!("Failed to register commands for guild {}: {}", guild.id, e);
+    async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
+        if let Err(e) =
+            crate::needs_info::handle_reaction(&ctx.http, &reaction, &self.config, &self.store)
+                .await
+        {
+            tracing::error!(
+                "Error handling reaction in {}: {:?}",
+                reaction.channel_id,
+                e
+            );
+        }
+        self.count_reactions(&ctx.http, &reaction).await;
+    }
+
+    async fn reaction_remove(&self, ctx: Context, reaction: Reaction) {
+        self.count_reactions(&ctx.http, &reaction).await;
+    }
+
+    async fn message(&self, ctx: Context, message: Message) {
+        let Some(bot_id) = self.user_id.get() else {
+            return;
+        };
+        if let Err(e) = crate::mentions::handle_message(
+            &ctx.http,
+            &self.config,
+            &self.store,
+            &self.cooldowns,
+            &self.audit,
+            *bot_id,
+            &message,
+        )
+        .await
+        {
+            tracing::error!("Error answering mention {}: {:?}", message.id, e);
+        }
+    }
+
+    async fn message_update(
+        &self,
+        _ctx: Context,
+        _old: Option<Message>,
+        _new: Option<Message>,
+        event: MessageUpdateEvent,
+    ) {
+        // Embed unfurls arrive as updates without content
+        if event.content.is_none() || !self.has_issue(event.channel_id) {
+            return;
+        }
+        self.queue(Job::MessageChange {
+            channel_id: event.channel_id.get(),
+            message_id: event.id.get(),
+        });
+    }
+
+    async fn message_delete(
+        &self,
+        _ctx: Context,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        _guild_id: Option<GuildId>,
+    ) {
+        if !self.has_issue(channel_id) {
+            return;
+        }
+        self.queue(Job::MessageChange {
+            channel_id: channel_id.get(),
+            message_id: message_id.get(),
+        });
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        self.handle_interaction(ctx.http.clone(), interaction).await;
+    }
+}
+
+impl Bot {
+    /// Register slash commands in each guild
+    pub async fn register_commands(&self, http: &Http, guilds: &[GuildId]) {
+        let commands = vec![
+            crate::commands::create_issue_command(),
+            crate::feedback::create_feedback_command(&self.config),
+            crate::project_info::create_project_command(),
+            crate::leaderboard::create_leaderboard_command(),
+            crate::debug_state::create_cardibot_command(),
+        ];
+
+        for guild_id in guilds {
+            if let Err(e) = guild_id.set_commands(http, commands.clone()).await {
+                tracing::error!("Failed to register commands for guild {}: {}", guild_id, e);
             } else {
-                tracing::info!("Registered commands for guild {}", guild.id);
+                tracing::info!("Registered commands for guild {}", guild_id);
             }
         }
     }
 
-    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
-        if let Interaction::Command(command) = interaction {
-            if command.data.name.as_str() == "issue" {
-                if let Err(e) =
-                    crate::commands::handle_issue_command(&ctx, &command, &self.config).await
-                {
-                    tracing::error!("Error handling command: {:?}", e);
-                }
+    /// Keep the leaderboard's reaction totals current
+    async fn count_reactions(&self, http: &Http, reaction: &Reaction) {
+        if let Err(e) = crate::leaderboard::handle_reaction(
+            http,
+            &self.store,
+            reaction.channel_id,
+            reaction.message_id,
+        )
+        .await
+        {
+            tracing::error!(
+                "Error counting reactions in {}: {:?}",
+                reaction.channel_id,
+                e
+            );
+        }
+    }
+
+    /// Whether a channel is a thread with a filed issue, so changes elsewhere
+    /// don't leave jobs behind
+    fn has_issue(&self, channel_id: ChannelId) -> bool {
+        match self.store.thread_issue(channel_id.get()) {
+            Ok(mapping) => mapping.is_some(),
+            Err(e) => {
+                tracing::error!("Failed to look up the issue of {}: {:?}", channel_id, e);
+                false
             }
         }
-        // Ignore other interaction types (buttons, select menus, etc.)
     }
+
+    /// Leave a job for the workers; the event is lost if it can't be stored
+    fn queue(&self, job: Job) {
+        if let Err(e) = self.jobs.push(&job) {
+            tracing::error!("Failed to queue {} job: {:?}", job.kind(), e);
+        }
+    }
+
+    /// Route an interaction from the gateway or the HTTP interactions endpoint
+    pub async fn handle_interaction(&self, http: Arc<Http>, interaction: Interaction) {
+        router().dispatch(self, http, interaction).await;
+    }
+}
+
+/// Every command, button and form the bot answers. Handlers run with a
+/// timeout and get a fallback reply if they fail, panic or hang.
+fn router() -> &'static Router {
+    static ROUTER: OnceLock<Router> = OnceLock::new();
+    ROUTER.get_or_init(|| {
+        Router::default()
+            .command("issue", "issue command", |bot, http, command| {
+                Box::pin(async move {
+                    crate::commands::handle_issue_command(
+                        &http,
+                        &command,
+                        &bot.config,
+                        &bot.store,
+                        &bot.cooldowns,
+                        &bot.audit,
+                    )
+                    .await
+                })
+            })
+            .command("feedback", "feedback command", |bot, http, command| {
+                Box::pin(async move {
+                    Ok(
+                        crate::feedback::handle_feedback_command(&http, &command, &bot.config)
+                            .await?,
+                    )
+                })
+            })
+            .command("project", "project command", |bot, http, command| {
+                Box::pin(async move {
+                    Ok(crate::project_info::handle_project_command(
+                        &http,
+                        &command,
+                        &bot.config,
+                        &bot.store,
+                    )
+                    .await?)
+                })
+            })
+            .timeout(Duration::from_secs(INTERACTION_QUICK_TIMEOUT_SECS))
+            .command(
+                "leaderboard",
+                "leaderboard command",
+                |bot, http, command| {
+                    Box::pin(async move {
+                        Ok(crate::leaderboard::handle_leaderboard_command(
+                            &http, &command, &bot.store,
+                        )
+                        .await?)
+                    })
+                },
+            )
+            .timeout(Duration::from_secs(INTERACTION_QUICK_TIMEOUT_SECS))
+            .command("cardibot", "cardibot command", |bot, http, command| {
+                Box::pin(async move {
+                    Ok(crate::debug_state::handle_cardibot_command(
+                        &http,
+                        &command,
+                        &bot.config,
+                        &bot.store,
+                        &bot.gateway,
+                    )
+                    .await?)
+                })
+            })
+            .timeout(Duration::from_secs(INTERACTION_QUICK_TIMEOUT_SECS))
+            .modal(
+                crate::feedback::is_feedback_modal,
+                "feedback form",
+                |bot, http, modal| {
+                    Box::pin(async move {
+                        Ok(crate::feedback::handle_feedback_modal(
+                            &http,
+                            &modal,
+                            &bot.config,
+                            &bot.store,
+                            &bot.audit,
+                        )
+                        .await?)
+                    })
+                },
+            )
+            .modal(
+                crate::split::is_split_modal,
+                "split form",
+                |bot, http, modal| {
+                    Box::pin(async move {
+                        Ok(crate::split::handle_modal(
+                            &http,
+                            &modal,
+                            &bot.config,
+                            &bot.store,
+                            &bot.audit,
+                        )
+                        .await?)
+                    })
+                },
+            )
+            .component(
+                crate::verify_fix::is_verify_fix_component,
+                "verify-fix button",
+                |bot, http, component| {
+                    Box::pin(async move {
+                        Ok(crate::verify_fix::handle_component(
+                            &http,
+                            &component,
+                            &bot.config,
+                            &bot.store,
+                            &bot.audit,
+                        )
+                        .await?)
+                    })
+                },
+            )
+            .component(
+                crate::preview::is_preview_component,
+                "issue preview",
+                |bot, http, component| {
+                    Box::pin(async move {
+                        Ok(crate::preview::handle_component(
+                            &http,
+                            &component,
+                            &bot.config,
+                            &bot.store,
+                            &bot.audit,
+                        )
+                        .await?)
+                    })
+                },
+            )
+            .component(
+                crate::title_suggestion::is_title_suggestion_component,
+                "title suggestion",
+                |bot, http, component| {
+                    Box::pin(async move {
+                        Ok(crate::title_suggestion::handle_component(
+                            &http,
+                            &component,
+                            &bot.config,
+                            &bot.store,
+                            &bot.audit,
+                        )
+                        .await?)
+                    })
+                },
+            )
+            .component(
+                crate::comment_reactions::is_comment_reaction_component,
+                "comment reaction",
+                |bot, http, component| {
+                    Box::pin(async move {
+                        Ok(crate::comment_reactions::handle_component(
+                            &http,
+                            &component,
+                            &bot.config,
+                            &bot.store,
+                        )
+                        .await?)
+                    })
+                },
+            )
+            .component(
+                crate::priority::is_priority_component,
+                "priority selection",
+                |bot, http, component| {
+                    Box::pin(async move {
+                        Ok(crate::priority::handle_component(
+                            &http,
+                            &component,
+                            &bot.config,
+                            &bot.store,
+                            &bot.audit,
+                        )
+                        .await?)
+                    })
+                },
+            )
+            .component(
+                crate::triage::is_triage_component,
+                "triage decision",
+                |bot, http, component| {
+                    Box::pin(async move {
+                        Ok(crate::triage::handle_component(
+                            &http,
+                            &component,
+                            &bot.config,
+                            &bot.store,
+                            &bot.audit,
+                        )
+                        .await?)
+                    })
+                },
+            )
+            .modal(
+                crate::triage::is_triage_modal,
+                "triage duplicate",
+                |bot, http, modal| {
+                    Box::pin(async move {
+                        Ok(crate::triage::handle_modal(
+                            &http,
+                            &modal,
+                            &bot.config,
+                            &bot.store,
+                            &bot.audit,
+                        )
+                        .await?)
+                    })
+                },
+            )
+            .component(
+                crate::duplicate_clusters::is_cluster_component,
+                "duplicate report",
+                |bot, http, component| {
+                    Box::pin(async move {
+                        Ok(crate::duplicate_clusters::handle_component(
+                            &http,
+                            &component,
+                            &bot.config,
+                            &bot.store,
+                            &bot.audit,
+                        )
+                        .await?)
+                    })
+                },
+            )
+            .modal(
+                crate::verify_fix::is_verify_fix_modal,
+                "verify-fix feedback",
+                |bot, http, modal| {
+                    Box::pin(async move {
+                        Ok(crate::verify_fix::handle_modal(
+                            &http,
+                            &modal,
+                            &bot.config,
+                            &bot.store,
+                            &bot.audit,
+                        )
+                        .await?)
+                    })
+                },
+            )
+            .component(
+                crate::needs_info::is_needs_info_component,
+                "needs-info form",
+                |bot, http, component| {
+                    Box::pin(async move {
+                        Ok(crate::needs_info::handle_component(
+                            &http,
+                            &component,
+                            &bot.config,
+                            &bot.store,
+                        )
+                        .await?)
+                    })
+                },
+            )
+            .modal(
+                crate::needs_info::is_needs_info_modal,
+                "needs-info answers",
+                |bot, http, modal| {
+                    Box::pin(async move {
+                        Ok(crate::needs_info::handle_modal(
+                            &http,
+                            &modal,
+                            &bot.config,
+                            &bot.store,
+                            &bot.audit,
+                        )
+                        .await?)
+                    })
+                },
+            )
+    })
 }
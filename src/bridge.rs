@@ -0,0 +1,103 @@
+use anyhow::Result;
+use octocrab::models::issues::Issue;
+use octocrab::Octocrab;
+use serenity::http::Http;
+use std::collections::BTreeMap;
+use tracing::{debug, info, warn};
+
+use crate::audit::project_key;
+use crate::config::Project;
+use crate::rate_budget::RateBudgets;
+use crate::redact::Origin;
+use crate::store::Store;
+use crate::summary::IssueSummary;
+
+/// Create the issue for a thread on a platform the syncer polls, such as a
+/// Matrix thread or a Telegram topic. `messages` are (author, text) pairs,
+/// oldest first, and `title` already starts with a prefix.
+#[allow(clippy::too_many_arguments)]
+pub async fn create_issue(
+    discord: &Http,
+    github: &Octocrab,
+    store: &Store,
+    project: &Project,
+    title: &str,
+    author: &str,
+    thread_url: &str,
+    messages: &[(&str, &str)],
+) -> Result<Issue> {
+    let content = messages
+        .iter()
+        .map(|(author, text)| crate::code_blocks::transcript_entry(author, text))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let origin = Origin::Bridge(thread_url);
+    let content = crate::redact::for_github_from(discord, store, project, origin, &content).await;
+    crate::redact::for_github_from(discord, store, project, origin, title).await;
+
+    let body =
+        crate::github::issue_body(&project.render_issue_body(&content, thread_url, Some(author)));
+    let title = project.github_title(title, crate::constants::GITHUB_ISSUE_TITLE_LIMIT);
+    let labels = project.labels_for_title(&title);
+
+    let issues = github.issues(&project.github_owner, &project.github_repo);
+    let mut create = issues.create(&title).body(body);
+    if !labels.is_empty() {
+        create = create.labels(labels);
+    }
+    Ok(create.send().await?)
+}
+
+/// The linked issues that were closed, looked up a batch at a time in the
+/// repository each link was filed into. `links` are (thread, project key,
+/// issue number); links to repositories the project no longer routes to are
+/// skipped. Stops early once a repository's rate budget is used up.
+pub async fn closed_issues<T>(
+    github: &Octocrab,
+    budgets: &RateBudgets,
+    project: &Project,
+    links: Vec<(T, String, u64)>,
+) -> Result<Vec<(T, Project, IssueSummary)>> {
+    let mut by_repo: BTreeMap<String, Vec<(T, u64)>> = BTreeMap::new();
+    for (thread, key, issue_number) in links {
+        by_repo.entry(key).or_default().push((thread, issue_number));
+    }
+
+    let routes = project.routes();
+    let mut closed = Vec::new();
+    for (key, threads) in by_repo {
+        let Some(route) = routes.iter().find(|r| project_key(r) == key) else {
+            debug!(
+                "Skipping {} threads linked to {}, which is no longer routed",
+                threads.len(),
+                key
+            );
+            continue;
+        };
+        let mut threads = threads.into_iter().peekable();
+        while threads.peek().is_some() {
+            let batch: Vec<(T, u64)> = threads
+                .by_ref()
+                .take(crate::constants::SYNC_ISSUE_BATCH_SIZE)
+                .collect();
+            if !budgets.try_take(route, 1) {
+                info!(
+                    "GitHub budget for {} used up; checking the remaining threads next cycle",
+                    key
+                );
+                return Ok(closed);
+            }
+            let numbers: Vec<u64> = batch.iter().map(|(_, number)| *number).collect();
+            let mut issues = crate::graphql::issue_summaries(github, route, &numbers).await?;
+
+            for (thread, issue_number) in batch {
+                match issues.remove(&issue_number) {
+                    Some(issue) if !issue.open => closed.push((thread, route.clone(), issue)),
+                    Some(_) => {}
+                    None => warn!("Issue #{} of {} not found", issue_number, key),
+                }
+            }
+        }
+    }
+    Ok(closed)
+}
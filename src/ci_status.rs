@@ -0,0 +1,241 @@
+use anyhow::Result;
+use octocrab::models::pulls::PullRequest;
+use octocrab::models::StatusState;
+use octocrab::params::repos::Commitish;
+use octocrab::{params, Octocrab};
+use regex::Regex;
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+use crate::config::Project;
+use crate::embeds::Embed;
+
+static CLOSING_KEYWORD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:close[sd]?|fix(?:e[sd])?|resolve[sd]?)\s+#(\d+)\b").unwrap()
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CiState {
+    Pending,
+    Passed,
+    Failed,
+}
+
+/// Posts CI results of pull requests that fix Discord-linked issues
+#[derive(Default)]
+pub struct CiStatusTracker {
+    /// Last reported "sha:state" per (repo, pull request number)
+    reported: Mutex<HashMap<(String, u64), String>>,
+}
+
+/// The repository's open pull requests by the issues they close, listed
+/// once per sync cycle rather than searched for each issue
+pub async fn open_fixes(
+    github: &Octocrab,
+    project: &Project,
+) -> Result<HashMap<u64, Vec<PullRequest>>> {
+    let first = github
+        .pulls(&project.github_owner, &project.github_repo)
+        .list()
+        .state(params::State::Open)
+        .per_page(100)
+        .send()
+        .await?;
+    let mut fixes: HashMap<u64, Vec<PullRequest>> = HashMap::new();
+    for pull in github.all_pages(first).await? {
+        for number in closed_issues(pull.body.as_deref().unwrap_or("")) {
+            fixes.entry(number).or_default().push(pull.clone());
+        }
+    }
+    Ok(fixes)
+}
+
+impl CiStatusTracker {
+    /// Post the CI results of `fixes`, the open pull requests closing the
+    /// thread's issue
+    pub async fn check_linked_fixes(
+        &self,
+        github: &Octocrab,
+        discord: &Http,
+        project: &Project,
+        fixes: &[PullRequest],
+        thread_id: u64,
+    ) -> Result<()> {
+        for pr in fixes {
+            let sha = pr.head.sha.clone();
+
+            let (state, preview_links) = self.evaluate(github, project, &sha).await?;
+            let Some(state) = state else {
+                debug!("PR #{} has no CI configured", pr.number);
+                continue;
+            };
+
+            // Only terminal states are worth a message in the thread
+            if state == CiState::Pending {
+                continue;
+            }
+
+            let key = (project.github_repo.clone(), pr.number);
+            let marker = format!("{sha}:{state:?}");
+            {
+                let mut reported = self.reported.lock().await;
+                if reported.get(&key) == Some(&marker) {
+                    continue;
+                }
+                reported.insert(key, marker);
+            }
+
+            let (title, color) = match state {
+                CiState::Passed => (
+                    crate::constants::MSG_CI_PASSED,
+                    crate::constants::COLOR_SUCCESS,
+                ),
+                _ => (
+                    crate::constants::MSG_CI_FAILED,
+                    crate::constants::COLOR_FAILURE,
+                ),
+            };
+
+            let mut embed = Embed::new()
+                .title(title)
+                .description(format!(
+                    "**Pull request**: {}",
+                    pr.html_url.as_ref().map(|u| u.as_str()).unwrap_or_default()
+                ))
+                .field("PR", format!("#{}", pr.number), true)
+                .field("Commit", sha.chars().take(7).collect::<String>(), true)
+                .color(color);
+
+            if !preview_links.is_empty() {
+                embed = embed.field(
+                    "Preview",
+                    crate::sanitize::sanitize_for_discord(&preview_links.join("\n")),
+                    false,
+                );
+            }
+
+            ChannelId::new(thread_id)
+                .send_message(
+                    discord,
+                    serenity::builder::CreateMessage::new()
+                        .embed(embed.build())
+                        .allowed_mentions(serenity::builder::CreateAllowedMentions::new()),
+                )
+                .await?;
+
+            info!(
+                "Posted CI {:?} for PR #{} to thread {}",
+                state, pr.number, thread_id
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Combine check runs and commit statuses into one state plus deploy preview links
+    async fn evaluate(
+        &self,
+        github: &Octocrab,
+        project: &Project,
+        sha: &str,
+    ) -> Result<(Option<CiState>, Vec<String>)> {
+        let check_runs = github
+            .checks(&project.github_owner, &project.github_repo)
+            .list_check_runs_for_git_ref(Commitish(sha.to_string()))
+            .send()
+            .await?;
+        // The typed ref helper only accepts branches and tags, so query the SHA directly
+        let statuses: octocrab::models::CombinedStatus = github
+            .get(
+                format!(
+                    "/repos/{}/{}/commits/{}/status",
+                    project.github_owner, project.github_repo, sha
+                ),
+                None::<&()>,
+            )
+            .await?;
+
+        let mut states = Vec::new();
+        let mut preview_links = Vec::new();
+
+        for run in &check_runs.check_runs {
+            states.push(match run.conclusion.as_deref() {
+                None => CiState::Pending,
+                Some("success" | "neutral" | "skipped") => CiState::Passed,
+                Some(_) => CiState::Failed,
+            });
+            if is_preview(&run.name) {
+                if let Some(url) = &run.details_url {
+                    preview_links.push(url.clone());
+                }
+            }
+        }
+
+        for status in &statuses.statuses {
+            states.push(match status.state {
+                StatusState::Pending => CiState::Pending,
+                StatusState::Success => CiState::Passed,
+                _ => CiState::Failed,
+            });
+            let context = status.context.as_deref().unwrap_or("");
+            let description = status.description.as_deref().unwrap_or("");
+            if is_preview(context) || is_preview(description) {
+                if let Some(url) = &status.target_url {
+                    preview_links.push(url.clone());
+                }
+            }
+        }
+
+        let state = if states.is_empty() {
+            None
+        } else if states.contains(&CiState::Failed) {
+            Some(CiState::Failed)
+        } else if states.contains(&CiState::Pending) {
+            Some(CiState::Pending)
+        } else {
+            Some(CiState::Passed)
+        };
+
+        // Check runs and statuses often point at the same deployment
+        let mut seen = HashSet::new();
+        preview_links.retain(|link| seen.insert(link.clone()));
+        Ok((state, preview_links))
+    }
+}
+
+fn is_preview(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.contains("preview") || name.contains("deploy")
+}
+
+/// Issues a PR body closes via GitHub closing keywords
+pub fn closed_issues(body: &str) -> Vec<u64> {
+    let mut numbers: Vec<u64> = CLOSING_KEYWORD
+        .captures_iter(body)
+        .filter_map(|c| c[1].parse().ok())
+        .collect();
+    numbers.sort_unstable();
+    numbers.dedup();
+    numbers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closed_issues() {
+        assert_eq!(closed_issues("Fixes #42"), vec![42]);
+        assert_eq!(closed_issues("This resolves #42."), vec![42]);
+        assert_eq!(
+            closed_issues("closed #42, fixes #7 and fixes #42"),
+            vec![7, 42]
+        );
+        assert_eq!(closed_issues("Fixes #421"), vec![421]);
+        assert!(closed_issues("Related to #42").is_empty());
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Per-project sync failure tracking. After `threshold` consecutive failed
+/// cycles a project is skipped until `cooldown` has passed, then retried.
+/// Any successful cycle closes the breaker again.
+pub struct CircuitBreakers {
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<HashMap<String, Breaker>>,
+}
+
+#[derive(Default)]
+struct Breaker {
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreakers {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            state: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Remaining cooldown if the project's breaker is open
+    pub fn open_for(&self, key: &str, now: Instant) -> Option<Duration> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .get(key)
+            .and_then(|b| b.open_until)
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+
+    pub fn record_success(&self, key: &str) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.remove(key);
+    }
+
+    /// Count a failed cycle; returns true when this failure trips the breaker
+    pub fn record_failure(&self, key: &str, now: Instant) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let breaker = state.entry(key.to_string()).or_default();
+        breaker.failures += 1;
+
+        // A retry after the cooldown that fails again re-opens straight away,
+        // without tripping (and alerting) a second time
+        if breaker.failures >= self.threshold {
+            breaker.open_until = Some(now + self.cooldown);
+        }
+        breaker.failures == self.threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_trips_and_recovers() {
+        let breakers = CircuitBreakers::new(3, Duration::from_secs(60));
+        let now = Instant::now();
+
+        assert!(!breakers.record_failure("acme/app", now));
+        assert!(!breakers.record_failure("acme/app", now));
+        assert_eq!(breakers.open_for("acme/app", now), None);
+        assert!(breakers.record_failure("acme/app", now));
+        assert_eq!(
+            breakers.open_for("acme/app", now),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(breakers.open_for("acme/docs", now), None);
+
+        let later = now + Duration::from_secs(61);
+        assert_eq!(breakers.open_for("acme/app", later), None);
+        assert!(!breakers.record_failure("acme/app", later));
+        assert!(breakers.open_for("acme/app", later).is_some());
+
+        // A success resets the failure count
+        breakers.record_failure("acme/docs", now);
+        breakers.record_success("acme/docs");
+        assert!(!breakers.record_failure("acme/docs", now));
+        assert!(!breakers.record_failure("acme/docs", now));
+    }
+}
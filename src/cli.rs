@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(name = "cardibot")]
@@ -13,17 +13,44 @@ pub enum Commands {
     /// Run the bot normally
     Run,
 
+    /// Run without a gateway connection, driven only by GitHub webhooks and
+    /// Discord's HTTP interactions endpoint (needs [webhook] and DISCORD_PUBLIC_KEY)
+    Serve,
+
     /// Check Discord server information and exit
     CheckDiscord,
 
     /// Validate configuration file
-    ValidateConfig,
+    ValidateConfig {
+        /// Also check IDs, bot permissions and repositories against the live APIs
+        #[arg(long)]
+        live: bool,
+    },
+
+    /// Replay a recorded GitHub webhook payload against the webhook handler
+    WebhookTest {
+        /// JSON payload file, e.g. copied from a repository's webhook deliveries page
+        #[arg(long)]
+        payload: String,
+        /// Event name from the X-GitHub-Event header
+        #[arg(long)]
+        event: String,
+        /// Delivery ID; a generated one is used when omitted
+        #[arg(long)]
+        delivery: Option<String>,
+    },
 
     /// Post feedback instructions to a Discord channel
     PostFeedback {
         /// Channel ID where to post the feedback instructions
         #[arg(long)]
         channel: String,
+        /// TOML or JSON template file; defaults to the project's feedback_template
+        #[arg(long)]
+        template: Option<String>,
+        /// Project whose template and prefixes to use, by name or repository
+        #[arg(long)]
+        project: Option<String>,
     },
 
     /// Debug sync status by checking for issues with thread IDs
@@ -34,4 +61,162 @@ pub enum Commands {
 
     /// Audit sync status between GitHub and Discord
     AuditSync,
+
+    /// Query the audit log of bot mutations
+    AuditLog {
+        /// Only show entries for the project with this name or repository
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Only show entries for this Discord thread ID
+        #[arg(long)]
+        thread: Option<u64>,
+
+        /// Only show entries at or after this time (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only show entries before this time (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Maximum number of entries to show
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+
+    /// Write a signed monthly report of bridged issues and who changed them,
+    /// from the audit log
+    Report {
+        /// Month to cover, as YYYY-MM
+        #[arg(long)]
+        month: String,
+
+        #[arg(long, value_enum, default_value_t = ReportFormat::Csv)]
+        format: ReportFormat,
+
+        /// Only include the project with this name or repository
+        #[arg(long)]
+        project: Option<String>,
+
+        /// File to write, with the signature in <file>.sig; prints otherwise
+        #[arg(long)]
+        out: Option<String>,
+    },
+
+    /// Create forum tags for the configured prefixes
+    SetupForum {
+        /// Only operate on the project with this name or repository
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Show what would be changed without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Create or update the GitHub labels the bot applies
+    SetupLabels {
+        /// Only operate on the project with this name or repository
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Show what would be changed without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Copy thread links from issue markers into the database and drop the
+    /// title suffixes, ahead of switching to `mapping_mode = "store"`
+    MigrateMappings {
+        /// Only operate on the project with this name or repository
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Show what would be changed without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Dump thread/issue mappings and other bot state to a JSON file
+    ExportState {
+        /// File to write
+        #[arg(long)]
+        out: String,
+    },
+
+    /// Print a JSON snapshot of sync results, queues and cached state for debugging
+    DumpState {
+        /// File to write instead of printing
+        #[arg(long)]
+        out: Option<String>,
+    },
+
+    /// Restore bot state from a file written by export-state
+    ImportState {
+        /// File to read
+        #[arg(long = "in")]
+        input: String,
+    },
+
+    /// Final sweep of a project marked `archived = true`: lock its threads and
+    /// label its open issues "forum-archived"
+    ArchiveProject {
+        /// Project with this name or repository
+        #[arg(long)]
+        project: String,
+
+        /// Show what would be changed without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Batch operations on threads and issues across projects
+    Threads {
+        #[command(subcommand)]
+        command: ThreadsCommands,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ReportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Subcommand)]
+pub enum ThreadsCommands {
+    /// Lock threads that have been inactive for longer than a given age
+    Lock {
+        /// Minimum inactivity age, e.g. 90d, 12h, 2w
+        #[arg(long)]
+        older_than: String,
+
+        /// Only lock threads with this prefix (defaults to all configured prefixes)
+        #[arg(long)]
+        prefix: Option<String>,
+
+        /// Only operate on the project with this name or repository
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Show what would be changed without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Close open Discord-linked GitHub issues carrying a label
+    CloseIssues {
+        /// GitHub label to match, e.g. stale
+        #[arg(long)]
+        label: String,
+
+        /// Only operate on the project with this name or repository
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Show what would be changed without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
@@ -25,4 +25,30 @@ impl Clients {
             discord_http,
         })
     }
+
+    /// GitHub client for a project, using its named account or GHES server when it has one
+    pub async fn github_for(
+        &self,
+        config: &crate::config::Config,
+        project: &crate::config::Project,
+    ) -> Result<Arc<Octocrab>> {
+        if project.github_account.is_some() || project.github_api_url.is_some() {
+            return Ok(Arc::new(
+                crate::github_app::create_project_client(config, project).await?,
+            ));
+        }
+        Ok(self.github.clone())
+    }
+
+    /// Discord client for a project, using its `discord_bot` token when it has one
+    pub fn discord_for(
+        &self,
+        config: &crate::config::Config,
+        project: &crate::config::Project,
+    ) -> Result<Arc<Http>> {
+        match &project.discord_bot {
+            Some(bot) => Ok(Arc::new(Http::new(&config.discord_token(Some(bot))?))),
+            None => Ok(self.discord_http.clone()),
+        }
+    }
 }
@@ -0,0 +1,230 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Lines that look like they were pasted from a log or a stack trace
+static LOG_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"^\s*\[?\d{4}-\d{2}-\d{2}[ T]\d{2}:\d{2}",
+        r"|^\s*\[?\d{2}:\d{2}:\d{2}",
+        r"|^\s*\[?(TRACE|DEBUG|INFO|WARN|WARNING|ERROR|FATAL)\b",
+        r"|^\s+at \S",
+        r#"|^\s*File ".+", line \d+"#,
+        r"|^Traceback \(most recent call last\)",
+        r"|^\s*\d+: (0x[0-9a-f]+|\S+::)",
+        r"|^thread '.+' panicked at",
+        r"|^\s*(Caused by|Exception in thread)\b",
+    ))
+    .expect("valid regex")
+});
+
+enum Segment<'a> {
+    Prose(&'a str),
+    Code {
+        language: Option<&'a str>,
+        code: &'a str,
+    },
+}
+
+/// One transcript entry for the issue body, with code moved onto its own
+/// lines by [`preserve_code`]
+pub fn transcript_entry(author: &str, content: &str) -> String {
+    match preserve_code(content) {
+        Some(formatted) => format!("**@{author}**:\n{formatted}"),
+        None => format!("**@{author}**: {content}"),
+    }
+}
+
+/// Code blocks and log pastes as fenced blocks with a language hint, each on
+/// its own lines so GitHub keeps their formatting instead of running them into
+/// the prose. None when the message has no code.
+pub fn preserve_code(content: &str) -> Option<String> {
+    let segments = segments(content);
+    if !segments.iter().any(|s| matches!(s, Segment::Code { .. })) {
+        return None;
+    }
+
+    let parts: Vec<String> = segments
+        .iter()
+        .filter_map(|segment| match segment {
+            Segment::Prose(text) => Some(text.trim())
+                .filter(|t| !t.is_empty())
+                .map(String::from),
+            Segment::Code { language, code } => Some(fenced(*language, code)),
+        })
+        .collect();
+    Some(parts.join("\n\n"))
+}
+
+/// Split a message into prose and code: Discord fences first, then runs of
+/// log-like lines in what's left
+fn segments(content: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = content;
+
+    while let Some(open) = rest.find("```") {
+        split_logs(&rest[..open], &mut segments);
+        let block = &rest[open + 3..];
+        // An unclosed fence runs to the end of the message, as on Discord
+        let (inner, after) = match block.find("```") {
+            Some(close) => (&block[..close], &block[close + 3..]),
+            None => (block, ""),
+        };
+
+        // Discord takes a single word directly after the fence as the language
+        let (language, code) = match inner.split_once('\n') {
+            Some((first, code)) if is_language(first.trim()) => (Some(first.trim()), code),
+            _ => (None, inner),
+        };
+        let code = code.trim_matches('\n');
+        if !code.trim().is_empty() {
+            segments.push(Segment::Code { language, code });
+        }
+        rest = after;
+    }
+    split_logs(rest, &mut segments);
+    segments
+}
+
+fn is_language(word: &str) -> bool {
+    !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '#' | '-' | '_' | '.'))
+}
+
+/// Append `text` as prose, lifting out runs of consecutive log lines
+fn split_logs<'a>(text: &'a str, segments: &mut Vec<Segment<'a>>) {
+    let mut prose_start = 0;
+    let mut run: Option<(usize, usize, usize)> = None; // (start, end, lines)
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let end = offset + line.len();
+        if LOG_LINE.is_match(line) {
+            run = Some(match run {
+                Some((start, _, lines)) => (start, end, lines + 1),
+                None => (offset, end, 1),
+            });
+        } else if let Some(finished) = run.take() {
+            lift_log(text, finished, &mut prose_start, segments);
+        }
+        offset = end;
+    }
+    if let Some(finished) = run {
+        lift_log(text, finished, &mut prose_start, segments);
+    }
+    push_prose(&text[prose_start..], segments);
+}
+
+/// Turn a run of log lines into a code segment if it is long enough to be a
+/// paste rather than a sentence that happens to start with "Error"
+fn lift_log<'a>(
+    text: &'a str,
+    (start, end, lines): (usize, usize, usize),
+    prose_start: &mut usize,
+    segments: &mut Vec<Segment<'a>>,
+) {
+    if lines < crate::constants::LOG_PASTE_MIN_LINES {
+        return;
+    }
+    push_prose(&text[*prose_start..start], segments);
+    segments.push(Segment::Code {
+        language: Some("text"),
+        code: text[start..end].trim_end(),
+    });
+    *prose_start = end;
+}
+
+fn push_prose<'a>(text: &'a str, segments: &mut Vec<Segment<'a>>) {
+    if !text.trim().is_empty() {
+        segments.push(Segment::Prose(text));
+    }
+}
+
+/// Fence `code`, with a guessed language when none was given. The fence is
+/// longer than any backtick run inside so pasted Markdown can't close it.
+fn fenced(language: Option<&str>, code: &str) -> String {
+    let longest_run = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat((longest_run + 1).max(3));
+    let language = language
+        .or_else(|| guess_language(code))
+        .unwrap_or_default();
+    format!("{fence}{language}\n{code}\n{fence}")
+}
+
+fn guess_language(code: &str) -> Option<&'static str> {
+    let trimmed = code.trim();
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(trimmed).is_ok()
+    {
+        return Some("json");
+    }
+    if trimmed.lines().filter(|l| LOG_LINE.is_match(l)).count() * 2 > trimmed.lines().count() {
+        return Some("text");
+    }
+    if trimmed
+        .lines()
+        .all(|l| l.starts_with("$ ") || !l.starts_with(' '))
+        && trimmed.starts_with("$ ")
+    {
+        return Some("console");
+    }
+    if trimmed.contains("fn ") && (trimmed.contains("let ") || trimmed.contains("->")) {
+        return Some("rust");
+    }
+    if trimmed.starts_with("def ") || trimmed.contains("\ndef ") || trimmed.starts_with("import ") {
+        return Some("python");
+    }
+    if trimmed.contains("const ") || trimmed.contains("function") || trimmed.contains("=>") {
+        return Some("javascript");
+    }
+    if trimmed.starts_with('<') && trimmed.ends_with('>') {
+        return Some("html");
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_message_is_unchanged() {
+        assert_eq!(transcript_entry("ana", "it broke"), "**@ana**: it broke");
+    }
+
+    #[test]
+    fn test_fenced_blocks() {
+        let entry = transcript_entry("ana", "Config:```toml\nkey = 1\n```and then it fails");
+        assert_eq!(
+            entry,
+            "**@ana**:\nConfig:\n\n```toml\nkey = 1\n```\n\nand then it fails"
+        );
+
+        // Missing language is guessed, and inner fences get a longer fence
+        let entry = transcript_entry("ana", "```\n{\"error\": \"boom\"}\n```");
+        assert!(entry.ends_with("```json\n{\"error\": \"boom\"}\n```"));
+        assert_eq!(
+            fenced(Some("md"), "wrap in ``` like this"),
+            "````md\nwrap in ``` like this\n````"
+        );
+    }
+
+    #[test]
+    fn test_log_pastes() {
+        let content = "Crashes on start:\n\
+                       2025-01-02 10:00:01 INFO starting\n\
+                       2025-01-02 10:00:02 ERROR wallet sync failed\n    at sync (app.js:10)\n\
+                       Any idea?";
+        assert_eq!(
+            transcript_entry("bo", content),
+            "**@bo**:\nCrashes on start:\n\n```text\n\
+             2025-01-02 10:00:01 INFO starting\n\
+             2025-01-02 10:00:02 ERROR wallet sync failed\n    at sync (app.js:10)\n```\n\nAny idea?"
+        );
+
+        // A single log-looking line stays inline
+        let single = "ERROR: it failed\nplease help";
+        assert_eq!(transcript_entry("bo", single), format!("**@bo**: {single}"));
+    }
+}
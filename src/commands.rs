@@ -1,56 +1,325 @@
-use crate::config::Config;
+use crate::audit::AuditLog;
+use crate::config::{Config, Project};
+use crate::cooldown::CommandCooldowns;
+use crate::error::BotError;
+use crate::selection::MessageSelection;
+use crate::store::Store;
 use serenity::all::*;
 use std::sync::Arc;
+use std::time::Duration;
 
 pub fn create_issue_command() -> CreateCommand {
-    CreateCommand::new("issue")
-        .description("Manage GitHub issues")
-        .add_option(CreateCommandOption::new(
-            CommandOptionType::SubCommand,
-            "create",
-            "Create a GitHub issue from this thread",
+    use crate::locales::option;
+
+    crate::locales::command(
+        CreateCommand::new("issue").description("Manage GitHub issues"),
+        "issue",
+    )
+    .add_option(
+        option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "create",
+                "Create a GitHub issue from this thread",
+            ),
+            "issue.create",
+        )
+        .add_sub_option(option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "messages",
+                "Include only the last N messages",
+            )
+            .min_int_value(1)
+            .max_int_value(crate::constants::THREAD_SELECTION_FETCH_LIMIT.into()),
+            "issue.create.messages",
         ))
+        .add_sub_option(option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "since",
+                "Include messages from this time (YYYY-MM-DD or RFC 3339)",
+            ),
+            "issue.create.since",
+        ))
+        .add_sub_option(option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "until",
+                "Include messages up to this time (YYYY-MM-DD or RFC 3339)",
+            ),
+            "issue.create.until",
+        ))
+        .add_sub_option(option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "from",
+                "Link to a message to file, with its replies, instead of this thread",
+            ),
+            "issue.create.from",
+        ))
+        .add_sub_option(option(
+            CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "anonymous",
+                "Leave your Discord name out of the GitHub issue",
+            ),
+            "issue.create.anonymous",
+        ))
+        .add_sub_option(option(
+            CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "preview",
+                "Show the issue before it is sent to GitHub",
+            ),
+            "issue.create.preview",
+        )),
+    )
+    .add_option(option(
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "priority",
+            "Set the priority of this thread's GitHub issue",
+        ),
+        "issue.priority",
+    ))
+    .add_option(
+        option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "duplicate",
+                "Close this thread's issue as a duplicate of another",
+            ),
+            "issue.duplicate",
+        )
+        .add_sub_option(option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "number",
+                "Number of the original issue",
+            )
+            .min_int_value(1)
+            .required(true),
+            "issue.duplicate.number",
+        )),
+    )
+    .add_option(option(
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "split",
+            "File each problem in this thread as its own issue",
+        ),
+        "issue.split",
+    ))
+    .add_option(
+        option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "move",
+                "Move this thread's issue to another project's repository",
+            ),
+            "issue.move",
+        )
+        .add_sub_option(option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "project",
+                "Project name or owner/repo to move the issue to",
+            )
+            .required(true),
+            "issue.move.project",
+        )),
+    )
+    .add_option(
+        option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "merge",
+                "Merge this thread into another thread's GitHub issue",
+            ),
+            "issue.merge",
+        )
+        .add_sub_option(option(
+            CreateCommandOption::new(
+                CommandOptionType::Channel,
+                "into",
+                "Thread whose issue this one joins",
+            )
+            .channel_types(vec![ChannelType::PublicThread])
+            .required(true),
+            "issue.merge.into",
+        )),
+    )
 }
 
+/// Run `/issue`, telling the user what went wrong if it fails
 pub async fn handle_issue_command(
-    ctx: &Context,
+    http: &Http,
     command: &CommandInteraction,
     config: &Arc<Config>,
-) -> Result<(), Box<dyn std::error::Error>> {
+    store: &Store,
+    cooldowns: &CommandCooldowns,
+    audit: &AuditLog,
+) -> Result<(), BotError> {
+    let result = run_issue_command(http, command, config, store, cooldowns, audit).await;
+    if let Err(e) = &result {
+        let reply = EditInteractionResponse::new().content(format!("❌ {}", e.user_message()));
+        if let Err(reply_error) = command.edit_response(http, reply).await {
+            tracing::warn!("Failed to report command error to user: {}", reply_error);
+        }
+    }
+    result
+}
+
+async fn run_issue_command(
+    http: &Http,
+    command: &CommandInteraction,
+    config: &Arc<Config>,
+    store: &Store,
+    cooldowns: &CommandCooldowns,
+    audit: &AuditLog,
+) -> Result<(), BotError> {
+    // The split form has to be the first response, so it can't be deferred
+    if command.data.options.first().map(|o| o.name.as_str()) == Some("split") {
+        return crate::split::show_modal(http, command, config, store)
+            .await
+            .map_err(Into::into);
+    }
+
     // Defer the response immediately to avoid timeout
     command
         .create_response(
-            &ctx,
+            http,
             CreateInteractionResponse::Defer(
                 CreateInteractionResponseMessage::new().ephemeral(true),
             ),
         )
         .await?;
 
-    // Check if in a forum thread
-    let channel = command.channel_id.to_channel(&ctx).await?;
+    // A message link can be filed from any channel
+    if let Some(link) = crate::message_link::from_option(command) {
+        return crate::message_link::file_from_message(
+            http, command, config, store, cooldowns, audit, link,
+        )
+        .await;
+    }
+
+    let roles = command
+        .member
+        .as_ref()
+        .map(|m| m.roles.as_slice())
+        .unwrap_or_default();
+    let guild_id = command
+        .guild_id
+        .ok_or_else(|| BotError::User("This command only works in servers!".to_string()))?;
+    let (thread, project) =
+        issue_thread(http, config, store, command.channel_id, guild_id, roles).await?;
+    let project = &project;
+
+    let subcommand = command.data.options.first().map(|o| o.name.as_str());
+    if subcommand == Some("priority") {
+        return crate::priority::show_menu(http, command, config, project, &thread)
+            .await
+            .map_err(Into::into);
+    }
+    if subcommand == Some("merge") {
+        return crate::merge::merge_thread(http, command, config, project, &thread, store, audit)
+            .await
+            .map_err(Into::into);
+    }
+    if subcommand == Some("move") {
+        return crate::move_issue::move_thread(
+            http, command, config, project, &thread, store, audit,
+        )
+        .await
+        .map_err(Into::into);
+    }
+    if subcommand == Some("duplicate") {
+        return crate::duplicate::mark_duplicate(
+            http, command, config, project, &thread, store, audit,
+        )
+        .await
+        .map_err(Into::into);
+    }
+
+    // Per-user cooldown to stop rapid repeated runs
+    let cooldown = Duration::from_secs(project.command_cooldown_seconds());
+    if let Some(remaining) = cooldowns.check_user(command.user.id.get(), cooldown) {
+        command
+            .edit_response(
+                http,
+                EditInteractionResponse::new().content(format!(
+                    "Please wait {} seconds before running this command again",
+                    remaining.as_secs().max(1)
+                )),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    // Only one creation per thread at a time, within this process and across restarts
+    let Some(_thread_guard) = cooldowns.begin_thread(thread.id.get()) else {
+        command
+            .edit_response(
+                http,
+                EditInteractionResponse::new().content(crate::constants::MSG_THREAD_BUSY),
+            )
+            .await?;
+        return Ok(());
+    };
+
+    if !store.try_lock_thread(thread.id.get(), crate::constants::THREAD_LOCK_TTL_SECS)? {
+        command
+            .edit_response(
+                http,
+                EditInteractionResponse::new().content(crate::constants::MSG_THREAD_BUSY),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let result =
+        create_issue_for_thread(http, command, config, project, &thread, store, audit).await;
+    store.unlock_thread(thread.id.get())?;
+    result
+}
+
+/// The thread an `/issue` command or a bot mention runs in and the project
+/// it files into, after the checks both share: a configured forum or a text
+/// channel thread its project accepts, a project that isn't archived and the
+/// allowed role
+pub async fn issue_thread(
+    http: &Http,
+    config: &Config,
+    store: &Store,
+    channel_id: ChannelId,
+    guild_id: GuildId,
+    roles: &[RoleId],
+) -> Result<(GuildChannel, Project), BotError> {
+    // Check if in a thread
+    let channel = crate::discord_cache::channel(http, channel_id).await?;
     let thread = match channel {
         Channel::Guild(ch) if ch.thread_metadata.is_some() => ch,
         _ => {
-            command
-                .edit_response(
-                    &ctx,
-                    EditInteractionResponse::new()
-                        .content("This command only works in forum threads!"),
-                )
-                .await?;
-            return Ok(());
+            return Err(BotError::User(
+                "This command only works in threads!".to_string(),
+            ))
         }
     };
 
     // Find project config
-    let guild_id = command.guild_id.unwrap();
-    let parent_id = thread.parent_id.unwrap();
+    let parent_id = thread
+        .parent_id
+        .ok_or_else(|| BotError::User("This command only works in threads!".to_string()))?;
 
     let project = match config.find_project(guild_id.get(), parent_id.get()) {
+        Some(p) => Some(p),
+        None => channel_thread_project(http, config, &thread).await?,
+    };
+    let project = match project {
         Some(p) => {
             tracing::info!(
-                "Found project '{}' for guild {} forum {}",
+                "Found project '{}' for guild {} channel {}",
                 p.name.as_deref().unwrap_or("unnamed"),
                 guild_id,
                 parent_id
@@ -58,111 +327,127 @@ pub async fn handle_issue_command(
             p
         }
         None => {
-            command
-                .edit_response(
-                    &ctx,
-                    EditInteractionResponse::new()
-                        .content("This forum is not configured for issue tracking"),
-                )
-                .await?;
-            return Ok(());
+            return Err(BotError::User(
+                "This channel is not configured for issue tracking".to_string(),
+            ))
         }
     };
+    if project.archived() {
+        return Err(BotError::User(
+            "This forum is archived; no new issues are filed from it".to_string(),
+        ));
+    }
+    let project = project.routed(&thread.name);
+    // A thread whose issue was moved follows it to the other repository
+    let moved = crate::move_issue::moved_to(config, store, &project, thread.id.get())?;
+    let project = moved.unwrap_or(project);
 
     // Check permissions
     if let Some(required_role_id) = &project.allowed_role_id {
-        let member = &command.member.as_ref().unwrap();
-        let required_role_id = required_role_id
-            .parse::<u64>()
-            .map_err(|_| "Invalid role ID in configuration")?;
+        let required_role_id = required_role_id.parse::<u64>().map_err(|_| {
+            BotError::Config(format!("invalid allowed_role_id '{required_role_id}'"))
+        })?;
 
-        let has_role = member
-            .roles
+        let has_role = roles
             .iter()
             .any(|role_id| role_id.get() == required_role_id);
 
         if !has_role {
-            command
-                .edit_response(
-                    &ctx,
-                    EditInteractionResponse::new()
-                        .content("You don't have permission to create issues"),
-                )
-                .await?;
-            return Ok(());
+            return Err(BotError::User(
+                "You don't have permission to create issues".to_string(),
+            ));
         }
     }
 
-    // Extract thread content
-    let content = crate::github::extract_thread_content(ctx, &thread).await?;
+    Ok((thread, project))
+}
 
-    // Get thread owner's username
-    let thread_owner_name = if let Some(owner_id) = thread.owner_id {
-        match owner_id.to_user(&ctx).await {
-            Ok(user) => user.name,
-            Err(_) => "Unknown".to_string(),
-        }
-    } else {
-        "Unknown".to_string()
+/// The guild's project for a public thread of a text channel, when one
+/// accepts them
+async fn channel_thread_project<'a>(
+    http: &Http,
+    config: &'a Config,
+    thread: &GuildChannel,
+) -> Result<Option<&'a Project>, BotError> {
+    if thread.kind != ChannelType::PublicThread {
+        return Ok(None);
+    }
+    let Some(parent_id) = thread.parent_id else {
+        return Ok(None);
     };
+    let parent = crate::discord_cache::channel(http, parent_id).await?;
+    if !matches!(parent, Channel::Guild(ref ch) if ch.kind == ChannelType::Text) {
+        return Ok(None);
+    }
+    Ok(config.find_thread_project(thread.guild_id.get(), parent_id.get()))
+}
 
-    // Create a fresh GitHub client
-    let github = crate::github_app::create_github_client().await?;
-
-    // Create or update GitHub issue
-    tracing::info!(
-        "Creating/updating GitHub issue for thread '{}' in project '{}'",
-        thread.name,
-        project.name.as_deref().unwrap_or(&project.github_repo)
-    );
-    let result = crate::github::create_or_update_issue(
-        &github,
-        project,
-        &thread,
-        content,
-        thread_owner_name,
+async fn create_issue_for_thread(
+    http: &Http,
+    command: &CommandInteraction,
+    config: &Config,
+    project: &Project,
+    thread: &GuildChannel,
+    store: &Store,
+    audit: &AuditLog,
+) -> Result<(), BotError> {
+    let selection = MessageSelection::from_command(command).map_err(BotError::User)?;
+    if let Some(reply) = crate::quota::check_thread(http, config, project, thread, store).await? {
+        command
+            .edit_response(http, EditInteractionResponse::new().content(reply))
+            .await?;
+        return Ok(());
+    }
+    let anonymous = crate::anonymous::requested(command);
+    if anonymous && thread.owner_id != Some(command.user.id) {
+        return Err(BotError::User(
+            "Only the thread's author can file it anonymously".to_string(),
+        ));
+    }
+    let preview = crate::preview::requested(command);
+    if crate::title_suggestion::offer(
+        http, command, project, thread, &selection, anonymous, preview,
     )
-    .await?;
-
-    let action = if result.was_updated {
-        "Updated"
-    } else {
-        "Created"
-    };
-    tracing::info!(
-        "{} GitHub issue #{} for project '{}'",
-        action,
-        result.issue.number,
-        project.name.as_deref().unwrap_or(&project.github_repo)
-    );
-
-    // Post GitHub link in thread
-    let embed_title = if result.was_updated {
-        crate::constants::MSG_ISSUE_UPDATED
-    } else {
-        crate::constants::MSG_ISSUE_CREATED
-    };
-
-    thread
-        .send_message(
-            &ctx,
-            CreateMessage::new().embed(
-                CreateEmbed::new()
-                    .title(embed_title)
-                    .description(format!("**Issue**: {}", result.issue.html_url))
-                    .field("Number", format!("#{}", result.issue.number), true)
-                    .field("Status", "Open", true)
-                    .color(crate::constants::COLOR_SUCCESS),
-            ),
+    .await?
+    {
+        return Ok(());
+    }
+    if preview {
+        let response = crate::preview::response(
+            http,
+            config,
+            project,
+            thread,
+            store,
+            &selection,
+            anonymous,
+            crate::progress::Progress::Command(http, command),
         )
         .await?;
+        command.edit_response(http, response).await?;
+        return Ok(());
+    }
+    if anonymous {
+        store.set_anonymous_reporter(thread.id.get(), command.user.id.get(), &command.user.name)?;
+    }
+    let filed = crate::filing::file_thread(
+        http,
+        config,
+        project,
+        thread,
+        store,
+        audit,
+        &command.user.name,
+        &selection,
+        crate::progress::Progress::Command(http, command),
+    )
+    .await?;
 
     // Update the deferred response
     command
         .edit_response(
-            &ctx,
-            EditInteractionResponse::new()
-                .content(format!("✅ {} issue #{}", action, result.issue.number)),
+            http,
+            EditInteractionResponse::new().content(format!("✅ {}", filed.summary())),
         )
         .await?;
 
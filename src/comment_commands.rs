@@ -0,0 +1,235 @@
+use anyhow::Result;
+use octocrab::models::AuthorAssociation;
+use octocrab::Octocrab;
+use serenity::builder::{CreateAllowedMentions, CreateMessage, EditThread};
+use serenity::http::Http;
+use serenity::model::channel::ChannelFlags;
+use serenity::model::id::ChannelId;
+use tracing::{debug, info, warn};
+
+use crate::audit::{AuditAction, AuditEntry, AuditLog};
+use crate::config::Project;
+use crate::embeds::Embed;
+use crate::store::Store;
+
+/// Maintainer command written on the GitHub issue as `/discord <command>`
+#[derive(Debug, PartialEq, Eq)]
+pub enum CommentCommand {
+    /// Ask the reporter for more details, with an optional question
+    AskInfo(Option<String>),
+    /// Relay a message into the thread
+    Reply(String),
+    Pin,
+    Close,
+}
+
+/// Parse every `/discord` command line in a comment body
+pub fn parse_commands(body: &str) -> Vec<CommentCommand> {
+    body.lines()
+        .filter_map(|line| line.trim().strip_prefix("/discord "))
+        .filter_map(|rest| {
+            let (name, arg) = match rest.trim().split_once(char::is_whitespace) {
+                Some((name, arg)) => (name, arg.trim()),
+                None => (rest.trim(), ""),
+            };
+            match name {
+                "ask-info" => Some(CommentCommand::AskInfo(
+                    Some(arg.to_string()).filter(|a| !a.is_empty()),
+                )),
+                "reply" if !arg.is_empty() => Some(CommentCommand::Reply(arg.to_string())),
+                "pin" => Some(CommentCommand::Pin),
+                "close" => Some(CommentCommand::Close),
+                _ => {
+                    debug!("Ignoring unknown comment command '{}'", name);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+pub fn is_maintainer(association: &AuthorAssociation) -> bool {
+    matches!(
+        association,
+        AuthorAssociation::Owner | AuthorAssociation::Member | AuthorAssociation::Collaborator
+    )
+}
+
+/// Run `/discord` commands from recent maintainer comments on the issue
+pub async fn process_issue_comments(
+    github: &Octocrab,
+    discord: &Http,
+    store: &Store,
+    audit: &AuditLog,
+    project: &Project,
+    issue_number: u64,
+    thread_id: u64,
+) -> Result<()> {
+    // Never replay commands from old history, e.g. on first start
+    let cutoff = chrono::Utc::now()
+        - chrono::Duration::hours(crate::constants::COMMENT_COMMAND_MAX_AGE_HOURS);
+
+    let issues = github.issues(&project.github_owner, &project.github_repo);
+    let comments = issues
+        .list_comments(issue_number)
+        .since(cutoff)
+        .per_page(100)
+        .send()
+        .await?;
+
+    for comment in comments.items {
+        if comment.created_at < cutoff || !is_maintainer(&comment.author_association) {
+            continue;
+        }
+        let commands = parse_commands(comment.body.as_deref().unwrap_or_default());
+        if commands.is_empty() || !store.mark_comment_processed(comment.id.0)? {
+            continue;
+        }
+
+        let login = &comment.user.login;
+        for command in commands {
+            info!(
+                "Running {:?} from {} on issue #{} in thread {}",
+                command, login, issue_number, thread_id
+            );
+            if let Err(e) = run_command(
+                discord,
+                store,
+                audit,
+                project,
+                issue_number,
+                thread_id,
+                comment.id.0,
+                login,
+                command,
+            )
+            .await
+            {
+                warn!("Comment command on issue #{} failed: {}", issue_number, e);
+            }
+        }
+
+        if let Err(e) = issues
+            .create_comment_reaction(
+                comment.id,
+                octocrab::models::reactions::ReactionContent::PlusOne,
+            )
+            .await
+        {
+            debug!("Failed to acknowledge comment {}: {}", comment.id, e);
+        }
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_command(
+    discord: &Http,
+    store: &Store,
+    audit: &AuditLog,
+    project: &Project,
+    issue_number: u64,
+    thread_id: u64,
+    comment_id: u64,
+    login: &str,
+    command: CommentCommand,
+) -> Result<()> {
+    let channel_id = ChannelId::new(thread_id);
+
+    match command {
+        CommentCommand::AskInfo(question) => {
+            let owner = channel_id
+                .to_channel(discord)
+                .await?
+                .guild()
+                .and_then(|thread| thread.owner_id);
+            let owner = crate::mute::unmuted(store, owner, Some(thread_id));
+            let mut content = crate::constants::MSG_NEEDS_INFO.to_string();
+            if let Some(owner) = owner {
+                content = format!("<@{owner}> {content}");
+            }
+            let mut embed = Embed::new()
+                .footer(format!("{login} on GitHub"))
+                .color(crate::constants::COLOR_SUCCESS);
+            if let Some(question) = question {
+                embed = embed.description(crate::sanitize::sanitize_for_discord(&question));
+            }
+            embed
+                .send(
+                    discord,
+                    channel_id,
+                    CreateMessage::new()
+                        .content(content)
+                        .allowed_mentions(CreateAllowedMentions::new().users(owner)),
+                )
+                .await?;
+        }
+        CommentCommand::Reply(text) => {
+            let mut message = CreateMessage::new().allowed_mentions(CreateAllowedMentions::new());
+            if project.comment_reactions() {
+                message = message.components(vec![crate::comment_reactions::buttons(
+                    issue_number,
+                    comment_id,
+                    0,
+                    0,
+                )]);
+            }
+            Embed::new()
+                .title(format!("💬 {login} on GitHub"))
+                .description(crate::sanitize::sanitize_for_discord(&text))
+                .color(crate::constants::COLOR_SUCCESS)
+                .send(discord, channel_id, message)
+                .await?;
+        }
+        CommentCommand::Pin => {
+            channel_id
+                .edit_thread(discord, EditThread::new().flags(ChannelFlags::PINNED))
+                .await?;
+            audit
+                .record(
+                    AuditEntry::new(project, AuditAction::ThreadPinned)
+                        .thread(thread_id)
+                        .issue(issue_number)
+                        .actor(login),
+                )
+                .await;
+        }
+        CommentCommand::Close => {
+            crate::sync::close_thread(discord, project, channel_id).await?;
+            audit
+                .record(
+                    AuditEntry::new(project, AuditAction::ThreadLocked)
+                        .thread(thread_id)
+                        .issue(issue_number)
+                        .actor(login)
+                        .details("/discord close on GitHub"),
+                )
+                .await;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        let body = "Thanks!\n/discord ask-info Which OS are you on?\n/discord pin\n/discord reply\n/discord close\n/discord dance";
+        assert_eq!(
+            parse_commands(body),
+            vec![
+                CommentCommand::AskInfo(Some("Which OS are you on?".to_string())),
+                CommentCommand::Pin,
+                CommentCommand::Close,
+            ]
+        );
+        assert_eq!(
+            parse_commands("/discord ask-info"),
+            vec![CommentCommand::AskInfo(None)]
+        );
+    }
+}
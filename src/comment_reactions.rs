@@ -0,0 +1,121 @@
+use anyhow::Result;
+use octocrab::models::reactions::ReactionContent;
+use octocrab::models::CommentId;
+use serenity::all::*;
+use tracing::{info, warn};
+
+use crate::config::Config;
+use crate::store::Store;
+
+const REACTION_PREFIX: &str = "comment_reaction:";
+
+/// 👍/👎 buttons with the current counts under a GitHub comment relayed into
+/// a thread
+pub fn buttons(issue_number: u64, comment_id: u64, up: u64, down: u64) -> CreateActionRow {
+    let button = |vote: &str, emoji: char, count: u64| {
+        let button = CreateButton::new(format!(
+            "{REACTION_PREFIX}{vote}:{issue_number}:{comment_id}"
+        ))
+        .emoji(emoji)
+        .style(ButtonStyle::Secondary);
+        if count > 0 {
+            button.label(count.to_string())
+        } else {
+            button
+        }
+    };
+    CreateActionRow::Buttons(vec![button("up", '👍', up), button("down", '👎', down)])
+}
+
+pub fn is_comment_reaction_component(custom_id: &str) -> bool {
+    custom_id.starts_with(REACTION_PREFIX)
+}
+
+/// `up:<issue>:<comment>` from a button's custom ID, as (vote, issue, comment)
+fn parse_custom_id(custom_id: &str) -> Option<(i8, u64, u64)> {
+    let mut parts = custom_id.strip_prefix(REACTION_PREFIX)?.split(':');
+    let vote = match parts.next()? {
+        "up" => 1,
+        "down" => -1,
+        _ => return None,
+    };
+    let issue_number = parts.next()?.parse().ok()?;
+    let comment_id = parts.next()?.parse().ok()?;
+    Some((vote, issue_number, comment_id))
+}
+
+/// Count a Discord user's vote and refresh the buttons. The first vote of a
+/// kind is passed on as the bot's reaction on the GitHub comment, so
+/// maintainers see the sentiment without opening Discord.
+pub async fn handle_component(
+    http: &Http,
+    component: &ComponentInteraction,
+    config: &Config,
+    store: &Store,
+) -> Result<()> {
+    let Some((vote, issue_number, comment_id)) = parse_custom_id(&component.data.custom_id) else {
+        return Ok(());
+    };
+    let Some((_, project)) =
+        crate::thread_events::resolve_thread(http, component.channel_id, config, store).await?
+    else {
+        return Ok(());
+    };
+
+    let (up, down) = store.toggle_comment_vote(comment_id, component.user.id.get(), vote)?;
+    component
+        .create_response(
+            http,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new().components(vec![buttons(
+                    issue_number,
+                    comment_id,
+                    up,
+                    down,
+                )]),
+            ),
+        )
+        .await?;
+
+    let first = if vote > 0 { up == 1 } else { down == 1 };
+    if !first {
+        return Ok(());
+    }
+    let content = if vote > 0 {
+        ReactionContent::PlusOne
+    } else {
+        ReactionContent::MinusOne
+    };
+    let github = crate::github_app::create_project_client(config, &project).await?;
+    match github
+        .issues(&project.github_owner, &project.github_repo)
+        .create_comment_reaction(CommentId(comment_id), content.clone())
+        .await
+    {
+        Ok(_) => info!(
+            "Forwarded {:?} from Discord to comment {} on issue #{}",
+            content, comment_id, issue_number
+        ),
+        Err(e) => warn!("Failed to react to comment {}: {}", comment_id, e),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_custom_id() {
+        assert_eq!(
+            parse_custom_id("comment_reaction:up:12:345"),
+            Some((1, 12, 345))
+        );
+        assert_eq!(
+            parse_custom_id("comment_reaction:down:12:345"),
+            Some((-1, 12, 345))
+        );
+        assert_eq!(parse_custom_id("comment_reaction:meh:12:345"), None);
+        assert_eq!(parse_custom_id("verify_fix:confirm:12"), None);
+    }
+}
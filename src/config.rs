@@ -1,10 +1,44 @@
 use anyhow::Result;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fs;
 
-#[derive(Debug, Deserialize)]
+use crate::quiet_hours::{MaintenanceWindow, QuietHours};
+
+#[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     pub log_level: Option<String>,
+    /// SQLite database file for persistent bot state
+    pub database_path: Option<String>,
+    /// Postgres connection string, used instead of `database_path` so several
+    /// processes can share state
+    pub database_url: Option<String>,
+    /// Discord channel that receives a copy of every audit log entry
+    pub audit_channel_id: Option<String>,
+    /// External OCR service used for screenshots in bug threads
+    pub ocr: Option<OcrConfig>,
+    /// Machine translation service for reports in other languages
+    pub translation: Option<TranslationConfig>,
+    /// S3-compatible bucket that holds attachments too large to link from
+    /// Discord
+    pub attachment_store: Option<AttachmentStoreConfig>,
+    /// Matrix homeserver for projects with `platform = "matrix"`
+    pub matrix: Option<MatrixConfig>,
+    /// HTTP listener for GitHub webhooks that trigger an immediate sync
+    pub webhook: Option<WebhookConfig>,
+    /// Restart the Discord client after the gateway has been down this long
+    pub gateway_watchdog_seconds: Option<u64>,
+    /// Refuse to start when a GitHub App installation lacks a repository or
+    /// issue write access, instead of only logging it
+    pub require_github_permissions: Option<bool>,
+    /// Extra GitHub credentials for repositories the default account can't reach
+    #[serde(default)]
+    pub github_accounts: Vec<GitHubAccount>,
+    /// Discord bots besides the `DISCORD_TOKEN` one, for branded deployments
+    #[serde(default)]
+    pub discord_bots: Vec<DiscordBotConfig>,
+    #[serde(default)]
+    pub defaults: ProjectDefaults,
     pub projects: Vec<Project>,
     pub sync: Option<SyncConfig>,
 }
@@ -15,6 +49,22 @@ pub struct SyncConfig {
     pub enabled: bool,
     #[serde(default = "default_sync_interval")]
     pub interval_seconds: u64,
+    /// Random delay of up to this long added to each cycle
+    pub jitter_seconds: Option<u64>,
+    /// Pause between projects within a cycle
+    pub stagger_seconds: Option<u64>,
+    /// Ops channel where a status message is edited after every cycle
+    pub status_channel_id: Option<String>,
+    /// Threads checked at once within a project's sync
+    pub concurrency: Option<usize>,
+}
+
+impl SyncConfig {
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+            .unwrap_or(crate::constants::DEFAULT_SYNC_CONCURRENCY)
+            .max(1)
+    }
 }
 
 fn default_sync_enabled() -> bool {
@@ -25,23 +75,936 @@ fn default_sync_interval() -> u64 {
     60 // 1 minute instead of 10 seconds to avoid rate limits
 }
 
+/// OCR service that accepts a raw image POST and answers with plain text.
+/// A bearer token is sent from `OCR_API_KEY` when set.
+#[derive(Debug, Deserialize, Clone)]
+pub struct OcrConfig {
+    pub endpoint: String,
+}
+
+/// Machine translation API. The key is read from the `api_key_env`
+/// environment variable; self-hosted LibreTranslate may not need one.
+#[derive(Debug, Deserialize, Clone)]
+pub struct TranslationConfig {
+    pub provider: TranslationProvider,
+    /// API base URL; the provider's public API by default, required for
+    /// LibreTranslate
+    pub endpoint: Option<String>,
+    pub api_key_env: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslationProvider {
+    Deepl,
+    Google,
+    LibreTranslate,
+}
+
+impl TranslationProvider {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TranslationProvider::Deepl => "DeepL",
+            TranslationProvider::Google => "Google Translate",
+            TranslationProvider::LibreTranslate => "LibreTranslate",
+        }
+    }
+}
+
+/// S3-compatible object store for oversized attachments, signed with
+/// `ATTACHMENT_STORE_ACCESS_KEY` and `ATTACHMENT_STORE_SECRET_KEY`
+#[derive(Debug, Deserialize, Clone)]
+pub struct AttachmentStoreConfig {
+    /// Service URL, e.g. "https://s3.us-east-1.amazonaws.com"; objects are
+    /// addressed path-style under it
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: Option<String>,
+    /// Public base URL the bucket's objects are served from, used in issues
+    pub public_url: String,
+    /// Attachments at least this large are re-hosted; smaller ones link to
+    /// Discord
+    pub min_size_mb: Option<u64>,
+    /// Attachments larger than this aren't downloaded and link to Discord
+    pub max_size_mb: Option<u64>,
+    /// Re-hosted attachments are deleted after this many days
+    pub expiry_days: Option<u64>,
+}
+
+/// Matrix account the bot uses, authenticated with `MATRIX_ACCESS_TOKEN`
+#[derive(Debug, Deserialize, Clone)]
+pub struct MatrixConfig {
+    /// Client-server API base URL, e.g. "https://matrix.example.org"
+    pub homeserver_url: String,
+}
+
+impl AttachmentStoreConfig {
+    pub fn region(&self) -> &str {
+        self.region.as_deref().unwrap_or("us-east-1")
+    }
+
+    pub fn min_size_bytes(&self) -> u64 {
+        self.min_size_mb
+            .unwrap_or(crate::constants::DEFAULT_ATTACHMENT_REHOST_MIN_MB)
+            * 1024
+            * 1024
+    }
+
+    pub fn max_size_bytes(&self) -> u64 {
+        self.max_size_mb
+            .unwrap_or(crate::constants::DEFAULT_ATTACHMENT_REHOST_MAX_MB)
+            * 1024
+            * 1024
+    }
+
+    pub fn expiry_days(&self) -> u64 {
+        self.expiry_days
+            .unwrap_or(crate::constants::DEFAULT_ATTACHMENT_EXPIRY_DAYS)
+    }
+}
+
+/// Deliveries must be signed with `GITHUB_WEBHOOK_SECRET`
+#[derive(Debug, Deserialize, Clone)]
+pub struct WebhookConfig {
+    /// Address to listen on, e.g. "0.0.0.0:8080"
+    pub listen: String,
+}
+
+/// Named GitHub credentials, either a personal access token read from an
+/// environment variable or a GitHub App installation
+#[derive(Debug, Deserialize, Clone)]
+pub struct GitHubAccount {
+    pub name: String,
+    /// Environment variable holding a personal access token
+    pub token_env: Option<String>,
+    pub app_id: Option<String>,
+    pub installation_id: Option<u64>,
+    pub private_key_path: Option<String>,
+    /// REST API base of a GitHub Enterprise Server, e.g. `https://github.example.com/api/v3`
+    pub api_url: Option<String>,
+}
+
+/// A separately branded Discord bot; each one keeps its own gateway connection
+#[derive(Debug, Deserialize, Clone)]
+pub struct DiscordBotConfig {
+    pub name: String,
+    /// Environment variable holding the bot token
+    pub token_env: String,
+}
+
+/// Reporter details appended to issue bodies
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ReporterProfileConfig {
+    /// Role names worth showing, such as "beta tester"; other roles are left out
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+/// Progress embeds for milestones with Discord-reported issues
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct MilestoneAnnouncementsConfig {
+    pub channel_id: String,
+    /// Completion percentages to announce, 50 and 100 by default
+    pub thresholds: Option<Vec<u8>>,
+}
+
+impl MilestoneAnnouncementsConfig {
+    pub fn thresholds(&self) -> Vec<u8> {
+        self.thresholds
+            .clone()
+            .unwrap_or_else(|| crate::constants::DEFAULT_MILESTONE_THRESHOLDS.to_vec())
+    }
+}
+
+/// Periodic reports of open threads that look like the same problem
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct DuplicateReportsConfig {
+    pub channel_id: String,
+    /// Hours between scans, 6 by default
+    pub interval_hours: Option<u64>,
+    /// Similarity from 0 to 1 at which two threads are grouped, 0.5 by default
+    pub similarity: Option<f64>,
+}
+
+impl DuplicateReportsConfig {
+    pub fn interval_hours(&self) -> u64 {
+        self.interval_hours
+            .unwrap_or(crate::constants::DEFAULT_DUPLICATE_SCAN_HOURS)
+    }
+
+    pub fn similarity(&self) -> f64 {
+        self.similarity
+            .unwrap_or(crate::constants::DEFAULT_DUPLICATE_SIMILARITY)
+    }
+}
+
+/// Event posted to an external reward system when a Discord-reported issue
+/// is closed as completed
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct RewardHookConfig {
+    pub url: String,
+    /// Environment variable holding the HMAC-SHA256 signing secret; events
+    /// are sent unsigned without one
+    pub secret_env: Option<String>,
+    /// Send the Discord ID of anonymous reporters too, so they're credited
+    pub include_anonymous: Option<bool>,
+}
+
+impl RewardHookConfig {
+    pub fn include_anonymous(&self) -> bool {
+        self.include_anonymous.unwrap_or(false)
+    }
+}
+
+/// Message sent once to each member who opens their first prefixed thread.
+/// `{user}`, `{project}`, `{repo_url}` and `{thread_url}` in `message` are
+/// filled in.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct OnboardingConfig {
+    pub message: Option<String>,
+}
+
+impl OnboardingConfig {
+    pub fn message(&self) -> &str {
+        self.message
+            .as_deref()
+            .unwrap_or(crate::constants::DEFAULT_ONBOARDING_MESSAGE)
+    }
+}
+
+/// Hide wallet addresses and transaction hashes in public GitHub issues
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct RedactWalletsConfig {
+    /// Private channel that receives the full values; they are only shortened
+    /// when unset
+    pub channel_id: Option<String>,
+}
+
+/// Keep Discord invites and scam bait out of public GitHub issues
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ScamFilterConfig {
+    /// Moderator channel told which threads had content removed
+    pub channel_id: Option<String>,
+    /// Regexes removed in addition to the built-in invite and seed phrase patterns
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+/// Color and description `setup-labels` gives a GitHub label
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct LabelStyle {
+    /// Hex color without the leading `#`
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Questionnaire posted when a maintainer reacts with `emoji` or adds `label`
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct NeedsInfoConfig {
+    pub emoji: Option<String>,
+    pub label: Option<String>,
+    /// Up to five questions; Discord forms hold no more
+    pub questions: Option<Vec<String>>,
+}
+
+impl NeedsInfoConfig {
+    pub fn emoji(&self) -> &str {
+        self.emoji
+            .as_deref()
+            .unwrap_or(crate::constants::DEFAULT_NEEDS_INFO_EMOJI)
+    }
+
+    pub fn label(&self) -> &str {
+        self.label
+            .as_deref()
+            .unwrap_or(crate::constants::DEFAULT_NEEDS_INFO_LABEL)
+    }
+
+    pub fn questions(&self) -> Vec<String> {
+        match &self.questions {
+            Some(questions) if !questions.is_empty() => questions.iter().take(5).cloned().collect(),
+            _ => crate::constants::DEFAULT_NEEDS_INFO_QUESTIONS
+                .iter()
+                .map(|q| q.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Forum tags the syncer keeps in step with the linked issue's state
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct StatusTagsConfig {
+    /// Open, unassigned, and labeled or milestoned by a maintainer
+    pub triaged: Option<String>,
+    /// Open and assigned
+    pub in_progress: Option<String>,
+    /// Closed as completed while its milestone, if any, is still open
+    pub fixed_pending_release: Option<String>,
+    /// Labels that count as triage; any label besides the prefix labels otherwise
+    pub triaged_labels: Option<Vec<String>>,
+}
+
+impl StatusTagsConfig {
+    pub fn triaged(&self) -> &str {
+        self.triaged
+            .as_deref()
+            .unwrap_or(crate::constants::DEFAULT_TRIAGED_TAG)
+    }
+
+    pub fn in_progress(&self) -> &str {
+        self.in_progress
+            .as_deref()
+            .unwrap_or(crate::constants::DEFAULT_IN_PROGRESS_TAG)
+    }
+
+    pub fn fixed_pending_release(&self) -> &str {
+        self.fixed_pending_release
+            .as_deref()
+            .unwrap_or(crate::constants::DEFAULT_FIXED_PENDING_RELEASE_TAG)
+    }
+
+    /// Every tag name, for setting up the forum and telling status tags apart
+    pub fn tag_names(&self) -> [&str; 3] {
+        [
+            self.triaged(),
+            self.in_progress(),
+            self.fixed_pending_release(),
+        ]
+    }
+}
+
+/// Thresholds for filing issues automatically; any one being met is enough
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct AutoCreateConfig {
+    pub min_messages: Option<usize>,
+    pub min_participants: Option<usize>,
+    /// 👍 reactions on the starter message
+    pub min_reactions: Option<usize>,
+    /// Hours before a below-threshold thread gets a nudge
+    pub nudge_after_hours: Option<u64>,
+}
+
+/// Organization-wide settings inherited by every project unless overridden
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ProjectDefaults {
+    pub github_owner: Option<String>,
+    pub allowed_role_id: Option<String>,
+    pub prefixes: Option<Vec<PrefixConfig>>,
+    pub sync_enabled: Option<bool>,
+    pub issue_body_template: Option<String>,
+    pub ci_status_updates: Option<bool>,
+    pub verify_fix: Option<bool>,
+    pub verify_fix_timeout_hours: Option<u64>,
+    pub command_cooldown_seconds: Option<u64>,
+    pub quiet_hours: Option<Vec<QuietHours>>,
+    pub maintenance_windows: Option<Vec<MaintenanceWindow>>,
+    pub notify_role_id: Option<String>,
+    pub triage_channel_id: Option<String>,
+    pub ocr_screenshots: Option<bool>,
+    pub translate_issues: Option<bool>,
+    pub rehost_attachments: Option<bool>,
+    pub comment_commands: Option<bool>,
+    pub priority_labels: Option<Vec<String>>,
+    pub feedback_auto_issue: Option<bool>,
+    pub mirror_replies: Option<bool>,
+    pub propagate_edits: Option<bool>,
+    pub stats_comment: Option<bool>,
+    pub auto_create: Option<AutoCreateConfig>,
+    pub triage_queue: Option<bool>,
+    pub max_open_issues: Option<u64>,
+    pub github_project_number: Option<u64>,
+    pub project_fields: Option<Vec<ProjectFieldMapping>>,
+    pub sla_targets: Option<Vec<SlaTarget>>,
+    pub github_account: Option<String>,
+    pub discord_bot: Option<String>,
+    pub text_channel_threads: Option<bool>,
+    pub pinned_summary: Option<bool>,
+    pub assignment_updates: Option<bool>,
+    pub release_notifications: Option<bool>,
+    pub mapping_mode: Option<MappingMode>,
+    pub anonymous_reports: Option<bool>,
+    pub transcript_format: Option<TranscriptFormat>,
+    pub moderator_closed_label: Option<String>,
+    pub github_calls_per_cycle: Option<u32>,
+    pub reporter_profile: Option<ReporterProfileConfig>,
+    pub needs_info: Option<NeedsInfoConfig>,
+    pub status_tags: Option<StatusTagsConfig>,
+    pub redact_wallets: Option<RedactWalletsConfig>,
+    pub scam_filter: Option<ScamFilterConfig>,
+    pub label_styles: Option<HashMap<String, LabelStyle>>,
+    pub title_suggestions: Option<bool>,
+    pub comment_reactions: Option<bool>,
+    pub archive_unlinked_after_days: Option<u64>,
+    pub team_mentions: Option<HashMap<String, String>>,
+    pub rename_prefix_variants: Option<bool>,
+    pub transcript_marker_emoji: Option<String>,
+    pub milestone_announcements: Option<MilestoneAnnouncementsConfig>,
+    pub duplicate_reports: Option<DuplicateReportsConfig>,
+    pub reward_hook: Option<RewardHookConfig>,
+    pub onboarding: Option<OnboardingConfig>,
+    pub staging: Option<bool>,
+    pub discord_post_label: Option<String>,
+    pub feedback_template: Option<crate::feedback_template::FeedbackTemplate>,
+    pub github_api_url: Option<String>,
+}
+
+/// Where threads with a prefix are filed on GitHub
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueTarget {
+    #[default]
+    Issues,
+    Discussions,
+    /// Draft item on the project's `github_project_number` board
+    ProjectItem,
+}
+
+/// Fills a Projects v2 field from the thread an item was filed from
+#[derive(Debug, Deserialize, Clone)]
+pub struct ProjectFieldMapping {
+    /// Field name on the board, e.g. "Priority"
+    pub field: String,
+    pub from: ThreadAttribute,
+    /// Attribute values to field values, e.g. `{ "[BUG]" = "Bug" }`; values
+    /// without an entry are used as they are. For `reaction_score` the keys are
+    /// minimum scores and the highest one reached wins.
+    #[serde(default)]
+    pub values: HashMap<String, String>,
+}
+
+/// How soon a maintainer should first respond to a linked issue
+#[derive(Debug, Deserialize, Clone)]
+pub struct SlaTarget {
+    /// Thread prefix the target is for, e.g. "[BUG]"; every issue when unset
+    pub prefix: Option<String>,
+    /// Hours from the issue being filed to the first maintainer comment
+    pub first_response_hours: u64,
+    /// Role pinged in the thread when the target is missed; `notify_role_id`
+    /// otherwise
+    pub role_id: Option<String>,
+}
+
+/// What a Projects v2 field is filled from
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThreadAttribute {
+    Prefix,
+    /// The thread's first forum tag, or the first with an entry in `values`
+    ForumTag,
+    /// 👍 reactions on the opening post
+    ReactionScore,
+    /// The reporter's highest role, or the highest with an entry in `values`
+    ReporterRole,
+}
+
+/// Chat platform whose threads a project bridges
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatPlatform {
+    /// Forum posts in `discord_forum_id`
+    #[default]
+    Discord,
+    /// Threads in `matrix_room_id`, polled by the syncer
+    Matrix,
+    /// Forum topics in the `telegram_chat_id` group, read from the bot's
+    /// updates by the syncer
+    Telegram,
+}
+
+/// Where the link between a thread and its issue is read from
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MappingMode {
+    /// The `[thread_id]` title suffix and hidden body marker on each issue
+    #[default]
+    Markers,
+    /// Markers and the database, with the database filling gaps while
+    /// `migrate-mappings` backfills it
+    Dual,
+    /// The database only; new issues no longer get the title suffix
+    Store,
+}
+
+/// How a thread's messages are laid out in the issue body
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptFormat {
+    /// `**@author**: message` paragraphs
+    #[default]
+    Text,
+    /// A Markdown table with a UTC timestamp per message
+    Table,
+    /// A JSON document in a collapsed code block, for tools that parse issues
+    Json,
+}
+
+/// Maps a Discord thread title prefix to a GitHub label
+#[derive(Debug, Deserialize, Clone)]
+pub struct PrefixConfig {
+    pub prefix: String,
+    pub label: String,
+    /// Forum tag name; defaults to the prefix without brackets
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub target: IssueTarget,
+    /// Category for `target = "discussions"`; defaults to General
+    pub discussion_category: Option<String>,
+    /// Repository for threads with this prefix, as `repo` under the project's
+    /// owner or `owner/repo`; defaults to the project's repository
+    pub github_repo: Option<String>,
+}
+
+impl PrefixConfig {
+    pub fn tag_name(&self) -> String {
+        match &self.tag {
+            Some(tag) => tag.clone(),
+            None => self
+                .prefix
+                .trim_matches(|c| c == '[' || c == ']')
+                .to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct Project {
     pub name: Option<String>,
+    /// Where the project's threads live; Discord unless set
+    pub platform: Option<ChatPlatform>,
+    #[serde(default)]
     pub discord_guild_id: String,
+    #[serde(default)]
     pub discord_forum_id: String,
+    /// Also file public threads of the guild's text channels; the first
+    /// project of a guild with this set takes threads outside its forums
+    pub text_channel_threads: Option<bool>,
+    /// Room whose threads are bridged when `platform = "matrix"`, e.g.
+    /// "!abc123:example.org"
+    pub matrix_room_id: Option<String>,
+    /// Forum-enabled supergroup whose topics are bridged when
+    /// `platform = "telegram"`, e.g. -1001234567890
+    pub telegram_chat_id: Option<i64>,
+    #[serde(default)]
     pub github_owner: String,
     pub github_repo: String,
     pub allowed_role_id: Option<String>,
+    pub prefixes: Option<Vec<PrefixConfig>>,
+    pub sync_enabled: Option<bool>,
+    /// Retired project: no new issues and no syncing, while existing links
+    /// stay queryable. `archive-project` does the final sweep.
+    pub archived: Option<bool>,
+    /// Issue body template; supports {content}, {discord_url} (the thread
+    /// link, on Matrix too) and {author}
+    pub issue_body_template: Option<String>,
+    /// Post CI results of pull requests that fix linked issues into the thread
+    pub ci_status_updates: Option<bool>,
+    /// Ask the reporter to confirm a fix before locking the thread of a closed issue
+    pub verify_fix: Option<bool>,
+    /// Hours to wait for the reporter before locking anyway
+    pub verify_fix_timeout_hours: Option<u64>,
+    /// Minimum seconds between `/issue` runs by the same user
+    pub command_cooldown_seconds: Option<u64>,
+    /// Recurring windows during which the syncer observes but makes no changes
+    pub quiet_hours: Option<Vec<QuietHours>>,
+    /// One-off windows during which the syncer observes but makes no changes
+    pub maintenance_windows: Option<Vec<MaintenanceWindow>>,
+    /// Role pinged when a new issue is created from a thread
+    pub notify_role_id: Option<String>,
+    /// Channel that receives a summary of every new issue
+    pub triage_channel_id: Option<String>,
+    /// Add text extracted from screenshots to bug issues
+    pub ocr_screenshots: Option<bool>,
+    /// Add an English machine translation to issues filed from threads in
+    /// other languages
+    pub translate_issues: Option<bool>,
+    /// Copy oversized attachments to `[attachment_store]`; off keeps them
+    /// out of third-party storage and lists them by name only
+    pub rehost_attachments: Option<bool>,
+    /// Run `/discord` commands from maintainer comments on linked issues
+    pub comment_commands: Option<bool>,
+    /// 👍/👎 buttons under relayed comments, forwarded as GitHub reactions
+    pub comment_reactions: Option<bool>,
+    /// Mutually exclusive labels offered by `/issue priority`
+    pub priority_labels: Option<Vec<String>>,
+    /// File the GitHub issue straight away for posts made with `/feedback`
+    pub feedback_auto_issue: Option<bool>,
+    /// Post new thread replies to the linked issue as comments
+    pub mirror_replies: Option<bool>,
+    /// Carry Discord edits and deletions over to the issue body and mirrored comments
+    pub propagate_edits: Option<bool>,
+    /// Keep a comment on the issue with the thread's participants, messages and reactions
+    pub stats_comment: Option<bool>,
+    /// File issues for active threads without waiting for `/issue create`
+    pub auto_create: Option<AutoCreateConfig>,
+    /// Send auto-create candidates to the triage channel for approval first
+    pub triage_queue: Option<bool>,
+    /// Open issues filed from Discord above which `/issue create` holds new
+    /// threads back, queuing them for triage when there is a triage channel
+    pub max_open_issues: Option<u64>,
+    /// Projects v2 board number for prefixes with `target = "project_item"`
+    /// and for `project_fields`
+    pub github_project_number: Option<u64>,
+    /// Add new issues to the `github_project_number` board and fill these
+    /// fields from the thread
+    pub project_fields: Option<Vec<ProjectFieldMapping>>,
+    /// Response time targets for linked issues; the first that applies wins
+    pub sla_targets: Option<Vec<SlaTarget>>,
+    /// Name of a `[[github_accounts]]` entry; the environment credentials otherwise
+    pub github_account: Option<String>,
+    /// Name of a `[[discord_bots]]` entry; the `DISCORD_TOKEN` bot otherwise
+    pub discord_bot: Option<String>,
+    /// Keep a pinned message with the issue's status at the top of each thread
+    pub pinned_summary: Option<bool>,
+    /// Tell the thread when the issue is assigned, reassigned or unassigned
+    pub assignment_updates: Option<bool>,
+    /// Tell threads which release fixed their issue when it is published
+    pub release_notifications: Option<bool>,
+    /// Where thread ↔ issue links are read from; see `migrate-mappings`
+    pub mapping_mode: Option<MappingMode>,
+    /// Leave reporters' Discord names out of GitHub issues unless they opt in
+    pub anonymous_reports: Option<bool>,
+    /// How the thread's messages are written into the issue body
+    pub transcript_format: Option<TranscriptFormat>,
+    /// Label added to the issue when a moderator locks or archives its thread
+    pub moderator_closed_label: Option<String>,
+    /// Rename threads like `bug: ...` or `(Bug) ...` to the configured `[BUG] ...`
+    pub rename_prefix_variants: Option<bool>,
+    /// Offer the thread owner a clearer title before filing a vague one
+    pub title_suggestions: Option<bool>,
+    /// Reaction that marks thread messages to include in the issue body
+    pub transcript_marker_emoji: Option<String>,
+    /// Announce milestone progress in a channel
+    pub milestone_announcements: Option<MilestoneAnnouncementsConfig>,
+    /// Report groups of open threads that look like duplicates to moderators
+    pub duplicate_reports: Option<DuplicateReportsConfig>,
+    /// Tell an external reward system when a reported issue is fixed
+    pub reward_hook: Option<RewardHookConfig>,
+    /// Explain the GitHub bridge to members opening their first thread
+    pub onboarding: Option<OnboardingConfig>,
+    /// Test a new cardibot version against the real forum: issues go to
+    /// `staging_repo` with `[staging]` titles and threads are never locked
+    /// or archived
+    pub staging: Option<bool>,
+    /// Sandbox repository of a staging project, as `owner/repo`
+    pub staging_repo: Option<String>,
+    /// Archive prefixed threads without an issue after this many idle days
+    pub archive_unlinked_after_days: Option<u64>,
+    /// Issues opened on GitHub with this label get a forum post
+    pub discord_post_label: Option<String>,
+    /// Instructions posted by `post-feedback` for this project
+    pub feedback_template: Option<crate::feedback_template::FeedbackTemplate>,
+    /// REST API base of a GitHub Enterprise Server; the account's `api_url`,
+    /// then `[defaults]`, then `GITHUB_API_URL`, then github.com otherwise
+    pub github_api_url: Option<String>,
+    /// Cap on follow-up GitHub API calls per sync interval; unlimited when unset
+    pub github_calls_per_cycle: Option<u32>,
+    /// GitHub team mentioned in new issues, by forum tag name or prefix
+    pub team_mentions: Option<HashMap<String, String>>,
+    /// Add the reporter's display name, relevant roles and account age to issue bodies
+    pub reporter_profile: Option<ReporterProfileConfig>,
+    /// Ask the reporter a questionnaire on a maintainer reaction or label
+    pub needs_info: Option<NeedsInfoConfig>,
+    /// Forum tags showing whether the issue is triaged, in progress or fixed
+    pub status_tags: Option<StatusTagsConfig>,
+    /// Shorten wallet addresses, tx hashes and home paths in issues
+    pub redact_wallets: Option<RedactWalletsConfig>,
+    /// Remove invite links and scam patterns from issues and flag the thread
+    pub scam_filter: Option<ScamFilterConfig>,
+    /// Colors and descriptions for labels created by `setup-labels`
+    pub label_styles: Option<HashMap<String, LabelStyle>>,
 }
 
 impl Config {
     pub fn load() -> Result<Self> {
         let contents = fs::read_to_string(crate::constants::DEFAULT_CONFIG_PATH)?;
-        let config: Config = toml::from_str(&contents)?;
+        let mut config: Config = toml::from_str(&contents)?;
+        config.apply_defaults()?;
         Ok(config)
     }
 
+    /// Fill unset project fields from the `[defaults]` section
+    fn apply_defaults(&mut self) -> Result<()> {
+        let defaults = self.defaults.clone();
+
+        if let Some(translation) = &self.translation {
+            if translation.provider == TranslationProvider::LibreTranslate
+                && translation.endpoint.is_none()
+            {
+                anyhow::bail!("[translation] with LibreTranslate needs an endpoint");
+            }
+        }
+
+        for project in &mut self.projects {
+            if project.github_owner.is_empty() {
+                project.github_owner = defaults.github_owner.clone().unwrap_or_default();
+            }
+            if project.allowed_role_id.is_none() {
+                project.allowed_role_id = defaults.allowed_role_id.clone();
+            }
+            if project.prefixes.is_none() {
+                project.prefixes = defaults.prefixes.clone();
+            }
+            if project.sync_enabled.is_none() {
+                project.sync_enabled = defaults.sync_enabled;
+            }
+            if project.issue_body_template.is_none() {
+                project.issue_body_template = defaults.issue_body_template.clone();
+            }
+            if project.ci_status_updates.is_none() {
+                project.ci_status_updates = defaults.ci_status_updates;
+            }
+            if project.verify_fix.is_none() {
+                project.verify_fix = defaults.verify_fix;
+            }
+            if project.verify_fix_timeout_hours.is_none() {
+                project.verify_fix_timeout_hours = defaults.verify_fix_timeout_hours;
+            }
+            if project.command_cooldown_seconds.is_none() {
+                project.command_cooldown_seconds = defaults.command_cooldown_seconds;
+            }
+            if project.quiet_hours.is_none() {
+                project.quiet_hours = defaults.quiet_hours.clone();
+            }
+            if project.maintenance_windows.is_none() {
+                project.maintenance_windows = defaults.maintenance_windows.clone();
+            }
+            if project.notify_role_id.is_none() {
+                project.notify_role_id = defaults.notify_role_id.clone();
+            }
+            if project.triage_channel_id.is_none() {
+                project.triage_channel_id = defaults.triage_channel_id.clone();
+            }
+            if project.ocr_screenshots.is_none() {
+                project.ocr_screenshots = defaults.ocr_screenshots;
+            }
+            if project.translate_issues.is_none() {
+                project.translate_issues = defaults.translate_issues;
+            }
+            if project.rehost_attachments.is_none() {
+                project.rehost_attachments = defaults.rehost_attachments;
+            }
+            if project.comment_commands.is_none() {
+                project.comment_commands = defaults.comment_commands;
+            }
+            if project.priority_labels.is_none() {
+                project.priority_labels = defaults.priority_labels.clone();
+            }
+            if project.feedback_auto_issue.is_none() {
+                project.feedback_auto_issue = defaults.feedback_auto_issue;
+            }
+            if project.mirror_replies.is_none() {
+                project.mirror_replies = defaults.mirror_replies;
+            }
+            if project.propagate_edits.is_none() {
+                project.propagate_edits = defaults.propagate_edits;
+            }
+            if project.stats_comment.is_none() {
+                project.stats_comment = defaults.stats_comment;
+            }
+            if project.max_open_issues.is_none() {
+                project.max_open_issues = defaults.max_open_issues;
+            }
+            if project.auto_create.is_none() {
+                project.auto_create = defaults.auto_create.clone();
+            }
+            if project.triage_queue.is_none() {
+                project.triage_queue = defaults.triage_queue;
+            }
+            if project.github_project_number.is_none() {
+                project.github_project_number = defaults.github_project_number;
+            }
+            if project.project_fields.is_none() {
+                project.project_fields = defaults.project_fields.clone();
+            }
+            if project.sla_targets.is_none() {
+                project.sla_targets = defaults.sla_targets.clone();
+            }
+            if project.github_account.is_none() {
+                project.github_account = defaults.github_account.clone();
+            }
+            if project.discord_bot.is_none() {
+                project.discord_bot = defaults.discord_bot.clone();
+            }
+            if project.text_channel_threads.is_none() {
+                project.text_channel_threads = defaults.text_channel_threads;
+            }
+            if project.pinned_summary.is_none() {
+                project.pinned_summary = defaults.pinned_summary;
+            }
+            if project.assignment_updates.is_none() {
+                project.assignment_updates = defaults.assignment_updates;
+            }
+            if project.release_notifications.is_none() {
+                project.release_notifications = defaults.release_notifications;
+            }
+            if project.mapping_mode.is_none() {
+                project.mapping_mode = defaults.mapping_mode;
+            }
+            if project.anonymous_reports.is_none() {
+                project.anonymous_reports = defaults.anonymous_reports;
+            }
+            if project.transcript_format.is_none() {
+                project.transcript_format = defaults.transcript_format;
+            }
+            if project.moderator_closed_label.is_none() {
+                project.moderator_closed_label = defaults.moderator_closed_label.clone();
+            }
+            if project.rename_prefix_variants.is_none() {
+                project.rename_prefix_variants = defaults.rename_prefix_variants;
+            }
+            if project.transcript_marker_emoji.is_none() {
+                project.transcript_marker_emoji = defaults.transcript_marker_emoji.clone();
+            }
+            if project.milestone_announcements.is_none() {
+                project.milestone_announcements = defaults.milestone_announcements.clone();
+            }
+            if project.duplicate_reports.is_none() {
+                project.duplicate_reports = defaults.duplicate_reports.clone();
+            }
+            if project.reward_hook.is_none() {
+                project.reward_hook = defaults.reward_hook.clone();
+            }
+            if project.onboarding.is_none() {
+                project.onboarding = defaults.onboarding.clone();
+            }
+            if project.staging.is_none() {
+                project.staging = defaults.staging;
+            }
+            if project.discord_post_label.is_none() {
+                project.discord_post_label = defaults.discord_post_label.clone();
+            }
+            if project.feedback_template.is_none() {
+                project.feedback_template = defaults.feedback_template.clone();
+            }
+            if project.github_calls_per_cycle.is_none() {
+                project.github_calls_per_cycle = defaults.github_calls_per_cycle;
+            }
+            if project.reporter_profile.is_none() {
+                project.reporter_profile = defaults.reporter_profile.clone();
+            }
+            if project.needs_info.is_none() {
+                project.needs_info = defaults.needs_info.clone();
+            }
+            if project.status_tags.is_none() {
+                project.status_tags = defaults.status_tags.clone();
+            }
+            if project.redact_wallets.is_none() {
+                project.redact_wallets = defaults.redact_wallets.clone();
+            }
+            if project.team_mentions.is_none() {
+                project.team_mentions = defaults.team_mentions.clone();
+            }
+            if project.archive_unlinked_after_days.is_none() {
+                project.archive_unlinked_after_days = defaults.archive_unlinked_after_days;
+            }
+            if project.comment_reactions.is_none() {
+                project.comment_reactions = defaults.comment_reactions;
+            }
+            if project.title_suggestions.is_none() {
+                project.title_suggestions = defaults.title_suggestions;
+            }
+            if project.label_styles.is_none() {
+                project.label_styles = defaults.label_styles.clone();
+            }
+            if project.scam_filter.is_none() {
+                project.scam_filter = defaults.scam_filter.clone();
+            }
+
+            // An empty role ID explicitly removes an inherited restriction
+            if project.allowed_role_id.as_deref() == Some("") {
+                project.allowed_role_id = None;
+            }
+
+            // Everything a staging project files goes to its sandbox
+            if project.staging() {
+                let Some((owner, repo)) = project
+                    .staging_repo
+                    .as_deref()
+                    .and_then(|r| r.split_once('/'))
+                else {
+                    anyhow::bail!(
+                        "Staging project '{}' needs a staging_repo as owner/repo",
+                        project.name.as_deref().unwrap_or(&project.github_repo)
+                    );
+                };
+                project.github_owner = owner.to_string();
+                project.github_repo = repo.to_string();
+            }
+
+            if project.github_owner.is_empty() {
+                anyhow::bail!(
+                    "Project '{}' has no github_owner and no default is configured",
+                    project.name.as_deref().unwrap_or(&project.github_repo)
+                );
+            }
+
+            if let Some(account) = &project.github_account {
+                let Some(account) = self.github_accounts.iter().find(|a| &a.name == account) else {
+                    anyhow::bail!(
+                        "Project '{}' uses unknown GitHub account '{}'",
+                        project.name.as_deref().unwrap_or(&project.github_repo),
+                        account
+                    );
+                };
+                if project.github_api_url.is_none() {
+                    project.github_api_url = account.api_url.clone();
+                }
+            }
+            // After the account's, which belongs to the project more closely
+            if project.github_api_url.is_none() {
+                project.github_api_url = defaults.github_api_url.clone();
+            }
+
+            for pattern in project.scam_filter.iter().flat_map(|f| &f.patterns) {
+                if let Err(e) = regex::Regex::new(pattern) {
+                    anyhow::bail!(
+                        "Project '{}' has an invalid scam_filter pattern '{}': {}",
+                        project.name.as_deref().unwrap_or(&project.github_repo),
+                        pattern,
+                        e
+                    );
+                }
+            }
+
+            let name = project.name.as_deref().unwrap_or(&project.github_repo);
+            match project.platform() {
+                ChatPlatform::Discord => {
+                    if project.discord_guild_id.is_empty() || project.discord_forum_id.is_empty() {
+                        anyhow::bail!(
+                            "Project '{}' needs discord_guild_id and discord_forum_id",
+                            name
+                        );
+                    }
+                }
+                ChatPlatform::Telegram => {
+                    if project.telegram_chat_id.is_none() {
+                        anyhow::bail!("Telegram project '{}' needs a telegram_chat_id", name);
+                    }
+                }
+                ChatPlatform::Matrix => {
+                    if project.matrix_room_id.is_none() {
+                        anyhow::bail!("Matrix project '{}' needs a matrix_room_id", name);
+                    }
+                    if self.matrix.is_none() {
+                        anyhow::bail!("Matrix project '{}' needs a [matrix] homeserver_url", name);
+                    }
+                }
+            }
+
+            if let Some(bot) = &project.discord_bot {
+                if !self.discord_bots.iter().any(|b| &b.name == bot) {
+                    anyhow::bail!(
+                        "Project '{}' uses unknown Discord bot '{}'",
+                        project.name.as_deref().unwrap_or(&project.github_repo),
+                        bot
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn find_project(&self, guild_id: u64, channel_id: u64) -> Option<&Project> {
         self.projects.iter().find(|p| {
             p.discord_guild_id == guild_id.to_string()
@@ -49,10 +1012,664 @@ impl Config {
         })
     }
 
+    /// The project of a thread in a forum or, failing that, the guild's
+    /// project for text channel threads
+    pub fn find_thread_project(&self, guild_id: u64, parent_id: u64) -> Option<&Project> {
+        self.find_project(guild_id, parent_id).or_else(|| {
+            self.projects
+                .iter()
+                .find(|p| p.discord_guild_id == guild_id.to_string() && p.text_channel_threads())
+        })
+    }
+
+    /// A project by `name`, or the project or prefix route for an
+    /// "owner/repo" key
+    pub fn find_route(&self, name: &str) -> Option<Project> {
+        let named = self.projects.iter().find(|p| {
+            p.name
+                .as_deref()
+                .is_some_and(|n| n.eq_ignore_ascii_case(name))
+        });
+        if let Some(project) = named {
+            return Some(project.clone());
+        }
+        self.projects
+            .iter()
+            .flat_map(Project::routes)
+            .find(|route| crate::audit::project_key(route).eq_ignore_ascii_case(name))
+    }
+
+    pub fn github_account(&self, name: &str) -> Option<&GitHubAccount> {
+        self.github_accounts.iter().find(|a| a.name == name)
+    }
+
+    /// Bots that serve at least one project, the `DISCORD_TOKEN` one first as None
+    pub fn discord_bot_names(&self) -> Vec<Option<String>> {
+        let mut names: Vec<Option<String>> = Vec::new();
+        for project in &self.projects {
+            if !names.contains(&project.discord_bot) {
+                names.push(project.discord_bot.clone());
+            }
+        }
+        names.sort_by_key(Option::is_some);
+        names
+    }
+
+    pub fn discord_token(&self, bot: Option<&str>) -> Result<String> {
+        let env = match bot {
+            Some(name) => match self.discord_bots.iter().find(|b| b.name == name) {
+                Some(bot) => bot.token_env.as_str(),
+                None => anyhow::bail!("Unknown Discord bot '{name}'"),
+            },
+            None => "DISCORD_TOKEN",
+        };
+        std::env::var(env).map_err(|_| anyhow::anyhow!("{env} is not set"))
+    }
+
+    /// This config narrowed to the projects one bot serves
+    pub fn for_discord_bot(&self, bot: Option<&str>) -> Config {
+        let mut config = self.clone();
+        config.projects.retain(|p| p.discord_bot.as_deref() == bot);
+        config
+    }
+
+    pub fn gateway_watchdog(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(
+            self.gateway_watchdog_seconds
+                .unwrap_or(crate::constants::DEFAULT_GATEWAY_WATCHDOG_SECS),
+        )
+    }
+
+    pub fn require_github_permissions(&self) -> bool {
+        self.require_github_permissions.unwrap_or(false)
+    }
+
+    /// `database_url`, or `DATABASE_URL` from the environment
+    pub fn database_url(&self) -> Option<String> {
+        self.database_url
+            .clone()
+            .or_else(|| std::env::var("DATABASE_URL").ok())
+            .filter(|url| !url.is_empty())
+    }
+
+    /// Where state is kept, for messages; Postgres credentials are left out
+    pub fn database_name(&self) -> String {
+        match self.database_url() {
+            Some(_) => "the Postgres database".to_string(),
+            None => self.database_path().to_string(),
+        }
+    }
+
+    pub fn database_path(&self) -> &str {
+        self.database_path
+            .as_deref()
+            .unwrap_or(crate::constants::DEFAULT_DATABASE_PATH)
+    }
+
     pub fn sync_config(&self) -> SyncConfig {
         self.sync.clone().unwrap_or(SyncConfig {
             enabled: default_sync_enabled(),
             interval_seconds: default_sync_interval(),
+            jitter_seconds: None,
+            stagger_seconds: None,
+            status_channel_id: None,
+            concurrency: None,
         })
     }
 }
+
+/// The built-in prefixes used when a project configures none
+pub fn default_prefixes() -> Vec<PrefixConfig> {
+    crate::constants::DEFAULT_PREFIX_LABELS
+        .iter()
+        .map(|(prefix, label)| PrefixConfig {
+            prefix: prefix.to_string(),
+            label: label.to_string(),
+            tag: None,
+            target: IssueTarget::default(),
+            discussion_category: None,
+            github_repo: None,
+        })
+        .collect()
+}
+
+impl Project {
+    /// Configured prefix taxonomy, falling back to the built-in prefixes
+    pub fn prefixes(&self) -> Vec<PrefixConfig> {
+        match &self.prefixes {
+            Some(prefixes) => prefixes.clone(),
+            None => default_prefixes(),
+        }
+    }
+
+    /// The project as seen by a thread with this title: pointed at its
+    /// prefix's `github_repo` when one is set, unless it is a staging project
+    pub fn routed(&self, title: &str) -> Project {
+        let mut project = self.clone();
+        // Prefix routes point at real repositories
+        if self.staging() {
+            return project;
+        }
+        if let Some(repo) = self.prefix_for_title(title).and_then(|p| p.github_repo) {
+            match repo.split_once('/') {
+                Some((owner, repo)) => {
+                    project.github_owner = owner.to_string();
+                    project.github_repo = repo.to_string();
+                }
+                None => project.github_repo = repo,
+            }
+        }
+        project
+    }
+
+    /// The project once per repository its prefixes route to, its own
+    /// repository first
+    pub fn routes(&self) -> Vec<Project> {
+        let mut routes = vec![self.clone()];
+        for prefix in self.prefixes() {
+            let routed = self.routed(&prefix.prefix);
+            let key = crate::audit::project_key(&routed);
+            if !routes.iter().any(|r| crate::audit::project_key(r) == key) {
+                routes.push(routed);
+            }
+        }
+        routes
+    }
+
+    /// Comma-separated list of prefixes for display
+    pub fn prefix_list(&self) -> String {
+        self.prefixes()
+            .iter()
+            .map(|p| p.prefix.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Whether the thread starts with a configured prefix or a variant of one
+    pub fn has_valid_prefix(&self, thread_name: &str) -> bool {
+        self.match_prefix(thread_name).is_some()
+    }
+
+    /// Prefix the title starts with, accepting `[bug]`, `(BUG)`, `BUG:` or a typo
+    pub fn match_prefix(&self, title: &str) -> Option<PrefixConfig> {
+        crate::prefix::match_prefix(title, &self.prefixes()).map(|(prefix, _)| prefix.clone())
+    }
+
+    /// Title with a prefix variant rewritten to the configured form, if it differs
+    pub fn canonical_title(&self, title: &str) -> Option<String> {
+        crate::prefix::match_prefix(title, &self.prefixes())
+            .map(|(_, canonical)| canonical)
+            .filter(|canonical| canonical != title)
+    }
+
+    pub fn project_fields(&self) -> &[ProjectFieldMapping] {
+        self.project_fields.as_deref().unwrap_or_default()
+    }
+
+    /// The SLA target for an issue with these labels, matched through the
+    /// label of each target's prefix
+    pub fn sla_target(&self, labels: &[String]) -> Option<&SlaTarget> {
+        let prefixes = self.prefixes();
+        self.sla_targets.as_deref()?.iter().find(|target| {
+            let Some(prefix) = &target.prefix else {
+                return true;
+            };
+            prefixes
+                .iter()
+                .filter(|p| p.prefix.eq_ignore_ascii_case(prefix))
+                .any(|p| labels.iter().any(|l| l.eq_ignore_ascii_case(&p.label)))
+        })
+    }
+
+    /// The first configured prefix found in the title
+    pub fn prefix_for_title(&self, title: &str) -> Option<PrefixConfig> {
+        self.prefixes()
+            .into_iter()
+            .find(|p| title.contains(&p.prefix))
+            .or_else(|| self.match_prefix(title))
+    }
+
+    /// GitHub labels for every configured prefix found in the title
+    pub fn labels_for_title(&self, title: &str) -> Vec<String> {
+        let mut labels: Vec<String> = self
+            .prefixes()
+            .into_iter()
+            .filter(|p| title.contains(&p.prefix))
+            .map(|p| p.label)
+            .collect();
+        if let Some(prefix) = self.match_prefix(title) {
+            if !labels.contains(&prefix.label) {
+                labels.insert(0, prefix.label);
+            }
+        }
+        labels
+    }
+
+    pub fn priority_labels(&self) -> Vec<String> {
+        match &self.priority_labels {
+            Some(labels) => labels.clone(),
+            None => crate::constants::DEFAULT_PRIORITY_LABELS
+                .iter()
+                .map(|l| l.to_string())
+                .collect(),
+        }
+    }
+
+    pub fn sync_enabled(&self) -> bool {
+        self.sync_enabled.unwrap_or(true) && !self.archived()
+    }
+
+    pub fn archived(&self) -> bool {
+        self.archived.unwrap_or(false)
+    }
+
+    pub fn ci_status_updates(&self) -> bool {
+        self.ci_status_updates.unwrap_or(false)
+    }
+
+    pub fn ocr_screenshots(&self) -> bool {
+        self.ocr_screenshots.unwrap_or(false)
+    }
+
+    pub fn translate_issues(&self) -> bool {
+        self.translate_issues.unwrap_or(false)
+    }
+
+    pub fn rehost_attachments(&self) -> bool {
+        self.rehost_attachments.unwrap_or(true)
+    }
+
+    pub fn feedback_auto_issue(&self) -> bool {
+        self.feedback_auto_issue.unwrap_or(false)
+    }
+
+    pub fn triage_queue(&self) -> bool {
+        self.triage_queue.unwrap_or(false)
+    }
+
+    pub fn pinned_summary(&self) -> bool {
+        self.pinned_summary.unwrap_or(false)
+    }
+
+    pub fn assignment_updates(&self) -> bool {
+        self.assignment_updates.unwrap_or(false)
+    }
+
+    pub fn release_notifications(&self) -> bool {
+        self.release_notifications.unwrap_or(false)
+    }
+
+    pub fn platform(&self) -> ChatPlatform {
+        self.platform.unwrap_or_default()
+    }
+
+    pub fn mapping_mode(&self) -> MappingMode {
+        self.mapping_mode.unwrap_or_default()
+    }
+
+    pub fn transcript_format(&self) -> TranscriptFormat {
+        self.transcript_format.unwrap_or_default()
+    }
+
+    pub fn anonymous_reports(&self) -> bool {
+        self.anonymous_reports.unwrap_or(false)
+    }
+
+    pub fn staging(&self) -> bool {
+        self.staging.unwrap_or(false)
+    }
+
+    /// A thread title made fit for GitHub: filtered and redacted like issue
+    /// bodies, within `max_chars` and marked as coming from a staging project
+    pub fn github_title(&self, title: &str, max_chars: usize) -> String {
+        let title = &self.filtered_text(title);
+        if !self.staging() {
+            return crate::sanitize::sanitize_issue_title(title, max_chars);
+        }
+        let prefix = crate::constants::STAGING_TITLE_PREFIX;
+        let title = crate::sanitize::sanitize_issue_title(
+            title,
+            max_chars.saturating_sub(prefix.chars().count()),
+        );
+        format!("{prefix}{title}")
+    }
+
+    /// Text with the project's scam filter and wallet redaction applied,
+    /// without reporting what was removed; see `redact::for_github`
+    pub fn filtered_text(&self, text: &str) -> String {
+        let text = match &self.scam_filter {
+            Some(filter) => {
+                crate::scam_filter::filter(text, &crate::scam_filter::extra_patterns(filter)).0
+            }
+            None => text.to_string(),
+        };
+        match &self.redact_wallets {
+            Some(_) => crate::redact::redact(&text).0,
+            None => text,
+        }
+    }
+
+    /// REST API base for this project's repository, None for github.com
+    pub fn github_api_url(&self) -> Option<String> {
+        self.github_api_url
+            .clone()
+            .or_else(|| std::env::var("GITHUB_API_URL").ok())
+            .filter(|url| !url.is_empty())
+            .map(|url| url.trim_end_matches('/').to_string())
+    }
+
+    /// Web URL issues and repositories live under
+    pub fn github_web_url(&self) -> String {
+        match self.github_api_url() {
+            Some(api) => api.trim_end_matches("/api/v3").to_string(),
+            None => "https://github.com".to_string(),
+        }
+    }
+
+    pub fn rename_prefix_variants(&self) -> bool {
+        self.rename_prefix_variants.unwrap_or(false)
+    }
+
+    pub fn title_suggestions(&self) -> bool {
+        self.title_suggestions.unwrap_or(false)
+    }
+
+    pub fn transcript_marker_emoji(&self) -> &str {
+        self.transcript_marker_emoji
+            .as_deref()
+            .unwrap_or(crate::constants::DEFAULT_TRANSCRIPT_MARKER_EMOJI)
+    }
+
+    pub fn mirror_replies(&self) -> bool {
+        self.mirror_replies.unwrap_or(false)
+    }
+
+    pub fn propagate_edits(&self) -> bool {
+        self.propagate_edits.unwrap_or(false)
+    }
+
+    pub fn text_channel_threads(&self) -> bool {
+        self.text_channel_threads.unwrap_or(false)
+    }
+
+    pub fn stats_comment(&self) -> bool {
+        self.stats_comment.unwrap_or(false)
+    }
+
+    pub fn comment_commands(&self) -> bool {
+        self.comment_commands.unwrap_or(false)
+    }
+
+    pub fn comment_reactions(&self) -> bool {
+        self.comment_reactions.unwrap_or(false)
+    }
+
+    pub fn verify_fix(&self) -> bool {
+        self.verify_fix.unwrap_or(false)
+    }
+
+    pub fn verify_fix_timeout_hours(&self) -> u64 {
+        self.verify_fix_timeout_hours
+            .unwrap_or(crate::constants::DEFAULT_VERIFY_FIX_TIMEOUT_HOURS)
+    }
+
+    pub fn command_cooldown_seconds(&self) -> u64 {
+        self.command_cooldown_seconds
+            .unwrap_or(crate::constants::DEFAULT_COMMAND_COOLDOWN_SECS)
+    }
+
+    /// Why sync mutations are paused right now, if they are
+    pub fn mutations_paused(&self, now: chrono::DateTime<chrono::Utc>) -> Option<String> {
+        if let Some(window) = self
+            .maintenance_windows
+            .iter()
+            .flatten()
+            .find(|w| w.contains(now))
+        {
+            return Some(match &window.reason {
+                Some(reason) => format!("maintenance window ({reason})"),
+                None => "maintenance window".to_string(),
+            });
+        }
+
+        self.quiet_hours
+            .iter()
+            .flatten()
+            .find(|w| w.contains(now))
+            .map(|w| format!("quiet hours {}-{}", w.start, w.end))
+    }
+
+    /// Issue body from the template; without an author (anonymous reports) the
+    /// template lines naming one are left out
+    pub fn render_issue_body(
+        &self,
+        content: &str,
+        discord_url: &str,
+        author: Option<&str>,
+    ) -> String {
+        let template = self
+            .issue_body_template
+            .as_deref()
+            .unwrap_or(match self.platform() {
+                ChatPlatform::Discord => crate::constants::DEFAULT_ISSUE_BODY_TEMPLATE,
+                ChatPlatform::Matrix => crate::constants::MATRIX_ISSUE_BODY_TEMPLATE,
+                ChatPlatform::Telegram => crate::constants::TELEGRAM_ISSUE_BODY_TEMPLATE,
+            });
+        let template = match author {
+            Some(_) => template.to_string(),
+            None => template
+                .lines()
+                .filter(|line| !line.contains("{author}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+        template
+            .replace("{content}", content)
+            .replace("{discord_url}", discord_url)
+            .replace("{author}", author.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_repo_routing() {
+        let project: Project = toml::from_str(
+            r#"
+            discord_guild_id = "1"
+            discord_forum_id = "2"
+            github_owner = "acme"
+            github_repo = "app"
+            prefixes = [
+              { prefix = "[BUG]", label = "bug" },
+              { prefix = "[FEATURE]", label = "enhancement", github_repo = "roadmap" },
+              { prefix = "[QUESTION]", label = "question", github_repo = "docs-org/docs", target = "discussions" },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        let key = |p: &Project| crate::audit::project_key(p);
+        assert_eq!(key(&project.routed("[BUG] Crash")), "acme/app");
+        assert_eq!(key(&project.routed("[FEATURE] Dark mode")), "acme/roadmap");
+        assert_eq!(key(&project.routed("[QUESTION] How?")), "docs-org/docs");
+        assert_eq!(key(&project.routed("No prefix")), "acme/app");
+        let routed = project.routed("[FEATURE] Dark mode");
+        assert_eq!(key(&routed.routed("[FEATURE] Dark mode")), "acme/roadmap");
+
+        let routes: Vec<String> = project.routes().iter().map(key).collect();
+        assert_eq!(routes, vec!["acme/app", "acme/roadmap", "docs-org/docs"]);
+    }
+
+    #[test]
+    fn test_render_issue_body_without_author() {
+        let project: Project = toml::from_str(
+            r#"
+            discord_guild_id = "1"
+            discord_forum_id = "2"
+            github_owner = "acme"
+            github_repo = "app"
+            "#,
+        )
+        .unwrap();
+
+        let body = project.render_issue_body("Crash", "https://discord.com/x", Some("ana"));
+        assert!(body.ends_with("**Created by**: ana"));
+        let body = project.render_issue_body("Crash", "https://discord.com/x", None);
+        assert!(!body.contains("Created by"));
+        assert!(body.ends_with("**Discord Thread**: https://discord.com/x"));
+    }
+
+    #[test]
+    fn test_project_inherits_defaults() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [defaults]
+            github_owner = "acme"
+            allowed_role_id = "42"
+            prefixes = [{ prefix = "[BUG]", label = "type:bug" }]
+
+            [[projects]]
+            discord_guild_id = "1"
+            discord_forum_id = "2"
+            github_repo = "app"
+
+            [[projects]]
+            discord_guild_id = "1"
+            discord_forum_id = "3"
+            github_owner = "other"
+            github_repo = "docs"
+            allowed_role_id = ""
+            github_account = "other-org"
+
+            [[github_accounts]]
+            name = "other-org"
+            token_env = "OTHER_ORG_GITHUB_TOKEN"
+            "#,
+        )
+        .unwrap();
+        config.apply_defaults().unwrap();
+
+        let app = &config.projects[0];
+        assert_eq!(app.github_owner, "acme");
+        assert_eq!(app.allowed_role_id.as_deref(), Some("42"));
+        assert_eq!(app.labels_for_title("[BUG] crash"), vec!["type:bug"]);
+        assert_eq!(app.prefixes()[0].tag_name(), "BUG");
+
+        let docs = &config.projects[1];
+        assert_eq!(docs.github_owner, "other");
+        assert_eq!(docs.allowed_role_id, None);
+        assert_eq!(docs.github_account.as_deref(), Some("other-org"));
+
+        config.projects[0].github_account = Some("missing".to_string());
+        assert!(config.apply_defaults().is_err());
+    }
+
+    #[test]
+    fn test_github_api_url_order() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [defaults]
+            github_owner = "acme"
+            github_api_url = "https://defaults.example.com/api/v3"
+
+            [[projects]]
+            discord_guild_id = "1"
+            discord_forum_id = "2"
+            github_repo = "app"
+            github_account = "enterprise"
+
+            [[projects]]
+            discord_guild_id = "1"
+            discord_forum_id = "3"
+            github_repo = "docs"
+
+            [[github_accounts]]
+            name = "enterprise"
+            token_env = "ENTERPRISE_GITHUB_TOKEN"
+            api_url = "https://account.example.com/api/v3"
+            "#,
+        )
+        .unwrap();
+        config.apply_defaults().unwrap();
+
+        assert_eq!(
+            config.projects[0].github_api_url.as_deref(),
+            Some("https://account.example.com/api/v3")
+        );
+        assert_eq!(
+            config.projects[1].github_api_url.as_deref(),
+            Some("https://defaults.example.com/api/v3")
+        );
+    }
+
+    #[test]
+    fn test_staging_project() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [[projects]]
+            discord_guild_id = "1"
+            discord_forum_id = "2"
+            github_owner = "acme"
+            github_repo = "app"
+            staging = true
+            staging_repo = "acme-sandbox/app"
+            prefixes = [{ prefix = "[FEATURE]", label = "enhancement", github_repo = "roadmap" }]
+            "#,
+        )
+        .unwrap();
+        config.apply_defaults().unwrap();
+
+        let project = &config.projects[0];
+        let key = |p: &Project| crate::audit::project_key(p);
+        assert_eq!(key(project), "acme-sandbox/app");
+        assert_eq!(
+            key(&project.routed("[FEATURE] Dark mode")),
+            "acme-sandbox/app"
+        );
+        assert_eq!(
+            project.github_title("[FEATURE]  Dark mode", 100),
+            "[staging] [FEATURE] Dark mode"
+        );
+        assert_eq!(project.github_title("abcdefgh", 14), "[staging] abc…");
+
+        config.projects[0].staging_repo = None;
+        assert!(config.apply_defaults().is_err());
+    }
+
+    #[test]
+    fn test_find_thread_project() {
+        let config: Config = toml::from_str(
+            r#"
+            [[projects]]
+            discord_guild_id = "1"
+            discord_forum_id = "2"
+            github_owner = "acme"
+            github_repo = "app"
+
+            [[projects]]
+            discord_guild_id = "1"
+            discord_forum_id = "3"
+            github_owner = "acme"
+            github_repo = "docs"
+            text_channel_threads = true
+            "#,
+        )
+        .unwrap();
+
+        let repo = |parent| {
+            config
+                .find_thread_project(1, parent)
+                .map(|p| p.github_repo.as_str())
+        };
+        assert_eq!(repo(2), Some("app"));
+        assert_eq!(repo(3), Some("docs"));
+        assert_eq!(repo(99), Some("docs"));
+        assert_eq!(
+            config.find_thread_project(5, 99).map(|p| &p.github_repo),
+            None
+        );
+    }
+}
@@ -1,13 +1,55 @@
 /// Constants used throughout the CardiBot application
 // Discord embed colors
 pub const COLOR_SUCCESS: u32 = 0x238636; // Green
+pub const COLOR_FAILURE: u32 = 0xDA3633; // Red
+pub const COLOR_FEEDBACK_TEMPLATE: u32 = 0x00ADB5; // Teal
+pub const COLOR_CLOSED: u32 = 0x8957E5; // Purple
 
 // API limits
 pub const DISCORD_MESSAGE_FETCH_LIMIT: u8 = 50;
 pub const GITHUB_THREAD_CONTENT_LIMIT: u8 = 10;
+pub const GITHUB_THREAD_CONTENT_MESSAGES: usize = 5;
+pub const THREAD_SELECTION_FETCH_LIMIT: u8 = 100;
+pub const DEFAULT_TRANSCRIPT_MARKER_EMOJI: &str = "📌";
+pub const LOG_PASTE_MIN_LINES: usize = 3;
+pub const DISCORD_EMBED_DESCRIPTION_LIMIT: usize = 4096;
+pub const DISCORD_THREAD_NAME_LIMIT: usize = 100;
+pub const GITHUB_SECONDARY_LIMIT_WAIT_SECS: u64 = 60;
+pub const DISCORD_EMBED_TITLE_LIMIT: usize = 256;
+pub const DISCORD_EMBED_FIELD_NAME_LIMIT: usize = 256;
+pub const DISCORD_EMBED_FIELD_VALUE_LIMIT: usize = 1024;
+pub const DISCORD_EMBED_FIELD_COUNT_LIMIT: usize = 25;
+pub const DISCORD_EMBED_FOOTER_LIMIT: usize = 2048;
+/// Characters across all text of the embeds in one message
+pub const DISCORD_EMBED_TOTAL_LIMIT: usize = 6000;
+pub const DISCORD_EMBEDS_PER_MESSAGE: usize = 10;
+pub const GITHUB_ISSUE_TITLE_LIMIT: usize = 256;
+/// Marks the issues of a staging project in its sandbox repository
+pub const STAGING_TITLE_PREFIX: &str = "[staging] ";
+pub const OCR_MAX_IMAGES: usize = 3;
+pub const OCR_MAX_TEXT_CHARS: usize = 4000;
+/// Transcript characters sent for translation, which providers bill by
+pub const TRANSLATION_MAX_CHARS: usize = 5000;
+pub const TRANSLATION_TIMEOUT_SECS: u64 = 20;
+/// Letters a transcript needs before its language is guessed
+pub const TRANSLATION_MIN_LETTERS: usize = 20;
+pub const DEFAULT_DEEPL_URL: &str = "https://api-free.deepl.com";
+pub const DEFAULT_GOOGLE_TRANSLATE_URL: &str = "https://translation.googleapis.com";
+/// Attachments listed in an issue body, from the oldest message on
+pub const MAX_ISSUE_ATTACHMENTS: usize = 25;
+pub const DEFAULT_ATTACHMENT_REHOST_MIN_MB: u64 = 8;
+pub const DEFAULT_ATTACHMENT_REHOST_MAX_MB: u64 = 100;
+/// Downloading one attachment and uploading it to the bucket
+pub const ATTACHMENT_TRANSFER_TIMEOUT_SECS: u64 = 120;
+pub const DEFAULT_ATTACHMENT_EXPIRY_DAYS: u64 = 90;
+/// Most recently active Matrix threads looked at per sync cycle
+pub const MATRIX_THREAD_LIMIT: usize = 100;
+pub const MATRIX_MESSAGE_FETCH_LIMIT: usize = 50;
+pub const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+/// Updates fetched per getUpdates request, the Bot API maximum
+pub const TELEGRAM_UPDATE_LIMIT: usize = 100;
 
-// Thread prefixes (fixed set for consistency)
-pub const THREAD_PREFIXES: &[&str] = &["[BUG]", "[FEATURE]", "[QUESTION]", "[FEEDBACK]"];
+// Default thread prefixes
 pub const PREFIX_BUG: &str = "[BUG]";
 pub const PREFIX_FEATURE: &str = "[FEATURE]";
 pub const PREFIX_QUESTION: &str = "[QUESTION]";
@@ -19,11 +61,217 @@ pub const LABEL_FEATURE: &str = "enhancement";
 pub const LABEL_QUESTION: &str = "question";
 pub const LABEL_FEEDBACK: &str = "feedback";
 
+// Default prefix -> label taxonomy when a project doesn't configure its own
+pub const DEFAULT_PREFIX_LABELS: &[(&str, &str)] = &[
+    (PREFIX_BUG, LABEL_BUG),
+    (PREFIX_FEATURE, LABEL_FEATURE),
+    (PREFIX_QUESTION, LABEL_QUESTION),
+    (PREFIX_FEEDBACK, LABEL_FEEDBACK),
+];
+
 // Bot messages
 pub const MSG_ISSUE_CREATED: &str = "GitHub Issue Created";
 pub const MSG_ISSUE_UPDATED: &str = "GitHub Issue Updated";
+pub const MSG_ISSUE_LINKED: &str = "Linked to Existing GitHub Issue";
+pub const MSG_ISSUE_MOVED: &str = "GitHub Issue Moved";
+pub const MSG_ISSUE_SUMMARY: &str = "📌 Issue summary";
+pub const MSG_DISCUSSION_CREATED: &str = "GitHub Discussion Started";
+pub const MSG_PROJECT_ITEM_CREATED: &str = "Added to GitHub Project";
+pub const MSG_TRIAGE_CANDIDATE: &str = "🗂️ Triage candidate";
+pub const MSG_NEW_ISSUE: &str = "📥 New issue from Discord";
 pub const MSG_ISSUE_CLOSED: &str = "🔒 Issue closed or merged on GitHub";
 pub const MSG_ISSUE_REOPENED: &str = "🔓 Issue reopened on GitHub";
+pub const MSG_PROJECT_ARCHIVED: &str =
+    "🗄️ This forum is archived; the linked GitHub issue stays open for maintainers";
+pub const MSG_CI_PASSED: &str = "✅ Fix passed CI";
+pub const MSG_CI_FAILED: &str = "❌ Fix failed CI";
+pub const MSG_NEEDS_INFO: &str = "❓ A maintainer needs more information about this report";
+pub const MSG_VERIFY_FIX: &str = "🧪 Please verify the fix";
+pub const MSG_NO_LINKED_ISSUE: &str =
+    "This thread has no linked GitHub issue yet, run `/issue create` first";
+pub const MSG_AUTO_CREATE_NUDGE: &str =
+    "👋 This post hasn't drawn much attention yet, so no GitHub issue has been filed.";
+pub const MSG_THREAD_BUSY: &str =
+    "An issue is already being created for this thread, please try again shortly";
+pub const MSG_QUOTA_FULL: &str =
+    "The maintainers have a lot of open reports right now, so no new issue was filed. Please try again once some are resolved.";
+pub const MSG_QUOTA_QUEUED: &str =
+    "The maintainers have a lot of open reports right now, so this thread was added to their triage list instead of being filed straight away.";
+pub const MSG_INTERACTION_FAILED: &str = "Something went wrong, please try again later";
+pub const MSG_INTERACTION_TIMED_OUT: &str =
+    "This is taking longer than expected; it keeps going in the background, check back in a few minutes";
+pub const MSG_THREAD_LOCKED_COMMENT: &str =
+    "🔒 The linked Discord thread was locked by a moderator, closing this issue.";
+
+// Audit log actors for changes not triggered by a Discord user
+pub const AUDIT_ACTOR_BOT: &str = "cardibot";
+pub const AUDIT_ACTOR_CLI: &str = "cli";
+/// Label the final sweep of an archived project puts on its open issues
+pub const ARCHIVED_ISSUE_LABEL: &str = "forum-archived";
 
 // Config defaults
 pub const DEFAULT_CONFIG_PATH: &str = "config.toml";
+pub const DEFAULT_DATABASE_PATH: &str = "cardibot.db";
+pub const DEFAULT_COMMAND_COOLDOWN_SECS: u64 = 10;
+// Outlasts the interaction handler timeout, as a handler keeps running past it
+pub const THREAD_LOCK_TTL_SECS: i64 = 10 * 60;
+pub const COMMENT_COMMAND_MAX_AGE_HOURS: i64 = 24;
+pub const MIRROR_GROUP_WINDOW_SECS: i64 = 300;
+// GitHub webhooks: largest accepted payload and how long delivery IDs are kept
+pub const WEBHOOK_MAX_BODY_BYTES: usize = 25 * 1024 * 1024;
+pub const WEBHOOK_DELIVERY_RETENTION_SECS: i64 = 7 * 24 * 60 * 60;
+// Discord HTTP interactions: accepted clock skew on signed timestamps
+pub const INTERACTION_MAX_AGE_SECS: i64 = 5 * 60;
+// Interaction handlers get a fallback reply after this long; reply tokens last 15 minutes
+pub const INTERACTION_HANDLER_TIMEOUT_SECS: u64 = 5 * 60;
+pub const INTERACTION_QUICK_TIMEOUT_SECS: u64 = 60;
+// Queued gateway events: worker tasks per bot, runs before a job is given up,
+// the first retry delay (doubling after each failure), the cut-off per run and
+// how long a claimed job is held from other processes sharing the store
+pub const JOB_WORKERS: usize = 4;
+pub const JOB_MAX_ATTEMPTS: u32 = 5;
+pub const JOB_RETRY_BASE_SECS: i64 = 30;
+pub const JOB_TIMEOUT_SECS: u64 = 5 * 60;
+pub const JOB_LEASE_SECS: i64 = JOB_TIMEOUT_SECS as i64 + 60;
+
+// Consecutive failed sync cycles before a project is skipped, and for how long
+pub const SYNC_BREAKER_THRESHOLD: u32 = 3;
+pub const SYNC_BREAKER_COOLDOWN_SECS: u64 = 30 * 60;
+// A crashed syncer restarts after this wait, doubling per crash in a row up to
+// the max; a run lasting the reset time counts as healthy again
+pub const SYNC_RESTART_BASE_SECS: u64 = 5;
+pub const SYNC_RESTART_MAX_SECS: u64 = 10 * 60;
+pub const SYNC_RESTART_RESET_SECS: u64 = 30 * 60;
+/// Threads a project's sync works on at once
+pub const DEFAULT_SYNC_CONCURRENCY: usize = 5;
+/// Linked issues looked up per GraphQL request when checking forum threads
+pub const SYNC_ISSUE_BATCH_SIZE: usize = 50;
+pub const STARTUP_RECONCILE_DAYS: i64 = 7;
+pub const STARTUP_RECONCILE_ARCHIVED_LIMIT: u64 = 100;
+pub const DEFAULT_VERIFY_FIX_TIMEOUT_HOURS: u64 = 72;
+pub const DEFAULT_AUTO_CREATE_NUDGE_HOURS: u64 = 24;
+// Discord modals hold at most five inputs
+pub const SPLIT_MAX_SECTIONS: usize = 5;
+pub const DEFAULT_DISCUSSION_CATEGORY: &str = "General";
+// Audit log entries searched for the moderator behind a thread lock or
+// archive, and how old the entry may be
+pub const MODERATOR_AUDIT_LOG_LIMIT: u8 = 25;
+pub const MODERATOR_AUDIT_LOG_WINDOW_SECS: i64 = 120;
+
+// Stands in for the reporter's name in anonymous reports
+pub const ANONYMOUS_REPORTER_NAME: &str = "reporter";
+pub const DEFAULT_TRIAGED_TAG: &str = "Triaged";
+pub const DEFAULT_IN_PROGRESS_TAG: &str = "In Progress";
+pub const DEFAULT_FIXED_PENDING_RELEASE_TAG: &str = "Fixed, Unreleased";
+pub const DEFAULT_NEEDS_INFO_EMOJI: &str = "❓";
+pub const DEFAULT_NEEDS_INFO_LABEL: &str = "needs-info";
+pub const DEFAULT_NEEDS_INFO_QUESTIONS: &[&str] = &[
+    "Environment (OS, browser or device)",
+    "App version",
+    "Wallet type",
+    "Steps to reproduce",
+];
+pub const DEFAULT_PRIORITY_LABELS: &[&str] = &[
+    "priority: critical",
+    "priority: high",
+    "priority: medium",
+    "priority: low",
+];
+// Colors and descriptions for labels `setup-labels` creates when the
+// project's label_styles doesn't set them
+pub const DEFAULT_LABEL_STYLES: &[(&str, &str, &str)] = &[
+    (LABEL_BUG, "d73a4a", "Something isn't working"),
+    (LABEL_FEATURE, "a2eeef", "New feature or request"),
+    (LABEL_QUESTION, "d876e3", "Further information is requested"),
+    (LABEL_FEEDBACK, "c5def5", "Feedback from the community"),
+    ("priority: critical", "b60205", "Needs attention right away"),
+    ("priority: high", "d93f0b", "Should be handled soon"),
+    ("priority: medium", "fbca04", "Normal priority"),
+    ("priority: low", "0e8a16", "Can wait"),
+    (
+        DEFAULT_NEEDS_INFO_LABEL,
+        "fef2c0",
+        "Waiting on details from the reporter",
+    ),
+];
+pub const DEFAULT_LABEL_COLOR: &str = "ededed";
+// Bot updates only replace the issue body between these markers
+pub const TRANSCRIPT_BEGIN_MARKER: &str = "<!-- cardibot:begin transcript -->";
+pub const TRANSCRIPT_END_MARKER: &str = "<!-- cardibot:end transcript -->";
+/// Older single marker: everything after it was bot-managed
+pub const LEGACY_TRANSCRIPT_MARKER: &str = "<!-- cardibot:transcript -->";
+pub const DEFAULT_ISSUE_BODY_TEMPLATE: &str =
+    "{content}\n\n---\n**Discord Thread**: {discord_url}\n**Created by**: {author}";
+pub const MATRIX_ISSUE_BODY_TEMPLATE: &str =
+    "{content}\n\n---\n**Matrix Thread**: {discord_url}\n**Created by**: {author}";
+pub const TELEGRAM_ISSUE_BODY_TEMPLATE: &str =
+    "{content}\n\n---\n**Telegram Topic**: {discord_url}\n**Created by**: {author}";
+
+// Milestone announcements
+pub const DEFAULT_MILESTONE_THRESHOLDS: &[u8] = &[50, 100];
+pub const MSG_MILESTONE_PROGRESS: &str = "🏁 Milestone progress";
+pub const MSG_REDACTED_VALUES: &str = "🔒 Values hidden from GitHub";
+pub const MSG_SYNC_STATUS: &str = "📊 Sync status";
+// Audit entries counted into one sync status message
+pub const SYNC_STATUS_AUDIT_LIMIT: usize = 1000;
+pub const MSG_SCAM_FILTERED: &str = "🚩 Suspicious content kept off GitHub";
+pub const MSG_THREAD_ABANDONED: &str =
+    "🗄️ No issue was filed for this thread and it has gone quiet, so it's been archived. Reply here if it's still relevant.";
+// Stands in for a mirrored reply that was deleted on Discord
+pub const MSG_MIRRORED_MESSAGE_DELETED: &str = "~~Message deleted on Discord~~";
+pub const MSG_ISSUE_FROM_GITHUB: &str =
+    "📣 The maintainers are tracking this on GitHub; updates will be posted here.";
+// Milestones closed longer ago than this are not announced
+pub const MILESTONE_RECENT_SECS: i64 = 24 * 60 * 60;
+// Drift the syncer keeps seeing is logged as an error once after this long
+pub const DRIFT_ESCALATE_SECS: i64 = 24 * 60 * 60;
+// Releases published longer ago than this are not announced in threads
+pub const RELEASE_RECENT_SECS: i64 = 7 * 24 * 60 * 60;
+// Releases looked at per sync cycle, newest first
+pub const RELEASE_FETCH_LIMIT: u8 = 10;
+// Pull requests whose closing issues are looked up per release
+pub const RELEASE_PR_LIMIT: usize = 50;
+// Most addressed reports listed in one announcement
+pub const MILESTONE_MAX_LISTED: usize = 15;
+
+// Duplicate reports: defaults, threads compared per scan, and the most
+// threads listed per cluster
+pub const DEFAULT_DUPLICATE_SCAN_HOURS: u64 = 6;
+pub const DEFAULT_DUPLICATE_SIMILARITY: f64 = 0.5;
+pub const DUPLICATE_SCAN_MAX_THREADS: usize = 200;
+pub const DUPLICATE_MAX_LISTED: usize = 10;
+pub const MSG_DUPLICATE_CLUSTER: &str = "🧩 Possible duplicates";
+
+// Reward hooks: signature header, request timeout, and retries of failed
+// deliveries (doubling from the base delay)
+pub const REWARD_HOOK_SIGNATURE_HEADER: &str = "X-Cardibot-Signature";
+pub const REWARD_HOOK_TIMEOUT_SECS: u64 = 10;
+pub const REWARD_HOOK_MAX_ATTEMPTS: u32 = 8;
+pub const REWARD_HOOK_RETRY_BASE_SECS: i64 = 60;
+
+// Reporters listed by /leaderboard
+pub const LEADERBOARD_SIZE: usize = 10;
+pub const MSG_LEADERBOARD: &str = "🏆 Top reporters";
+
+// Sent once to each member opening their first prefixed thread, unless the
+// project's onboarding sets a message
+pub const DEFAULT_ONBOARDING_MESSAGE: &str = "👋 Hi {user}, thanks for your first report in {project}!
+
+**How it works:** maintainers file threads like yours as GitHub issues in {repo_url}. Updates on the issue are posted back to your thread ({thread_url}), and the thread is locked once the issue is closed.
+
+**Help us fix it quickly:**
+• What you did and what you expected to happen
+• What happened instead, with the exact error text
+• App version, device and operating system
+• Screenshots or logs, if you have them
+
+**Privacy:** the messages in your thread, your Discord name and your attachments are copied into a public GitHub issue. Never post seed phrases, private keys, passwords or personal details.";
+
+// Discord metadata cache
+pub const DISCORD_CACHE_CHANNEL_TTL_SECS: u64 = 30;
+pub const DISCORD_CACHE_PROFILE_TTL_SECS: u64 = 10 * 60;
+pub const DISCORD_CACHE_MAX_ENTRIES: usize = 10_000;
+
+// Discord gateway watchdog
+pub const DEFAULT_GATEWAY_WATCHDOG_SECS: u64 = 300;
+pub const GATEWAY_WATCHDOG_CHECK: std::time::Duration = std::time::Duration::from_secs(30);
@@ -0,0 +1,82 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// In-process guards against duplicate and rapid-fire command runs
+#[derive(Default)]
+pub struct CommandCooldowns {
+    in_flight: Arc<Mutex<HashSet<u64>>>,
+    last_use: Mutex<HashMap<u64, Instant>>,
+}
+
+/// Marks a thread as busy until dropped
+pub struct ThreadGuard {
+    thread_id: u64,
+    in_flight: Arc<Mutex<HashSet<u64>>>,
+}
+
+impl Drop for ThreadGuard {
+    fn drop(&mut self) {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.thread_id);
+    }
+}
+
+impl CommandCooldowns {
+    /// Returns the remaining wait if the user ran a command too recently,
+    /// otherwise records this use
+    pub fn check_user(&self, user_id: u64, cooldown: Duration) -> Option<Duration> {
+        let mut last_use = self.last_use.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+
+        if let Some(previous) = last_use.get(&user_id) {
+            let elapsed = now.duration_since(*previous);
+            if elapsed < cooldown {
+                return Some(cooldown - elapsed);
+            }
+        }
+
+        last_use.insert(user_id, now);
+        None
+    }
+
+    /// Claim a thread for the duration of a command; None if it is already being processed
+    pub fn begin_thread(&self, thread_id: u64) -> Option<ThreadGuard> {
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        if !in_flight.insert(thread_id) {
+            return None;
+        }
+
+        Some(ThreadGuard {
+            thread_id,
+            in_flight: self.in_flight.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_guard_releases_on_drop() {
+        let cooldowns = CommandCooldowns::default();
+        let guard = cooldowns.begin_thread(1);
+        assert!(guard.is_some());
+        assert!(cooldowns.begin_thread(1).is_none());
+        drop(guard);
+        assert!(cooldowns.begin_thread(1).is_some());
+    }
+
+    #[test]
+    fn test_user_cooldown() {
+        let cooldowns = CommandCooldowns::default();
+        assert!(cooldowns.check_user(1, Duration::from_secs(60)).is_none());
+        assert!(cooldowns.check_user(1, Duration::from_secs(60)).is_some());
+        assert!(cooldowns.check_user(2, Duration::from_secs(60)).is_none());
+        assert!(cooldowns.check_user(3, Duration::ZERO).is_none());
+        assert!(cooldowns.check_user(3, Duration::ZERO).is_none());
+    }
+}
@@ -108,7 +108,13 @@ pub async fn check_discord() -> anyhow::Result<()> {
     Ok(())
 }
 
-pub async fn post_feedback_instructions(channel_id: &str) -> anyhow::Result<()> {
+/// Post feedback instructions from `--template`, the project's
+/// `feedback_template`, or the built-in template
+pub async fn post_feedback_instructions(
+    channel_id: &str,
+    template_path: Option<&str>,
+    project_filter: Option<&str>,
+) -> anyhow::Result<()> {
     let channel_id = ChannelId::new(channel_id.parse::<u64>()?);
 
     // Use shared clients
@@ -118,118 +124,49 @@ pub async fn post_feedback_instructions(channel_id: &str) -> anyhow::Result<()>
     // Check if it's a forum channel
     let channel = channel_id.to_channel(&http).await?;
 
+    // The project picked by name, or the one whose server holds the channel
+    let config = crate::config::Config::load().ok();
+    let project = config.as_ref().and_then(|config| match project_filter {
+        Some(_) => crate::threads::selected_projects(config, project_filter)
+            .into_iter()
+            .next(),
+        None => match &channel {
+            Channel::Guild(c) => config
+                .projects
+                .iter()
+                .find(|p| p.discord_guild_id == c.guild_id.to_string()),
+            _ => None,
+        },
+    });
+    if project_filter.is_some() && project.is_none() {
+        anyhow::bail!("No configured project matches {:?}", project_filter);
+    }
+
+    let template = match (
+        template_path,
+        project.and_then(|p| p.feedback_template.clone()),
+    ) {
+        (Some(path), _) => crate::feedback_template::FeedbackTemplate::load(path)?,
+        (None, Some(template)) => template,
+        (None, None) => crate::feedback_template::FeedbackTemplate::default(),
+    };
+    let prefixes = project
+        .map(|p| p.prefixes())
+        .unwrap_or_else(crate::config::default_prefixes);
+    let message = template.message(&prefixes)?;
+
     match channel {
         Channel::Guild(guild_channel) if guild_channel.kind == ChannelType::Forum => {
             // For forum channels, create a new thread
             println!("Creating a new thread in forum channel...");
 
-            let embed = CreateEmbed::new()
-                .title("🚀 Welcome to Levvy V3 Testnet!")
-                .description("We need your feedback to make Levvy even better.")
-                .color(0x00ADB5)
-                .field(
-                    "📋 How to Provide Feedback",
-                    "1. **Test the Platform**: Visit https://v3.levvy.fi/\n\
-                     2. **Try Different Features**: Loans, borrows, position management\n\
-                     3. **Report Issues**: Create a new forum thread with these prefixes in your title:\n\
-                        • `[BUG] Your title here` - Something isn't working\n\
-                        • `[FEEDBACK] Your title` - General suggestions\n\
-                        • `[FEATURE] Your title` - New feature requests\n\
-                        • `[QUESTION] Your title` - Need help or clarification\n\
-                     4. **Be Specific**: Include steps to reproduce, screenshots if applicable",
-                    false
-                )
-                .field(
-                    "✅ What's Ready to Test",
-                    "• Classic loans / borrows\n\
-                     • Perpetual loans / borrows\n\
-                     • Manage positions (open/close, adjust collateral)\n\
-                     • Simplified \"New Levvy\" loan flow",
-                    false
-                )
-                .field(
-                    "🚧 Coming Soon",
-                    "• Position history (toggle off for now)\n\
-                     • Levvy Reaper liquidator bot (public release soon)\n\
-                     • NFT loan/borrow capability\n\
-                     • Stats & analytics page",
-                    false
-                )
-                .field(
-                    "💰 Getting Test Funds",
-                    "• **Test ADA**: [Cardano Faucet](https://docs.cardano.org/cardano-testnets/tools/faucet)\n\
-                     • **Test Tokens**: Use the faucet button at bottom-right of Levvy UI",
-                    false
-                )
-                .field(
-                    "⚠️ Important",
-                    "**MAKE SURE YOUR WALLET IS SET TO CARDANO PREVIEW TESTNET**\n\
-                     Use Preview testnet settings, not Pre-prod",
-                    false
-                )
-                .footer(CreateEmbedFooter::new("Thank you for helping us test Levvy V3!"));
-
-            // Create a forum thread
-            let message = CreateMessage::new()
-                .content(
-                    "Please read the instructions below to provide feedback on Levvy V3 Testnet:",
-                )
-                .embed(embed);
-
-            let thread = CreateForumPost::new("📢 Levvy V3 Testnet Feedback Instructions", message);
+            let thread = CreateForumPost::new(template.post_title(), message);
 
             channel_id.create_forum_post(&http, thread).await?;
             println!("✅ Feedback thread created in forum {channel_id}");
         }
         _ => {
             // For regular channels, just send a message
-            let embed = CreateEmbed::new()
-                .title("🚀 Levvy V3 Testnet Feedback Instructions")
-                .description("Welcome to the Levvy V3 Testnet! We need your feedback to make Levvy even better.")
-                .color(0x00ADB5)
-                .field(
-                    "📋 How to Provide Feedback",
-                    "1. **Test the Platform**: Visit https://v3.levvy.fi/\n\
-                     2. **Try Different Features**: Loans, borrows, position management\n\
-                     3. **Report Issues**: Create a new forum thread with these prefixes in your title:\n\
-                        • `[BUG] Your title here` - Something isn't working\n\
-                        • `[FEEDBACK] Your title` - General suggestions\n\
-                        • `[FEATURE] Your title` - New feature requests\n\
-                        • `[QUESTION] Your title` - Need help or clarification\n\
-                     4. **Be Specific**: Include steps to reproduce, screenshots if applicable",
-                    false
-                )
-                .field(
-                    "✅ What's Ready to Test",
-                    "• Classic loans / borrows\n\
-                     • Perpetual loans / borrows\n\
-                     • Manage positions (open/close, adjust collateral)\n\
-                     • Simplified \"New Levvy\" loan flow",
-                    false
-                )
-                .field(
-                    "🚧 Coming Soon",
-                    "• Position history (toggle off for now)\n\
-                     • Levvy Reaper liquidator bot (public release soon)\n\
-                     • NFT loan/borrow capability\n\
-                     • Stats & analytics page",
-                    false
-                )
-                .field(
-                    "💰 Getting Test Funds",
-                    "• **Test ADA**: [Cardano Faucet](https://docs.cardano.org/cardano-testnets/tools/faucet)\n\
-                     • **Test Tokens**: Use the faucet button at bottom-right of Levvy UI",
-                    false
-                )
-                .field(
-                    "⚠️ Important",
-                    "**MAKE SURE YOUR WALLET IS SET TO CARDANO PREVIEW TESTNET**\n\
-                     Use Preview testnet settings, not Pre-prod",
-                    false
-                )
-                .footer(CreateEmbedFooter::new("Thank you for helping us test Levvy V3!"));
-
-            let message = CreateMessage::new().embed(embed);
             channel_id.send_message(&http, message).await?;
             println!("✅ Feedback instructions posted to channel {channel_id}");
         }
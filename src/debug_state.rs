@@ -0,0 +1,237 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serenity::all::*;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::config::Config;
+use crate::gateway::{GatewayHealth, GatewayStatus};
+use crate::store::{DriftItem, Store};
+
+const SNAPSHOT_FILE_NAME: &str = "cardibot-state.json";
+
+/// What the bot currently holds in memory and in its store, for diagnosing
+/// a running deployment. Counts and credentials shared by every server are
+/// left out of a single server's snapshot.
+#[derive(Debug, Serialize)]
+pub struct StateSnapshot {
+    pub generated_at: String,
+    /// Only known inside the process holding the gateway connection
+    pub gateway: Option<GatewayStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discord_cache: Option<BTreeMap<&'static str, usize>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub github: Option<GitHubAuth>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mappings: Option<Mappings>,
+    pub projects: Vec<ProjectState>,
+    pub queues: Queues,
+    /// Mismatches the syncer keeps running into
+    pub drift: Vec<DriftItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GitHubAuth {
+    /// Credentials used by projects without a `github_account`
+    pub default_auth: &'static str,
+    /// Installation ID to the expiry of the last token minted for it
+    pub installation_tokens: BTreeMap<u64, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Mappings {
+    pub thread_issues: usize,
+    pub mirror_cursors: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectState {
+    pub project: String,
+    pub name: Option<String>,
+    pub sync_enabled: bool,
+    pub archived: bool,
+    pub linked_threads: u64,
+    pub last_sync: Option<LastSync>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LastSync {
+    pub finished_at: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Queues {
+    /// Thread creations in progress
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_locks: Option<u64>,
+    /// Project to triage status to candidate count
+    pub triage: BTreeMap<String, BTreeMap<String, u64>>,
+}
+
+/// Snapshot of the projects in `guild_id`, or of every project when None
+pub fn snapshot(
+    config: &Config,
+    store: &Store,
+    gateway: Option<&GatewayHealth>,
+    guild_id: Option<u64>,
+) -> Result<StateSnapshot> {
+    let projects: Vec<_> = config
+        .projects
+        .iter()
+        .filter(|p| guild_id.is_none_or(|id| p.discord_guild_id == id.to_string()))
+        .flat_map(|p| p.routes())
+        .collect();
+    let keys: HashSet<String> = projects.iter().map(crate::audit::project_key).collect();
+
+    let mut last_syncs: BTreeMap<String, LastSync> = store
+        .sync_results()?
+        .into_iter()
+        .map(|result| {
+            let finished_at = chrono::DateTime::from_timestamp(result.finished_at, 0)
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default();
+            (
+                result.project,
+                LastSync {
+                    finished_at,
+                    error: result.error,
+                },
+            )
+        })
+        .collect();
+
+    let mut project_states = Vec::new();
+    for project in &projects {
+        let key = crate::audit::project_key(project);
+        project_states.push(ProjectState {
+            name: project.name.clone(),
+            sync_enabled: config.sync_config().enabled && project.sync_enabled(),
+            archived: project.archived(),
+            linked_threads: store.linked_thread_count(&key)?,
+            last_sync: last_syncs.remove(&key),
+            project: key,
+        });
+    }
+
+    let mut triage: BTreeMap<String, BTreeMap<String, u64>> = BTreeMap::new();
+    for (project, status, count) in store.triage_queue_depths()? {
+        if keys.contains(&project) {
+            triage.entry(project).or_default().insert(status, count);
+        }
+    }
+
+    // Mappings and locks are counted across all projects, as not every
+    // table records which project a row belongs to, so only the full
+    // snapshot has them
+    let global = guild_id.is_none();
+    Ok(StateSnapshot {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        gateway: gateway.map(GatewayHealth::status),
+        discord_cache: global.then(|| crate::discord_cache::sizes().into_iter().collect()),
+        github: global.then(|| GitHubAuth {
+            default_auth: crate::github_app::default_auth_mode(),
+            installation_tokens: crate::github_app::token_expiries().into_iter().collect(),
+        }),
+        mappings: if global {
+            Some(Mappings {
+                thread_issues: store.thread_issues()?.len(),
+                mirror_cursors: store.mirror_cursors()?.len(),
+            })
+        } else {
+            None
+        },
+        projects: project_states,
+        queues: Queues {
+            thread_locks: if global {
+                Some(store.thread_lock_count()?)
+            } else {
+                None
+            },
+            triage,
+        },
+        drift: store
+            .drift_items()?
+            .into_iter()
+            .filter(|d| keys.contains(&d.project))
+            .collect(),
+    })
+}
+
+/// `/cardibot debug` gives server administrators the state snapshot as a
+/// file; `mute` and `unmute` are for everyone, so the command isn't
+/// restricted to administrators on Discord's side
+pub fn create_cardibot_command() -> CreateCommand {
+    let command = CreateCommand::new("cardibot")
+        .description("Administer the bot")
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "debug",
+            "Download a JSON snapshot of the bot's current state",
+        ));
+    crate::mute::subcommands()
+        .into_iter()
+        .fold(command, |command, option| command.add_option(option))
+}
+
+pub async fn handle_cardibot_command(
+    http: &Http,
+    command: &CommandInteraction,
+    config: &Config,
+    store: &Store,
+    gateway: &GatewayHealth,
+) -> Result<()> {
+    match command.data.options.first().map(|o| o.name.as_str()) {
+        Some("mute") => return crate::mute::handle_mute_command(http, command, store, true).await,
+        Some("unmute") => {
+            return crate::mute::handle_mute_command(http, command, store, false).await
+        }
+        _ => {}
+    }
+
+    // Server settings can open the command to other roles; the snapshot
+    // stays with administrators
+    let is_admin = command
+        .member
+        .as_ref()
+        .and_then(|m| m.permissions)
+        .is_some_and(|p| p.administrator());
+    let message = if !is_admin {
+        CreateInteractionResponseMessage::new()
+            .content("Only server administrators can use this command")
+    } else {
+        let snapshot = snapshot(
+            config,
+            store,
+            Some(gateway),
+            command.guild_id.map(|g| g.get()),
+        )?;
+        let json = serde_json::to_vec_pretty(&snapshot)?;
+        CreateInteractionResponseMessage::new()
+            .content(format!("State as of {}", snapshot.generated_at))
+            .add_file(CreateAttachment::bytes(json, SNAPSHOT_FILE_NAME))
+    };
+
+    command
+        .create_response(
+            http,
+            CreateInteractionResponse::Message(message.ephemeral(true)),
+        )
+        .await?;
+    Ok(())
+}
+
+/// CLI entry point: print the snapshot of every project, or write it to `out`
+pub fn dump_state(out: Option<&str>) -> Result<()> {
+    let config = Config::load()?;
+    let store = Store::open(&config)?;
+    let json = serde_json::to_string_pretty(&snapshot(&config, &store, None, None)?)?;
+
+    match out {
+        Some(out) => {
+            std::fs::write(out, json).with_context(|| format!("Failed to write {out}"))?;
+            println!("Wrote state snapshot to {out}");
+        }
+        None => println!("{json}"),
+    }
+    Ok(())
+}
@@ -1,5 +1,5 @@
-use crate::config::Config;
-use crate::sync::extract_thread_id;
+use crate::config::{ChatPlatform, Config};
+use crate::sync::issue_thread_id;
 use anyhow::Result;
 use octocrab::Octocrab;
 
@@ -13,18 +13,19 @@ pub async fn debug_sync_status() -> Result<()> {
     println!("Sync Configuration:");
     println!("  - Enabled: {}", sync_config.enabled);
     println!("  - Interval: {} seconds", sync_config.interval_seconds);
-    println!(
-        "  - Thread prefixes: {:?}",
-        crate::constants::THREAD_PREFIXES
-    );
     println!();
 
     // Use shared clients
     let clients = crate::clients::Clients::new_standalone().await?;
-    let github = &clients.github;
 
     // Check each project
-    for (idx, project) in config.projects.iter().enumerate() {
+    // Matrix links are kept in the database, not in issues
+    for (idx, project) in config
+        .projects
+        .iter()
+        .filter(|p| p.platform() == ChatPlatform::Discord)
+        .enumerate()
+    {
         println!(
             "Project {}: {}",
             idx + 1,
@@ -36,9 +37,11 @@ pub async fn debug_sync_status() -> Result<()> {
         );
         println!("  - Discord Guild: {}", project.discord_guild_id);
         println!("  - Discord Forum: {}", project.discord_forum_id);
+        println!("  - Thread prefixes: {}", project.prefix_list());
 
         // Search for issues with thread IDs
-        match debug_project_sync(github, project).await {
+        let github = clients.github_for(&config, project).await?;
+        match debug_project_sync(&github, project).await {
             Ok(()) => {}
             Err(e) => {
                 eprintln!("  ❌ Error checking project: {e}");
@@ -72,7 +75,7 @@ async fn debug_project_sync(github: &Octocrab, project: &crate::config::Project)
     let issues_with_thread_ids: Vec<_> = search_result
         .items
         .iter()
-        .filter(|issue| extract_thread_id(&issue.title).is_some())
+        .filter(|issue| issue_thread_id(issue).is_some())
         .collect();
 
     println!(
@@ -93,7 +96,7 @@ async fn debug_project_sync(github: &Octocrab, project: &crate::config::Project)
 
     // List issues with their states
     for issue in issues_with_thread_ids.iter().take(10) {
-        if let Some(thread_id) = extract_thread_id(&issue.title) {
+        if let Some(thread_id) = issue_thread_id(issue) {
             println!(
                 "    • Issue #{} [{}] - Thread ID: {} - State: {:?}",
                 issue.number, issue.title, thread_id, issue.state
@@ -109,7 +112,7 @@ async fn debug_project_sync(github: &Octocrab, project: &crate::config::Project)
     let open_count = search_result
         .items
         .into_iter()
-        .filter(|issue| extract_thread_id(&issue.title).is_some())
+        .filter(|issue| issue_thread_id(issue).is_some())
         .filter(|i| matches!(i.state, octocrab::models::IssueState::Open))
         .count();
 
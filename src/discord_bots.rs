@@ -0,0 +1,94 @@
+use anyhow::{Context, Result};
+use serenity::http::Http;
+use serenity::prelude::*;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::config::Config;
+
+/// One Discord bot identity and the projects it serves
+pub struct DiscordBot {
+    /// `[[discord_bots]]` name; None for the `DISCORD_TOKEN` bot
+    pub name: Option<String>,
+    pub token: String,
+    /// Only the projects this bot serves
+    pub config: Arc<Config>,
+    pub http: Arc<Http>,
+}
+
+impl DiscordBot {
+    pub fn label(&self) -> &str {
+        label(&self.name)
+    }
+}
+
+/// A bot per distinct `discord_bot` among the projects, the `DISCORD_TOKEN`
+/// one first when any project uses it
+pub fn from_config(config: &Config) -> Result<Vec<DiscordBot>> {
+    let mut names = config.discord_bot_names();
+    if names.is_empty() {
+        names.push(None);
+    }
+
+    names
+        .into_iter()
+        .map(|name| {
+            let token = config
+                .discord_token(name.as_deref())
+                .with_context(|| format!("No token for Discord bot '{}'", label(&name)))?;
+            Ok(DiscordBot {
+                config: Arc::new(config.for_discord_bot(name.as_deref())),
+                http: Arc::new(Http::new(&token)),
+                name,
+                token,
+            })
+        })
+        .collect()
+}
+
+fn label(name: &Option<String>) -> &str {
+    name.as_deref().unwrap_or("default")
+}
+
+/// Keep a bot's gateway connection up, rebuilding the client whenever the
+/// watchdog gives up on a connection that doesn't come back
+pub async fn run_gateway(
+    token: &str,
+    handler: Arc<crate::bot::Bot>,
+    watchdog_after: Duration,
+) -> Result<()> {
+    let intents = GatewayIntents::GUILDS
+        | GatewayIntents::GUILD_MESSAGES
+        | GatewayIntents::GUILD_MESSAGE_REACTIONS
+        | GatewayIntents::MESSAGE_CONTENT;
+
+    loop {
+        let mut client = Client::builder(token, intents)
+            .event_handler_arc(handler.clone())
+            .await?;
+        let watchdog = tokio::spawn(crate::gateway::watchdog(
+            handler.gateway.clone(),
+            client.shard_manager.clone(),
+            watchdog_after,
+        ));
+        let result = client.start().await;
+        watchdog.abort();
+        result?;
+
+        if !handler.gateway.take_restart_request() {
+            return Ok(());
+        }
+        tracing::warn!("Starting a new Discord client");
+    }
+}
+
+/// Pass each wake-up from the webhook listener on to every bot's syncer
+pub async fn fan_out_wake(wake: Arc<Notify>, syncers: Vec<Arc<Notify>>) {
+    loop {
+        wake.notified().await;
+        for syncer in &syncers {
+            syncer.notify_one();
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use serenity::all::{
+    Channel, ChannelId, GuildId, Member, Message, MessageId, Role, RoleId, User, UserId,
+};
+use serenity::http::Http;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Map whose entries expire after a fixed time
+pub struct TtlCache<K, V> {
+    ttl: Duration,
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+}
+
+impl<K: Eq + Hash, V: Clone> TtlCache<K, V> {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(key)
+            .filter(|(stored, _)| stored.elapsed() < self.ttl)
+            .map(|(_, value)| value.clone())
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        // Drop expired entries now and then so the map can't grow without bound
+        if entries.len() >= crate::constants::DISCORD_CACHE_MAX_ENTRIES {
+            entries.retain(|_, (stored, _)| stored.elapsed() < self.ttl);
+        }
+        entries.insert(key, (Instant::now(), value));
+    }
+
+    /// Entries that have not expired
+    pub fn live_count(&self) -> usize {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .values()
+            .filter(|(stored, _)| stored.elapsed() < self.ttl)
+            .count()
+    }
+
+    pub fn remove(&self, key: &K) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(key);
+    }
+}
+
+// Thread state (name, locked, archived) changes often, so channels are kept
+// briefly; users, members and roles rarely change within minutes
+static CHANNELS: LazyLock<TtlCache<ChannelId, Channel>> = LazyLock::new(|| {
+    TtlCache::new(Duration::from_secs(
+        crate::constants::DISCORD_CACHE_CHANNEL_TTL_SECS,
+    ))
+});
+static USERS: LazyLock<TtlCache<UserId, User>> = LazyLock::new(|| {
+    TtlCache::new(Duration::from_secs(
+        crate::constants::DISCORD_CACHE_PROFILE_TTL_SECS,
+    ))
+});
+static MEMBERS: LazyLock<TtlCache<(GuildId, UserId), Member>> = LazyLock::new(|| {
+    TtlCache::new(Duration::from_secs(
+        crate::constants::DISCORD_CACHE_PROFILE_TTL_SECS,
+    ))
+});
+static ROLES: LazyLock<TtlCache<GuildId, HashMap<RoleId, Role>>> = LazyLock::new(|| {
+    TtlCache::new(Duration::from_secs(
+        crate::constants::DISCORD_CACHE_PROFILE_TTL_SECS,
+    ))
+});
+
+/// Live entries in each cache, for `/cardibot debug`
+pub fn sizes() -> Vec<(&'static str, usize)> {
+    vec![
+        ("channels", CHANNELS.live_count()),
+        ("users", USERS.live_count()),
+        ("members", MEMBERS.live_count()),
+        ("roles", ROLES.live_count()),
+    ]
+}
+
+pub async fn channel(http: &Http, channel_id: ChannelId) -> serenity::Result<Channel> {
+    if let Some(channel) = CHANNELS.get(&channel_id) {
+        return Ok(channel);
+    }
+    let channel = channel_id.to_channel(http).await?;
+    CHANNELS.insert(channel_id, channel.clone());
+    Ok(channel)
+}
+
+/// Forget a channel after the bot or a gateway event changed it
+pub fn invalidate_channel(channel_id: ChannelId) {
+    CHANNELS.remove(&channel_id);
+}
+
+/// A forum post's opening message shares the thread's ID; text channel
+/// threads have no such message
+pub fn starter_id(thread_id: ChannelId) -> MessageId {
+    MessageId::new(thread_id.get())
+}
+
+/// A forum post's opening message. Not cached, as its reactions and content
+/// are what callers look at.
+pub async fn starter_message(http: &Http, thread_id: ChannelId) -> serenity::Result<Message> {
+    thread_id.message(http, starter_id(thread_id)).await
+}
+
+pub async fn user(http: &Http, user_id: UserId) -> serenity::Result<User> {
+    if let Some(user) = USERS.get(&user_id) {
+        return Ok(user);
+    }
+    let user = user_id.to_user(http).await?;
+    USERS.insert(user_id, user.clone());
+    Ok(user)
+}
+
+pub async fn member(http: &Http, guild_id: GuildId, user_id: UserId) -> serenity::Result<Member> {
+    if let Some(member) = MEMBERS.get(&(guild_id, user_id)) {
+        return Ok(member);
+    }
+    let member = guild_id.member(http, user_id).await?;
+    MEMBERS.insert((guild_id, user_id), member.clone());
+    Ok(member)
+}
+
+pub async fn roles(http: &Http, guild_id: GuildId) -> serenity::Result<HashMap<RoleId, Role>> {
+    if let Some(roles) = ROLES.get(&guild_id) {
+        return Ok(roles);
+    }
+    let roles = guild_id.roles(http).await?;
+    ROLES.insert(guild_id, roles.clone());
+    Ok(roles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ttl_cache() {
+        let cache = TtlCache::new(Duration::from_secs(60));
+        assert_eq!(cache.get(&1), None);
+        cache.insert(1, "a");
+        assert_eq!(cache.get(&1), Some("a"));
+        assert_eq!(cache.live_count(), 1);
+        cache.remove(&1);
+        assert_eq!(cache.get(&1), None);
+
+        let expired = TtlCache::new(Duration::ZERO);
+        expired.insert(1, "a");
+        assert_eq!(expired.get(&1), None);
+        assert_eq!(expired.live_count(), 0);
+    }
+}
@@ -0,0 +1,168 @@
+use anyhow::Result;
+use tracing::{debug, error, info, warn};
+
+use crate::audit::{project_key, AuditAction, AuditEntry, AuditLog};
+use crate::config::Project;
+use crate::store::{DriftItem, Store};
+
+/// A mismatch between GitHub and Discord that the syncer sees every cycle
+/// until someone fixes it
+pub struct DriftCondition {
+    /// Stable key, e.g. `missing_thread:123`
+    pub item: String,
+    pub thread_id: Option<u64>,
+    pub issue_number: Option<u64>,
+}
+
+impl DriftCondition {
+    /// An open issue whose Discord thread can't be loaded
+    pub fn missing_thread(thread_id: u64, issue_number: u64) -> Self {
+        Self {
+            item: format!("missing_thread:{thread_id}"),
+            thread_id: Some(thread_id),
+            issue_number: Some(issue_number),
+        }
+    }
+
+    fn audit_entry(&self, project: &Project, action: AuditAction) -> AuditEntry {
+        let mut entry = AuditEntry::new(project, action);
+        entry.thread_id = self.thread_id;
+        entry.issue_number = self.issue_number;
+        entry
+    }
+}
+
+/// How loudly a sighting should be reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sighting {
+    New,
+    /// Seen before, but the details differ
+    Changed,
+    /// Unchanged for longer than the escalation threshold, reported once
+    Escalated,
+    Known,
+}
+
+pub fn classify(previous: Option<&DriftItem>, detail: &str, now: i64) -> Sighting {
+    match previous {
+        None => Sighting::New,
+        Some(item) if item.detail != detail => Sighting::Changed,
+        Some(item)
+            if !item.escalated
+                && now - item.first_seen >= crate::constants::DRIFT_ESCALATE_SECS =>
+        {
+            Sighting::Escalated
+        }
+        Some(_) => Sighting::Known,
+    }
+}
+
+/// Record a drift condition, logging and auditing it only when it first
+/// appears, changes, or has persisted long enough to escalate
+pub async fn report(
+    store: &Store,
+    audit: &AuditLog,
+    project: &Project,
+    condition: &DriftCondition,
+    detail: &str,
+) -> Result<()> {
+    let key = project_key(project);
+    let previous = store.drift_item(&key, &condition.item)?;
+    let sighting = classify(previous.as_ref(), detail, chrono::Utc::now().timestamp());
+
+    let action = match sighting {
+        Sighting::New => {
+            warn!("{} in {}: {}", condition.item, key, detail);
+            Some(AuditAction::DriftDetected)
+        }
+        Sighting::Changed => {
+            warn!("{} in {} changed: {}", condition.item, key, detail);
+            Some(AuditAction::DriftDetected)
+        }
+        Sighting::Escalated => {
+            error!(
+                "{} in {} unresolved for {}h: {}",
+                condition.item,
+                key,
+                crate::constants::DRIFT_ESCALATE_SECS / 3600,
+                detail
+            );
+            Some(AuditAction::DriftEscalated)
+        }
+        Sighting::Known => {
+            debug!("{} in {} still present", condition.item, key);
+            None
+        }
+    };
+
+    // Escalation is remembered until the details change
+    let escalated = match sighting {
+        Sighting::Escalated => true,
+        Sighting::Known => previous.is_some_and(|p| p.escalated),
+        Sighting::New | Sighting::Changed => false,
+    };
+    store.upsert_drift_item(&key, &condition.item, detail, escalated)?;
+
+    if let Some(action) = action {
+        audit
+            .record(condition.audit_entry(project, action).details(detail))
+            .await;
+    }
+    Ok(())
+}
+
+/// The condition no longer holds; logs and audits it if it had been reported
+pub async fn clear(
+    store: &Store,
+    audit: &AuditLog,
+    project: &Project,
+    condition: &DriftCondition,
+) -> Result<()> {
+    let key = project_key(project);
+    if store.remove_drift_item(&key, &condition.item)? {
+        info!("{} in {} resolved", condition.item, key);
+        audit
+            .record(condition.audit_entry(project, AuditAction::DriftResolved))
+            .await;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let day = crate::constants::DRIFT_ESCALATE_SECS;
+        let item = DriftItem {
+            project: "org/app".to_string(),
+            item: "missing_thread:1".to_string(),
+            detail: "Unknown Channel".to_string(),
+            first_seen: 1000,
+            last_seen: 1000,
+            escalated: false,
+        };
+        assert_eq!(classify(None, "Unknown Channel", 1000), Sighting::New);
+        assert_eq!(
+            classify(Some(&item), "Unknown Channel", 2000),
+            Sighting::Known
+        );
+        assert_eq!(
+            classify(Some(&item), "Missing Access", 2000),
+            Sighting::Changed
+        );
+        assert_eq!(
+            classify(Some(&item), "Unknown Channel", 1000 + day),
+            Sighting::Escalated
+        );
+        let escalated = DriftItem {
+            escalated: true,
+            ..item
+        };
+        assert_eq!(
+            classify(Some(&escalated), "Unknown Channel", 1000 + 2 * day),
+            Sighting::Known
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use anyhow::Result;
+use serenity::all::*;
+use tracing::info;
+
+use crate::audit::{project_key, AuditAction, AuditEntry, AuditLog};
+use crate::config::{Config, Project};
+use crate::store::Store;
+
+/// `number` option of a `/issue <subcommand>` invocation
+pub fn subcommand_issue_number(command: &CommandInteraction) -> Option<u64> {
+    let CommandDataOptionValue::SubCommand(options) = &command.data.options.first()?.value else {
+        return None;
+    };
+    options
+        .iter()
+        .find(|o| o.name == "number")
+        .and_then(|o| o.value.as_i64())
+        .and_then(|n| u64::try_from(n).ok())
+}
+
+/// Handle `/issue duplicate <number>`: close the thread's issue as a duplicate,
+/// point the thread at the canonical issue and archive it
+pub async fn mark_duplicate(
+    http: &Http,
+    command: &CommandInteraction,
+    config: &Config,
+    project: &Project,
+    thread: &GuildChannel,
+    store: &Store,
+    audit: &AuditLog,
+) -> Result<()> {
+    let Some(canonical) = subcommand_issue_number(command) else {
+        command
+            .edit_response(
+                http,
+                EditInteractionResponse::new().content("Give the number of the original issue"),
+            )
+            .await?;
+        return Ok(());
+    };
+
+    let github = crate::github_app::create_project_client(config, project).await?;
+    let Some(issue) =
+        crate::github::find_issue_for_thread(&github, project, thread.id.get()).await?
+    else {
+        command
+            .edit_response(
+                http,
+                EditInteractionResponse::new().content(crate::constants::MSG_NO_LINKED_ISSUE),
+            )
+            .await?;
+        return Ok(());
+    };
+    if issue.number == canonical {
+        command
+            .edit_response(
+                http,
+                EditInteractionResponse::new()
+                    .content(format!("This thread is already linked to #{canonical}")),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    // Cross-references the thread on the canonical issue, so its subscribers
+    // see the new report, and posts the canonical link in the thread
+    crate::filing::link_duplicate_thread(&github, http, project, thread, canonical).await?;
+
+    let issues = github.issues(&project.github_owner, &project.github_repo);
+    issues
+        .create_comment(
+            issue.number,
+            format!(
+                "Duplicate of #{canonical}, marked by {} on Discord.",
+                command.user.name
+            ),
+        )
+        .await?;
+    issues
+        .update(issue.number)
+        .state(octocrab::models::IssueState::Closed)
+        .state_reason(octocrab::models::issues::IssueStateReason::Duplicate)
+        .send()
+        .await?;
+
+    store.upsert_thread_issue(thread.id.get(), &project_key(project), canonical)?;
+    audit
+        .record(
+            AuditEntry::new(project, AuditAction::IssueClosed)
+                .thread(thread.id.get())
+                .issue(issue.number)
+                .actor(&command.user.name)
+                .details(format!("duplicate of #{canonical}")),
+        )
+        .await;
+    info!(
+        "Closed issue #{} as duplicate of #{} for thread {}",
+        issue.number, canonical, thread.id
+    );
+
+    command
+        .edit_response(
+            http,
+            EditInteractionResponse::new().content(format!(
+                "✅ Closed #{} as a duplicate of #{canonical}",
+                issue.number
+            )),
+        )
+        .await?;
+
+    crate::sync::close_thread(http, project, thread.id).await?;
+    audit
+        .record(
+            AuditEntry::new(project, AuditAction::ThreadArchived)
+                .thread(thread.id.get())
+                .issue(canonical)
+                .actor(&command.user.name)
+                .details("duplicate"),
+        )
+        .await;
+
+    Ok(())
+}
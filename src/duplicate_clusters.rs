@@ -0,0 +1,548 @@
+use anyhow::Result;
+use serenity::all::*;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::audit::{project_key, AuditLog};
+use crate::config::{Config, DuplicateReportsConfig, Project};
+use crate::embeds::Embed;
+use crate::filing::Filed;
+use crate::store::Store;
+
+const CLUSTER_PREFIX: &str = "dedup:";
+
+pub const STATUS_MERGED: &str = "merged";
+pub const STATUS_DISMISSED: &str = "dismissed";
+
+/// Words too common in reports to say anything about the problem
+const STOP_WORDS: &[&str] = &[
+    "and", "are", "but", "can", "cant", "does", "doesnt", "dont", "for", "from", "get", "got",
+    "has", "have", "help", "how", "issue", "its", "just", "not", "please", "problem", "that",
+    "the", "then", "there", "this", "was", "what", "when", "where", "why", "will", "with", "work",
+    "working", "you", "your",
+];
+
+/// When each project was last scanned, so scans run once per interval
+#[derive(Default)]
+pub struct DuplicateScans {
+    last: std::sync::Mutex<HashMap<String, Instant>>,
+}
+
+impl DuplicateScans {
+    /// Whether a project's scan is due, counting it as started if so
+    pub fn start(&self, project: &Project, reports: &DuplicateReportsConfig) -> bool {
+        let interval = Duration::from_secs(reports.interval_hours() * 60 * 60);
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+        let key = project_key(project);
+        if last.get(&key).is_some_and(|at| at.elapsed() < interval) {
+            return false;
+        }
+        last.insert(key, Instant::now());
+        true
+    }
+}
+
+/// Open threads as weighted term vectors. Terms are weighted by how rare they
+/// are across the forum, so words every report shares count for little.
+pub struct Corpus {
+    vectors: Vec<HashMap<String, f64>>,
+}
+
+impl Corpus {
+    /// Build from each thread's title and opening post; title words count twice
+    pub fn new(threads: &[(String, String)]) -> Self {
+        let counts: Vec<HashMap<String, f64>> = threads
+            .iter()
+            .map(|(title, body)| {
+                let mut counts = HashMap::new();
+                for term in tokens(title) {
+                    *counts.entry(term).or_insert(0.0) += 2.0;
+                }
+                for term in tokens(body) {
+                    *counts.entry(term).or_insert(0.0) += 1.0;
+                }
+                counts
+            })
+            .collect();
+
+        let mut frequency: HashMap<&str, usize> = HashMap::new();
+        for terms in &counts {
+            for term in terms.keys() {
+                *frequency.entry(term).or_insert(0) += 1;
+            }
+        }
+        let n = counts.len() as f64;
+        let vectors = counts
+            .iter()
+            .map(|terms| {
+                let mut vector: HashMap<String, f64> = terms
+                    .iter()
+                    .map(|(term, count)| {
+                        let idf = ((1.0 + n) / (1.0 + frequency[term.as_str()] as f64)).ln() + 1.0;
+                        (term.clone(), count * idf)
+                    })
+                    .collect();
+                let norm = vector.values().map(|w| w * w).sum::<f64>().sqrt();
+                if norm > 0.0 {
+                    vector.values_mut().for_each(|w| *w /= norm);
+                }
+                vector
+            })
+            .collect();
+        Self { vectors }
+    }
+
+    /// Cosine similarity of two threads, from 0 to 1
+    pub fn similarity(&self, a: usize, b: usize) -> f64 {
+        let (small, large) = match self.vectors[a].len() <= self.vectors[b].len() {
+            true => (&self.vectors[a], &self.vectors[b]),
+            false => (&self.vectors[b], &self.vectors[a]),
+        };
+        small
+            .iter()
+            .filter_map(|(term, w)| large.get(term).map(|v| w * v))
+            .sum()
+    }
+
+    /// Groups of two or more threads, each linked to another in its group by
+    /// a similarity of at least `threshold`; largest groups first
+    pub fn clusters(&self, threshold: f64) -> Vec<Vec<usize>> {
+        let mut parent: Vec<usize> = (0..self.vectors.len()).collect();
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        for a in 0..self.vectors.len() {
+            for b in a + 1..self.vectors.len() {
+                if self.similarity(a, b) >= threshold {
+                    let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+                    parent[ra.max(rb)] = ra.min(rb);
+                }
+            }
+        }
+
+        let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for i in 0..self.vectors.len() {
+            groups.entry(root(&mut parent, i)).or_default().push(i);
+        }
+        let mut clusters: Vec<Vec<usize>> = groups.into_values().filter(|g| g.len() > 1).collect();
+        clusters.sort_by_key(|g| std::cmp::Reverse(g.len()));
+        clusters
+    }
+
+    /// The heaviest terms every thread of a cluster has
+    pub fn shared_terms(&self, members: &[usize], limit: usize) -> Vec<String> {
+        let Some((first, rest)) = members.split_first() else {
+            return Vec::new();
+        };
+        let mut shared: Vec<(&String, f64)> = self.vectors[*first]
+            .keys()
+            .filter(|term| rest.iter().all(|i| self.vectors[*i].contains_key(*term)))
+            .map(|term| (term, members.iter().map(|i| self.vectors[*i][term]).sum()))
+            .collect();
+        shared.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        shared
+            .into_iter()
+            .take(limit)
+            .map(|(term, _)| term.clone())
+            .collect()
+    }
+}
+
+/// Lowercase words of three or more letters, without stop words or numbers
+fn tokens(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|word| {
+            word.chars().count() >= 3
+                && !word.chars().all(|c| c.is_ascii_digit())
+                && !STOP_WORDS.contains(&word.as_str())
+        })
+}
+
+/// Compare the project's open threads and post each new cluster of likely
+/// duplicates to the moderator channel, with buttons to merge or dismiss it
+pub async fn report_clusters(
+    discord: &Http,
+    store: &Store,
+    project: &Project,
+    reports: &DuplicateReportsConfig,
+) -> Result<()> {
+    let channel_id = ChannelId::new(reports.channel_id.parse()?);
+    let guild_id = GuildId::new(project.discord_guild_id.parse()?);
+    let forum_id = ChannelId::new(project.discord_forum_id.parse()?);
+
+    let active = guild_id.get_active_threads(discord).await?;
+    let mut threads: Vec<&GuildChannel> = active
+        .threads
+        .iter()
+        .filter(|thread| {
+            thread.parent_id == Some(forum_id)
+                && project.has_valid_prefix(&thread.name)
+                && !thread.thread_metadata.as_ref().is_some_and(|m| m.locked)
+        })
+        .collect();
+    // Newest threads matter most during a spike
+    threads.sort_by_key(|thread| std::cmp::Reverse(thread.id));
+    threads.truncate(crate::constants::DUPLICATE_SCAN_MAX_THREADS);
+    threads.reverse();
+
+    let prefixes = project.prefixes();
+    let mut texts = Vec::with_capacity(threads.len());
+    for thread in &threads {
+        let title = crate::prefix::match_prefix(&thread.name, &prefixes)
+            .map_or_else(|| thread.name.clone(), |(_, title)| title);
+        let body = match crate::discord_cache::starter_message(discord, thread.id).await {
+            Ok(message) => message.content,
+            Err(e) => {
+                debug!("No opening post for thread {}: {}", thread.id, e);
+                String::new()
+            }
+        };
+        texts.push((title, body));
+    }
+
+    let corpus = Corpus::new(&texts);
+    let clusters = corpus.clusters(reports.similarity());
+    debug!(
+        "Compared {} threads of {}: {} possible duplicate clusters",
+        threads.len(),
+        project_key(project),
+        clusters.len()
+    );
+
+    for members in clusters {
+        let ids: Vec<u64> = members.iter().map(|i| threads[*i].id.get()).collect();
+        let Some(cluster_id) = store.record_duplicate_cluster(&project_key(project), &ids)? else {
+            continue;
+        };
+        let terms = corpus.shared_terms(&members, 3);
+        let message = cluster_message(store, cluster_id, &ids, &terms)?;
+        if let Err(e) = channel_id.send_message(discord, message).await {
+            // Forgotten so the next scan reports it again
+            store.delete_duplicate_cluster(cluster_id)?;
+            return Err(e.into());
+        }
+        info!(
+            "Reported {} possible duplicate threads in {}",
+            ids.len(),
+            project_key(project)
+        );
+    }
+    Ok(())
+}
+
+fn cluster_message(
+    store: &Store,
+    cluster_id: i64,
+    threads: &[u64],
+    terms: &[String],
+) -> Result<CreateMessage> {
+    let target = merge_target(store, threads)?;
+    let mut description = format!(
+        "These {} open threads look like the same problem",
+        threads.len()
+    );
+    if !terms.is_empty() {
+        description.push_str(&format!(" ({})", terms.join(", ")));
+    }
+    description.push_str(":\n");
+    for thread_id in threads.iter().take(crate::constants::DUPLICATE_MAX_LISTED) {
+        description.push_str(&format!("\n• <#{thread_id}>"));
+        if let Some(mapping) = store.thread_issue(*thread_id)? {
+            description.push_str(&format!(" - issue #{}", mapping.issue_number));
+        }
+    }
+    if let Some(more) = threads
+        .len()
+        .checked_sub(crate::constants::DUPLICATE_MAX_LISTED)
+        .filter(|more| *more > 0)
+    {
+        description.push_str(&format!("\n…and {more} more"));
+    }
+    description.push_str(&format!(
+        "\n\nMerging files the others under <#{target}>'s issue and points them at it."
+    ));
+
+    let embed = Embed::new()
+        .title(crate::constants::MSG_DUPLICATE_CLUSTER)
+        .description(description)
+        .color(crate::constants::COLOR_SUCCESS);
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{CLUSTER_PREFIX}merge:{cluster_id}"))
+            .label("Merge")
+            .style(ButtonStyle::Primary),
+        CreateButton::new(format!("{CLUSTER_PREFIX}dismiss:{cluster_id}"))
+            .label("Not duplicates")
+            .style(ButtonStyle::Secondary),
+    ]);
+    Ok(CreateMessage::new()
+        .embed(embed.build())
+        .components(vec![buttons])
+        .allowed_mentions(CreateAllowedMentions::new()))
+}
+
+/// The thread the others are merged into: the oldest with an issue, else the
+/// oldest
+fn merge_target(store: &Store, threads: &[u64]) -> Result<u64> {
+    for thread_id in threads {
+        if store.thread_issue(*thread_id)?.is_some() {
+            return Ok(*thread_id);
+        }
+    }
+    threads
+        .first()
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("Duplicate cluster has no threads"))
+}
+
+pub fn is_cluster_component(custom_id: &str) -> bool {
+    custom_id.starts_with(CLUSTER_PREFIX)
+}
+
+/// Merge a reported cluster into its target thread, or dismiss it
+pub async fn handle_component(
+    http: &Http,
+    component: &ComponentInteraction,
+    config: &Arc<Config>,
+    store: &Store,
+    audit: &AuditLog,
+) -> Result<()> {
+    let Some((action, cluster_id)) = component
+        .data
+        .custom_id
+        .strip_prefix(CLUSTER_PREFIX)
+        .and_then(|rest| rest.split_once(':'))
+        .and_then(|(action, id)| Some((action, id.parse::<i64>().ok()?)))
+    else {
+        return Ok(());
+    };
+    let Some(cluster) = store.duplicate_cluster(cluster_id)? else {
+        return Ok(());
+    };
+
+    let target = ChannelId::new(merge_target(store, &cluster.threads)?);
+    let Some((target_thread, project)) =
+        crate::thread_events::resolve_thread(http, target, config, store).await?
+    else {
+        return reply(http, component, "The threads of this report are gone").await;
+    };
+    if !crate::priority::has_allowed_role(&project, component.member.as_ref()) {
+        return reply(
+            http,
+            component,
+            "You don't have permission to merge threads",
+        )
+        .await;
+    }
+    if cluster.status != "reported" {
+        return reply(http, component, "This report was already handled").await;
+    }
+
+    match action {
+        "dismiss" => {
+            store.set_duplicate_cluster_status(cluster_id, STATUS_DISMISSED)?;
+            component
+                .create_response(
+                    http,
+                    CreateInteractionResponse::UpdateMessage(
+                        CreateInteractionResponseMessage::new()
+                            .content(format!("🙅 Dismissed by <@{}>", component.user.id))
+                            .components(vec![]),
+                    ),
+                )
+                .await?;
+        }
+        "merge" => {
+            component.defer(http).await?;
+            let actor = &component.user.name;
+            let github = crate::github_app::create_project_client(config, &project).await?;
+            let target_issue = match crate::merge::linked_issue(&github, store, &project, target)
+                .await?
+            {
+                Some(issue) => issue,
+                None => match crate::filing::file_thread(
+                    http,
+                    config,
+                    &project,
+                    &target_thread,
+                    store,
+                    audit,
+                    actor,
+                    &Default::default(),
+                    crate::progress::Progress::Silent,
+                )
+                .await?
+                {
+                    Filed::Issue(result) => result.issue.number,
+                    Filed::Elsewhere { .. } => {
+                        component
+                                .edit_response(
+                                    http,
+                                    EditInteractionResponse::new().content(format!(
+                                        "<#{target}> is filed outside GitHub issues, so threads can't be merged into it"
+                                    )),
+                                )
+                                .await?;
+                        return Ok(());
+                    }
+                },
+            };
+
+            let mut merged = 0;
+            let mut failed = Vec::new();
+            for thread_id in cluster.threads.iter().filter(|id| **id != target.get()) {
+                let result = merge_member(
+                    http,
+                    config,
+                    store,
+                    audit,
+                    ChannelId::new(*thread_id),
+                    target,
+                    &project_key(&project),
+                    target_issue,
+                    actor,
+                )
+                .await;
+                match result {
+                    Ok(true) => merged += 1,
+                    Ok(false) => {}
+                    Err(e) => {
+                        warn!(
+                            "Failed to merge thread {} into {}: {}",
+                            thread_id, target, e
+                        );
+                        failed.push(format!("<#{thread_id}>"));
+                    }
+                }
+            }
+            store.set_duplicate_cluster_status(cluster_id, STATUS_MERGED)?;
+
+            let mut content = format!(
+                "🔀 <@{}> merged {merged} threads into <#{target}> (issue #{target_issue})",
+                component.user.id
+            );
+            if !failed.is_empty() {
+                content.push_str(&format!("; failed: {}", failed.join(", ")));
+            }
+            component
+                .edit_response(
+                    http,
+                    EditInteractionResponse::new()
+                        .content(content)
+                        .components(vec![]),
+                )
+                .await?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Merge one thread of a cluster; false if it no longer belongs to a project
+#[allow(clippy::too_many_arguments)]
+async fn merge_member(
+    http: &Http,
+    config: &Config,
+    store: &Store,
+    audit: &AuditLog,
+    thread_id: ChannelId,
+    target: ChannelId,
+    target_key: &str,
+    target_issue: u64,
+    actor: &str,
+) -> Result<bool> {
+    let Some((thread, project)) =
+        crate::thread_events::resolve_thread(http, thread_id, config, store).await?
+    else {
+        return Ok(false);
+    };
+    // Issue numbers only mean something in the repository they were filed in
+    if project_key(&project) != target_key {
+        anyhow::bail!(
+            "Thread {} is tracked in {}, not {}",
+            thread.id,
+            project_key(&project),
+            target_key
+        );
+    }
+    let github = crate::github_app::create_project_client(config, &project).await?;
+    crate::merge::merge_into(
+        &github,
+        http,
+        &project,
+        &thread,
+        target,
+        target_issue,
+        store,
+        audit,
+        actor,
+    )
+    .await?;
+    Ok(true)
+}
+
+async fn reply(http: &Http, component: &ComponentInteraction, content: &str) -> Result<()> {
+    component
+        .create_response(
+            http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus(threads: &[(&str, &str)]) -> Corpus {
+        let threads: Vec<(String, String)> = threads
+            .iter()
+            .map(|(title, body)| (title.to_string(), body.to_string()))
+            .collect();
+        Corpus::new(&threads)
+    }
+
+    #[test]
+    fn test_clusters() {
+        let corpus = corpus(&[
+            (
+                "Wallet connect fails",
+                "WalletConnect popup closes on mobile",
+            ),
+            (
+                "Dark mode colors off",
+                "The sidebar is unreadable in dark mode",
+            ),
+            ("Can't connect wallet", "wallet connect spins forever"),
+            ("Wallet connect broken", "connect button does nothing"),
+            ("Export to CSV", "Please add an export button"),
+        ]);
+        assert_eq!(corpus.clusters(0.3), vec![vec![0, 2, 3]]);
+        assert!(corpus.similarity(0, 2) > corpus.similarity(0, 1));
+        assert_eq!(corpus.similarity(1, 4), 0.0);
+        assert_eq!(
+            corpus.shared_terms(&[0, 2, 3], 2),
+            vec!["connect".to_string(), "wallet".to_string()]
+        );
+        assert!(corpus.clusters(1.1).is_empty());
+    }
+
+    #[test]
+    fn test_tokens() {
+        assert_eq!(
+            tokens("The app crashes on v2, error 500!").collect::<Vec<_>>(),
+            vec!["app", "crashes", "error"]
+        );
+    }
+}
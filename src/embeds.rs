@@ -0,0 +1,285 @@
+use serenity::all::{
+    ChannelId, CreateAllowedMentions, CreateEmbed, CreateEmbedFooter, CreateMessage, Http, Message,
+    Timestamp,
+};
+
+use crate::constants::{
+    DISCORD_EMBEDS_PER_MESSAGE, DISCORD_EMBED_DESCRIPTION_LIMIT, DISCORD_EMBED_FIELD_COUNT_LIMIT,
+    DISCORD_EMBED_FIELD_NAME_LIMIT, DISCORD_EMBED_FIELD_VALUE_LIMIT, DISCORD_EMBED_FOOTER_LIMIT,
+    DISCORD_EMBED_TITLE_LIMIT, DISCORD_EMBED_TOTAL_LIMIT,
+};
+use crate::sanitize::truncate_for_discord;
+
+/// An embed kept within Discord's limits, which reject the whole message
+/// otherwise. Titles, field names and values and footers are cut; a long
+/// description is cut by `build` or continued in more embeds by `send`.
+#[derive(Debug, Clone, Default)]
+pub struct Embed {
+    title: Option<String>,
+    url: Option<String>,
+    description: Option<String>,
+    fields: Vec<(String, String, bool)>,
+    footer: Option<String>,
+    timestamp: Option<Timestamp>,
+    color: Option<u32>,
+}
+
+/// A built embed and the characters it counts toward a message's total
+struct Piece {
+    embed: CreateEmbed,
+    len: usize,
+}
+
+impl Embed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn field(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+        inline: bool,
+    ) -> Self {
+        self.fields.push((name.into(), value.into(), inline));
+        self
+    }
+
+    pub fn footer(mut self, footer: impl Into<String>) -> Self {
+        self.footer = Some(footer.into());
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: Timestamp) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    pub fn color(mut self, color: u32) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// A single embed, for messages that are edited in place and interaction
+    /// replies. The description gives way when the parts together are too long.
+    pub fn build(self) -> CreateEmbed {
+        let fitted = self.fitted();
+        let room = DISCORD_EMBED_TOTAL_LIMIT.saturating_sub(fitted.title_len() + fitted.tail_len());
+        let description = fitted
+            .description
+            .as_deref()
+            .map(|d| truncate_for_discord(d, room.min(DISCORD_EMBED_DESCRIPTION_LIMIT)));
+        fitted.piece(true, description, true).embed
+    }
+
+    /// Send the embed to a channel, with `message` carrying the content,
+    /// components and mentions of the first message. Embeds past what one
+    /// message holds follow in more messages. Returns the first message.
+    pub async fn send(
+        self,
+        http: &Http,
+        channel: ChannelId,
+        message: CreateMessage,
+    ) -> serenity::Result<Message> {
+        let mut groups = group(self.pieces()).into_iter();
+        let first = channel
+            .send_message(http, message.embeds(groups.next().unwrap_or_default()))
+            .await?;
+        for embeds in groups {
+            channel
+                .send_message(
+                    http,
+                    CreateMessage::new()
+                        .embeds(embeds)
+                        .allowed_mentions(CreateAllowedMentions::new()),
+                )
+                .await?;
+        }
+        Ok(first)
+    }
+
+    /// The embed, followed by as many more as its description needs. The
+    /// first keeps the title and link, the last the fields and footer.
+    fn pieces(self) -> Vec<Piece> {
+        let fitted = self.fitted();
+        let chunks = match &fitted.description {
+            Some(description) => split_text(description, DISCORD_EMBED_DESCRIPTION_LIMIT),
+            None => vec![String::new()],
+        };
+        let last = chunks.len() - 1;
+        let mut pieces = Vec::new();
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            let head = i == 0;
+            let description = Some(chunk).filter(|c| !c.is_empty());
+            if i < last {
+                pieces.push(fitted.piece(head, description, false));
+                continue;
+            }
+            let len = description.as_ref().map_or(0, |d| d.chars().count());
+            let title_len = if head { fitted.title_len() } else { 0 };
+            // Fields and footer get an embed of their own when they don't fit
+            if title_len + len + fitted.tail_len() > DISCORD_EMBED_TOTAL_LIMIT {
+                pieces.push(fitted.piece(head, description, false));
+                pieces.push(fitted.piece(false, None, true));
+            } else {
+                pieces.push(fitted.piece(head, description, true));
+            }
+        }
+        pieces
+    }
+
+    /// The parts other than the description cut to their limits
+    fn fitted(mut self) -> Self {
+        self.title = self
+            .title
+            .map(|t| truncate_for_discord(&t, DISCORD_EMBED_TITLE_LIMIT));
+        self.fields.truncate(DISCORD_EMBED_FIELD_COUNT_LIMIT);
+        for (name, value, _) in &mut self.fields {
+            *name = truncate_for_discord(name, DISCORD_EMBED_FIELD_NAME_LIMIT);
+            *value = truncate_for_discord(value, DISCORD_EMBED_FIELD_VALUE_LIMIT);
+        }
+        self.footer = self
+            .footer
+            .map(|f| truncate_for_discord(&f, DISCORD_EMBED_FOOTER_LIMIT));
+        self
+    }
+
+    fn title_len(&self) -> usize {
+        self.title.as_ref().map_or(0, |t| t.chars().count())
+    }
+
+    /// Characters in the fields and footer
+    fn tail_len(&self) -> usize {
+        let fields: usize = self
+            .fields
+            .iter()
+            .map(|(name, value, _)| name.chars().count() + value.chars().count())
+            .sum();
+        fields + self.footer.as_ref().map_or(0, |f| f.chars().count())
+    }
+
+    fn piece(&self, head: bool, description: Option<String>, tail: bool) -> Piece {
+        let mut embed = CreateEmbed::new();
+        let mut len = 0;
+        if let Some(color) = self.color {
+            embed = embed.color(color);
+        }
+        if head {
+            if let Some(title) = &self.title {
+                embed = embed.title(title);
+                len += self.title_len();
+            }
+            if let Some(url) = &self.url {
+                embed = embed.url(url);
+            }
+        }
+        if let Some(description) = description {
+            len += description.chars().count();
+            embed = embed.description(description);
+        }
+        if tail {
+            for (name, value, inline) in &self.fields {
+                embed = embed.field(name, value, *inline);
+            }
+            if let Some(footer) = &self.footer {
+                embed = embed.footer(CreateEmbedFooter::new(footer));
+            }
+            if let Some(timestamp) = self.timestamp {
+                embed = embed.timestamp(timestamp);
+            }
+            len += self.tail_len();
+        }
+        Piece { embed, len }
+    }
+}
+
+/// Embeds grouped into messages within the per-message count and total
+fn group(pieces: Vec<Piece>) -> Vec<Vec<CreateEmbed>> {
+    let mut groups: Vec<Vec<CreateEmbed>> = Vec::new();
+    let mut total = 0;
+    for piece in pieces {
+        let fits = groups.last().is_some_and(|g| {
+            g.len() < DISCORD_EMBEDS_PER_MESSAGE && total + piece.len <= DISCORD_EMBED_TOTAL_LIMIT
+        });
+        if fits {
+            total += piece.len;
+            groups.last_mut().unwrap().push(piece.embed);
+        } else {
+            total = piece.len;
+            groups.push(vec![piece.embed]);
+        }
+    }
+    groups
+}
+
+/// Cut text into chunks of at most `max_chars`, at a line break in the
+/// second half of a chunk when there is one
+pub fn split_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while rest.chars().count() > max_chars {
+        let end = rest
+            .char_indices()
+            .nth(max_chars)
+            .map_or(rest.len(), |(i, _)| i);
+        let cut = match rest[..end].rfind('\n') {
+            Some(i) if i >= end / 2 => i,
+            _ => end,
+        };
+        chunks.push(rest[..cut].to_string());
+        rest = rest[cut..].strip_prefix('\n').unwrap_or(&rest[cut..]);
+    }
+    if !rest.is_empty() || chunks.is_empty() {
+        chunks.push(rest.to_string());
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_text() {
+        assert_eq!(split_text("short", 10), vec!["short"]);
+        assert_eq!(split_text("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(split_text("one two\nthree", 10), vec!["one two", "three"]);
+        // A break early in the chunk would waste most of it
+        assert_eq!(split_text("a\nbcdefgh", 4), vec!["a\nbc", "defg", "h"]);
+        assert_eq!(split_text("ééééé", 2), vec!["éé", "éé", "é"]);
+    }
+
+    #[test]
+    fn test_split_long_description() {
+        let description = "x".repeat(DISCORD_EMBED_DESCRIPTION_LIMIT * 2 + 10);
+        let embed = Embed::new().title("Title").description(description).field(
+            "Name",
+            "v".repeat(2000),
+            true,
+        );
+        let pieces = embed.pieces();
+        assert_eq!(pieces.len(), 3);
+        assert!(pieces.iter().all(|p| p.len <= DISCORD_EMBED_TOTAL_LIMIT));
+        assert_eq!(
+            pieces[2].len,
+            10 + "Name".len() + DISCORD_EMBED_FIELD_VALUE_LIMIT
+        );
+        // Two full descriptions are over one message's total; the rest fits
+        assert_eq!(group(pieces).len(), 2);
+    }
+}
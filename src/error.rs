@@ -0,0 +1,174 @@
+use octocrab::GitHubError;
+use serde_json::Value;
+
+/// Errors from slash command handlers, classified so the user gets a precise
+/// ephemeral message and the logs carry a countable category
+#[derive(Debug, thiserror::Error)]
+pub enum BotError {
+    /// Something the user can act on; shown to them as is
+    #[error("{0}")]
+    User(String),
+    /// GitHub refused the request, e.g. an invalid label or missing access
+    #[error("GitHub rejected the request: {}", .0.message)]
+    GitHubRejected(Box<GitHubError>),
+    #[error("GitHub request failed: {0}")]
+    GitHub(octocrab::Error),
+    #[error("Discord request failed: {0}")]
+    Discord(#[from] serenity::Error),
+    #[error("Invalid configuration: {0}")]
+    Config(String),
+    #[error(transparent)]
+    Internal(anyhow::Error),
+}
+
+impl BotError {
+    /// Stable name for logs and error counts
+    pub fn category(&self) -> &'static str {
+        match self {
+            Self::User(_) => "user",
+            Self::GitHubRejected(_) => "github_rejected",
+            Self::GitHub(_) => "github",
+            Self::Discord(_) => "discord",
+            Self::Config(_) => "config",
+            Self::Internal(_) => "internal",
+        }
+    }
+
+    /// Whether the message is meant for the user rather than the logs
+    pub fn is_user_facing(&self) -> bool {
+        matches!(
+            self,
+            Self::User(_) | Self::GitHubRejected(_) | Self::Config(_)
+        )
+    }
+
+    /// Text for the ephemeral reply
+    pub fn user_message(&self) -> String {
+        match self {
+            Self::User(message) => message.clone(),
+            Self::GitHubRejected(error) => github_rejection(
+                error.status_code.as_u16(),
+                &error.message,
+                error.errors.as_deref().unwrap_or_default(),
+            ),
+            Self::Config(message) => {
+                format!("This project is misconfigured ({message}); please tell the maintainers")
+            }
+            Self::GitHub(_) => "GitHub could not be reached; please try again later".to_string(),
+            Self::Discord(_) | Self::Internal(_) => {
+                "Something went wrong; the maintainers can find details in the bot logs".to_string()
+            }
+        }
+    }
+}
+
+impl From<octocrab::Error> for BotError {
+    fn from(error: octocrab::Error) -> Self {
+        match error {
+            octocrab::Error::GitHub { source, .. } if source.status_code.is_client_error() => {
+                Self::GitHubRejected(source)
+            }
+            error => Self::GitHub(error),
+        }
+    }
+}
+
+impl From<anyhow::Error> for BotError {
+    fn from(error: anyhow::Error) -> Self {
+        // Helpers return anyhow; recover the API error they wrap where possible
+        let error = match error.downcast::<octocrab::Error>() {
+            Ok(github) => return github.into(),
+            Err(error) => error,
+        };
+        match error.downcast::<serenity::Error>() {
+            Ok(discord) => Self::Discord(discord),
+            Err(error) => Self::Internal(error),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for BotError {
+    fn from(error: rusqlite::Error) -> Self {
+        Self::Internal(error.into())
+    }
+}
+
+/// Whether GitHub answered that the issue or comment doesn't exist
+pub fn is_github_not_found(error: &octocrab::Error) -> bool {
+    matches!(
+        error,
+        octocrab::Error::GitHub { source, .. } if source.status_code.as_u16() == 404
+    )
+}
+
+/// Whether Discord answered that the message or channel doesn't exist, as
+/// opposed to failing to answer at all
+pub fn is_discord_not_found(error: &serenity::Error) -> bool {
+    matches!(
+        error,
+        serenity::Error::Http(serenity::http::HttpError::UnsuccessfulRequest(response))
+            if response.status_code == serenity::http::StatusCode::NOT_FOUND
+    )
+}
+
+/// "GitHub rejected the label `X`" from a validation error, or GitHub's own
+/// message for other refusals
+fn github_rejection(status: u16, message: &str, errors: &[Value]) -> String {
+    let details: Vec<String> = errors
+        .iter()
+        .filter_map(describe_validation_error)
+        .collect();
+    if !details.is_empty() {
+        return format!("GitHub rejected {}", details.join(", "));
+    }
+    match status {
+        401 | 403 => format!("GitHub denied access: {message}"),
+        404 => "GitHub could not find the repository or issue; check the project's github_owner and github_repo".to_string(),
+        _ => format!("GitHub rejected the request: {message}"),
+    }
+}
+
+fn describe_validation_error(error: &Value) -> Option<String> {
+    if let Some(message) = error["message"].as_str() {
+        return Some(message.to_string());
+    }
+    let resource = error["resource"].as_str()?.to_lowercase();
+    Some(match error["value"].as_str() {
+        Some(value) => format!("the {resource} `{value}`"),
+        None => match error["field"].as_str() {
+            Some(field) => format!("the {resource} {field}"),
+            None => format!("the {resource}"),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_github_rejection() {
+        let label = serde_json::json!({
+            "resource": "Label", "code": "invalid", "field": "name", "value": "bug "
+        });
+        assert_eq!(
+            github_rejection(422, "Validation Failed", &[label]),
+            "GitHub rejected the label `bug `"
+        );
+
+        let custom = serde_json::json!({ "code": "custom", "message": "title is too long" });
+        assert_eq!(
+            github_rejection(422, "Validation Failed", &[custom]),
+            "GitHub rejected title is too long"
+        );
+
+        assert!(github_rejection(403, "Forbidden", &[]).starts_with("GitHub denied access"));
+    }
+
+    #[test]
+    fn test_classify_anyhow() {
+        let error: BotError = anyhow::anyhow!("boom").into();
+        assert_eq!(error.category(), "internal");
+        assert!(!error.is_user_facing());
+    }
+}
@@ -0,0 +1,287 @@
+use anyhow::Result;
+use serenity::all::*;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::audit::{project_key, AuditAction, AuditEntry, AuditLog};
+use crate::config::{Config, Project};
+use crate::store::Store;
+
+const FEEDBACK_MODAL_PREFIX: &str = "feedback_post:";
+const TITLE_INPUT_ID: &str = "title";
+const DETAILS_INPUT_ID: &str = "details";
+
+/// `/feedback` lets users outside the forum file a properly prefixed post
+pub fn create_feedback_command(config: &Config) -> CreateCommand {
+    let mut kind = CreateCommandOption::new(
+        CommandOptionType::String,
+        "type",
+        "What kind of feedback this is",
+    )
+    .required(true);
+
+    let mut seen = Vec::new();
+    for prefix in config.projects.iter().flat_map(|p| p.prefixes()) {
+        // Discord allows at most 25 choices
+        if seen.len() == 25 || seen.contains(&prefix.prefix) {
+            continue;
+        }
+        kind = kind.add_string_choice(prefix.tag_name(), prefix.prefix.clone());
+        seen.push(prefix.prefix);
+    }
+
+    CreateCommand::new("feedback")
+        .description("Send feedback to the team without finding the forum")
+        .add_option(kind)
+}
+
+pub fn is_feedback_modal(custom_id: &str) -> bool {
+    custom_id.starts_with(FEEDBACK_MODAL_PREFIX)
+}
+
+async fn reply(http: &Http, command: &CommandInteraction, content: &str) -> Result<()> {
+    command
+        .create_response(
+            http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Open the feedback form; the chosen prefix travels in the modal ID
+pub async fn handle_feedback_command(
+    http: &Http,
+    command: &CommandInteraction,
+    config: &Config,
+) -> Result<()> {
+    let Some(project) = feedback_project(http, config, command.channel_id).await? else {
+        return reply(http, command, "This channel is not configured for feedback").await;
+    };
+    if !crate::priority::has_allowed_role(project, command.member.as_deref()) {
+        return reply(http, command, "You don't have permission to send feedback").await;
+    }
+
+    let prefix = command
+        .data
+        .options
+        .iter()
+        .find(|o| o.name == "type")
+        .and_then(|o| o.value.as_str())
+        .filter(|p| project.prefixes().iter().any(|c| c.prefix == *p))
+        .unwrap_or(crate::constants::PREFIX_FEEDBACK)
+        .to_string();
+
+    let modal = CreateModal::new(format!("{FEEDBACK_MODAL_PREFIX}{prefix}"), "Send feedback")
+        .components(vec![
+            CreateActionRow::InputText(
+                CreateInputText::new(InputTextStyle::Short, "Title", TITLE_INPUT_ID)
+                    .placeholder("A short summary")
+                    .max_length(90)
+                    .required(true),
+            ),
+            CreateActionRow::InputText(
+                CreateInputText::new(InputTextStyle::Paragraph, "Details", DETAILS_INPUT_ID)
+                    .placeholder("What happened, what you expected, steps to reproduce...")
+                    .required(true),
+            ),
+        ]);
+
+    command
+        .create_response(http, CreateInteractionResponse::Modal(modal))
+        .await?;
+    Ok(())
+}
+
+/// Create the forum post for a submitted form, and file the issue if configured
+pub async fn handle_feedback_modal(
+    http: &Http,
+    modal: &ModalInteraction,
+    config: &Arc<Config>,
+    store: &Store,
+    audit: &AuditLog,
+) -> Result<()> {
+    let Some(prefix) = modal.data.custom_id.strip_prefix(FEEDBACK_MODAL_PREFIX) else {
+        return Ok(());
+    };
+    // Checked again, as the form can outlive a role change
+    let Some(project) = feedback_project(http, config, modal.channel_id).await? else {
+        return Ok(());
+    };
+    if !crate::priority::has_allowed_role(project, modal.member.as_ref()) {
+        modal
+            .create_response(
+                http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content("You don't have permission to send feedback")
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    modal
+        .create_response(
+            http,
+            CreateInteractionResponse::Defer(
+                CreateInteractionResponseMessage::new().ephemeral(true),
+            ),
+        )
+        .await?;
+
+    let title = input_value(modal, TITLE_INPUT_ID);
+    let details = input_value(modal, DETAILS_INPUT_ID);
+
+    let forum_id = ChannelId::new(project.discord_forum_id.parse()?);
+    let forum = crate::discord_cache::channel(http, forum_id)
+        .await?
+        .guild()
+        .ok_or_else(|| anyhow::anyhow!("Forum {forum_id} is not a guild channel"))?;
+
+    let mut post = CreateForumPost::new(
+        format!("{prefix} {title}"),
+        CreateMessage::new()
+            .content(format!(
+                "{details}\n\n— submitted by <@{}> via `/feedback`",
+                modal.user.id
+            ))
+            .allowed_mentions(CreateAllowedMentions::new().users(crate::mute::unmuted(
+                store,
+                Some(modal.user.id),
+                None,
+            ))),
+    );
+    if let Some(config) = project.prefixes().iter().find(|p| p.prefix == prefix) {
+        let tag_name = config.tag_name();
+        if let Some(tag) = forum
+            .available_tags
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(&tag_name))
+        {
+            post = post.add_applied_tag(tag.id);
+        }
+    }
+
+    let thread = forum_id.create_forum_post(http, post).await?;
+    info!(
+        "Created forum post {} for /feedback from {}",
+        thread.id, modal.user.name
+    );
+
+    let mut reply = format!("✅ Thanks! Your post is at <#{}>", thread.id);
+
+    if project.feedback_auto_issue() {
+        // The post is the bot's, so the form's author is the one to hide
+        let hidden = project.anonymous_reports().then_some(modal.user.id);
+        if hidden.is_some() {
+            store.set_anonymous_reporter(thread.id.get(), modal.user.id.get(), &modal.user.name)?;
+        }
+        let author = crate::anonymous::display_name(modal.user.id, &modal.user.name, hidden);
+        // The form's answers are the post's opening message
+        let entry = crate::transcript::Entry {
+            id: thread.id.get(),
+            author,
+            timestamp: chrono::Utc::now().timestamp(),
+            content: &details,
+        };
+        let content = crate::transcript::render(project.transcript_format(), &[entry]);
+        let content =
+            crate::redact::for_github(http, store, project, thread.id.get(), &content).await;
+        let github = crate::github_app::create_project_client(config, project).await?;
+        let result = crate::github::create_or_update_issue(
+            &github,
+            project,
+            &thread,
+            content,
+            hidden.is_none().then(|| modal.user.name.clone()),
+        )
+        .await?;
+
+        store.upsert_thread_issue(thread.id.get(), &project_key(project), result.issue.number)?;
+        audit
+            .record(
+                AuditEntry::new(project, AuditAction::IssueCreated)
+                    .thread(thread.id.get())
+                    .issue(result.issue.number)
+                    .actor(modal.user.name.clone())
+                    .details("filed via /feedback"),
+            )
+            .await;
+
+        crate::filing::post_issue_link(http, project, thread.id, &result).await?;
+        crate::notify::announce_new_issue(http, project, &thread, &result.issue, &modal.user.name)
+            .await;
+
+        reply.push_str(&format!(" and was filed as issue #{}", result.issue.number));
+    }
+
+    modal
+        .edit_response(http, EditInteractionResponse::new().content(reply))
+        .await?;
+    Ok(())
+}
+
+/// The project feedback sent from a channel goes to: the one the channel
+/// files into, else the one whose forum shares the channel's category, else
+/// the guild's only project
+async fn feedback_project<'a>(
+    http: &Http,
+    config: &'a Config,
+    channel_id: ChannelId,
+) -> Result<Option<&'a Project>> {
+    let Channel::Guild(channel) = crate::discord_cache::channel(http, channel_id).await? else {
+        return Ok(None);
+    };
+    if let Some(project) = crate::message_link::source_project(config, &channel) {
+        return Ok(Some(project));
+    }
+
+    let guild_id = channel.guild_id.get().to_string();
+    let candidates: Vec<&Project> = config
+        .projects
+        .iter()
+        .filter(|p| p.discord_guild_id == guild_id && !p.archived())
+        .collect();
+    if let Some(category) = channel.parent_id {
+        let mut siblings = Vec::new();
+        for project in &candidates {
+            let Ok(forum_id) = project.discord_forum_id.parse::<u64>() else {
+                continue;
+            };
+            let forum = crate::discord_cache::channel(http, ChannelId::new(forum_id)).await;
+            if let Ok(Channel::Guild(forum)) = forum {
+                if forum.parent_id == Some(category) {
+                    siblings.push(*project);
+                }
+            }
+        }
+        if let [project] = siblings[..] {
+            return Ok(Some(project));
+        }
+    }
+    Ok(match candidates[..] {
+        [project] => Some(project),
+        _ => None,
+    })
+}
+
+fn input_value(modal: &ModalInteraction, custom_id: &str) -> String {
+    modal
+        .data
+        .components
+        .iter()
+        .flat_map(|row| row.components.iter())
+        .find_map(|component| match component {
+            ActionRowComponent::InputText(input) if input.custom_id == custom_id => {
+                input.value.clone()
+            }
+            _ => None,
+        })
+        .unwrap_or_default()
+}
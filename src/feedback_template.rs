@@ -0,0 +1,174 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serenity::builder::{
+    CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter, CreateMessage,
+};
+
+use crate::config::PrefixConfig;
+
+/// Instructions posted by `post-feedback`, loaded from a TOML or JSON file or
+/// a project's `feedback_template`.
+///
+/// `{prefixes}` in the description or a field value expands to the project's
+/// thread prefixes.
+#[derive(Debug, Deserialize, Clone)]
+pub struct FeedbackTemplate {
+    /// Name of the forum post when posting to a forum channel
+    pub post_title: Option<String>,
+    /// Plain text shown above the embed
+    pub content: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    /// Embed color as hex, e.g. "#00ADB5"
+    pub color: Option<String>,
+    #[serde(default)]
+    pub fields: Vec<TemplateField>,
+    pub footer: Option<String>,
+    /// Shown as link buttons below the embed
+    #[serde(default)]
+    pub links: Vec<TemplateLink>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TemplateField {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub inline: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TemplateLink {
+    pub label: String,
+    pub url: String,
+}
+
+impl Default for FeedbackTemplate {
+    fn default() -> Self {
+        Self {
+            post_title: Some("📢 How to report bugs and give feedback".to_string()),
+            content: None,
+            title: "📋 How to Provide Feedback".to_string(),
+            description: Some(
+                "Create a new post in this forum with one of these prefixes in the title:\n\
+                 {prefixes}\n\n\
+                 Be specific: include steps to reproduce and screenshots if applicable."
+                    .to_string(),
+            ),
+            url: None,
+            color: None,
+            fields: Vec::new(),
+            footer: Some("Thank you for your feedback!".to_string()),
+            links: Vec::new(),
+        }
+    }
+}
+
+impl FeedbackTemplate {
+    /// Parse a template file, as JSON for `.json` files and TOML otherwise
+    pub fn load(path: &str) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read template {path}"))?;
+        let template = if path.ends_with(".json") {
+            serde_json::from_str(&text).with_context(|| format!("Invalid JSON template {path}"))?
+        } else {
+            toml::from_str(&text).with_context(|| format!("Invalid TOML template {path}"))?
+        };
+        Ok(template)
+    }
+
+    pub fn color(&self) -> Result<u32> {
+        match &self.color {
+            Some(hex) => u32::from_str_radix(hex.trim_start_matches('#'), 16)
+                .with_context(|| format!("Invalid template color '{hex}'")),
+            None => Ok(crate::constants::COLOR_FEEDBACK_TEMPLATE),
+        }
+    }
+
+    pub fn embed(&self, prefixes: &[PrefixConfig]) -> Result<CreateEmbed> {
+        let expand = |text: &str| text.replace("{prefixes}", &prefix_list(prefixes));
+
+        let mut embed = CreateEmbed::new().title(&self.title).color(self.color()?);
+        if let Some(description) = &self.description {
+            embed = embed.description(expand(description));
+        }
+        if let Some(url) = &self.url {
+            embed = embed.url(url);
+        }
+        for field in &self.fields {
+            embed = embed.field(&field.name, expand(&field.value), field.inline);
+        }
+        if let Some(footer) = &self.footer {
+            embed = embed.footer(CreateEmbedFooter::new(footer));
+        }
+        Ok(embed)
+    }
+
+    pub fn message(&self, prefixes: &[PrefixConfig]) -> Result<CreateMessage> {
+        let mut message = CreateMessage::new().embed(self.embed(prefixes)?);
+        if let Some(content) = &self.content {
+            message = message.content(content);
+        }
+        if !self.links.is_empty() {
+            // Discord allows five buttons per row
+            let rows = self
+                .links
+                .chunks(5)
+                .map(|links| {
+                    CreateActionRow::Buttons(
+                        links
+                            .iter()
+                            .map(|l| CreateButton::new_link(&l.url).label(&l.label))
+                            .collect(),
+                    )
+                })
+                .collect();
+            message = message.components(rows);
+        }
+        Ok(message)
+    }
+
+    pub fn post_title(&self) -> &str {
+        self.post_title.as_deref().unwrap_or(&self.title)
+    }
+}
+
+/// One bullet per prefix, e.g. "• `[BUG] Your title here`"
+fn prefix_list(prefixes: &[PrefixConfig]) -> String {
+    prefixes
+        .iter()
+        .map(|p| format!("• `{} Your title here`", p.prefix))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_template() {
+        let template: FeedbackTemplate = toml::from_str(
+            r##"
+            title = "Welcome"
+            color = "#00ADB5"
+            [[fields]]
+            name = "How"
+            value = "{prefixes}"
+            [[links]]
+            label = "Docs"
+            url = "https://example.com"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(template.color().unwrap(), 0x00ADB5);
+        assert_eq!(template.post_title(), "Welcome");
+        assert_eq!(template.fields.len(), 1);
+        assert!(!template.fields[0].inline);
+
+        let json: FeedbackTemplate =
+            serde_json::from_str(r#"{"title": "Welcome", "color": "zz"}"#).unwrap();
+        assert!(json.color().is_err());
+    }
+}
@@ -0,0 +1,703 @@
+use anyhow::{Context, Result};
+use serenity::builder::GetMessages;
+use serenity::builder::{CreateEmbed, CreateMessage};
+use serenity::http::Http;
+use serenity::model::channel::{GuildChannel, Message};
+use serenity::model::id::{ChannelId, UserId};
+use tracing::{info, warn};
+
+use crate::audit::{project_key, AuditAction, AuditEntry, AuditLog};
+use crate::config::{Config, IssueTarget, PrefixConfig, Project};
+use crate::github::{IssueResult, ThreadContent};
+use crate::progress::Progress;
+use crate::selection::MessageSelection;
+use crate::store::Store;
+
+/// What filing a thread produced on GitHub
+pub enum Filed {
+    Issue(Box<IssueResult>),
+    /// A discussion or Projects v2 draft item, which the syncer does not track
+    Elsewhere {
+        target: IssueTarget,
+        url: String,
+    },
+}
+
+impl Filed {
+    /// Short confirmation for whoever asked for the thread to be filed
+    pub fn summary(&self) -> String {
+        match self {
+            Filed::Issue(result) if result.was_updated => {
+                format!("Updated issue #{}", result.issue.number)
+            }
+            Filed::Issue(result) => format!("Created issue #{}", result.issue.number),
+            Filed::Elsewhere {
+                target: IssueTarget::Discussions,
+                url,
+            } => format!("Started discussion {url}"),
+            Filed::Elsewhere { url, .. } => format!("Added to project {url}"),
+        }
+    }
+}
+
+/// File a thread wherever its prefix is configured to go
+#[allow(clippy::too_many_arguments)]
+pub async fn file_thread(
+    discord: &Http,
+    config: &Config,
+    project: &Project,
+    thread: &GuildChannel,
+    store: &Store,
+    audit: &AuditLog,
+    actor: &str,
+    selection: &MessageSelection,
+    progress: Progress<'_>,
+) -> Result<Filed> {
+    let project = &project.routed(&thread.name);
+    match project.prefix_for_title(&thread.name) {
+        Some(prefix) if prefix.target != IssueTarget::Issues => {
+            file_elsewhere(
+                discord, config, project, &prefix, thread, store, audit, actor, selection, progress,
+            )
+            .await
+        }
+        _ => file_issue(
+            discord, config, project, thread, store, audit, actor, selection, progress,
+        )
+        .await
+        .map(|result| Filed::Issue(Box::new(result))),
+    }
+}
+
+/// Create or update the issue for a thread and record the result everywhere:
+/// the mapping store, the audit log, the thread itself and, for new issues,
+/// the maintainer notification channels
+#[allow(clippy::too_many_arguments)]
+pub async fn file_issue(
+    discord: &Http,
+    config: &Config,
+    project: &Project,
+    thread: &GuildChannel,
+    store: &Store,
+    audit: &AuditLog,
+    actor: &str,
+    selection: &MessageSelection,
+    progress: Progress<'_>,
+) -> Result<IssueResult> {
+    let hidden =
+        crate::anonymous::hidden_reporter(discord, store, project, thread.id.get()).await?;
+    let ThreadContent {
+        content,
+        fetched,
+        message_ids,
+    } = thread_issue_content(
+        discord, config, project, thread, store, selection, hidden, progress,
+    )
+    .await?;
+    let thread_owner_name = thread_owner_name(discord, thread).await;
+    let author = issue_author(discord, store, project, thread).await?;
+
+    progress
+        .update(&format!("Fetched {fetched} messages... creating issue..."))
+        .await;
+    // Create a fresh GitHub client
+    let github = crate::github_app::create_project_client(config, project).await?;
+
+    info!(
+        "Creating/updating GitHub issue for thread '{}' in project '{}'",
+        thread.name,
+        project.name.as_deref().unwrap_or(&project.github_repo)
+    );
+    let result =
+        crate::github::create_or_update_issue(&github, project, thread, content, author).await?;
+
+    store.upsert_thread_issue(thread.id.get(), &project_key(project), result.issue.number)?;
+    // So edits to these messages can refresh the transcript as it was filed
+    let selection = selection.pinned(chrono::Utc::now().timestamp());
+    store.set_issue_transcript(thread.id.get(), &selection.encode(), &message_ids)?;
+
+    let audit_action = if result.was_updated {
+        AuditAction::IssueUpdated
+    } else {
+        AuditAction::IssueCreated
+    };
+    audit
+        .record(
+            AuditEntry::new(project, audit_action)
+                .thread(thread.id.get())
+                .issue(result.issue.number)
+                .actor(actor),
+        )
+        .await;
+    info!(
+        "{} GitHub issue #{} for project '{}'",
+        if result.was_updated {
+            "Updated"
+        } else {
+            "Created"
+        },
+        result.issue.number,
+        project.name.as_deref().unwrap_or(&project.github_repo)
+    );
+
+    progress
+        .update(&format!(
+            "Filed issue #{}... linking the thread...",
+            result.issue.number
+        ))
+        .await;
+    post_issue_link(discord, project, thread.id, &result).await?;
+
+    if !result.was_updated {
+        if let Err(e) = crate::project_fields::add_issue(
+            discord,
+            &github,
+            project,
+            thread,
+            &result.issue.node_id,
+        )
+        .await
+        {
+            warn!(
+                "Failed to add issue #{} to the project board: {}",
+                result.issue.number, e
+            );
+        }
+        crate::notify::announce_new_issue(
+            discord,
+            project,
+            thread,
+            &result.issue,
+            &thread_owner_name,
+        )
+        .await;
+        if let Err(e) =
+            crate::leaderboard::record_report(discord, store, project, thread, result.issue.number)
+                .await
+        {
+            warn!(
+                "Failed to credit the reporter of issue #{}: {}",
+                result.issue.number, e
+            );
+        }
+    }
+
+    Ok(result)
+}
+
+/// The redacted transcript an issue is filed with, plus its translation, OCR
+/// text and footers. `hidden` is the reporter shown as anonymous, if any.
+#[allow(clippy::too_many_arguments)]
+pub async fn thread_issue_content(
+    discord: &Http,
+    config: &Config,
+    project: &Project,
+    thread: &GuildChannel,
+    store: &Store,
+    selection: &MessageSelection,
+    hidden: Option<UserId>,
+    progress: Progress<'_>,
+) -> Result<ThreadContent> {
+    progress.update("Reading the thread...").await;
+    let ThreadContent {
+        mut content,
+        fetched,
+        message_ids,
+    } = crate::github::extract_thread_content(
+        discord,
+        thread,
+        selection,
+        project.transcript_marker_emoji(),
+        hidden,
+        project.transcript_format(),
+    )
+    .await?;
+
+    crate::redact::report_title(discord, store, project, thread.id.get(), &thread.name).await;
+    if let Some(text) = translation(
+        discord,
+        config,
+        project,
+        store,
+        thread.id.get(),
+        &content,
+        fetched,
+        progress,
+    )
+    .await
+    {
+        content = format!("{content}\n\n{text}");
+    }
+
+    // Make error screenshots in bug reports searchable
+    if let Some(ocr) = config.ocr.as_ref().filter(|_| project.ocr_screenshots()) {
+        if thread.name.contains(crate::constants::PREFIX_BUG) {
+            progress
+                .update(&format!(
+                    "Fetched {fetched} messages... reading screenshots..."
+                ))
+                .await;
+            if let Some(text) = crate::ocr::screenshot_text(ocr, discord, thread).await {
+                content = format!("{content}\n\n{text}");
+            }
+        }
+    }
+
+    if let Some(attachment_store) = &config.attachment_store {
+        if let Some(list) =
+            crate::attachments::section(attachment_store, project, discord, thread, store).await
+        {
+            content = format!("{content}\n\n{list}");
+        }
+    }
+
+    if let Some(profile) = project
+        .reporter_profile
+        .as_ref()
+        .filter(|_| hidden.is_none())
+    {
+        if let Some(footer) = crate::reporter::thread_owner_footer(discord, profile, thread).await {
+            content = format!("{content}\n\n{footer}");
+        }
+    }
+
+    if let Some(teams) = crate::team_mentions::thread_teams_line(discord, project, thread).await {
+        content = format!("{content}\n\n{teams}");
+    }
+
+    let content =
+        crate::redact::for_github(discord, store, project, thread.id.get(), &content).await;
+    Ok(ThreadContent {
+        content,
+        fetched,
+        message_ids,
+    })
+}
+
+/// Maintainers triage reports from other communities in English. The
+/// provider only sees what GitHub will. Sections are cached per thread, so
+/// previews and refreshes of an unchanged transcript don't translate again.
+#[allow(clippy::too_many_arguments)]
+async fn translation(
+    discord: &Http,
+    config: &Config,
+    project: &Project,
+    store: &Store,
+    thread_id: u64,
+    content: &str,
+    fetched: usize,
+    progress: Progress<'_>,
+) -> Option<String> {
+    let service = config
+        .translation
+        .as_ref()
+        .filter(|_| project.translate_issues())?;
+    let language = crate::translation::detect_language(content)?;
+    let hash = crate::translation::source_hash(content);
+    match store.cached_translation(thread_id, &hash) {
+        Ok(Some(section)) => return Some(section),
+        Ok(None) => {}
+        Err(e) => warn!("Failed to read the cached translation: {}", e),
+    }
+    progress
+        .update(&format!(
+            "Fetched {fetched} messages... translating from {}...",
+            crate::translation::language_name(language)
+        ))
+        .await;
+    let content = crate::redact::for_github(discord, store, project, thread_id, content).await;
+    let section = crate::translation::section(service, &content, language).await?;
+    if let Err(e) = store.set_cached_translation(thread_id, &hash, &section) {
+        warn!("Failed to cache the translation: {}", e);
+    }
+    Some(section)
+}
+
+/// A message and the replies to it, filed from outside the forum
+pub struct MessageReport<'a> {
+    pub channel: &'a GuildChannel,
+    /// The message first, then its replies
+    pub messages: &'a [Message],
+    pub title: &'a str,
+    pub url: &'a str,
+    /// Whether the message's author asked to be left out of the issue
+    pub anonymous: bool,
+}
+
+/// Create the issue for a message report with what filing a thread adds:
+/// translation, attachments, the reporter footer and anonymity. The message
+/// ID stands in for the thread ID in the store.
+pub async fn file_message(
+    discord: &Http,
+    config: &Config,
+    project: &Project,
+    report: &MessageReport<'_>,
+    store: &Store,
+    progress: Progress<'_>,
+) -> Result<octocrab::models::issues::Issue> {
+    let message = report
+        .messages
+        .first()
+        .context("A message report needs its message")?;
+    let hidden = (report.anonymous || project.anonymous_reports()).then_some(message.author.id);
+
+    let entries: Vec<_> = report
+        .messages
+        .iter()
+        .map(|m| crate::transcript::Entry {
+            id: m.id.get(),
+            author: crate::anonymous::display_name(m.author.id, &m.author.name, hidden),
+            timestamp: m.timestamp.unix_timestamp(),
+            content: &m.content,
+        })
+        .collect();
+    let mut content = crate::transcript::render(project.transcript_format(), &entries);
+    let fetched = report.messages.len();
+
+    crate::redact::report_title(discord, store, project, message.id.get(), report.title).await;
+    if let Some(text) = translation(
+        discord,
+        config,
+        project,
+        store,
+        message.id.get(),
+        &content,
+        fetched,
+        progress,
+    )
+    .await
+    {
+        content = format!("{content}\n\n{text}");
+    }
+
+    if let Some(attachment_store) = &config.attachment_store {
+        let newest_first: Vec<Message> = report.messages.iter().rev().cloned().collect();
+        if let Some(list) = crate::attachments::messages_section(
+            attachment_store,
+            project,
+            store,
+            report.channel.id,
+            &newest_first,
+        )
+        .await
+        {
+            content = format!("{content}\n\n{list}");
+        }
+    }
+
+    if let Some(profile) = project
+        .reporter_profile
+        .as_ref()
+        .filter(|_| hidden.is_none())
+    {
+        if let Some(footer) = crate::reporter::member_footer(
+            discord,
+            profile,
+            report.channel.guild_id,
+            message.author.id,
+        )
+        .await
+        {
+            content = format!("{content}\n\n{footer}");
+        }
+    }
+
+    let content =
+        crate::redact::for_github(discord, store, project, message.id.get(), &content).await;
+    let author = match hidden {
+        Some(reporter) => {
+            store.set_anonymous_reporter(
+                message.id.get(),
+                reporter.get(),
+                crate::constants::AUDIT_ACTOR_BOT,
+            )?;
+            None
+        }
+        None => Some(message.author.name.as_str()),
+    };
+    let body = crate::github::issue_body(&project.render_issue_body(&content, report.url, author));
+
+    progress
+        .update(&format!("Fetched {fetched} messages... creating issue..."))
+        .await;
+    let github = crate::github_app::create_project_client(config, project).await?;
+    let issues = github.issues(&project.github_owner, &project.github_repo);
+    let labels = project.labels_for_title(report.title);
+    let mut create = issues
+        .create(project.github_title(report.title, crate::constants::GITHUB_ISSUE_TITLE_LIMIT))
+        .body(body);
+    if !labels.is_empty() {
+        create = create.labels(labels);
+    }
+    let issue = create.send().await?;
+    info!(
+        "Created issue #{} in {} from message {}",
+        issue.number,
+        project_key(project),
+        report.url
+    );
+    Ok(issue)
+}
+
+/// Start a discussion or add a project draft item for a thread. These are
+/// created once; later runs point back at the first one.
+#[allow(clippy::too_many_arguments)]
+async fn file_elsewhere(
+    discord: &Http,
+    config: &Config,
+    project: &Project,
+    prefix: &PrefixConfig,
+    thread: &GuildChannel,
+    store: &Store,
+    audit: &AuditLog,
+    actor: &str,
+    selection: &MessageSelection,
+    progress: Progress<'_>,
+) -> Result<Filed> {
+    progress.update("Reading the thread...").await;
+    let messages = thread
+        .messages(
+            discord,
+            GetMessages::new().limit(crate::constants::DISCORD_MESSAGE_FETCH_LIMIT),
+        )
+        .await?;
+    if let Some(url) = filed_elsewhere_url(&messages) {
+        return Ok(Filed::Elsewhere {
+            target: prefix.target,
+            url,
+        });
+    }
+
+    let hidden =
+        crate::anonymous::hidden_reporter(discord, store, project, thread.id.get()).await?;
+    let ThreadContent {
+        content, fetched, ..
+    } = crate::github::extract_thread_content(
+        discord,
+        thread,
+        selection,
+        project.transcript_marker_emoji(),
+        hidden,
+        project.transcript_format(),
+    )
+    .await?;
+    progress
+        .update(&format!(
+            "Fetched {fetched} messages... filing on GitHub..."
+        ))
+        .await;
+    let content =
+        crate::redact::for_github(discord, store, project, thread.id.get(), &content).await;
+    crate::redact::report_title(discord, store, project, thread.id.get(), &thread.name).await;
+    let discord_url = format!(
+        "https://discord.com/channels/{}/{}",
+        thread.guild_id, thread.id
+    );
+    let body = project.render_issue_body(
+        &content,
+        &discord_url,
+        issue_author(discord, store, project, thread)
+            .await?
+            .as_deref(),
+    );
+
+    let title = project.github_title(&thread.name, crate::constants::GITHUB_ISSUE_TITLE_LIMIT);
+
+    let github = crate::github_app::create_project_client(config, project).await?;
+    let (url, embed_title) = match prefix.target {
+        IssueTarget::Discussions => {
+            let category = prefix
+                .discussion_category
+                .as_deref()
+                .unwrap_or(crate::constants::DEFAULT_DISCUSSION_CATEGORY);
+            let url = crate::graphql::create_discussion(&github, project, category, &title, &body)
+                .await?;
+            (url, crate::constants::MSG_DISCUSSION_CREATED)
+        }
+        IssueTarget::ProjectItem => {
+            let number = project.github_project_number.with_context(|| {
+                format!(
+                    "Prefix {} targets a project item but github_project_number is not set",
+                    prefix.prefix
+                )
+            })?;
+            let board = crate::graphql::project_v2(&github, project, number).await?;
+            let item_id =
+                crate::graphql::add_project_draft(&github, project, &board.id, &title, &body)
+                    .await?;
+            if let Err(e) = crate::project_fields::fill_fields(
+                discord, &github, project, thread, &board.id, &item_id,
+            )
+            .await
+            {
+                warn!(
+                    "Failed to fill project fields for thread {}: {}",
+                    thread.id, e
+                );
+            }
+            (board.url, crate::constants::MSG_PROJECT_ITEM_CREATED)
+        }
+        IssueTarget::Issues => unreachable!("issues are filed by file_issue"),
+    };
+
+    audit
+        .record(
+            AuditEntry::new(project, AuditAction::IssueCreated)
+                .thread(thread.id.get())
+                .actor(actor)
+                .details(format!("{embed_title}: {url}")),
+        )
+        .await;
+    info!("{} for thread {}: {}", embed_title, thread.id, url);
+
+    if !project.staging() {
+        thread
+            .id
+            .send_message(
+                discord,
+                CreateMessage::new().embed(
+                    CreateEmbed::new()
+                        .title(embed_title)
+                        .description(format!("**Link**: {url}"))
+                        .color(crate::constants::COLOR_SUCCESS),
+                ),
+            )
+            .await?;
+    }
+
+    Ok(Filed::Elsewhere {
+        target: prefix.target,
+        url,
+    })
+}
+
+/// Link from an earlier discussion or project item embed in the thread
+pub fn filed_elsewhere_url(messages: &[Message]) -> Option<String> {
+    messages
+        .iter()
+        .filter(|m| m.author.bot)
+        .flat_map(|m| &m.embeds)
+        .find(|e| {
+            matches!(
+                e.title.as_deref(),
+                Some(crate::constants::MSG_DISCUSSION_CREATED)
+                    | Some(crate::constants::MSG_PROJECT_ITEM_CREATED)
+            )
+        })
+        .and_then(|e| e.description.as_deref())
+        .map(|d| d.trim_start_matches("**Link**: ").to_string())
+}
+
+/// The "Created by" name for the thread's issue; None for anonymous reports,
+/// whose reporter is recorded for maintainers instead
+pub async fn issue_author(
+    discord: &Http,
+    store: &Store,
+    project: &Project,
+    thread: &GuildChannel,
+) -> Result<Option<String>> {
+    match crate::anonymous::hidden_reporter(discord, store, project, thread.id.get()).await? {
+        Some(reporter) => {
+            store.set_anonymous_reporter(
+                thread.id.get(),
+                reporter.get(),
+                crate::constants::AUDIT_ACTOR_BOT,
+            )?;
+            Ok(None)
+        }
+        None => Ok(Some(thread_owner_name(discord, thread).await)),
+    }
+}
+
+pub async fn thread_owner_name(discord: &Http, thread: &GuildChannel) -> String {
+    match thread.owner_id {
+        Some(owner_id) => match crate::discord_cache::user(discord, owner_id).await {
+            Ok(user) => user.name,
+            Err(_) => "Unknown".to_string(),
+        },
+        None => "Unknown".to_string(),
+    }
+}
+
+/// Post the GitHub link embed the syncer later looks for in the thread.
+/// Staging projects don't post, as they never close the thread anyway.
+pub async fn post_issue_link(
+    discord: &Http,
+    project: &Project,
+    thread_id: ChannelId,
+    result: &IssueResult,
+) -> Result<()> {
+    if project.staging() {
+        return Ok(());
+    }
+    let embed_title = if result.was_updated {
+        crate::constants::MSG_ISSUE_UPDATED
+    } else {
+        crate::constants::MSG_ISSUE_CREATED
+    };
+
+    thread_id
+        .send_message(
+            discord,
+            CreateMessage::new().embed(
+                CreateEmbed::new()
+                    .title(embed_title)
+                    .description(format!("**Issue**: {}", result.issue.html_url))
+                    .field("Number", format!("#{}", result.issue.number), true)
+                    .field("Status", "Open", true)
+                    .color(crate::constants::COLOR_SUCCESS),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Point a thread at an existing issue instead of filing a new one
+pub async fn link_duplicate_thread(
+    github: &octocrab::Octocrab,
+    discord: &Http,
+    project: &Project,
+    thread: &GuildChannel,
+    issue_number: u64,
+) -> Result<octocrab::models::issues::Issue> {
+    let issues = github.issues(&project.github_owner, &project.github_repo);
+    let issue = issues.get(issue_number).await?;
+
+    let discord_url = format!(
+        "https://discord.com/channels/{}/{}",
+        thread.guild_id, thread.id
+    );
+    issues
+        .create_comment(
+            issue_number,
+            format!(
+                "🔁 Also reported on Discord: [{}]({discord_url})",
+                thread.name
+            ),
+        )
+        .await?;
+    if project.staging() {
+        return Ok(issue);
+    }
+
+    thread
+        .id
+        .send_message(
+            discord,
+            CreateMessage::new().embed(
+                CreateEmbed::new()
+                    .title(crate::constants::MSG_ISSUE_LINKED)
+                    .description(format!("**Issue**: {}", issue.html_url))
+                    .field("Number", format!("#{}", issue.number), true)
+                    .color(crate::constants::COLOR_SUCCESS),
+            ),
+        )
+        .await?;
+
+    info!(
+        "Linked thread {} to existing issue #{}",
+        thread.id, issue_number
+    );
+    Ok(issue)
+}
@@ -0,0 +1,153 @@
+use serde::Serialize;
+use serenity::gateway::{ConnectionStage, ShardManager};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Gateway connection state, shared between the event handler, the watchdog
+/// and the health endpoint
+#[derive(Default)]
+pub struct GatewayHealth {
+    state: Mutex<GatewayState>,
+    restart_requested: AtomicBool,
+}
+
+#[derive(Default)]
+struct GatewayState {
+    stage: Option<ConnectionStage>,
+    ever_connected: bool,
+    /// When the connection was last lost; None while connected
+    disconnected_at: Option<Instant>,
+    connected_since: Option<chrono::DateTime<chrono::Utc>>,
+    reconnects: u64,
+    resumes: u64,
+    restarts: u64,
+}
+
+/// Snapshot served on `GET /health`
+#[derive(Debug, Serialize)]
+pub struct GatewayStatus {
+    pub connected: bool,
+    pub stage: String,
+    pub connected_since: Option<String>,
+    pub disconnected_seconds: Option<u64>,
+    pub reconnects: u64,
+    pub resumes: u64,
+    pub restarts: u64,
+}
+
+impl GatewayHealth {
+    pub fn record_stage(&self, old: ConnectionStage, new: ConnectionStage) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.stage = Some(new);
+
+        if new == ConnectionStage::Connected {
+            if let Some(lost) = state.disconnected_at.take() {
+                if state.ever_connected {
+                    state.reconnects += 1;
+                    info!(
+                        "Discord gateway reconnected after {}s",
+                        lost.elapsed().as_secs()
+                    );
+                }
+            }
+            state.ever_connected = true;
+            state.connected_since = Some(chrono::Utc::now());
+        } else if state.disconnected_at.is_none() {
+            if state.ever_connected {
+                warn!("Discord gateway connection lost ({} -> {})", old, new);
+            }
+            state.disconnected_at = Some(Instant::now());
+            state.connected_since = None;
+        }
+    }
+
+    pub fn record_resume(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.resumes += 1;
+        info!("Discord gateway session resumed ({} so far)", state.resumes);
+    }
+
+    /// How long the gateway has been down, if it is
+    pub fn disconnected_for(&self) -> Option<Duration> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.disconnected_at.map(|at| at.elapsed())
+    }
+
+    pub fn status(&self) -> GatewayStatus {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        GatewayStatus {
+            connected: state.stage == Some(ConnectionStage::Connected),
+            stage: state
+                .stage
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| "starting".to_string()),
+            connected_since: state.connected_since.map(|t| t.to_rfc3339()),
+            disconnected_seconds: state.disconnected_at.map(|at| at.elapsed().as_secs()),
+            reconnects: state.reconnects,
+            resumes: state.resumes,
+            restarts: state.restarts,
+        }
+    }
+
+    /// Whether the watchdog shut the client down and it should be rebuilt;
+    /// clears the request
+    pub fn take_restart_request(&self) -> bool {
+        self.restart_requested.swap(false, Ordering::SeqCst)
+    }
+}
+
+/// Shut the client down when the gateway stays disconnected longer than
+/// `threshold`, so the caller can start a fresh one
+pub async fn watchdog(health: Arc<GatewayHealth>, shards: Arc<ShardManager>, threshold: Duration) {
+    let mut interval = tokio::time::interval(crate::constants::GATEWAY_WATCHDOG_CHECK);
+    loop {
+        interval.tick().await;
+        let Some(down) = health.disconnected_for() else {
+            continue;
+        };
+        if down < threshold {
+            continue;
+        }
+
+        warn!(
+            "Discord gateway down for {}s; restarting the client",
+            down.as_secs()
+        );
+        {
+            let mut state = health.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.restarts += 1;
+            state.disconnected_at = Some(Instant::now());
+        }
+        health.restart_requested.store(true, Ordering::SeqCst);
+        shards.shutdown_all().await;
+        return;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_tracking() {
+        let health = GatewayHealth::default();
+        assert!(!health.status().connected);
+
+        health.record_stage(ConnectionStage::Identifying, ConnectionStage::Connected);
+        assert!(health.status().connected);
+        assert!(health.disconnected_for().is_none());
+        assert_eq!(health.status().reconnects, 0);
+
+        health.record_stage(ConnectionStage::Connected, ConnectionStage::Resuming);
+        health.record_stage(ConnectionStage::Resuming, ConnectionStage::Connecting);
+        assert!(health.disconnected_for().is_some());
+
+        health.record_stage(ConnectionStage::Connecting, ConnectionStage::Connected);
+        let status = health.status();
+        assert!(status.connected);
+        assert_eq!(status.reconnects, 1);
+        assert!(status.disconnected_seconds.is_none());
+    }
+}
@@ -1,8 +1,10 @@
 use crate::config::Project;
+use crate::selection::MessageSelection;
 use anyhow::Result;
 use octocrab::models::issues::Issue;
 use serenity::builder::GetMessages;
 use serenity::model::channel::GuildChannel;
+use serenity::model::id::UserId;
 
 pub struct IssueResult {
     pub issue: Issue,
@@ -14,63 +16,42 @@ pub async fn create_or_update_issue(
     project: &Project,
     thread: &GuildChannel,
     content: String,
-    thread_owner_name: String,
+    thread_owner_name: Option<String>,
 ) -> Result<IssueResult> {
     let discord_url = format!(
         "https://discord.com/channels/{}/{}",
         thread.guild_id, thread.id
     );
 
-    // Extract tag from thread title if present
+    // Map configured thread prefixes to GitHub labels
     let original_title = thread.name.clone();
-    let mut labels = Vec::new();
-
-    // Check for thread prefixes and map to GitHub labels
-    if original_title.contains(crate::constants::PREFIX_BUG) {
-        labels.push(crate::constants::LABEL_BUG.to_string());
-    }
-    if original_title.contains(crate::constants::PREFIX_FEATURE) {
-        labels.push(crate::constants::LABEL_FEATURE.to_string());
-    }
-    if original_title.contains(crate::constants::PREFIX_QUESTION) {
-        labels.push(crate::constants::LABEL_QUESTION.to_string());
-    }
-    if original_title.contains(crate::constants::PREFIX_FEEDBACK) {
-        labels.push(crate::constants::LABEL_FEEDBACK.to_string());
-    }
+    let labels = project.labels_for_title(&original_title);
 
     // Add thread ID to title to make it unique
-    let title = format!("{} [{}]", original_title, thread.id);
+    let title = issue_title(project, &original_title, thread.id.get());
 
-    let body = format!(
-        "{content}\n\n---\n**Discord Thread**: {discord_url}\n**Created by**: {thread_owner_name}"
+    let transcript = format!(
+        "{}\n\n{}",
+        project.render_issue_body(&content, &discord_url, thread_owner_name.as_deref()),
+        crate::sync::thread_marker(thread.id.get())
     );
 
     // Search for existing issue with this thread ID
-    let search_query = format!(
-        "[{}] in:title repo:{}/{} is:issue",
-        thread.id, project.github_owner, project.github_repo
-    );
-
-    let existing_issues = github
-        .search()
-        .issues_and_pull_requests(&search_query)
-        .send()
-        .await
-        .map_err(|e| {
-            tracing::error!(
-                "GitHub API search failed for query '{}': {:?}",
-                search_query,
-                e
-            );
-            e
-        })?;
+    let existing_issue = find_issue_for_thread(github, project, thread.id.get()).await?;
 
     // Check if we found an existing issue
-    if let Some(existing_issue) = existing_issues.items.first() {
+    if let Some(existing_issue) = existing_issue {
         // Update the existing issue
         let issue_number = existing_issue.number;
 
+        let Some(body) = merge_issue_body(existing_issue.body.as_deref(), &transcript) else {
+            tracing::debug!("Issue #{} is already up to date", issue_number);
+            return Ok(IssueResult {
+                issue: existing_issue,
+                was_updated: true,
+            });
+        };
+
         let updated_issue = github
             .issues(&project.github_owner, &project.github_repo)
             .update(issue_number)
@@ -92,7 +73,7 @@ pub async fn create_or_update_issue(
             github
                 .issues(&project.github_owner, &project.github_repo)
                 .create(title)
-                .body(body)
+                .body(issue_body(&transcript))
                 .send()
                 .await
                 .map_err(|e| {
@@ -108,7 +89,7 @@ pub async fn create_or_update_issue(
             github
                 .issues(&project.github_owner, &project.github_repo)
                 .create(title)
-                .body(body)
+                .body(issue_body(&transcript))
                 .labels(labels)
                 .send()
                 .await
@@ -130,24 +111,181 @@ pub async fn create_or_update_issue(
     }
 }
 
+/// Issue body made up only of the bot-managed transcript block
+pub fn issue_body(transcript: &str) -> String {
+    format!(
+        "{}\n{}\n{}",
+        crate::constants::TRANSCRIPT_BEGIN_MARKER,
+        transcript,
+        crate::constants::TRANSCRIPT_END_MARKER
+    )
+}
+
+/// Replace the transcript block in an existing body, keeping triage notes,
+/// checklists and anything else maintainers added around it. Returns None when
+/// the body would not materially change.
+pub fn merge_issue_body(current: Option<&str>, transcript: &str) -> Option<String> {
+    use crate::constants::{
+        LEGACY_TRANSCRIPT_MARKER, TRANSCRIPT_BEGIN_MARKER, TRANSCRIPT_END_MARKER,
+    };
+
+    let current = current.unwrap_or_default();
+    let block = issue_body(transcript);
+
+    let begin = current.find(TRANSCRIPT_BEGIN_MARKER);
+    let end = begin.and_then(|b| {
+        current[b..]
+            .find(TRANSCRIPT_END_MARKER)
+            .map(|e| b + e + TRANSCRIPT_END_MARKER.len())
+    });
+
+    let merged = match (begin, end) {
+        (Some(begin), Some(end)) => format!("{}{}{}", &current[..begin], block, &current[end..]),
+        // An end marker removed by hand: treat the rest of the body as the transcript
+        (Some(begin), None) => format!("{}{}", &current[..begin], block),
+        _ => match current.find(LEGACY_TRANSCRIPT_MARKER) {
+            Some(pos) => format!("{}{}", &current[..pos], block),
+            // Bodies from before any marker existed are entirely bot-written
+            None => block,
+        },
+    };
+
+    (normalize_body(&merged) != normalize_body(current)).then_some(merged)
+}
+
+/// Ignore line-ending and trailing whitespace differences GitHub introduces
+fn normalize_body(body: &str) -> String {
+    body.lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// GitHub issue title for a thread, with the thread ID as a readable suffix
+/// until the project's links have moved to the database. The name is tidied
+/// and cut to leave room for the suffix within GitHub's title limit.
+pub fn issue_title(project: &Project, thread_name: &str, thread_id: u64) -> String {
+    let suffix = match project.mapping_mode() {
+        crate::config::MappingMode::Store => String::new(),
+        _ => format!(" [{thread_id}]"),
+    };
+    let name = project.github_title(
+        thread_name,
+        crate::constants::GITHUB_ISSUE_TITLE_LIMIT - suffix.len(),
+    );
+    format!("{name}{suffix}")
+}
+
+/// Find the issue (open or closed) that was created for a Discord thread
+pub async fn find_issue_for_thread(
+    github: &octocrab::Octocrab,
+    project: &Project,
+    thread_id: u64,
+) -> Result<Option<Issue>> {
+    // Matches both the body marker and the title suffix of older issues
+    let search_query = format!(
+        "{} in:title,body repo:{}/{} is:issue",
+        thread_id, project.github_owner, project.github_repo
+    );
+
+    let existing_issues = github
+        .search()
+        .issues_and_pull_requests(&search_query)
+        .send()
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                "GitHub API search failed for query '{}': {:?}",
+                search_query,
+                e
+            );
+            e
+        })?;
+
+    Ok(existing_issues
+        .items
+        .into_iter()
+        .find(|issue| crate::sync::issue_thread_id(issue) == Some(thread_id)))
+}
+
+/// A thread's transcript for an issue body
+#[derive(Debug)]
+pub struct ThreadContent {
+    pub content: String,
+    /// Messages fetched to build it
+    pub fetched: usize,
+    /// The messages it shows
+    pub message_ids: Vec<u64>,
+}
+
+/// The issue body transcript for a thread. `hidden` is a reporter shown
+/// without their name.
 pub async fn extract_thread_content(
-    ctx: &serenity::prelude::Context,
+    discord: &serenity::http::Http,
     thread: &GuildChannel,
-) -> Result<String> {
-    let messages = thread
+    selection: &MessageSelection,
+    marker: &str,
+    hidden: Option<UserId>,
+    format: crate::config::TranscriptFormat,
+) -> Result<ThreadContent> {
+    let mut messages = thread
         .messages(
-            &ctx,
-            GetMessages::new().limit(crate::constants::GITHUB_THREAD_CONTENT_LIMIT),
+            discord,
+            GetMessages::new().limit(crate::constants::THREAD_SELECTION_FETCH_LIMIT),
         )
         .await?;
 
-    let content = messages
+    // Long threads need the opening post fetched on its own
+    let starter = crate::discord_cache::starter_id(thread.id);
+    if !messages.iter().any(|m| m.id == starter) {
+        if let Ok(opening) = crate::discord_cache::starter_message(discord, thread.id).await {
+            messages.push(opening);
+        }
+    }
+
+    let selected = crate::selection::select_messages(&messages, selection, marker, starter);
+    let entries: Vec<_> = selected
         .iter()
-        .rev()
-        .take(5)
-        .map(|m| format!("**@{}**: {}", m.author.name, m.content))
-        .collect::<Vec<_>>()
-        .join("\n\n");
+        .map(|m| crate::transcript::Entry {
+            id: m.id.get(),
+            author: crate::anonymous::display_name(m.author.id, &m.author.name, hidden),
+            timestamp: m.timestamp.unix_timestamp(),
+            content: &m.content,
+        })
+        .collect();
+
+    Ok(ThreadContent {
+        content: crate::transcript::render(format, &entries),
+        fetched: messages.len(),
+        message_ids: selected.iter().map(|m| m.id.get()).collect(),
+    })
+}
 
-    Ok(content)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_issue_body() {
+        let current = format!(
+            "Triage: P1\r\n\n{}\n\n- [ ] repro on macOS",
+            issue_body("old transcript")
+        );
+        let merged = merge_issue_body(Some(&current), "new transcript").unwrap();
+        assert!(merged.starts_with("Triage: P1"));
+        assert!(merged.contains("new transcript"));
+        assert!(!merged.contains("old transcript"));
+        assert!(merged.ends_with("- [ ] repro on macOS"));
+
+        // Whitespace-only differences are not worth an edit
+        let unchanged = format!("{}  \r\n", issue_body("same"));
+        assert_eq!(merge_issue_body(Some(&unchanged), "same"), None);
+
+        // Bodies written with the older single marker keep the text above it
+        let legacy = "Notes\n<!-- cardibot:transcript -->\nold";
+        let merged = merge_issue_body(Some(legacy), "new").unwrap();
+        assert_eq!(merged, format!("Notes\n{}", issue_body("new")));
+    }
 }
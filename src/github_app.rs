@@ -3,7 +3,17 @@ use chrono::{Duration, Utc};
 use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
 use octocrab::Octocrab;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
+use std::sync::{LazyLock, Mutex};
+
+use crate::config::{Config, Project};
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Expiry of the last installation token minted per installation ID. Tokens
+/// are not reused; this only tells `/cardibot debug` what GitHub handed out.
+static TOKEN_EXPIRIES: LazyLock<Mutex<HashMap<u64, String>>> = LazyLock::new(Default::default);
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Claims {
@@ -15,16 +25,23 @@ struct Claims {
 #[derive(Debug, Deserialize)]
 struct InstallationToken {
     token: String,
+    expires_at: Option<String>,
 }
 
 pub struct GitHubApp {
     app_id: String,
     private_key: String,
     installation_id: u64,
+    api_url: Option<String>,
 }
 
 impl GitHubApp {
-    pub fn new(app_id: String, private_key_path: String, installation_id: u64) -> Result<Self> {
+    pub fn new(
+        app_id: String,
+        private_key_path: String,
+        installation_id: u64,
+        api_url: Option<String>,
+    ) -> Result<Self> {
         let private_key = fs::read_to_string(&private_key_path)
             .with_context(|| format!("Failed to read private key from {private_key_path}"))?;
 
@@ -32,6 +49,7 @@ impl GitHubApp {
             app_id,
             private_key,
             installation_id,
+            api_url,
         })
     }
 
@@ -55,7 +73,8 @@ impl GitHubApp {
         let client = reqwest::Client::new();
         let response = client
             .post(format!(
-                "https://api.github.com/app/installations/{}/access_tokens",
+                "{}/app/installations/{}/access_tokens",
+                self.api_url.as_deref().unwrap_or(GITHUB_API_URL),
                 self.installation_id
             ))
             .header("Authorization", format!("Bearer {jwt}"))
@@ -71,22 +90,181 @@ impl GitHubApp {
         }
 
         let token_response: InstallationToken = response.json().await?;
+        if let Some(expires_at) = token_response.expires_at {
+            TOKEN_EXPIRIES
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(self.installation_id, expires_at);
+        }
         Ok(token_response.token)
     }
 
     pub async fn create_octocrab_instance(&self) -> Result<Octocrab> {
         let token = self.get_installation_token().await?;
+        token_client(token, self.api_url.as_deref())
+    }
+
+    /// What the installation was granted, as GitHub reports it to the App
+    pub async fn installation_grant(&self) -> Result<InstallationGrant> {
+        let jwt = self.generate_jwt()?;
+        let response = reqwest::Client::new()
+            .get(format!(
+                "{}/app/installations/{}",
+                self.api_url.as_deref().unwrap_or(GITHUB_API_URL),
+                self.installation_id
+            ))
+            .header("Authorization", format!("Bearer {jwt}"))
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", "CardiBot")
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await?;
+            anyhow::bail!("Failed to read installation: {} - {}", status, text);
+        }
+        let installation: Installation = response.json().await?;
+
+        let repositories = if installation.repository_selection == "all" {
+            None
+        } else {
+            Some(self.installation_repositories().await?)
+        };
+        Ok(InstallationGrant {
+            installation_id: self.installation_id,
+            permissions: installation.permissions,
+            repositories,
+        })
+    }
 
-        Octocrab::builder()
-            .personal_token(token)
-            .build()
-            .context("Failed to create Octocrab instance")
+    /// Full names of the repositories a "selected repositories" installation can reach
+    async fn installation_repositories(&self) -> Result<Vec<String>> {
+        let github = self.create_octocrab_instance().await?;
+        let mut names = Vec::new();
+        let mut page: u32 = 1;
+        loop {
+            let response: InstallationRepositories = github
+                .get(
+                    "/installation/repositories",
+                    Some(&[("per_page", "100"), ("page", &page.to_string())]),
+                )
+                .await?;
+            let count = response.repositories.len();
+            names.extend(response.repositories.into_iter().map(|r| r.full_name));
+            if count < 100 {
+                return Ok(names);
+            }
+            page += 1;
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Installation {
+    #[serde(default)]
+    permissions: HashMap<String, String>,
+    repository_selection: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstallationRepositories {
+    repositories: Vec<InstallationRepository>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstallationRepository {
+    full_name: String,
+}
+
+/// Permissions and repositories of an App installation
+#[derive(Debug, Clone)]
+pub struct InstallationGrant {
+    pub installation_id: u64,
+    /// Permission name to "read", "write" or "admin"
+    pub permissions: HashMap<String, String>,
+    /// None when the installation covers every repository of its account
+    pub repositories: Option<Vec<String>>,
+}
+
+impl InstallationGrant {
+    /// Whether the installation has at least `level` ("read" or "write") on
+    /// `permission`
+    pub fn allows(&self, permission: &str, level: &str) -> bool {
+        let rank = |level: &str| match level {
+            "read" => 1,
+            "write" => 2,
+            "admin" => 3,
+            _ => 0,
+        };
+        self.permissions
+            .get(permission)
+            .is_some_and(|granted| rank(granted) >= rank(level))
+    }
+
+    pub fn covers(&self, full_name: &str) -> bool {
+        match &self.repositories {
+            None => true,
+            Some(names) => names.iter().any(|n| n.eq_ignore_ascii_case(full_name)),
+        }
+    }
+}
+
+/// (installation ID, expiry) of the installation tokens minted by this process
+pub fn token_expiries() -> Vec<(u64, String)> {
+    let mut expiries: Vec<_> = TOKEN_EXPIRIES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(|(id, at)| (*id, at.clone()))
+        .collect();
+    expiries.sort();
+    expiries
+}
+
+/// Octocrab authenticated with a token, against GHES when `api_url` is set
+fn token_client(token: String, api_url: Option<&str>) -> Result<Octocrab> {
+    let mut builder = Octocrab::builder().personal_token(token);
+    if let Some(api_url) = api_url {
+        builder = builder
+            .base_uri(api_url)
+            .with_context(|| format!("Invalid GitHub API URL {api_url}"))?;
+    }
+    builder
+        .build()
+        .context("Failed to create Octocrab instance")
+}
+
+/// `GITHUB_API_URL` for the default credentials, if set
+fn default_api_url() -> Option<String> {
+    std::env::var("GITHUB_API_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+        .map(|url| url.trim_end_matches('/').to_string())
+}
+
+/// Which credentials the environment provides for projects without a
+/// `github_account`: "app", "token" or "none"
+pub fn default_auth_mode() -> &'static str {
+    let set = |name: &str| std::env::var(name).is_ok_and(|v| !v.is_empty());
+    if set("GITHUB_APP_ID")
+        && set("GITHUB_APP_INSTALLATION_ID")
+        && set("GITHUB_APP_PRIVATE_KEY_PATH")
+    {
+        "app"
+    } else if set("GITHUB_TOKEN") {
+        "token"
+    } else {
+        "none"
     }
 }
 
 // Helper function to create either GitHub App or PAT authenticated client
 pub async fn create_github_client() -> Result<Octocrab> {
-    // Check if GitHub App credentials are available
+    create_env_client(default_api_url()).await
+}
+
+/// The GitHub App from the environment credentials, if they are for one
+fn env_app(api_url: Option<String>) -> Result<Option<GitHubApp>> {
     if let (Ok(app_id), Ok(installation_id)) = (
         std::env::var("GITHUB_APP_ID"),
         std::env::var("GITHUB_APP_INSTALLATION_ID"),
@@ -95,24 +273,89 @@ pub async fn create_github_client() -> Result<Octocrab> {
             let installation_id = installation_id
                 .parse()
                 .context("Invalid GITHUB_APP_INSTALLATION_ID")?;
-
-            tracing::info!(
-                "Using GitHub App authentication (App ID: {}, Installation: {})",
-                app_id,
-                installation_id
-            );
-            let app = GitHubApp::new(app_id, private_key_path, installation_id)?;
-            return app.create_octocrab_instance().await;
+            return GitHubApp::new(app_id, private_key_path, installation_id, api_url).map(Some);
         }
     }
+    Ok(None)
+}
+
+/// Client from the environment credentials against the given API base
+async fn create_env_client(api_url: Option<String>) -> Result<Octocrab> {
+    // Check if GitHub App credentials are available
+    if let Some(app) = env_app(api_url.clone())? {
+        tracing::info!(
+            "Using GitHub App authentication (App ID: {}, Installation: {})",
+            app.app_id,
+            app.installation_id
+        );
+        return app.create_octocrab_instance().await;
+    }
 
     // Fall back to PAT authentication
     let github_token = std::env::var("GITHUB_TOKEN")
         .context("GITHUB_TOKEN not set and GitHub App credentials not configured")?;
 
     tracing::info!("Using GitHub PAT authentication");
-    Octocrab::builder()
-        .personal_token(github_token)
-        .build()
-        .context("Failed to create Octocrab instance with PAT")
+    token_client(github_token, api_url.as_deref())
+}
+
+/// The GitHub App the project authenticates as; None for token credentials
+pub fn project_app(config: &Config, project: &Project) -> Result<Option<GitHubApp>> {
+    let Some(name) = &project.github_account else {
+        return env_app(project.github_api_url());
+    };
+    let account = config
+        .github_account(name)
+        .with_context(|| format!("Unknown GitHub account '{name}'"))?;
+    match (
+        &account.app_id,
+        account.installation_id,
+        &account.private_key_path,
+    ) {
+        (Some(app_id), Some(installation_id), Some(private_key_path)) => GitHubApp::new(
+            app_id.clone(),
+            private_key_path.clone(),
+            installation_id,
+            project.github_api_url(),
+        )
+        .map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Client for the project's configured GitHub account, or the default credentials
+pub async fn create_project_client(config: &Config, project: &Project) -> Result<Octocrab> {
+    let Some(name) = &project.github_account else {
+        if project.github_api_url.is_some() {
+            return create_env_client(project.github_api_url()).await;
+        }
+        return create_github_client().await;
+    };
+    let account = config
+        .github_account(name)
+        .with_context(|| format!("Unknown GitHub account '{name}'"))?;
+
+    if let (Some(app_id), Some(installation_id), Some(private_key_path)) = (
+        &account.app_id,
+        account.installation_id,
+        &account.private_key_path,
+    ) {
+        tracing::debug!("Using GitHub App credentials of account '{}'", name);
+        let app = GitHubApp::new(
+            app_id.clone(),
+            private_key_path.clone(),
+            installation_id,
+            project.github_api_url(),
+        )?;
+        return app.create_octocrab_instance().await;
+    }
+
+    let token_env = account.token_env.as_deref().with_context(|| {
+        format!("GitHub account '{name}' needs token_env or app_id, installation_id and private_key_path")
+    })?;
+    let token = std::env::var(token_env)
+        .with_context(|| format!("{token_env} not set for GitHub account '{name}'"))?;
+
+    tracing::debug!("Using PAT of GitHub account '{}'", name);
+    token_client(token, project.github_api_url().as_deref())
 }
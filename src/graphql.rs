@@ -0,0 +1,467 @@
+use anyhow::{Context, Result};
+use octocrab::Octocrab;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::config::Project;
+use crate::summary::IssueSummary;
+
+/// GraphQL endpoint for a REST API base: GHES serves it at `/api/graphql`
+/// rather than under `/api/v3`
+pub fn graphql_url(api_url: Option<&str>) -> String {
+    match api_url {
+        Some(api) => match api.strip_suffix("/api/v3") {
+            Some(host) => format!("{host}/api/graphql"),
+            None => format!("{api}/graphql"),
+        },
+        None => "/graphql".to_string(),
+    }
+}
+
+async fn post(
+    github: &Octocrab,
+    project: &Project,
+    query: &str,
+    variables: Value,
+) -> Result<Value> {
+    Ok(github
+        .post(
+            graphql_url(project.github_api_url().as_deref()),
+            Some(&json!({ "query": query, "variables": variables })),
+        )
+        .await?)
+}
+
+/// Fail on the errors a GraphQL response reports, except those `ignore` accepts
+fn check_errors(response: &Value, ignore: impl Fn(&Value) -> bool) -> Result<()> {
+    let messages: Vec<&str> = response
+        .get("errors")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|e| !ignore(e))
+        .map(|e| {
+            e.get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown error")
+        })
+        .collect();
+    if !messages.is_empty() {
+        anyhow::bail!("GitHub GraphQL error: {}", messages.join("; "));
+    }
+    Ok(())
+}
+
+/// Run a GraphQL query and return its `data`, failing on any reported error
+pub async fn query(
+    github: &Octocrab,
+    project: &Project,
+    query: &str,
+    variables: Value,
+) -> Result<Value> {
+    let response = post(github, project, query, variables).await?;
+    check_errors(&response, |_| false)?;
+
+    response
+        .get("data")
+        .cloned()
+        .context("GitHub GraphQL response had no data")
+}
+
+/// Query for the state of several issues at once, each under an `i<number>`
+/// alias
+pub fn issue_batch_query(numbers: &[u64]) -> String {
+    let issues: String = numbers
+        .iter()
+        .map(|n| format!("i{n}: issue(number: {n}) {{ ...SummaryFields }}\n"))
+        .collect();
+    format!(
+        "query($owner: String!, $name: String!) {{
+            repository(owner: $owner, name: $name) {{
+                {issues}
+            }}
+        }}
+        fragment SummaryFields on Issue {{
+            number url state stateReason updatedAt
+            milestone {{ state }}
+            labels(first: 100) {{ nodes {{ name }} }}
+            assignees(first: 100) {{ nodes {{ login }} }}
+        }}"
+    )
+}
+
+/// An issue from the response to `issue_batch_query`
+pub fn parse_issue_summary(issue: &Value) -> Option<IssueSummary> {
+    let nodes = |field: &str, key: &str| -> Vec<String> {
+        issue[field]["nodes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|node| node[key].as_str().map(str::to_string))
+            .collect()
+    };
+    let updated_at = chrono::DateTime::parse_from_rfc3339(issue["updatedAt"].as_str()?).ok()?;
+
+    Some(IssueSummary {
+        number: issue["number"].as_u64()?,
+        url: issue["url"].as_str()?.to_string(),
+        open: issue["state"].as_str()? == "OPEN",
+        completed: matches!(issue["stateReason"].as_str(), None | Some("COMPLETED")),
+        milestone_closed: issue["milestone"]["state"].as_str().map(|s| s == "CLOSED"),
+        labels: nodes("labels", "name"),
+        assignees: nodes("assignees", "login"),
+        updated_at: updated_at.timestamp(),
+    })
+}
+
+/// Look up several issues of a repository in one request. Issues that no
+/// longer exist, such as ones transferred away, are missing from the result.
+pub async fn issue_summaries(
+    github: &Octocrab,
+    project: &Project,
+    numbers: &[u64],
+) -> Result<HashMap<u64, IssueSummary>> {
+    let response = post(
+        github,
+        project,
+        &issue_batch_query(numbers),
+        json!({ "owner": project.github_owner, "name": project.github_repo }),
+    )
+    .await?;
+    // A missing issue only nulls its own alias
+    check_errors(&response, |e| e["type"].as_str() == Some("NOT_FOUND"))?;
+
+    let repository = &response["data"]["repository"];
+    if repository.is_null() {
+        anyhow::bail!(
+            "Repository {}/{} not found",
+            project.github_owner,
+            project.github_repo
+        );
+    }
+    Ok(numbers
+        .iter()
+        .filter_map(|n| parse_issue_summary(&repository[format!("i{n}")]))
+        .map(|issue| (issue.number, issue))
+        .collect())
+}
+
+/// Start a discussion in the named category, returning its URL
+pub async fn create_discussion(
+    github: &Octocrab,
+    project: &Project,
+    category: &str,
+    title: &str,
+    body: &str,
+) -> Result<String> {
+    let data = query(
+        github,
+        project,
+        "query($owner: String!, $name: String!) {
+            repository(owner: $owner, name: $name) {
+                id
+                discussionCategories(first: 25) { nodes { id name } }
+            }
+        }",
+        json!({ "owner": project.github_owner, "name": project.github_repo }),
+    )
+    .await?;
+
+    let repository_id = data["repository"]["id"]
+        .as_str()
+        .context("Repository not found")?;
+    let category_id = data["repository"]["discussionCategories"]["nodes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|c| {
+            c["name"]
+                .as_str()
+                .is_some_and(|n| n.eq_ignore_ascii_case(category))
+        })
+        .and_then(|c| c["id"].as_str())
+        .with_context(|| format!("Discussion category '{category}' not found"))?;
+
+    let data = query(
+        github,
+        project,
+        "mutation($repositoryId: ID!, $categoryId: ID!, $title: String!, $body: String!) {
+            createDiscussion(input: {
+                repositoryId: $repositoryId, categoryId: $categoryId, title: $title, body: $body
+            }) { discussion { url } }
+        }",
+        json!({
+            "repositoryId": repository_id,
+            "categoryId": category_id,
+            "title": title,
+            "body": body,
+        }),
+    )
+    .await?;
+
+    data["createDiscussion"]["discussion"]["url"]
+        .as_str()
+        .map(str::to_string)
+        .context("GitHub did not return the new discussion")
+}
+
+/// Transfer an issue to `target`'s repository, returning its new number
+pub async fn transfer_issue(github: &Octocrab, target: &Project, issue_id: &str) -> Result<u64> {
+    let data = query(
+        github,
+        target,
+        "query($owner: String!, $name: String!) {
+            repository(owner: $owner, name: $name) { id }
+        }",
+        json!({ "owner": target.github_owner, "name": target.github_repo }),
+    )
+    .await?;
+    let repository_id = data["repository"]["id"]
+        .as_str()
+        .context("Repository not found")?;
+
+    let data = query(
+        github,
+        target,
+        "mutation($issueId: ID!, $repositoryId: ID!) {
+            transferIssue(input: { issueId: $issueId, repositoryId: $repositoryId }) {
+                issue { number }
+            }
+        }",
+        json!({ "issueId": issue_id, "repositoryId": repository_id }),
+    )
+    .await?;
+
+    data["transferIssue"]["issue"]["number"]
+        .as_u64()
+        .context("GitHub did not return the transferred issue")
+}
+
+/// A Projects v2 board
+pub struct ProjectV2 {
+    pub id: String,
+    pub url: String,
+}
+
+/// Look up a Projects v2 board owned by a user or organization
+pub async fn project_v2(github: &Octocrab, project: &Project, number: u64) -> Result<ProjectV2> {
+    let owner = &project.github_owner;
+    let data = query(
+        github,
+        project,
+        "query($owner: String!, $number: Int!) {
+            repositoryOwner(login: $owner) {
+                ... on Organization { projectV2(number: $number) { id url } }
+                ... on User { projectV2(number: $number) { id url } }
+            }
+        }",
+        json!({ "owner": owner, "number": number }),
+    )
+    .await?;
+
+    let board = &data["repositoryOwner"]["projectV2"];
+    match (board["id"].as_str(), board["url"].as_str()) {
+        (Some(id), Some(url)) => Ok(ProjectV2 {
+            id: id.to_string(),
+            url: url.to_string(),
+        }),
+        _ => anyhow::bail!("Project #{number} not found for {owner}"),
+    }
+}
+
+/// Add a draft item to a Projects v2 board, returning the item's node ID
+pub async fn add_project_draft(
+    github: &Octocrab,
+    project: &Project,
+    project_id: &str,
+    title: &str,
+    body: &str,
+) -> Result<String> {
+    let data = query(
+        github,
+        project,
+        "mutation($projectId: ID!, $title: String!, $body: String!) {
+            addProjectV2DraftIssue(input: { projectId: $projectId, title: $title, body: $body }) {
+                projectItem { id }
+            }
+        }",
+        json!({ "projectId": project_id, "title": title, "body": body }),
+    )
+    .await?;
+
+    data["addProjectV2DraftIssue"]["projectItem"]["id"]
+        .as_str()
+        .map(str::to_string)
+        .context("GitHub did not return the new project item")
+}
+
+/// A field of a Projects v2 board
+#[derive(Debug)]
+pub struct ProjectV2Field {
+    pub id: String,
+    pub name: String,
+    /// GraphQL `ProjectV2FieldType`, e.g. "SINGLE_SELECT" or "NUMBER"
+    pub data_type: String,
+    /// (ID, name) of each option of a single select field
+    pub options: Vec<(String, String)>,
+}
+
+/// The fields of a Projects v2 board
+pub async fn project_v2_fields(
+    github: &Octocrab,
+    project: &Project,
+    project_id: &str,
+) -> Result<Vec<ProjectV2Field>> {
+    let data = query(
+        github,
+        project,
+        "query($projectId: ID!) {
+            node(id: $projectId) {
+                ... on ProjectV2 {
+                    fields(first: 50) {
+                        nodes {
+                            ... on ProjectV2FieldCommon { id name dataType }
+                            ... on ProjectV2SingleSelectField { options { id name } }
+                        }
+                    }
+                }
+            }
+        }",
+        json!({ "projectId": project_id }),
+    )
+    .await?;
+
+    Ok(data["node"]["fields"]["nodes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|field| {
+            Some(ProjectV2Field {
+                id: field["id"].as_str()?.to_string(),
+                name: field["name"].as_str()?.to_string(),
+                data_type: field["dataType"].as_str()?.to_string(),
+                options: field["options"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|o| {
+                        Some((
+                            o["id"].as_str()?.to_string(),
+                            o["name"].as_str()?.to_string(),
+                        ))
+                    })
+                    .collect(),
+            })
+        })
+        .collect())
+}
+
+/// Add an issue to a Projects v2 board, returning the item's node ID. An
+/// issue already on the board keeps its item.
+pub async fn add_project_item(
+    github: &Octocrab,
+    project: &Project,
+    project_id: &str,
+    content_id: &str,
+) -> Result<String> {
+    let data = query(
+        github,
+        project,
+        "mutation($projectId: ID!, $contentId: ID!) {
+            addProjectV2ItemById(input: { projectId: $projectId, contentId: $contentId }) {
+                item { id }
+            }
+        }",
+        json!({ "projectId": project_id, "contentId": content_id }),
+    )
+    .await?;
+
+    data["addProjectV2ItemById"]["item"]["id"]
+        .as_str()
+        .map(str::to_string)
+        .context("GitHub did not return the project item")
+}
+
+/// Set one field of a Projects v2 item; `value` is a `ProjectV2FieldValue`
+/// such as `{ "text": "..." }`
+pub async fn set_project_field(
+    github: &Octocrab,
+    project: &Project,
+    project_id: &str,
+    item_id: &str,
+    field_id: &str,
+    value: Value,
+) -> Result<()> {
+    query(
+        github,
+        project,
+        "mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $value: ProjectV2FieldValue!) {
+            updateProjectV2ItemFieldValue(input: {
+                projectId: $projectId, itemId: $itemId, fieldId: $fieldId, value: $value
+            }) { projectV2Item { id } }
+        }",
+        json!({
+            "projectId": project_id,
+            "itemId": item_id,
+            "fieldId": field_id,
+            "value": value,
+        }),
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graphql_url() {
+        assert_eq!(graphql_url(None), "/graphql");
+        assert_eq!(
+            graphql_url(Some("https://github.example.com/api/v3")),
+            "https://github.example.com/api/graphql"
+        );
+        assert_eq!(
+            graphql_url(Some("https://api.github.com")),
+            "https://api.github.com/graphql"
+        );
+    }
+
+    #[test]
+    fn test_parse_issue_summary() {
+        let issue = parse_issue_summary(&json!({
+            "number": 12,
+            "url": "https://github.com/acme/app/issues/12",
+            "state": "CLOSED",
+            "stateReason": "NOT_PLANNED",
+            "updatedAt": "2025-01-01T00:00:00Z",
+            "milestone": { "state": "OPEN" },
+            "labels": { "nodes": [{ "name": "bug" }] },
+            "assignees": { "nodes": [{ "login": "octocat" }] }
+        }))
+        .unwrap();
+        assert_eq!(issue.number, 12);
+        assert!(!issue.open);
+        assert!(!issue.completed);
+        assert_eq!(issue.milestone_closed, Some(false));
+        assert_eq!(issue.labels, vec!["bug"]);
+        assert_eq!(issue.assignees, vec!["octocat"]);
+        assert_eq!(issue.updated_at, 1_735_689_600);
+
+        let open = parse_issue_summary(&json!({
+            "number": 13,
+            "url": "https://github.com/acme/app/issues/13",
+            "state": "OPEN",
+            "stateReason": null,
+            "updatedAt": "2025-01-01T00:00:00Z",
+            "milestone": null,
+            "labels": { "nodes": [] },
+            "assignees": { "nodes": [] }
+        }))
+        .unwrap();
+        assert!(open.open);
+        assert_eq!(open.milestone_closed, None);
+        assert!(parse_issue_summary(&Value::Null).is_none());
+    }
+}
@@ -0,0 +1,147 @@
+use http_body_util::Full;
+use hyper::body::Bytes;
+use hyper::{HeaderMap, Response, StatusCode};
+use serenity::http::Http;
+use serenity::model::application::Interaction;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use crate::bot::Bot;
+use crate::server::reply;
+
+/// Discord's HTTP interactions endpoint. Requests are verified with the
+/// application's public key and handed to the same handlers the gateway uses,
+/// which answer through the interaction callback API.
+pub struct InteractionsEndpoint {
+    bot: Arc<Bot>,
+    http: Arc<Http>,
+    public_key: Vec<u8>,
+}
+
+impl InteractionsEndpoint {
+    pub fn new(bot: Arc<Bot>, http: Arc<Http>, public_key: Vec<u8>) -> Self {
+        Self {
+            bot,
+            http,
+            public_key,
+        }
+    }
+
+    /// Endpoint for `DISCORD_PUBLIC_KEY` (hex, from the Developer Portal), if set
+    pub fn from_env(bot: Arc<Bot>, http: Arc<Http>) -> anyhow::Result<Option<Self>> {
+        let Ok(key) = std::env::var("DISCORD_PUBLIC_KEY") else {
+            return Ok(None);
+        };
+        let public_key = crate::webhook::decode_hex(key.trim())
+            .filter(|k| k.len() == 32)
+            .ok_or_else(|| anyhow::anyhow!("DISCORD_PUBLIC_KEY is not a 32-byte hex key"))?;
+        Ok(Some(Self::new(bot, http, public_key)))
+    }
+}
+
+/// Check `X-Signature-Ed25519` over the timestamp followed by the body
+pub fn verify_signature(
+    public_key: &[u8],
+    timestamp: Option<&str>,
+    body: &[u8],
+    signature: Option<&str>,
+) -> bool {
+    let (Some(timestamp), Some(signature)) =
+        (timestamp, signature.and_then(crate::webhook::decode_hex))
+    else {
+        return false;
+    };
+
+    // Stale timestamps are replays of captured requests
+    let fresh = timestamp.parse::<i64>().is_ok_and(|ts| {
+        (chrono::Utc::now().timestamp() - ts).abs() <= crate::constants::INTERACTION_MAX_AGE_SECS
+    });
+
+    let mut message = timestamp.as_bytes().to_vec();
+    message.extend_from_slice(body);
+    fresh
+        && ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, public_key)
+            .verify(&message, &signature)
+            .is_ok()
+}
+
+pub async fn respond(
+    endpoint: &InteractionsEndpoint,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Response<Full<Bytes>> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    if !verify_signature(
+        &endpoint.public_key,
+        header("X-Signature-Timestamp"),
+        body,
+        header("X-Signature-Ed25519"),
+    ) {
+        return reply(StatusCode::UNAUTHORIZED, "invalid request signature");
+    }
+
+    let interaction: Interaction = match serde_json::from_slice(body) {
+        Ok(interaction) => interaction,
+        Err(e) => {
+            warn!("Unreadable interaction payload: {}", e);
+            return reply(StatusCode::BAD_REQUEST, "bad interaction");
+        }
+    };
+
+    // Discord checks the endpoint with pings that must be echoed in the response
+    if let Interaction::Ping(_) = interaction {
+        let mut response = Response::new(Full::new(Bytes::from_static(br#"{"type":1}"#)));
+        response
+            .headers_mut()
+            .insert("Content-Type", "application/json".parse().unwrap());
+        return response;
+    }
+
+    let bot = endpoint.bot.clone();
+    let http = endpoint.http.clone();
+    tokio::spawn(async move {
+        debug!("Handling HTTP interaction {}", interaction.id());
+        bot.handle_interaction(http, interaction).await;
+    });
+    reply(StatusCode::ACCEPTED, "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::KeyPair;
+
+    #[test]
+    fn test_verify_signature() {
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = ring::signature::Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let key = ring::signature::Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        let body = br#"{"type":1}"#;
+        let mut message = timestamp.as_bytes().to_vec();
+        message.extend_from_slice(body);
+        let signature = crate::webhook::encode_hex(key.sign(&message).as_ref());
+
+        let public_key = key.public_key().as_ref();
+        assert!(verify_signature(
+            public_key,
+            Some(&timestamp),
+            body,
+            Some(&signature)
+        ));
+        assert!(!verify_signature(
+            public_key,
+            Some(&timestamp),
+            b"{}",
+            Some(&signature)
+        ));
+        assert!(!verify_signature(
+            public_key,
+            Some("0"),
+            body,
+            Some(&signature)
+        ));
+        assert!(!verify_signature(public_key, None, body, Some(&signature)));
+    }
+}
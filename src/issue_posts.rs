@@ -0,0 +1,156 @@
+use anyhow::Result;
+use octocrab::models::issues::Issue;
+use octocrab::params;
+use octocrab::Octocrab;
+use serenity::builder::{CreateAllowedMentions, CreateForumPost, CreateMessage};
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use tracing::info;
+
+use crate::audit::{project_key, AuditAction, AuditEntry, AuditLog};
+use crate::config::Project;
+use crate::embeds::Embed;
+use crate::store::Store;
+
+/// Open a forum post for each open issue carrying `label` that has no thread
+/// yet, and link the two so the usual sync takes over from there
+pub async fn post_labeled_issues(
+    github: &Octocrab,
+    discord: &Http,
+    store: &Store,
+    audit: &AuditLog,
+    project: &Project,
+    label: &str,
+) -> Result<()> {
+    let issues = github
+        .issues(&project.github_owner, &project.github_repo)
+        .list()
+        .labels(&[label.to_string()])
+        .state(params::State::Open)
+        .per_page(100)
+        .send()
+        .await?
+        .items;
+
+    let key = project_key(project);
+    for issue in issues {
+        if issue.pull_request.is_some() || crate::sync::issue_thread_id(&issue).is_some() {
+            continue;
+        }
+        // Posted before, but linking it on GitHub failed
+        if let Some(thread_id) = store.thread_for_issue(&key, issue.number)? {
+            link_issue(github, project, &issue, thread_id).await?;
+            continue;
+        }
+
+        let thread_id = create_post(discord, project, &issue).await?;
+        store.upsert_thread_issue(thread_id, &key, issue.number)?;
+        link_issue(github, project, &issue, thread_id).await?;
+
+        audit
+            .record(
+                AuditEntry::new(project, AuditAction::ThreadCreated)
+                    .thread(thread_id)
+                    .issue(issue.number)
+                    .actor(crate::constants::AUDIT_ACTOR_BOT)
+                    .details(format!("opened on GitHub with label {label}")),
+            )
+            .await;
+        info!(
+            "Created forum post {} for issue #{} in {}",
+            thread_id, issue.number, key
+        );
+    }
+    Ok(())
+}
+
+async fn create_post(discord: &Http, project: &Project, issue: &Issue) -> Result<u64> {
+    let forum_id = ChannelId::new(project.discord_forum_id.parse()?);
+    let prefix = project
+        .prefixes()
+        .into_iter()
+        .find(|p| issue.labels.iter().any(|l| l.name == p.label));
+
+    let body = issue.body.as_deref().unwrap_or_default();
+    let embed = Embed::new()
+        .title(format!("#{} {}", issue.number, issue.title))
+        .url(issue.html_url.as_str())
+        .description(crate::sanitize::sanitize_for_discord(body))
+        .color(crate::constants::COLOR_SUCCESS)
+        .build();
+    let mut post = CreateForumPost::new(
+        post_title(&issue.title, prefix.as_ref().map(|p| p.prefix.as_str())),
+        CreateMessage::new()
+            .content(crate::constants::MSG_ISSUE_FROM_GITHUB)
+            .embed(embed)
+            .allowed_mentions(CreateAllowedMentions::new()),
+    );
+
+    if let Some(prefix) = &prefix {
+        let forum = crate::discord_cache::channel(discord, forum_id)
+            .await?
+            .guild()
+            .ok_or_else(|| anyhow::anyhow!("Forum {forum_id} is not a guild channel"))?;
+        let tag_name = prefix.tag_name();
+        if let Some(tag) = forum
+            .available_tags
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(&tag_name))
+        {
+            post = post.add_applied_tag(tag.id);
+        }
+    }
+
+    let thread = forum_id.create_forum_post(discord, post).await?;
+    Ok(thread.id.get())
+}
+
+/// Add the thread marker to the issue body so the syncer treats it like any
+/// issue filed from Discord
+async fn link_issue(
+    github: &Octocrab,
+    project: &Project,
+    issue: &Issue,
+    thread_id: u64,
+) -> Result<()> {
+    let body = format!(
+        "{}\n\n{}",
+        issue.body.as_deref().unwrap_or_default().trim_end(),
+        crate::sync::thread_marker(thread_id)
+    );
+    github
+        .issues(&project.github_owner, &project.github_repo)
+        .update(issue.number)
+        .body(&body)
+        .send()
+        .await?;
+    Ok(())
+}
+
+/// Forum post name for an issue, led by the prefix its labels map to and cut
+/// to Discord's limit
+fn post_title(title: &str, prefix: Option<&str>) -> String {
+    let title = match prefix {
+        Some(prefix) if !title.starts_with(prefix) => format!("{prefix} {title}"),
+        _ => title.to_string(),
+    };
+    crate::sanitize::truncate_for_discord(&title, crate::constants::DISCORD_THREAD_NAME_LIMIT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_post_title() {
+        assert_eq!(
+            post_title("Sync stalls on large wallets", Some("[BUG]")),
+            "[BUG] Sync stalls on large wallets"
+        );
+        assert_eq!(
+            post_title("[BUG] Already there", Some("[BUG]")),
+            "[BUG] Already there"
+        );
+        assert_eq!(post_title(&"x".repeat(150), None).chars().count(), 100);
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::Result;
+use serenity::builder::{CreateMessage, EditThread};
+use serenity::http::Http;
+use serenity::model::channel::{GuildChannel, Message};
+use tracing::info;
+
+use crate::audit::{AuditAction, AuditEntry, AuditLog};
+use crate::config::Project;
+use crate::store::Store;
+
+/// Archive a prefixed thread that never got an issue once it has been quiet
+/// for the project's `archive_unlinked_after_days`, saying why first. The
+/// thread is left unlocked so anyone can bring it back by replying.
+pub async fn archive_if_abandoned(
+    discord: &Http,
+    project: &Project,
+    thread: &GuildChannel,
+    messages: &[Message],
+    store: &Store,
+    audit: &AuditLog,
+    paused: bool,
+) -> Result<()> {
+    let Some(days) = project.archive_unlinked_after_days else {
+        return Ok(());
+    };
+    // Filed after all, or waiting on a triage decision
+    if store.thread_issue(thread.id.get())?.is_some()
+        || crate::filing::filed_elsewhere_url(messages).is_some()
+        || store.triage_status(thread.id.get())?.is_some()
+    {
+        return Ok(());
+    }
+
+    let idle_secs = chrono::Utc::now().timestamp() - crate::threads::last_activity(thread);
+    if idle_secs < (days * 86_400) as i64 {
+        return Ok(());
+    }
+    if paused || project.staging() {
+        let reason = if paused { "paused" } else { "staging" };
+        info!("Would archive abandoned thread {} ({})", thread.id, reason);
+        return Ok(());
+    }
+
+    thread
+        .id
+        .send_message(
+            discord,
+            CreateMessage::new().content(crate::constants::MSG_THREAD_ABANDONED),
+        )
+        .await?;
+    thread
+        .id
+        .edit_thread(discord, EditThread::new().archived(true))
+        .await?;
+    crate::discord_cache::invalidate_channel(thread.id);
+
+    info!(
+        "Archived thread {} with no issue after {} idle days",
+        thread.id,
+        idle_secs / 86_400
+    );
+    audit
+        .record(
+            AuditEntry::new(project, AuditAction::ThreadArchived)
+                .thread(thread.id.get())
+                .details(format!("no issue filed, idle for {days} days")),
+        )
+        .await;
+    Ok(())
+}
@@ -0,0 +1,256 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serenity::all::{Channel, ChannelId, Http, MessageId};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+use tracing::{debug, error, warn};
+
+use crate::bot::Bot;
+use crate::constants::{
+    JOB_LEASE_SECS, JOB_MAX_ATTEMPTS, JOB_RETRY_BASE_SECS, JOB_TIMEOUT_SECS, JOB_WORKERS,
+};
+use crate::store::Store;
+
+/// Work a gateway event leaves for the workers. Jobs carry IDs rather than
+/// the event, so a retry acts on the thread or message as it is by then.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Job {
+    /// Normalize a new thread's prefix and welcome its reporter
+    ThreadCreate { thread_id: u64 },
+    /// Carry a thread rename, lock or archive over to its issue
+    ThreadUpdate { thread_id: u64 },
+    /// Carry an edited or deleted message over to its issue
+    MessageChange { channel_id: u64, message_id: u64 },
+}
+
+impl Job {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ThreadCreate { .. } => "thread_create",
+            Self::ThreadUpdate { .. } => "thread_update",
+            Self::MessageChange { .. } => "message_change",
+        }
+    }
+
+    async fn run(&self, bot: &Bot, http: &Http) -> Result<()> {
+        match *self {
+            Self::ThreadCreate { thread_id } => {
+                let channel =
+                    crate::discord_cache::channel(http, ChannelId::new(thread_id)).await?;
+                let Channel::Guild(thread) = channel else {
+                    return Ok(());
+                };
+                crate::thread_events::handle_thread_create(
+                    http,
+                    &bot.config,
+                    &bot.store,
+                    &bot.audit,
+                    &thread,
+                )
+                .await
+            }
+            Self::ThreadUpdate { thread_id } => {
+                crate::thread_events::handle_thread_update(
+                    http,
+                    &bot.config,
+                    &bot.store,
+                    &bot.audit,
+                    ChannelId::new(thread_id),
+                )
+                .await
+            }
+            Self::MessageChange {
+                channel_id,
+                message_id,
+            } => {
+                crate::message_edits::handle_message_change(
+                    http,
+                    &bot.config,
+                    &bot.store,
+                    &bot.audit,
+                    ChannelId::new(channel_id),
+                    MessageId::new(message_id),
+                )
+                .await
+            }
+        }
+    }
+}
+
+/// One bot's jobs. Gateway handlers only queue them, so slow GitHub calls
+/// don't hold up the events behind them; worker tasks run the jobs and retry
+/// failures with backoff. Jobs are stored before they are queued, so those a
+/// restart interrupts run again once the workers start. A worker claims a job
+/// in the store before running it, so processes sharing a database run each
+/// job once.
+///
+/// GitHub webhooks only wake the syncer, and interactions answer their user
+/// directly, so neither goes through the queue.
+pub struct JobQueue {
+    /// The bot's label, so each bot's workers only pick up its own jobs
+    name: String,
+    store: Arc<Store>,
+    sender: mpsc::UnboundedSender<i64>,
+    receiver: Mutex<mpsc::UnboundedReceiver<i64>>,
+}
+
+impl JobQueue {
+    pub fn new(name: &str, store: Arc<Store>) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Self {
+            name: name.to_string(),
+            store,
+            sender,
+            receiver: Mutex::new(receiver),
+        }
+    }
+
+    /// Store a job and hand it to a worker
+    pub fn push(&self, job: &Job) -> Result<()> {
+        let payload = serde_json::to_string(job)?;
+        let id = self.store.enqueue_job(&self.name, job.kind(), &payload)?;
+        debug!("Queued {} job {}", job.kind(), id);
+        // Only fails once the workers are gone; the stored job runs next start
+        let _ = self.sender.send(id);
+        Ok(())
+    }
+
+    /// Hand a job to a worker once `run_after` has passed
+    fn schedule(&self, id: i64, run_after: i64) {
+        let delay = run_after - chrono::Utc::now().timestamp();
+        if delay <= 0 {
+            let _ = self.sender.send(id);
+            return;
+        }
+        let sender = self.sender.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(delay as u64)).await;
+            let _ = sender.send(id);
+        });
+    }
+}
+
+/// Start a bot's workers, after queuing the jobs a previous run left
+pub fn start_workers(bot: &Bot, http: Arc<Http>) -> Result<()> {
+    let pending = bot.jobs.store.pending_jobs(&bot.jobs.name)?;
+    if !pending.is_empty() {
+        debug!("Resuming {} jobs of bot '{}'", pending.len(), bot.jobs.name);
+    }
+    for job in pending {
+        bot.jobs
+            .schedule(job.id, job.run_after.max(job.lease_until));
+    }
+    for _ in 0..JOB_WORKERS {
+        tokio::spawn(work(bot.clone(), http.clone()));
+    }
+    Ok(())
+}
+
+async fn work(bot: Bot, http: Arc<Http>) {
+    loop {
+        let Some(id) = bot.jobs.receiver.lock().await.recv().await else {
+            return;
+        };
+        if let Err(e) = process(&bot, &http, id).await {
+            error!("Failed to process job {}: {:?}", id, e);
+        }
+    }
+}
+
+/// Run a job, then drop it or schedule its retry
+async fn process(bot: &Bot, http: &Arc<Http>, id: i64) -> Result<()> {
+    let store = &bot.jobs.store;
+    let now = chrono::Utc::now().timestamp();
+    let Some(queued) = store.claim_job(id, now, now + JOB_LEASE_SECS)? else {
+        // Not due yet or run by another process; look again once it's free
+        // in case that process goes away
+        if let Some(held) = store.job(id)? {
+            bot.jobs.schedule(id, held.run_after.max(held.lease_until));
+        }
+        return Ok(());
+    };
+    let job: Job = match serde_json::from_str(&queued.payload) {
+        Ok(job) => job,
+        Err(e) => {
+            warn!("Dropping unreadable {} job {}: {}", queued.kind, id, e);
+            return store.finish_job(id);
+        }
+    };
+
+    // A panic or a hang counts as a failed run
+    let mut task = tokio::spawn({
+        let (bot, http, job) = (bot.clone(), http.clone(), job.clone());
+        async move { job.run(&bot, &http).await }
+    });
+    let result = match tokio::time::timeout(Duration::from_secs(JOB_TIMEOUT_SECS), &mut task).await
+    {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => Err(anyhow::anyhow!("job panicked: {e}")),
+        Err(_) => {
+            task.abort();
+            Err(anyhow::anyhow!("timed out after {JOB_TIMEOUT_SECS}s"))
+        }
+    };
+
+    let attempts = queued.attempts + 1;
+    match result {
+        Ok(()) => store.finish_job(id),
+        Err(e) if attempts >= JOB_MAX_ATTEMPTS => {
+            error!(
+                "Giving up on {} job {} after {} attempts: {:?}",
+                job.kind(),
+                id,
+                attempts,
+                e
+            );
+            store.finish_job(id)
+        }
+        Err(e) => {
+            let delay = retry_delay(queued.attempts);
+            warn!(
+                "{} job {} failed, retrying in {}s: {:?}",
+                job.kind(),
+                id,
+                delay,
+                e
+            );
+            let run_after = chrono::Utc::now().timestamp() + delay;
+            store.retry_job(id, run_after)?;
+            bot.jobs.schedule(id, run_after);
+            Ok(())
+        }
+    }
+}
+
+/// Seconds before the next run of a job that failed `attempts` times before
+fn retry_delay(attempts: u32) -> i64 {
+    JOB_RETRY_BASE_SECS << attempts.min(10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_payload() {
+        let job = Job::MessageChange {
+            channel_id: 1,
+            message_id: 2,
+        };
+        let payload = serde_json::to_string(&job).unwrap();
+        assert_eq!(
+            payload,
+            r#"{"kind":"message_change","channel_id":1,"message_id":2}"#
+        );
+        assert_eq!(serde_json::from_str::<Job>(&payload).unwrap(), job);
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(0), JOB_RETRY_BASE_SECS);
+        assert_eq!(retry_delay(1), 2 * JOB_RETRY_BASE_SECS);
+        assert_eq!(retry_delay(3), 8 * JOB_RETRY_BASE_SECS);
+    }
+}
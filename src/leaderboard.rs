@@ -0,0 +1,220 @@
+use anyhow::Result;
+use serenity::all::*;
+use std::collections::HashMap;
+use tracing::warn;
+
+use crate::audit::project_key;
+use crate::config::Project;
+use crate::embeds::Embed;
+use crate::store::{Contribution, Store};
+
+/// Periods `/leaderboard` can cover, with their length in days
+const PERIODS: &[(&str, &str, Option<i64>)] = &[
+    ("week", "Last 7 days", Some(7)),
+    ("month", "Last 30 days", Some(30)),
+    ("quarter", "Last 90 days", Some(90)),
+    ("all", "All time", None),
+];
+
+/// `/leaderboard` ranks the members whose threads became issues
+pub fn create_leaderboard_command() -> CreateCommand {
+    let mut period = CreateCommandOption::new(
+        CommandOptionType::String,
+        "period",
+        "Which reports to count (last 30 days by default)",
+    );
+    for (value, name, _) in PERIODS {
+        period = period.add_string_choice(*name, *value);
+    }
+    CreateCommand::new("leaderboard")
+        .description("Show the top reporters whose threads became issues")
+        .add_option(period)
+}
+
+/// One reporter's totals over the period
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Standing {
+    pub reporter_id: u64,
+    pub issues: u64,
+    pub fixed: u64,
+    pub reactions: u64,
+}
+
+/// Reporters ranked by issues filed, then fixes, then reactions
+pub fn standings(contributions: &[Contribution], limit: usize) -> Vec<Standing> {
+    let mut by_reporter: HashMap<u64, Standing> = HashMap::new();
+    for contribution in contributions {
+        let standing = by_reporter
+            .entry(contribution.reporter_id)
+            .or_insert_with(|| Standing {
+                reporter_id: contribution.reporter_id,
+                ..Default::default()
+            });
+        standing.issues += 1;
+        standing.fixed += u64::from(contribution.fixed_at.is_some());
+        standing.reactions += contribution.reactions;
+    }
+    let mut standings: Vec<Standing> = by_reporter.into_values().collect();
+    standings.sort_by(|a, b| {
+        (b.issues, b.fixed, b.reactions, a.reporter_id).cmp(&(
+            a.issues,
+            a.fixed,
+            a.reactions,
+            b.reporter_id,
+        ))
+    });
+    standings.truncate(limit);
+    standings
+}
+
+pub async fn handle_leaderboard_command(
+    http: &Http,
+    command: &CommandInteraction,
+    store: &Store,
+) -> Result<()> {
+    let Some(guild_id) = command.guild_id else {
+        return Ok(());
+    };
+    let chosen = command
+        .data
+        .options
+        .iter()
+        .find(|o| o.name == "period")
+        .and_then(|o| o.value.as_str())
+        .unwrap_or("month");
+    let (_, label, days) = PERIODS
+        .iter()
+        .find(|(value, _, _)| *value == chosen)
+        .unwrap_or(&PERIODS[1]);
+    let since = days.map_or(0, |days| {
+        chrono::Utc::now().timestamp() - days * 24 * 60 * 60
+    });
+
+    let contributions = store.contributions_since(guild_id.get(), since)?;
+    let standings = standings(&contributions, crate::constants::LEADERBOARD_SIZE);
+    let description = if standings.is_empty() {
+        "No threads became issues in this period yet.".to_string()
+    } else {
+        standings
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                format!(
+                    "{}. <@{}> - {} {}, {} fixed, {} reactions",
+                    i + 1,
+                    s.reporter_id,
+                    s.issues,
+                    if s.issues == 1 { "issue" } else { "issues" },
+                    s.fixed,
+                    s.reactions
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let embed = Embed::new()
+        .title(format!("{} ({})", crate::constants::MSG_LEADERBOARD, label))
+        .description(description)
+        .color(crate::constants::COLOR_SUCCESS);
+    command
+        .create_response(
+            http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new().embed(embed.build()),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Credit the thread owner with a newly filed issue. Anonymous and staging
+/// reports stay off the leaderboard.
+pub async fn record_report(
+    http: &Http,
+    store: &Store,
+    project: &Project,
+    thread: &GuildChannel,
+    issue_number: u64,
+) -> Result<()> {
+    let Some(owner_id) = thread.owner_id else {
+        return Ok(());
+    };
+    if project.staging()
+        || project.anonymous_reports()
+        || store.anonymous_reporter(thread.id.get())?.is_some()
+    {
+        return Ok(());
+    }
+    store.record_contribution(
+        thread.id.get(),
+        thread.guild_id.get(),
+        &project_key(project),
+        owner_id.get(),
+        issue_number,
+    )?;
+    update_reactions(http, store, thread.id).await
+}
+
+/// Recount the reactions on a reported thread's opening post
+pub async fn handle_reaction(
+    http: &Http,
+    store: &Store,
+    channel_id: ChannelId,
+    message_id: MessageId,
+) -> Result<()> {
+    if message_id != crate::discord_cache::starter_id(channel_id)
+        || !store.has_contribution(channel_id.get())?
+    {
+        return Ok(());
+    }
+    update_reactions(http, store, channel_id).await
+}
+
+async fn update_reactions(http: &Http, store: &Store, thread_id: ChannelId) -> Result<()> {
+    let message = match crate::discord_cache::starter_message(http, thread_id).await {
+        Ok(message) => message,
+        // Text channel threads have no opening post of their own
+        Err(e) => {
+            warn!(
+                "No opening post to count reactions of in {}: {}",
+                thread_id, e
+            );
+            return Ok(());
+        }
+    };
+    let reactions = message.reactions.iter().map(|r| r.count).sum();
+    store.set_contribution_reactions(thread_id.get(), reactions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contribution(reporter_id: u64, fixed: bool, reactions: u64) -> Contribution {
+        Contribution {
+            thread_id: 0,
+            reporter_id,
+            issue_number: 0,
+            filed_at: 0,
+            fixed_at: fixed.then_some(1),
+            reactions,
+        }
+    }
+
+    #[test]
+    fn test_standings() {
+        let contributions = [
+            contribution(1, false, 3),
+            contribution(2, true, 0),
+            contribution(2, false, 1),
+            contribution(3, true, 0),
+            contribution(4, false, 2),
+        ];
+        let ranked: Vec<(u64, u64, u64, u64)> = standings(&contributions, 3)
+            .iter()
+            .map(|s| (s.reporter_id, s.issues, s.fixed, s.reactions))
+            .collect();
+        assert_eq!(ranked, vec![(2, 2, 1, 1), (3, 1, 1, 0), (1, 1, 0, 3)]);
+    }
+}
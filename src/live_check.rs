@@ -0,0 +1,273 @@
+use anyhow::{Context, Result};
+use serenity::http::Http;
+use serenity::model::channel::ChannelType;
+use serenity::model::id::{ChannelId, GuildId, RoleId};
+use serenity::model::Permissions;
+
+use crate::config::{ChatPlatform, Config, Project};
+
+/// Permissions the bot needs in a project's forum
+const REQUIRED_FORUM_PERMISSIONS: &[(Permissions, &str)] = &[
+    (Permissions::VIEW_CHANNEL, "View Channel"),
+    (Permissions::SEND_MESSAGES, "Send Messages"),
+    (
+        Permissions::SEND_MESSAGES_IN_THREADS,
+        "Send Messages in Threads",
+    ),
+    (Permissions::MANAGE_THREADS, "Manage Threads"),
+];
+
+/// Outcome of one live check
+struct Check {
+    name: String,
+    result: Result<(), String>,
+}
+
+/// Verify each project against the live Discord and GitHub APIs and print a
+/// pass/fail report. Returns false if any check failed.
+pub async fn check_projects(config: &Config) -> Result<bool> {
+    println!("\nRunning live checks...");
+    let clients = crate::clients::Clients::new_standalone().await?;
+
+    let mut all_passed = true;
+    for (i, project) in config.projects.iter().enumerate() {
+        println!(
+            "\n  Project {}: {}",
+            i + 1,
+            project.name.as_deref().unwrap_or("(unnamed)")
+        );
+
+        let mut checks = match project.platform() {
+            ChatPlatform::Discord => {
+                // Projects can be served by different bots
+                let discord = clients.discord_for(config, project)?;
+                let bot_user = discord.get_current_user().await?;
+                check_discord(&discord, project, bot_user.id).await
+            }
+            ChatPlatform::Matrix => vec![check_matrix(config).await],
+            ChatPlatform::Telegram => vec![check_telegram().await],
+        };
+        match clients.github_for(config, project).await {
+            Ok(github) => checks.push(check_github(&github, project).await),
+            Err(e) => checks.push(Check {
+                name: "GitHub credentials".to_string(),
+                result: Err(e.to_string()),
+            }),
+        }
+
+        for check in checks {
+            match check.result {
+                Ok(()) => println!("    ✓ {}", check.name),
+                Err(reason) => {
+                    all_passed = false;
+                    println!("    ✗ {}: {}", check.name, reason);
+                }
+            }
+        }
+    }
+
+    Ok(all_passed)
+}
+
+/// The Matrix access token is accepted by the homeserver
+async fn check_matrix(config: &Config) -> Check {
+    let result = async {
+        let matrix_config = config.matrix.as_ref().context("[matrix] is not set")?;
+        crate::matrix::MatrixClient::from_env(matrix_config)?
+            .whoami()
+            .await
+    }
+    .await;
+    match result {
+        Ok(user_id) => Check {
+            name: format!("Matrix account {user_id}"),
+            result: Ok(()),
+        },
+        Err(e) => Check {
+            name: "Matrix account".to_string(),
+            result: Err(format!("{e:#}")),
+        },
+    }
+}
+
+/// `TELEGRAM_BOT_TOKEN` belongs to a bot
+async fn check_telegram() -> Check {
+    let result = async {
+        crate::telegram::TelegramClient::from_env()?
+            .username()
+            .await
+    }
+    .await;
+    match result {
+        Ok(username) => Check {
+            name: format!("Telegram bot @{username}"),
+            result: Ok(()),
+        },
+        Err(e) => Check {
+            name: "Telegram bot".to_string(),
+            result: Err(format!("{e:#}")),
+        },
+    }
+}
+
+async fn check_discord(
+    discord: &Http,
+    project: &Project,
+    bot_id: serenity::model::id::UserId,
+) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let guild = match parse_id(&project.discord_guild_id) {
+        Ok(id) => GuildId::new(id).to_partial_guild(discord).await,
+        Err(e) => {
+            checks.push(Check {
+                name: "Discord guild".to_string(),
+                result: Err(e),
+            });
+            return checks;
+        }
+    };
+    let guild = match guild {
+        Ok(guild) => {
+            checks.push(Check {
+                name: format!("Discord guild '{}'", guild.name),
+                result: Ok(()),
+            });
+            guild
+        }
+        Err(e) => {
+            checks.push(Check {
+                name: "Discord guild".to_string(),
+                result: Err(e.to_string()),
+            });
+            return checks;
+        }
+    };
+
+    let forum = match parse_id(&project.discord_forum_id) {
+        Ok(id) => ChannelId::new(id)
+            .to_channel(discord)
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|c| c.guild().ok_or_else(|| "not a guild channel".to_string())),
+        Err(e) => Err(e),
+    };
+    let forum = match forum {
+        Ok(forum) if forum.kind != ChannelType::Forum => Err(format!(
+            "#{} is a {:?} channel, not a forum",
+            forum.name, forum.kind
+        )),
+        Ok(forum) if forum.guild_id != guild.id => {
+            Err(format!("#{} belongs to another guild", forum.name))
+        }
+        other => other,
+    };
+    let forum = match forum {
+        Ok(forum) => {
+            checks.push(Check {
+                name: format!("Forum #{}", forum.name),
+                result: Ok(()),
+            });
+            Some(forum)
+        }
+        Err(e) => {
+            checks.push(Check {
+                name: "Forum channel".to_string(),
+                result: Err(e),
+            });
+            None
+        }
+    };
+
+    for (setting, role_id) in [
+        ("allowed_role_id", &project.allowed_role_id),
+        ("notify_role_id", &project.notify_role_id),
+    ] {
+        let Some(role_id) = role_id else {
+            continue;
+        };
+        let result = parse_id(role_id).and_then(|id| {
+            if guild.roles.contains_key(&RoleId::new(id)) {
+                Ok(())
+            } else {
+                Err(format!("role {id} not found in the guild"))
+            }
+        });
+        checks.push(Check {
+            name: format!("Role {setting}"),
+            result,
+        });
+    }
+
+    if let Some(channel_id) = &project.triage_channel_id {
+        let result = match parse_id(channel_id) {
+            Ok(id) => ChannelId::new(id)
+                .to_channel(discord)
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+        checks.push(Check {
+            name: "Triage channel".to_string(),
+            result,
+        });
+    }
+
+    if let Some(forum) = forum {
+        let result = match guild.member(discord, bot_id).await {
+            Ok(member) => {
+                let granted = guild.user_permissions_in(&forum, &member);
+                let missing: Vec<&str> = REQUIRED_FORUM_PERMISSIONS
+                    .iter()
+                    .filter(|(permission, _)| !granted.contains(*permission))
+                    .map(|(_, name)| *name)
+                    .collect();
+                if missing.is_empty() {
+                    Ok(())
+                } else {
+                    Err(format!("missing {}", missing.join(", ")))
+                }
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        checks.push(Check {
+            name: "Bot permissions in forum".to_string(),
+            result,
+        });
+    }
+
+    checks
+}
+
+/// The repository exists, has issues enabled and the token can label them
+async fn check_github(github: &octocrab::Octocrab, project: &Project) -> Check {
+    let name = format!(
+        "GitHub repo {}/{}",
+        project.github_owner, project.github_repo
+    );
+    let result = match github
+        .repos(&project.github_owner, &project.github_repo)
+        .get()
+        .await
+    {
+        Ok(repo) if repo.has_issues == Some(false) => Err("issues are disabled".to_string()),
+        // Installation tokens don't report permissions; PATs do
+        Ok(repo) => match repo.permissions {
+            Some(p) if !(p.admin || p.maintain || p.push || p.triage) => {
+                Err("token can open issues but not label or close them".to_string())
+            }
+            _ => Ok(()),
+        },
+        Err(e) => Err(e.to_string()),
+    };
+
+    Check { name, result }
+}
+
+fn parse_id(id: &str) -> Result<u64, String> {
+    id.parse()
+        .ok()
+        .filter(|id| *id != 0)
+        .ok_or_else(|| format!("'{id}' is not a valid Discord ID"))
+}
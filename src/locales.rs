@@ -0,0 +1,114 @@
+use serde::Deserialize;
+use serenity::all::{CreateCommand, CreateCommandOption};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Translated slash command text from `locales/<discord locale>.toml`
+const BUNDLES: &[(&str, &str)] = &[
+    ("de", include_str!("../locales/de.toml")),
+    ("es-ES", include_str!("../locales/es-ES.toml")),
+    ("fr", include_str!("../locales/fr.toml")),
+    ("pt-BR", include_str!("../locales/pt-BR.toml")),
+];
+
+#[derive(Debug, Deserialize)]
+struct Bundle {
+    commands: HashMap<String, Localized>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Localized {
+    name: String,
+    description: String,
+}
+
+static LOADED: LazyLock<Vec<(&'static str, Bundle)>> = LazyLock::new(|| {
+    BUNDLES
+        .iter()
+        .filter_map(|(locale, text)| match toml::from_str(text) {
+            Ok(bundle) => Some((*locale, bundle)),
+            Err(e) => {
+                tracing::error!("Invalid locale bundle {}: {}", locale, e);
+                None
+            }
+        })
+        .collect()
+});
+
+/// Translations of a command or option, by locale. `path` is the command
+/// name followed by its subcommand and option names, e.g. `issue.create.since`.
+fn translations(path: &str) -> impl Iterator<Item = (&'static str, &'static Localized)> + '_ {
+    LOADED
+        .iter()
+        .filter_map(move |(locale, bundle)| Some((*locale, bundle.commands.get(path)?)))
+}
+
+pub fn command(mut command: CreateCommand, path: &str) -> CreateCommand {
+    for (locale, text) in translations(path) {
+        command = command
+            .name_localized(locale, &text.name)
+            .description_localized(locale, &text.description);
+    }
+    command
+}
+
+pub fn option(mut option: CreateCommandOption, path: &str) -> CreateCommandOption {
+    for (locale, text) in translations(path) {
+        option = option
+            .name_localized(locale, &text.name)
+            .description_localized(locale, &text.description);
+    }
+    option
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISSUE_PATHS: &[&str] = &[
+        "issue",
+        "issue.create",
+        "issue.create.messages",
+        "issue.create.since",
+        "issue.create.until",
+        "issue.create.from",
+        "issue.create.anonymous",
+        "issue.create.preview",
+        "issue.priority",
+        "issue.duplicate",
+        "issue.duplicate.number",
+        "issue.split",
+        "issue.merge",
+        "issue.merge.into",
+        "issue.move",
+        "issue.move.project",
+    ];
+
+    #[test]
+    fn test_bundles_are_complete() {
+        assert_eq!(LOADED.len(), BUNDLES.len());
+        for (locale, bundle) in LOADED.iter() {
+            for path in ISSUE_PATHS {
+                let text = bundle
+                    .commands
+                    .get(*path)
+                    .unwrap_or_else(|| panic!("{locale} is missing {path}"));
+                // Discord rejects the whole registration over one bad entry
+                assert!(
+                    !text.name.is_empty()
+                        && text.name.chars().count() <= 32
+                        && text.name.chars().all(|c| c == '-'
+                            || c == '_'
+                            || (c.is_alphanumeric() && !c.is_uppercase())),
+                    "{locale} {path} name '{}'",
+                    text.name
+                );
+                assert!(
+                    !text.description.is_empty() && text.description.chars().count() <= 100,
+                    "{locale} {path} description"
+                );
+            }
+            assert_eq!(bundle.commands.len(), ISSUE_PATHS.len(), "{locale}");
+        }
+    }
+}
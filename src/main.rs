@@ -1,20 +1,114 @@
+mod anonymous;
+mod archive_project;
 mod archive_threads;
+mod assignments;
+mod attachments;
+mod audit;
 mod audit_sync;
+mod auto_create;
 mod bot;
+mod bridge;
+mod ci_status;
+mod circuit_breaker;
 mod cli;
 mod clients;
+mod code_blocks;
 mod commands;
+mod comment_commands;
+mod comment_reactions;
 mod config;
 mod constants;
+mod cooldown;
 mod debug;
+mod debug_state;
 mod debug_sync;
+mod discord_bots;
+mod discord_cache;
+mod drift;
+mod duplicate;
+mod duplicate_clusters;
+mod embeds;
+mod error;
+mod feedback;
+mod feedback_template;
+mod filing;
+mod gateway;
 mod github;
 mod github_app;
+mod graphql;
+mod interactions;
+mod issue_posts;
+mod janitor;
+mod jobs;
+mod leaderboard;
+mod live_check;
+mod locales;
+mod mappings;
+mod matrix;
+mod matrix_sync;
+mod mentions;
+mod merge;
+mod message_edits;
+mod message_link;
+mod milestones;
+mod mirror;
+mod moderator_actions;
+mod move_issue;
+mod mute;
+mod needs_info;
+mod notify;
+mod object_store;
+mod ocr;
+mod onboarding;
+mod permission_check;
+mod prefix;
+mod preview;
+mod priority;
+mod progress;
+mod project_fields;
+mod project_info;
+mod quiet_hours;
+mod quota;
+mod rate_budget;
+mod reconcile;
+mod redact;
+mod releases;
+mod report;
+mod reporter;
+mod reward_hooks;
+mod router;
+mod sanitize;
+mod scam_filter;
+mod selection;
+mod server;
+mod setup_forum;
+mod setup_labels;
+mod sla;
+mod split;
+mod state;
+mod status_tags;
+mod store;
+mod summary;
+mod supervisor;
 mod sync;
+mod sync_schedule;
+mod sync_status;
+mod team_mentions;
+mod telegram;
+mod telegram_sync;
+mod thread_events;
+mod thread_stats;
+mod threads;
+mod title_suggestion;
+mod transcript;
+mod translation;
+mod triage;
+mod verify_fix;
+mod webhook;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
-use serenity::prelude::*;
+use serenity::all::{ApplicationId, GuildId};
 use std::sync::Arc;
 
 #[tokio::main]
@@ -30,11 +124,16 @@ async fn main() -> Result<()> {
             println!("Checking Discord configuration...");
             debug::check_discord().await?;
         }
-        cli::Commands::PostFeedback { channel } => {
+        cli::Commands::PostFeedback {
+            channel,
+            template,
+            project,
+        } => {
             println!("Posting feedback instructions to channel {channel}...");
-            debug::post_feedback_instructions(&channel).await?;
+            debug::post_feedback_instructions(&channel, template.as_deref(), project.as_deref())
+                .await?;
         }
-        cli::Commands::ValidateConfig => {
+        cli::Commands::ValidateConfig { live } => {
             println!("Validating configuration...");
             match config::Config::load() {
                 Ok(config) => {
@@ -50,8 +149,20 @@ async fn main() -> Result<()> {
                             "    - Name: {}",
                             project.name.as_deref().unwrap_or("(unnamed)")
                         );
-                        println!("    - Discord Guild: {}", project.discord_guild_id);
-                        println!("    - Discord Forum: {}", project.discord_forum_id);
+                        match project.platform() {
+                            config::ChatPlatform::Matrix => println!(
+                                "    - Matrix Room: {}",
+                                project.matrix_room_id.as_deref().unwrap_or_default()
+                            ),
+                            config::ChatPlatform::Telegram => println!(
+                                "    - Telegram Chat: {}",
+                                project.telegram_chat_id.unwrap_or_default()
+                            ),
+                            config::ChatPlatform::Discord => {
+                                println!("    - Discord Guild: {}", project.discord_guild_id);
+                                println!("    - Discord Forum: {}", project.discord_forum_id);
+                            }
+                        }
                         println!(
                             "    - GitHub: {}/{}",
                             project.github_owner, project.github_repo
@@ -60,6 +171,11 @@ async fn main() -> Result<()> {
                             println!("    - Required Role ID: {role_id}");
                         }
                     }
+
+                    if live && !live_check::check_projects(&config).await? {
+                        eprintln!("\n✗ Live checks failed");
+                        std::process::exit(1);
+                    }
                 }
                 Err(e) => {
                     eprintln!("✗ Configuration error: {e}");
@@ -76,49 +192,301 @@ async fn main() -> Result<()> {
         cli::Commands::AuditSync => {
             audit_sync::audit_sync_status().await?;
         }
+        cli::Commands::AuditLog {
+            project,
+            thread,
+            since,
+            until,
+            limit,
+        } => {
+            audit::print_audit_log(project, thread, since, until, limit).await?;
+        }
+        cli::Commands::Report {
+            month,
+            format,
+            project,
+            out,
+        } => {
+            report::generate_report(&month, format, project, out.as_deref())?;
+        }
+        cli::Commands::SetupForum { project, dry_run } => {
+            setup_forum::setup_forum(project.as_deref(), dry_run).await?;
+        }
+        cli::Commands::SetupLabels { project, dry_run } => {
+            setup_labels::setup_labels(project.as_deref(), dry_run).await?;
+        }
+        cli::Commands::MigrateMappings { project, dry_run } => {
+            mappings::migrate_mappings(project.as_deref(), dry_run).await?;
+        }
+        cli::Commands::WebhookTest {
+            payload,
+            event,
+            delivery,
+        } => {
+            webhook::webhook_test(&payload, &event, delivery).await?;
+        }
+        cli::Commands::ExportState { out } => {
+            state::export_state(&out)?;
+        }
+        cli::Commands::DumpState { out } => {
+            debug_state::dump_state(out.as_deref())?;
+        }
+        cli::Commands::ImportState { input } => {
+            state::import_state(&input)?;
+        }
+        cli::Commands::ArchiveProject { project, dry_run } => {
+            archive_project::archive_project(&project, dry_run).await?;
+        }
+        cli::Commands::Threads { command } => match command {
+            cli::ThreadsCommands::Lock {
+                older_than,
+                prefix,
+                project,
+                dry_run,
+            } => {
+                threads::lock_threads(&older_than, prefix.as_deref(), project.as_deref(), dry_run)
+                    .await?;
+            }
+            cli::ThreadsCommands::CloseIssues {
+                label,
+                project,
+                dry_run,
+            } => {
+                threads::close_issues(&label, project.as_deref(), dry_run).await?;
+            }
+        },
         cli::Commands::Run => {
-            // Load configuration first to get log level
             let config = Arc::new(config::Config::load()?);
+            init_logging(&config);
+            tracing::info!("Loaded {} projects", config.projects.len());
+            permission_check::check_github_permissions(&config).await?;
 
-            // Initialize logging with configured level
-            let log_level = config.log_level.as_deref().unwrap_or("info");
-            use tracing_subscriber::EnvFilter;
+            // One REST client, gateway connection and syncer per bot token
+            let bots = discord_bots::from_config(&config)?;
+            let primary = &bots[0];
+            let store = Arc::new(store::Store::open(&config)?);
+            let audit = Arc::new(audit::AuditLog::new(
+                store.clone(),
+                Some(primary.http.clone()),
+                &config,
+            ));
 
-            // Build filter to exclude octocrab and HTTP client deprecation warnings
-            let filter =
-                EnvFilter::new(format!("{log_level},octocrab=warn,reqwest=warn,hyper=warn"));
+            let mut handlers = Vec::new();
+            let mut syncer_wakes = Vec::new();
+            let mut syncers = Vec::new();
+            for bot in &bots {
+                let handler = Arc::new(new_bot(&bot.config, &store, &audit, bot.label()));
+                jobs::start_workers(&handler, bot.http.clone())?;
+                let wake = Arc::new(tokio::sync::Notify::new());
+                let syncer = spawn_syncer(
+                    bot.label(),
+                    &bot.config,
+                    bot.http.clone(),
+                    store.clone(),
+                    audit.clone(),
+                    wake.clone(),
+                );
+                handlers.push(handler);
+                syncer_wakes.push(wake);
+                syncers.push((bot.label().to_string(), syncer));
+            }
 
-            tracing_subscriber::fmt().with_env_filter(filter).init();
+            // GitHub webhooks wake the syncers instead of waiting for their interval
+            let sync_wake = Arc::new(tokio::sync::Notify::new());
+            tokio::spawn(discord_bots::fan_out_wake(sync_wake.clone(), syncer_wakes));
+            if let Some(webhook_config) = config.webhook.clone() {
+                let gateways = bots
+                    .iter()
+                    .zip(&handlers)
+                    .map(|(bot, handler)| (bot.label().to_string(), handler.gateway.clone()))
+                    .collect();
+                let routes = routes(
+                    &config,
+                    &store,
+                    &handlers[0],
+                    &primary.http,
+                    &sync_wake,
+                    gateways,
+                    syncers,
+                )?;
+                tokio::spawn(async move {
+                    if let Err(e) = server::serve(routes, &webhook_config.listen).await {
+                        tracing::error!("Webhook listener stopped: {:?}", e);
+                    }
+                });
+            }
 
+            tracing::info!("Starting CardiBot with {} Discord bot(s)...", bots.len());
+            let mut gateways = tokio::task::JoinSet::new();
+            for (bot, handler) in bots.iter().zip(handlers) {
+                let token = bot.token.clone();
+                let label = bot.label().to_string();
+                let watchdog_after = config.gateway_watchdog();
+                gateways.spawn(async move {
+                    discord_bots::run_gateway(&token, handler, watchdog_after)
+                        .await
+                        .with_context(|| format!("Discord bot '{label}' stopped"))
+                });
+            }
+            // Any bot stopping for good stops the process
+            if let Some(result) = gateways.join_next().await {
+                result??;
+            }
+        }
+        cli::Commands::Serve => {
+            let config = Arc::new(config::Config::load()?);
+            init_logging(&config);
             tracing::info!("Loaded {} projects", config.projects.len());
+            permission_check::check_github_permissions(&config).await?;
 
-            // Initialize Discord bot
-            let discord_token = std::env::var("DISCORD_TOKEN")?;
-            let intents = GatewayIntents::GUILDS
-                | GatewayIntents::GUILD_MESSAGES
-                | GatewayIntents::MESSAGE_CONTENT;
-
-            let bot = bot::Bot {
-                config: config.clone(),
+            let Some(webhook_config) = config.webhook.clone() else {
+                anyhow::bail!("serve needs a [webhook] listen address");
             };
+            if std::env::var("DISCORD_PUBLIC_KEY").is_err() {
+                anyhow::bail!("serve needs DISCORD_PUBLIC_KEY to verify Discord interactions");
+            }
 
-            let mut client = Client::builder(&discord_token, intents)
-                .event_handler(bot)
-                .await?;
+            // Interactions are verified with one application's key, so only
+            // the DISCORD_TOKEN bot's projects are served
+            if !config.discord_bots.is_empty() {
+                tracing::warn!(
+                    "serve only runs the DISCORD_TOKEN bot; projects with discord_bot are skipped"
+                );
+            }
+            let config = Arc::new(config.for_discord_bot(None));
+
+            // Plain REST client: no gateway connection, so reactions and
+            // thread create/update events are not received in this mode
+            let discord_token = config.discord_token(None)?;
+            let http = Arc::new(serenity::http::Http::new(&discord_token));
+            let user = http.get_current_user().await?;
+            http.set_application_id(ApplicationId::new(user.id.get()));
+
+            let store = Arc::new(store::Store::open(&config)?);
+            let audit = Arc::new(audit::AuditLog::new(
+                store.clone(),
+                Some(http.clone()),
+                &config,
+            ));
+            // Runs the jobs a gateway run of the DISCORD_TOKEN bot left
+            let bot = Arc::new(new_bot(&config, &store, &audit, "default"));
+            jobs::start_workers(&bot, http.clone())?;
 
-            // Spawn sync task if enabled
-            let sync_config_clone = config.clone();
-            let discord_http_clone = client.http.clone();
-            tokio::spawn(async move {
-                let syncer = sync::IssueSyncer::new(sync_config_clone, discord_http_clone);
-                syncer.start().await;
-            });
+            let mut guilds: Vec<GuildId> = config
+                .projects
+                .iter()
+                .filter_map(|p| p.discord_guild_id.parse().ok())
+                .map(GuildId::new)
+                .collect();
+            guilds.sort();
+            guilds.dedup();
+            bot.register_commands(&http, &guilds).await;
 
-            // Start the bot
-            tracing::info!("Starting CardiBot...");
-            client.start().await?;
+            let sync_wake = Arc::new(tokio::sync::Notify::new());
+            let syncer = spawn_syncer(
+                "default",
+                &config,
+                http.clone(),
+                store.clone(),
+                audit,
+                sync_wake.clone(),
+            );
+            let syncers = vec![("default".to_string(), syncer)];
+            let routes = routes(
+                &config,
+                &store,
+                &bot,
+                &http,
+                &sync_wake,
+                Vec::new(),
+                syncers,
+            )?;
+
+            tracing::info!("Starting CardiBot without a gateway connection...");
+            server::serve(routes, &webhook_config.listen).await?;
         }
     }
 
     Ok(())
 }
+
+fn init_logging(config: &config::Config) {
+    let log_level = config.log_level.as_deref().unwrap_or("info");
+    use tracing_subscriber::EnvFilter;
+
+    // Build filter to exclude octocrab and HTTP client deprecation warnings
+    let filter = EnvFilter::new(format!("{log_level},octocrab=warn,reqwest=warn,hyper=warn"));
+
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+}
+
+fn new_bot(
+    config: &Arc<config::Config>,
+    store: &Arc<store::Store>,
+    audit: &Arc<audit::AuditLog>,
+    label: &str,
+) -> bot::Bot {
+    bot::Bot {
+        config: config.clone(),
+        store: store.clone(),
+        cooldowns: Arc::new(cooldown::CommandCooldowns::default()),
+        audit: audit.clone(),
+        gateway: Arc::new(gateway::GatewayHealth::default()),
+        jobs: Arc::new(jobs::JobQueue::new(label, store.clone())),
+        user_id: Arc::default(),
+    }
+}
+
+/// GitHub webhooks, plus Discord interactions when `DISCORD_PUBLIC_KEY` is set
+/// and the health of each bot's gateway connection and syncer
+fn routes(
+    config: &Arc<config::Config>,
+    store: &Arc<store::Store>,
+    bot: &Arc<bot::Bot>,
+    http: &Arc<serenity::http::Http>,
+    sync_wake: &Arc<tokio::sync::Notify>,
+    gateways: Vec<(String, Arc<gateway::GatewayHealth>)>,
+    syncers: Vec<(String, Arc<supervisor::SyncerHealth>)>,
+) -> Result<Arc<server::Routes>> {
+    let webhook = webhook::WebhookHandler::new(
+        config.clone(),
+        store.clone(),
+        webhook::WebhookHandler::secret_from_env(),
+        sync_wake.clone(),
+    );
+    let interactions = interactions::InteractionsEndpoint::from_env(bot.clone(), http.clone())?;
+    Ok(Arc::new(server::Routes {
+        webhook: Some(Arc::new(webhook)),
+        interactions: interactions.map(Arc::new),
+        gateways,
+        syncers,
+    }))
+}
+
+/// Run a bot's syncer under a supervisor that restarts it when it panics
+fn spawn_syncer(
+    label: &str,
+    config: &Arc<config::Config>,
+    discord: Arc<serenity::http::Http>,
+    store: Arc<store::Store>,
+    audit: Arc<audit::AuditLog>,
+    sync_wake: Arc<tokio::sync::Notify>,
+) -> Arc<supervisor::SyncerHealth> {
+    let health = Arc::new(supervisor::SyncerHealth::default());
+    let config = config.clone();
+    tokio::spawn(supervisor::supervise(
+        label.to_string(),
+        health.clone(),
+        move || {
+            let syncer = sync::IssueSyncer::new(
+                config.clone(),
+                discord.clone(),
+                store.clone(),
+                audit.clone(),
+                sync_wake.clone(),
+            );
+            syncer.start()
+        },
+    ));
+    health
+}
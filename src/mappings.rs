@@ -0,0 +1,261 @@
+use anyhow::Result;
+use octocrab::models::issues::Issue;
+use octocrab::params::State;
+use tracing::warn;
+
+use crate::audit::project_key;
+use crate::config::{Config, MappingMode, Project};
+use crate::store::Store;
+use crate::sync::issue_thread_id;
+
+/// An issue's thread as recorded on the issue itself and in the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mapping {
+    /// From the title suffix or hidden body marker
+    pub marker: Option<u64>,
+    pub stored: Option<u64>,
+}
+
+impl Mapping {
+    pub fn lookup(store: &Store, project: &Project, issue: &Issue) -> Result<Self> {
+        let key = project_key(project);
+        let marker = issue_thread_id(issue);
+        // Several threads can point at one issue after merges, so the marker's
+        // own thread is checked first
+        let stored = match marker {
+            Some(thread_id)
+                if store.thread_issue(thread_id)?.is_some_and(|linked| {
+                    linked.project == key && linked.issue_number == issue.number
+                }) =>
+            {
+                Some(thread_id)
+            }
+            _ => store.thread_for_issue(&key, issue.number)?,
+        };
+        Ok(Self { marker, stored })
+    }
+
+    /// The thread to use under `mode`; markers win while both are read
+    pub fn thread_id(&self, mode: MappingMode) -> Option<u64> {
+        match mode {
+            MappingMode::Markers => self.marker,
+            MappingMode::Dual => self.marker.or(self.stored),
+            MappingMode::Store => self.stored,
+        }
+    }
+
+    /// How the two sources disagree, if they do
+    pub fn discrepancy(&self) -> Option<String> {
+        match (self.marker, self.stored) {
+            (Some(marker), None) => {
+                Some(format!("marker names thread {marker}, database has none"))
+            }
+            (None, Some(stored)) => Some(format!(
+                "database names thread {stored}, issue has no marker"
+            )),
+            (Some(marker), Some(stored)) if marker != stored => Some(format!(
+                "marker names thread {marker}, database names thread {stored}"
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// The thread linked to an issue under the project's mapping mode
+pub fn thread_for(store: &Store, project: &Project, issue: &Issue) -> Option<u64> {
+    let mode = project.mapping_mode();
+    if mode == MappingMode::Markers {
+        return issue_thread_id(issue);
+    }
+    match Mapping::lookup(store, project, issue) {
+        Ok(mapping) => mapping.thread_id(mode),
+        Err(e) => {
+            warn!("Failed to read mapping for issue #{}: {}", issue.number, e);
+            // The database is the only source after the cut-over
+            match mode {
+                MappingMode::Store => None,
+                _ => issue_thread_id(issue),
+            }
+        }
+    }
+}
+
+/// The title without the `[thread_id]` suffix the bot appended, or None when
+/// it has none
+pub fn strip_title_marker(title: &str, thread_id: u64) -> Option<String> {
+    let stripped = title.strip_suffix(&format!("[{thread_id}]"))?.trim_end();
+    (!stripped.is_empty()).then(|| stripped.to_string())
+}
+
+#[derive(Default)]
+struct MigrationCounts {
+    backfilled: u64,
+    retitled: u64,
+    conflicts: u64,
+}
+
+/// Copy every marker link into the database and drop the title suffixes, so
+/// projects can switch to `mapping_mode = "store"`
+pub async fn migrate_mappings(project: Option<&str>, dry_run: bool) -> Result<()> {
+    let config = Config::load()?;
+    let store = Store::open(&config)?;
+    let clients = crate::clients::Clients::new_standalone().await?;
+
+    let mut conflicts = 0;
+    for project in crate::threads::selected_projects(&config, project) {
+        for route in project.routes() {
+            // Routes can live on another account or GitHub instance
+            let github = clients.github_for(&config, &route).await?;
+            println!(
+                "📦 {}/{} ({:?} mode)",
+                route.github_owner,
+                route.github_repo,
+                route.mapping_mode()
+            );
+            let counts = migrate_repo(&github, &store, &route, dry_run).await?;
+            println!(
+                "  - {} links backfilled, {} titles cleaned, {} conflicts",
+                counts.backfilled, counts.retitled, counts.conflicts
+            );
+            conflicts += counts.conflicts;
+        }
+    }
+
+    if dry_run {
+        println!("\nDry run; nothing was changed");
+    } else if conflicts > 0 {
+        println!("\n⚠️  Resolve the conflicts above and run again before switching to mapping_mode = \"store\"");
+    } else {
+        println!("\n✅ Done; set mapping_mode = \"store\" to read links from the database only");
+    }
+    Ok(())
+}
+
+async fn migrate_repo(
+    github: &octocrab::Octocrab,
+    store: &Store,
+    project: &Project,
+    dry_run: bool,
+) -> Result<MigrationCounts> {
+    let mut counts = MigrationCounts::default();
+    let issues = github.issues(&project.github_owner, &project.github_repo);
+    let mut page: u32 = 1;
+    loop {
+        let batch = issues
+            .list()
+            .state(State::All)
+            .per_page(100)
+            .page(page)
+            .send()
+            .await?;
+        for issue in &batch.items {
+            if issue.pull_request.is_some() {
+                continue;
+            }
+            let mapping = Mapping::lookup(store, project, issue)?;
+            let Some(thread_id) = mapping.marker else {
+                continue;
+            };
+
+            match mapping.stored {
+                Some(stored) if stored != thread_id => {
+                    println!(
+                        "  ⚠️  Issue #{}: {}",
+                        issue.number,
+                        mapping.discrepancy().unwrap_or_default()
+                    );
+                    counts.conflicts += 1;
+                    continue;
+                }
+                Some(_) => {}
+                None => {
+                    if !dry_run {
+                        store.upsert_thread_issue(
+                            thread_id,
+                            &project_key(project),
+                            issue.number,
+                        )?;
+                    }
+                    counts.backfilled += 1;
+                }
+            }
+
+            // The hidden body marker stays as a fallback; only the visible
+            // suffix goes
+            let has_body_marker = issue
+                .body
+                .as_deref()
+                .and_then(crate::sync::extract_marker_thread_id)
+                == Some(thread_id);
+            if let Some(title) = strip_title_marker(&issue.title, thread_id) {
+                if !dry_run {
+                    let update = issues.update(issue.number).title(&title);
+                    if has_body_marker {
+                        update.send().await?;
+                    } else {
+                        let body = format!(
+                            "{}\n\n{}",
+                            issue.body.as_deref().unwrap_or_default(),
+                            crate::sync::thread_marker(thread_id)
+                        );
+                        update.body(&body).send().await?;
+                    }
+                }
+                counts.retitled += 1;
+            }
+        }
+
+        if batch.next.is_none() {
+            return Ok(counts);
+        }
+        page += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mapping_modes_and_discrepancies() {
+        let both = Mapping {
+            marker: Some(1),
+            stored: Some(1),
+        };
+        assert_eq!(both.discrepancy(), None);
+
+        let marker_only = Mapping {
+            marker: Some(1),
+            stored: None,
+        };
+        assert_eq!(marker_only.thread_id(MappingMode::Dual), Some(1));
+        assert_eq!(marker_only.thread_id(MappingMode::Store), None);
+        assert!(marker_only.discrepancy().is_some());
+
+        let stored_only = Mapping {
+            marker: None,
+            stored: Some(2),
+        };
+        assert_eq!(stored_only.thread_id(MappingMode::Markers), None);
+        assert_eq!(stored_only.thread_id(MappingMode::Dual), Some(2));
+
+        let split = Mapping {
+            marker: Some(1),
+            stored: Some(2),
+        };
+        assert_eq!(split.thread_id(MappingMode::Dual), Some(1));
+        assert_eq!(split.thread_id(MappingMode::Store), Some(2));
+        assert!(split.discrepancy().unwrap().contains("thread 2"));
+    }
+
+    #[test]
+    fn test_strip_title_marker() {
+        assert_eq!(
+            strip_title_marker("[BUG] Crash on save [123]", 123).as_deref(),
+            Some("[BUG] Crash on save")
+        );
+        assert_eq!(strip_title_marker("[BUG] Crash on save", 123), None);
+        assert_eq!(strip_title_marker("Crash [124]", 123), None);
+        assert_eq!(strip_title_marker("[123]", 123), None);
+    }
+}
@@ -0,0 +1,226 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::config::MatrixConfig;
+
+/// Makes transaction IDs unique within a process; the timestamp covers
+/// restarts
+static TXN_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A room event as the client-server API returns it
+#[derive(Debug, Deserialize)]
+pub struct RoomEvent {
+    pub event_id: String,
+    pub sender: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub content: Value,
+}
+
+impl RoomEvent {
+    /// Text of a message, or None for other events and for edits, which
+    /// repeat a message already seen
+    pub fn body(&self) -> Option<&str> {
+        if self.kind != "m.room.message" {
+            return None;
+        }
+        let rel_type = self.content.pointer("/m.relates_to/rel_type");
+        if rel_type.and_then(Value::as_str) == Some("m.replace") {
+            return None;
+        }
+        self.content.get("body")?.as_str()
+    }
+}
+
+#[derive(Deserialize)]
+struct Chunk {
+    chunk: Vec<RoomEvent>,
+}
+
+/// Client for the bot's Matrix account. Encrypted rooms aren't supported:
+/// their messages arrive as `m.room.encrypted` events without a body.
+pub struct MatrixClient<'a> {
+    config: &'a MatrixConfig,
+    access_token: String,
+    client: reqwest::Client,
+}
+
+impl<'a> MatrixClient<'a> {
+    /// The access token comes from `MATRIX_ACCESS_TOKEN`
+    pub fn from_env(config: &'a MatrixConfig) -> Result<Self> {
+        Ok(Self {
+            config,
+            access_token: std::env::var("MATRIX_ACCESS_TOKEN")
+                .context("MATRIX_ACCESS_TOKEN must be set for [matrix]")?,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// The bot's own user ID, e.g. "@cardibot:example.org"
+    pub async fn whoami(&self) -> Result<String> {
+        let response: Value = self
+            .get(&["v3", "account", "whoami"], &[])?
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        response["user_id"]
+            .as_str()
+            .map(str::to_string)
+            .context("whoami response has no user_id")
+    }
+
+    /// Root events of the room's threads, most recently active first
+    pub async fn threads(&self, room_id: &str) -> Result<Vec<RoomEvent>> {
+        let limit = crate::constants::MATRIX_THREAD_LIMIT.to_string();
+        let response: Chunk = self
+            .get(
+                &["v1", "rooms", room_id, "threads"],
+                &[("include", "all"), ("limit", &limit)],
+            )?
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response.chunk)
+    }
+
+    /// Replies in a thread, oldest first, without the root event
+    pub async fn thread_messages(
+        &self,
+        room_id: &str,
+        root_event_id: &str,
+    ) -> Result<Vec<RoomEvent>> {
+        let limit = crate::constants::MATRIX_MESSAGE_FETCH_LIMIT.to_string();
+        let response: Chunk = self
+            .get(
+                &[
+                    "v1",
+                    "rooms",
+                    room_id,
+                    "relations",
+                    root_event_id,
+                    "m.thread",
+                ],
+                &[("dir", "f"), ("limit", &limit)],
+            )?
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response.chunk)
+    }
+
+    /// Post a plain text reply in a thread
+    pub async fn send_to_thread(
+        &self,
+        room_id: &str,
+        root_event_id: &str,
+        text: &str,
+    ) -> Result<()> {
+        let txn_id = format!(
+            "cardibot-{}-{}",
+            chrono::Utc::now().timestamp_millis(),
+            TXN_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let content = json!({
+            "msgtype": "m.notice",
+            "body": text,
+            "m.relates_to": {
+                "rel_type": "m.thread",
+                "event_id": root_event_id,
+                // Clients without thread support show it as a reply
+                "is_falling_back": true,
+                "m.in_reply_to": { "event_id": root_event_id },
+            },
+        });
+        let url = self.url(&["v3", "rooms", room_id, "send", "m.room.message", &txn_id])?;
+        self.client
+            .put(url)
+            .bearer_auth(&self.access_token)
+            .json(&content)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    fn get(&self, path: &[&str], query: &[(&str, &str)]) -> Result<reqwest::RequestBuilder> {
+        Ok(self
+            .client
+            .get(self.url(path)?)
+            .bearer_auth(&self.access_token)
+            .query(query))
+    }
+
+    /// Client-server API URL with each path segment percent-encoded, as room
+    /// and event IDs contain reserved characters
+    fn url(&self, path: &[&str]) -> Result<reqwest::Url> {
+        let mut url = reqwest::Url::parse(&self.config.homeserver_url)
+            .context("Invalid [matrix] homeserver_url")?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("Invalid [matrix] homeserver_url"))?
+            .pop_if_empty()
+            .extend(["_matrix", "client"])
+            .extend(path);
+        Ok(url)
+    }
+}
+
+/// Link to a thread that opens in the reader's Matrix client
+pub fn thread_url(room_id: &str, root_event_id: &str) -> String {
+    format!("https://matrix.to/#/{room_id}/{root_event_id}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url() {
+        let config = MatrixConfig {
+            homeserver_url: "https://matrix.example.org/".to_string(),
+        };
+        let client = MatrixClient {
+            config: &config,
+            access_token: String::new(),
+            client: reqwest::Client::new(),
+        };
+        assert_eq!(
+            client
+                .url(&["v1", "rooms", "!abc:example.org", "threads"])
+                .unwrap()
+                .as_str(),
+            "https://matrix.example.org/_matrix/client/v1/rooms/!abc:example.org/threads"
+        );
+        assert_eq!(
+            client
+                .url(&["v1", "rooms", "!a:b", "relations", "$e/v+", "m.thread"])
+                .unwrap()
+                .as_str(),
+            "https://matrix.example.org/_matrix/client/v1/rooms/!a:b/relations/$e%2Fv+/m.thread"
+        );
+    }
+
+    #[test]
+    fn test_body() {
+        let event = |content: Value| RoomEvent {
+            event_id: "$e".to_string(),
+            sender: "@alice:example.org".to_string(),
+            kind: "m.room.message".to_string(),
+            content,
+        };
+        assert_eq!(event(json!({ "body": "hi" })).body(), Some("hi"));
+        let edit = event(json!({
+            "body": "* hi",
+            "m.relates_to": { "rel_type": "m.replace", "event_id": "$e" },
+        }));
+        assert_eq!(edit.body(), None);
+    }
+}
@@ -0,0 +1,192 @@
+use anyhow::{Context, Result};
+use octocrab::Octocrab;
+use serenity::all::Http;
+use tracing::{info, warn};
+
+use crate::audit::{project_key, AuditAction, AuditEntry, AuditLog};
+use crate::config::{Config, Project};
+use crate::matrix::{MatrixClient, RoomEvent};
+use crate::rate_budget::RateBudgets;
+use crate::store::Store;
+
+/// Sync a Matrix project's room: file threads whose first line starts with a
+/// prefix as issues, and announce in the thread when its issue is closed.
+/// Threads are filed into the repository their prefix routes to.
+#[allow(clippy::too_many_arguments)]
+pub async fn sync_room(
+    discord: &Http,
+    config: &Config,
+    github: &Octocrab,
+    store: &Store,
+    audit: &AuditLog,
+    budgets: &RateBudgets,
+    project: &Project,
+    paused: bool,
+) -> Result<()> {
+    let matrix_config = config
+        .matrix
+        .as_ref()
+        .context("[matrix] is not configured")?;
+    let room_id = project
+        .matrix_room_id
+        .as_deref()
+        .context("Project has no matrix_room_id")?;
+    let matrix = MatrixClient::from_env(matrix_config)?;
+    let bot_user = matrix.whoami().await?;
+
+    let threads = matrix.threads(room_id).await?;
+    info!("Found {} threads in Matrix room {}", threads.len(), room_id);
+
+    let prefixes = project.prefixes();
+    for root in threads.iter().filter(|t| t.sender != bot_user) {
+        let Some(first_line) = root.body().and_then(|body| body.lines().next()) else {
+            continue;
+        };
+        let Some((_, title)) = crate::prefix::match_prefix(first_line, &prefixes) else {
+            continue;
+        };
+        if store
+            .matrix_thread_issue(room_id, &root.event_id)?
+            .is_some()
+        {
+            continue;
+        }
+        if paused {
+            info!("Would file Matrix thread {} (paused)", root.event_id);
+            continue;
+        }
+        let route = project.routed(&title);
+        if !budgets.try_take(&route, 1) {
+            info!(
+                "GitHub budget for {} used up; filing the remaining Matrix threads next cycle",
+                project_key(&route)
+            );
+            break;
+        }
+        if let Err(e) = file_thread(
+            discord, &matrix, github, store, audit, &route, room_id, root, &title, &bot_user,
+        )
+        .await
+        {
+            warn!("Failed to file Matrix thread {}: {}", root.event_id, e);
+        }
+    }
+
+    announce_closed_issues(
+        &matrix, github, store, audit, budgets, project, room_id, paused,
+    )
+    .await
+}
+
+/// Create the issue for a thread, then link back to it from the thread
+#[allow(clippy::too_many_arguments)]
+async fn file_thread(
+    discord: &Http,
+    matrix: &MatrixClient<'_>,
+    github: &Octocrab,
+    store: &Store,
+    audit: &AuditLog,
+    project: &Project,
+    room_id: &str,
+    root: &RoomEvent,
+    title: &str,
+    bot_user: &str,
+) -> Result<()> {
+    let replies = matrix.thread_messages(room_id, &root.event_id).await?;
+    let messages: Vec<(&str, &str)> = std::iter::once(root)
+        .chain(&replies)
+        .filter(|event| event.sender != bot_user)
+        .filter_map(|event| Some((event.sender.trim_start_matches('@'), event.body()?)))
+        .collect();
+    let thread_url = crate::matrix::thread_url(room_id, &root.event_id);
+    let issue = crate::bridge::create_issue(
+        discord,
+        github,
+        store,
+        project,
+        title,
+        &root.sender,
+        &thread_url,
+        &messages,
+    )
+    .await?;
+
+    // Linked before announcing, so a failed message can't file it twice
+    store.link_matrix_thread(room_id, &root.event_id, &project_key(project), issue.number)?;
+    info!(
+        "Created issue #{} from Matrix thread {}",
+        issue.number, root.event_id
+    );
+    audit
+        .record(
+            AuditEntry::new(project, AuditAction::IssueCreated)
+                .issue(issue.number)
+                .actor(&root.sender)
+                .details(format!("from Matrix thread {thread_url}")),
+        )
+        .await;
+
+    matrix
+        .send_to_thread(
+            room_id,
+            &root.event_id,
+            &format!(
+                "{}: {}",
+                crate::constants::MSG_ISSUE_CREATED,
+                issue.html_url
+            ),
+        )
+        .await
+}
+
+/// Tell each thread whose issue was closed. Matrix threads can't be locked,
+/// so they stay open.
+#[allow(clippy::too_many_arguments)]
+async fn announce_closed_issues(
+    matrix: &MatrixClient<'_>,
+    github: &Octocrab,
+    store: &Store,
+    audit: &AuditLog,
+    budgets: &RateBudgets,
+    project: &Project,
+    room_id: &str,
+    paused: bool,
+) -> Result<()> {
+    let links = store.open_matrix_threads(room_id)?;
+    for (root_event_id, route, issue) in
+        crate::bridge::closed_issues(github, budgets, project, links).await?
+    {
+        if paused {
+            info!(
+                "Would announce closed issue #{} in Matrix thread {} (paused)",
+                issue.number, root_event_id
+            );
+            continue;
+        }
+
+        let text = format!("{}: {}", crate::constants::MSG_ISSUE_CLOSED, issue.url);
+        if let Err(e) = matrix.send_to_thread(room_id, &root_event_id, &text).await {
+            warn!(
+                "Failed to announce closed issue #{} in Matrix thread {}: {}",
+                issue.number, root_event_id, e
+            );
+            continue;
+        }
+        store.close_matrix_thread(room_id, &root_event_id, chrono::Utc::now().timestamp())?;
+        info!(
+            "Announced closed issue #{} in Matrix thread {}",
+            issue.number, root_event_id
+        );
+        audit
+            .record(
+                AuditEntry::new(&route, AuditAction::ThreadArchived)
+                    .issue(issue.number)
+                    .details(format!(
+                        "issue closed on GitHub; Matrix thread {}",
+                        crate::matrix::thread_url(room_id, &root_event_id)
+                    )),
+            )
+            .await;
+    }
+    Ok(())
+}
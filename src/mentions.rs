@@ -0,0 +1,190 @@
+use anyhow::Result;
+use serenity::all::*;
+use std::time::Duration;
+use tracing::info;
+
+use crate::audit::AuditLog;
+use crate::config::Config;
+use crate::cooldown::CommandCooldowns;
+use crate::error::BotError;
+use crate::selection::MessageSelection;
+use crate::store::Store;
+
+/// What a message mentioning the bot asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MentionCommand {
+    /// `@CardiBot create issue`, like `/issue create`
+    CreateIssue,
+    /// `@CardiBot status`, the thread's line of `/project info`
+    Status,
+}
+
+/// The command in a message that starts by mentioning the bot, for clients
+/// where slash commands are awkward
+pub fn parse(content: &str, bot_id: UserId) -> Option<MentionCommand> {
+    let content = content.trim_start();
+    let rest = [format!("<@{bot_id}>"), format!("<@!{bot_id}>")]
+        .iter()
+        .find_map(|mention| content.strip_prefix(mention.as_str()))?;
+    let words: Vec<String> = rest
+        .split_whitespace()
+        .map(|w| {
+            w.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .collect();
+    match words.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["create"] | ["create", "issue"] | ["issue", "create"] => Some(MentionCommand::CreateIssue),
+        ["status"] => Some(MentionCommand::Status),
+        _ => None,
+    }
+}
+
+/// Answer a mention command in a thread. Runs the checks `/issue` does, so
+/// mentioning the bot grants nothing the slash command wouldn't.
+pub async fn handle_message(
+    http: &Http,
+    config: &Config,
+    store: &Store,
+    cooldowns: &CommandCooldowns,
+    audit: &AuditLog,
+    bot_id: UserId,
+    message: &Message,
+) -> Result<()> {
+    if message.author.bot {
+        return Ok(());
+    }
+    let (Some(command), Some(guild_id)) = (parse(&message.content, bot_id), message.guild_id)
+    else {
+        return Ok(());
+    };
+    info!(
+        "{} asked for {:?} by mention in {}",
+        message.author.name, command, message.channel_id
+    );
+
+    let content = match run(
+        http, config, store, cooldowns, audit, message, guild_id, command,
+    )
+    .await
+    {
+        Ok(content) => content,
+        Err(e) => format!("❌ {}", e.user_message()),
+    };
+    message
+        .channel_id
+        .send_message(
+            http,
+            CreateMessage::new()
+                .content(content)
+                .reference_message(message)
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run(
+    http: &Http,
+    config: &Config,
+    store: &Store,
+    cooldowns: &CommandCooldowns,
+    audit: &AuditLog,
+    message: &Message,
+    guild_id: GuildId,
+    command: MentionCommand,
+) -> Result<String, BotError> {
+    let roles = message
+        .member
+        .as_ref()
+        .map(|m| m.roles.as_slice())
+        .unwrap_or_default();
+    let (thread, project) =
+        crate::commands::issue_thread(http, config, store, message.channel_id, guild_id, roles)
+            .await?;
+
+    if command == MentionCommand::Status {
+        // Replies are public, so anonymous reporters stay hidden
+        return Ok(crate::project_info::thread_status(
+            &project, store, &thread, false,
+        )?);
+    }
+
+    let cooldown = Duration::from_secs(project.command_cooldown_seconds());
+    if let Some(remaining) = cooldowns.check_user(message.author.id.get(), cooldown) {
+        return Ok(format!(
+            "Please wait {} seconds before running this command again",
+            remaining.as_secs().max(1)
+        ));
+    }
+    let Some(_thread_guard) = cooldowns.begin_thread(thread.id.get()) else {
+        return Ok(crate::constants::MSG_THREAD_BUSY.to_string());
+    };
+    if !store.try_lock_thread(thread.id.get(), crate::constants::THREAD_LOCK_TTL_SECS)? {
+        return Ok(crate::constants::MSG_THREAD_BUSY.to_string());
+    }
+
+    let result = file(
+        http,
+        config,
+        &project,
+        &thread,
+        store,
+        audit,
+        &message.author.name,
+    )
+    .await;
+    store.unlock_thread(thread.id.get())?;
+    result
+}
+
+async fn file(
+    http: &Http,
+    config: &Config,
+    project: &crate::config::Project,
+    thread: &GuildChannel,
+    store: &Store,
+    audit: &AuditLog,
+    actor: &str,
+) -> Result<String, BotError> {
+    if let Some(reply) = crate::quota::check_thread(http, config, project, thread, store).await? {
+        return Ok(reply);
+    }
+    let filed = crate::filing::file_thread(
+        http,
+        config,
+        project,
+        thread,
+        store,
+        audit,
+        actor,
+        &MessageSelection::default(),
+        crate::progress::Progress::Silent,
+    )
+    .await?;
+    Ok(format!("✅ {}", filed.summary()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let bot = UserId::new(42);
+        assert_eq!(
+            parse("<@42> create issue", bot),
+            Some(MentionCommand::CreateIssue)
+        );
+        assert_eq!(
+            parse("  <@!42>  Create Issue!", bot),
+            Some(MentionCommand::CreateIssue)
+        );
+        assert_eq!(parse("<@42> status?", bot), Some(MentionCommand::Status));
+        assert_eq!(parse("<@7> status", bot), None);
+        assert_eq!(parse("thanks <@42> status", bot), None);
+        assert_eq!(parse("<@42> status of the fix?", bot), None);
+    }
+}
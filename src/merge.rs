@@ -0,0 +1,193 @@
+use anyhow::Result;
+use serenity::all::*;
+use tracing::info;
+
+use crate::audit::{project_key, AuditAction, AuditEntry, AuditLog};
+use crate::config::{Config, Project};
+use crate::store::Store;
+
+/// `into` option of `/issue merge`
+fn target_thread(command: &CommandInteraction) -> Option<ChannelId> {
+    let CommandDataOptionValue::SubCommand(options) = &command.data.options.first()?.value else {
+        return None;
+    };
+    options
+        .iter()
+        .find(|o| o.name == "into")
+        .and_then(|o| o.value.as_channel_id())
+}
+
+/// Handle `/issue merge <into>` run in thread B: B's replies go to thread A's
+/// issue from now on and B's own issue (if any) is closed. B stays open with
+/// a pointer to A, so its reporters can keep adding to the issue.
+pub async fn merge_thread(
+    http: &Http,
+    command: &CommandInteraction,
+    config: &Config,
+    project: &Project,
+    thread: &GuildChannel,
+    store: &Store,
+    audit: &AuditLog,
+) -> Result<()> {
+    let Some(target) = target_thread(command).filter(|id| *id != thread.id) else {
+        command
+            .edit_response(
+                http,
+                EditInteractionResponse::new()
+                    .content("Pick another thread to merge this one into"),
+            )
+            .await?;
+        return Ok(());
+    };
+
+    // Issue numbers only mean something in the repository they were filed in
+    if let Some(mapping) = store
+        .thread_issue(target.get())?
+        .filter(|m| m.project != project_key(project))
+    {
+        command
+            .edit_response(
+                http,
+                EditInteractionResponse::new().content(format!(
+                    "<#{target}> is tracked in {}, not {}; only threads of the same repository can be merged",
+                    mapping.project,
+                    project_key(project)
+                )),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let github = crate::github_app::create_project_client(config, project).await?;
+    let Some(target_issue) = linked_issue(&github, store, project, target).await? else {
+        command
+            .edit_response(
+                http,
+                EditInteractionResponse::new()
+                    .content(format!("<#{target}> has no linked GitHub issue yet")),
+            )
+            .await?;
+        return Ok(());
+    };
+
+    merge_into(
+        &github,
+        http,
+        project,
+        thread,
+        target,
+        target_issue,
+        store,
+        audit,
+        &command.user.name,
+    )
+    .await?;
+
+    command
+        .edit_response(
+            http,
+            EditInteractionResponse::new().content(format!(
+                "✅ Merged into <#{target}> (issue #{target_issue})"
+            )),
+        )
+        .await?;
+    Ok(())
+}
+
+/// The issue a thread is linked to, from the store or else GitHub
+pub async fn linked_issue(
+    github: &octocrab::Octocrab,
+    store: &Store,
+    project: &Project,
+    thread: ChannelId,
+) -> Result<Option<u64>> {
+    match store.thread_issue(thread.get())? {
+        Some(mapping) => Ok(Some(mapping.issue_number)),
+        None => Ok(
+            crate::github::find_issue_for_thread(github, project, thread.get())
+                .await?
+                .map(|issue| issue.number),
+        ),
+    }
+}
+
+/// Merge `thread` into `target`, whose issue is `target_issue`: the thread's
+/// own issue is closed as a duplicate and its replies go to the target's
+/// issue from now on. The thread stays open.
+#[allow(clippy::too_many_arguments)]
+pub async fn merge_into(
+    github: &octocrab::Octocrab,
+    http: &Http,
+    project: &Project,
+    thread: &GuildChannel,
+    target: ChannelId,
+    target_issue: u64,
+    store: &Store,
+    audit: &AuditLog,
+    actor: &str,
+) -> Result<()> {
+    // The thread's own issue is superseded by the target's
+    if let Some(own) = crate::github::find_issue_for_thread(github, project, thread.id.get())
+        .await?
+        .filter(|issue| issue.number != target_issue)
+    {
+        let issues = github.issues(&project.github_owner, &project.github_repo);
+        issues
+            .create_comment(
+                own.number,
+                format!("Merged into #{target_issue} by {actor} on Discord."),
+            )
+            .await?;
+        issues
+            .update(own.number)
+            .state(octocrab::models::IssueState::Closed)
+            .state_reason(octocrab::models::issues::IssueStateReason::Duplicate)
+            .send()
+            .await?;
+        audit
+            .record(
+                AuditEntry::new(project, AuditAction::IssueClosed)
+                    .thread(thread.id.get())
+                    .issue(own.number)
+                    .actor(actor)
+                    .details(format!("merged into #{target_issue}")),
+            )
+            .await;
+    }
+
+    crate::filing::link_duplicate_thread(github, http, project, thread, target_issue).await?;
+    // Only replies after the merge are mirrored
+    let cursor = if project.staging() {
+        thread
+            .last_message_id
+            .map_or(thread.id.get(), |id| id.get())
+    } else {
+        let pointer = thread
+            .id
+            .send_message(
+                http,
+                CreateMessage::new().content(format!(
+                    "🔀 This thread was merged into <#{target}>. Replies here are still added to issue #{target_issue}."
+                )),
+            )
+            .await?;
+        pointer.id.get()
+    };
+    store.set_mirror_cursor(thread.id.get(), cursor)?;
+    store.upsert_thread_issue(thread.id.get(), &project_key(project), target_issue)?;
+    store.merge_thread(thread.id.get(), target.get())?;
+    audit
+        .record(
+            AuditEntry::new(project, AuditAction::ThreadMerged)
+                .thread(thread.id.get())
+                .issue(target_issue)
+                .actor(actor)
+                .details(format!("into thread {target}")),
+        )
+        .await;
+    info!(
+        "Merged thread {} into thread {} (issue #{})",
+        thread.id, target, target_issue
+    );
+    Ok(())
+}
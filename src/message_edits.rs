@@ -0,0 +1,119 @@
+use anyhow::Result;
+use octocrab::models::CommentId;
+use serenity::http::Http;
+use serenity::model::id::{ChannelId, MessageId};
+use tracing::info;
+
+use crate::audit::{AuditAction, AuditEntry, AuditLog};
+use crate::config::Config;
+use crate::mirror::MirrorMessage;
+use crate::selection::MessageSelection;
+use crate::store::Store;
+
+/// Bring GitHub in line after a message in a linked thread was edited or
+/// deleted: a mirrored reply's comment is rewritten, and a change to a
+/// message in the issue's transcript refreshes it with the selection it was
+/// filed with. Deleted replies are replaced with a note so removed text
+/// doesn't stay public.
+pub async fn handle_message_change(
+    http: &Http,
+    config: &Config,
+    store: &Store,
+    audit: &AuditLog,
+    channel_id: ChannelId,
+    message_id: MessageId,
+) -> Result<()> {
+    let Some((thread, project)) =
+        crate::thread_events::resolve_thread(http, channel_id, config, store).await?
+    else {
+        return Ok(());
+    };
+    if !project.propagate_edits() {
+        return Ok(());
+    }
+
+    if let Some((issue_number, comment_id)) = store.mirrored_comment(message_id.get())? {
+        let hidden =
+            crate::anonymous::hidden_reporter(http, store, &project, thread.id.get()).await?;
+        let mut messages = Vec::new();
+        for (id, author) in store.comment_messages(comment_id)? {
+            let message = match channel_id.message(http, MessageId::new(id)).await {
+                Ok(message) => MirrorMessage::shown(&message, hidden),
+                // Any other failure leaves the comment as it is
+                Err(e) if !crate::error::is_discord_not_found(&e) => return Err(e.into()),
+                Err(_) => MirrorMessage {
+                    id,
+                    author,
+                    timestamp: 0,
+                    content: crate::constants::MSG_MIRRORED_MESSAGE_DELETED.to_string(),
+                    reply_to: None,
+                },
+            };
+            messages.push(message);
+        }
+        // The messages already shared a comment, so keep them together
+        let body = crate::mirror::group_messages(&messages, i64::MAX)
+            .into_iter()
+            .map(|comment| comment.body)
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let body = crate::redact::for_github(http, store, &project, thread.id.get(), &body).await;
+
+        let github = crate::github_app::create_project_client(config, &project).await?;
+        github
+            .issues(&project.github_owner, &project.github_repo)
+            .update_comment(CommentId(comment_id), body)
+            .await?;
+        info!(
+            "Updated comment {} on issue #{} after message {} changed",
+            comment_id, issue_number, message_id
+        );
+        return Ok(());
+    }
+
+    if store.thread_issue(thread.id.get())?.is_none() {
+        return Ok(());
+    }
+    // Issues filed before transcripts were recorded only know their opening post
+    let (selection, message_ids) = match store.issue_transcript(thread.id.get())? {
+        Some((selection, ids)) => (MessageSelection::decode(&selection), ids),
+        None => (
+            MessageSelection::default(),
+            vec![crate::discord_cache::starter_id(thread.id).get()],
+        ),
+    };
+    if !message_ids.contains(&message_id.get()) {
+        return Ok(());
+    }
+    let hidden = crate::anonymous::hidden_reporter(http, store, &project, thread.id.get()).await?;
+    let content = crate::filing::thread_issue_content(
+        http,
+        config,
+        &project,
+        &thread,
+        store,
+        &selection,
+        hidden,
+        crate::progress::Progress::Silent,
+    )
+    .await?;
+    let owner = crate::filing::issue_author(http, store, &project, &thread).await?;
+    let github = crate::github_app::create_project_client(config, &project).await?;
+    let result =
+        crate::github::create_or_update_issue(&github, &project, &thread, content.content, owner)
+            .await?;
+    store.set_issue_transcript(thread.id.get(), &selection.encode(), &content.message_ids)?;
+    info!(
+        "Refreshed issue #{} after message {} in its transcript changed",
+        result.issue.number, message_id
+    );
+    audit
+        .record(
+            AuditEntry::new(&project, AuditAction::IssueUpdated)
+                .thread(thread.id.get())
+                .issue(result.issue.number)
+                .details("transcript message edited or deleted on Discord"),
+        )
+        .await;
+    Ok(())
+}
@@ -0,0 +1,344 @@
+use regex::Regex;
+use serenity::all::*;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use crate::audit::{project_key, AuditAction, AuditEntry, AuditLog};
+use crate::config::{Config, Project};
+use crate::cooldown::CommandCooldowns;
+use crate::error::BotError;
+use crate::filing::MessageReport;
+use crate::progress::Progress;
+use crate::store::Store;
+
+static MESSAGE_LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^https://(?:(?:ptb|canary)\.)?discord(?:app)?\.com/channels/(\d+)/(\d+)/(\d+)/?$")
+        .expect("valid regex")
+});
+
+/// A message addressed by its `https://discord.com/channels/...` link
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageLink {
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub message_id: u64,
+}
+
+impl MessageLink {
+    pub fn parse(link: &str) -> Option<Self> {
+        let caps = MESSAGE_LINK.captures(link.trim())?;
+        Some(Self {
+            guild_id: caps[1].parse().ok()?,
+            channel_id: caps[2].parse().ok()?,
+            message_id: caps[3].parse().ok()?,
+        })
+    }
+
+    pub fn url(&self) -> String {
+        format!(
+            "https://discord.com/channels/{}/{}/{}",
+            self.guild_id, self.channel_id, self.message_id
+        )
+    }
+}
+
+/// The `from` option of `/issue create`, if given
+pub fn from_option(command: &CommandInteraction) -> Option<&str> {
+    let Some(CommandDataOptionValue::SubCommand(options)) =
+        command.data.options.first().map(|o| &o.value)
+    else {
+        return None;
+    };
+    options
+        .iter()
+        .find(|o| o.name == "from")
+        .and_then(|o| o.value.as_str())
+}
+
+/// File an issue from one message and the replies to it, wherever in the
+/// server it was posted, and answer the message with the issue link
+pub async fn file_from_message(
+    http: &Http,
+    command: &CommandInteraction,
+    config: &Config,
+    store: &Store,
+    cooldowns: &CommandCooldowns,
+    audit: &AuditLog,
+    link: &str,
+) -> Result<(), BotError> {
+    let link = MessageLink::parse(link)
+        .ok_or_else(|| BotError::User("`from` must be a Discord message link".to_string()))?;
+    if command.guild_id.map(|g| g.get()) != Some(link.guild_id) {
+        return Err(BotError::User(
+            "The message must be in this server".to_string(),
+        ));
+    }
+    let channel = crate::discord_cache::channel(http, ChannelId::new(link.channel_id))
+        .await
+        .ok()
+        .and_then(Channel::guild)
+        .ok_or_else(|| BotError::User("I can't read that message".to_string()))?;
+    if !can_read(http, command, &channel).await? {
+        return Err(BotError::User(
+            "You can't read the channel of that message".to_string(),
+        ));
+    }
+    let project = source_project(config, &channel).ok_or_else(|| {
+        BotError::User("That channel is not configured for issue tracking".to_string())
+    })?;
+    if !crate::priority::has_allowed_role(project, command.member.as_deref()) {
+        return Err(BotError::User(
+            "You don't have permission to create issues".to_string(),
+        ));
+    }
+    if let Some(number) = store.message_issue(link.message_id)? {
+        command
+            .edit_response(
+                http,
+                EditInteractionResponse::new()
+                    .content(format!("That message was already filed as issue #{number}")),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let cooldown = Duration::from_secs(project.command_cooldown_seconds());
+    if let Some(remaining) = cooldowns.check_user(command.user.id.get(), cooldown) {
+        command
+            .edit_response(
+                http,
+                EditInteractionResponse::new().content(format!(
+                    "Please wait {} seconds before running this command again",
+                    remaining.as_secs().max(1)
+                )),
+            )
+            .await?;
+        return Ok(());
+    }
+    // The message ID locks the report like a thread ID locks a thread
+    let Some(_message_guard) = cooldowns.begin_thread(link.message_id) else {
+        command
+            .edit_response(
+                http,
+                EditInteractionResponse::new().content(crate::constants::MSG_THREAD_BUSY),
+            )
+            .await?;
+        return Ok(());
+    };
+    if !store.try_lock_thread(link.message_id, crate::constants::THREAD_LOCK_TTL_SECS)? {
+        command
+            .edit_response(
+                http,
+                EditInteractionResponse::new().content(crate::constants::MSG_THREAD_BUSY),
+            )
+            .await?;
+        return Ok(());
+    }
+    let result =
+        file_message_locked(http, command, config, project, store, audit, &channel, link).await;
+    store.unlock_thread(link.message_id)?;
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn file_message_locked(
+    http: &Http,
+    command: &CommandInteraction,
+    config: &Config,
+    project: &Project,
+    store: &Store,
+    audit: &AuditLog,
+    channel: &GuildChannel,
+    link: MessageLink,
+) -> Result<(), BotError> {
+    // Another run may have filed it while this one waited for the lock
+    if let Some(number) = store.message_issue(link.message_id)? {
+        command
+            .edit_response(
+                http,
+                EditInteractionResponse::new()
+                    .content(format!("That message was already filed as issue #{number}")),
+            )
+            .await?;
+        return Ok(());
+    }
+    if crate::quota::is_full(config, project).await? {
+        return Err(BotError::User(crate::constants::MSG_QUOTA_FULL.to_string()));
+    }
+
+    let message = channel
+        .id
+        .message(http, MessageId::new(link.message_id))
+        .await
+        .map_err(|_| BotError::User("I can't read that message".to_string()))?;
+    let later = channel
+        .id
+        .messages(
+            http,
+            GetMessages::new()
+                .after(message.id)
+                .limit(crate::constants::THREAD_SELECTION_FETCH_LIMIT),
+        )
+        .await?;
+    let chain = reply_chain(&message, later);
+
+    let title = crate::title_suggestion::suggest_title(None, &message.content)
+        .unwrap_or_else(|| format!("Report from {}", message.author.name));
+    let project = &project.routed(&title);
+    let url = link.url();
+    let report = MessageReport {
+        channel,
+        messages: &chain,
+        title: &title,
+        url: &url,
+        // Only the reporter can hide themselves
+        anonymous: crate::anonymous::requested(command) && command.user.id == message.author.id,
+    };
+    let issue = crate::filing::file_message(
+        http,
+        config,
+        project,
+        &report,
+        store,
+        Progress::Command(http, command),
+    )
+    .await?;
+
+    let key = project_key(project);
+    store.record_message_issue(link.message_id, &key, issue.number)?;
+    audit
+        .record(
+            AuditEntry::new(project, AuditAction::IssueCreated)
+                .issue(issue.number)
+                .actor(&command.user.name)
+                .details(format!("from message {url}")),
+        )
+        .await;
+
+    channel
+        .id
+        .send_message(
+            http,
+            CreateMessage::new()
+                .reference_message(&message)
+                .embed(
+                    CreateEmbed::new()
+                        .title(crate::constants::MSG_ISSUE_CREATED)
+                        .description(format!("**Issue**: {}", issue.html_url))
+                        .field("Number", format!("#{}", issue.number), true)
+                        .color(crate::constants::COLOR_SUCCESS),
+                )
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
+
+    command
+        .edit_response(
+            http,
+            EditInteractionResponse::new().content(format!("✅ Created issue #{}", issue.number)),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Whether the member running the command can read the message's channel
+/// themselves; threads take their parent's permissions
+async fn can_read(
+    http: &Http,
+    command: &CommandInteraction,
+    channel: &GuildChannel,
+) -> Result<bool, BotError> {
+    let Some(member) = command.member.as_deref() else {
+        return Ok(false);
+    };
+    let channel = match channel
+        .parent_id
+        .filter(|_| channel.thread_metadata.is_some())
+    {
+        Some(parent_id) => match crate::discord_cache::channel(http, parent_id).await? {
+            Channel::Guild(parent) => parent,
+            _ => return Ok(false),
+        },
+        None => channel.clone(),
+    };
+    let guild = channel.guild_id.to_partial_guild(http).await?;
+    let granted = guild.user_permissions_in(&channel, member);
+    Ok(granted.contains(Permissions::VIEW_CHANNEL | Permissions::READ_MESSAGE_HISTORY))
+}
+
+/// The project a message belongs to: the forum's for a forum thread, or the
+/// guild's project that takes text channel reports
+pub fn source_project<'a>(config: &'a Config, channel: &GuildChannel) -> Option<&'a Project> {
+    let parent_id = channel
+        .parent_id
+        .filter(|_| channel.thread_metadata.is_some())
+        .unwrap_or(channel.id);
+    config
+        .find_thread_project(channel.guild_id.get(), parent_id.get())
+        .filter(|p| !p.archived())
+}
+
+/// The message followed by every later message that replies to it, directly
+/// or through another reply, oldest first
+fn reply_chain(message: &Message, later: Vec<Message>) -> Vec<Message> {
+    let mut later = later;
+    later.sort_by_key(|m| m.id);
+
+    let mut included: HashSet<MessageId> = HashSet::from([message.id]);
+    let mut chain = vec![message.clone()];
+    for m in later {
+        let replied_to = m.message_reference.as_ref().and_then(|r| r.message_id);
+        if replied_to.is_some_and(|id| included.contains(&id)) {
+            included.insert(m.id);
+            chain.push(m);
+        }
+    }
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_link() {
+        assert_eq!(
+            MessageLink::parse("https://discord.com/channels/1/22/333"),
+            Some(MessageLink {
+                guild_id: 1,
+                channel_id: 22,
+                message_id: 333
+            })
+        );
+        assert!(MessageLink::parse("https://ptb.discord.com/channels/1/22/333/").is_some());
+        assert!(MessageLink::parse("https://discord.com/channels/1/22").is_none());
+        assert!(MessageLink::parse("https://evil.example/channels/1/22/333").is_none());
+    }
+
+    #[test]
+    fn test_reply_chain() {
+        let message = |id: u64, reply_to: Option<u64>| {
+            let mut m = Message::default();
+            m.id = MessageId::new(id);
+            m.message_reference = reply_to.map(|to| {
+                serde_json::from_value(
+                    serde_json::json!({ "channel_id": "22", "message_id": to.to_string() }),
+                )
+                .unwrap()
+            });
+            m
+        };
+        let chain = reply_chain(
+            &message(1, None),
+            vec![
+                message(5, Some(4)),
+                message(4, Some(2)),
+                message(3, Some(9)),
+                message(2, Some(1)),
+            ],
+        );
+        let ids: Vec<u64> = chain.iter().map(|m| m.id.get()).collect();
+        assert_eq!(ids, vec![1, 2, 4, 5]);
+    }
+}
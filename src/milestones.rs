@@ -0,0 +1,249 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use octocrab::models::issues::Issue;
+use octocrab::models::{IssueState, Milestone};
+use octocrab::params;
+use octocrab::Octocrab;
+use serenity::builder::{CreateAllowedMentions, CreateEmbed, CreateMessage};
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use tracing::info;
+
+use crate::config::{MilestoneAnnouncementsConfig, Project};
+use crate::embeds::Embed;
+use crate::store::Store;
+
+const EVENT_DUE: &str = "due";
+/// Recorded under milestone 0, which GitHub never uses, once a project's
+/// existing milestones were seeded
+const EVENT_SEEDED: &str = "seeded";
+
+/// Events of a milestone that haven't been announced yet: each completion
+/// threshold reached, and `due` once the due date has passed
+pub fn pending_events(
+    milestone: &Milestone,
+    thresholds: &[u8],
+    announced: &[String],
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let percent = completion(milestone);
+    let mut events: Vec<String> = thresholds
+        .iter()
+        .filter(|t| percent >= **t as i64)
+        .map(|t| t.to_string())
+        .collect();
+    if milestone.due_on.is_some_and(|due| due <= now) {
+        events.push(EVENT_DUE.to_string());
+    }
+    events.retain(|e| !announced.contains(e));
+    events
+}
+
+fn completion(milestone: &Milestone) -> i64 {
+    let open = milestone.open_issues.unwrap_or(0);
+    let closed = milestone.closed_issues.unwrap_or(0);
+    if open + closed == 0 {
+        return 0;
+    }
+    closed * 100 / (open + closed)
+}
+
+/// Post a progress embed for milestones that reached a threshold or their due
+/// date, listing the Discord reports they addressed. The first run only
+/// records where existing milestones stand.
+pub async fn check_milestones(
+    github: &Octocrab,
+    discord: &Http,
+    store: &Store,
+    project: &Project,
+    config: &MilestoneAnnouncementsConfig,
+) -> Result<()> {
+    let channel = ChannelId::new(config.channel_id.parse()?);
+    let key = crate::audit::project_key(project);
+    let now = Utc::now();
+
+    let milestones: Vec<Milestone> = github
+        .get(
+            format!(
+                "/repos/{}/{}/milestones",
+                project.github_owner, project.github_repo
+            ),
+            Some(&serde_json::json!({ "state": "all", "sort": "due_on", "per_page": 100 })),
+        )
+        .await?;
+
+    let seeded = store
+        .milestone_announcements(&key, 0)?
+        .iter()
+        .any(|e| e == EVENT_SEEDED);
+    for milestone in milestones {
+        // Long-closed milestones are past announcing
+        if milestone.closed_at.is_some_and(|closed| {
+            (now - closed).num_seconds() > crate::constants::MILESTONE_RECENT_SECS
+        }) {
+            continue;
+        }
+        let number = milestone.number as u64;
+        let announced = store.milestone_announcements(&key, number)?;
+        let events = pending_events(&milestone, &config.thresholds(), &announced, now);
+        if events.is_empty() {
+            continue;
+        }
+        if !seeded {
+            for event in &events {
+                store.record_milestone_announcement(&key, number, event)?;
+            }
+            continue;
+        }
+
+        let issues = github
+            .issues(&project.github_owner, &project.github_repo)
+            .list()
+            .milestone(number)
+            .state(params::State::All)
+            .per_page(100)
+            .send()
+            .await?
+            .items;
+        let reports: Vec<&Issue> = issues
+            .iter()
+            .filter(|i| i.pull_request.is_none() && crate::sync::issue_thread_id(i).is_some())
+            .collect();
+
+        // Only milestones with community reports are worth announcing
+        if !reports.is_empty() {
+            channel
+                .send_message(
+                    discord,
+                    CreateMessage::new()
+                        .embed(progress_embed(&milestone, &reports, &events))
+                        .allowed_mentions(CreateAllowedMentions::new()),
+                )
+                .await?;
+            info!(
+                "Announced milestone '{}' ({}) for {}",
+                milestone.title,
+                events.join(", "),
+                key
+            );
+        }
+        for event in &events {
+            store.record_milestone_announcement(&key, number, event)?;
+        }
+    }
+    if !seeded {
+        store.record_milestone_announcement(&key, 0, EVENT_SEEDED)?;
+        info!(
+            "Recorded the current milestones of {} without announcing",
+            key
+        );
+    }
+
+    Ok(())
+}
+
+fn progress_embed(milestone: &Milestone, reports: &[&Issue], events: &[String]) -> CreateEmbed {
+    let addressed: Vec<&&Issue> = reports
+        .iter()
+        .filter(|i| matches!(i.state, IssueState::Closed))
+        .collect();
+
+    let headline = if events.iter().any(|e| e == EVENT_DUE) {
+        format!("**{}** has reached its due date", milestone.title)
+    } else {
+        format!(
+            "**{}** is {}% complete",
+            milestone.title,
+            completion(milestone)
+        )
+    };
+    let mut lines: Vec<String> = addressed
+        .iter()
+        .take(crate::constants::MILESTONE_MAX_LISTED)
+        .map(|issue| {
+            let thread = crate::sync::issue_thread_id(issue)
+                .map(|id| format!(" · <#{id}>"))
+                .unwrap_or_default();
+            format!(
+                "• [#{}]({}) {}{}",
+                issue.number, issue.html_url, issue.title, thread
+            )
+        })
+        .collect();
+    if addressed.len() > crate::constants::MILESTONE_MAX_LISTED {
+        lines.push(format!(
+            "…and {} more",
+            addressed.len() - crate::constants::MILESTONE_MAX_LISTED
+        ));
+    }
+    let description = if lines.is_empty() {
+        headline
+    } else {
+        format!(
+            "{headline}\n\nCommunity reports addressed:\n{}",
+            lines.join("\n")
+        )
+    };
+
+    let mut embed = Embed::new()
+        .title(crate::constants::MSG_MILESTONE_PROGRESS)
+        .url(milestone.html_url.as_str())
+        .description(crate::sanitize::sanitize_for_discord(&description))
+        .field(
+            "Community reports",
+            format!("{} of {} addressed", addressed.len(), reports.len()),
+            true,
+        )
+        .color(crate::constants::COLOR_SUCCESS);
+    if let Some(due) = milestone.due_on {
+        embed = embed.field("Due", format!("<t:{}:D>", due.timestamp()), true);
+    }
+    embed.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn milestone(open: i64, closed: i64, due_on: Option<DateTime<Utc>>) -> Milestone {
+        serde_json::from_value(serde_json::json!({
+            "url": "https://api.github.com/repos/o/r/milestones/1",
+            "html_url": "https://github.com/o/r/milestone/1",
+            "id": 1,
+            "node_id": "M_1",
+            "number": 1,
+            "title": "v1.0",
+            "open_issues": open,
+            "closed_issues": closed,
+            "created_at": "2025-01-01T00:00:00Z",
+            "due_on": due_on,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_pending_events() {
+        let now = Utc::now();
+        let thresholds = [50, 100];
+
+        assert!(pending_events(&milestone(3, 1, None), &thresholds, &[], now).is_empty());
+        assert_eq!(
+            pending_events(&milestone(2, 2, None), &thresholds, &[], now),
+            vec!["50"]
+        );
+        assert_eq!(
+            pending_events(
+                &milestone(0, 4, None),
+                &thresholds,
+                &["50".to_string()],
+                now
+            ),
+            vec!["100"]
+        );
+
+        let overdue = milestone(3, 1, Some(now - chrono::Duration::days(1)));
+        assert_eq!(pending_events(&overdue, &thresholds, &[], now), vec!["due"]);
+        assert!(pending_events(&overdue, &thresholds, &["due".to_string()], now).is_empty());
+        assert!(pending_events(&milestone(0, 0, None), &thresholds, &[], now).is_empty());
+    }
+}
@@ -0,0 +1,223 @@
+use anyhow::Result;
+use octocrab::Octocrab;
+use serenity::builder::GetMessages;
+use serenity::http::Http;
+use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, MessageId, UserId};
+use tracing::{debug, info};
+
+use crate::config::Project;
+use crate::store::Store;
+
+/// A Discord message reduced to what the GitHub comment needs
+#[derive(Debug, Clone)]
+pub struct MirrorMessage {
+    pub id: u64,
+    pub author: String,
+    pub timestamp: i64,
+    pub content: String,
+    /// Author and text of the message this one replies to
+    pub reply_to: Option<(String, String)>,
+}
+
+impl From<&Message> for MirrorMessage {
+    fn from(msg: &Message) -> Self {
+        Self {
+            id: msg.id.get(),
+            author: msg.author.name.clone(),
+            timestamp: msg.timestamp.unix_timestamp(),
+            content: msg.content.clone(),
+            reply_to: msg
+                .referenced_message
+                .as_ref()
+                .map(|r| (r.author.name.clone(), r.content.clone())),
+        }
+    }
+}
+
+impl MirrorMessage {
+    /// The message as GitHub shows it, with the anonymous reporter's name
+    /// hidden wherever it appears
+    pub fn shown(msg: &Message, hidden: Option<UserId>) -> Self {
+        let mut reply = Self::from(msg);
+        reply.author =
+            crate::anonymous::display_name(msg.author.id, &reply.author, hidden).to_string();
+        if let (Some((author, _)), Some(r)) = (&mut reply.reply_to, &msg.referenced_message) {
+            *author = crate::anonymous::display_name(r.author.id, author, hidden).to_string();
+        }
+        reply
+    }
+}
+
+/// Post new thread replies to the linked issue, one comment per burst of
+/// messages from the same author
+pub async fn mirror_replies(
+    github: &Octocrab,
+    discord: &Http,
+    store: &Store,
+    project: &Project,
+    issue_number: u64,
+    thread_id: u64,
+) -> Result<()> {
+    let channel_id = ChannelId::new(thread_id);
+
+    // Start from the newest message the first time so history isn't replayed
+    let Some(cursor) = store.mirror_cursor(thread_id)? else {
+        let latest = channel_id
+            .messages(discord, GetMessages::new().limit(1))
+            .await?;
+        if let Some(msg) = latest.first() {
+            store.set_mirror_cursor(thread_id, msg.id.get())?;
+        }
+        return Ok(());
+    };
+
+    let mut messages = channel_id
+        .messages(
+            discord,
+            GetMessages::new()
+                .after(MessageId::new(cursor))
+                .limit(crate::constants::DISCORD_MESSAGE_FETCH_LIMIT),
+        )
+        .await?;
+    if messages.is_empty() {
+        return Ok(());
+    }
+    messages.sort_by_key(|m| m.id);
+    let newest = messages.last().map(|m| m.id.get()).unwrap_or(cursor);
+
+    let hidden = crate::anonymous::hidden_reporter(discord, store, project, thread_id).await?;
+    let replies: Vec<MirrorMessage> = messages
+        .iter()
+        .filter(|m| !m.author.bot && !m.content.trim().is_empty())
+        .map(|m| MirrorMessage::shown(m, hidden))
+        .collect();
+
+    let comments = group_messages(&replies, crate::constants::MIRROR_GROUP_WINDOW_SECS);
+    let issues = github.issues(&project.github_owner, &project.github_repo);
+    for comment in &comments {
+        let body =
+            crate::redact::for_github(discord, store, project, thread_id, &comment.body).await;
+        let posted = issues.create_comment(issue_number, body).await?;
+        // Kept so later edits and deletions on Discord can follow
+        for (id, author) in &comment.messages {
+            store.record_mirrored_message(*id, author, issue_number, posted.id.0)?;
+        }
+        // Advanced per comment, so a failure further on doesn't post this
+        // one again
+        if let Some((last, _)) = comment.messages.last() {
+            store.set_mirror_cursor(thread_id, *last)?;
+        }
+    }
+
+    // Bot and empty messages after the last reply are skipped too
+    store.set_mirror_cursor(thread_id, newest)?;
+
+    if comments.is_empty() {
+        debug!("No new replies to mirror from thread {}", thread_id);
+    } else {
+        info!(
+            "Mirrored {} messages from thread {} to issue #{} as {} comments",
+            replies.len(),
+            thread_id,
+            issue_number,
+            comments.len()
+        );
+    }
+    Ok(())
+}
+
+/// One GitHub comment's worth of thread replies
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MirrorComment {
+    pub body: String,
+    /// IDs and authors of the messages in `body`
+    pub messages: Vec<(u64, String)>,
+}
+
+/// Merge consecutive messages by the same author sent within `window_secs` of
+/// each other into one comment body, quoting the message each reply answers
+pub fn group_messages(messages: &[MirrorMessage], window_secs: i64) -> Vec<MirrorComment> {
+    let mut groups: Vec<(&MirrorMessage, Vec<&MirrorMessage>, Vec<String>)> = Vec::new();
+    let mut last_timestamp = i64::MIN;
+
+    for msg in messages {
+        let continues = groups.last().is_some_and(|(first, _, _)| {
+            first.author == msg.author && msg.timestamp - last_timestamp <= window_secs
+        });
+        last_timestamp = msg.timestamp;
+
+        let mut part = String::new();
+        if let Some((author, content)) = &msg.reply_to {
+            let first_line = content.lines().next().unwrap_or_default();
+            part.push_str(&format!("> **@{author}**: {first_line}\n\n"));
+        }
+        match crate::code_blocks::preserve_code(&msg.content) {
+            Some(formatted) => part.push_str(&formatted),
+            None => part.push_str(&msg.content),
+        }
+
+        match groups.last_mut() {
+            Some((_, included, parts)) if continues => {
+                included.push(msg);
+                parts.push(part);
+            }
+            _ => groups.push((msg, vec![msg], vec![part])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(first, included, parts)| MirrorComment {
+            body: format!(
+                "**@{}** on Discord:\n\n{}",
+                first.author,
+                parts.join("\n\n")
+            ),
+            messages: included.iter().map(|m| (m.id, m.author.clone())).collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(author: &str, timestamp: i64, content: &str) -> MirrorMessage {
+        MirrorMessage {
+            id: timestamp as u64,
+            author: author.to_string(),
+            timestamp,
+            content: content.to_string(),
+            reply_to: None,
+        }
+    }
+
+    #[test]
+    fn test_group_messages() {
+        let mut answer = msg("bob", 130, "Which version?");
+        answer.reply_to = Some(("alice".to_string(), "It crashes\nwhen saving".to_string()));
+
+        let comments = group_messages(
+            &[
+                msg("alice", 0, "It crashes"),
+                msg("alice", 60, "when saving"),
+                answer,
+                msg("alice", 1000, "v2.1"),
+            ],
+            120,
+        );
+
+        assert_eq!(comments.len(), 3);
+        assert_eq!(
+            comments[0].body,
+            "**@alice** on Discord:\n\nIt crashes\n\nwhen saving"
+        );
+        let ids = |c: &MirrorComment| c.messages.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+        assert_eq!(ids(&comments[0]), vec![0, 60]);
+        assert!(comments[1]
+            .body
+            .contains("> **@alice**: It crashes\n\nWhich version?"));
+        assert!(comments[2].body.ends_with("v2.1"));
+    }
+}
@@ -0,0 +1,193 @@
+use anyhow::Result;
+use octocrab::models::issues::Issue;
+use octocrab::Octocrab;
+use serenity::all::audit_log::{Action, Change, ThreadAction};
+use serenity::all::{GuildChannel, Http, User};
+use tracing::{info, warn};
+
+use crate::audit::{AuditAction, AuditEntry, AuditLog};
+use crate::config::Project;
+use crate::store::Store;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Locked,
+    Archived,
+}
+
+impl Kind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Locked => "locked",
+            Self::Archived => "archived",
+        }
+    }
+}
+
+/// What a thread update in the audit log did, a lock winning over an archive
+pub fn closing_change(changes: &[Change]) -> Option<Kind> {
+    let set = |kind: Kind| {
+        changes.iter().any(|change| match (kind, change) {
+            (Kind::Locked, Change::Locked { new, .. }) => *new == Some(true),
+            (Kind::Archived, Change::Archived { new, .. }) => *new == Some(true),
+            _ => false,
+        })
+    };
+    [Kind::Locked, Kind::Archived].into_iter().find(|k| set(*k))
+}
+
+/// The person behind the latest lock or archive of the thread, from the
+/// guild audit log; None when it was a bot (this one included)
+async fn find_moderator(http: &Http, thread: &GuildChannel) -> Result<Option<(Kind, User)>> {
+    let logs = thread
+        .guild_id
+        .audit_logs(
+            http,
+            Some(Action::Thread(ThreadAction::Update)),
+            None,
+            None,
+            Some(crate::constants::MODERATOR_AUDIT_LOG_LIMIT),
+        )
+        .await?;
+    let now = chrono::Utc::now().timestamp();
+
+    // Entries come newest first
+    let Some((entry, kind)) = logs
+        .entries
+        .iter()
+        .filter(|e| e.target_id.map(|t| t.get()) == Some(thread.id.get()))
+        .filter(|e| {
+            now - e.id.created_at().unix_timestamp()
+                <= crate::constants::MODERATOR_AUDIT_LOG_WINDOW_SECS
+        })
+        .find_map(|e| closing_change(e.changes.as_deref().unwrap_or_default()).map(|k| (e, k)))
+    else {
+        return Ok(None);
+    };
+    Ok(logs
+        .users
+        .get(&entry.user_id)
+        .filter(|user| !user.bot)
+        .map(|user| (kind, user.clone())))
+}
+
+/// Attribute a lock or archive of a linked thread to the moderator who made
+/// it, so the syncer leaves the thread alone, and return their name. Needs
+/// the View Audit Log permission; without it the change stays unattributed.
+#[allow(clippy::too_many_arguments)]
+pub async fn attribute(
+    http: &Http,
+    store: &Store,
+    audit: &AuditLog,
+    project: &Project,
+    github: &Octocrab,
+    issue: &Issue,
+    thread: &GuildChannel,
+    locked: bool,
+) -> Option<String> {
+    let current = if locked { Kind::Locked } else { Kind::Archived };
+    // Gateway updates repeat for unrelated edits; the first sighting is enough
+    match store.moderator_action(thread.id.get()) {
+        Ok(Some(recorded)) if recorded.action == current.as_str() => {
+            return Some(recorded.moderator)
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Failed to read moderator action for {}: {}", thread.id, e),
+    }
+
+    let (kind, moderator) = match find_moderator(http, thread).await {
+        Ok(found) => found?,
+        Err(e) => {
+            warn!(
+                "Could not read the audit log for thread {}: {}",
+                thread.id, e
+            );
+            return None;
+        }
+    };
+    if let Err(e) = record(
+        store, audit, project, github, issue, thread, kind, &moderator,
+    )
+    .await
+    {
+        warn!(
+            "Failed to record moderator action on thread {}: {}",
+            thread.id, e
+        );
+    }
+    Some(moderator.name)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn record(
+    store: &Store,
+    audit: &AuditLog,
+    project: &Project,
+    github: &Octocrab,
+    issue: &Issue,
+    thread: &GuildChannel,
+    kind: Kind,
+    moderator: &User,
+) -> Result<()> {
+    store.record_moderator_action(
+        thread.id.get(),
+        kind.as_str(),
+        moderator.id.get(),
+        &moderator.name,
+    )?;
+    if let Some(label) = &project.moderator_closed_label {
+        github
+            .issues(&project.github_owner, &project.github_repo)
+            .add_labels(issue.number, std::slice::from_ref(label))
+            .await?;
+    }
+    let action = match kind {
+        Kind::Locked => AuditAction::ThreadLocked,
+        Kind::Archived => AuditAction::ThreadArchived,
+    };
+    audit
+        .record(
+            AuditEntry::new(project, action)
+                .thread(thread.id.get())
+                .issue(issue.number)
+                .actor(&moderator.name)
+                .details("by a moderator on Discord"),
+        )
+        .await;
+    info!(
+        "Thread {} was {} by moderator {}",
+        thread.id,
+        kind.as_str(),
+        moderator.name
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closing_change() {
+        let archived = Change::Archived {
+            old: Some(false),
+            new: Some(true),
+        };
+        let locked = Change::Locked {
+            old: Some(false),
+            new: Some(true),
+        };
+        let unarchived = Change::Archived {
+            old: Some(true),
+            new: Some(false),
+        };
+
+        assert_eq!(
+            closing_change(std::slice::from_ref(&archived)),
+            Some(Kind::Archived)
+        );
+        assert_eq!(closing_change(&[archived, locked]), Some(Kind::Locked));
+        assert_eq!(closing_change(&[unarchived]), None);
+        assert_eq!(closing_change(&[]), None);
+    }
+}
@@ -0,0 +1,272 @@
+use anyhow::Result;
+use octocrab::models::issues::{Issue, IssueStateReason};
+use octocrab::models::IssueState;
+use serenity::all::*;
+use tracing::{info, warn};
+
+use crate::audit::{project_key, AuditAction, AuditEntry, AuditLog};
+use crate::config::{Config, Project};
+use crate::selection::MessageSelection;
+use crate::store::Store;
+
+/// `project` option of `/issue move`
+fn target_name(command: &CommandInteraction) -> Option<&str> {
+    let CommandDataOptionValue::SubCommand(options) = &command.data.options.first()?.value else {
+        return None;
+    };
+    options
+        .iter()
+        .find(|o| o.name == "project")
+        .and_then(|o| o.value.as_str())
+}
+
+/// The project a thread's issue was moved to, when it is not `project`
+pub fn moved_to(
+    config: &Config,
+    store: &Store,
+    project: &Project,
+    thread_id: u64,
+) -> Result<Option<Project>> {
+    let Some(linked) = store.thread_issue(thread_id)? else {
+        return Ok(None);
+    };
+    if linked.project.eq_ignore_ascii_case(&project_key(project)) {
+        return Ok(None);
+    }
+    Ok(config.find_route(&linked.project))
+}
+
+/// GitHub only transfers issues between repositories of the same owner, and
+/// the bot needs one client that can see both
+fn can_transfer(from: &Project, to: &Project) -> bool {
+    from.github_owner.eq_ignore_ascii_case(&to.github_owner)
+        && from.github_account == to.github_account
+        && from.github_api_url == to.github_api_url
+}
+
+/// The old issue's title and body without the thread markers, so nothing
+/// follows it back to the thread once it is closed
+fn without_markers(issue: &Issue, thread_id: u64) -> (String, String) {
+    let title = crate::mappings::strip_title_marker(&issue.title, thread_id)
+        .unwrap_or_else(|| issue.title.clone());
+    let body = issue
+        .body
+        .as_deref()
+        .unwrap_or_default()
+        .replace(&crate::sync::thread_marker(thread_id), "")
+        .trim_end()
+        .to_string();
+    (title, body)
+}
+
+async fn reply(http: &Http, command: &CommandInteraction, content: &str) -> Result<()> {
+    command
+        .edit_response(http, EditInteractionResponse::new().content(content))
+        .await?;
+    Ok(())
+}
+
+/// Handle `/issue move <project>`: transfer the thread's issue to another
+/// configured repository, or file it there and close the old one when GitHub
+/// can't transfer it, then point the thread at the new issue
+pub async fn move_thread(
+    http: &Http,
+    command: &CommandInteraction,
+    config: &Config,
+    project: &Project,
+    thread: &GuildChannel,
+    store: &Store,
+    audit: &AuditLog,
+) -> Result<()> {
+    if !crate::split::is_maintainer(command.member.as_deref()) {
+        return reply(
+            http,
+            command,
+            "Only maintainers (Manage Threads) can move a thread",
+        )
+        .await;
+    }
+    let name = target_name(command).unwrap_or_default();
+    let Some(target) = config.find_route(name) else {
+        return reply(
+            http,
+            command,
+            &format!("No project or repository named `{name}` is configured"),
+        )
+        .await;
+    };
+    let target_key = project_key(&target);
+    if target_key == project_key(project) {
+        return reply(
+            http,
+            command,
+            &format!("This thread's issue is already in {target_key}"),
+        )
+        .await;
+    }
+
+    let github = crate::github_app::create_project_client(config, project).await?;
+    let Some(issue) =
+        crate::github::find_issue_for_thread(&github, project, thread.id.get()).await?
+    else {
+        return reply(http, command, crate::constants::MSG_NO_LINKED_ISSUE).await;
+    };
+
+    let moved = if can_transfer(project, &target) {
+        let number = crate::graphql::transfer_issue(&github, &target, &issue.node_id).await?;
+        let moved = github
+            .issues(&target.github_owner, &target.github_repo)
+            .get(number)
+            .await?;
+        if !project.staging() {
+            thread
+                .id
+                .send_message(
+                    http,
+                    CreateMessage::new().embed(
+                        CreateEmbed::new()
+                            .title(crate::constants::MSG_ISSUE_MOVED)
+                            .description(format!("**Issue**: {}", moved.html_url))
+                            .field("Number", format!("#{}", moved.number), true)
+                            .field("Status", "Open", true)
+                            .color(crate::constants::COLOR_SUCCESS),
+                    ),
+                )
+                .await?;
+        }
+        moved
+    } else {
+        let result = crate::filing::file_issue(
+            http,
+            config,
+            &target,
+            thread,
+            store,
+            audit,
+            &command.user.name,
+            &MessageSelection::default(),
+            crate::progress::Progress::Command(http, command),
+        )
+        .await?;
+
+        let issues = github.issues(&project.github_owner, &project.github_repo);
+        issues
+            .create_comment(
+                issue.number,
+                format!(
+                    "Moved to {}, by {} on Discord.",
+                    result.issue.html_url, command.user.name
+                ),
+            )
+            .await?;
+        let (title, body) = without_markers(&issue, thread.id.get());
+        issues
+            .update(issue.number)
+            .title(&title)
+            .body(&body)
+            .state(IssueState::Closed)
+            .state_reason(IssueStateReason::NotPlanned)
+            .send()
+            .await?;
+        result.issue
+    };
+
+    store.upsert_thread_issue(thread.id.get(), &target_key, moved.number)?;
+    audit
+        .record(
+            AuditEntry::new(&target, AuditAction::IssueMoved)
+                .thread(thread.id.get())
+                .issue(moved.number)
+                .actor(&command.user.name)
+                .details(format!("from {}#{}", project_key(project), issue.number)),
+        )
+        .await;
+    if target.pinned_summary() {
+        let summary = crate::summary::IssueSummary::from_issue(&moved);
+        if let Err(e) = crate::summary::update_summary(http, store, thread.id.get(), &summary).await
+        {
+            warn!(
+                "Failed to update summary for issue #{}: {}",
+                moved.number, e
+            );
+        }
+    }
+    info!(
+        "Moved issue for thread {} from {}#{} to {}#{}",
+        thread.id,
+        project_key(project),
+        issue.number,
+        target_key,
+        moved.number
+    );
+
+    reply(
+        http,
+        command,
+        &format!(
+            "✅ Moved #{} to {target_key}#{}",
+            issue.number, moved.number
+        ),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(toml: &str) -> Project {
+        toml::from_str(&format!(
+            "discord_guild_id = \"1\"\ndiscord_forum_id = \"2\"\n{toml}"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_can_transfer() {
+        let app = project("github_owner = \"acme\"\ngithub_repo = \"app\"");
+        let docs = project("github_owner = \"Acme\"\ngithub_repo = \"docs\"");
+        let other = project("github_owner = \"other\"\ngithub_repo = \"app\"");
+        let enterprise = project(
+            "github_owner = \"acme\"\ngithub_repo = \"docs\"\ngithub_account = \"enterprise\"",
+        );
+
+        assert!(can_transfer(&app, &docs));
+        assert!(!can_transfer(&app, &other));
+        assert!(!can_transfer(&app, &enterprise));
+    }
+
+    #[test]
+    fn test_moved_to() {
+        let config: Config = toml::from_str(
+            r#"
+            [[projects]]
+            name = "App"
+            discord_guild_id = "1"
+            discord_forum_id = "2"
+            github_owner = "acme"
+            github_repo = "app"
+
+            [[projects]]
+            discord_guild_id = "1"
+            discord_forum_id = "3"
+            github_owner = "acme"
+            github_repo = "web"
+            "#,
+        )
+        .unwrap();
+        let store = Store::open_in_memory().unwrap();
+        let app = &config.projects[0];
+
+        assert!(moved_to(&config, &store, app, 10).unwrap().is_none());
+        store.upsert_thread_issue(10, "acme/app", 4).unwrap();
+        assert!(moved_to(&config, &store, app, 10).unwrap().is_none());
+        store.upsert_thread_issue(10, "acme/web", 7).unwrap();
+        let moved = moved_to(&config, &store, app, 10).unwrap().unwrap();
+        assert_eq!(project_key(&moved), "acme/web");
+        assert_eq!(
+            config.find_route("app").map(|p| project_key(&p)).as_deref(),
+            Some("acme/app")
+        );
+    }
+}
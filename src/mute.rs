@@ -0,0 +1,112 @@
+use anyhow::Result;
+use serenity::all::*;
+use tracing::{info, warn};
+
+use crate::store::Store;
+
+/// `mute` and `unmute` subcommands of `/cardibot`, open to everyone
+pub fn subcommands() -> [CreateCommandOption; 2] {
+    let everywhere = |action: &str| {
+        CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "everywhere",
+            format!("{action} in every thread, not just this one"),
+        )
+    };
+    [
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "mute",
+            "Stop the bot from pinging you in this thread",
+        )
+        .add_sub_option(everywhere("Stop pinging you")),
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "unmute",
+            "Let the bot ping you again in this thread",
+        )
+        .add_sub_option(everywhere("Ping you again")),
+    ]
+}
+
+/// `everywhere` option of `/cardibot mute` and `/cardibot unmute`
+fn everywhere(command: &CommandInteraction) -> bool {
+    let Some(CommandDataOptionValue::SubCommand(options)) =
+        command.data.options.first().map(|o| &o.value)
+    else {
+        return false;
+    };
+    options
+        .iter()
+        .find(|o| o.name == "everywhere")
+        .and_then(|o| o.value.as_bool())
+        .unwrap_or(false)
+}
+
+/// The user to ping, unless they muted the bot for this thread or everywhere.
+/// A failed lookup pings anyway rather than dropping the notification.
+pub fn unmuted(store: &Store, user: Option<UserId>, thread_id: Option<u64>) -> Option<UserId> {
+    let user = user?;
+    match store.is_muted(user.get(), thread_id) {
+        Ok(true) => None,
+        Ok(false) => Some(user),
+        Err(e) => {
+            warn!("Failed to read mute setting of {}: {}", user, e);
+            Some(user)
+        }
+    }
+}
+
+/// Handle `/cardibot mute` and `/cardibot unmute`. Outside a thread the
+/// setting always applies everywhere.
+pub async fn handle_mute_command(
+    http: &Http,
+    command: &CommandInteraction,
+    store: &Store,
+    mute: bool,
+) -> Result<()> {
+    let in_thread = match crate::discord_cache::channel(http, command.channel_id).await {
+        Ok(Channel::Guild(channel)) => channel.thread_metadata.is_some(),
+        Ok(_) => false,
+        Err(e) => {
+            warn!("Failed to fetch channel {}: {}", command.channel_id, e);
+            false
+        }
+    };
+    let thread_id = (in_thread && !everywhere(command)).then(|| command.channel_id.get());
+    let user_id = command.user.id.get();
+
+    let content = if mute {
+        store.mute_user(user_id, thread_id)?;
+        info!(
+            "{} muted the bot {}",
+            command.user.name,
+            thread_id.map_or("everywhere".to_string(), |id| format!("in thread {id}"))
+        );
+        match thread_id {
+            Some(_) => "🔕 The bot won't ping you in this thread anymore",
+            None => "🔕 The bot won't ping you anywhere anymore",
+        }
+    } else if store.unmute_user(user_id, thread_id)? {
+        match thread_id {
+            Some(_) => "🔔 The bot can ping you in this thread again",
+            None => "🔔 The bot can ping you again",
+        }
+    } else if thread_id.is_some() && store.is_muted(user_id, thread_id)? {
+        "You muted the bot everywhere; use `/cardibot unmute everywhere:true` to undo that"
+    } else {
+        "You haven't muted the bot here"
+    };
+
+    command
+        .create_response(
+            http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+    Ok(())
+}
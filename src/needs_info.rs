@@ -0,0 +1,328 @@
+use anyhow::Result;
+use serenity::all::*;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::audit::{AuditAction, AuditEntry, AuditLog};
+use crate::config::{Config, NeedsInfoConfig, Project};
+use crate::store::Store;
+
+const ANSWER_PREFIX: &str = "needs_info:answer:";
+const MODAL_PREFIX: &str = "needs_info_modal:";
+const QUESTION_INPUT_PREFIX: &str = "q";
+
+/// Discord allows 45 characters per input label
+const MAX_LABEL_CHARS: usize = 45;
+
+/// Post the project's questionnaire in a thread, pinging the reporter
+pub async fn post_questionnaire(
+    discord: &Http,
+    store: &Store,
+    needs_info: &NeedsInfoConfig,
+    thread_id: ChannelId,
+    issue_number: u64,
+) -> Result<()> {
+    let owner = crate::discord_cache::channel(discord, thread_id)
+        .await?
+        .guild()
+        .and_then(|thread| thread.owner_id);
+    let owner = crate::mute::unmuted(store, owner, Some(thread_id.get()));
+
+    let questions = needs_info.questions();
+    let description = questions
+        .iter()
+        .enumerate()
+        .map(|(i, q)| format!("{}. {q}", i + 1))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut content = crate::constants::MSG_NEEDS_INFO.to_string();
+    if let Some(owner) = owner {
+        content = format!("<@{owner}> {content}");
+    }
+
+    thread_id
+        .send_message(
+            discord,
+            CreateMessage::new()
+                .content(content)
+                .embed(
+                    CreateEmbed::new()
+                        .description(description)
+                        .color(crate::constants::COLOR_SUCCESS),
+                )
+                .components(vec![CreateActionRow::Buttons(vec![CreateButton::new(
+                    format!("{ANSWER_PREFIX}{issue_number}"),
+                )
+                .label("Answer")
+                .emoji('📝')
+                .style(ButtonStyle::Primary)])])
+                .allowed_mentions(CreateAllowedMentions::new().users(owner)),
+        )
+        .await?;
+
+    info!(
+        "Posted needs-info questionnaire for issue #{} in thread {}",
+        issue_number, thread_id
+    );
+    Ok(())
+}
+
+/// A maintainer reacting with the configured emoji asks for more information
+pub async fn handle_reaction(
+    http: &Http,
+    reaction: &Reaction,
+    config: &Arc<Config>,
+    store: &Store,
+) -> Result<()> {
+    let Some((thread, project)) =
+        crate::thread_events::resolve_thread(http, reaction.channel_id, config, store).await?
+    else {
+        return Ok(());
+    };
+    let Some(needs_info) = &project.needs_info else {
+        return Ok(());
+    };
+
+    let emoji_matches = matches!(
+        &reaction.emoji,
+        ReactionType::Unicode(emoji) if *emoji == needs_info.emoji()
+    );
+    let is_maintainer = project.allowed_role_id.is_some()
+        && crate::priority::has_allowed_role(&project, reaction.member.as_ref());
+    if !emoji_matches || !is_maintainer {
+        return Ok(());
+    }
+
+    let Some(mapping) = store.thread_issue(thread.id.get())? else {
+        return Ok(());
+    };
+    // Once per thread, however many maintainers react
+    if !store.mark_needs_info_prompted(thread.id.get())? {
+        return Ok(());
+    }
+    post_questionnaire(http, store, needs_info, thread.id, mapping.issue_number).await
+}
+
+pub fn is_needs_info_component(custom_id: &str) -> bool {
+    custom_id.starts_with(ANSWER_PREFIX)
+}
+
+pub fn is_needs_info_modal(custom_id: &str) -> bool {
+    custom_id.starts_with(MODAL_PREFIX)
+}
+
+pub async fn handle_component(
+    http: &Http,
+    component: &ComponentInteraction,
+    config: &Arc<Config>,
+    store: &Store,
+) -> Result<()> {
+    let Some(issue_number) = component.data.custom_id.strip_prefix(ANSWER_PREFIX) else {
+        return Ok(());
+    };
+    let Some((_, project)) =
+        crate::thread_events::resolve_thread(http, component.channel_id, config, store).await?
+    else {
+        return Ok(());
+    };
+    let Some(needs_info) = &project.needs_info else {
+        return Ok(());
+    };
+
+    let inputs = needs_info
+        .questions()
+        .iter()
+        .enumerate()
+        .map(|(i, question)| {
+            CreateActionRow::InputText(
+                CreateInputText::new(
+                    InputTextStyle::Paragraph,
+                    input_label(question),
+                    format!("{QUESTION_INPUT_PREFIX}{i}"),
+                )
+                .required(false),
+            )
+        })
+        .collect();
+
+    component
+        .create_response(
+            http,
+            CreateInteractionResponse::Modal(
+                CreateModal::new(format!("{MODAL_PREFIX}{issue_number}"), "More information")
+                    .components(inputs),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+pub async fn handle_modal(
+    http: &Http,
+    modal: &ModalInteraction,
+    config: &Arc<Config>,
+    store: &Store,
+    audit: &AuditLog,
+) -> Result<()> {
+    let Some(issue_number) = modal
+        .data
+        .custom_id
+        .strip_prefix(MODAL_PREFIX)
+        .and_then(|n| n.parse::<u64>().ok())
+    else {
+        return Ok(());
+    };
+    let Some((thread, project)) =
+        crate::thread_events::resolve_thread(http, modal.channel_id, config, store).await?
+    else {
+        return Ok(());
+    };
+    let Some(needs_info) = &project.needs_info else {
+        return Ok(());
+    };
+
+    let questions = needs_info.questions();
+    let answers: Vec<(String, String)> = modal
+        .data
+        .components
+        .iter()
+        .flat_map(|row| row.components.iter())
+        .filter_map(|component| match component {
+            ActionRowComponent::InputText(input) => {
+                let index: usize = input
+                    .custom_id
+                    .strip_prefix(QUESTION_INPUT_PREFIX)?
+                    .parse()
+                    .ok()?;
+                let answer = input.value.clone()?;
+                Some((questions.get(index)?.clone(), answer))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let Some(comment) = format_answers(&modal.user.name, &answers) else {
+        modal
+            .create_response(
+                http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content("Please answer at least one question")
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+        return Ok(());
+    };
+
+    modal.defer(http).await?;
+    let comment = crate::redact::for_github(http, store, &project, thread.id.get(), &comment).await;
+    let github = crate::github_app::create_project_client(config, &project).await?;
+    let issues = github.issues(&project.github_owner, &project.github_repo);
+    issues.create_comment(issue_number, comment).await?;
+    // The label may have been added by hand or not at all
+    let _ = issues.remove_label(issue_number, needs_info.label()).await;
+    store.clear_needs_info_prompt(thread.id.get())?;
+
+    audit
+        .record(
+            AuditEntry::new(&project, AuditAction::IssueUpdated)
+                .thread(thread.id.get())
+                .issue(issue_number)
+                .actor(modal.user.name.clone())
+                .details("needs-info answers"),
+        )
+        .await;
+    info!(
+        "Added needs-info answers from {} to issue #{}",
+        modal.user.name, issue_number
+    );
+
+    modal
+        .create_followup(
+            http,
+            CreateInteractionResponseFollowup::new().content(format!(
+                "📝 Thanks <@{}>, your answers were added to issue #{issue_number}",
+                modal.user.id
+            )),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Sync hook: post the questionnaire once when the needs-info label appears
+pub async fn check_label(
+    discord: &Http,
+    store: &Store,
+    project: &Project,
+    issue: &octocrab::models::issues::Issue,
+    thread_id: u64,
+) -> Result<()> {
+    let Some(needs_info) = &project.needs_info else {
+        return Ok(());
+    };
+    let labeled = issue.labels.iter().any(|l| l.name == needs_info.label());
+    if labeled && store.mark_needs_info_prompted(thread_id)? {
+        post_questionnaire(
+            discord,
+            store,
+            needs_info,
+            ChannelId::new(thread_id),
+            issue.number,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+fn input_label(question: &str) -> String {
+    if question.chars().count() <= MAX_LABEL_CHARS {
+        question.to_string()
+    } else {
+        let truncated: String = question.chars().take(MAX_LABEL_CHARS - 1).collect();
+        format!("{truncated}…")
+    }
+}
+
+/// Issue comment with the answered questions, or None if nothing was answered
+pub fn format_answers(user: &str, answers: &[(String, String)]) -> Option<String> {
+    let answered: Vec<String> = answers
+        .iter()
+        .filter(|(_, answer)| !answer.trim().is_empty())
+        .map(|(question, answer)| format!("**{question}**\n{}", answer.trim()))
+        .collect();
+    if answered.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "📝 **@{user}** answered on Discord:\n\n{}",
+        answered.join("\n\n")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_answers() {
+        let answers = vec![
+            ("Version".to_string(), " 1.2.3 ".to_string()),
+            ("Wallet".to_string(), "".to_string()),
+        ];
+        assert_eq!(
+            format_answers("ada", &answers).unwrap(),
+            "📝 **@ada** answered on Discord:\n\n**Version**\n1.2.3"
+        );
+        assert_eq!(
+            format_answers("ada", &[("Version".to_string(), " ".to_string())]),
+            None
+        );
+        assert_eq!(
+            input_label(&"x".repeat(50)).chars().count(),
+            MAX_LABEL_CHARS
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use serenity::builder::{CreateAllowedMentions, CreateMessage};
+use serenity::http::Http;
+use serenity::model::channel::GuildChannel;
+use serenity::model::id::{ChannelId, RoleId};
+use tracing::warn;
+
+use crate::config::Project;
+use crate::embeds::Embed;
+
+/// Let maintainers know about a newly created issue, either in a triage
+/// channel or by pinging their role in the thread itself.
+///
+/// Failures are logged; the issue has already been created at this point.
+pub async fn announce_new_issue(
+    discord: &Http,
+    project: &Project,
+    thread: &GuildChannel,
+    issue: &octocrab::models::issues::Issue,
+    author: &str,
+) {
+    let role = project
+        .notify_role_id
+        .as_deref()
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(RoleId::new);
+    let triage_channel = project
+        .triage_channel_id
+        .as_deref()
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(ChannelId::new);
+
+    let (channel, message) = match (triage_channel, role) {
+        (Some(channel), role) => {
+            let labels = issue
+                .labels
+                .iter()
+                .map(|l| l.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let embed = Embed::new()
+                .title(crate::constants::MSG_NEW_ISSUE)
+                .description(format!("**{}**\n{}", issue.title, issue.html_url))
+                .field("Thread", format!("<#{}>", thread.id), true)
+                .field("Reported by", author, true)
+                .field(
+                    "Labels",
+                    if labels.is_empty() { "none" } else { &labels },
+                    true,
+                )
+                .color(crate::constants::COLOR_SUCCESS);
+
+            let mut message = CreateMessage::new().embed(embed.build());
+            if let Some(role) = role {
+                message = message.content(format!("<@&{role}>"));
+            }
+            (channel, message)
+        }
+        (None, Some(role)) => (
+            thread.id,
+            CreateMessage::new().content(format!(
+                "<@&{role}> new issue #{} is ready for triage",
+                issue.number
+            )),
+        ),
+        (None, None) => return,
+    };
+
+    let message = message
+        .allowed_mentions(CreateAllowedMentions::new().roles(role.into_iter().collect::<Vec<_>>()));
+    if let Err(e) = channel.send_message(discord, message).await {
+        warn!(
+            "Failed to announce issue #{} in channel {}: {}",
+            issue.number, channel, e
+        );
+    }
+}
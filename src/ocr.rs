@@ -0,0 +1,104 @@
+use anyhow::{Context, Result};
+use serenity::builder::GetMessages;
+use serenity::model::channel::{Attachment, GuildChannel};
+use tracing::{debug, warn};
+
+use crate::config::OcrConfig;
+
+/// Run screenshots attached to the thread through the configured OCR service.
+///
+/// Returns a collapsible Markdown section for the issue body, or None when no
+/// text was found. Individual failures are logged and skipped.
+pub async fn screenshot_text(
+    ocr: &OcrConfig,
+    discord: &serenity::http::Http,
+    thread: &GuildChannel,
+) -> Option<String> {
+    let messages = match thread
+        .messages(
+            discord,
+            GetMessages::new().limit(crate::constants::GITHUB_THREAD_CONTENT_LIMIT),
+        )
+        .await
+    {
+        Ok(messages) => messages,
+        Err(e) => {
+            warn!("Failed to fetch messages for OCR in {}: {}", thread.id, e);
+            return None;
+        }
+    };
+
+    let images = messages
+        .iter()
+        .rev()
+        .flat_map(|m| m.attachments.iter())
+        .filter(|a| is_image(a))
+        .take(crate::constants::OCR_MAX_IMAGES);
+
+    let client = reqwest::Client::new();
+    let mut sections = Vec::new();
+    for attachment in images {
+        match recognize(&client, ocr, attachment).await {
+            Ok(text) if !text.trim().is_empty() => {
+                sections.push(format!(
+                    "**{}**\n```\n{}\n```",
+                    attachment.filename,
+                    text.trim()
+                ));
+            }
+            Ok(_) => debug!("No text found in {}", attachment.filename),
+            Err(e) => warn!("OCR failed for {}: {}", attachment.filename, e),
+        }
+    }
+
+    if sections.is_empty() {
+        return None;
+    }
+
+    let mut text = sections.join("\n\n");
+    if text.len() > crate::constants::OCR_MAX_TEXT_CHARS {
+        let mut end = crate::constants::OCR_MAX_TEXT_CHARS;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push_str("\n```\n*(truncated)*");
+    }
+
+    Some(format!(
+        "<details>\n<summary>Text extracted from screenshots</summary>\n\n{text}\n\n</details>"
+    ))
+}
+
+fn is_image(attachment: &Attachment) -> bool {
+    attachment
+        .content_type
+        .as_deref()
+        .is_some_and(|t| t.starts_with("image/"))
+}
+
+/// POST the raw image to the OCR endpoint, which answers with plain text
+async fn recognize(
+    client: &reqwest::Client,
+    ocr: &OcrConfig,
+    attachment: &Attachment,
+) -> Result<String> {
+    let image = attachment
+        .download()
+        .await
+        .context("Failed to download attachment")?;
+
+    let mut request = client
+        .post(&ocr.endpoint)
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            attachment.content_type.as_deref().unwrap_or("image/png"),
+        )
+        .body(image);
+    if let Ok(key) = std::env::var("OCR_API_KEY") {
+        request = request.bearer_auth(key);
+    }
+
+    let response = request.send().await?.error_for_status()?;
+    Ok(response.text().await?)
+}
@@ -0,0 +1,123 @@
+use anyhow::Result;
+use serenity::all::*;
+use tracing::{debug, info};
+
+use crate::audit::project_key;
+use crate::config::Project;
+use crate::embeds::Embed;
+use crate::store::Store;
+
+/// Explain the GitHub bridge to a member opening their first prefixed thread
+/// in the project. The message goes by DM, or to the thread with a mention
+/// when their DMs are closed.
+pub async fn welcome_reporter(
+    http: &Http,
+    store: &Store,
+    project: &Project,
+    thread: &GuildChannel,
+) -> Result<()> {
+    let Some(onboarding) = &project.onboarding else {
+        return Ok(());
+    };
+    // Production already welcomes the same members
+    if project.staging() {
+        return Ok(());
+    }
+    let Some(owner_id) = thread.owner_id else {
+        return Ok(());
+    };
+    // Muted members get neither the DM nor the ping, and aren't recorded so
+    // they're welcomed once they unmute
+    if crate::mute::unmuted(store, Some(owner_id), Some(thread.id.get())).is_none() {
+        return Ok(());
+    }
+    let key = project_key(project);
+    if !store.record_onboarding(&key, owner_id.get())? {
+        return Ok(());
+    }
+    // Checked once per member, as bots stay recorded too
+    if owner_id.to_user(http).await?.bot {
+        return Ok(());
+    }
+
+    let text = render(
+        onboarding.message(),
+        &project.routed(&thread.name),
+        owner_id.get(),
+        thread.guild_id.get(),
+        thread.id.get(),
+    );
+    let embed = Embed::new()
+        .description(text)
+        .color(crate::constants::COLOR_SUCCESS);
+
+    let dm = owner_id
+        .direct_message(http, CreateMessage::new().embed(embed.clone().build()))
+        .await;
+    if let Err(e) = dm {
+        debug!("Couldn't DM {} the onboarding message: {}", owner_id, e);
+        let message = CreateMessage::new()
+            .content(format!("<@{owner_id}>"))
+            .allowed_mentions(CreateAllowedMentions::new().users([owner_id]));
+        if let Err(e) = embed.send(http, thread.id, message).await {
+            // Another thread of theirs gets to try again
+            store.forget_onboarding(&key, owner_id.get())?;
+            return Err(e.into());
+        }
+    }
+    info!("Sent the onboarding message of {} to {}", key, owner_id);
+    Ok(())
+}
+
+/// Fill in the template's placeholders; `project` is routed to the thread's
+/// repository
+fn render(
+    template: &str,
+    project: &Project,
+    user_id: u64,
+    guild_id: u64,
+    thread_id: u64,
+) -> String {
+    let name = project.name.as_deref().unwrap_or(&project.github_repo);
+    let repo_url = format!(
+        "{}/{}/{}",
+        project.github_web_url(),
+        project.github_owner,
+        project.github_repo
+    );
+    let thread_url = format!("https://discord.com/channels/{guild_id}/{thread_id}");
+    template
+        .replace("{user}", &format!("<@{user_id}>"))
+        .replace("{project}", name)
+        .replace("{repo_url}", &repo_url)
+        .replace("{thread_url}", &thread_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let project: Project = toml::from_str(
+            r#"
+            name = "Wallet"
+            discord_guild_id = "1"
+            discord_forum_id = "2"
+            github_owner = "acme"
+            github_repo = "wallet"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            render(
+                "Hi {user}! {project} files {thread_url} into {repo_url}",
+                &project,
+                7,
+                1,
+                3
+            ),
+            "Hi <@7>! Wallet files https://discord.com/channels/1/3 into https://github.com/acme/wallet"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use tracing::{error, info, warn};
+
+use crate::audit::project_key;
+use crate::config::{Config, IssueTarget, Project};
+use crate::github_app::InstallationGrant;
+
+/// Permissions every repository's installation needs, as (permission, level)
+const REQUIRED_PERMISSIONS: &[(&str, &str)] = &[("issues", "write"), ("metadata", "read")];
+
+/// What a route's installation is missing, one line per problem
+pub fn problems(grant: &InstallationGrant, repository: &str, discussions: bool) -> Vec<String> {
+    let mut required = REQUIRED_PERMISSIONS.to_vec();
+    if discussions {
+        required.push(("discussions", "write"));
+    }
+
+    let mut problems: Vec<String> = required
+        .into_iter()
+        .filter(|(permission, level)| !grant.allows(permission, level))
+        .map(|(permission, level)| format!("{permission}: {level} not granted"))
+        .collect();
+    if !grant.covers(repository) {
+        problems.push("repository not selected for the installation".to_string());
+    }
+    problems
+}
+
+/// Whether threads with a Discussions prefix are filed into this route
+fn files_discussions(project: &Project, route: &Project) -> bool {
+    let key = project_key(route);
+    project.prefixes().iter().any(|prefix| {
+        prefix.target == IssueTarget::Discussions
+            && project_key(&project.routed(&prefix.prefix)) == key
+    })
+}
+
+/// Check at startup that each GitHub App installation can reach and write
+/// issues in the repositories it serves, instead of meeting 403s mid-sync.
+/// Problems are logged as errors, and fail startup with
+/// `require_github_permissions`. Token credentials are left to
+/// `validate-config --live`.
+pub async fn check_github_permissions(config: &Config) -> Result<()> {
+    let mut grants: HashMap<(Option<String>, Option<String>), Option<InstallationGrant>> =
+        HashMap::new();
+    let mut failures = Vec::new();
+    let mut checked = 0;
+
+    for project in &config.projects {
+        for route in project.routes() {
+            let key = (route.github_account.clone(), route.github_api_url());
+            if !grants.contains_key(&key) {
+                let grant = match crate::github_app::project_app(config, &route) {
+                    Ok(Some(app)) => match app.installation_grant().await {
+                        Ok(grant) => Some(grant),
+                        Err(e) => {
+                            warn!("Could not read the GitHub App installation: {}", e);
+                            None
+                        }
+                    },
+                    Ok(None) => None,
+                    Err(e) => {
+                        warn!("Could not load GitHub App credentials: {}", e);
+                        None
+                    }
+                };
+                grants.insert(key.clone(), grant);
+            }
+            let Some(grant) = &grants[&key] else {
+                continue;
+            };
+
+            let repository = project_key(&route);
+            checked += 1;
+            for problem in problems(grant, &repository, files_discussions(project, &route)) {
+                error!(
+                    "GitHub App installation {} for {}: {}",
+                    grant.installation_id, repository, problem
+                );
+                failures.push(format!("{repository}: {problem}"));
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        if checked > 0 {
+            info!("GitHub App permissions cover all {} repositories", checked);
+        }
+        return Ok(());
+    }
+    if config.require_github_permissions() {
+        anyhow::bail!(
+            "GitHub App installation is missing access: {}",
+            failures.join("; ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grant(permissions: &[(&str, &str)], repositories: Option<&[&str]>) -> InstallationGrant {
+        InstallationGrant {
+            installation_id: 1,
+            permissions: permissions
+                .iter()
+                .map(|(p, l)| (p.to_string(), l.to_string()))
+                .collect(),
+            repositories: repositories.map(|r| r.iter().map(|n| n.to_string()).collect()),
+        }
+    }
+
+    #[test]
+    fn test_problems() {
+        let full = grant(&[("issues", "write"), ("metadata", "read")], None);
+        assert!(problems(&full, "acme/app", false).is_empty());
+        assert_eq!(
+            problems(&full, "acme/app", true),
+            vec!["discussions: write not granted"]
+        );
+
+        let read_only = grant(
+            &[("issues", "read"), ("metadata", "read")],
+            Some(&["Acme/App"]),
+        );
+        assert_eq!(
+            problems(&read_only, "acme/app", false),
+            vec!["issues: write not granted"]
+        );
+        assert_eq!(
+            problems(&read_only, "acme/docs", false),
+            vec![
+                "issues: write not granted",
+                "repository not selected for the installation"
+            ]
+        );
+    }
+}
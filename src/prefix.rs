@@ -0,0 +1,175 @@
+use anyhow::Result;
+use serenity::builder::EditThread;
+use serenity::http::Http;
+use serenity::model::channel::GuildChannel;
+use tracing::info;
+
+use crate::audit::{AuditAction, AuditEntry, AuditLog};
+use crate::config::{PrefixConfig, Project};
+
+/// The configured prefix a title starts with, tolerating the usual variants:
+/// case (`[bug]`), bracket style (`(BUG)`, `BUG:`) and a single typo
+/// (`[FEATRUE]`). Returns the prefix and the title in canonical form.
+pub fn match_prefix<'a>(
+    title: &str,
+    prefixes: &'a [PrefixConfig],
+) -> Option<(&'a PrefixConfig, String)> {
+    let title = title.trim_start();
+    if let Some(exact) = prefixes.iter().find(|p| title.starts_with(&p.prefix)) {
+        return Some((exact, title.to_string()));
+    }
+
+    let (marker, rest) = leading_marker(title)?;
+    let marker = marker.to_uppercase();
+    let prefix = prefixes
+        .iter()
+        .find(|p| prefix_word(&p.prefix) == marker)
+        .or_else(|| {
+            prefixes.iter().find(|p| {
+                let word = prefix_word(&p.prefix);
+                word.chars().count() >= 4 && within_one_edit(&word, &marker)
+            })
+        })?;
+
+    let rest = rest.trim_start();
+    let canonical = if rest.is_empty() {
+        prefix.prefix.clone()
+    } else {
+        format!("{} {}", prefix.prefix, rest)
+    };
+    Some((prefix, canonical))
+}
+
+/// Rename a thread titled with a prefix variant to the canonical form when the
+/// project opts in
+pub async fn normalize_thread_name(
+    discord: &Http,
+    project: &Project,
+    thread: &GuildChannel,
+    audit: &AuditLog,
+) -> Result<()> {
+    if !project.rename_prefix_variants() {
+        return Ok(());
+    }
+    let Some(canonical) = project.canonical_title(&thread.name) else {
+        return Ok(());
+    };
+    // Discord caps thread names at 100 characters
+    let canonical = crate::sanitize::truncate_for_discord(&canonical, 100);
+
+    thread
+        .id
+        .edit_thread(discord, EditThread::new().name(&canonical))
+        .await?;
+    crate::discord_cache::invalidate_channel(thread.id);
+    info!("Renamed thread {} to '{}'", thread.id, canonical);
+    audit
+        .record(
+            AuditEntry::new(project, AuditAction::ThreadRenamed)
+                .thread(thread.id.get())
+                .details(format!("'{}' -> '{}'", thread.name, canonical)),
+        )
+        .await;
+    Ok(())
+}
+
+/// `[BUG]` -> `BUG`
+fn prefix_word(prefix: &str) -> String {
+    prefix
+        .trim_matches(|c: char| matches!(c, '[' | ']' | '(' | ')' | ':') || c.is_whitespace())
+        .to_uppercase()
+}
+
+/// Leading `[word]`, `(word)` or `word:` and the rest of the title
+fn leading_marker(title: &str) -> Option<(&str, &str)> {
+    let (marker, rest) = if let Some(inner) = title.strip_prefix('[') {
+        inner.split_once(']')?
+    } else if let Some(inner) = title.strip_prefix('(') {
+        inner.split_once(')')?
+    } else {
+        title.split_once(':')?
+    };
+    let marker = marker.trim();
+    let is_word = !marker.is_empty()
+        && marker
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    is_word.then_some((marker, rest))
+}
+
+/// At most one insertion, deletion, substitution or adjacent swap apart
+fn within_one_edit(a: &str, b: &str) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > 1 {
+        return false;
+    }
+    let start = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    if start == a.len() && start == b.len() {
+        return true;
+    }
+    let (a, b) = (&a[start..], &b[start..]);
+    match a.len().cmp(&b.len()) {
+        std::cmp::Ordering::Equal => {
+            a[1..] == b[1..] || (a.len() >= 2 && a[0] == b[1] && a[1] == b[0] && a[2..] == b[2..])
+        }
+        std::cmp::Ordering::Greater => a[1..] == *b,
+        std::cmp::Ordering::Less => *a == b[1..],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::IssueTarget;
+
+    fn prefix(prefix: &str, label: &str) -> PrefixConfig {
+        PrefixConfig {
+            prefix: prefix.to_string(),
+            label: label.to_string(),
+            tag: None,
+            target: IssueTarget::default(),
+            discussion_category: None,
+            github_repo: None,
+        }
+    }
+
+    #[test]
+    fn test_match_prefix() {
+        let prefixes = vec![prefix("[BUG]", "bug"), prefix("[FEATURE]", "enhancement")];
+        let canonical =
+            |title: &str| match_prefix(title, &prefixes).map(|(p, t)| (p.label.clone(), t));
+
+        assert_eq!(
+            canonical("[BUG] App crashes"),
+            Some(("bug".to_string(), "[BUG] App crashes".to_string()))
+        );
+        for variant in ["[bug] App crashes", "(BUG) App crashes", "Bug: App crashes"] {
+            assert_eq!(
+                canonical(variant),
+                Some(("bug".to_string(), "[BUG] App crashes".to_string())),
+                "{variant}"
+            );
+        }
+        assert_eq!(
+            canonical("[Featrue] Dark mode"),
+            Some(("enhancement".to_string(), "[FEATURE] Dark mode".to_string()))
+        );
+
+        // Short prefixes need an exact word; sentences with colons aren't markers
+        assert_eq!(canonical("[BUY] Something"), None);
+        assert_eq!(canonical("Question about sync: is it slow?"), None);
+        assert_eq!(canonical("App crashes [BUG]"), None);
+    }
+
+    #[test]
+    fn test_within_one_edit() {
+        assert!(within_one_edit("FEATURE", "FEATURE"));
+        assert!(within_one_edit("FEATURE", "FEATRUE"));
+        assert!(within_one_edit("FEATURE", "FEATURES"));
+        assert!(within_one_edit("FEATURE", "FETURE"));
+        assert!(within_one_edit("FEATURE", "FEATUXE"));
+        assert!(!within_one_edit("FEATURE", "FAETRUE"));
+        assert!(!within_one_edit("FEATURE", "FEAT"));
+    }
+}
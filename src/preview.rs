@@ -0,0 +1,201 @@
+use anyhow::Result;
+use serenity::all::*;
+
+use crate::audit::{project_key, AuditLog};
+use crate::config::{Config, Project};
+use crate::embeds::Embed;
+use crate::progress::Progress;
+use crate::selection::MessageSelection;
+use crate::store::Store;
+
+const CONFIRM_PREFIX: &str = "issue_preview:confirm:";
+const CANCEL_PREFIX: &str = "issue_preview:cancel:";
+
+/// `preview` option of `/issue create`
+pub fn requested(command: &CommandInteraction) -> bool {
+    let Some(CommandDataOptionValue::SubCommand(options)) =
+        command.data.options.first().map(|o| &o.value)
+    else {
+        return false;
+    };
+    options
+        .iter()
+        .find(|o| o.name == "preview")
+        .and_then(|o| o.value.as_bool())
+        .unwrap_or(false)
+}
+
+/// The issue `/issue create` would file, with buttons to go ahead or drop
+/// it. Nothing is sent to GitHub until it is confirmed; an anonymous request
+/// is only recorded then.
+#[allow(clippy::too_many_arguments)]
+pub async fn response(
+    http: &Http,
+    config: &Config,
+    project: &Project,
+    thread: &GuildChannel,
+    store: &Store,
+    selection: &MessageSelection,
+    anonymous: bool,
+    progress: Progress<'_>,
+) -> Result<EditInteractionResponse> {
+    let hidden = match thread.owner_id.filter(|_| anonymous) {
+        Some(owner) => Some(owner),
+        None => crate::anonymous::hidden_reporter(http, store, project, thread.id.get()).await?,
+    };
+    let content = crate::filing::thread_issue_content(
+        http, config, project, thread, store, selection, hidden, progress,
+    )
+    .await?;
+    let author = match hidden {
+        Some(_) => None,
+        None => Some(crate::filing::thread_owner_name(http, thread).await),
+    };
+    let discord_url = format!(
+        "https://discord.com/channels/{}/{}",
+        thread.guild_id, thread.id
+    );
+    let body = project.render_issue_body(&content.content, &discord_url, author.as_deref());
+    let existing = store.thread_issue(thread.id.get())?;
+
+    let encoded = selection.encode();
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{CONFIRM_PREFIX}{}:{encoded}", u8::from(anonymous)))
+            .label(if existing.is_some() {
+                "Update issue"
+            } else {
+                "Create issue"
+            })
+            .style(ButtonStyle::Success),
+        CreateButton::new(CANCEL_PREFIX)
+            .label("Cancel")
+            .style(ButtonStyle::Secondary),
+    ]);
+    Ok(EditInteractionResponse::new()
+        .content("This is the issue as it would be filed. Create it as is, or cancel, fix the thread and run the command again.")
+        .embed(embed(project, thread, &body, existing.map(|m| m.issue_number)))
+        .components(vec![buttons]))
+}
+
+fn embed(
+    project: &Project,
+    thread: &GuildChannel,
+    body: &str,
+    existing: Option<u64>,
+) -> CreateEmbed {
+    let title = crate::github::issue_title(project, &thread.name, thread.id.get());
+    let labels = project.labels_for_title(&thread.name);
+    let labels = if labels.is_empty() {
+        "None".to_string()
+    } else {
+        labels
+            .iter()
+            .map(|l| format!("`{l}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let issue = match existing {
+        Some(number) => format!("Updates #{number}"),
+        None => "New issue".to_string(),
+    };
+
+    Embed::new()
+        .title(title)
+        .description(body)
+        .field("Repository", project_key(project), true)
+        .field("Labels", labels, true)
+        .field("Issue", issue, true)
+        .color(crate::constants::COLOR_SUCCESS)
+        .build()
+}
+
+pub fn is_preview_component(custom_id: &str) -> bool {
+    custom_id.starts_with(CONFIRM_PREFIX) || custom_id.starts_with(CANCEL_PREFIX)
+}
+
+/// File the previewed thread with the selection the command was run with, or
+/// drop the preview. The preview is ephemeral, so only the person who ran
+/// `/issue create` can press these.
+pub async fn handle_component(
+    http: &Http,
+    component: &ComponentInteraction,
+    config: &Config,
+    store: &Store,
+    audit: &AuditLog,
+) -> Result<()> {
+    let Some(encoded) = component.data.custom_id.strip_prefix(CONFIRM_PREFIX) else {
+        component
+            .create_response(
+                http,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .content("Cancelled; nothing was sent to GitHub")
+                        .embeds(vec![])
+                        .components(vec![]),
+                ),
+            )
+            .await?;
+        return Ok(());
+    };
+    let (anonymous, encoded) = encoded.split_once(':').unwrap_or(("0", encoded));
+    let selection = MessageSelection::decode(encoded);
+
+    let Some((thread, project)) =
+        crate::thread_events::resolve_thread(http, component.channel_id, config, store).await?
+    else {
+        return Ok(());
+    };
+
+    component
+        .create_response(
+            http,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content("⏳ Creating the issue...")
+                    .embeds(vec![])
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+
+    if !store.try_lock_thread(thread.id.get(), crate::constants::THREAD_LOCK_TTL_SECS)? {
+        component
+            .edit_response(
+                http,
+                EditInteractionResponse::new().content(crate::constants::MSG_THREAD_BUSY),
+            )
+            .await?;
+        return Ok(());
+    }
+    // The preview only went to the owner who asked to stay anonymous
+    if anonymous == "1" {
+        if let Some(owner) = thread.owner_id {
+            store.set_anonymous_reporter(thread.id.get(), owner.get(), &component.user.name)?;
+        }
+    }
+    let result = crate::filing::file_thread(
+        http,
+        config,
+        &project,
+        &thread,
+        store,
+        audit,
+        &component.user.name,
+        &selection,
+        Progress::Component(http, component),
+    )
+    .await;
+    store.unlock_thread(thread.id.get())?;
+
+    let (content, result) = match result {
+        Ok(filed) => (format!("✅ {}", filed.summary()), Ok(())),
+        Err(e) => {
+            let e = crate::error::BotError::from(e);
+            (format!("❌ {}", e.user_message()), Err(e.into()))
+        }
+    };
+    component
+        .edit_response(http, EditInteractionResponse::new().content(content))
+        .await?;
+    result
+}
@@ -0,0 +1,181 @@
+use anyhow::Result;
+use serenity::all::*;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::audit::{AuditAction, AuditEntry, AuditLog};
+use crate::config::{Config, Project};
+use crate::store::Store;
+
+const PRIORITY_PREFIX: &str = "issue_priority:";
+
+/// Reply to `/issue priority` with a menu of the project's priority labels
+pub async fn show_menu(
+    http: &Http,
+    command: &CommandInteraction,
+    config: &Config,
+    project: &Project,
+    thread: &GuildChannel,
+) -> Result<()> {
+    let github = crate::github_app::create_project_client(config, project).await?;
+    let Some(issue) =
+        crate::github::find_issue_for_thread(&github, project, thread.id.get()).await?
+    else {
+        command
+            .edit_response(
+                http,
+                EditInteractionResponse::new().content(crate::constants::MSG_NO_LINKED_ISSUE),
+            )
+            .await?;
+        return Ok(());
+    };
+
+    let options = project
+        .priority_labels()
+        .into_iter()
+        .map(|label| {
+            let current = issue.labels.iter().any(|l| l.name == label);
+            CreateSelectMenuOption::new(label.clone(), label).default_selection(current)
+        })
+        .collect();
+
+    let menu = CreateSelectMenu::new(
+        format!("{PRIORITY_PREFIX}{}", issue.number),
+        CreateSelectMenuKind::String { options },
+    )
+    .placeholder("Choose a priority");
+
+    command
+        .edit_response(
+            http,
+            EditInteractionResponse::new()
+                .content(format!("Set the priority of issue #{}", issue.number))
+                .components(vec![CreateActionRow::SelectMenu(menu)]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub fn is_priority_component(custom_id: &str) -> bool {
+    custom_id.starts_with(PRIORITY_PREFIX)
+}
+
+/// Apply the chosen priority label, removing the project's other priority labels
+pub async fn handle_component(
+    http: &Http,
+    component: &ComponentInteraction,
+    config: &Arc<Config>,
+    store: &Store,
+    audit: &AuditLog,
+) -> Result<()> {
+    let Some(issue_number) = component
+        .data
+        .custom_id
+        .strip_prefix(PRIORITY_PREFIX)
+        .and_then(|n| n.parse::<u64>().ok())
+    else {
+        return Ok(());
+    };
+    let ComponentInteractionDataKind::StringSelect { values } = &component.data.kind else {
+        return Ok(());
+    };
+    let Some(chosen) = values.first() else {
+        return Ok(());
+    };
+
+    let Some((thread, project)) =
+        crate::thread_events::resolve_thread(http, component.channel_id, config, store).await?
+    else {
+        return Ok(());
+    };
+
+    let priorities = project.priority_labels();
+    if !priorities.contains(chosen) || !has_allowed_role(&project, component.member.as_ref()) {
+        component
+            .create_response(
+                http,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .content("You don't have permission to change the priority")
+                        .components(vec![]),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let github = crate::github_app::create_project_client(config, &project).await?;
+    let issues = github.issues(&project.github_owner, &project.github_repo);
+    let issue = issues.get(issue_number).await?;
+
+    let labels = apply_priority(
+        issue.labels.iter().map(|l| l.name.clone()).collect(),
+        &priorities,
+        chosen,
+    );
+    issues.update(issue_number).labels(&labels).send().await?;
+
+    component
+        .create_response(
+            http,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(format!(
+                        "Priority of issue #{issue_number} set to **{chosen}**"
+                    ))
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+
+    info!(
+        "Set priority of issue #{} to '{}' from thread {}",
+        issue_number, chosen, thread.id
+    );
+    audit
+        .record(
+            AuditEntry::new(&project, AuditAction::IssuePrioritized)
+                .thread(thread.id.get())
+                .issue(issue_number)
+                .actor(component.user.name.clone())
+                .details(format!("priority set to '{chosen}'")),
+        )
+        .await;
+
+    Ok(())
+}
+
+/// Replace any priority label in `labels` with `chosen`
+pub fn apply_priority(labels: Vec<String>, priorities: &[String], chosen: &str) -> Vec<String> {
+    let mut labels: Vec<String> = labels
+        .into_iter()
+        .filter(|l| !priorities.contains(l))
+        .collect();
+    labels.push(chosen.to_string());
+    labels
+}
+
+/// Members holding the project's allowed role, or anyone if none is configured
+pub fn has_allowed_role(project: &Project, member: Option<&Member>) -> bool {
+    match (&project.allowed_role_id, member) {
+        (None, _) => true,
+        (Some(role_id), Some(member)) => member
+            .roles
+            .iter()
+            .any(|role| role.get().to_string() == *role_id),
+        (Some(_), None) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_priority_is_exclusive() {
+        let priorities = vec!["P0".to_string(), "P1".to_string(), "P2".to_string()];
+        let labels = apply_priority(vec!["bug".to_string(), "P2".to_string()], &priorities, "P0");
+        assert_eq!(labels, vec!["bug", "P0"]);
+    }
+}
@@ -0,0 +1,27 @@
+use serenity::all::{CommandInteraction, ComponentInteraction, EditInteractionResponse, Http};
+use tracing::debug;
+
+/// Where filing reports how far it got: the deferred response of the command
+/// or button that asked for it, or nowhere when the bot files on its own
+#[derive(Clone, Copy)]
+pub enum Progress<'a> {
+    Silent,
+    Command(&'a Http, &'a CommandInteraction),
+    Component(&'a Http, &'a ComponentInteraction),
+}
+
+impl Progress<'_> {
+    /// Show the current stage in place of the previous one; a failed edit only
+    /// costs the update, not the filing
+    pub async fn update(&self, stage: &str) {
+        let edit = EditInteractionResponse::new().content(format!("⏳ {stage}"));
+        let result = match self {
+            Self::Silent => return,
+            Self::Command(http, command) => command.edit_response(http, edit).await,
+            Self::Component(http, component) => component.edit_response(http, edit).await,
+        };
+        if let Err(e) = result {
+            debug!("Failed to report filing progress: {}", e);
+        }
+    }
+}
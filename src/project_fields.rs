@@ -0,0 +1,268 @@
+use anyhow::Result;
+use octocrab::Octocrab;
+use serde_json::{json, Value};
+use serenity::http::Http;
+use serenity::model::channel::GuildChannel;
+use tracing::{debug, info, warn};
+
+use crate::config::{Project, ProjectFieldMapping, ThreadAttribute};
+use crate::graphql::ProjectV2Field;
+
+/// What `project_fields` can read from a thread
+#[derive(Debug, Default)]
+pub struct ThreadAttributes {
+    pub prefix: Option<String>,
+    /// Forum tag names in the order they were applied
+    pub forum_tags: Vec<String>,
+    pub reaction_score: usize,
+    /// Role names of the reporter, highest first
+    pub reporter_roles: Vec<String>,
+}
+
+impl ThreadAttributes {
+    /// Read the attributes the mappings use; ones that can't be fetched are
+    /// left empty
+    pub async fn collect(
+        discord: &Http,
+        project: &Project,
+        thread: &GuildChannel,
+        mappings: &[ProjectFieldMapping],
+    ) -> Self {
+        let uses = |attribute| mappings.iter().any(|m| m.from == attribute);
+        let mut attributes = Self {
+            prefix: project.prefix_for_title(&thread.name).map(|p| p.prefix),
+            ..Default::default()
+        };
+
+        if uses(ThreadAttribute::ForumTag) {
+            if let Some(forum_id) = thread.parent_id {
+                match crate::discord_cache::channel(discord, forum_id).await {
+                    Ok(forum) => {
+                        let available = forum.guild().map(|f| f.available_tags).unwrap_or_default();
+                        attributes.forum_tags = thread
+                            .applied_tags
+                            .iter()
+                            .filter_map(|id| available.iter().find(|t| t.id == *id))
+                            .map(|t| t.name.clone())
+                            .collect();
+                    }
+                    Err(e) => warn!("Failed to fetch forum {}: {}", forum_id, e),
+                }
+            }
+        }
+
+        if uses(ThreadAttribute::ReactionScore) {
+            match crate::discord_cache::starter_message(discord, thread.id).await {
+                Ok(starter) => {
+                    attributes.reaction_score =
+                        crate::auto_create::thread_activity(thread, &[starter]).reactions;
+                }
+                Err(e) => debug!("No opening post for thread {}: {}", thread.id, e),
+            }
+        }
+
+        if uses(ThreadAttribute::ReporterRole) {
+            if let Some(owner_id) = thread.owner_id {
+                let member = crate::discord_cache::member(discord, thread.guild_id, owner_id).await;
+                let roles = crate::discord_cache::roles(discord, thread.guild_id).await;
+                match (member, roles) {
+                    (Ok(member), Ok(roles)) => {
+                        let mut held: Vec<_> =
+                            member.roles.iter().filter_map(|id| roles.get(id)).collect();
+                        held.sort_by_key(|role| std::cmp::Reverse(role.position));
+                        attributes.reporter_roles = held.iter().map(|r| r.name.clone()).collect();
+                    }
+                    (Err(e), _) | (_, Err(e)) => {
+                        debug!("Could not fetch roles of reporter {}: {}", owner_id, e)
+                    }
+                }
+            }
+        }
+
+        attributes
+    }
+}
+
+/// The value a mapping gives its field, or None when the thread has nothing
+/// for it
+pub fn field_value(mapping: &ProjectFieldMapping, attributes: &ThreadAttributes) -> Option<String> {
+    let mapped = |value: &str| {
+        mapping
+            .values
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(value))
+            .map(|(_, field_value)| field_value.clone())
+    };
+    // Of several candidates, the first with an entry wins, else the first
+    let first = |candidates: &[String]| {
+        candidates.iter().find_map(|c| mapped(c)).or_else(|| {
+            mapping
+                .values
+                .is_empty()
+                .then(|| candidates.first().cloned())
+                .flatten()
+        })
+    };
+
+    match mapping.from {
+        ThreadAttribute::Prefix => {
+            let prefix = attributes.prefix.as_deref()?;
+            mapped(prefix).or_else(|| Some(prefix.to_string()))
+        }
+        ThreadAttribute::ForumTag => first(&attributes.forum_tags),
+        ThreadAttribute::ReporterRole => first(&attributes.reporter_roles),
+        ThreadAttribute::ReactionScore => {
+            if mapping.values.is_empty() {
+                return Some(attributes.reaction_score.to_string());
+            }
+            mapping
+                .values
+                .iter()
+                .filter_map(|(min, value)| Some((min.trim().parse::<usize>().ok()?, value)))
+                .filter(|(min, _)| *min <= attributes.reaction_score)
+                .max_by_key(|(min, _)| *min)
+                .map(|(_, value)| value.clone())
+        }
+    }
+}
+
+/// `ProjectV2FieldValue` input for a field, or None when the value doesn't
+/// fit it, such as an unknown single select option
+pub fn field_input(field: &ProjectV2Field, value: &str) -> Option<Value> {
+    match field.data_type.as_str() {
+        "SINGLE_SELECT" => field
+            .options
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(value))
+            .map(|(id, _)| json!({ "singleSelectOptionId": id })),
+        "NUMBER" => value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .map(|number| json!({ "number": number })),
+        "TEXT" => Some(json!({ "text": value })),
+        _ => None,
+    }
+}
+
+/// Add a new issue to the project's board and fill its `project_fields`.
+/// Does nothing unless both `github_project_number` and `project_fields` are
+/// set.
+pub async fn add_issue(
+    discord: &Http,
+    github: &Octocrab,
+    project: &Project,
+    thread: &GuildChannel,
+    issue_node_id: &str,
+) -> Result<()> {
+    let Some(number) = project
+        .github_project_number
+        .filter(|_| !project.project_fields().is_empty())
+    else {
+        return Ok(());
+    };
+    let board = crate::graphql::project_v2(github, project, number).await?;
+    let item_id =
+        crate::graphql::add_project_item(github, project, &board.id, issue_node_id).await?;
+    fill_fields(discord, github, project, thread, &board.id, &item_id).await
+}
+
+/// Fill the `project_fields` of a board item filed from a thread. Fields
+/// missing from the board or values that don't fit them are skipped.
+pub async fn fill_fields(
+    discord: &Http,
+    github: &Octocrab,
+    project: &Project,
+    thread: &GuildChannel,
+    project_id: &str,
+    item_id: &str,
+) -> Result<()> {
+    let mappings = project.project_fields();
+    if mappings.is_empty() {
+        return Ok(());
+    }
+    let fields = crate::graphql::project_v2_fields(github, project, project_id).await?;
+    let attributes = ThreadAttributes::collect(discord, project, thread, mappings).await;
+
+    for mapping in mappings {
+        let Some(field) = fields
+            .iter()
+            .find(|f| f.name.eq_ignore_ascii_case(&mapping.field))
+        else {
+            warn!("Project field '{}' not found on the board", mapping.field);
+            continue;
+        };
+        let Some(value) = field_value(mapping, &attributes) else {
+            continue;
+        };
+        let Some(input) = field_input(field, &value) else {
+            warn!(
+                "'{}' is not a valid value for project field '{}'",
+                value, field.name
+            );
+            continue;
+        };
+        crate::graphql::set_project_field(github, project, project_id, item_id, &field.id, input)
+            .await?;
+        info!(
+            "Set project field '{}' to '{}' for thread {}",
+            field.name, value, thread.id
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(from: ThreadAttribute, values: &[(&str, &str)]) -> ProjectFieldMapping {
+        ProjectFieldMapping {
+            field: "Field".to_string(),
+            from,
+            values: values
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_field_value() {
+        let attributes = ThreadAttributes {
+            prefix: Some("[BUG]".to_string()),
+            forum_tags: vec!["Windows".to_string(), "Crash".to_string()],
+            reaction_score: 7,
+            reporter_roles: vec!["Moderator".to_string(), "Member".to_string()],
+        };
+
+        let source = mapping(ThreadAttribute::Prefix, &[("[bug]", "Bug report")]);
+        assert_eq!(
+            field_value(&source, &attributes).as_deref(),
+            Some("Bug report")
+        );
+        let raw = mapping(ThreadAttribute::Prefix, &[]);
+        assert_eq!(field_value(&raw, &attributes).as_deref(), Some("[BUG]"));
+
+        let tag = mapping(ThreadAttribute::ForumTag, &[("crash", "P0")]);
+        assert_eq!(field_value(&tag, &attributes).as_deref(), Some("P0"));
+        let unmapped = mapping(ThreadAttribute::ForumTag, &[("docs", "P3")]);
+        assert_eq!(field_value(&unmapped, &attributes), None);
+        let role = mapping(ThreadAttribute::ReporterRole, &[]);
+        assert_eq!(
+            field_value(&role, &attributes).as_deref(),
+            Some("Moderator")
+        );
+
+        let priority = mapping(
+            ThreadAttribute::ReactionScore,
+            &[("0", "Low"), ("5", "Medium"), ("20", "High")],
+        );
+        assert_eq!(
+            field_value(&priority, &attributes).as_deref(),
+            Some("Medium")
+        );
+        let score = mapping(ThreadAttribute::ReactionScore, &[]);
+        assert_eq!(field_value(&score, &attributes).as_deref(), Some("7"));
+    }
+}
@@ -0,0 +1,277 @@
+use anyhow::Result;
+use serenity::all::*;
+
+use crate::config::{Config, Project, SyncConfig};
+use crate::embeds::Embed;
+use crate::store::Store;
+
+/// `/project info` shows moderators how the bot is set up for a forum
+pub fn create_project_command() -> CreateCommand {
+    CreateCommand::new("project")
+        .description("Show how issue tracking is set up here")
+        .default_member_permissions(Permissions::MANAGE_THREADS)
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "info",
+            "Show the project configuration for this forum",
+        ))
+}
+
+/// Reply with the configuration of the forum the command ran in, or of the
+/// forum a thread belongs to
+pub async fn handle_project_command(
+    http: &Http,
+    command: &CommandInteraction,
+    config: &Config,
+    store: &Store,
+) -> Result<()> {
+    command
+        .create_response(
+            http,
+            CreateInteractionResponse::Defer(
+                CreateInteractionResponseMessage::new().ephemeral(true),
+            ),
+        )
+        .await?;
+
+    let channel = crate::discord_cache::channel(http, command.channel_id).await?;
+    let (forum_id, thread) = match channel {
+        Channel::Guild(ch) if ch.thread_metadata.is_some() => {
+            (ch.parent_id.map(|id| id.get()), Some(ch))
+        }
+        Channel::Guild(ch) => (Some(ch.id.get()), None),
+        _ => (None, None),
+    };
+    let project = command
+        .guild_id
+        .zip(forum_id)
+        .and_then(|(guild_id, forum_id)| config.find_project(guild_id.get(), forum_id));
+    let Some(project) = project else {
+        command
+            .edit_response(
+                http,
+                EditInteractionResponse::new()
+                    .content("This channel is not configured for issue tracking"),
+            )
+            .await?;
+        return Ok(());
+    };
+
+    // Anonymous reporters are only shown to those who can manage threads
+    let maintainer = command
+        .member
+        .as_ref()
+        .and_then(|m| m.permissions)
+        .is_some_and(|p| p.manage_threads());
+    let embed = info_embed(config, project, store, thread.as_ref(), maintainer).await?;
+    command
+        .edit_response(http, EditInteractionResponse::new().embed(embed))
+        .await?;
+    Ok(())
+}
+
+async fn info_embed(
+    config: &Config,
+    project: &Project,
+    store: &Store,
+    thread: Option<&GuildChannel>,
+    maintainer: bool,
+) -> Result<CreateEmbed> {
+    let key = crate::audit::project_key(project);
+    let repo_url = format!("{}/{}", project.github_web_url(), key);
+
+    let mut repository = format!("[{key}]({repo_url})");
+    if let Some(account) = &project.github_account {
+        repository.push_str(&format!("\nAccount: `{account}`"));
+    }
+    if let Some(bot) = &project.discord_bot {
+        repository.push_str(&format!("\nDiscord bot: `{bot}`"));
+    }
+
+    let labels = project
+        .prefixes()
+        .iter()
+        .map(|p| match &p.github_repo {
+            Some(repo) => format!("`{}` → `{}` in `{repo}`", p.prefix, p.label),
+            None => format!("`{}` → `{}`", p.prefix, p.label),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let creators = match project.allowed_role_id.as_deref() {
+        Some(role) if !role.is_empty() => format!("<@&{role}>"),
+        _ => "Everyone".to_string(),
+    };
+
+    let linked = store.linked_thread_count(&key)?;
+    let mut issues = format!("{linked} threads linked to issues");
+    match open_issue_count(config, project).await {
+        Ok(open) => issues.push_str(&format!("\n{open} open issues on GitHub")),
+        Err(e) => tracing::warn!("Failed to count open issues for {}: {}", key, e),
+    }
+
+    let mut embed = Embed::new()
+        .title(project.name.as_deref().unwrap_or(&key))
+        .url(repo_url)
+        .field("Repository", repository, false)
+        .field("Labels", labels, false)
+        .field("Who can create issues", creators, true)
+        .field(
+            "Sync",
+            sync_status(&config.sync_config(), project, chrono::Utc::now()),
+            true,
+        )
+        .field("Issues", issues, false)
+        .color(crate::constants::COLOR_SUCCESS);
+
+    if project.sla_targets.is_some() {
+        let since = chrono::Utc::now().timestamp() - 30 * 24 * 60 * 60;
+        embed = embed.field(
+            "Response SLA (30 days)",
+            sla_summary(&store.sla_stats(&key, since)?),
+            false,
+        );
+    }
+
+    if let Some(thread) = thread {
+        embed = embed.field(
+            "This thread",
+            thread_status(project, store, thread, maintainer)?,
+            false,
+        );
+    }
+    Ok(embed.build())
+}
+
+fn sla_summary(stats: &crate::store::SlaStats) -> String {
+    let mut summary = format!(
+        "{} met · {} missed · {} pending",
+        stats.met, stats.missed, stats.pending
+    );
+    if let Some(average) = stats.average_response {
+        summary.push_str(&format!(
+            "\nAverage first response: {}h",
+            (average + 1800) / 3600
+        ));
+    }
+    summary
+}
+
+async fn open_issue_count(config: &Config, project: &Project) -> Result<u64> {
+    let github = crate::github_app::create_project_client(config, project).await?;
+    let query = format!(
+        "repo:{}/{} is:issue is:open",
+        project.github_owner, project.github_repo
+    );
+    let page = github
+        .search()
+        .issues_and_pull_requests(&query)
+        .per_page(1)
+        .send()
+        .await?;
+    Ok(page.total_count.unwrap_or_default())
+}
+
+/// Whether the syncer picks the project up, and how often
+fn sync_status(sync: &SyncConfig, project: &Project, now: chrono::DateTime<chrono::Utc>) -> String {
+    if !sync.enabled {
+        return "Disabled for all projects".to_string();
+    }
+    if project.archived() {
+        return "Archived".to_string();
+    }
+    if !project.sync_enabled() {
+        return "Disabled for this project".to_string();
+    }
+    let mut status = format!("Every {}s", sync.interval_seconds);
+    if let Some(reason) = project.mutations_paused(now) {
+        status.push_str(&format!("\nPaused: {reason}"));
+    }
+    status
+}
+
+/// Why the thread the command ran in is or isn't syncing
+pub fn thread_status(
+    project: &Project,
+    store: &Store,
+    thread: &GuildChannel,
+    maintainer: bool,
+) -> Result<String> {
+    if let Some(linked) = store.thread_issue(thread.id.get())? {
+        let mut status = format!(
+            "Linked to [#{}]({}/{}/issues/{})",
+            linked.issue_number,
+            project.github_web_url(),
+            linked.project,
+            linked.issue_number
+        );
+        let split = store.split_issues(thread.id.get())?;
+        if !split.is_empty() {
+            let numbers = split
+                .iter()
+                .map(|n| format!("#{n}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            status.push_str(&format!("\nSplit into {numbers}"));
+        }
+        if maintainer {
+            if let Some(reporter) = store.anonymous_reporter(thread.id.get())? {
+                status.push_str(&format!("\nReported anonymously by <@{reporter}>"));
+            }
+        }
+        return Ok(status);
+    }
+    if !project.has_valid_prefix(&thread.name) {
+        return Ok(format!(
+            "Not linked; the title has none of the prefixes {}",
+            project.prefix_list()
+        ));
+    }
+    Ok("Not linked; run `/issue create` to file it".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_status() {
+        let mut project: Project = toml::from_str(
+            r#"
+            discord_guild_id = "1"
+            discord_forum_id = "2"
+            github_repo = "app"
+            maintenance_windows = [
+              { start = "2025-07-01T00:00:00Z", end = "2025-07-01T06:00:00Z", reason = "migration" },
+            ]
+            "#,
+        )
+        .unwrap();
+        let mut sync = SyncConfig {
+            enabled: true,
+            interval_seconds: 60,
+            jitter_seconds: None,
+            stagger_seconds: None,
+            status_channel_id: None,
+            concurrency: None,
+        };
+        let during = "2025-07-01T03:00:00Z".parse().unwrap();
+        let after = "2025-07-02T03:00:00Z".parse().unwrap();
+
+        assert_eq!(sync_status(&sync, &project, after), "Every 60s");
+        assert_eq!(
+            sync_status(&sync, &project, during),
+            "Every 60s\nPaused: maintenance window (migration)"
+        );
+        project.sync_enabled = Some(false);
+        assert_eq!(
+            sync_status(&sync, &project, after),
+            "Disabled for this project"
+        );
+        sync.enabled = false;
+        assert_eq!(
+            sync_status(&sync, &project, after),
+            "Disabled for all projects"
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::{DateTime, Datelike, FixedOffset, NaiveTime, Utc};
+use serde::Deserialize;
+
+/// Recurring daily window, e.g. nights or weekends, in a fixed UTC offset
+#[derive(Debug, Deserialize, Clone)]
+pub struct QuietHours {
+    /// Start time, "HH:MM"
+    pub start: String,
+    /// End time, "HH:MM"; may be earlier than start to wrap past midnight
+    pub end: String,
+    /// Days the window starts on ("mon".."sun"); every day when omitted
+    pub days: Option<Vec<String>>,
+    #[serde(default)]
+    pub utc_offset_hours: i32,
+}
+
+/// One-off maintenance window with absolute RFC 3339 bounds
+#[derive(Debug, Deserialize, Clone)]
+pub struct MaintenanceWindow {
+    pub start: String,
+    pub end: String,
+    pub reason: Option<String>,
+}
+
+impl QuietHours {
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        let (Ok(start), Ok(end)) = (
+            NaiveTime::parse_from_str(&self.start, "%H:%M"),
+            NaiveTime::parse_from_str(&self.end, "%H:%M"),
+        ) else {
+            return false;
+        };
+        let Some(offset) = FixedOffset::east_opt(self.utc_offset_hours * 3600) else {
+            return false;
+        };
+
+        let local = now.with_timezone(&offset);
+        let time = local.time();
+
+        if start <= end {
+            time >= start && time < end && self.applies_on(local.weekday())
+        } else if time >= start {
+            // Evening part of a window that wraps past midnight
+            self.applies_on(local.weekday())
+        } else if time < end {
+            // Morning part belongs to the window that started the previous day
+            self.applies_on(local.weekday().pred())
+        } else {
+            false
+        }
+    }
+
+    fn applies_on(&self, weekday: chrono::Weekday) -> bool {
+        match &self.days {
+            None => true,
+            Some(days) => days.iter().any(|d| {
+                d.to_lowercase()
+                    .parse::<chrono::Weekday>()
+                    .map(|day| day == weekday)
+                    .unwrap_or(false)
+            }),
+        }
+    }
+}
+
+impl MaintenanceWindow {
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        match (
+            DateTime::parse_from_rfc3339(&self.start),
+            DateTime::parse_from_rfc3339(&self.end),
+        ) {
+            (Ok(start), Ok(end)) => now >= start && now < end,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value).unwrap().into()
+    }
+
+    #[test]
+    fn test_quiet_hours_wrap_midnight() {
+        let nights = QuietHours {
+            start: "22:00".to_string(),
+            end: "07:00".to_string(),
+            days: None,
+            utc_offset_hours: 0,
+        };
+        assert!(nights.contains(at("2025-06-02T23:30:00Z")));
+        assert!(nights.contains(at("2025-06-03T03:00:00Z")));
+        assert!(!nights.contains(at("2025-06-03T12:00:00Z")));
+    }
+
+    #[test]
+    fn test_quiet_hours_days_and_offset() {
+        // Friday night 22:00-07:00 at UTC+2
+        let friday_nights = QuietHours {
+            start: "22:00".to_string(),
+            end: "07:00".to_string(),
+            days: Some(vec!["fri".to_string()]),
+            utc_offset_hours: 2,
+        };
+        // 2025-06-06 is a Friday; 21:00Z is 23:00 local
+        assert!(friday_nights.contains(at("2025-06-06T21:00:00Z")));
+        // Saturday 03:00 local still belongs to Friday's window
+        assert!(friday_nights.contains(at("2025-06-07T01:00:00Z")));
+        // Saturday night is not covered
+        assert!(!friday_nights.contains(at("2025-06-07T21:00:00Z")));
+    }
+
+    #[test]
+    fn test_maintenance_window() {
+        let window = MaintenanceWindow {
+            start: "2025-07-01T00:00:00Z".to_string(),
+            end: "2025-07-01T06:00:00Z".to_string(),
+            reason: None,
+        };
+        assert!(window.contains(at("2025-07-01T03:00:00Z")));
+        assert!(!window.contains(at("2025-07-01T06:00:00Z")));
+    }
+}
@@ -0,0 +1,85 @@
+use anyhow::Result;
+use octocrab::params::State;
+use serenity::all::*;
+use tracing::info;
+
+use crate::config::{Config, Project};
+use crate::store::Store;
+
+/// Whether the project already has `max_open_issues` open issues filed from
+/// Discord. Stops paging once the limit is reached.
+pub async fn is_full(config: &Config, project: &Project) -> Result<bool> {
+    let Some(limit) = project.max_open_issues else {
+        return Ok(false);
+    };
+    let github = crate::github_app::create_project_client(config, project).await?;
+    let issues = github.issues(&project.github_owner, &project.github_repo);
+
+    let mut open = 0;
+    let mut page: u32 = 1;
+    loop {
+        let batch = issues
+            .list()
+            .state(State::Open)
+            .per_page(100)
+            .page(page)
+            .send()
+            .await?;
+        open += batch
+            .items
+            .iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .filter(|issue| crate::sync::issue_thread_id(issue).is_some())
+            .count() as u64;
+        if open >= limit {
+            return Ok(true);
+        }
+        if batch.next.is_none() {
+            return Ok(false);
+        }
+        page += 1;
+    }
+}
+
+/// What to tell the user when a thread is held back by the project's quota,
+/// or None when it can be filed. Threads that already have an issue can
+/// always be refreshed.
+pub async fn check_thread(
+    discord: &Http,
+    config: &Config,
+    project: &Project,
+    thread: &GuildChannel,
+    store: &Store,
+) -> Result<Option<String>> {
+    if store.thread_issue(thread.id.get())?.is_some() || !is_full(config, project).await? {
+        return Ok(None);
+    }
+    Ok(Some(hold_thread(discord, project, thread, store).await?))
+}
+
+/// Hold back a thread while the project is over its quota: it goes to the
+/// triage channel when there is one, so maintainers can still let it through.
+/// Returns what to tell the user.
+async fn hold_thread(
+    discord: &Http,
+    project: &Project,
+    thread: &GuildChannel,
+    store: &Store,
+) -> Result<String> {
+    if project.triage_channel_id.is_none() {
+        return Ok(crate::constants::MSG_QUOTA_FULL.to_string());
+    }
+    let messages = thread
+        .messages(
+            discord,
+            GetMessages::new().limit(crate::constants::DISCORD_MESSAGE_FETCH_LIMIT),
+        )
+        .await?;
+    let activity = crate::auto_create::thread_activity(thread, &messages);
+    crate::triage::queue_candidate(discord, project, thread, &activity, store).await?;
+    info!(
+        "Project over its issue quota, held thread {} for triage",
+        thread.id
+    );
+    Ok(crate::constants::MSG_QUOTA_QUEUED.to_string())
+}
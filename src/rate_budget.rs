@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::audit::project_key;
+use crate::config::Project;
+
+/// Token bucket holding a project's GitHub API call allowance. It refills
+/// at `capacity` tokens per sync interval and never holds more than that,
+/// so a quiet cycle can't be saved up for a burst later.
+#[derive(Debug)]
+pub struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(capacity: u32, interval: Duration, now: Instant) -> Self {
+        Self {
+            capacity: capacity as f64,
+            tokens: capacity as f64,
+            refill_per_sec: capacity as f64 / interval.as_secs_f64().max(1.0),
+            last_refill: now,
+        }
+    }
+
+    pub fn try_take(&mut self, cost: u32, now: Instant) -> bool {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= cost as f64 {
+            self.tokens -= cost as f64;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Per-project GitHub call budgets shared by one installation token, plus
+/// where each project's deferred work resumes next cycle
+pub struct RateBudgets {
+    interval: Duration,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+    resume_at: Mutex<HashMap<String, usize>>,
+}
+
+impl RateBudgets {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            buckets: Mutex::new(HashMap::new()),
+            resume_at: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Spend from the project's budget; always succeeds for unlimited projects
+    pub fn try_take(&self, project: &Project, cost: u32) -> bool {
+        let Some(capacity) = project.github_calls_per_cycle else {
+            return true;
+        };
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        buckets
+            .entry(project_key(project))
+            .or_insert_with(|| TokenBucket::new(capacity, self.interval, now))
+            .try_take(cost, now)
+    }
+
+    /// Index of the first item deferred last cycle, so it is served first
+    pub fn resume_index(&self, project: &Project, len: usize) -> usize {
+        let resume_at = self.resume_at.lock().unwrap_or_else(|e| e.into_inner());
+        resume_at
+            .get(&project_key(project))
+            .copied()
+            .filter(|i| *i < len)
+            .unwrap_or(0)
+    }
+
+    pub fn set_resume_index(&self, project: &Project, index: usize) {
+        let mut resume_at = self.resume_at.lock().unwrap_or_else(|e| e.into_inner());
+        resume_at.insert(project_key(project), index);
+    }
+}
+
+/// Visit `0..len` starting at `start` and wrapping around
+pub fn rotated(start: usize, len: usize) -> impl Iterator<Item = usize> {
+    (start..len).chain(0..start.min(len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_refills_per_interval() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(10, Duration::from_secs(60), now);
+
+        assert!(bucket.try_take(6, now));
+        assert!(!bucket.try_take(6, now));
+        assert!(bucket.try_take(4, now));
+
+        // Half an interval refills half the budget
+        let later = now + Duration::from_secs(30);
+        assert!(bucket.try_take(5, later));
+        assert!(!bucket.try_take(1, later));
+
+        // Idle time never grows the bucket past one cycle's budget
+        let much_later = later + Duration::from_secs(600);
+        assert!(bucket.try_take(10, much_later));
+        assert!(!bucket.try_take(1, much_later));
+    }
+
+    #[test]
+    fn test_rotated() {
+        assert_eq!(rotated(2, 4).collect::<Vec<_>>(), vec![2, 3, 0, 1]);
+        assert_eq!(rotated(0, 3).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(rotated(0, 0).count(), 0);
+    }
+}
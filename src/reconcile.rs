@@ -0,0 +1,169 @@
+use anyhow::Result;
+use octocrab::models::issues::IssueStateReason;
+use octocrab::models::IssueState;
+use octocrab::Octocrab;
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use std::collections::HashSet;
+use tracing::{debug, info, warn};
+
+use crate::audit::{project_key, AuditAction, AuditEntry, AuditLog};
+use crate::config::Project;
+use crate::store::Store;
+use crate::sync::{close_thread, issue_thread_id};
+
+/// One-time deep pass run at startup so state converges after downtime.
+///
+/// Unlike the periodic sync, this also looks at recently closed issues and
+/// archived threads, which the fast loop deliberately skips.
+pub async fn reconcile_project(
+    github: &Octocrab,
+    discord: &Http,
+    store: &Store,
+    audit: &AuditLog,
+    project: &Project,
+    paused: bool,
+) -> Result<()> {
+    let mut handled = HashSet::new();
+    let mut locked = 0;
+
+    // Issues closed while the bot was down
+    let since =
+        chrono::Utc::now() - chrono::Duration::days(crate::constants::STARTUP_RECONCILE_DAYS);
+    let query = format!(
+        "repo:{}/{} is:issue is:closed closed:>={}",
+        project.github_owner,
+        project.github_repo,
+        since.format("%Y-%m-%d")
+    );
+    let closed = github
+        .search()
+        .issues_and_pull_requests(&query)
+        .per_page(100)
+        .send()
+        .await?;
+
+    for issue in &closed.items {
+        let Some(thread_id) = issue_thread_id(issue) else {
+            continue;
+        };
+        store.upsert_thread_issue(thread_id, &project_key(project), issue.number)?;
+        handled.insert(thread_id);
+
+        // Fixes awaiting reporter verification are left to the periodic sync
+        let completed = matches!(issue.state_reason, None | Some(IssueStateReason::Completed));
+        if project.verify_fix() && completed {
+            continue;
+        }
+
+        if lock_if_open(discord, audit, project, thread_id, issue.number, paused).await? {
+            locked += 1;
+        }
+    }
+
+    // Archived threads whose issues were closed earlier than the window above
+    let forum_id = ChannelId::new(project.discord_forum_id.parse()?);
+    let archived = forum_id
+        .get_archived_public_threads(
+            discord,
+            None,
+            Some(crate::constants::STARTUP_RECONCILE_ARCHIVED_LIMIT),
+        )
+        .await?;
+
+    for thread in archived.threads {
+        let thread_id = thread.id.get();
+        let is_locked = thread
+            .thread_metadata
+            .as_ref()
+            .map(|m| m.locked)
+            .unwrap_or(false);
+        if is_locked || handled.contains(&thread_id) || !project.has_valid_prefix(&thread.name) {
+            continue;
+        }
+
+        let Some(mapping) = store.thread_issue(thread_id)? else {
+            debug!("Archived thread {} has no known issue", thread_id);
+            continue;
+        };
+
+        let issue = github
+            .issues(&project.github_owner, &project.github_repo)
+            .get(mapping.issue_number)
+            .await?;
+        if matches!(issue.state, IssueState::Closed)
+            && lock_if_open(discord, audit, project, thread_id, issue.number, paused).await?
+        {
+            locked += 1;
+        }
+    }
+
+    let verb = if paused { "Would lock" } else { "Locked" };
+    info!(
+        "Startup reconciliation for {}: {} {} threads",
+        project.name.as_deref().unwrap_or("unnamed"),
+        verb,
+        locked
+    );
+    Ok(())
+}
+
+/// Lock and archive the thread of a closed issue if it isn't locked yet
+async fn lock_if_open(
+    discord: &Http,
+    audit: &AuditLog,
+    project: &Project,
+    thread_id: u64,
+    issue_number: u64,
+    paused: bool,
+) -> Result<bool> {
+    let channel_id = ChannelId::new(thread_id);
+    let thread = match discord.get_channel(channel_id).await {
+        Ok(channel) => channel.guild(),
+        Err(e) => {
+            debug!(
+                "Thread {} for issue #{} not found: {}",
+                thread_id, issue_number, e
+            );
+            return Ok(false);
+        }
+    };
+    let Some(thread) = thread else {
+        return Ok(false);
+    };
+
+    let is_locked = thread
+        .thread_metadata
+        .as_ref()
+        .map(|m| m.locked)
+        .unwrap_or(false);
+    if is_locked {
+        return Ok(false);
+    }
+
+    if project.staging() {
+        return Ok(false);
+    }
+    if paused {
+        info!(
+            "Would lock thread {} for closed issue #{} (paused)",
+            thread_id, issue_number
+        );
+        return Ok(true);
+    }
+
+    if let Err(e) = close_thread(discord, project, channel_id).await {
+        warn!("Failed to lock thread {}: {}", thread_id, e);
+        return Ok(false);
+    }
+
+    audit
+        .record(
+            AuditEntry::new(project, AuditAction::ThreadLocked)
+                .thread(thread_id)
+                .issue(issue_number)
+                .details("issue closed during downtime"),
+        )
+        .await;
+    Ok(true)
+}
@@ -0,0 +1,224 @@
+use regex::Regex;
+use serenity::builder::{CreateAllowedMentions, CreateMessage};
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use std::sync::LazyLock;
+use tracing::warn;
+
+use crate::config::Project;
+use crate::embeds::Embed;
+use crate::store::Store;
+
+// Shelley (bech32) and Byron (base58) addresses, and transaction hashes
+static WALLET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"\b(?:addr|addr_test|stake|stake_test)1[qpzry9x8gf2tvdw0s3jn54khce6mua7l]{40,}\b",
+        r"|\b(?:Ae2|DdzFF)[1-9A-HJ-NP-Za-km-z]{50,}\b",
+        r"|\b[0-9a-fA-F]{64}\b",
+    ))
+    .expect("valid regex")
+});
+
+// Home directories in stack traces give away the reporter's account name
+static HOME_DIR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(\b[A-Z]:\\Users\\|/home/|/Users/)[^\\/\s]+").expect("valid regex")
+});
+
+/// A value hidden from the public issue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redaction {
+    pub placeholder: String,
+    pub value: String,
+}
+
+/// Replace wallet addresses and transaction hashes with shortened
+/// placeholders and strip user names from home directory paths
+pub fn redact(text: &str) -> (String, Vec<Redaction>) {
+    let mut redactions: Vec<Redaction> = Vec::new();
+    let text = WALLET.replace_all(text, |caps: &regex::Captures| {
+        let value = &caps[0];
+        let placeholder = shorten(value);
+        if !redactions.iter().any(|r| r.value == value) {
+            redactions.push(Redaction {
+                placeholder: placeholder.clone(),
+                value: value.to_string(),
+            });
+        }
+        placeholder
+    });
+    let text = HOME_DIR.replace_all(&text, "${1}<user>");
+    (text.into_owned(), redactions)
+}
+
+/// `addr1qx2fxv…k9m3`: enough to tell values apart, not enough to look one up
+fn shorten(value: &str) -> String {
+    // Keep the bech32 prefix ("addr1", "stake_test1") plus a few characters
+    let keep_start = match value.find('1') {
+        Some(hrp) if value.starts_with("addr") || value.starts_with("stake") => hrp + 7,
+        _ => 8,
+    };
+    let start: String = value.chars().take(keep_start).collect();
+    let end: String = value
+        .chars()
+        .skip(value.chars().count().saturating_sub(4))
+        .collect();
+    format!("{start}…{end}")
+}
+
+/// Where text for GitHub came from, for the reports to maintainers
+#[derive(Debug, Clone, Copy)]
+pub enum Origin<'a> {
+    Thread(u64),
+    /// A Matrix thread or Telegram topic, by its link. Bridged threads share
+    /// one record per project, so a value is reported once across them.
+    Bridge(&'a str),
+}
+
+impl Origin<'_> {
+    /// The thread ID values are recorded under
+    pub fn record_id(&self) -> u64 {
+        match self {
+            Origin::Thread(id) => *id,
+            Origin::Bridge(_) => 0,
+        }
+    }
+
+    pub fn mention(&self) -> String {
+        match self {
+            Origin::Thread(id) => format!("<#{id}>"),
+            Origin::Bridge(url) => url.to_string(),
+        }
+    }
+}
+
+/// Text for a public GitHub issue or comment from a thread, after the
+/// project's `scam_filter`. With `redact_wallets` set, values are shortened
+/// and the full ones are sent once per thread to the project's private
+/// channel for maintainers.
+pub async fn for_github(
+    discord: &Http,
+    store: &Store,
+    project: &Project,
+    thread_id: u64,
+    text: &str,
+) -> String {
+    for_github_from(discord, store, project, Origin::Thread(thread_id), text).await
+}
+
+/// `for_github` for text from any origin
+pub async fn for_github_from(
+    discord: &Http,
+    store: &Store,
+    project: &Project,
+    origin: Origin<'_>,
+    text: &str,
+) -> String {
+    let text = crate::scam_filter::for_github(discord, store, project, origin, text).await;
+    let Some(redaction) = &project.redact_wallets else {
+        return text;
+    };
+    let (redacted, redactions) = redact(&text);
+
+    let Some(channel) = redaction
+        .channel_id
+        .as_deref()
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(ChannelId::new)
+    else {
+        return redacted;
+    };
+    let key = crate::audit::project_key(project);
+    let mut new = Vec::new();
+    for r in redactions {
+        match store.redacted_value_reported(&key, origin.record_id(), &r.value) {
+            Ok(false) => new.push(r),
+            Ok(true) => {}
+            Err(e) => warn!("Failed to look up redacted value: {}", e),
+        }
+    }
+    if new.is_empty() {
+        return redacted;
+    }
+    // Recorded once sent, so a failed report is retried with the next text
+    match report(discord, channel, origin, &new).await {
+        Ok(()) => {
+            for r in &new {
+                if let Err(e) = store.record_redacted_value(&key, origin.record_id(), &r.value) {
+                    warn!("Failed to record redacted value: {}", e);
+                }
+            }
+        }
+        Err(e) => warn!(
+            "Failed to send redacted values from {} to channel {}: {}",
+            origin.mention(),
+            channel,
+            e
+        ),
+    }
+    redacted
+}
+
+/// Report what an issue title loses to the scam filter and redaction.
+/// `Project::github_title` applies both itself, as titles are also built
+/// where nothing can be reported.
+pub async fn report_title(
+    discord: &Http,
+    store: &Store,
+    project: &Project,
+    thread_id: u64,
+    title: &str,
+) {
+    for_github(discord, store, project, thread_id, title).await;
+}
+
+async fn report(
+    discord: &Http,
+    channel: ChannelId,
+    origin: Origin<'_>,
+    redactions: &[Redaction],
+) -> serenity::Result<()> {
+    let lines: Vec<String> = redactions
+        .iter()
+        .map(|r| format!("`{}`\n```\n{}\n```", r.placeholder, r.value))
+        .collect();
+    let description = format!("From {}\n\n{}", origin.mention(), lines.join("\n"));
+    Embed::new()
+        .title(crate::constants::MSG_REDACTED_VALUES)
+        .description(description)
+        .color(crate::constants::COLOR_CLOSED)
+        .send(
+            discord,
+            channel,
+            CreateMessage::new().allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        let address = "addr1qx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgse35a3x";
+        let tx = "8f3a9c2b7d1e4f5a6b8c9d0e1f2a3b4c5d6e7f8a9b0c1d2e3f4a5b6c7d8e9f0a";
+        let text = format!(
+            "Sent from {address} in tx {tx}, again {address}\n  at C:\\Users\\alice\\app\\main.js:10"
+        );
+
+        let (redacted, redactions) = redact(&text);
+        assert_eq!(
+            redacted,
+            "Sent from addr1qx2fxv…5a3x in tx 8f3a9c2b…9f0a, again addr1qx2fxv…5a3x\n  at C:\\Users\\<user>\\app\\main.js:10"
+        );
+        assert_eq!(redactions.len(), 2);
+        assert_eq!(redactions[0].value, address);
+        assert_eq!(redactions[1].placeholder, "8f3a9c2b…9f0a");
+
+        // Commit SHAs and ordinary text are left alone
+        let sha = "fixed in 3f786850e387550fdab836ed7e6dc881de23001b";
+        assert_eq!(redact(sha), (sha.to_string(), Vec::new()));
+        assert_eq!(redact("/home/bob/.config").0, "/home/<user>/.config");
+    }
+}
@@ -0,0 +1,243 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use octocrab::Octocrab;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::json;
+use serenity::all::{Channel, ChannelId, CreateAllowedMentions, CreateMessage, EditThread, Http};
+use std::collections::BTreeSet;
+use std::sync::LazyLock;
+use tracing::{info, warn};
+
+use crate::config::Project;
+use crate::store::Store;
+
+// GitHub's closing keywords: "Fixes #12", "closes: #3"
+static CLOSING_REF: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:close[sd]?|fix(?:e[sd])?|resolve[sd]?)\s*:?\s+#(\d+)\b")
+        .expect("valid regex")
+});
+
+// Squash merges end their subject with "(#123)"; merge commits start with
+// "Merge pull request #123"
+static PULL_REF: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^Merge pull request #(\d+)\b|\(#(\d+)\)\s*$").expect("valid regex")
+});
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    id: u64,
+    tag_name: String,
+    name: Option<String>,
+    html_url: String,
+    draft: bool,
+    prerelease: bool,
+    published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Comparison {
+    commits: Vec<ComparedCommit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ComparedCommit {
+    commit: CommitDetails,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitDetails {
+    message: String,
+}
+
+/// Issues a commit closes by keyword, and the pull request it merged
+pub fn commit_references(message: &str) -> (Vec<u64>, Option<u64>) {
+    let closes = CLOSING_REF
+        .captures_iter(message)
+        .filter_map(|caps| caps[1].parse().ok())
+        .collect();
+    let subject = message.lines().next().unwrap_or_default();
+    let pull = PULL_REF.captures(subject).and_then(|caps| {
+        caps.get(1)
+            .or_else(|| caps.get(2))
+            .and_then(|m| m.as_str().parse().ok())
+    });
+    (closes, pull)
+}
+
+/// Post "fixed in" in the threads of issues closed by commits of recently
+/// published releases, once per release. The first release of a repository
+/// has nothing to compare against and is skipped.
+pub async fn announce_releases(
+    github: &Octocrab,
+    discord: &Http,
+    store: &Store,
+    project: &Project,
+) -> Result<()> {
+    let key = crate::audit::project_key(project);
+    let now = Utc::now();
+    let releases: Vec<Release> = github
+        .get(
+            format!(
+                "/repos/{}/{}/releases",
+                project.github_owner, project.github_repo
+            ),
+            Some(&json!({ "per_page": crate::constants::RELEASE_FETCH_LIMIT })),
+        )
+        .await?;
+    // Newest first, as GitHub lists them
+    let published: Vec<&Release> = releases
+        .iter()
+        .filter(|r| !r.draft && !r.prerelease && r.published_at.is_some())
+        .collect();
+
+    for (index, release) in published.iter().enumerate().rev() {
+        let recent = release
+            .published_at
+            .is_some_and(|at| (now - at).num_seconds() <= crate::constants::RELEASE_RECENT_SECS);
+        if !recent || store.release_announced(&key, release.id)? {
+            continue;
+        }
+        if let Some(previous) = published.get(index + 1) {
+            let fixed =
+                fixed_issues(github, project, &previous.tag_name, &release.tag_name).await?;
+            let name = release.name.as_deref().filter(|n| !n.is_empty());
+            let text = format!(
+                "🚀 This was fixed in **{}** — [release notes]({})",
+                name.unwrap_or(&release.tag_name),
+                release.html_url
+            );
+            for number in fixed {
+                let Some(thread_id) = store.thread_for_issue(&key, number)? else {
+                    continue;
+                };
+                if let Err(e) = post_in_thread(discord, ChannelId::new(thread_id), &text).await {
+                    warn!(
+                        "Failed to announce {} in thread {} for issue #{}: {}",
+                        release.tag_name, thread_id, number, e
+                    );
+                }
+            }
+            info!("Announced release {} of {}", release.tag_name, key);
+        }
+        store.record_release_announcement(&key, release.id)?;
+    }
+    Ok(())
+}
+
+/// Issues closed by the commits between two tags, by keyword or through the
+/// pull requests those commits merged
+async fn fixed_issues(
+    github: &Octocrab,
+    project: &Project,
+    base: &str,
+    head: &str,
+) -> Result<BTreeSet<u64>> {
+    let comparison: Comparison = github
+        .get(
+            format!(
+                "/repos/{}/{}/compare/{base}...{head}",
+                project.github_owner, project.github_repo
+            ),
+            None::<&()>,
+        )
+        .await?;
+
+    let mut issues = BTreeSet::new();
+    let mut pulls = BTreeSet::new();
+    for commit in &comparison.commits {
+        let (closes, pull) = commit_references(&commit.commit.message);
+        issues.extend(closes);
+        pulls.extend(pull);
+    }
+    issues.extend(pull_closing_issues(github, project, &pulls).await?);
+    Ok(issues)
+}
+
+/// Issues linked as closed by each pull request, in one GraphQL query
+async fn pull_closing_issues(
+    github: &Octocrab,
+    project: &Project,
+    pulls: &BTreeSet<u64>,
+) -> Result<Vec<u64>> {
+    if pulls.is_empty() {
+        return Ok(Vec::new());
+    }
+    let fields: Vec<String> = pulls
+        .iter()
+        .take(crate::constants::RELEASE_PR_LIMIT)
+        .map(|n| {
+            format!("pr{n}: pullRequest(number: {n}) {{ closingIssuesReferences(first: 20) {{ nodes {{ number }} }} }}")
+        })
+        .collect();
+    let query = format!(
+        "query($owner: String!, $repo: String!) {{ repository(owner: $owner, name: $repo) {{ {} }} }}",
+        fields.join(" ")
+    );
+    let data = crate::graphql::query(
+        github,
+        project,
+        &query,
+        json!({ "owner": project.github_owner, "repo": project.github_repo }),
+    )
+    .await?;
+
+    let Some(repository) = data["repository"].as_object() else {
+        return Ok(Vec::new());
+    };
+    Ok(repository
+        .values()
+        .flat_map(|pr| pr["closingIssuesReferences"]["nodes"].as_array().cloned())
+        .flatten()
+        .filter_map(|node| node["number"].as_u64())
+        .collect())
+}
+
+/// Post in a thread, unarchiving it for the message and archiving it again
+async fn post_in_thread(discord: &Http, thread: ChannelId, text: &str) -> Result<()> {
+    let Channel::Guild(channel) = crate::discord_cache::channel(discord, thread).await? else {
+        return Ok(());
+    };
+    let archived = channel.thread_metadata.is_some_and(|m| m.archived);
+    if archived {
+        thread
+            .edit_thread(discord, EditThread::new().archived(false))
+            .await?;
+    }
+    thread
+        .send_message(
+            discord,
+            CreateMessage::new()
+                .content(text)
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
+    if archived {
+        thread
+            .edit_thread(discord, EditThread::new().archived(true))
+            .await?;
+    }
+    crate::discord_cache::invalidate_channel(thread);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_references() {
+        assert_eq!(
+            commit_references("Fix crash on start (#45)\n\nFixes #12, closes: #13"),
+            (vec![12, 13], Some(45))
+        );
+        assert_eq!(
+            commit_references("Merge pull request #7 from acme/fix\n\nResolves #3"),
+            (vec![3], Some(7))
+        );
+        assert_eq!(
+            commit_references("Bump version; see #9 (#10) for context\n"),
+            (vec![], None)
+        );
+    }
+}
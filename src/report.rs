@@ -0,0 +1,214 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::audit::{AuditEntry, AuditQuery};
+use crate::cli::ReportFormat;
+use crate::config::Config;
+use crate::store::Store;
+
+/// One bridged issue and what happened to it during the month
+#[derive(Debug, Serialize)]
+struct IssueHistory<'a> {
+    project: &'a str,
+    issue_number: u64,
+    thread_id: Option<u64>,
+    transitions: Vec<Transition<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct Transition<'a> {
+    time: String,
+    action: &'a str,
+    actor: &'a str,
+    details: Option<&'a str>,
+}
+
+/// Write a month's report of bridged issues from the audit log, with an
+/// HMAC-SHA256 signature keyed by `REPORT_SIGNING_KEY`
+pub fn generate_report(
+    month: &str,
+    format: ReportFormat,
+    project: Option<String>,
+    out: Option<&str>,
+) -> Result<()> {
+    let key = std::env::var("REPORT_SIGNING_KEY")
+        .context("REPORT_SIGNING_KEY must be set to sign the report")?;
+    let config = Config::load()?;
+    let store = Store::open(&config)?;
+    let (since, until) = month_range(month)?;
+
+    let query = AuditQuery {
+        project: project.map(|filter| crate::audit::filter_key(&config, filter)),
+        thread_id: None,
+        since: Some(since),
+        until: Some(until),
+        limit: i64::MAX as usize,
+    };
+    let mut entries: Vec<AuditEntry> = store
+        .query_audit(&query)?
+        .into_iter()
+        .filter(|e| e.issue_number.is_some())
+        .collect();
+    entries.reverse();
+
+    let report = match format {
+        ReportFormat::Csv => csv(&entries),
+        ReportFormat::Json => json(month, &entries)?,
+    };
+    let signature = sign(key.as_bytes(), report.as_bytes());
+
+    match out {
+        Some(out) => {
+            std::fs::write(out, &report).with_context(|| format!("Failed to write {out}"))?;
+            let sig_path = format!("{out}.sig");
+            std::fs::write(&sig_path, format!("{signature}\n"))
+                .with_context(|| format!("Failed to write {sig_path}"))?;
+            println!(
+                "Wrote report of {} audit entries to {out}, signature to {sig_path}",
+                entries.len()
+            );
+        }
+        None => {
+            print!("{report}");
+            eprintln!("HMAC-SHA256: {signature}");
+        }
+    }
+    Ok(())
+}
+
+/// Start and end (exclusive) of a YYYY-MM month in UTC
+fn month_range(month: &str) -> Result<(i64, i64)> {
+    let start = chrono::NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("Invalid month '{month}', expected YYYY-MM"))?;
+    let end = start
+        .checked_add_months(chrono::Months::new(1))
+        .context("Month out of range")?;
+    let timestamp = |date: chrono::NaiveDate| {
+        date.and_hms_opt(0, 0, 0)
+            .unwrap_or_default()
+            .and_utc()
+            .timestamp()
+    };
+    Ok((timestamp(start), timestamp(end)))
+}
+
+fn rfc3339(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_default()
+}
+
+/// One row per audit entry, oldest first
+fn csv(entries: &[AuditEntry]) -> String {
+    let field = |value: &str| {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    };
+    let mut report =
+        String::from("timestamp,project,issue_number,thread_id,action,actor,details\n");
+    for entry in entries {
+        let row = [
+            rfc3339(entry.timestamp),
+            entry.project.clone(),
+            entry
+                .issue_number
+                .map(|n| n.to_string())
+                .unwrap_or_default(),
+            entry.thread_id.map(|id| id.to_string()).unwrap_or_default(),
+            entry.action.clone(),
+            entry.actor.clone(),
+            entry.details.clone().unwrap_or_default(),
+        ];
+        let row: Vec<String> = row.iter().map(|value| field(value)).collect();
+        report.push_str(&row.join(","));
+        report.push('\n');
+    }
+    report
+}
+
+/// Entries grouped per issue, each with its transitions oldest first
+fn json(month: &str, entries: &[AuditEntry]) -> Result<String> {
+    let mut issues: BTreeMap<(&str, u64), IssueHistory> = BTreeMap::new();
+    for entry in entries {
+        let Some(issue_number) = entry.issue_number else {
+            continue;
+        };
+        let history = issues
+            .entry((entry.project.as_str(), issue_number))
+            .or_insert_with(|| IssueHistory {
+                project: &entry.project,
+                issue_number,
+                thread_id: None,
+                transitions: Vec::new(),
+            });
+        history.thread_id = entry.thread_id.or(history.thread_id);
+        history.transitions.push(Transition {
+            time: rfc3339(entry.timestamp),
+            action: &entry.action,
+            actor: &entry.actor,
+            details: entry.details.as_deref(),
+        });
+    }
+
+    let issues: Vec<IssueHistory> = issues.into_values().collect();
+    let mut report = serde_json::to_string_pretty(&serde_json::json!({
+        "month": month,
+        "generated_at": chrono::Utc::now().to_rfc3339(),
+        "issues": issues,
+    }))?;
+    report.push('\n');
+    Ok(report)
+}
+
+fn sign(key: &[u8], report: &[u8]) -> String {
+    let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, key);
+    crate::webhook::encode_hex(ring::hmac::sign(&key, report).as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: i64, issue: u64, action: &str, details: Option<&str>) -> AuditEntry {
+        AuditEntry {
+            timestamp,
+            project: "acme/app".to_string(),
+            action: action.to_string(),
+            thread_id: Some(9),
+            issue_number: Some(issue),
+            actor: "alice".to_string(),
+            details: details.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_month_range() {
+        assert_eq!(
+            month_range("2025-06").unwrap(),
+            (1_748_736_000, 1_751_328_000)
+        );
+        assert_eq!(month_range("2025-12").unwrap().1, 1_767_225_600);
+        assert!(month_range("June").is_err());
+    }
+
+    #[test]
+    fn test_csv() {
+        let report = csv(&[
+            entry(1_748_736_000, 7, "issue_created", None),
+            entry(1_748_736_060, 7, "issue_closed", Some("fixed, \"shipped\"")),
+        ]);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(
+            lines[1],
+            "2025-06-01T00:00:00+00:00,acme/app,7,9,issue_created,alice,"
+        );
+        assert_eq!(
+            lines[2],
+            "2025-06-01T00:01:00+00:00,acme/app,7,9,issue_closed,alice,\"fixed, \"\"shipped\"\"\""
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use serenity::http::Http;
+use serenity::model::channel::GuildChannel;
+use serenity::model::id::{GuildId, UserId};
+
+use crate::config::ReporterProfileConfig;
+
+/// Compact reporter details for the bottom of an issue body, so maintainers
+/// can weigh a report without looking the reporter up on Discord
+pub fn profile_footer(
+    display_name: &str,
+    username: &str,
+    roles: &[String],
+    account_created: chrono::DateTime<chrono::Utc>,
+) -> String {
+    let mut parts = vec![if display_name == username {
+        format!("Reporter: **{username}**")
+    } else {
+        format!("Reporter: **{display_name}** (@{username})")
+    }];
+    if !roles.is_empty() {
+        parts.push(format!("Roles: {}", roles.join(", ")));
+    }
+    parts.push(format!(
+        "Discord account since {}",
+        account_created.format("%Y-%m-%d")
+    ));
+
+    format!("<sub>{}</sub>", parts.join(" · "))
+}
+
+/// Footer for the thread's creator, or None if their profile can't be fetched
+pub async fn thread_owner_footer(
+    discord: &Http,
+    config: &ReporterProfileConfig,
+    thread: &GuildChannel,
+) -> Option<String> {
+    member_footer(discord, config, thread.guild_id, thread.owner_id?).await
+}
+
+/// Footer for any member of the guild, or None if they can't be fetched
+pub async fn member_footer(
+    discord: &Http,
+    config: &ReporterProfileConfig,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Option<String> {
+    let member = match crate::discord_cache::member(discord, guild_id, user_id).await {
+        Ok(member) => member,
+        Err(e) => {
+            tracing::debug!("Could not fetch reporter {} for footer: {}", user_id, e);
+            return None;
+        }
+    };
+
+    // Only roles the project cares about, in the configured order
+    let roles = if config.roles.is_empty() {
+        Vec::new()
+    } else {
+        let guild_roles = crate::discord_cache::roles(discord, guild_id).await.ok()?;
+        config
+            .roles
+            .iter()
+            .filter(|wanted| {
+                member.roles.iter().any(|id| {
+                    guild_roles
+                        .get(id)
+                        .is_some_and(|role| role.name.eq_ignore_ascii_case(wanted))
+                })
+            })
+            .cloned()
+            .collect()
+    };
+
+    let created =
+        chrono::DateTime::from_timestamp(member.user.id.created_at().unix_timestamp(), 0)?;
+    Some(profile_footer(
+        member.display_name(),
+        &member.user.name,
+        &roles,
+        created,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_footer() {
+        let created = chrono::DateTime::from_timestamp(1_614_816_000, 0).unwrap();
+        assert_eq!(
+            profile_footer("Ada", "ada_l", &["beta tester".to_string()], created),
+            "<sub>Reporter: **Ada** (@ada_l) · Roles: beta tester · Discord account since 2021-03-04</sub>"
+        );
+        assert_eq!(
+            profile_footer("ada_l", "ada_l", &[], created),
+            "<sub>Reporter: **ada_l** · Discord account since 2021-03-04</sub>"
+        );
+    }
+}
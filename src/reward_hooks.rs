@@ -0,0 +1,218 @@
+use anyhow::{Context, Result};
+use serenity::model::channel::GuildChannel;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use crate::audit::project_key;
+use crate::config::{Project, RewardHookConfig};
+use crate::constants::{
+    REWARD_HOOK_MAX_ATTEMPTS, REWARD_HOOK_RETRY_BASE_SECS, REWARD_HOOK_SIGNATURE_HEADER,
+    REWARD_HOOK_TIMEOUT_SECS,
+};
+use crate::store::{RewardDelivery, Store};
+use crate::summary::IssueSummary;
+
+/// Queue the event for a thread whose issue was closed as completed. Events
+/// are stored first and delivered by `deliver_pending`, so a reward system
+/// that is down gets them later.
+pub fn queue_completed(
+    store: &Store,
+    project: &Project,
+    thread: &GuildChannel,
+    issue: &IssueSummary,
+) -> Result<()> {
+    let Some(hook) = &project.reward_hook else {
+        return Ok(());
+    };
+    let hidden = store.anonymous_reporter(thread.id.get())?;
+    let Some(reporter) = hidden.or(thread.owner_id.map(|id| id.get())) else {
+        return Ok(());
+    };
+    let anonymous = hidden.is_some() || project.anonymous_reports();
+    let payload = completed_event(
+        project,
+        thread.guild_id.get(),
+        thread.id.get(),
+        reporter,
+        anonymous,
+        hook.include_anonymous(),
+        issue,
+        chrono::Utc::now(),
+    );
+    if store.queue_reward_hook(&project_key(project), issue.number, &payload.to_string())? {
+        info!(
+            "Queued reward hook for issue #{} reported by {}",
+            issue.number, reporter
+        );
+    }
+    Ok(())
+}
+
+/// Discord IDs are strings, as in Discord's own API, since they don't fit
+/// in a JavaScript number. Anonymous reporters' IDs are left out unless the
+/// hook includes them.
+#[allow(clippy::too_many_arguments)]
+fn completed_event(
+    project: &Project,
+    guild_id: u64,
+    thread_id: u64,
+    reporter_id: u64,
+    anonymous: bool,
+    include_anonymous: bool,
+    issue: &IssueSummary,
+    closed_at: chrono::DateTime<chrono::Utc>,
+) -> serde_json::Value {
+    let reporter = if anonymous && !include_anonymous {
+        serde_json::json!({ "anonymous": true })
+    } else {
+        serde_json::json!({ "discord_id": reporter_id.to_string(), "anonymous": anonymous })
+    };
+    serde_json::json!({
+        "event": "issue.completed",
+        "project": project_key(project),
+        "issue": {
+            "number": issue.number,
+            "url": issue.url,
+            "labels": issue.labels,
+        },
+        "thread": {
+            "id": thread_id.to_string(),
+            "guild_id": guild_id.to_string(),
+            "url": format!("https://discord.com/channels/{guild_id}/{thread_id}"),
+        },
+        "reporter": reporter,
+        "closed_at": closed_at.to_rfc3339(),
+    })
+}
+
+/// Post a project's due events, scheduling failed ones for a retry
+pub async fn deliver_pending(store: &Store, project: &Project) -> Result<()> {
+    let Some(hook) = &project.reward_hook else {
+        return Ok(());
+    };
+    let now = chrono::Utc::now().timestamp();
+    let due = store.due_reward_hooks(&project_key(project), now, REWARD_HOOK_MAX_ATTEMPTS)?;
+    if due.is_empty() {
+        return Ok(());
+    }
+    let secret = match &hook.secret_env {
+        Some(name) => Some(
+            std::env::var(name).with_context(|| format!("{name} must be set for reward_hook"))?,
+        ),
+        None => None,
+    };
+
+    let client = reqwest::Client::new();
+    for delivery in due {
+        match deliver(&client, hook, secret.as_deref(), &delivery).await {
+            Ok(()) => {
+                store.finish_reward_hook(delivery.id)?;
+                info!("Delivered reward hook for issue #{}", delivery.issue_number);
+            }
+            Err(e) if delivery.attempts + 1 >= REWARD_HOOK_MAX_ATTEMPTS => {
+                store.retry_reward_hook(delivery.id, now)?;
+                error!(
+                    "Giving up on the reward hook for issue #{} after {} attempts: {:#}",
+                    delivery.issue_number,
+                    delivery.attempts + 1,
+                    e
+                );
+            }
+            Err(e) => {
+                let delay = retry_delay(delivery.attempts);
+                store.retry_reward_hook(delivery.id, now + delay)?;
+                warn!(
+                    "Reward hook for issue #{} failed, retrying in {}s: {:#}",
+                    delivery.issue_number, delay, e
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn deliver(
+    client: &reqwest::Client,
+    hook: &RewardHookConfig,
+    secret: Option<&str>,
+    delivery: &RewardDelivery,
+) -> Result<()> {
+    let mut request = client
+        .post(&hook.url)
+        .timeout(Duration::from_secs(REWARD_HOOK_TIMEOUT_SECS))
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("X-Cardibot-Delivery", delivery.id.to_string())
+        .body(delivery.payload.clone());
+    if let Some(secret) = secret {
+        let signature = crate::webhook::sign(secret.as_bytes(), delivery.payload.as_bytes());
+        request = request.header(REWARD_HOOK_SIGNATURE_HEADER, signature);
+    }
+    request
+        .send()
+        .await
+        .map_err(|e| e.without_url())?
+        .error_for_status()
+        .map_err(|e| e.without_url())?;
+    Ok(())
+}
+
+/// Seconds before the next delivery of an event that failed `attempts` times
+fn retry_delay(attempts: u32) -> i64 {
+    REWARD_HOOK_RETRY_BASE_SECS << attempts.min(10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completed_event() {
+        let project: Project = toml::from_str(
+            r#"
+            discord_guild_id = "1"
+            discord_forum_id = "2"
+            github_owner = "o"
+            github_repo = "r"
+            "#,
+        )
+        .unwrap();
+        let issue = IssueSummary {
+            number: 12,
+            url: "https://github.com/o/r/issues/12".to_string(),
+            open: false,
+            completed: true,
+            milestone_closed: None,
+            labels: vec!["bug".to_string()],
+            assignees: Vec::new(),
+            updated_at: 0,
+        };
+        let closed_at = chrono::DateTime::from_timestamp(0, 0).unwrap();
+        let event = completed_event(
+            &project,
+            1,
+            3,
+            123456789012345678,
+            false,
+            false,
+            &issue,
+            closed_at,
+        );
+        assert_eq!(event["event"], "issue.completed");
+        assert_eq!(event["project"], "o/r");
+        assert_eq!(event["issue"]["number"], 12);
+        assert_eq!(event["reporter"]["discord_id"], "123456789012345678");
+        assert_eq!(event["thread"]["url"], "https://discord.com/channels/1/3");
+        assert_eq!(event["closed_at"], "1970-01-01T00:00:00+00:00");
+
+        let anonymous = completed_event(&project, 1, 3, 7, true, false, &issue, closed_at);
+        assert!(anonymous["reporter"].get("discord_id").is_none());
+        let included = completed_event(&project, 1, 3, 7, true, true, &issue, closed_at);
+        assert_eq!(included["reporter"]["discord_id"], "7");
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(0), REWARD_HOOK_RETRY_BASE_SECS);
+        assert_eq!(retry_delay(2), 4 * REWARD_HOOK_RETRY_BASE_SECS);
+    }
+}
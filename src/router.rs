@@ -0,0 +1,244 @@
+use serenity::all::{
+    CommandInteraction, ComponentInteraction, CreateInteractionResponse,
+    CreateInteractionResponseFollowup, CreateInteractionResponseMessage, Http, Interaction,
+    ModalInteraction,
+};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use crate::bot::Bot;
+use crate::constants::{MSG_INTERACTION_FAILED, MSG_INTERACTION_TIMED_OUT};
+use crate::error::BotError;
+
+/// What an interaction handler runs; it owns its inputs so it can be spawned
+pub type HandlerFuture = Pin<Box<dyn Future<Output = Result<(), BotError>> + Send>>;
+
+type CommandHandler = fn(Bot, Arc<Http>, CommandInteraction) -> HandlerFuture;
+type ComponentHandler = fn(Bot, Arc<Http>, ComponentInteraction) -> HandlerFuture;
+type ModalHandler = fn(Bot, Arc<Http>, ModalInteraction) -> HandlerFuture;
+
+enum Handler {
+    /// Slash command by name
+    Command(&'static str, CommandHandler),
+    /// Button or select menu whose custom ID the predicate accepts
+    Component(fn(&str) -> bool, ComponentHandler),
+    Modal(fn(&str) -> bool, ModalHandler),
+}
+
+struct Route {
+    /// Used in logs, e.g. "triage decision"
+    label: &'static str,
+    timeout: Duration,
+    handler: Handler,
+}
+
+/// How a handler run ended, and what the user is told if it never answered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Done,
+    Failed,
+    Panicked,
+    TimedOut,
+}
+
+impl Outcome {
+    fn fallback(self) -> Option<&'static str> {
+        match self {
+            Self::Done => None,
+            Self::Failed | Self::Panicked => Some(MSG_INTERACTION_FAILED),
+            Self::TimedOut => Some(MSG_INTERACTION_TIMED_OUT),
+        }
+    }
+
+    /// A handler that stopped mid-way may have deferred and left Discord
+    /// showing "thinking…"; one that returned an error has already reported it
+    /// if it got as far as answering
+    fn needs_followup(self) -> bool {
+        matches!(self, Self::Panicked | Self::TimedOut)
+    }
+}
+
+/// Interaction handlers, matched in the order they were added
+#[derive(Default)]
+pub struct Router {
+    routes: Vec<Route>,
+}
+
+impl Router {
+    pub fn command(
+        mut self,
+        name: &'static str,
+        label: &'static str,
+        handler: CommandHandler,
+    ) -> Self {
+        self.push(label, Handler::Command(name, handler));
+        self
+    }
+
+    pub fn component(
+        mut self,
+        matches: fn(&str) -> bool,
+        label: &'static str,
+        handler: ComponentHandler,
+    ) -> Self {
+        self.push(label, Handler::Component(matches, handler));
+        self
+    }
+
+    pub fn modal(
+        mut self,
+        matches: fn(&str) -> bool,
+        label: &'static str,
+        handler: ModalHandler,
+    ) -> Self {
+        self.push(label, Handler::Modal(matches, handler));
+        self
+    }
+
+    /// Override the timeout of the route added last
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        if let Some(route) = self.routes.last_mut() {
+            route.timeout = timeout;
+        }
+        self
+    }
+
+    fn push(&mut self, label: &'static str, handler: Handler) {
+        self.routes.push(Route {
+            label,
+            timeout: Duration::from_secs(crate::constants::INTERACTION_HANDLER_TIMEOUT_SECS),
+            handler,
+        });
+    }
+
+    /// The matching route and its handler's future
+    fn route(
+        &self,
+        bot: &Bot,
+        http: &Arc<Http>,
+        interaction: &Interaction,
+    ) -> Option<(&Route, HandlerFuture)> {
+        self.routes.iter().find_map(|route| {
+            let future = match (&route.handler, interaction) {
+                (Handler::Command(name, handler), Interaction::Command(command))
+                    if command.data.name == *name =>
+                {
+                    handler(bot.clone(), http.clone(), command.clone())
+                }
+                (Handler::Component(matches, handler), Interaction::Component(component))
+                    if matches(&component.data.custom_id) =>
+                {
+                    handler(bot.clone(), http.clone(), component.clone())
+                }
+                (Handler::Modal(matches, handler), Interaction::Modal(modal))
+                    if matches(&modal.data.custom_id) =>
+                {
+                    handler(bot.clone(), http.clone(), modal.clone())
+                }
+                _ => return None,
+            };
+            Some((route, future))
+        })
+    }
+
+    /// Run the matching handler with its timeout, catching panics, and make
+    /// sure the user gets an answer when it fails without giving one.
+    /// Unmatched interactions (autocomplete, pings, etc.) are ignored.
+    pub async fn dispatch(&self, bot: &Bot, http: Arc<Http>, interaction: Interaction) {
+        let Some((route, future)) = self.route(bot, &http, &interaction) else {
+            return;
+        };
+
+        let mut task = tokio::spawn(future);
+        let outcome = match tokio::time::timeout(route.timeout, &mut task).await {
+            Ok(Ok(Ok(()))) => Outcome::Done,
+            Ok(Ok(Err(e))) => {
+                if e.is_user_facing() {
+                    warn!(category = e.category(), "{} failed: {}", route.label, e);
+                } else {
+                    error!(
+                        category = e.category(),
+                        "Error handling {}: {:?}", route.label, e
+                    );
+                }
+                Outcome::Failed
+            }
+            Ok(Err(e)) => {
+                error!("Handler for {} panicked: {}", route.label, e);
+                Outcome::Panicked
+            }
+            Err(_) => {
+                error!(
+                    "Handler for {} timed out after {}s",
+                    route.label,
+                    route.timeout.as_secs()
+                );
+                // Cancelling could stop a filing between GitHub and the store,
+                // so the handler is left to finish and log its result
+                let label = route.label;
+                tokio::spawn(async move {
+                    match task.await {
+                        Ok(Ok(())) => info!("Handler for {} finished after timing out", label),
+                        Ok(Err(e)) => error!("Error handling {} after timing out: {:?}", label, e),
+                        Err(e) => error!("Handler for {} panicked: {}", label, e),
+                    }
+                });
+                Outcome::TimedOut
+            }
+        };
+
+        if let Some(message) = outcome.fallback() {
+            send_fallback(&http, &interaction, message, outcome.needs_followup()).await;
+        }
+    }
+}
+
+/// Answer an interaction the handler left unanswered, or replace its
+/// "thinking…" state with a follow-up when `followup` is set
+async fn send_fallback(http: &Http, interaction: &Interaction, message: &str, followup: bool) {
+    let response = CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .content(format!("❌ {message}"))
+            .ephemeral(true),
+    );
+    // Fails when the handler already answered
+    if http
+        .create_interaction_response(interaction.id(), interaction.token(), &response, vec![])
+        .await
+        .is_ok()
+        || !followup
+    {
+        return;
+    }
+
+    let followup = CreateInteractionResponseFollowup::new()
+        .content(format!("❌ {message}"))
+        .ephemeral(true);
+    if let Err(e) = http
+        .create_followup_message(interaction.token(), &followup, vec![])
+        .await
+    {
+        warn!("Failed to send fallback response: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outcome_fallbacks() {
+        assert_eq!(Outcome::Done.fallback(), None);
+        assert_eq!(Outcome::Failed.fallback(), Some(MSG_INTERACTION_FAILED));
+        assert!(!Outcome::Failed.needs_followup());
+        assert_eq!(
+            Outcome::TimedOut.fallback(),
+            Some(MSG_INTERACTION_TIMED_OUT)
+        );
+        assert!(Outcome::TimedOut.needs_followup());
+        assert!(Outcome::Panicked.needs_followup());
+    }
+}
@@ -0,0 +1,108 @@
+use regex::Regex;
+
+/// Make GitHub-authored text safe to post on Discord.
+///
+/// Mentions are defanged as text as well as blocked through allowed-mentions,
+/// so a missed builder call can't ping a whole server, and embedded images
+/// become plain links so they don't expand into huge previews.
+pub fn sanitize_for_discord(text: &str) -> String {
+    let zero_width = "\u{200B}";
+
+    // @everyone / @here
+    let mass = Regex::new(r"(^|[^\w])@(everyone|here)\b").expect("valid regex");
+    let text = mass.replace_all(text, format!("${{1}}@{zero_width}${{2}}"));
+
+    // Role mentions <@&123> and user mentions <@123>/<@!123>
+    let role = Regex::new(r"<@&(\d+)>").expect("valid regex");
+    let text = role.replace_all(&text, "@role");
+    let user = Regex::new(r"<@!?(\d+)>").expect("valid regex");
+    let text = user.replace_all(&text, "@user");
+
+    // Markdown images ![alt](url) and HTML <img src="url">
+    let image = Regex::new(r"!\[([^\]]*)\]\(([^)\s]+)[^)]*\)").expect("valid regex");
+    let text = image.replace_all(&text, |caps: &regex::Captures| {
+        let alt = caps.get(1).map(|m| m.as_str()).unwrap_or_default();
+        let label = if alt.is_empty() { "image" } else { alt };
+        format!("[{label}](<{}>)", &caps[2])
+    });
+    let img_tag = Regex::new(r#"(?i)<img[^>]*\bsrc=["']([^"']+)["'][^>]*>"#).expect("valid regex");
+    let text = img_tag.replace_all(&text, "[image](<$1>)");
+
+    text.into_owned()
+}
+
+/// Cut text to Discord's limit on a character boundary, marking the cut
+pub fn truncate_for_discord(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{kept}…")
+}
+
+/// Tidy a thread name into an issue title of at most `max_chars`: control
+/// and invisible formatting characters are dropped, whitespace runs including
+/// newlines become single spaces, and a long name is cut at a word boundary
+/// when one is near the end
+pub fn sanitize_issue_title(title: &str, max_chars: usize) -> String {
+    let cleaned: String = title
+        .chars()
+        .map(|c| if c.is_whitespace() { ' ' } else { c })
+        .filter(|c| !c.is_control() && !is_invisible(*c))
+        .collect();
+    let title = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    if title.chars().count() <= max_chars {
+        return title;
+    }
+
+    let kept: String = title.chars().take(max_chars.saturating_sub(1)).collect();
+    let cut = match kept.rfind(' ') {
+        Some(i) if i >= kept.len() / 2 => &kept[..i],
+        _ => &kept,
+    };
+    format!("{}…", cut.trim_end())
+}
+
+/// Zero-width and direction-changing characters that make titles render oddly.
+/// The zero-width joiner is kept since emoji sequences need it.
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{200B}' | '\u{200C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}' | '\u{2066}'..='\u{2069}' | '\u{FEFF}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_for_discord() {
+        let text = "@everyone look <@&42> <@!7> ![screenshot](https://x.io/a.png \"t\") <img src=\"https://x.io/b.png\" width=900>";
+        assert_eq!(
+            sanitize_for_discord(text),
+            "@\u{200B}everyone look @role @user [screenshot](<https://x.io/a.png>) [image](<https://x.io/b.png>)"
+        );
+        assert_eq!(sanitize_for_discord("mail me@here.com"), "mail me@here.com");
+        assert_eq!(truncate_for_discord("abcdef", 4), "abc…");
+    }
+
+    #[test]
+    fn test_sanitize_issue_title() {
+        assert_eq!(
+            sanitize_issue_title("[BUG]  Crash\non\tsave\u{200B} ", 100),
+            "[BUG] Crash on save"
+        );
+        assert_eq!(
+            sanitize_issue_title("🐛 Bug 👩\u{200D}💻", 100),
+            "🐛 Bug 👩\u{200D}💻"
+        );
+        assert_eq!(
+            sanitize_issue_title("[BUG] Crash on save", 14),
+            "[BUG] Crash…"
+        );
+        // No word boundary near the end
+        assert_eq!(sanitize_issue_title("abcdefgh ij", 6), "abcde…");
+    }
+}
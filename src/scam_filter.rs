@@ -0,0 +1,204 @@
+use regex::Regex;
+use serenity::builder::{CreateAllowedMentions, CreateMessage};
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use std::sync::LazyLock;
+use tracing::warn;
+
+use crate::config::{Project, ScamFilterConfig};
+use crate::embeds::Embed;
+use crate::redact::Origin;
+use crate::store::Store;
+
+static INVITE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)(?:https?://)?(?:www\.)?(?:discord(?:app)?\.com/invite|discord\.(?:gg|io|me|li)|dsc\.gg)/[a-z0-9-]+",
+    )
+    .expect("valid regex")
+});
+
+// "Send me your seed phrase", "verify your 24 words"; a leading negation
+// ("never share your seed phrase") is captured so warnings are kept
+static SEED_PHRASE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"(?i)(\b(?:never|not|don't|dont)\s+)?",
+        r"\b(?:send|share|enter|type|paste|provide|give|import|submit|verify|validate|confirm)\b",
+        r"[^\n.!?]{0,60}?",
+        r"\b(?:(?:seed|recovery|secret|mnemonic|backup)[ -]?(?:phrase|words?|keys?)|private[ -]?keys?|(?:12|15|24)[ -]words?)\b",
+    ))
+    .expect("valid regex")
+});
+
+// Fake "wallet sync/validation" sites that drain wallets
+static WALLET_LURE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:wallet|dapp)s?[ -]?(?:connect|sync|synchroni[sz]e|validat(?:e|ion)|rectif(?:y|ication)|recovery|restore)\b[^\n]{0,40}?https?://\S+",
+    )
+    .expect("valid regex")
+});
+
+/// Text kept off GitHub and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Removal {
+    pub reason: &'static str,
+    pub value: String,
+}
+
+/// Replace invite links, seed phrase requests, wallet lures and any `extra`
+/// pattern with a `[removed: reason]` placeholder
+pub fn filter(text: &str, extra: &[Regex]) -> (String, Vec<Removal>) {
+    let mut removals = Vec::new();
+    let mut text = text.to_string();
+
+    let builtin: [(&Regex, &'static str); 3] = [
+        (&INVITE, "invite link"),
+        (&SEED_PHRASE, "seed phrase request"),
+        (&WALLET_LURE, "wallet lure"),
+    ];
+    let patterns = builtin
+        .into_iter()
+        .chain(extra.iter().map(|re| (re, "filtered pattern")));
+    for (pattern, reason) in patterns {
+        text = pattern
+            .replace_all(&text, |caps: &regex::Captures| {
+                if caps.get(1).is_some() && std::ptr::eq(pattern, &*SEED_PHRASE) {
+                    return caps[0].to_string();
+                }
+                let value = caps[0].to_string();
+                if !removals.iter().any(|r: &Removal| r.value == value) {
+                    removals.push(Removal { reason, value });
+                }
+                format!("[removed: {reason}]")
+            })
+            .into_owned();
+    }
+    (text, removals)
+}
+
+/// Text with the project's `scam_filter` applied. Removed content is posted
+/// once per thread to the filter's moderator channel.
+pub async fn for_github(
+    discord: &Http,
+    store: &Store,
+    project: &Project,
+    origin: Origin<'_>,
+    text: &str,
+) -> String {
+    let Some(config) = &project.scam_filter else {
+        return text.to_string();
+    };
+    let (filtered, removals) = filter(text, &extra_patterns(config));
+    if removals.is_empty() {
+        return filtered;
+    }
+
+    let key = crate::audit::project_key(project);
+    let mut new = Vec::new();
+    for r in removals {
+        match store.redacted_value_reported(&key, origin.record_id(), &r.value) {
+            Ok(false) => new.push(r),
+            Ok(true) => {}
+            Err(e) => warn!("Failed to look up filtered content: {}", e),
+        }
+    }
+    if new.is_empty() {
+        return filtered;
+    }
+    warn!(
+        "Kept {} suspicious snippets from {} off GitHub",
+        new.len(),
+        origin.mention()
+    );
+
+    let channel = config
+        .channel_id
+        .as_deref()
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(ChannelId::new);
+    if let Some(channel) = channel {
+        // Recorded once sent, so a failed flag is retried with the next text
+        if let Err(e) = flag_thread(discord, channel, origin, &new).await {
+            warn!(
+                "Failed to flag {} to channel {}: {}",
+                origin.mention(),
+                channel,
+                e
+            );
+            return filtered;
+        }
+    }
+    for r in &new {
+        if let Err(e) = store.record_redacted_value(&key, origin.record_id(), &r.value) {
+            warn!("Failed to record filtered content: {}", e);
+        }
+    }
+    filtered
+}
+
+/// The project's own patterns, checked when the config is loaded
+pub fn extra_patterns(config: &ScamFilterConfig) -> Vec<Regex> {
+    config
+        .patterns
+        .iter()
+        .filter_map(|p| Regex::new(p).ok())
+        .collect()
+}
+
+async fn flag_thread(
+    discord: &Http,
+    channel: ChannelId,
+    origin: Origin<'_>,
+    removals: &[Removal],
+) -> serenity::Result<()> {
+    let lines: Vec<String> = removals
+        .iter()
+        .map(|r| format!("**{}**\n```\n{}\n```", r.reason, r.value.replace('`', "'")))
+        .collect();
+    let description = format!("In {}\n\n{}", origin.mention(), lines.join("\n"));
+    Embed::new()
+        .title(crate::constants::MSG_SCAM_FILTERED)
+        .description(description)
+        .color(crate::constants::COLOR_FAILURE)
+        .send(
+            discord,
+            channel,
+            CreateMessage::new().allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter() {
+        let text = "Join discord.gg/free-ada for help.\n\
+                    Support here: please send your 24 words so we can verify.\n\
+                    Go to the wallet sync page https://evil.example/sync now.\n\
+                    Never share your seed phrase with anyone.";
+        let (filtered, removals) = filter(text, &[]);
+        assert_eq!(
+            filtered,
+            "Join [removed: invite link] for help.\n\
+             Support here: please [removed: seed phrase request] so we can verify.\n\
+             Go to the [removed: wallet lure] now.\n\
+             Never share your seed phrase with anyone."
+        );
+        let reasons: Vec<_> = removals.iter().map(|r| r.reason).collect();
+        assert_eq!(
+            reasons,
+            vec!["invite link", "seed phrase request", "wallet lure"]
+        );
+
+        let extra = [Regex::new(r"(?i)airdrop").unwrap()];
+        let (filtered, removals) = filter("Free AIRDROP today", &extra);
+        assert_eq!(filtered, "Free [removed: filtered pattern] today");
+        assert_eq!(removals[0].value, "AIRDROP");
+
+        let (clean, removals) = filter("The app crashes on start", &[]);
+        assert_eq!(clean, "The app crashes on start");
+        assert!(removals.is_empty());
+    }
+}
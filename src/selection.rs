@@ -0,0 +1,253 @@
+use serenity::all::{CommandDataOptionValue, CommandInteraction};
+use serenity::model::channel::Message;
+use serenity::model::id::MessageId;
+
+/// Which thread messages go into the issue body. With nothing set, messages
+/// marked with the project's marker reaction are used, or the opening
+/// messages when none are marked.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MessageSelection {
+    /// Only the last `n` messages
+    pub last: Option<usize>,
+    /// Unix timestamps bounding the messages, `until` exclusive
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+}
+
+impl MessageSelection {
+    /// Read the `messages`, `since` and `until` options of `/issue create`;
+    /// errors are meant for the user
+    pub fn from_command(command: &CommandInteraction) -> Result<Self, String> {
+        let mut selection = Self::default();
+        let Some(CommandDataOptionValue::SubCommand(options)) =
+            command.data.options.first().map(|o| &o.value)
+        else {
+            return Ok(selection);
+        };
+
+        for option in options {
+            match (option.name.as_str(), &option.value) {
+                ("messages", CommandDataOptionValue::Integer(n)) => {
+                    selection.last = usize::try_from(*n).ok();
+                }
+                ("since", CommandDataOptionValue::String(value)) => {
+                    selection.since = Some(parse_bound(value, false)?);
+                }
+                ("until", CommandDataOptionValue::String(value)) => {
+                    selection.until = Some(parse_bound(value, true)?);
+                }
+                _ => {}
+            }
+        }
+
+        if let (Some(since), Some(until)) = (selection.since, selection.until) {
+            if since >= until {
+                return Err("`since` must be before `until`".to_string());
+            }
+        }
+        Ok(selection)
+    }
+
+    /// Compact `last:since:until` form for carrying a selection in a
+    /// component's custom ID
+    pub fn encode(&self) -> String {
+        let part = |v: Option<i64>| v.map(|v| v.to_string()).unwrap_or_default();
+        format!(
+            "{}:{}:{}",
+            part(self.last.map(|n| n as i64)),
+            part(self.since),
+            part(self.until)
+        )
+    }
+
+    /// Read a selection written by `encode`; anything unreadable is left unset
+    pub fn decode(value: &str) -> Self {
+        let mut parts = value.splitn(3, ':').map(|p| p.parse::<i64>().ok());
+        let mut next = || parts.next().flatten();
+        Self {
+            last: next().and_then(|n| usize::try_from(n).ok()),
+            since: next(),
+            until: next(),
+        }
+    }
+
+    /// The selection as filed at `now`: a range left open stops there, so
+    /// rendering it again later doesn't take in newer replies
+    pub fn pinned(&self, now: i64) -> Self {
+        let mut pinned = self.clone();
+        if self.is_explicit() && pinned.until.is_none() {
+            pinned.until = Some(now + 1);
+        }
+        pinned
+    }
+
+    fn is_explicit(&self) -> bool {
+        self.last.is_some() || self.since.is_some() || self.until.is_some()
+    }
+}
+
+/// A plain date as `until` covers that whole day
+fn parse_bound(value: &str, end: bool) -> Result<i64, String> {
+    let value = value.trim();
+    let time = crate::audit::parse_time(value).map_err(|e| e.to_string())?;
+    let whole_day = chrono::DateTime::parse_from_rfc3339(value).is_err();
+    Ok(if end && whole_day {
+        time + 86_400
+    } else {
+        time
+    })
+}
+
+/// Pick the messages for the issue body from `messages` (newest first, as
+/// Discord returns them), oldest first. The thread's opening post is kept
+/// whenever it was fetched.
+pub fn select_messages<'a>(
+    messages: &'a [Message],
+    selection: &MessageSelection,
+    marker: &str,
+    starter: MessageId,
+) -> Vec<&'a Message> {
+    let mut selected: Vec<&Message> = if selection.is_explicit() {
+        let in_range: Vec<&Message> = messages
+            .iter()
+            .rev()
+            .filter(|m| {
+                let at = m.timestamp.unix_timestamp();
+                selection.since.is_none_or(|since| at >= since)
+                    && selection.until.is_none_or(|until| at < until)
+            })
+            .collect();
+        let skip = selection
+            .last
+            .map_or(0, |n| in_range.len().saturating_sub(n));
+        in_range.into_iter().skip(skip).collect()
+    } else {
+        let marked: Vec<&Message> = messages
+            .iter()
+            .rev()
+            .filter(|m| {
+                m.reactions
+                    .iter()
+                    .any(|r| r.reaction_type.unicode_eq(marker))
+            })
+            .collect();
+        if marked.is_empty() {
+            messages
+                .iter()
+                .take(crate::constants::GITHUB_THREAD_CONTENT_LIMIT as usize)
+                .rev()
+                .take(crate::constants::GITHUB_THREAD_CONTENT_MESSAGES)
+                .collect()
+        } else {
+            marked
+        }
+    };
+
+    if !selected.iter().any(|m| m.id == starter) {
+        if let Some(opening) = messages.iter().find(|m| m.id == starter) {
+            selected.insert(0, opening);
+        }
+    }
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serenity::model::channel::{MessageReaction, ReactionType};
+    use serenity::model::Timestamp;
+
+    fn message(id: u64, at: i64, marked: bool) -> Message {
+        let mut message = Message::default();
+        message.id = MessageId::new(id);
+        message.timestamp = Timestamp::from_unix_timestamp(at).unwrap();
+        if marked {
+            let mut reaction: MessageReaction = serde_json::from_value(serde_json::json!({
+                "count": 1,
+                "count_details": { "burst": 0, "normal": 1 },
+                "me": false,
+                "me_burst": false,
+                "emoji": { "id": null, "name": "📌" },
+                "burst_colors": []
+            }))
+            .unwrap();
+            reaction.reaction_type = ReactionType::Unicode("📌".to_string());
+            message.reactions.push(reaction);
+        }
+        message
+    }
+
+    fn ids(messages: &[&Message]) -> Vec<u64> {
+        messages.iter().map(|m| m.id.get()).collect()
+    }
+
+    #[test]
+    fn test_select_messages() {
+        // Newest first, as fetched; message 1 opens the thread
+        let messages: Vec<Message> = (1..=8)
+            .rev()
+            .map(|id| message(id, id as i64 * 100, id == 6))
+            .collect();
+        let starter = MessageId::new(1);
+
+        let marked = select_messages(&messages, &MessageSelection::default(), "📌", starter);
+        assert_eq!(ids(&marked), vec![1, 6]);
+
+        let unmarked = select_messages(&messages, &MessageSelection::default(), "✅", starter);
+        assert_eq!(ids(&unmarked), vec![1, 2, 3, 4, 5]);
+
+        let last = MessageSelection {
+            last: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(&select_messages(&messages, &last, "📌", starter)),
+            vec![1, 7, 8]
+        );
+
+        let range = MessageSelection {
+            since: Some(300),
+            until: Some(500),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(&select_messages(&messages, &range, "📌", starter)),
+            vec![1, 3, 4]
+        );
+    }
+
+    #[test]
+    fn test_parse_bound() {
+        assert_eq!(parse_bound("2025-01-02", false).unwrap(), 1_735_776_000);
+        assert_eq!(parse_bound("2025-01-02", true).unwrap(), 1_735_862_400);
+        assert_eq!(
+            parse_bound("2025-01-02T12:00:00Z", true).unwrap(),
+            1_735_819_200
+        );
+        assert!(parse_bound("yesterday", false).is_err());
+    }
+
+    #[test]
+    fn test_encode() {
+        let selection = MessageSelection {
+            last: Some(20),
+            since: None,
+            until: Some(1_735_862_400),
+        };
+        assert_eq!(selection.encode(), "20::1735862400");
+        assert_eq!(MessageSelection::decode(&selection.encode()), selection);
+        assert_eq!(MessageSelection::decode("::"), MessageSelection::default());
+
+        // Filing pins an open range, never the default selection
+        let last = MessageSelection {
+            last: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(last.pinned(100).until, Some(101));
+        assert_eq!(selection.pinned(100), selection);
+        assert_eq!(
+            MessageSelection::default().pinned(100),
+            MessageSelection::default()
+        );
+    }
+}
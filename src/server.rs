@@ -0,0 +1,130 @@
+use anyhow::{Context, Result};
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Bytes, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tracing::{debug, info};
+
+use crate::gateway::{GatewayHealth, GatewayStatus};
+use crate::interactions::InteractionsEndpoint;
+use crate::supervisor::SyncerHealth;
+use crate::webhook::WebhookHandler;
+
+/// Endpoints served on the `[webhook]` listen address
+pub struct Routes {
+    /// `POST /github/webhook`
+    pub webhook: Option<Arc<WebhookHandler>>,
+    /// `POST /discord/interactions`
+    pub interactions: Option<Arc<InteractionsEndpoint>>,
+    /// Reported on `GET /health` per bot, the main one first; empty when
+    /// running without a gateway
+    pub gateways: Vec<(String, Arc<GatewayHealth>)>,
+    /// Restarts of each bot's syncer, reported on `GET /health`
+    pub syncers: Vec<(String, Arc<SyncerHealth>)>,
+}
+
+pub async fn serve(routes: Arc<Routes>, listen: &str) -> Result<()> {
+    let listener = TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("Failed to listen on {listen}"))?;
+    info!(
+        "Listening on {} (GitHub webhooks: {}, Discord interactions: {})",
+        listen,
+        routes.webhook.is_some(),
+        routes.interactions.is_some()
+    );
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let routes = routes.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |request| route(routes.clone(), request));
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                debug!("HTTP connection error: {}", e);
+            }
+        });
+    }
+}
+
+async fn route(
+    routes: Arc<Routes>,
+    request: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, std::convert::Infallible> {
+    if request.method() == Method::GET && request.uri().path() == "/health" {
+        return Ok(health(&routes.gateways, &routes.syncers));
+    }
+    if request.method() != Method::POST {
+        return Ok(reply(StatusCode::NOT_FOUND, "not found"));
+    }
+
+    let (parts, body) = request.into_parts();
+    let body = match Limited::new(body, crate::constants::WEBHOOK_MAX_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(body) => body.to_bytes(),
+        Err(_) => return Ok(reply(StatusCode::PAYLOAD_TOO_LARGE, "body too large")),
+    };
+
+    Ok(
+        match (parts.uri.path(), &routes.webhook, &routes.interactions) {
+            ("/github/webhook", Some(handler), _) => {
+                crate::webhook::respond(handler, &parts.headers, &body)
+            }
+            ("/discord/interactions", _, Some(endpoint)) => {
+                crate::interactions::respond(endpoint, &parts.headers, &body).await
+            }
+            _ => reply(StatusCode::NOT_FOUND, "not found"),
+        },
+    )
+}
+
+/// 200 while every gateway is connected (or none is used), 503 otherwise.
+/// `gateway` is the main bot; `bots` lists each one when there are several.
+/// `syncers` has each bot's syncer and how often it was restarted.
+fn health(
+    gateways: &[(String, Arc<GatewayHealth>)],
+    syncers: &[(String, Arc<SyncerHealth>)],
+) -> Response<Full<Bytes>> {
+    let statuses: Vec<(&str, GatewayStatus)> = gateways
+        .iter()
+        .map(|(name, gateway)| (name.as_str(), gateway.status()))
+        .collect();
+    let code = if statuses.iter().all(|(_, status)| status.connected) {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    let mut body = serde_json::json!({ "gateway": statuses.first().map(|(_, status)| status) });
+    if statuses.len() > 1 {
+        body["bots"] = statuses
+            .iter()
+            .map(|(name, status)| (name.to_string(), serde_json::json!(status)))
+            .collect::<serde_json::Map<_, _>>()
+            .into();
+    }
+    body["syncers"] = syncers
+        .iter()
+        .map(|(name, syncer)| (name.to_string(), serde_json::json!(syncer.status())))
+        .collect::<serde_json::Map<_, _>>()
+        .into();
+    let mut response = reply(code, &body.to_string());
+    response
+        .headers_mut()
+        .insert("Content-Type", "application/json".parse().unwrap());
+    response
+}
+
+pub fn reply(status: StatusCode, text: &str) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(text.to_string())));
+    *response.status_mut() = status;
+    response
+}
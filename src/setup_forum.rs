@@ -0,0 +1,125 @@
+use anyhow::Result;
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+
+use crate::config::{Config, Project};
+
+/// Discord's limits on forum tags
+const MAX_FORUM_TAGS: usize = 20;
+const MAX_TAG_NAME_LEN: usize = 20;
+
+/// Create any forum tags missing from the configured prefix taxonomy
+pub async fn setup_forum(project_filter: Option<&str>, dry_run: bool) -> Result<()> {
+    println!("🏷️  Setting up forum tags...");
+    if dry_run {
+        println!("   (dry run - no changes will be made)");
+    }
+    println!();
+
+    let config = Config::load()?;
+    let clients = crate::clients::Clients::new_standalone().await?;
+
+    for (idx, project) in crate::threads::selected_projects(&config, project_filter)
+        .into_iter()
+        .enumerate()
+    {
+        println!(
+            "Project {}: {}",
+            idx + 1,
+            project.name.as_deref().unwrap_or("unnamed")
+        );
+
+        let discord = clients.discord_for(&config, project)?;
+        match setup_project_forum(&discord, project, dry_run).await {
+            Ok(0) => println!("  ✅ All tags already exist"),
+            Ok(count) => {
+                let verb = if dry_run { "Would create" } else { "Created" };
+                println!("  ✅ {verb} {count} tags");
+            }
+            Err(e) => eprintln!("  ❌ Error: {e}"),
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Tags for the prefix taxonomy and, when enabled, the status tags
+fn wanted_tags(project: &Project) -> Vec<String> {
+    let mut names: Vec<String> = project.prefixes().iter().map(|p| p.tag_name()).collect();
+    if let Some(status_tags) = &project.status_tags {
+        names.extend(status_tags.tag_names().iter().map(|name| name.to_string()));
+    }
+    names
+}
+
+async fn setup_project_forum(discord: &Http, project: &Project, dry_run: bool) -> Result<usize> {
+    let forum_id = ChannelId::new(project.discord_forum_id.parse()?);
+    let forum = forum_id
+        .to_channel(discord)
+        .await?
+        .guild()
+        .ok_or_else(|| anyhow::anyhow!("Channel {forum_id} is not a guild channel"))?;
+
+    let existing = &forum.available_tags;
+    let wanted = wanted_tags(project);
+    let missing: Vec<String> = wanted
+        .iter()
+        .filter(|name| !existing.iter().any(|t| t.name.eq_ignore_ascii_case(name)))
+        .cloned()
+        .collect();
+
+    for tag in existing {
+        let managed = wanted
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(&tag.name));
+        let marker = if managed { "✓" } else { "·" };
+        println!("  {marker} {}", tag.name);
+    }
+    for name in &missing {
+        println!("  + {name}");
+    }
+
+    if let Some(name) = missing
+        .iter()
+        .find(|n| n.chars().count() > MAX_TAG_NAME_LEN)
+    {
+        anyhow::bail!("Tag '{name}' is longer than {MAX_TAG_NAME_LEN} characters");
+    }
+    if existing.len() + missing.len() > MAX_FORUM_TAGS {
+        anyhow::bail!(
+            "Forum would have {} tags, Discord allows at most {MAX_FORUM_TAGS}",
+            existing.len() + missing.len()
+        );
+    }
+
+    if missing.is_empty() || dry_run {
+        return Ok(missing.len());
+    }
+
+    // Existing tags are sent back with their IDs so posts keep them
+    let mut tags: Vec<serde_json::Value> = existing
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<_, _>>()?;
+    // Status tags are the syncer's to set, so members can't apply them
+    let status_tags: Vec<&str> = project
+        .status_tags
+        .as_ref()
+        .map(|t| t.tag_names().to_vec())
+        .unwrap_or_default();
+    tags.extend(missing.iter().map(|name| {
+        let moderated = status_tags.iter().any(|s| s.eq_ignore_ascii_case(name));
+        serde_json::json!({ "name": name, "moderated": moderated })
+    }));
+
+    discord
+        .edit_channel(
+            forum_id,
+            &serde_json::json!({ "available_tags": tags }),
+            Some("cardibot setup-forum"),
+        )
+        .await?;
+
+    Ok(missing.len())
+}
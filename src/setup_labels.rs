@@ -0,0 +1,239 @@
+use anyhow::Result;
+use octocrab::models::Label;
+use octocrab::Octocrab;
+
+use crate::config::{Config, Project};
+
+/// A label cardibot applies, as it should look on GitHub
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LabelSpec {
+    name: String,
+    color: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum LabelChange {
+    Create(LabelSpec),
+    Update(LabelSpec),
+}
+
+/// Create or restyle the labels each project's bot applies
+pub async fn setup_labels(project_filter: Option<&str>, dry_run: bool) -> Result<()> {
+    println!("🏷️  Setting up GitHub labels...");
+    if dry_run {
+        println!("   (dry run - no changes will be made)");
+    }
+    println!();
+
+    let config = Config::load()?;
+    let clients = crate::clients::Clients::new_standalone().await?;
+
+    for (idx, project) in crate::threads::selected_projects(&config, project_filter)
+        .into_iter()
+        .enumerate()
+    {
+        println!(
+            "Project {}: {}",
+            idx + 1,
+            project.name.as_deref().unwrap_or("unnamed")
+        );
+
+        // Prefixes routed to other repositories need their labels there too
+        for route in &project.routes() {
+            println!("  - GitHub: {}/{}", route.github_owner, route.github_repo);
+            let github = clients.github_for(&config, route).await?;
+            match setup_project_labels(&github, route, dry_run).await {
+                Ok(0) => println!("  ✅ All labels are up to date"),
+                Ok(count) => {
+                    let verb = if dry_run { "Would change" } else { "Changed" };
+                    println!("  ✅ {verb} {count} labels");
+                }
+                Err(e) => eprintln!("  ❌ Error: {e}"),
+            }
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+async fn setup_project_labels(
+    github: &Octocrab,
+    project: &Project,
+    dry_run: bool,
+) -> Result<usize> {
+    let issues = github.issues(&project.github_owner, &project.github_repo);
+    let first = issues.list_labels_for_repo().per_page(100).send().await?;
+    let existing = github.all_pages(first).await?;
+
+    let changes = plan(&wanted_labels(project), &existing);
+    for change in &changes {
+        match change {
+            LabelChange::Create(spec) => println!("  + {}", spec.name),
+            LabelChange::Update(spec) => println!("  ~ {}", spec.name),
+        }
+    }
+    if dry_run {
+        return Ok(changes.len());
+    }
+
+    for change in &changes {
+        match change {
+            LabelChange::Create(spec) => {
+                issues
+                    .create_label(
+                        &spec.name,
+                        spec.color
+                            .as_deref()
+                            .unwrap_or(crate::constants::DEFAULT_LABEL_COLOR),
+                        spec.description.as_deref().unwrap_or_default(),
+                    )
+                    .await?;
+            }
+            LabelChange::Update(spec) => update_label(github, project, spec).await?,
+        }
+    }
+    Ok(changes.len())
+}
+
+/// octocrab has no label update, so PATCH the label directly
+async fn update_label(github: &Octocrab, project: &Project, spec: &LabelSpec) -> Result<()> {
+    // Label names like "priority: high" need escaping in the path
+    let mut url = reqwest::Url::parse("https://api.github.com")?;
+    url.path_segments_mut()
+        .map_err(|_| anyhow::anyhow!("Invalid label URL"))?
+        .extend([
+            "repos",
+            &project.github_owner,
+            &project.github_repo,
+            "labels",
+            &spec.name,
+        ]);
+
+    let mut body = serde_json::Map::new();
+    if let Some(color) = &spec.color {
+        body.insert("color".into(), color.clone().into());
+    }
+    if let Some(description) = &spec.description {
+        body.insert("description".into(), description.clone().into());
+    }
+    let _: Label = github.patch(url.path(), Some(&body)).await?;
+    Ok(())
+}
+
+/// Every label the project's prefixes, priorities, needs-info flow and
+/// GitHub-to-Discord posts use, styled from `label_styles` or the built-ins
+fn wanted_labels(project: &Project) -> Vec<LabelSpec> {
+    let mut names: Vec<String> = project.prefixes().into_iter().map(|p| p.label).collect();
+    names.extend(project.priority_labels());
+    if let Some(needs_info) = &project.needs_info {
+        names.push(needs_info.label().to_string());
+    }
+    names.extend(project.discord_post_label.clone());
+
+    let mut seen: Vec<String> = Vec::new();
+    names
+        .into_iter()
+        .filter(|name| {
+            let new = !seen.iter().any(|s| s.eq_ignore_ascii_case(name));
+            seen.push(name.clone());
+            new
+        })
+        .map(|name| {
+            let configured = project.label_styles.as_ref().and_then(|s| s.get(&name));
+            let builtin = crate::constants::DEFAULT_LABEL_STYLES
+                .iter()
+                .find(|(label, _, _)| label.eq_ignore_ascii_case(&name));
+            LabelSpec {
+                color: configured
+                    .and_then(|s| s.color.as_deref())
+                    .or(builtin.map(|(_, color, _)| *color))
+                    .map(|c| c.trim_start_matches('#').to_lowercase()),
+                description: configured
+                    .and_then(|s| s.description.clone())
+                    .or(builtin.map(|(_, _, description)| description.to_string())),
+                name,
+            }
+        })
+        .collect()
+}
+
+/// Missing labels are created; existing ones only change where a color or
+/// description is known and differs
+fn plan(wanted: &[LabelSpec], existing: &[Label]) -> Vec<LabelChange> {
+    wanted
+        .iter()
+        .filter_map(|spec| {
+            let Some(label) = existing
+                .iter()
+                .find(|l| l.name.eq_ignore_ascii_case(&spec.name))
+            else {
+                return Some(LabelChange::Create(spec.clone()));
+            };
+            let color_differs = spec
+                .color
+                .as_ref()
+                .is_some_and(|c| !c.eq_ignore_ascii_case(&label.color));
+            let description_differs = spec
+                .description
+                .as_ref()
+                .is_some_and(|d| label.description.as_deref().unwrap_or_default() != d);
+            (color_differs || description_differs).then(|| {
+                LabelChange::Update(LabelSpec {
+                    // Keep GitHub's casing so the PATCH path matches
+                    name: label.name.clone(),
+                    ..spec.clone()
+                })
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(name: &str, color: &str, description: Option<&str>) -> Label {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "node_id": "L",
+            "url": "https://api.github.com/repos/o/r/labels/x",
+            "name": name,
+            "color": color,
+            "description": description,
+            "default": false,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_plan() {
+        let project: Project = toml::from_str(
+            r##"
+            discord_guild_id = "1"
+            discord_forum_id = "2"
+            github_repo = "app"
+            prefixes = [{ prefix = "[BUG]", label = "bug" }, { prefix = "[IDEA]", label = "idea" }]
+            priority_labels = ["P1"]
+            label_styles = { P1 = { color = "#FF0000" } }
+            "##,
+        )
+        .unwrap();
+        let wanted = wanted_labels(&project);
+        assert_eq!(
+            wanted.iter().map(|l| l.name.as_str()).collect::<Vec<_>>(),
+            ["bug", "idea", "P1"]
+        );
+        assert_eq!(wanted[2].color.as_deref(), Some("ff0000"));
+
+        let existing = [
+            label("Bug", "d73a4a", Some("Something isn't working")),
+            label("P1", "000000", None),
+        ];
+        let changes = plan(&wanted, &existing);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0], LabelChange::Create(wanted[1].clone()));
+        assert!(matches!(&changes[1], LabelChange::Update(spec) if spec.name == "P1"));
+    }
+}
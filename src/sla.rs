@@ -0,0 +1,146 @@
+use anyhow::Result;
+use octocrab::models::issues::Issue;
+use octocrab::Octocrab;
+use serenity::builder::{CreateAllowedMentions, CreateMessage};
+use serenity::http::Http;
+use serenity::model::id::{ChannelId, RoleId};
+use tracing::info;
+
+use crate::audit::{project_key, AuditAction, AuditEntry, AuditLog};
+use crate::config::Project;
+use crate::store::Store;
+
+fn labels(issue: &Issue) -> Vec<String> {
+    issue.labels.iter().map(|l| l.name.clone()).collect()
+}
+
+/// Whether an open issue still waits for its first maintainer response, so
+/// the syncer spends a call on checking it
+pub fn pending(store: &Store, project: &Project, issue: &Issue) -> bool {
+    if project.sla_target(&labels(issue)).is_none() {
+        return false;
+    }
+    match store.sla_timer(&project_key(project), issue.number) {
+        Ok(Some(timer)) => timer.responded_at.is_none(),
+        Ok(None) => true,
+        Err(_) => false,
+    }
+}
+
+/// Run an issue's SLA clock: start it, stop it at the first maintainer
+/// comment, or ping the maintainer role in the thread once it runs out
+pub async fn check(
+    github: &Octocrab,
+    discord: &Http,
+    store: &Store,
+    audit: &AuditLog,
+    project: &Project,
+    issue: &Issue,
+    thread_id: u64,
+) -> Result<()> {
+    let Some(target) = project.sla_target(&labels(issue)) else {
+        return Ok(());
+    };
+    let key = project_key(project);
+    let started_at = issue.created_at.timestamp();
+    let hours = target.first_response_hours;
+    store.start_sla_timer(
+        &key,
+        issue.number,
+        thread_id,
+        started_at,
+        started_at + (hours * 60 * 60) as i64,
+    )?;
+    let Some(timer) = store.sla_timer(&key, issue.number)? else {
+        return Ok(());
+    };
+    if timer.responded_at.is_some() {
+        return Ok(());
+    }
+
+    if let Some(responded_at) = first_maintainer_response(github, project, issue).await? {
+        store.record_sla_response(&key, issue.number, responded_at)?;
+        let took = (responded_at - timer.started_at) / 3600;
+        let outcome = if responded_at <= timer.due_at {
+            "met"
+        } else {
+            "missed"
+        };
+        info!(
+            "First maintainer response to issue #{} after {}h ({} the {}h target)",
+            issue.number, took, outcome, hours
+        );
+        audit
+            .record(
+                AuditEntry::new(project, AuditAction::SlaResponded)
+                    .thread(thread_id)
+                    .issue(issue.number)
+                    .details(format!("after {took}h, {outcome} the {hours}h target")),
+            )
+            .await;
+        return Ok(());
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    if now < timer.due_at || timer.breached_at.is_some() {
+        return Ok(());
+    }
+
+    let role = target
+        .role_id
+        .as_deref()
+        .or(project.notify_role_id.as_deref())
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(RoleId::new);
+    let text = format!(
+        "⏰ Issue #{} has had no maintainer response for {hours} hours",
+        issue.number
+    );
+    let message = match role {
+        Some(role) => CreateMessage::new()
+            .content(format!("<@&{role}> {text}"))
+            .allowed_mentions(CreateAllowedMentions::new().roles([role])),
+        None => CreateMessage::new().content(text),
+    };
+    ChannelId::new(thread_id)
+        .send_message(discord, message)
+        .await?;
+    store.record_sla_breach(&key, issue.number, now)?;
+    info!(
+        "Issue #{} missed its {}h response target",
+        issue.number, hours
+    );
+    audit
+        .record(
+            AuditEntry::new(project, AuditAction::SlaBreached)
+                .thread(thread_id)
+                .issue(issue.number)
+                .details(format!("no maintainer response within {hours}h")),
+        )
+        .await;
+    Ok(())
+}
+
+/// When a maintainer first commented on the issue. The bot files issues
+/// itself, so comments by the issue's author are its own and don't count.
+async fn first_maintainer_response(
+    github: &Octocrab,
+    project: &Project,
+    issue: &Issue,
+) -> Result<Option<i64>> {
+    let comments = github
+        .issues(&project.github_owner, &project.github_repo)
+        .list_comments(issue.number)
+        .since(issue.created_at)
+        .per_page(100)
+        .send()
+        .await?;
+
+    Ok(comments
+        .items
+        .iter()
+        .filter(|c| c.user.login != issue.user.login && c.user.r#type != "Bot")
+        .filter(|c| crate::comment_commands::is_maintainer(&c.author_association))
+        .map(|c| c.created_at.timestamp())
+        .min())
+}
@@ -0,0 +1,404 @@
+use anyhow::Result;
+use serenity::all::*;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::audit::{project_key, AuditAction, AuditEntry, AuditLog};
+use crate::config::{Config, Project};
+use crate::filing::Filed;
+use crate::selection::MessageSelection;
+use crate::store::Store;
+
+const SPLIT_MODAL_PREFIX: &str = "split:";
+const SECTION_INPUT_PREFIX: &str = "section";
+
+/// One problem from the split form: the first line is the issue title
+#[derive(Debug, PartialEq, Eq)]
+pub struct Section {
+    pub title: String,
+    pub body: String,
+}
+
+/// Parse a form field; blank fields are skipped
+pub fn parse_section(text: &str) -> Option<Section> {
+    let text = text.trim();
+    let (title, body) = text.split_once('\n').unwrap_or((text, ""));
+    let title = crate::sanitize::sanitize_issue_title(
+        title.trim().trim_start_matches('#'),
+        crate::constants::GITHUB_ISSUE_TITLE_LIMIT,
+    );
+    if title.is_empty() {
+        return None;
+    }
+    Some(Section {
+        title,
+        body: body.trim().to_string(),
+    })
+}
+
+/// Task list appended to the parent issue, one entry per child
+pub fn task_list(children: &[u64]) -> String {
+    let items = children
+        .iter()
+        .map(|number| format!("- [ ] #{number}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("### Split into\n\n{items}")
+}
+
+pub fn is_split_modal(custom_id: &str) -> bool {
+    custom_id.starts_with(SPLIT_MODAL_PREFIX)
+}
+
+/// Maintainers are the members who can manage threads
+pub fn is_maintainer(member: Option<&Member>) -> bool {
+    member
+        .and_then(|m| m.permissions)
+        .is_some_and(|p| p.manage_threads())
+}
+
+async fn reply(http: &Http, command: &CommandInteraction, content: &str) -> Result<()> {
+    command
+        .create_response(
+            http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Handle `/issue split`: ask for the problems to file, one per field. Runs
+/// before the command is deferred, since a form must be the first response.
+pub async fn show_modal(
+    http: &Http,
+    command: &CommandInteraction,
+    config: &Config,
+    store: &Store,
+) -> Result<()> {
+    let Some((thread, project)) =
+        crate::thread_events::resolve_thread(http, command.channel_id, config, store).await?
+    else {
+        return reply(http, command, "This command only works in threads!").await;
+    };
+    if !is_maintainer(command.member.as_deref()) {
+        return reply(
+            http,
+            command,
+            "Only maintainers (Manage Threads) can split a thread",
+        )
+        .await;
+    }
+    if !project.has_valid_prefix(&thread.name) {
+        return reply(
+            http,
+            command,
+            &format!(
+                "Threads need one of the prefixes {} to be filed",
+                project.prefix_list()
+            ),
+        )
+        .await;
+    }
+
+    let rows = (1..=crate::constants::SPLIT_MAX_SECTIONS)
+        .map(|n| {
+            CreateActionRow::InputText(
+                CreateInputText::new(
+                    InputTextStyle::Paragraph,
+                    format!("Problem {n}"),
+                    format!("{SECTION_INPUT_PREFIX}{n}"),
+                )
+                .placeholder("Title on the first line, then the details")
+                .required(n <= 2),
+            )
+        })
+        .collect();
+    let modal = CreateModal::new(
+        format!("{SPLIT_MODAL_PREFIX}{}", thread.id),
+        "Split into separate issues",
+    )
+    .components(rows);
+    command
+        .create_response(http, CreateInteractionResponse::Modal(modal))
+        .await?;
+    Ok(())
+}
+
+/// File one child issue per form field, list them in the thread's parent
+/// issue and link them all back to the thread
+pub async fn handle_modal(
+    http: &Http,
+    modal: &ModalInteraction,
+    config: &Arc<Config>,
+    store: &Store,
+    audit: &AuditLog,
+) -> Result<()> {
+    modal
+        .create_response(
+            http,
+            CreateInteractionResponse::Defer(
+                CreateInteractionResponseMessage::new().ephemeral(true),
+            ),
+        )
+        .await?;
+
+    let content = match split_thread(http, modal, config, store, audit).await {
+        Ok(content) => content,
+        Err(e) => {
+            let reply = EditInteractionResponse::new().content(format!("❌ {e}"));
+            modal.edit_response(http, reply).await?;
+            return Err(e);
+        }
+    };
+    modal
+        .edit_response(http, EditInteractionResponse::new().content(content))
+        .await?;
+    Ok(())
+}
+
+async fn split_thread(
+    http: &Http,
+    modal: &ModalInteraction,
+    config: &Arc<Config>,
+    store: &Store,
+    audit: &AuditLog,
+) -> Result<String> {
+    let Some((thread, project)) =
+        crate::thread_events::resolve_thread(http, modal.channel_id, config, store).await?
+    else {
+        return Ok("This thread is no longer in a configured forum".to_string());
+    };
+    if !is_maintainer(modal.member.as_ref()) {
+        return Ok("Only maintainers (Manage Threads) can split a thread".to_string());
+    }
+
+    let sections: Vec<Section> = modal
+        .data
+        .components
+        .iter()
+        .flat_map(|row| row.components.iter())
+        .filter_map(|component| match component {
+            ActionRowComponent::InputText(input)
+                if input.custom_id.starts_with(SECTION_INPUT_PREFIX) =>
+            {
+                input.value.as_deref().and_then(parse_section)
+            }
+            _ => None,
+        })
+        .collect();
+    if sections.len() < 2 {
+        return Ok("Describe at least two problems to split the thread".to_string());
+    }
+
+    if !store.try_lock_thread(thread.id.get(), crate::constants::THREAD_LOCK_TTL_SECS)? {
+        return Ok(crate::constants::MSG_THREAD_BUSY.to_string());
+    }
+    let result = split_locked(
+        http, modal, config, store, audit, &thread, &project, &sections,
+    )
+    .await;
+    store.unlock_thread(thread.id.get())?;
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn split_locked(
+    http: &Http,
+    modal: &ModalInteraction,
+    config: &Arc<Config>,
+    store: &Store,
+    audit: &AuditLog,
+    thread: &GuildChannel,
+    project: &Project,
+    sections: &[Section],
+) -> Result<String> {
+    let parent = match store.thread_issue(thread.id.get())? {
+        Some(linked) => linked.issue_number,
+        None => match crate::filing::file_thread(
+            http,
+            config,
+            project,
+            thread,
+            store,
+            audit,
+            &modal.user.name,
+            &MessageSelection::default(),
+            crate::progress::Progress::Silent,
+        )
+        .await?
+        {
+            Filed::Issue(result) => result.issue.number,
+            Filed::Elsewhere { .. } => {
+                return Ok("Only threads filed as GitHub issues can be split".to_string())
+            }
+        },
+    };
+
+    let github = crate::github_app::create_project_client(config, project).await?;
+    let issues = github.issues(&project.github_owner, &project.github_repo);
+    let discord_url = format!(
+        "https://discord.com/channels/{}/{}",
+        thread.guild_id, thread.id
+    );
+    let labels = project.labels_for_title(&thread.name);
+
+    let mut children = Vec::new();
+    let mut failure = None;
+    for section in sections {
+        let filed: Result<u64> = async {
+            let details =
+                crate::redact::for_github(http, store, project, thread.id.get(), &section.body)
+                    .await;
+            crate::redact::report_title(http, store, project, thread.id.get(), &section.title)
+                .await;
+            let body = format!(
+                "{details}\n\n---\nSplit from #{parent} by {} on [Discord]({discord_url}).",
+                modal.user.name
+            );
+            let child = issues
+                .create(
+                    project
+                        .github_title(&section.title, crate::constants::GITHUB_ISSUE_TITLE_LIMIT),
+                )
+                .body(body)
+                .labels(labels.clone())
+                .send()
+                .await?;
+
+            // Native sub-issues where the repository supports them; the task
+            // list below covers the rest
+            let route = format!(
+                "/repos/{}/{}/issues/{parent}/sub_issues",
+                project.github_owner, project.github_repo
+            );
+            let link = serde_json::json!({ "sub_issue_id": child.id.0 });
+            if let Err(e) = github
+                .post::<_, serde_json::Value>(route, Some(&link))
+                .await
+            {
+                warn!(
+                    "Could not add #{} as a sub-issue of #{}: {}",
+                    child.number, parent, e
+                );
+            }
+
+            store.record_split_issue(
+                &project_key(project),
+                child.number,
+                thread.id.get(),
+                parent,
+            )?;
+            audit
+                .record(
+                    AuditEntry::new(project, AuditAction::IssueSplit)
+                        .thread(thread.id.get())
+                        .issue(child.number)
+                        .actor(&modal.user.name)
+                        .details(format!("split from #{parent}")),
+                )
+                .await;
+            Ok(child.number)
+        }
+        .await;
+        match filed {
+            Ok(number) => children.push(number),
+            Err(e) => {
+                failure = Some(e);
+                break;
+            }
+        }
+    }
+    if children.is_empty() {
+        if let Some(e) = failure.take() {
+            return Err(e);
+        }
+    }
+
+    // Anything after the transcript block survives the syncer's body updates
+    let current = issues.get(parent).await?;
+    let body = format!(
+        "{}\n\n{}",
+        current.body.as_deref().unwrap_or_default().trim_end(),
+        task_list(&children)
+    );
+    issues.update(parent).body(&body).send().await?;
+
+    let listed = children
+        .iter()
+        .zip(sections)
+        .map(|(number, section)| {
+            format!(
+                "- [#{number}]({}/{}/{}/issues/{number}) {}",
+                project.github_web_url(),
+                project.github_owner,
+                project.github_repo,
+                section.title
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if !project.staging() {
+        thread
+            .id
+            .send_message(
+                http,
+                CreateMessage::new()
+                    .content(format!(
+                        "🪓 <@{}> split this thread into separate issues, tracked in #{parent}:\n{listed}",
+                        modal.user.id
+                    ))
+                    .allowed_mentions(CreateAllowedMentions::new()),
+            )
+            .await?;
+    }
+    info!(
+        "Split thread {} into issues {:?} under #{}",
+        thread.id, children, parent
+    );
+
+    // Issues filed before a failure are still listed above
+    if let Some(e) = failure {
+        return Err(e.context(format!(
+            "Created only {} of {} issues under #{parent}",
+            children.len(),
+            sections.len()
+        )));
+    }
+    Ok(format!(
+        "✅ Created {} issues under #{parent}",
+        children.len()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_section() {
+        assert_eq!(
+            parse_section("## Crash on save\nSteps:\n1. Save\n"),
+            Some(Section {
+                title: "Crash on save".to_string(),
+                body: "Steps:\n1. Save".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_section("Typo in settings"),
+            Some(Section {
+                title: "Typo in settings".to_string(),
+                body: String::new(),
+            })
+        );
+        assert_eq!(parse_section("  \n "), None);
+    }
+
+    #[test]
+    fn test_task_list() {
+        assert_eq!(task_list(&[4, 5]), "### Split into\n\n- [ ] #4\n- [ ] #5");
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::audit::AuditEntry;
+use crate::config::Config;
+use crate::store::{Store, TableRows, ThreadIssue};
+
+/// Bump when the dump layout changes incompatibly
+pub const STATE_FORMAT_VERSION: u32 = 2;
+
+/// Portable copy of everything the bot persists
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateDump {
+    pub version: u32,
+    pub exported_at: String,
+    pub thread_issues: Vec<ThreadIssue>,
+    pub processed_comments: Vec<u64>,
+    /// (thread ID, last mirrored message ID)
+    #[serde(default)]
+    pub mirror_cursors: Vec<(u64, u64)>,
+    pub audit_log: Vec<AuditEntry>,
+    /// Every other table, by name; version 1 dumps had none
+    #[serde(default)]
+    pub tables: BTreeMap<String, TableRows>,
+}
+
+/// CLI entry point: write the store's contents to a JSON file
+pub fn export_state(out: &str) -> Result<()> {
+    let config = Config::load()?;
+    let store = Store::open(&config)?;
+
+    let dump = StateDump {
+        version: STATE_FORMAT_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        thread_issues: store.thread_issues()?,
+        processed_comments: store.processed_comments()?,
+        mirror_cursors: store.mirror_cursors()?,
+        audit_log: store.all_audit_entries()?,
+        tables: store.dump_tables()?,
+    };
+
+    fs::write(out, serde_json::to_string_pretty(&dump)?)
+        .with_context(|| format!("Failed to write {out}"))?;
+
+    println!("Exported state to {out}:");
+    println!("  - Thread/issue mappings: {}", dump.thread_issues.len());
+    println!("  - Processed comments: {}", dump.processed_comments.len());
+    println!("  - Mirror cursors: {}", dump.mirror_cursors.len());
+    println!("  - Audit entries: {}", dump.audit_log.len());
+    println!(
+        "  - Other rows: {} in {} tables",
+        dump.tables.values().map(|t| t.rows.len()).sum::<usize>(),
+        dump.tables.len()
+    );
+    Ok(())
+}
+
+/// CLI entry point: merge a JSON dump into the configured database
+pub fn import_state(input: &str) -> Result<()> {
+    let contents = fs::read_to_string(input).with_context(|| format!("Failed to read {input}"))?;
+    let dump: StateDump =
+        serde_json::from_str(&contents).with_context(|| format!("Invalid state file {input}"))?;
+
+    // Version 1 dumps only lack the other tables
+    if !(1..=STATE_FORMAT_VERSION).contains(&dump.version) {
+        anyhow::bail!(
+            "Unsupported state format version {} (expected {})",
+            dump.version,
+            STATE_FORMAT_VERSION
+        );
+    }
+
+    let config = Config::load()?;
+    let store = Store::open(&config)?;
+    let imported = store.import(&dump)?;
+
+    println!(
+        "Imported state from {input} (exported {}) into {}:",
+        dump.exported_at,
+        config.database_name()
+    );
+    println!("  - Thread/issue mappings: {}", imported.thread_issues);
+    println!("  - Processed comments: {}", imported.processed_comments);
+    println!("  - Mirror cursors: {}", imported.mirror_cursors);
+    println!(
+        "  - Audit entries: {} ({} already present)",
+        imported.audit_log,
+        dump.audit_log.len() - imported.audit_log
+    );
+    println!("  - Other rows: {}", imported.other_rows);
+    Ok(())
+}
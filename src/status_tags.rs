@@ -0,0 +1,245 @@
+use anyhow::Result;
+use serenity::builder::EditThread;
+use serenity::http::Http;
+use serenity::model::id::{ChannelId, ForumTagId};
+use tracing::info;
+
+use crate::config::{Project, StatusTagsConfig};
+use crate::summary::IssueSummary;
+
+/// Discord allows at most this many tags on a post
+const MAX_APPLIED_TAGS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Triaged,
+    InProgress,
+    FixedPendingRelease,
+}
+
+impl Status {
+    pub fn tag_name(self, config: &StatusTagsConfig) -> &str {
+        match self {
+            Self::Triaged => config.triaged(),
+            Self::InProgress => config.in_progress(),
+            Self::FixedPendingRelease => config.fixed_pending_release(),
+        }
+    }
+}
+
+/// Where the issue stands, from its state, assignees, labels and milestone;
+/// None for untouched open issues and for fixes that have shipped
+pub fn status(
+    issue: &IssueSummary,
+    config: &StatusTagsConfig,
+    prefix_labels: &[String],
+) -> Option<Status> {
+    let milestone_closed = issue.milestone_closed == Some(true);
+
+    if !issue.open {
+        return (issue.completed && !milestone_closed).then_some(Status::FixedPendingRelease);
+    }
+    if !issue.assignees.is_empty() {
+        return Some(Status::InProgress);
+    }
+    let triaged = issue.milestone_closed.is_some()
+        || issue
+            .labels
+            .iter()
+            .any(|label| match &config.triaged_labels {
+                Some(labels) => labels.iter().any(|l| l.eq_ignore_ascii_case(label)),
+                None => !prefix_labels.iter().any(|l| l.eq_ignore_ascii_case(label)),
+            });
+    triaged.then_some(Status::Triaged)
+}
+
+/// The thread's tags with `wanted` as its only status tag, or None when they
+/// already are. Other tags are kept; the oldest give way if the post is full.
+pub fn next_tags(
+    applied: &[ForumTagId],
+    status_tags: &[ForumTagId],
+    wanted: Option<ForumTagId>,
+) -> Option<Vec<ForumTagId>> {
+    let current: Vec<&ForumTagId> = applied
+        .iter()
+        .filter(|id| status_tags.contains(id))
+        .collect();
+    if current == wanted.iter().collect::<Vec<_>>() {
+        return None;
+    }
+
+    let mut tags: Vec<ForumTagId> = applied
+        .iter()
+        .filter(|id| !status_tags.contains(id))
+        .copied()
+        .collect();
+    if let Some(wanted) = wanted {
+        if tags.len() >= MAX_APPLIED_TAGS {
+            tags.drain(..=tags.len() - MAX_APPLIED_TAGS);
+        }
+        tags.push(wanted);
+    }
+    Some(tags)
+}
+
+/// Apply the status tag for the linked issue to its thread. Tags missing from
+/// the forum are skipped; `setup-forum` creates them.
+pub async fn update_tags(
+    discord: &Http,
+    project: &Project,
+    config: &StatusTagsConfig,
+    thread_id: u64,
+    issue: &IssueSummary,
+) -> Result<()> {
+    let forum_id = ChannelId::new(project.discord_forum_id.parse()?);
+    let forum = crate::discord_cache::channel(discord, forum_id)
+        .await?
+        .guild()
+        .ok_or_else(|| anyhow::anyhow!("Forum {forum_id} is not a guild channel"))?;
+    let thread = crate::discord_cache::channel(discord, ChannelId::new(thread_id))
+        .await?
+        .guild()
+        .ok_or_else(|| anyhow::anyhow!("Thread {thread_id} is not a guild channel"))?;
+    // Text channel threads have no tags
+    if thread.parent_id != Some(forum_id) {
+        return Ok(());
+    }
+
+    let tag_id = |name: &str| {
+        forum
+            .available_tags
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+            .map(|t| t.id)
+    };
+    let status_tags: Vec<ForumTagId> = config.tag_names().into_iter().filter_map(tag_id).collect();
+    let prefix_labels: Vec<String> = project.prefixes().into_iter().map(|p| p.label).collect();
+    let status = status(issue, config, &prefix_labels);
+    let wanted = status.and_then(|s| tag_id(s.tag_name(config)));
+
+    let Some(tags) = next_tags(&thread.applied_tags, &status_tags, wanted) else {
+        return Ok(());
+    };
+    ChannelId::new(thread_id)
+        .edit_thread(discord, EditThread::new().applied_tags(tags))
+        .await?;
+    crate::discord_cache::invalidate_channel(ChannelId::new(thread_id));
+    info!(
+        "Set status of thread {} to {:?} for issue #{}",
+        thread_id, status, issue.number
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use octocrab::models::issues::Issue;
+
+    fn issue(extra: serde_json::Value) -> IssueSummary {
+        let mut value = serde_json::json!({
+            "id": 1,
+            "node_id": "I_1",
+            "url": "https://api.github.com/repos/o/r/issues/1",
+            "repository_url": "https://api.github.com/repos/o/r",
+            "labels_url": "https://api.github.com/repos/o/r/issues/1/labels{/name}",
+            "comments_url": "https://api.github.com/repos/o/r/issues/1/comments",
+            "events_url": "https://api.github.com/repos/o/r/issues/1/events",
+            "html_url": "https://github.com/o/r/issues/1",
+            "number": 1,
+            "state": "open",
+            "title": "Crash",
+            "user": {
+                "login": "u", "id": 1, "node_id": "U_1",
+                "avatar_url": "https://example.com/a", "gravatar_id": "",
+                "url": "https://api.github.com/users/u", "html_url": "https://github.com/u",
+                "followers_url": "https://api.github.com/users/u/followers",
+                "following_url": "https://api.github.com/users/u/following{/other_user}",
+                "gists_url": "https://api.github.com/users/u/gists{/gist_id}",
+                "starred_url": "https://api.github.com/users/u/starred{/owner}{/repo}",
+                "subscriptions_url": "https://api.github.com/users/u/subscriptions",
+                "organizations_url": "https://api.github.com/users/u/orgs",
+                "repos_url": "https://api.github.com/users/u/repos",
+                "events_url": "https://api.github.com/users/u/events{/privacy}",
+                "received_events_url": "https://api.github.com/users/u/received_events",
+                "type": "User", "site_admin": false
+            },
+            "labels": [],
+            "assignees": [],
+            "author_association": "NONE",
+            "locked": false,
+            "comments": 0,
+            "created_at": "2025-01-01T00:00:00Z",
+            "updated_at": "2025-01-01T00:00:00Z"
+        });
+        value
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        IssueSummary::from_issue(&serde_json::from_value::<Issue>(value).unwrap())
+    }
+
+    fn label(name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": 1, "node_id": "L_1", "url": "https://api.github.com/repos/o/r/labels/x",
+            "name": name, "color": "ffffff", "default": false
+        })
+    }
+
+    #[test]
+    fn test_status() {
+        let config = StatusTagsConfig::default();
+        let prefixes = vec!["bug".to_string()];
+
+        assert_eq!(
+            status(&issue(serde_json::json!({})), &config, &prefixes),
+            None
+        );
+        let bug_only = issue(serde_json::json!({ "labels": [label("bug")] }));
+        assert_eq!(status(&bug_only, &config, &prefixes), None);
+        let labeled = issue(serde_json::json!({ "labels": [label("bug"), label("ui")] }));
+        assert_eq!(status(&labeled, &config, &prefixes), Some(Status::Triaged));
+
+        let closed = issue(serde_json::json!({ "state": "closed", "state_reason": "completed" }));
+        assert_eq!(
+            status(&closed, &config, &prefixes),
+            Some(Status::FixedPendingRelease)
+        );
+        let not_planned =
+            issue(serde_json::json!({ "state": "closed", "state_reason": "not_planned" }));
+        assert_eq!(status(&not_planned, &config, &prefixes), None);
+
+        let only_priority = StatusTagsConfig {
+            triaged_labels: Some(vec!["priority: high".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(status(&labeled, &only_priority, &prefixes), None);
+    }
+
+    #[test]
+    fn test_next_tags() {
+        let tag = ForumTagId::new;
+        let status_tags = [tag(10), tag(11)];
+
+        assert_eq!(
+            next_tags(&[tag(1), tag(10)], &status_tags, Some(tag(11))),
+            Some(vec![tag(1), tag(11)])
+        );
+        assert_eq!(
+            next_tags(&[tag(1), tag(11)], &status_tags, Some(tag(11))),
+            None
+        );
+        assert_eq!(
+            next_tags(&[tag(1), tag(10)], &status_tags, None),
+            Some(vec![tag(1)])
+        );
+        assert_eq!(
+            next_tags(
+                &[tag(1), tag(2), tag(3), tag(4), tag(5)],
+                &status_tags,
+                Some(tag(10))
+            ),
+            Some(vec![tag(2), tag(3), tag(4), tag(5), tag(10)])
+        );
+    }
+}
@@ -0,0 +1,3506 @@
+use anyhow::{Context, Result};
+use rusqlite::types::{ToSqlOutput, ValueRef};
+use rusqlite::Connection;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::audit::{AuditEntry, AuditQuery};
+use crate::config::Config;
+use crate::state::StateDump;
+
+/// Persistent bot state, in a local SQLite file or, for deployments where
+/// several processes share it, a Postgres database
+pub struct Store {
+    backend: Backend,
+}
+
+enum Backend {
+    Sqlite(Mutex<Connection>),
+    Postgres(Box<Postgres>),
+}
+
+/// The connection settings are kept to reconnect after the server drops the
+/// connection. The client is None while the store is dropped or after a
+/// reconnect failed; the next statement tries again.
+struct Postgres {
+    config: postgres::Config,
+    client: Mutex<Option<postgres::Client>>,
+}
+
+/// A lock or archive a moderator made by hand, which the syncer leaves alone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModeratorAction {
+    /// "locked" or "archived"
+    pub action: String,
+    pub moderator_id: u64,
+    pub moderator: String,
+    pub acted_at: i64,
+}
+
+/// Known link between a Discord thread and its GitHub issue
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ThreadIssue {
+    pub thread_id: u64,
+    pub project: String,
+    pub issue_number: u64,
+    pub updated_at: i64,
+}
+
+/// Outcome of a project's most recent sync
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct SyncResult {
+    pub project: String,
+    pub finished_at: i64,
+    /// None when the sync succeeded
+    pub error: Option<String>,
+}
+
+/// A persistent mismatch the syncer keeps running into
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct DriftItem {
+    pub project: String,
+    pub item: String,
+    pub detail: String,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    pub escalated: bool,
+}
+
+/// Response time clock of a linked issue with an SLA target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlaTimer {
+    pub thread_id: u64,
+    pub started_at: i64,
+    pub due_at: i64,
+    /// First maintainer comment, once there is one
+    pub responded_at: Option<i64>,
+    /// When the thread was told the target was missed
+    pub breached_at: Option<i64>,
+}
+
+/// A Telegram forum topic the bot saw created
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TelegramTopic {
+    pub topic_id: i64,
+    pub name: String,
+    /// Display name of the member who opened it
+    pub creator: String,
+    /// Set once the topic is filed
+    pub issue_number: Option<u64>,
+}
+
+/// A gateway event waiting for a worker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedJob {
+    pub id: i64,
+    pub kind: String,
+    /// The job as JSON
+    pub payload: String,
+    /// Failed runs so far
+    pub attempts: u32,
+    pub run_after: i64,
+    /// Until when the process running it holds the job
+    pub lease_until: i64,
+}
+
+/// A reward hook event not delivered yet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewardDelivery {
+    pub id: i64,
+    pub issue_number: u64,
+    /// The event as JSON
+    pub payload: String,
+    /// Failed deliveries so far
+    pub attempts: u32,
+}
+
+/// A thread that became an issue, credited to its reporter on the leaderboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contribution {
+    pub thread_id: u64,
+    pub reporter_id: u64,
+    pub issue_number: u64,
+    pub filed_at: i64,
+    pub fixed_at: Option<i64>,
+    /// Reactions on the thread's opening post
+    pub reactions: u64,
+}
+
+/// Open threads reported to moderators as looking like one problem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateCluster {
+    pub id: i64,
+    pub project: String,
+    /// Sorted thread IDs
+    pub threads: Vec<u64>,
+    /// `reported`, `merged` or `dismissed`
+    pub status: String,
+}
+
+/// SLA outcomes of the timers started in a period
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SlaStats {
+    pub met: u64,
+    pub missed: u64,
+    /// No response yet and still within the target
+    pub pending: u64,
+    /// Mean time to the first response, in seconds
+    pub average_response: Option<i64>,
+}
+
+/// Rows added by an import
+#[derive(Debug, Default)]
+pub struct ImportCounts {
+    pub thread_issues: usize,
+    pub processed_comments: usize,
+    pub mirror_cursors: usize,
+    pub audit_log: usize,
+    /// Rows of every other table
+    pub other_rows: usize,
+}
+
+/// The rows of a table without a typed place in the state dump, in the
+/// order of `columns`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TableRows {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+/// Tables the state dump has typed fields for
+const TYPED_TABLES: &[&str] = &[
+    "thread_issues",
+    "processed_comments",
+    "mirror_cursors",
+    "audit_log",
+];
+
+/// Tables that only matter to the running process: thread locks expire
+/// within minutes and would block the target if copied
+const EPHEMERAL_TABLES: &[&str] = &["thread_locks"];
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS thread_locks (
+    thread_id INTEGER PRIMARY KEY,
+    expires_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS thread_issues (
+    thread_id INTEGER PRIMARY KEY,
+    project TEXT NOT NULL,
+    issue_number INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS mirror_cursors (
+    thread_id INTEGER PRIMARY KEY,
+    last_message_id INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS thread_summaries (
+    thread_id INTEGER PRIMARY KEY,
+    message_id INTEGER NOT NULL,
+    content TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS stats_comments (
+    thread_id INTEGER PRIMARY KEY,
+    comment_id INTEGER NOT NULL,
+    content TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS stats_cursors (
+    thread_id INTEGER PRIMARY KEY,
+    last_message_id INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS thread_translations (
+    thread_id INTEGER PRIMARY KEY,
+    source_hash TEXT NOT NULL,
+    section TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS issue_transcripts (
+    thread_id INTEGER PRIMARY KEY,
+    selection TEXT NOT NULL,
+    message_ids TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS merged_threads (
+    thread_id INTEGER PRIMARY KEY,
+    into_thread_id INTEGER NOT NULL,
+    merged_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS anonymous_reporters (
+    thread_id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    requested_by TEXT NOT NULL,
+    requested_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS moderator_actions (
+    thread_id INTEGER PRIMARY KEY,
+    action TEXT NOT NULL,
+    moderator_id INTEGER NOT NULL,
+    moderator TEXT NOT NULL,
+    acted_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS muted_users (
+    user_id INTEGER NOT NULL,
+    thread_id INTEGER NOT NULL,
+    muted_at INTEGER NOT NULL,
+    PRIMARY KEY (user_id, thread_id)
+);
+
+CREATE TABLE IF NOT EXISTS rehosted_attachments (
+    attachment_id INTEGER PRIMARY KEY,
+    object_key TEXT NOT NULL,
+    url TEXT NOT NULL,
+    expires_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS sla_timers (
+    project TEXT NOT NULL,
+    issue_number INTEGER NOT NULL,
+    thread_id INTEGER NOT NULL,
+    started_at INTEGER NOT NULL,
+    due_at INTEGER NOT NULL,
+    responded_at INTEGER,
+    breached_at INTEGER,
+    PRIMARY KEY (project, issue_number)
+);
+
+CREATE TABLE IF NOT EXISTS matrix_threads (
+    room_id TEXT NOT NULL,
+    root_event_id TEXT NOT NULL,
+    project TEXT NOT NULL,
+    issue_number INTEGER NOT NULL,
+    closed_at INTEGER,
+    PRIMARY KEY (room_id, root_event_id)
+);
+
+CREATE TABLE IF NOT EXISTS telegram_topics (
+    chat_id INTEGER NOT NULL,
+    topic_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    creator TEXT NOT NULL,
+    project TEXT,
+    issue_number INTEGER,
+    closed_at INTEGER,
+    PRIMARY KEY (chat_id, topic_id)
+);
+
+CREATE TABLE IF NOT EXISTS telegram_messages (
+    chat_id INTEGER NOT NULL,
+    message_id INTEGER NOT NULL,
+    topic_id INTEGER NOT NULL,
+    author TEXT NOT NULL,
+    text TEXT NOT NULL,
+    PRIMARY KEY (chat_id, message_id)
+);
+
+CREATE TABLE IF NOT EXISTS telegram_update_offset (
+    id INTEGER PRIMARY KEY,
+    next_update_id INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    queue TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    run_after INTEGER NOT NULL,
+    lease_until INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS reward_hooks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project TEXT NOT NULL,
+    issue_number INTEGER NOT NULL,
+    payload TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    next_attempt_at INTEGER NOT NULL,
+    delivered_at INTEGER,
+    UNIQUE (project, issue_number)
+);
+
+CREATE TABLE IF NOT EXISTS contributions (
+    thread_id INTEGER PRIMARY KEY,
+    guild_id INTEGER NOT NULL,
+    project TEXT NOT NULL,
+    reporter_id INTEGER NOT NULL,
+    issue_number INTEGER NOT NULL,
+    filed_at INTEGER NOT NULL,
+    fixed_at INTEGER,
+    reactions INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS contributions_guild_time ON contributions (guild_id, filed_at);
+
+CREATE TABLE IF NOT EXISTS onboarded_reporters (
+    project TEXT NOT NULL,
+    user_id INTEGER NOT NULL,
+    onboarded_at INTEGER NOT NULL,
+    PRIMARY KEY (project, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS duplicate_clusters (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project TEXT NOT NULL,
+    threads TEXT NOT NULL,
+    status TEXT NOT NULL,
+    reported_at INTEGER NOT NULL,
+    UNIQUE (project, threads)
+);
+
+CREATE TABLE IF NOT EXISTS split_issues (
+    project TEXT NOT NULL,
+    issue_number INTEGER NOT NULL,
+    thread_id INTEGER NOT NULL,
+    parent_number INTEGER NOT NULL,
+    PRIMARY KEY (project, issue_number)
+);
+
+CREATE TABLE IF NOT EXISTS needs_info_prompts (
+    thread_id INTEGER PRIMARY KEY,
+    posted_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS milestone_announcements (
+    project TEXT NOT NULL,
+    milestone INTEGER NOT NULL,
+    event TEXT NOT NULL,
+    announced_at INTEGER NOT NULL,
+    PRIMARY KEY (project, milestone, event)
+);
+
+CREATE TABLE IF NOT EXISTS release_announcements (
+    project TEXT NOT NULL,
+    release_id INTEGER NOT NULL,
+    announced_at INTEGER NOT NULL,
+    PRIMARY KEY (project, release_id)
+);
+
+CREATE TABLE IF NOT EXISTS redacted_values (
+    project TEXT NOT NULL,
+    thread_id INTEGER NOT NULL,
+    value TEXT NOT NULL,
+    reported_at INTEGER NOT NULL,
+    PRIMARY KEY (project, thread_id, value)
+);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    delivery_id TEXT PRIMARY KEY,
+    received_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS triage_queue (
+    thread_id INTEGER PRIMARY KEY,
+    project TEXT NOT NULL,
+    status TEXT NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS issue_assignees (
+    thread_id INTEGER PRIMARY KEY,
+    assignees TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS drift_items (
+    project TEXT NOT NULL,
+    item TEXT NOT NULL,
+    detail TEXT NOT NULL,
+    first_seen INTEGER NOT NULL,
+    last_seen INTEGER NOT NULL,
+    escalated INTEGER NOT NULL,
+    PRIMARY KEY (project, item)
+);
+
+CREATE TABLE IF NOT EXISTS sync_results (
+    project TEXT PRIMARY KEY,
+    finished_at INTEGER NOT NULL,
+    error TEXT
+);
+
+CREATE TABLE IF NOT EXISTS processed_comments (
+    comment_id INTEGER PRIMARY KEY,
+    processed_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS message_issues (
+    message_id INTEGER PRIMARY KEY,
+    project TEXT NOT NULL,
+    issue_number INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS comment_votes (
+    comment_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    vote INTEGER NOT NULL,
+    PRIMARY KEY (comment_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS mirrored_messages (
+    message_id INTEGER PRIMARY KEY,
+    author TEXT NOT NULL,
+    issue_number INTEGER NOT NULL,
+    comment_id INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    project TEXT NOT NULL,
+    action TEXT NOT NULL,
+    thread_id INTEGER,
+    issue_number INTEGER,
+    actor TEXT NOT NULL,
+    details TEXT
+);
+CREATE INDEX IF NOT EXISTS audit_log_project_time ON audit_log (project, timestamp);
+CREATE INDEX IF NOT EXISTS audit_log_thread ON audit_log (thread_id);
+
+-- The audit log is append-only
+CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log
+BEGIN SELECT RAISE(ABORT, 'audit_log is append-only'); END;
+CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log
+BEGIN SELECT RAISE(ABORT, 'audit_log is append-only'); END;
+";
+
+// Same tables with 64-bit integer columns; Discord IDs don't fit Postgres' INTEGER
+const POSTGRES_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS thread_locks (
+    thread_id BIGINT PRIMARY KEY,
+    expires_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS thread_issues (
+    thread_id BIGINT PRIMARY KEY,
+    project TEXT NOT NULL,
+    issue_number BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS mirror_cursors (
+    thread_id BIGINT PRIMARY KEY,
+    last_message_id BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS thread_summaries (
+    thread_id BIGINT PRIMARY KEY,
+    message_id BIGINT NOT NULL,
+    content TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS stats_comments (
+    thread_id BIGINT PRIMARY KEY,
+    comment_id BIGINT NOT NULL,
+    content TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS stats_cursors (
+    thread_id BIGINT PRIMARY KEY,
+    last_message_id BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS thread_translations (
+    thread_id BIGINT PRIMARY KEY,
+    source_hash TEXT NOT NULL,
+    section TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS issue_transcripts (
+    thread_id BIGINT PRIMARY KEY,
+    selection TEXT NOT NULL,
+    message_ids TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS merged_threads (
+    thread_id BIGINT PRIMARY KEY,
+    into_thread_id BIGINT NOT NULL,
+    merged_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS anonymous_reporters (
+    thread_id BIGINT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    requested_by TEXT NOT NULL,
+    requested_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS moderator_actions (
+    thread_id BIGINT PRIMARY KEY,
+    action TEXT NOT NULL,
+    moderator_id BIGINT NOT NULL,
+    moderator TEXT NOT NULL,
+    acted_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS muted_users (
+    user_id BIGINT NOT NULL,
+    thread_id BIGINT NOT NULL,
+    muted_at BIGINT NOT NULL,
+    PRIMARY KEY (user_id, thread_id)
+);
+
+CREATE TABLE IF NOT EXISTS rehosted_attachments (
+    attachment_id BIGINT PRIMARY KEY,
+    object_key TEXT NOT NULL,
+    url TEXT NOT NULL,
+    expires_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS sla_timers (
+    project TEXT NOT NULL,
+    issue_number BIGINT NOT NULL,
+    thread_id BIGINT NOT NULL,
+    started_at BIGINT NOT NULL,
+    due_at BIGINT NOT NULL,
+    responded_at BIGINT,
+    breached_at BIGINT,
+    PRIMARY KEY (project, issue_number)
+);
+
+CREATE TABLE IF NOT EXISTS matrix_threads (
+    room_id TEXT NOT NULL,
+    root_event_id TEXT NOT NULL,
+    project TEXT NOT NULL,
+    issue_number BIGINT NOT NULL,
+    closed_at BIGINT,
+    PRIMARY KEY (room_id, root_event_id)
+);
+
+CREATE TABLE IF NOT EXISTS telegram_topics (
+    chat_id BIGINT NOT NULL,
+    topic_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    creator TEXT NOT NULL,
+    project TEXT,
+    issue_number BIGINT,
+    closed_at BIGINT,
+    PRIMARY KEY (chat_id, topic_id)
+);
+
+CREATE TABLE IF NOT EXISTS telegram_messages (
+    chat_id BIGINT NOT NULL,
+    message_id BIGINT NOT NULL,
+    topic_id BIGINT NOT NULL,
+    author TEXT NOT NULL,
+    text TEXT NOT NULL,
+    PRIMARY KEY (chat_id, message_id)
+);
+
+CREATE TABLE IF NOT EXISTS telegram_update_offset (
+    id BIGINT PRIMARY KEY,
+    next_update_id BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS jobs (
+    id BIGSERIAL PRIMARY KEY,
+    queue TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    attempts BIGINT NOT NULL,
+    run_after BIGINT NOT NULL,
+    lease_until BIGINT NOT NULL DEFAULT 0,
+    created_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS reward_hooks (
+    id BIGSERIAL PRIMARY KEY,
+    project TEXT NOT NULL,
+    issue_number BIGINT NOT NULL,
+    payload TEXT NOT NULL,
+    attempts BIGINT NOT NULL,
+    next_attempt_at BIGINT NOT NULL,
+    delivered_at BIGINT,
+    UNIQUE (project, issue_number)
+);
+
+CREATE TABLE IF NOT EXISTS contributions (
+    thread_id BIGINT PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    project TEXT NOT NULL,
+    reporter_id BIGINT NOT NULL,
+    issue_number BIGINT NOT NULL,
+    filed_at BIGINT NOT NULL,
+    fixed_at BIGINT,
+    reactions BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS contributions_guild_time ON contributions (guild_id, filed_at);
+
+CREATE TABLE IF NOT EXISTS onboarded_reporters (
+    project TEXT NOT NULL,
+    user_id BIGINT NOT NULL,
+    onboarded_at BIGINT NOT NULL,
+    PRIMARY KEY (project, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS duplicate_clusters (
+    id BIGSERIAL PRIMARY KEY,
+    project TEXT NOT NULL,
+    threads TEXT NOT NULL,
+    status TEXT NOT NULL,
+    reported_at BIGINT NOT NULL,
+    UNIQUE (project, threads)
+);
+
+CREATE TABLE IF NOT EXISTS split_issues (
+    project TEXT NOT NULL,
+    issue_number BIGINT NOT NULL,
+    thread_id BIGINT NOT NULL,
+    parent_number BIGINT NOT NULL,
+    PRIMARY KEY (project, issue_number)
+);
+
+CREATE TABLE IF NOT EXISTS needs_info_prompts (
+    thread_id BIGINT PRIMARY KEY,
+    posted_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS milestone_announcements (
+    project TEXT NOT NULL,
+    milestone BIGINT NOT NULL,
+    event TEXT NOT NULL,
+    announced_at BIGINT NOT NULL,
+    PRIMARY KEY (project, milestone, event)
+);
+
+CREATE TABLE IF NOT EXISTS release_announcements (
+    project TEXT NOT NULL,
+    release_id BIGINT NOT NULL,
+    announced_at BIGINT NOT NULL,
+    PRIMARY KEY (project, release_id)
+);
+
+CREATE TABLE IF NOT EXISTS redacted_values (
+    project TEXT NOT NULL,
+    thread_id BIGINT NOT NULL,
+    value TEXT NOT NULL,
+    reported_at BIGINT NOT NULL,
+    PRIMARY KEY (project, thread_id, value)
+);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    delivery_id TEXT PRIMARY KEY,
+    received_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS triage_queue (
+    thread_id BIGINT PRIMARY KEY,
+    project TEXT NOT NULL,
+    status TEXT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS issue_assignees (
+    thread_id BIGINT PRIMARY KEY,
+    assignees TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS drift_items (
+    project TEXT NOT NULL,
+    item TEXT NOT NULL,
+    detail TEXT NOT NULL,
+    first_seen BIGINT NOT NULL,
+    last_seen BIGINT NOT NULL,
+    escalated BIGINT NOT NULL,
+    PRIMARY KEY (project, item)
+);
+
+CREATE TABLE IF NOT EXISTS sync_results (
+    project TEXT PRIMARY KEY,
+    finished_at BIGINT NOT NULL,
+    error TEXT
+);
+
+CREATE TABLE IF NOT EXISTS processed_comments (
+    comment_id BIGINT PRIMARY KEY,
+    processed_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS message_issues (
+    message_id BIGINT PRIMARY KEY,
+    project TEXT NOT NULL,
+    issue_number BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS comment_votes (
+    comment_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    vote BIGINT NOT NULL,
+    PRIMARY KEY (comment_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS mirrored_messages (
+    message_id BIGINT PRIMARY KEY,
+    author TEXT NOT NULL,
+    issue_number BIGINT NOT NULL,
+    comment_id BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    timestamp BIGINT NOT NULL,
+    project TEXT NOT NULL,
+    action TEXT NOT NULL,
+    thread_id BIGINT,
+    issue_number BIGINT,
+    actor TEXT NOT NULL,
+    details TEXT
+);
+CREATE INDEX IF NOT EXISTS audit_log_project_time ON audit_log (project, timestamp);
+CREATE INDEX IF NOT EXISTS audit_log_thread ON audit_log (thread_id);
+
+-- The audit log is append-only
+CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS trigger AS $$
+BEGIN RAISE EXCEPTION 'audit_log is append-only'; END;
+$$ LANGUAGE plpgsql;
+DO $$ BEGIN
+    CREATE TRIGGER audit_log_append_only BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION audit_log_append_only();
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+";
+
+impl Store {
+    /// Open the configured database: Postgres when `database_url` is set,
+    /// the SQLite file at `database_path` otherwise
+    pub fn open(config: &Config) -> Result<Self> {
+        match config.database_url() {
+            Some(url) => Self::open_postgres(&url),
+            None => Self::open_sqlite(config.database_path()),
+        }
+    }
+
+    pub fn open_sqlite(path: &str) -> Result<Self> {
+        let conn =
+            Connection::open(path).with_context(|| format!("Failed to open database {path}"))?;
+        Self::from_connection(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)
+            .context("Failed to initialize database schema")?;
+        Ok(Self {
+            backend: Backend::Sqlite(Mutex::new(conn)),
+        })
+    }
+
+    pub fn open_postgres(url: &str) -> Result<Self> {
+        let config: postgres::Config = url.parse().context("Invalid database_url")?;
+        let mut client = connect_postgres(&config)?;
+        blocking(|| client.batch_execute(POSTGRES_SCHEMA))
+            .context("Failed to initialize database schema")?;
+        Ok(Self {
+            backend: Backend::Postgres(Box::new(Postgres {
+                config,
+                client: Mutex::new(Some(client)),
+            })),
+        })
+    }
+
+    fn conn(&self) -> Conn<'_> {
+        match &self.backend {
+            Backend::Sqlite(conn) => Conn::Sqlite(conn.lock().unwrap_or_else(|e| e.into_inner())),
+            Backend::Postgres(postgres) => Conn::Postgres(
+                &postgres.config,
+                postgres.client.lock().unwrap_or_else(|e| e.into_inner()),
+            ),
+        }
+    }
+
+    /// Take a lease on a thread so only one process works on it at a time.
+    ///
+    /// Returns false if another unexpired lease is held.
+    pub fn try_lock_thread(&self, thread_id: u64, ttl_secs: i64) -> Result<bool> {
+        let now = chrono::Utc::now().timestamp();
+        let mut conn = self.conn();
+
+        conn.execute(
+            "DELETE FROM thread_locks WHERE thread_id = ?1 AND expires_at <= ?2",
+            values![thread_id, now],
+        )?;
+        let inserted = conn.execute(
+            "INSERT INTO thread_locks (thread_id, expires_at) VALUES (?1, ?2)
+             ON CONFLICT DO NOTHING",
+            values![thread_id, now + ttl_secs],
+        )?;
+
+        Ok(inserted == 1)
+    }
+
+    pub fn unlock_thread(&self, thread_id: u64) -> Result<()> {
+        self.conn().execute(
+            "DELETE FROM thread_locks WHERE thread_id = ?1",
+            values![thread_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn upsert_thread_issue(
+        &self,
+        thread_id: u64,
+        project: &str,
+        issue_number: u64,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO thread_issues (thread_id, project, issue_number, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (thread_id) DO UPDATE SET
+                project = excluded.project,
+                issue_number = excluded.issue_number,
+                updated_at = excluded.updated_at",
+            values![
+                thread_id,
+                project,
+                issue_number,
+                chrono::Utc::now().timestamp()
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn thread_issue(&self, thread_id: u64) -> Result<Option<ThreadIssue>> {
+        self.conn()
+            .query_one(
+                "SELECT thread_id, project, issue_number, updated_at FROM thread_issues WHERE thread_id = ?1",
+                values![thread_id],
+            )?
+            .map(|row| thread_issue_from_row(&row))
+            .transpose()
+    }
+
+    pub fn thread_for_issue(&self, project: &str, issue_number: u64) -> Result<Option<u64>> {
+        self.conn()
+            .query_one(
+                "SELECT thread_id FROM thread_issues WHERE project = ?1 AND issue_number = ?2",
+                values![project, issue_number],
+            )?
+            .map(|row| row.id(0))
+            .transpose()
+    }
+
+    /// Threads linked to an issue in a project
+    pub fn linked_thread_count(&self, project: &str) -> Result<u64> {
+        let row = self.conn().query_one(
+            "SELECT COUNT(*) FROM thread_issues WHERE project = ?1",
+            values![project],
+        )?;
+        row.map_or(Ok(0), |row| row.id(0))
+    }
+
+    pub fn thread_issues(&self) -> Result<Vec<ThreadIssue>> {
+        self.conn()
+            .query(
+                "SELECT thread_id, project, issue_number, updated_at FROM thread_issues ORDER BY thread_id",
+                values![],
+            )?
+            .iter()
+            .map(thread_issue_from_row)
+            .collect()
+    }
+
+    /// Last Discord message mirrored to GitHub for a thread
+    pub fn mirror_cursor(&self, thread_id: u64) -> Result<Option<u64>> {
+        self.conn()
+            .query_one(
+                "SELECT last_message_id FROM mirror_cursors WHERE thread_id = ?1",
+                values![thread_id],
+            )?
+            .map(|row| row.id(0))
+            .transpose()
+    }
+
+    pub fn set_mirror_cursor(&self, thread_id: u64, message_id: u64) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO mirror_cursors (thread_id, last_message_id) VALUES (?1, ?2)
+             ON CONFLICT (thread_id) DO UPDATE SET last_message_id = excluded.last_message_id",
+            values![thread_id, message_id],
+        )?;
+        Ok(())
+    }
+
+    /// All mirror cursors as (thread ID, last message ID)
+    pub fn mirror_cursors(&self) -> Result<Vec<(u64, u64)>> {
+        self.conn()
+            .query(
+                "SELECT thread_id, last_message_id FROM mirror_cursors ORDER BY thread_id",
+                values![],
+            )?
+            .iter()
+            .map(|row| Ok((row.id(0)?, row.id(1)?)))
+            .collect()
+    }
+
+    /// Record that a thread's replies now belong to another thread's issue
+    pub fn merge_thread(&self, thread_id: u64, into_thread_id: u64) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO merged_threads (thread_id, into_thread_id, merged_at)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT (thread_id) DO UPDATE SET
+                into_thread_id = excluded.into_thread_id,
+                merged_at = excluded.merged_at",
+            values![thread_id, into_thread_id, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Keep a thread's reporter out of its GitHub issue; only maintainers see
+    /// who it was
+    pub fn set_anonymous_reporter(
+        &self,
+        thread_id: u64,
+        user_id: u64,
+        requested_by: &str,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO anonymous_reporters (thread_id, user_id, requested_by, requested_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (thread_id) DO NOTHING",
+            values![
+                thread_id,
+                user_id,
+                requested_by,
+                chrono::Utc::now().timestamp()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// The hidden reporter of a thread, if it was filed anonymously
+    pub fn anonymous_reporter(&self, thread_id: u64) -> Result<Option<u64>> {
+        self.conn()
+            .query_one(
+                "SELECT user_id FROM anonymous_reporters WHERE thread_id = ?1",
+                values![thread_id],
+            )?
+            .map(|row| row.id(0))
+            .transpose()
+    }
+
+    /// Remember that a moderator, not the bot, locked or archived a thread
+    pub fn record_moderator_action(
+        &self,
+        thread_id: u64,
+        action: &str,
+        moderator_id: u64,
+        moderator: &str,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO moderator_actions (thread_id, action, moderator_id, moderator, acted_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (thread_id) DO UPDATE SET
+                action = excluded.action,
+                moderator_id = excluded.moderator_id,
+                moderator = excluded.moderator,
+                acted_at = excluded.acted_at",
+            values![
+                thread_id,
+                action,
+                moderator_id,
+                moderator,
+                chrono::Utc::now().timestamp()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// The standing moderator lock or archive of a thread, if any
+    pub fn moderator_action(&self, thread_id: u64) -> Result<Option<ModeratorAction>> {
+        self.conn()
+            .query_one(
+                "SELECT action, moderator_id, moderator, acted_at
+                 FROM moderator_actions WHERE thread_id = ?1",
+                values![thread_id],
+            )?
+            .map(|row| {
+                Ok(ModeratorAction {
+                    action: row.text(0)?,
+                    moderator_id: row.id(1)?,
+                    moderator: row.text(2)?,
+                    acted_at: row.int(3)?,
+                })
+            })
+            .transpose()
+    }
+
+    /// Forget a moderator action once the thread is open again
+    pub fn clear_moderator_action(&self, thread_id: u64) -> Result<bool> {
+        let removed = self.conn().execute(
+            "DELETE FROM moderator_actions WHERE thread_id = ?1",
+            values![thread_id],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// Stop pinging a user in one thread, or everywhere when `thread_id` is
+    /// None
+    pub fn mute_user(&self, user_id: u64, thread_id: Option<u64>) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO muted_users (user_id, thread_id, muted_at)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT (user_id, thread_id) DO NOTHING",
+            values![
+                user_id,
+                thread_id.unwrap_or(0),
+                chrono::Utc::now().timestamp()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Undo `mute_user` for the same scope; false when it wasn't muted
+    pub fn unmute_user(&self, user_id: u64, thread_id: Option<u64>) -> Result<bool> {
+        let removed = self.conn().execute(
+            "DELETE FROM muted_users WHERE user_id = ?1 AND thread_id = ?2",
+            values![user_id, thread_id.unwrap_or(0)],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// Whether the user muted the bot in this thread or everywhere
+    pub fn is_muted(&self, user_id: u64, thread_id: Option<u64>) -> Result<bool> {
+        Ok(self
+            .conn()
+            .query_one(
+                "SELECT 1 FROM muted_users
+                 WHERE user_id = ?1 AND (thread_id = 0 OR thread_id = ?2)",
+                values![user_id, thread_id.unwrap_or(0)],
+            )?
+            .is_some())
+    }
+
+    /// Object store URL of a re-hosted attachment that hasn't expired
+    pub fn rehosted_attachment(&self, attachment_id: u64) -> Result<Option<(String, i64)>> {
+        self.conn()
+            .query_one(
+                "SELECT url, expires_at FROM rehosted_attachments
+                 WHERE attachment_id = ?1 AND expires_at > ?2",
+                values![attachment_id, chrono::Utc::now().timestamp()],
+            )?
+            .map(|row| Ok((row.text(0)?, row.int(1)?)))
+            .transpose()
+    }
+
+    pub fn record_rehosted_attachment(
+        &self,
+        attachment_id: u64,
+        object_key: &str,
+        url: &str,
+        expires_at: i64,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO rehosted_attachments (attachment_id, object_key, url, expires_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (attachment_id) DO UPDATE
+             SET object_key = excluded.object_key, url = excluded.url,
+                 expires_at = excluded.expires_at",
+            values![attachment_id, object_key, url, expires_at],
+        )?;
+        Ok(())
+    }
+
+    /// Re-hosted attachments past their expiry, as (attachment ID, object key)
+    pub fn expired_attachments(&self) -> Result<Vec<(u64, String)>> {
+        self.conn()
+            .query(
+                "SELECT attachment_id, object_key FROM rehosted_attachments
+                 WHERE expires_at <= ?1 ORDER BY attachment_id",
+                values![chrono::Utc::now().timestamp()],
+            )?
+            .iter()
+            .map(|row| Ok((row.id(0)?, row.text(1)?)))
+            .collect()
+    }
+
+    pub fn forget_rehosted_attachment(&self, attachment_id: u64) -> Result<()> {
+        self.conn().execute(
+            "DELETE FROM rehosted_attachments WHERE attachment_id = ?1",
+            values![attachment_id],
+        )?;
+        Ok(())
+    }
+
+    /// Start an issue's SLA clock unless it is already running
+    pub fn start_sla_timer(
+        &self,
+        project: &str,
+        issue_number: u64,
+        thread_id: u64,
+        started_at: i64,
+        due_at: i64,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO sla_timers (project, issue_number, thread_id, started_at, due_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (project, issue_number) DO NOTHING",
+            values![project, issue_number, thread_id, started_at, due_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn sla_timer(&self, project: &str, issue_number: u64) -> Result<Option<SlaTimer>> {
+        self.conn()
+            .query_one(
+                "SELECT thread_id, started_at, due_at, responded_at, breached_at
+                 FROM sla_timers WHERE project = ?1 AND issue_number = ?2",
+                values![project, issue_number],
+            )?
+            .map(|row| {
+                Ok(SlaTimer {
+                    thread_id: row.id(0)?,
+                    started_at: row.int(1)?,
+                    due_at: row.int(2)?,
+                    responded_at: row.opt_int(3),
+                    breached_at: row.opt_int(4),
+                })
+            })
+            .transpose()
+    }
+
+    pub fn record_sla_response(
+        &self,
+        project: &str,
+        issue_number: u64,
+        responded_at: i64,
+    ) -> Result<()> {
+        self.conn().execute(
+            "UPDATE sla_timers SET responded_at = ?3 WHERE project = ?1 AND issue_number = ?2",
+            values![project, issue_number, responded_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_sla_breach(
+        &self,
+        project: &str,
+        issue_number: u64,
+        breached_at: i64,
+    ) -> Result<()> {
+        self.conn().execute(
+            "UPDATE sla_timers SET breached_at = ?3 WHERE project = ?1 AND issue_number = ?2",
+            values![project, issue_number, breached_at],
+        )?;
+        Ok(())
+    }
+
+    /// Outcomes of the project's SLA timers started at or after `since`
+    pub fn sla_stats(&self, project: &str, since: i64) -> Result<SlaStats> {
+        let now = chrono::Utc::now().timestamp();
+        let rows = self.conn().query(
+            "SELECT started_at, due_at, responded_at FROM sla_timers
+             WHERE project = ?1 AND started_at >= ?2",
+            values![project, since],
+        )?;
+
+        let mut stats = SlaStats::default();
+        let mut response_times = Vec::new();
+        for row in rows.iter() {
+            let (started_at, due_at) = (row.int(0)?, row.int(1)?);
+            match row.opt_int(2) {
+                Some(responded_at) => {
+                    response_times.push(responded_at - started_at);
+                    if responded_at <= due_at {
+                        stats.met += 1;
+                    } else {
+                        stats.missed += 1;
+                    }
+                }
+                None if now > due_at => stats.missed += 1,
+                None => stats.pending += 1,
+            }
+        }
+        stats.average_response = (!response_times.is_empty())
+            .then(|| response_times.iter().sum::<i64>() / response_times.len() as i64);
+        Ok(stats)
+    }
+
+    /// The issue a Matrix thread was filed as, with its project key
+    pub fn matrix_thread_issue(
+        &self,
+        room_id: &str,
+        root_event_id: &str,
+    ) -> Result<Option<(String, u64)>> {
+        self.conn()
+            .query_one(
+                "SELECT project, issue_number FROM matrix_threads
+                 WHERE room_id = ?1 AND root_event_id = ?2",
+                values![room_id, root_event_id],
+            )?
+            .map(|row| Ok((row.text(0)?, row.id(1)?)))
+            .transpose()
+    }
+
+    pub fn link_matrix_thread(
+        &self,
+        room_id: &str,
+        root_event_id: &str,
+        project: &str,
+        issue_number: u64,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO matrix_threads (room_id, root_event_id, project, issue_number)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (room_id, root_event_id) DO NOTHING",
+            values![room_id, root_event_id, project, issue_number],
+        )?;
+        Ok(())
+    }
+
+    /// Threads of a room whose issue hasn't been seen closed, as (root event
+    /// ID, project key, issue number)
+    pub fn open_matrix_threads(&self, room_id: &str) -> Result<Vec<(String, String, u64)>> {
+        self.conn()
+            .query(
+                "SELECT root_event_id, project, issue_number FROM matrix_threads
+                 WHERE room_id = ?1 AND closed_at IS NULL ORDER BY issue_number",
+                values![room_id],
+            )?
+            .iter()
+            .map(|row| Ok((row.text(0)?, row.text(1)?, row.id(2)?)))
+            .collect()
+    }
+
+    pub fn close_matrix_thread(
+        &self,
+        room_id: &str,
+        root_event_id: &str,
+        closed_at: i64,
+    ) -> Result<()> {
+        self.conn().execute(
+            "UPDATE matrix_threads SET closed_at = ?3 WHERE room_id = ?1 AND root_event_id = ?2",
+            values![room_id, root_event_id, closed_at],
+        )?;
+        Ok(())
+    }
+
+    /// First Telegram update not yet processed, 0 before the first poll
+    pub fn telegram_update_offset(&self) -> Result<i64> {
+        Ok(self
+            .conn()
+            .query_one(
+                "SELECT next_update_id FROM telegram_update_offset WHERE id = 1",
+                values![],
+            )?
+            .map(|row| row.int(0))
+            .transpose()?
+            .unwrap_or(0))
+    }
+
+    pub fn set_telegram_update_offset(&self, next_update_id: i64) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO telegram_update_offset (id, next_update_id) VALUES (1, ?1)
+             ON CONFLICT (id) DO UPDATE SET next_update_id = excluded.next_update_id",
+            values![next_update_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_telegram_topic(
+        &self,
+        chat_id: i64,
+        topic_id: i64,
+        name: &str,
+        creator: &str,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO telegram_topics (chat_id, topic_id, name, creator)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (chat_id, topic_id) DO NOTHING",
+            values![chat_id, topic_id, name, creator],
+        )?;
+        Ok(())
+    }
+
+    pub fn rename_telegram_topic(&self, chat_id: i64, topic_id: i64, name: &str) -> Result<()> {
+        self.conn().execute(
+            "UPDATE telegram_topics SET name = ?3 WHERE chat_id = ?1 AND topic_id = ?2",
+            values![chat_id, topic_id, name],
+        )?;
+        Ok(())
+    }
+
+    /// A topic the bot saw created, or None for unknown topics
+    pub fn telegram_topic(&self, chat_id: i64, topic_id: i64) -> Result<Option<TelegramTopic>> {
+        self.conn()
+            .query_one(
+                "SELECT topic_id, name, creator, issue_number FROM telegram_topics
+                 WHERE chat_id = ?1 AND topic_id = ?2",
+                values![chat_id, topic_id],
+            )?
+            .map(|row| telegram_topic_from_row(&row))
+            .transpose()
+    }
+
+    /// Topics of a chat not yet filed as an issue
+    pub fn unfiled_telegram_topics(&self, chat_id: i64) -> Result<Vec<TelegramTopic>> {
+        self.conn()
+            .query(
+                "SELECT topic_id, name, creator, issue_number FROM telegram_topics
+                 WHERE chat_id = ?1 AND issue_number IS NULL ORDER BY topic_id",
+                values![chat_id],
+            )?
+            .iter()
+            .map(telegram_topic_from_row)
+            .collect()
+    }
+
+    /// Keep a message of a topic that may still be filed
+    pub fn record_telegram_message(
+        &self,
+        chat_id: i64,
+        message_id: i64,
+        topic_id: i64,
+        author: &str,
+        text: &str,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO telegram_messages (chat_id, message_id, topic_id, author, text)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (chat_id, message_id) DO NOTHING",
+            values![chat_id, message_id, topic_id, author, text],
+        )?;
+        Ok(())
+    }
+
+    /// A topic's kept messages as (author, text), oldest first
+    pub fn telegram_messages(&self, chat_id: i64, topic_id: i64) -> Result<Vec<(String, String)>> {
+        self.conn()
+            .query(
+                "SELECT author, text FROM telegram_messages
+                 WHERE chat_id = ?1 AND topic_id = ?2 ORDER BY message_id",
+                values![chat_id, topic_id],
+            )?
+            .iter()
+            .map(|row| Ok((row.text(0)?, row.text(1)?)))
+            .collect()
+    }
+
+    /// Link a topic to its issue; its kept messages are no longer needed
+    pub fn link_telegram_topic(
+        &self,
+        chat_id: i64,
+        topic_id: i64,
+        project: &str,
+        issue_number: u64,
+    ) -> Result<()> {
+        let mut conn = self.conn();
+        conn.execute(
+            "UPDATE telegram_topics SET project = ?3, issue_number = ?4
+             WHERE chat_id = ?1 AND topic_id = ?2",
+            values![chat_id, topic_id, project, issue_number],
+        )?;
+        conn.execute(
+            "DELETE FROM telegram_messages WHERE chat_id = ?1 AND topic_id = ?2",
+            values![chat_id, topic_id],
+        )?;
+        Ok(())
+    }
+
+    /// Filed topics of a chat whose issue hasn't been seen closed, as (topic
+    /// ID, project key, issue number)
+    pub fn open_telegram_topics(&self, chat_id: i64) -> Result<Vec<(i64, String, u64)>> {
+        self.conn()
+            .query(
+                "SELECT topic_id, project, issue_number FROM telegram_topics
+                 WHERE chat_id = ?1 AND issue_number IS NOT NULL AND closed_at IS NULL
+                 ORDER BY issue_number",
+                values![chat_id],
+            )?
+            .iter()
+            .map(|row| Ok((row.int(0)?, row.text(1)?, row.id(2)?)))
+            .collect()
+    }
+
+    pub fn close_telegram_topic(&self, chat_id: i64, topic_id: i64, closed_at: i64) -> Result<()> {
+        self.conn().execute(
+            "UPDATE telegram_topics SET closed_at = ?3 WHERE chat_id = ?1 AND topic_id = ?2",
+            values![chat_id, topic_id, closed_at],
+        )?;
+        Ok(())
+    }
+
+    /// Persist a job, returning its ID
+    pub fn enqueue_job(&self, queue: &str, kind: &str, payload: &str) -> Result<i64> {
+        let now = chrono::Utc::now().timestamp();
+        self.conn()
+            .query_one(
+                "INSERT INTO jobs (queue, kind, payload, attempts, run_after, created_at)
+                 VALUES (?1, ?2, ?3, 0, ?4, ?4) RETURNING id",
+                values![queue, kind, payload, now],
+            )?
+            .context("Job insert returned no ID")?
+            .int(0)
+    }
+
+    /// A job that hasn't finished, or None once it has
+    pub fn job(&self, id: i64) -> Result<Option<QueuedJob>> {
+        self.conn()
+            .query_one(
+                "SELECT id, kind, payload, attempts, run_after, lease_until FROM jobs WHERE id = ?1",
+                values![id],
+            )?
+            .map(|row| job_from_row(&row))
+            .transpose()
+    }
+
+    /// Unfinished jobs of a queue, oldest first
+    pub fn pending_jobs(&self, queue: &str) -> Result<Vec<QueuedJob>> {
+        self.conn()
+            .query(
+                "SELECT id, kind, payload, attempts, run_after, lease_until FROM jobs
+                 WHERE queue = ?1 ORDER BY id",
+                values![queue],
+            )?
+            .iter()
+            .map(job_from_row)
+            .collect()
+    }
+
+    /// Take a job that is due and not held by another process, holding it
+    /// until `lease_until`; None if it isn't free to run
+    pub fn claim_job(&self, id: i64, now: i64, lease_until: i64) -> Result<Option<QueuedJob>> {
+        self.conn()
+            .query_one(
+                "UPDATE jobs SET lease_until = ?3
+                 WHERE id = ?1 AND run_after <= ?2 AND lease_until <= ?2
+                 RETURNING id, kind, payload, attempts, run_after, lease_until",
+                values![id, now, lease_until],
+            )?
+            .map(|row| job_from_row(&row))
+            .transpose()
+    }
+
+    /// Count a failed run, release the job and hold it back until `run_after`
+    pub fn retry_job(&self, id: i64, run_after: i64) -> Result<()> {
+        self.conn().execute(
+            "UPDATE jobs SET attempts = attempts + 1, run_after = ?2, lease_until = 0
+             WHERE id = ?1",
+            values![id, run_after],
+        )?;
+        Ok(())
+    }
+
+    /// Drop a job that succeeded or ran out of attempts
+    pub fn finish_job(&self, id: i64) -> Result<()> {
+        self.conn()
+            .execute("DELETE FROM jobs WHERE id = ?1", values![id])?;
+        Ok(())
+    }
+
+    /// Record a cluster of possible duplicates; None if the same threads were
+    /// reported before
+    pub fn record_duplicate_cluster(&self, project: &str, threads: &[u64]) -> Result<Option<i64>> {
+        let mut threads = threads.to_vec();
+        threads.sort_unstable();
+        let threads = threads
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        self.conn()
+            .query_one(
+                "INSERT INTO duplicate_clusters (project, threads, status, reported_at)
+                 VALUES (?1, ?2, 'reported', ?3)
+                 ON CONFLICT DO NOTHING RETURNING id",
+                values![project, &threads, chrono::Utc::now().timestamp()],
+            )?
+            .map(|row| row.int(0))
+            .transpose()
+    }
+
+    pub fn duplicate_cluster(&self, id: i64) -> Result<Option<DuplicateCluster>> {
+        self.conn()
+            .query_one(
+                "SELECT id, project, threads, status FROM duplicate_clusters WHERE id = ?1",
+                values![id],
+            )?
+            .map(|row| duplicate_cluster_from_row(&row))
+            .transpose()
+    }
+
+    pub fn set_duplicate_cluster_status(&self, id: i64, status: &str) -> Result<()> {
+        self.conn().execute(
+            "UPDATE duplicate_clusters SET status = ?2 WHERE id = ?1",
+            values![id, status],
+        )?;
+        Ok(())
+    }
+
+    /// Queue a reward hook event for an issue; false if one was queued for
+    /// it before, so reopened and closed again issues aren't rewarded twice
+    pub fn queue_reward_hook(
+        &self,
+        project: &str,
+        issue_number: u64,
+        payload: &str,
+    ) -> Result<bool> {
+        let inserted = self.conn().execute(
+            "INSERT INTO reward_hooks (project, issue_number, payload, attempts, next_attempt_at)
+             VALUES (?1, ?2, ?3, 0, ?4)
+             ON CONFLICT DO NOTHING",
+            values![
+                project,
+                issue_number,
+                payload,
+                chrono::Utc::now().timestamp()
+            ],
+        )?;
+        Ok(inserted == 1)
+    }
+
+    /// Undelivered events of a project due by `now` with attempts left
+    pub fn due_reward_hooks(
+        &self,
+        project: &str,
+        now: i64,
+        max_attempts: u32,
+    ) -> Result<Vec<RewardDelivery>> {
+        self.conn()
+            .query(
+                "SELECT id, issue_number, payload, attempts FROM reward_hooks
+                 WHERE project = ?1 AND delivered_at IS NULL
+                   AND next_attempt_at <= ?2 AND attempts < ?3
+                 ORDER BY id",
+                values![project, now, i64::from(max_attempts)],
+            )?
+            .iter()
+            .map(reward_delivery_from_row)
+            .collect()
+    }
+
+    pub fn finish_reward_hook(&self, id: i64) -> Result<()> {
+        self.conn().execute(
+            "UPDATE reward_hooks SET delivered_at = ?2 WHERE id = ?1",
+            values![id, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Count a failed delivery and hold the event back until `next_attempt_at`
+    pub fn retry_reward_hook(&self, id: i64, next_attempt_at: i64) -> Result<()> {
+        self.conn().execute(
+            "UPDATE reward_hooks SET attempts = attempts + 1, next_attempt_at = ?2 WHERE id = ?1",
+            values![id, next_attempt_at],
+        )?;
+        Ok(())
+    }
+
+    /// Credit a reporter with the issue filed from their thread
+    pub fn record_contribution(
+        &self,
+        thread_id: u64,
+        guild_id: u64,
+        project: &str,
+        reporter_id: u64,
+        issue_number: u64,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO contributions
+                (thread_id, guild_id, project, reporter_id, issue_number, filed_at, reactions)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0)
+             ON CONFLICT DO NOTHING",
+            values![
+                thread_id,
+                guild_id,
+                project,
+                reporter_id,
+                issue_number,
+                chrono::Utc::now().timestamp()
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn has_contribution(&self, thread_id: u64) -> Result<bool> {
+        Ok(self
+            .conn()
+            .query_one(
+                "SELECT 1 FROM contributions WHERE thread_id = ?1",
+                values![thread_id],
+            )?
+            .is_some())
+    }
+
+    pub fn set_contribution_reactions(&self, thread_id: u64, reactions: u64) -> Result<()> {
+        self.conn().execute(
+            "UPDATE contributions SET reactions = ?2 WHERE thread_id = ?1",
+            values![thread_id, reactions],
+        )?;
+        Ok(())
+    }
+
+    /// Count a contribution's issue as fixed, the first time it is closed as
+    /// completed
+    pub fn mark_contribution_fixed(&self, thread_id: u64) -> Result<()> {
+        self.conn().execute(
+            "UPDATE contributions SET fixed_at = ?2 WHERE thread_id = ?1 AND fixed_at IS NULL",
+            values![thread_id, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Contributions in a guild filed at or after `since`
+    pub fn contributions_since(&self, guild_id: u64, since: i64) -> Result<Vec<Contribution>> {
+        self.conn()
+            .query(
+                "SELECT thread_id, reporter_id, issue_number, filed_at, fixed_at, reactions
+                 FROM contributions WHERE guild_id = ?1 AND filed_at >= ?2",
+                values![guild_id, since],
+            )?
+            .iter()
+            .map(contribution_from_row)
+            .collect()
+    }
+
+    /// Remember that a member got the project's onboarding message; false if
+    /// they already had
+    pub fn record_onboarding(&self, project: &str, user_id: u64) -> Result<bool> {
+        let inserted = self.conn().execute(
+            "INSERT INTO onboarded_reporters (project, user_id, onboarded_at)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT DO NOTHING",
+            values![project, user_id, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(inserted == 1)
+    }
+
+    /// Let a member get the onboarding message again, after it failed to send
+    pub fn forget_onboarding(&self, project: &str, user_id: u64) -> Result<()> {
+        self.conn().execute(
+            "DELETE FROM onboarded_reporters WHERE project = ?1 AND user_id = ?2",
+            values![project, user_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_duplicate_cluster(&self, id: i64) -> Result<()> {
+        self.conn()
+            .execute("DELETE FROM duplicate_clusters WHERE id = ?1", values![id])?;
+        Ok(())
+    }
+
+    /// Record an issue split out of a thread's parent issue by `/issue split`
+    pub fn record_split_issue(
+        &self,
+        project: &str,
+        issue_number: u64,
+        thread_id: u64,
+        parent_number: u64,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO split_issues (project, issue_number, thread_id, parent_number)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (project, issue_number) DO UPDATE SET
+                thread_id = excluded.thread_id,
+                parent_number = excluded.parent_number",
+            values![project, issue_number, thread_id, parent_number],
+        )?;
+        Ok(())
+    }
+
+    /// Issues split out of a thread, oldest first
+    pub fn split_issues(&self, thread_id: u64) -> Result<Vec<u64>> {
+        self.conn()
+            .query(
+                "SELECT issue_number FROM split_issues WHERE thread_id = ?1 ORDER BY issue_number",
+                values![thread_id],
+            )?
+            .iter()
+            .map(|row| row.id(0))
+            .collect()
+    }
+
+    /// Threads merged into a thread
+    pub fn merged_threads(&self, into_thread_id: u64) -> Result<Vec<u64>> {
+        self.conn()
+            .query(
+                "SELECT thread_id FROM merged_threads WHERE into_thread_id = ?1 ORDER BY thread_id",
+                values![into_thread_id],
+            )?
+            .iter()
+            .map(|row| row.id(0))
+            .collect()
+    }
+
+    /// Remember that a thread was sent the needs-info questionnaire.
+    ///
+    /// Returns false if it already has an unanswered one.
+    pub fn mark_needs_info_prompted(&self, thread_id: u64) -> Result<bool> {
+        let inserted = self.conn().execute(
+            "INSERT INTO needs_info_prompts (thread_id, posted_at) VALUES (?1, ?2)
+             ON CONFLICT DO NOTHING",
+            values![thread_id, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(inserted == 1)
+    }
+
+    pub fn clear_needs_info_prompt(&self, thread_id: u64) -> Result<()> {
+        self.conn().execute(
+            "DELETE FROM needs_info_prompts WHERE thread_id = ?1",
+            values![thread_id],
+        )?;
+        Ok(())
+    }
+
+    /// Milestone events (`50`, `100`, `due`) already announced
+    pub fn milestone_announcements(&self, project: &str, milestone: u64) -> Result<Vec<String>> {
+        self.conn()
+            .query(
+                "SELECT event FROM milestone_announcements WHERE project = ?1 AND milestone = ?2",
+                values![project, milestone],
+            )?
+            .iter()
+            .map(|row| row.text(0))
+            .collect()
+    }
+
+    pub fn record_milestone_announcement(
+        &self,
+        project: &str,
+        milestone: u64,
+        event: &str,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO milestone_announcements (project, milestone, event, announced_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT DO NOTHING",
+            values![project, milestone, event, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Whether the threads fixed by a release were already told about it
+    pub fn release_announced(&self, project: &str, release_id: u64) -> Result<bool> {
+        Ok(self
+            .conn()
+            .query_one(
+                "SELECT 1 FROM release_announcements WHERE project = ?1 AND release_id = ?2",
+                values![project, release_id],
+            )?
+            .is_some())
+    }
+
+    pub fn record_release_announcement(&self, project: &str, release_id: u64) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO release_announcements (project, release_id, announced_at)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT DO NOTHING",
+            values![project, release_id, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Whether a value hidden from a thread's issue was sent to the maintainers
+    pub fn redacted_value_reported(
+        &self,
+        project: &str,
+        thread_id: u64,
+        value: &str,
+    ) -> Result<bool> {
+        Ok(self
+            .conn()
+            .query_one(
+                "SELECT 1 FROM redacted_values
+                 WHERE project = ?1 AND thread_id = ?2 AND value = ?3",
+                values![project, thread_id, value],
+            )?
+            .is_some())
+    }
+
+    /// Remember a value hidden from a thread's issue once it was sent to the
+    /// maintainers; false if it already was
+    pub fn record_redacted_value(
+        &self,
+        project: &str,
+        thread_id: u64,
+        value: &str,
+    ) -> Result<bool> {
+        let inserted = self.conn().execute(
+            "INSERT INTO redacted_values (project, thread_id, value, reported_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT DO NOTHING",
+            values![project, thread_id, value, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(inserted == 1)
+    }
+
+    /// Remember a GitHub webhook delivery, forgetting ones past the retention window.
+    ///
+    /// Returns false if the delivery was already seen.
+    pub fn record_webhook_delivery(&self, delivery_id: &str) -> Result<bool> {
+        let now = chrono::Utc::now().timestamp();
+        let mut conn = self.conn();
+        conn.execute(
+            "DELETE FROM webhook_deliveries WHERE received_at < ?1",
+            values![now - crate::constants::WEBHOOK_DELIVERY_RETENTION_SECS],
+        )?;
+        let inserted = conn.execute(
+            "INSERT INTO webhook_deliveries (delivery_id, received_at) VALUES (?1, ?2)
+             ON CONFLICT DO NOTHING",
+            values![delivery_id, now],
+        )?;
+        Ok(inserted == 1)
+    }
+
+    /// Pinned summary message of a thread and the text it last showed
+    pub fn summary_message(&self, thread_id: u64) -> Result<Option<(u64, String)>> {
+        self.conn()
+            .query_one(
+                "SELECT message_id, content FROM thread_summaries WHERE thread_id = ?1",
+                values![thread_id],
+            )?
+            .map(|row| Ok((row.id(0)?, row.text(1)?)))
+            .transpose()
+    }
+
+    pub fn set_summary_message(
+        &self,
+        thread_id: u64,
+        message_id: u64,
+        content: &str,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO thread_summaries (thread_id, message_id, content)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT (thread_id) DO UPDATE SET
+                message_id = excluded.message_id,
+                content = excluded.content",
+            values![thread_id, message_id, content],
+        )?;
+        Ok(())
+    }
+
+    /// The activity comment on a thread's issue and what it last said
+    pub fn stats_comment(&self, thread_id: u64) -> Result<Option<(u64, String)>> {
+        self.conn()
+            .query_one(
+                "SELECT comment_id, content FROM stats_comments WHERE thread_id = ?1",
+                values![thread_id],
+            )?
+            .map(|row| Ok((row.id(0)?, row.text(1)?)))
+            .transpose()
+    }
+
+    pub fn set_stats_comment(&self, thread_id: u64, comment_id: u64, content: &str) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO stats_comments (thread_id, comment_id, content)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT (thread_id) DO UPDATE SET
+                comment_id = excluded.comment_id,
+                content = excluded.content",
+            values![thread_id, comment_id, content],
+        )?;
+        Ok(())
+    }
+
+    /// The translation section made for a thread, if its transcript still
+    /// hashes to `source_hash`
+    pub fn cached_translation(&self, thread_id: u64, source_hash: &str) -> Result<Option<String>> {
+        self.conn()
+            .query_one(
+                "SELECT section FROM thread_translations
+                 WHERE thread_id = ?1 AND source_hash = ?2",
+                values![thread_id, source_hash],
+            )?
+            .map(|row| row.text(0))
+            .transpose()
+    }
+
+    pub fn set_cached_translation(
+        &self,
+        thread_id: u64,
+        source_hash: &str,
+        section: &str,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO thread_translations (thread_id, source_hash, section)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT (thread_id) DO UPDATE SET
+                source_hash = excluded.source_hash,
+                section = excluded.section",
+            values![thread_id, source_hash, section],
+        )?;
+        Ok(())
+    }
+
+    /// How a thread's issue transcript was selected, as written by
+    /// `MessageSelection::encode`, and the messages that went into it
+    pub fn issue_transcript(&self, thread_id: u64) -> Result<Option<(String, Vec<u64>)>> {
+        self.conn()
+            .query_one(
+                "SELECT selection, message_ids FROM issue_transcripts WHERE thread_id = ?1",
+                values![thread_id],
+            )?
+            .map(|row| {
+                let ids = row
+                    .text(1)?
+                    .split(',')
+                    .filter_map(|id| id.parse().ok())
+                    .collect();
+                Ok((row.text(0)?, ids))
+            })
+            .transpose()
+    }
+
+    pub fn set_issue_transcript(
+        &self,
+        thread_id: u64,
+        selection: &str,
+        message_ids: &[u64],
+    ) -> Result<()> {
+        let message_ids = message_ids
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        self.conn().execute(
+            "INSERT INTO issue_transcripts (thread_id, selection, message_ids)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT (thread_id) DO UPDATE SET
+                selection = excluded.selection,
+                message_ids = excluded.message_ids",
+            values![thread_id, selection, &message_ids],
+        )?;
+        Ok(())
+    }
+
+    /// The thread's last message when its activity was last counted
+    pub fn stats_cursor(&self, thread_id: u64) -> Result<Option<u64>> {
+        self.conn()
+            .query_one(
+                "SELECT last_message_id FROM stats_cursors WHERE thread_id = ?1",
+                values![thread_id],
+            )?
+            .map(|row| row.id(0))
+            .transpose()
+    }
+
+    pub fn set_stats_cursor(&self, thread_id: u64, message_id: u64) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO stats_cursors (thread_id, last_message_id) VALUES (?1, ?2)
+             ON CONFLICT (thread_id) DO UPDATE SET last_message_id = excluded.last_message_id",
+            values![thread_id, message_id],
+        )?;
+        Ok(())
+    }
+
+    /// Triage state of a candidate thread, if it was ever queued
+    pub fn triage_status(&self, thread_id: u64) -> Result<Option<String>> {
+        self.conn()
+            .query_one(
+                "SELECT status FROM triage_queue WHERE thread_id = ?1",
+                values![thread_id],
+            )?
+            .map(|row| row.text(0))
+            .transpose()
+    }
+
+    pub fn set_triage_status(&self, thread_id: u64, project: &str, status: &str) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO triage_queue (thread_id, project, status, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (thread_id) DO UPDATE SET
+                project = excluded.project,
+                status = excluded.status,
+                updated_at = excluded.updated_at",
+            values![thread_id, project, status, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Move a queued thread from one triage state to another; false when it
+    /// was no longer in `from`, such as after another moderator's decision
+    pub fn claim_triage(&self, thread_id: u64, from: &str, to: &str) -> Result<bool> {
+        let changed = self.conn().execute(
+            "UPDATE triage_queue SET status = ?3, updated_at = ?4
+             WHERE thread_id = ?1 AND status = ?2",
+            values![thread_id, from, to, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// GitHub logins assigned to a thread's issue when the syncer last looked
+    pub fn issue_assignees(&self, thread_id: u64) -> Result<Option<Vec<String>>> {
+        self.conn()
+            .query_one(
+                "SELECT assignees FROM issue_assignees WHERE thread_id = ?1",
+                values![thread_id],
+            )?
+            .map(|row| {
+                let assignees = row.text(0)?;
+                Ok(assignees
+                    .split(',')
+                    .filter(|login| !login.is_empty())
+                    .map(str::to_string)
+                    .collect())
+            })
+            .transpose()
+    }
+
+    pub fn set_issue_assignees(&self, thread_id: u64, assignees: &[String]) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO issue_assignees (thread_id, assignees) VALUES (?1, ?2)
+             ON CONFLICT (thread_id) DO UPDATE SET assignees = excluded.assignees",
+            values![thread_id, &assignees.join(",")],
+        )?;
+        Ok(())
+    }
+
+    /// Queued triage candidates per project and status
+    pub fn triage_queue_depths(&self) -> Result<Vec<(String, String, u64)>> {
+        self.conn()
+            .query(
+                "SELECT project, status, COUNT(*) FROM triage_queue
+                 GROUP BY project, status ORDER BY project, status",
+                values![],
+            )?
+            .iter()
+            .map(|row| Ok((row.text(0)?, row.text(1)?, row.id(2)?)))
+            .collect()
+    }
+
+    /// Thread creation locks that have not expired yet
+    pub fn thread_lock_count(&self) -> Result<u64> {
+        let row = self.conn().query_one(
+            "SELECT COUNT(*) FROM thread_locks WHERE expires_at > ?1",
+            values![chrono::Utc::now().timestamp()],
+        )?;
+        row.map_or(Ok(0), |row| row.id(0))
+    }
+
+    pub fn drift_item(&self, project: &str, item: &str) -> Result<Option<DriftItem>> {
+        self.conn()
+            .query_one(
+                "SELECT project, item, detail, first_seen, last_seen, escalated
+                 FROM drift_items WHERE project = ?1 AND item = ?2",
+                values![project, item],
+            )?
+            .map(|row| drift_item_from_row(&row))
+            .transpose()
+    }
+
+    /// Record a drift condition as seen now, keeping when it was first seen
+    pub fn upsert_drift_item(
+        &self,
+        project: &str,
+        item: &str,
+        detail: &str,
+        escalated: bool,
+    ) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        self.conn().execute(
+            "INSERT INTO drift_items (project, item, detail, first_seen, last_seen, escalated)
+             VALUES (?1, ?2, ?3, ?4, ?4, ?5)
+             ON CONFLICT (project, item) DO UPDATE SET
+                detail = excluded.detail,
+                last_seen = excluded.last_seen,
+                escalated = excluded.escalated",
+            values![project, item, detail, now, escalated as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Forget a drift condition; false if it wasn't recorded
+    pub fn remove_drift_item(&self, project: &str, item: &str) -> Result<bool> {
+        let removed = self.conn().execute(
+            "DELETE FROM drift_items WHERE project = ?1 AND item = ?2",
+            values![project, item],
+        )?;
+        Ok(removed > 0)
+    }
+
+    pub fn drift_items(&self) -> Result<Vec<DriftItem>> {
+        self.conn()
+            .query(
+                "SELECT project, item, detail, first_seen, last_seen, escalated
+                 FROM drift_items ORDER BY project, item",
+                values![],
+            )?
+            .iter()
+            .map(drift_item_from_row)
+            .collect()
+    }
+
+    pub fn record_sync_result(&self, project: &str, error: Option<&str>) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO sync_results (project, finished_at, error) VALUES (?1, ?2, ?3)
+             ON CONFLICT (project) DO UPDATE SET
+                finished_at = excluded.finished_at,
+                error = excluded.error",
+            values![project, chrono::Utc::now().timestamp(), error],
+        )?;
+        Ok(())
+    }
+
+    pub fn sync_results(&self) -> Result<Vec<SyncResult>> {
+        self.conn()
+            .query(
+                "SELECT project, finished_at, error FROM sync_results ORDER BY project",
+                values![],
+            )?
+            .iter()
+            .map(|row| {
+                Ok(SyncResult {
+                    project: row.text(0)?,
+                    finished_at: row.int(1)?,
+                    error: row.opt_text(2),
+                })
+            })
+            .collect()
+    }
+
+    /// Remember a GitHub comment whose commands have run.
+    ///
+    /// Returns false if it was already processed.
+    pub fn mark_comment_processed(&self, comment_id: u64) -> Result<bool> {
+        let inserted = self.conn().execute(
+            "INSERT INTO processed_comments (comment_id, processed_at) VALUES (?1, ?2)
+             ON CONFLICT DO NOTHING",
+            values![comment_id, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(inserted == 1)
+    }
+
+    /// Issue filed from a single Discord message with `/issue create from:`
+    pub fn message_issue(&self, message_id: u64) -> Result<Option<u64>> {
+        self.conn()
+            .query_one(
+                "SELECT issue_number FROM message_issues WHERE message_id = ?1",
+                values![message_id],
+            )?
+            .map(|row| row.id(0))
+            .transpose()
+    }
+
+    pub fn record_message_issue(
+        &self,
+        message_id: u64,
+        project: &str,
+        issue_number: u64,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO message_issues (message_id, project, issue_number)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT (message_id) DO UPDATE SET
+                project = excluded.project,
+                issue_number = excluded.issue_number",
+            values![message_id, project, issue_number],
+        )?;
+        Ok(())
+    }
+
+    /// Record a Discord user's 👍 (1) or 👎 (-1) on a relayed GitHub comment;
+    /// voting the same way again takes the vote back.
+    ///
+    /// Returns the comment's 👍 and 👎 counts afterwards.
+    pub fn toggle_comment_vote(
+        &self,
+        comment_id: u64,
+        user_id: u64,
+        vote: i8,
+    ) -> Result<(u64, u64)> {
+        let mut conn = self.conn();
+        let removed = conn.execute(
+            "DELETE FROM comment_votes WHERE comment_id = ?1 AND user_id = ?2 AND vote = ?3",
+            values![comment_id, user_id, vote],
+        )?;
+        if removed == 0 {
+            conn.execute(
+                "INSERT INTO comment_votes (comment_id, user_id, vote) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (comment_id, user_id) DO UPDATE SET vote = excluded.vote",
+                values![comment_id, user_id, vote],
+            )?;
+        }
+        let row = conn.query_one(
+            "SELECT COUNT(CASE WHEN vote = 1 THEN 1 END), COUNT(CASE WHEN vote = -1 THEN 1 END)
+             FROM comment_votes WHERE comment_id = ?1",
+            values![comment_id],
+        )?;
+        row.map_or(Ok((0, 0)), |row| Ok((row.id(0)?, row.id(1)?)))
+    }
+
+    /// Remember which issue comment a mirrored thread reply went into
+    pub fn record_mirrored_message(
+        &self,
+        message_id: u64,
+        author: &str,
+        issue_number: u64,
+        comment_id: u64,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO mirrored_messages (message_id, author, issue_number, comment_id)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (message_id) DO UPDATE SET
+                author = excluded.author,
+                issue_number = excluded.issue_number,
+                comment_id = excluded.comment_id",
+            values![message_id, author, issue_number, comment_id],
+        )?;
+        Ok(())
+    }
+
+    /// Issue and comment a thread reply was mirrored into
+    pub fn mirrored_comment(&self, message_id: u64) -> Result<Option<(u64, u64)>> {
+        self.conn()
+            .query_one(
+                "SELECT issue_number, comment_id FROM mirrored_messages WHERE message_id = ?1",
+                values![message_id],
+            )?
+            .map(|row| Ok((row.id(0)?, row.id(1)?)))
+            .transpose()
+    }
+
+    /// Messages that make up a mirrored comment, oldest first, with their authors
+    pub fn comment_messages(&self, comment_id: u64) -> Result<Vec<(u64, String)>> {
+        self.conn()
+            .query(
+                "SELECT message_id, author FROM mirrored_messages WHERE comment_id = ?1 ORDER BY message_id",
+                values![comment_id],
+            )?
+            .iter()
+            .map(|row| Ok((row.id(0)?, row.text(1)?)))
+            .collect()
+    }
+
+    pub fn processed_comments(&self) -> Result<Vec<u64>> {
+        self.conn()
+            .query(
+                "SELECT comment_id FROM processed_comments ORDER BY comment_id",
+                values![],
+            )?
+            .iter()
+            .map(|row| row.id(0))
+            .collect()
+    }
+
+    pub fn insert_audit(&self, entry: &AuditEntry) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO audit_log (timestamp, project, action, thread_id, issue_number, actor, details)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            values![
+                entry.timestamp,
+                &entry.project,
+                &entry.action,
+                entry.thread_id,
+                entry.issue_number,
+                &entry.actor,
+                &entry.details,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Every audit entry, oldest first
+    pub fn all_audit_entries(&self) -> Result<Vec<AuditEntry>> {
+        let mut entries = self.query_audit(&AuditQuery {
+            limit: i64::MAX as usize,
+            ..Default::default()
+        })?;
+        entries.reverse();
+        Ok(entries)
+    }
+
+    /// The rows of every table the state dump has no typed field for
+    pub fn dump_tables(&self) -> Result<std::collections::BTreeMap<String, TableRows>> {
+        let mut conn = self.conn();
+        schema_tables()
+            .into_iter()
+            .filter(|(name, _)| !TYPED_TABLES.contains(name) && !EPHEMERAL_TABLES.contains(name))
+            .map(|(name, columns)| {
+                let columns: Vec<String> = columns
+                    .iter()
+                    .map(|(column, _)| column.to_string())
+                    .collect();
+                let rows = conn
+                    .query(
+                        &format!(
+                            "SELECT {} FROM {name} ORDER BY {}",
+                            columns.join(", "),
+                            columns.join(", ")
+                        ),
+                        values![],
+                    )?
+                    .iter()
+                    .map(|row| row.0.iter().map(json_value).collect())
+                    .collect();
+                Ok((name.to_string(), TableRows { columns, rows }))
+            })
+            .collect()
+    }
+
+    /// Merge a state dump; existing rows win except for mappings, which are
+    /// replaced, and audit entries already present are skipped
+    pub fn import(&self, dump: &StateDump) -> Result<ImportCounts> {
+        let mut conn = self.conn();
+        conn.execute_batch("BEGIN")?;
+        let result = import_rows(&mut conn, dump);
+        conn.execute_batch(if result.is_ok() { "COMMIT" } else { "ROLLBACK" })?;
+        result
+    }
+
+    /// Newest-first audit entries matching the query
+    pub fn query_audit(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        let rows = self.conn().query(
+            "SELECT timestamp, project, action, thread_id, issue_number, actor, details
+             FROM audit_log
+             WHERE (CAST(?1 AS TEXT) IS NULL OR project = ?1)
+               AND (CAST(?2 AS BIGINT) IS NULL OR thread_id = ?2)
+               AND (CAST(?3 AS BIGINT) IS NULL OR timestamp >= ?3)
+               AND (CAST(?4 AS BIGINT) IS NULL OR timestamp < ?4)
+             ORDER BY timestamp DESC, id DESC
+             LIMIT ?5",
+            values![
+                &query.project,
+                query.thread_id,
+                query.since,
+                query.until,
+                query.limit as i64,
+            ],
+        )?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(AuditEntry {
+                    timestamp: row.int(0)?,
+                    project: row.text(1)?,
+                    action: row.text(2)?,
+                    thread_id: row.opt_int(3).map(|id| id as u64),
+                    issue_number: row.opt_int(4).map(|n| n as u64),
+                    actor: row.text(5)?,
+                    details: row.opt_text(6),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Every table of the schema with its columns and whether each holds text.
+/// Auto-assigned IDs are left out, as the target hands out its own.
+fn schema_tables() -> Vec<(&'static str, Vec<(&'static str, bool)>)> {
+    SCHEMA
+        .split("CREATE TABLE IF NOT EXISTS ")
+        .skip(1)
+        .filter_map(|table| {
+            let (name, body) = table.split_once('(')?;
+            let (body, _) = body.split_once("\n);")?;
+            let columns = body
+                .lines()
+                .map(|line| line.trim().trim_end_matches(','))
+                .filter(|line| {
+                    !line.is_empty()
+                        && !line.starts_with("PRIMARY KEY")
+                        && !line.starts_with("UNIQUE")
+                        && !line.contains("AUTOINCREMENT")
+                })
+                .filter_map(|line| {
+                    let mut words = line.split_whitespace();
+                    Some((words.next()?, words.next()? == "TEXT"))
+                })
+                .collect();
+            Some((name.trim(), columns))
+        })
+        .collect()
+}
+
+fn json_value(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Int(value) => value.map_or(serde_json::Value::Null, serde_json::Value::from),
+        Value::Text(value) => value
+            .as_deref()
+            .map_or(serde_json::Value::Null, serde_json::Value::from),
+    }
+}
+
+fn import_rows(conn: &mut Conn<'_>, dump: &StateDump) -> Result<ImportCounts> {
+    let mut counts = ImportCounts::default();
+
+    let schema = schema_tables();
+    for (table, dumped) in &dump.tables {
+        let (table, columns) = schema
+            .iter()
+            .find(|(name, _)| name == table && !TYPED_TABLES.contains(name))
+            .with_context(|| format!("Unknown table {table} in the state dump"))?;
+        let is_text = dumped
+            .columns
+            .iter()
+            .map(|column| {
+                columns
+                    .iter()
+                    .find(|(name, _)| name == column)
+                    .map(|(_, is_text)| *is_text)
+                    .with_context(|| format!("Unknown column {table}.{column} in the state dump"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let placeholders: Vec<String> = (1..=is_text.len()).map(|i| format!("?{i}")).collect();
+        let sql = format!(
+            "INSERT INTO {table} ({}) VALUES ({}) ON CONFLICT DO NOTHING",
+            dumped.columns.join(", "),
+            placeholders.join(", ")
+        );
+        for row in &dumped.rows {
+            if row.len() != is_text.len() {
+                anyhow::bail!("A row of {table} in the state dump has the wrong column count");
+            }
+            let params: Vec<Value> = row
+                .iter()
+                .zip(&is_text)
+                .map(|(value, is_text)| {
+                    if *is_text {
+                        Value::Text(value.as_str().map(str::to_string))
+                    } else {
+                        Value::Int(value.as_i64())
+                    }
+                })
+                .collect();
+            counts.other_rows += conn.execute(&sql, &params)?;
+        }
+    }
+
+    for mapping in &dump.thread_issues {
+        counts.thread_issues += conn.execute(
+            "INSERT INTO thread_issues (thread_id, project, issue_number, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (thread_id) DO UPDATE SET
+                project = excluded.project,
+                issue_number = excluded.issue_number,
+                updated_at = excluded.updated_at",
+            values![
+                mapping.thread_id,
+                &mapping.project,
+                mapping.issue_number,
+                mapping.updated_at
+            ],
+        )?;
+    }
+
+    for comment_id in &dump.processed_comments {
+        counts.processed_comments += conn.execute(
+            "INSERT INTO processed_comments (comment_id, processed_at) VALUES (?1, ?2)
+             ON CONFLICT DO NOTHING",
+            values![*comment_id, chrono::Utc::now().timestamp()],
+        )?;
+    }
+
+    for (thread_id, message_id) in &dump.mirror_cursors {
+        counts.mirror_cursors += conn.execute(
+            "INSERT INTO mirror_cursors (thread_id, last_message_id) VALUES (?1, ?2)
+             ON CONFLICT (thread_id) DO UPDATE SET last_message_id = excluded.last_message_id",
+            values![*thread_id, *message_id],
+        )?;
+    }
+
+    for entry in &dump.audit_log {
+        counts.audit_log += conn.execute(
+            "INSERT INTO audit_log (timestamp, project, action, thread_id, issue_number, actor, details)
+             SELECT CAST(?1 AS BIGINT), CAST(?2 AS TEXT), CAST(?3 AS TEXT), CAST(?4 AS BIGINT),
+                    CAST(?5 AS BIGINT), CAST(?6 AS TEXT), CAST(?7 AS TEXT)
+             WHERE NOT EXISTS (
+                SELECT 1 FROM audit_log
+                WHERE timestamp = ?1 AND project = ?2 AND action = ?3
+                  AND thread_id IS NOT DISTINCT FROM ?4
+                  AND issue_number IS NOT DISTINCT FROM ?5 AND actor = ?6
+             )",
+            values![
+                entry.timestamp,
+                &entry.project,
+                &entry.action,
+                entry.thread_id,
+                entry.issue_number,
+                &entry.actor,
+                &entry.details,
+            ],
+        )?;
+    }
+
+    Ok(counts)
+}
+
+impl Drop for Store {
+    fn drop(&mut self) {
+        // Closing the Postgres connection blocks as well
+        if let Backend::Postgres(postgres) = &mut self.backend {
+            let client = postgres
+                .client
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner())
+                .take();
+            blocking(|| drop(client));
+        }
+    }
+}
+
+fn thread_issue_from_row(row: &Row) -> Result<ThreadIssue> {
+    Ok(ThreadIssue {
+        thread_id: row.id(0)?,
+        project: row.text(1)?,
+        issue_number: row.id(2)?,
+        updated_at: row.int(3)?,
+    })
+}
+
+fn telegram_topic_from_row(row: &Row) -> Result<TelegramTopic> {
+    Ok(TelegramTopic {
+        topic_id: row.int(0)?,
+        name: row.text(1)?,
+        creator: row.text(2)?,
+        issue_number: row.opt_int(3).map(|n| n as u64),
+    })
+}
+
+fn job_from_row(row: &Row) -> Result<QueuedJob> {
+    Ok(QueuedJob {
+        id: row.int(0)?,
+        kind: row.text(1)?,
+        payload: row.text(2)?,
+        attempts: row.int(3)? as u32,
+        run_after: row.int(4)?,
+        lease_until: row.int(5)?,
+    })
+}
+
+fn reward_delivery_from_row(row: &Row) -> Result<RewardDelivery> {
+    Ok(RewardDelivery {
+        id: row.int(0)?,
+        issue_number: row.int(1)? as u64,
+        payload: row.text(2)?,
+        attempts: row.int(3)? as u32,
+    })
+}
+
+fn contribution_from_row(row: &Row) -> Result<Contribution> {
+    Ok(Contribution {
+        thread_id: row.int(0)? as u64,
+        reporter_id: row.int(1)? as u64,
+        issue_number: row.int(2)? as u64,
+        filed_at: row.int(3)?,
+        fixed_at: row.opt_int(4),
+        reactions: row.int(5)? as u64,
+    })
+}
+
+fn duplicate_cluster_from_row(row: &Row) -> Result<DuplicateCluster> {
+    let threads = row
+        .text(2)?
+        .split(',')
+        .map(|id| id.parse().context("Invalid thread ID in duplicate cluster"))
+        .collect::<Result<_>>()?;
+    Ok(DuplicateCluster {
+        id: row.int(0)?,
+        project: row.text(1)?,
+        threads,
+        status: row.text(3)?,
+    })
+}
+
+fn drift_item_from_row(row: &Row) -> Result<DriftItem> {
+    Ok(DriftItem {
+        project: row.text(0)?,
+        item: row.text(1)?,
+        detail: row.text(2)?,
+        first_seen: row.int(3)?,
+        last_seen: row.int(4)?,
+        escalated: row.int(5)? != 0,
+    })
+}
+
+/// A query parameter or column value. Nulls keep their type because Postgres
+/// checks parameter types even for NULL.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Int(Option<i64>),
+    Text(Option<String>),
+}
+
+macro_rules! values {
+    ($($value:expr),* $(,)?) => {
+        &[$(Value::from($value)),*]
+    };
+}
+use values;
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Int(Some(value))
+    }
+}
+
+impl From<i8> for Value {
+    fn from(value: i8) -> Self {
+        Value::Int(Some(value.into()))
+    }
+}
+
+// IDs are stored as their bit pattern in a signed 64-bit column
+impl From<u64> for Value {
+    fn from(value: u64) -> Self {
+        Value::Int(Some(value as i64))
+    }
+}
+
+impl From<Option<i64>> for Value {
+    fn from(value: Option<i64>) -> Self {
+        Value::Int(value)
+    }
+}
+
+impl From<Option<u64>> for Value {
+    fn from(value: Option<u64>) -> Self {
+        Value::Int(value.map(|v| v as i64))
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Text(Some(value.to_string()))
+    }
+}
+
+impl From<&String> for Value {
+    fn from(value: &String) -> Self {
+        Value::Text(Some(value.clone()))
+    }
+}
+
+impl From<Option<&str>> for Value {
+    fn from(value: Option<&str>) -> Self {
+        Value::Text(value.map(str::to_string))
+    }
+}
+
+impl From<&Option<String>> for Value {
+    fn from(value: &Option<String>) -> Self {
+        Value::Text(value.clone())
+    }
+}
+
+impl rusqlite::ToSql for Value {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        match self {
+            Value::Int(value) => value.to_sql(),
+            Value::Text(value) => value.to_sql(),
+        }
+    }
+}
+
+/// One result row, read the same way from either backend
+struct Row(Vec<Value>);
+
+impl Row {
+    fn opt_int(&self, index: usize) -> Option<i64> {
+        match self.0.get(index) {
+            Some(Value::Int(value)) => *value,
+            _ => None,
+        }
+    }
+
+    fn opt_text(&self, index: usize) -> Option<String> {
+        match self.0.get(index) {
+            Some(Value::Text(value)) => value.clone(),
+            _ => None,
+        }
+    }
+
+    fn int(&self, index: usize) -> Result<i64> {
+        self.opt_int(index)
+            .with_context(|| format!("Expected an integer in column {index}"))
+    }
+
+    fn id(&self, index: usize) -> Result<u64> {
+        Ok(self.int(index)? as u64)
+    }
+
+    fn text(&self, index: usize) -> Result<String> {
+        self.opt_text(index)
+            .with_context(|| format!("Expected text in column {index}"))
+    }
+}
+
+/// A locked connection. Statements are written for SQLite with `?N`
+/// parameters and kept to syntax Postgres shares.
+enum Conn<'a> {
+    Sqlite(MutexGuard<'a, Connection>),
+    Postgres(
+        &'a postgres::Config,
+        MutexGuard<'a, Option<postgres::Client>>,
+    ),
+}
+
+impl Conn<'_> {
+    fn execute(&mut self, sql: &str, params: &[Value]) -> Result<usize> {
+        match self {
+            Conn::Sqlite(conn) => Ok(conn.execute(sql, rusqlite::params_from_iter(params))?),
+            Conn::Postgres(config, client) => {
+                let client = open_client(config, client)?;
+                let sql = postgres_placeholders(sql);
+                let params = postgres_params(params);
+                let changed = blocking(|| client.execute(sql.as_str(), &params))?;
+                Ok(changed as usize)
+            }
+        }
+    }
+
+    fn execute_batch(&mut self, sql: &str) -> Result<()> {
+        match self {
+            Conn::Sqlite(conn) => conn.execute_batch(sql)?,
+            Conn::Postgres(config, client) => {
+                let client = open_client(config, client)?;
+                blocking(|| client.batch_execute(sql))?
+            }
+        }
+        Ok(())
+    }
+
+    fn query(&mut self, sql: &str, params: &[Value]) -> Result<Vec<Row>> {
+        match self {
+            Conn::Sqlite(conn) => {
+                let mut stmt = conn.prepare(sql)?;
+                let columns = stmt.column_count();
+                let rows = stmt.query_map(rusqlite::params_from_iter(params), |row| {
+                    (0..columns)
+                        .map(|i| {
+                            Ok(match row.get_ref(i)? {
+                                ValueRef::Integer(value) => Value::Int(Some(value)),
+                                ValueRef::Text(text) => {
+                                    Value::Text(Some(String::from_utf8_lossy(text).into_owned()))
+                                }
+                                _ => Value::Int(None),
+                            })
+                        })
+                        .collect::<rusqlite::Result<Vec<_>>>()
+                        .map(Row)
+                })?;
+                Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+            }
+            Conn::Postgres(config, client) => {
+                let client = open_client(config, client)?;
+                let sql = postgres_placeholders(sql);
+                let params = postgres_params(params);
+                let rows = blocking(|| client.query(sql.as_str(), &params))?;
+                rows.iter().map(postgres_row).collect()
+            }
+        }
+    }
+
+    fn query_one(&mut self, sql: &str, params: &[Value]) -> Result<Option<Row>> {
+        Ok(self.query(sql, params)?.into_iter().next())
+    }
+}
+
+/// The connected client, reconnecting first when the server closed the
+/// connection (a restart or failover) or the last reconnect failed
+fn open_client<'a>(
+    config: &postgres::Config,
+    client: &'a mut MutexGuard<'_, Option<postgres::Client>>,
+) -> Result<&'a mut postgres::Client> {
+    if client.as_ref().is_none_or(postgres::Client::is_closed) {
+        tracing::warn!("The Postgres connection was closed, reconnecting");
+        // Dropped first, so a failed reconnect doesn't keep the dead client;
+        // closing blocks like any other call
+        let dead = client.take();
+        blocking(|| drop(dead));
+        **client = Some(connect_postgres(config)?);
+    }
+    client.as_mut().context("The Postgres connection is closed")
+}
+
+fn connect_postgres(config: &postgres::Config) -> Result<postgres::Client> {
+    // Unix sockets have no host name to check a certificate against
+    let local = config
+        .get_hosts()
+        .iter()
+        .all(|host| !matches!(host, postgres::config::Host::Tcp(_)));
+    if local {
+        blocking(|| config.connect(postgres::NoTls))
+    } else {
+        let mut roots = rustls::RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let tls = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        let tls = tokio_postgres_rustls::MakeRustlsConnect::new(tls);
+        blocking(|| config.connect(tls))
+    }
+    .context("Failed to connect to the Postgres database")
+}
+
+/// `?1` placeholders as Postgres' `$1`
+fn postgres_placeholders(sql: &str) -> String {
+    static PLACEHOLDER: std::sync::LazyLock<regex::Regex> =
+        std::sync::LazyLock::new(|| regex::Regex::new(r"\?(\d+)").expect("valid regex"));
+    PLACEHOLDER.replace_all(sql, "$$$1").into_owned()
+}
+
+fn postgres_params(params: &[Value]) -> Vec<&(dyn postgres::types::ToSql + Sync)> {
+    params
+        .iter()
+        .map(|value| match value {
+            Value::Int(value) => value as &(dyn postgres::types::ToSql + Sync),
+            Value::Text(value) => value as &(dyn postgres::types::ToSql + Sync),
+        })
+        .collect()
+}
+
+fn postgres_row(row: &postgres::Row) -> Result<Row> {
+    use postgres::types::Type;
+
+    (0..row.len())
+        .map(|i| {
+            let column = &row.columns()[i];
+            Ok(match *column.type_() {
+                Type::INT8 => Value::Int(row.try_get(i)?),
+                Type::INT4 => Value::Int(row.try_get::<_, Option<i32>>(i)?.map(i64::from)),
+                Type::TEXT | Type::VARCHAR => Value::Text(row.try_get(i)?),
+                ref other => anyhow::bail!("Unsupported column type {other} for {}", column.name()),
+            })
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(Row)
+}
+
+/// The Postgres client blocks on its own runtime, which tokio only allows
+/// once the worker thread has been handed over
+fn blocking<T>(f: impl FnOnce() -> T) -> T {
+    use tokio::runtime::{Handle, RuntimeFlavor};
+
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(f)
+        }
+        _ => f(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_lock_is_exclusive() {
+        let store = Store::open_in_memory().unwrap();
+        assert!(store.try_lock_thread(1, 60).unwrap());
+        assert!(!store.try_lock_thread(1, 60).unwrap());
+        assert!(store.try_lock_thread(2, 60).unwrap());
+
+        store.unlock_thread(1).unwrap();
+        assert!(store.try_lock_thread(1, 60).unwrap());
+
+        // Expired leases can be taken over
+        assert!(store.try_lock_thread(3, -1).unwrap());
+        assert!(store.try_lock_thread(3, 60).unwrap());
+    }
+
+    #[test]
+    fn test_audit_log_query_and_append_only() {
+        let store = Store::open_in_memory().unwrap();
+        for (timestamp, project, thread_id) in [(10, "a/x", 1), (20, "a/x", 2), (30, "b/y", 1)] {
+            store
+                .insert_audit(&AuditEntry {
+                    timestamp,
+                    project: project.to_string(),
+                    action: "thread_locked".to_string(),
+                    thread_id: Some(thread_id),
+                    issue_number: None,
+                    actor: "cardibot".to_string(),
+                    details: None,
+                })
+                .unwrap();
+        }
+
+        let by_project = store
+            .query_audit(&AuditQuery {
+                project: Some("a/x".to_string()),
+                limit: 10,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(by_project.len(), 2);
+        assert_eq!(by_project[0].timestamp, 20);
+
+        let by_thread_and_time = store
+            .query_audit(&AuditQuery {
+                thread_id: Some(1),
+                since: Some(15),
+                limit: 10,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(by_thread_and_time.len(), 1);
+        assert_eq!(by_thread_and_time[0].project, "b/y");
+
+        assert!(store
+            .conn()
+            .execute("DELETE FROM audit_log", values![])
+            .is_err());
+    }
+
+    #[test]
+    fn test_comment_votes() {
+        let store = Store::open_in_memory().unwrap();
+        assert_eq!(store.toggle_comment_vote(7, 1, 1).unwrap(), (1, 0));
+        assert_eq!(store.toggle_comment_vote(7, 2, 1).unwrap(), (2, 0));
+        // Switching sides moves the vote, repeating it takes it back
+        assert_eq!(store.toggle_comment_vote(7, 2, -1).unwrap(), (1, 1));
+        assert_eq!(store.toggle_comment_vote(7, 1, 1).unwrap(), (0, 1));
+        assert_eq!(store.toggle_comment_vote(8, 1, -1).unwrap(), (0, 1));
+    }
+
+    #[test]
+    fn test_mirrored_messages() {
+        let store = Store::open_in_memory().unwrap();
+        store.record_mirrored_message(12, "bob", 3, 900).unwrap();
+        store.record_mirrored_message(11, "bob", 3, 900).unwrap();
+        store.record_mirrored_message(20, "alice", 3, 901).unwrap();
+
+        assert_eq!(store.mirrored_comment(11).unwrap(), Some((3, 900)));
+        assert_eq!(store.mirrored_comment(99).unwrap(), None);
+        assert_eq!(
+            store.comment_messages(900).unwrap(),
+            vec![(11, "bob".to_string()), (12, "bob".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_sync_results_and_queue_depths() {
+        let store = Store::open_in_memory().unwrap();
+        store.record_sync_result("org/app", Some("boom")).unwrap();
+        store.record_sync_result("org/app", None).unwrap();
+        store
+            .record_sync_result("org/web", Some("rate limited"))
+            .unwrap();
+        let results = store.sync_results().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].error, None);
+        assert_eq!(results[1].error.as_deref(), Some("rate limited"));
+
+        store.set_triage_status(1, "org/app", "pending").unwrap();
+        store.set_triage_status(2, "org/app", "pending").unwrap();
+        store.set_triage_status(3, "org/app", "dismissed").unwrap();
+        assert_eq!(
+            store.triage_queue_depths().unwrap(),
+            vec![
+                ("org/app".to_string(), "dismissed".to_string(), 1),
+                ("org/app".to_string(), "pending".to_string(), 2),
+            ]
+        );
+
+        assert!(store.try_lock_thread(5, 60).unwrap());
+        assert_eq!(store.thread_lock_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_moderator_actions() {
+        let store = Store::open_in_memory().unwrap();
+        assert_eq!(store.moderator_action(1).unwrap(), None);
+
+        store
+            .record_moderator_action(1, "archived", 7, "mod")
+            .unwrap();
+        store
+            .record_moderator_action(1, "locked", 8, "admin")
+            .unwrap();
+        let action = store.moderator_action(1).unwrap().unwrap();
+        assert_eq!(action.action, "locked");
+        assert_eq!(action.moderator, "admin");
+        assert_eq!(action.moderator_id, 8);
+
+        assert!(store.clear_moderator_action(1).unwrap());
+        assert!(!store.clear_moderator_action(1).unwrap());
+        assert_eq!(store.moderator_action(1).unwrap(), None);
+    }
+
+    #[test]
+    fn test_rehosted_attachments() {
+        let store = Store::open_in_memory().unwrap();
+        let url = "https://media.example.com/1/5/log.txt";
+        let future = chrono::Utc::now().timestamp() + 60;
+        store
+            .record_rehosted_attachment(5, "1/5/log.txt", url, future)
+            .unwrap();
+        assert_eq!(
+            store.rehosted_attachment(5).unwrap(),
+            Some((url.to_string(), future))
+        );
+        assert!(store.expired_attachments().unwrap().is_empty());
+
+        store
+            .record_rehosted_attachment(5, "1/5/log.txt", url, 0)
+            .unwrap();
+        assert_eq!(store.rehosted_attachment(5).unwrap(), None);
+        assert_eq!(
+            store.expired_attachments().unwrap(),
+            vec![(5, "1/5/log.txt".to_string())]
+        );
+        store.forget_rehosted_attachment(5).unwrap();
+        assert!(store.expired_attachments().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sla_timers() {
+        let store = Store::open_in_memory().unwrap();
+        let now = chrono::Utc::now().timestamp();
+
+        store
+            .start_sla_timer("o/r", 1, 10, now - 100, now + 100)
+            .unwrap();
+        store
+            .start_sla_timer("o/r", 2, 20, now - 300, now - 200)
+            .unwrap();
+        store
+            .start_sla_timer("o/r", 3, 30, now - 300, now - 100)
+            .unwrap();
+        // A running timer keeps its start
+        store.start_sla_timer("o/r", 1, 10, now, now + 500).unwrap();
+        assert_eq!(
+            store.sla_timer("o/r", 1).unwrap().unwrap().due_at,
+            now + 100
+        );
+
+        store.record_sla_response("o/r", 1, now - 50).unwrap();
+        store.record_sla_response("o/r", 3, now - 50).unwrap();
+        store.record_sla_breach("o/r", 2, now).unwrap();
+        assert_eq!(
+            store.sla_timer("o/r", 2).unwrap().unwrap().breached_at,
+            Some(now)
+        );
+        assert_eq!(
+            store.sla_stats("o/r", now - 1000).unwrap(),
+            SlaStats {
+                met: 1,
+                missed: 2,
+                pending: 0,
+                average_response: Some(150),
+            }
+        );
+    }
+
+    #[test]
+    fn test_matrix_threads() {
+        let store = Store::open_in_memory().unwrap();
+        let room = "!room:example.org";
+        assert_eq!(store.matrix_thread_issue(room, "$a").unwrap(), None);
+
+        store.link_matrix_thread(room, "$a", "o/r", 4).unwrap();
+        store.link_matrix_thread(room, "$b", "o/docs", 2).unwrap();
+        store
+            .link_matrix_thread("!other:example.org", "$c", "o/r", 9)
+            .unwrap();
+        // The first link stays
+        store.link_matrix_thread(room, "$a", "o/r", 5).unwrap();
+        assert_eq!(
+            store.matrix_thread_issue(room, "$a").unwrap(),
+            Some(("o/r".to_string(), 4))
+        );
+
+        store.close_matrix_thread(room, "$a", 100).unwrap();
+        assert_eq!(
+            store.open_matrix_threads(room).unwrap(),
+            vec![("$b".to_string(), "o/docs".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_telegram_topics() {
+        let store = Store::open_in_memory().unwrap();
+        let chat = -100_123;
+        assert_eq!(store.telegram_update_offset().unwrap(), 0);
+        store.set_telegram_update_offset(42).unwrap();
+        store.set_telegram_update_offset(43).unwrap();
+        assert_eq!(store.telegram_update_offset().unwrap(), 43);
+
+        store
+            .record_telegram_topic(chat, 7, "Crash", "alice")
+            .unwrap();
+        store.rename_telegram_topic(chat, 7, "[BUG] Crash").unwrap();
+        store.record_telegram_topic(chat, 9, "Idea", "bob").unwrap();
+        store
+            .record_telegram_message(chat, 8, 7, "alice", "it crashes")
+            .unwrap();
+        store
+            .record_telegram_message(chat, 10, 7, "bob", "me too")
+            .unwrap();
+        store
+            .record_telegram_message(chat, 8, 7, "alice", "again")
+            .unwrap();
+        assert_eq!(
+            store.telegram_messages(chat, 7).unwrap(),
+            vec![
+                ("alice".to_string(), "it crashes".to_string()),
+                ("bob".to_string(), "me too".to_string()),
+            ]
+        );
+
+        store.link_telegram_topic(chat, 7, "o/r", 3).unwrap();
+        assert!(store.telegram_messages(chat, 7).unwrap().is_empty());
+        assert_eq!(
+            store.telegram_topic(chat, 7).unwrap().unwrap().issue_number,
+            Some(3)
+        );
+        let unfiled = store.unfiled_telegram_topics(chat).unwrap();
+        assert_eq!(unfiled.len(), 1);
+        assert_eq!(unfiled[0].name, "Idea");
+
+        assert_eq!(
+            store.open_telegram_topics(chat).unwrap(),
+            vec![(7, "o/r".to_string(), 3)]
+        );
+        store.close_telegram_topic(chat, 7, 100).unwrap();
+        assert!(store.open_telegram_topics(chat).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_jobs() {
+        let store = Store::open_in_memory().unwrap();
+        let first = store.enqueue_job("default", "thread_update", "{}").unwrap();
+        let second = store
+            .enqueue_job("default", "message_change", "[]")
+            .unwrap();
+        store.enqueue_job("other", "thread_update", "{}").unwrap();
+        assert_ne!(first, second);
+
+        let pending = store.pending_jobs("default").unwrap();
+        assert_eq!(
+            pending.iter().map(|j| j.id).collect::<Vec<_>>(),
+            vec![first, second]
+        );
+        assert_eq!(pending[1].kind, "message_change");
+        assert_eq!(pending[1].payload, "[]");
+        assert_eq!(pending[1].attempts, 0);
+
+        let now = chrono::Utc::now().timestamp();
+        let claimed = store.claim_job(first, now, now + 60).unwrap().unwrap();
+        assert_eq!(claimed.lease_until, now + 60);
+        // Held by the first claim
+        assert_eq!(store.claim_job(first, now, now + 60).unwrap(), None);
+
+        store.retry_job(first, now + 1234).unwrap();
+        let job = store.job(first).unwrap().unwrap();
+        assert_eq!(
+            (job.attempts, job.run_after, job.lease_until),
+            (1, now + 1234, 0)
+        );
+        // Not due yet
+        assert_eq!(store.claim_job(first, now, now + 60).unwrap(), None);
+        assert!(store
+            .claim_job(first, now + 1234, now + 1294)
+            .unwrap()
+            .is_some());
+
+        store.finish_job(first).unwrap();
+        assert_eq!(store.job(first).unwrap(), None);
+        assert_eq!(store.pending_jobs("default").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_reward_hooks() {
+        let store = Store::open_in_memory().unwrap();
+        assert!(store.queue_reward_hook("o/r", 1, "{}").unwrap());
+        assert!(!store.queue_reward_hook("o/r", 1, "{}").unwrap());
+        assert!(store.queue_reward_hook("o/r", 2, "[]").unwrap());
+        assert!(store.queue_reward_hook("o/other", 1, "{}").unwrap());
+
+        let now = chrono::Utc::now().timestamp();
+        let due = store.due_reward_hooks("o/r", now, 3).unwrap();
+        assert_eq!(
+            due.iter().map(|d| d.issue_number).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+
+        store.finish_reward_hook(due[0].id).unwrap();
+        store.retry_reward_hook(due[1].id, now + 60).unwrap();
+        assert!(store.due_reward_hooks("o/r", now, 3).unwrap().is_empty());
+        let retried = store.due_reward_hooks("o/r", now + 60, 3).unwrap();
+        assert_eq!(
+            (retried[0].attempts, retried[0].payload.as_str()),
+            (1, "[]")
+        );
+        // Out of attempts
+        assert!(store
+            .due_reward_hooks("o/r", now + 60, 1)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_contributions() {
+        let store = Store::open_in_memory().unwrap();
+        store.record_contribution(1, 100, "o/r", 7, 11).unwrap();
+        store.record_contribution(2, 100, "o/r", 8, 12).unwrap();
+        store.record_contribution(3, 200, "o/r", 7, 13).unwrap();
+        // Filing again keeps the first record
+        store.record_contribution(1, 100, "o/r", 9, 11).unwrap();
+        assert!(store.has_contribution(1).unwrap());
+        assert!(!store.has_contribution(4).unwrap());
+
+        store.set_contribution_reactions(1, 5).unwrap();
+        store.mark_contribution_fixed(1).unwrap();
+        let mut contributions = store.contributions_since(100, 0).unwrap();
+        contributions.sort_by_key(|c| c.thread_id);
+        assert_eq!(contributions.len(), 2);
+        assert_eq!(
+            (contributions[0].reporter_id, contributions[0].reactions),
+            (7, 5)
+        );
+        assert!(contributions[0].fixed_at.is_some());
+        assert_eq!(contributions[1].fixed_at, None);
+        assert!(store.contributions_since(100, i64::MAX).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_clusters() {
+        let store = Store::open_in_memory().unwrap();
+        let id = store
+            .record_duplicate_cluster("o/r", &[30, 10, 20])
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            store
+                .record_duplicate_cluster("o/r", &[10, 20, 30])
+                .unwrap(),
+            None
+        );
+        assert!(store
+            .record_duplicate_cluster("o/r", &[10, 20])
+            .unwrap()
+            .is_some());
+
+        let cluster = store.duplicate_cluster(id).unwrap().unwrap();
+        assert_eq!(cluster.threads, vec![10, 20, 30]);
+        assert_eq!(cluster.status, "reported");
+        store.set_duplicate_cluster_status(id, "merged").unwrap();
+        assert_eq!(
+            store.duplicate_cluster(id).unwrap().unwrap().status,
+            "merged"
+        );
+        assert_eq!(store.duplicate_cluster(id + 100).unwrap(), None);
+    }
+
+    #[test]
+    fn test_issue_transcripts() {
+        let store = Store::open_in_memory().unwrap();
+        assert_eq!(store.issue_transcript(1).unwrap(), None);
+        store.set_issue_transcript(1, "2::", &[1, 5, 9]).unwrap();
+        store.set_issue_transcript(1, ":10:20", &[1, 5]).unwrap();
+        assert_eq!(
+            store.issue_transcript(1).unwrap(),
+            Some((":10:20".to_string(), vec![1, 5]))
+        );
+    }
+
+    #[test]
+    fn test_onboarding() {
+        let store = Store::open_in_memory().unwrap();
+        assert!(store.record_onboarding("o/r", 1).unwrap());
+        assert!(!store.record_onboarding("o/r", 1).unwrap());
+        assert!(store.record_onboarding("o/other", 1).unwrap());
+        store.forget_onboarding("o/r", 1).unwrap();
+        assert!(store.record_onboarding("o/r", 1).unwrap());
+    }
+
+    #[test]
+    fn test_state_dump_covers_every_table() {
+        let store = Store::open_in_memory().unwrap();
+        let row = store
+            .conn()
+            .query_one(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+                values![],
+            )
+            .unwrap()
+            .unwrap();
+        let tables = row.int(0).unwrap() as usize;
+        let dumped = store.dump_tables().unwrap();
+        assert_eq!(
+            dumped.len() + TYPED_TABLES.len() + EPHEMERAL_TABLES.len(),
+            tables
+        );
+        assert_eq!(POSTGRES_SCHEMA.matches("CREATE TABLE").count(), tables);
+
+        store.mute_user(7, Some(1)).unwrap();
+        store.record_onboarding("acme/app", 7).unwrap();
+        store.record_message_issue(33, "acme/app", 12).unwrap();
+        let dump = StateDump {
+            version: crate::state::STATE_FORMAT_VERSION,
+            exported_at: String::new(),
+            thread_issues: Vec::new(),
+            processed_comments: Vec::new(),
+            mirror_cursors: Vec::new(),
+            audit_log: Vec::new(),
+            tables: store.dump_tables().unwrap(),
+        };
+
+        let copy = Store::open_in_memory().unwrap();
+        assert_eq!(copy.import(&dump).unwrap().other_rows, 3);
+        assert_eq!(copy.dump_tables().unwrap(), dump.tables);
+        assert_eq!(copy.import(&dump).unwrap().other_rows, 0);
+    }
+
+    #[test]
+    fn test_muted_users() {
+        let store = Store::open_in_memory().unwrap();
+        assert!(!store.is_muted(7, Some(1)).unwrap());
+
+        store.mute_user(7, Some(1)).unwrap();
+        assert!(store.is_muted(7, Some(1)).unwrap());
+        assert!(!store.is_muted(7, Some(2)).unwrap());
+        assert!(!store.is_muted(7, None).unwrap());
+
+        store.mute_user(7, None).unwrap();
+        assert!(store.is_muted(7, Some(2)).unwrap());
+        assert!(store.unmute_user(7, None).unwrap());
+        assert!(!store.unmute_user(7, None).unwrap());
+        assert!(store.is_muted(7, Some(1)).unwrap());
+        assert!(!store.is_muted(8, Some(1)).unwrap());
+    }
+
+    #[test]
+    fn test_drift_items() {
+        let store = Store::open_in_memory().unwrap();
+        store
+            .upsert_drift_item("org/app", "missing_thread:1", "gone", false)
+            .unwrap();
+        let first = store
+            .drift_item("org/app", "missing_thread:1")
+            .unwrap()
+            .unwrap();
+        store
+            .upsert_drift_item("org/app", "missing_thread:1", "still gone", true)
+            .unwrap();
+        let second = store
+            .drift_item("org/app", "missing_thread:1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.first_seen, first.first_seen);
+        assert_eq!(second.detail, "still gone");
+        assert!(second.escalated);
+        assert_eq!(store.drift_items().unwrap().len(), 1);
+
+        assert!(store
+            .remove_drift_item("org/app", "missing_thread:1")
+            .unwrap());
+        assert!(!store
+            .remove_drift_item("org/app", "missing_thread:1")
+            .unwrap());
+    }
+
+    #[test]
+    fn test_postgres_placeholders() {
+        assert_eq!(
+            postgres_placeholders("SELECT ?1 WHERE a = ?12 AND b IS ?1"),
+            "SELECT $1 WHERE a = $12 AND b IS $1"
+        );
+    }
+
+    // Runs against a scratch database when CARDIBOT_TEST_DATABASE_URL is set
+    #[test]
+    fn test_postgres_backend() {
+        let Ok(url) = std::env::var("CARDIBOT_TEST_DATABASE_URL") else {
+            return;
+        };
+        let store = Store::open_postgres(&url).unwrap();
+        let id = chrono::Utc::now().timestamp_micros() as u64;
+        let project = format!("test/{id}");
+
+        assert!(store.try_lock_thread(id, 60).unwrap());
+        assert!(!store.try_lock_thread(id, 60).unwrap());
+        store.unlock_thread(id).unwrap();
+
+        store.upsert_thread_issue(id, &project, 1).unwrap();
+        store.upsert_thread_issue(id, &project, 2).unwrap();
+        assert_eq!(store.thread_issue(id).unwrap().unwrap().issue_number, 2);
+        assert_eq!(store.thread_for_issue(&project, 2).unwrap(), Some(id));
+        assert_eq!(store.linked_thread_count(&project).unwrap(), 1);
+
+        // Discord IDs use the full 64 bits
+        store.set_mirror_cursor(id, u64::MAX - 1).unwrap();
+        assert_eq!(store.mirror_cursor(id).unwrap(), Some(u64::MAX - 1));
+
+        assert_eq!(store.toggle_comment_vote(id, 1, 1).unwrap(), (1, 0));
+        assert_eq!(store.toggle_comment_vote(id, 1, -1).unwrap(), (0, 1));
+
+        let entry = AuditEntry {
+            timestamp: 10,
+            project: project.clone(),
+            action: "issue_created".to_string(),
+            thread_id: Some(id),
+            issue_number: None,
+            actor: "cardibot".to_string(),
+            details: None,
+        };
+        store.insert_audit(&entry).unwrap();
+        let dump = StateDump {
+            version: 1,
+            exported_at: String::new(),
+            thread_issues: vec![],
+            processed_comments: vec![id],
+            mirror_cursors: vec![],
+            audit_log: vec![entry],
+            tables: Default::default(),
+        };
+        let imported = store.import(&dump).unwrap();
+        assert_eq!((imported.processed_comments, imported.audit_log), (1, 0));
+        let entries = store
+            .query_audit(&AuditQuery {
+                project: Some(project),
+                limit: 10,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(store
+            .conn()
+            .execute("DELETE FROM audit_log", values![])
+            .is_err());
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let source = Store::open_in_memory().unwrap();
+        source.upsert_thread_issue(1, "a/x", 10).unwrap();
+        source.upsert_thread_issue(1, "a/x", 11).unwrap();
+        source.mark_comment_processed(99).unwrap();
+        source
+            .insert_audit(&AuditEntry {
+                timestamp: 10,
+                project: "a/x".to_string(),
+                action: "issue_created".to_string(),
+                thread_id: Some(1),
+                issue_number: Some(11),
+                actor: "cardibot".to_string(),
+                details: None,
+            })
+            .unwrap();
+
+        let dump = StateDump {
+            version: 1,
+            exported_at: String::new(),
+            thread_issues: source.thread_issues().unwrap(),
+            processed_comments: source.processed_comments().unwrap(),
+            mirror_cursors: source.mirror_cursors().unwrap(),
+            audit_log: source.all_audit_entries().unwrap(),
+            tables: Default::default(),
+        };
+        assert_eq!(dump.thread_issues.len(), 1);
+        assert_eq!(dump.thread_issues[0].issue_number, 11);
+
+        let target = Store::open_in_memory().unwrap();
+        let first = target.import(&dump).unwrap();
+        assert_eq!(first.audit_log, 1);
+        assert!(!target.mark_comment_processed(99).unwrap());
+
+        // Importing twice does not duplicate audit entries
+        let second = target.import(&dump).unwrap();
+        assert_eq!(second.audit_log, 0);
+        assert_eq!(target.all_audit_entries().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,160 @@
+use anyhow::Result;
+use octocrab::models::issues::Issue;
+use serenity::builder::{CreateMessage, EditMessage};
+use serenity::http::Http;
+use serenity::model::id::{ChannelId, MessageId};
+use tracing::{debug, info};
+
+use crate::embeds::Embed;
+use crate::store::Store;
+
+/// The parts of an issue mirrored into its thread: the pinned summary and
+/// the status tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueSummary {
+    pub number: u64,
+    pub url: String,
+    pub open: bool,
+    /// Closed as completed rather than as not planned or a duplicate
+    pub completed: bool,
+    /// Whether the issue's milestone is closed; None without a milestone
+    pub milestone_closed: Option<bool>,
+    pub labels: Vec<String>,
+    pub assignees: Vec<String>,
+    /// Unix timestamp of the last change on GitHub
+    pub updated_at: i64,
+}
+
+impl IssueSummary {
+    pub fn from_issue(issue: &Issue) -> Self {
+        Self {
+            number: issue.number,
+            url: issue.html_url.to_string(),
+            open: matches!(issue.state, octocrab::models::IssueState::Open),
+            completed: matches!(
+                issue.state_reason,
+                None | Some(octocrab::models::issues::IssueStateReason::Completed)
+            ),
+            milestone_closed: issue
+                .milestone
+                .as_ref()
+                .map(|m| m.state.as_deref() == Some("closed")),
+            labels: issue.labels.iter().map(|l| l.name.clone()).collect(),
+            assignees: issue.assignees.iter().map(|a| a.login.clone()).collect(),
+            updated_at: issue.updated_at.timestamp(),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        let list = |items: &[String], empty: &str| {
+            if items.is_empty() {
+                empty.to_string()
+            } else {
+                items.join(", ")
+            }
+        };
+
+        format!(
+            "**Issue**: [#{}]({})\n**Status**: {}\n**Labels**: {}\n**Assignee**: {}\n**Last activity**: <t:{}:R>",
+            self.number,
+            self.url,
+            if self.open { "🟢 Open" } else { "🟣 Closed" },
+            list(&self.labels, "none"),
+            list(&self.assignees, "unassigned"),
+            self.updated_at
+        )
+    }
+}
+
+/// Create or refresh the pinned summary message at the top of a thread.
+/// The message is only edited when the summary changed since the last run.
+pub async fn update_summary(
+    discord: &Http,
+    store: &Store,
+    thread_id: u64,
+    summary: &IssueSummary,
+) -> Result<()> {
+    let description = summary.description();
+    let channel_id = ChannelId::new(thread_id);
+
+    let embed = || {
+        Embed::new()
+            .title(crate::constants::MSG_ISSUE_SUMMARY)
+            .description(description.clone())
+            .color(if summary.open {
+                crate::constants::COLOR_SUCCESS
+            } else {
+                crate::constants::COLOR_CLOSED
+            })
+            .build()
+    };
+
+    if let Some((message_id, previous)) = store.summary_message(thread_id)? {
+        if previous == description {
+            return Ok(());
+        }
+        match channel_id
+            .edit_message(
+                discord,
+                MessageId::new(message_id),
+                EditMessage::new().embed(embed()),
+            )
+            .await
+        {
+            Ok(_) => {
+                store.set_summary_message(thread_id, message_id, &description)?;
+                debug!("Refreshed summary for issue #{}", summary.number);
+                return Ok(());
+            }
+            // Deleted by a moderator: post a fresh one below
+            Err(e) if crate::error::is_discord_not_found(&e) => {
+                debug!("Summary message in thread {} is gone", thread_id)
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let message = channel_id
+        .send_message(discord, CreateMessage::new().embed(embed()))
+        .await?;
+    message.pin(discord).await?;
+    store.set_summary_message(thread_id, message.id.get(), &description)?;
+    info!(
+        "Pinned summary for issue #{} in thread {}",
+        summary.number, thread_id
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_description() {
+        let mut summary = IssueSummary {
+            number: 7,
+            url: "https://github.com/acme/app/issues/7".to_string(),
+            open: true,
+            completed: true,
+            milestone_closed: None,
+            labels: vec![],
+            assignees: vec![],
+            updated_at: 1_700_000_000,
+        };
+        let description = summary.description();
+        assert!(description.contains("[#7](https://github.com/acme/app/issues/7)"));
+        assert!(description.contains("**Labels**: none"));
+        assert!(description.contains("**Assignee**: unassigned"));
+        assert!(description.contains("<t:1700000000:R>"));
+
+        summary.open = false;
+        summary.labels = vec!["bug".to_string(), "P1".to_string()];
+        summary.assignees = vec!["octocat".to_string()];
+        let description = summary.description();
+        assert!(description.contains("🟣 Closed"));
+        assert!(description.contains("**Labels**: bug, P1"));
+        assert!(description.contains("**Assignee**: octocat"));
+    }
+}
@@ -0,0 +1,116 @@
+use serde::Serialize;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{error, info};
+
+use crate::constants::{SYNC_RESTART_BASE_SECS, SYNC_RESTART_MAX_SECS, SYNC_RESTART_RESET_SECS};
+
+/// Restarts of a bot's syncer, shared between its supervisor and the health
+/// endpoint
+#[derive(Default)]
+pub struct SyncerHealth {
+    state: Mutex<SyncerState>,
+}
+
+#[derive(Default)]
+struct SyncerState {
+    /// Set while waiting to restart after a crash
+    restarting_at: Option<chrono::DateTime<chrono::Utc>>,
+    restarts: u64,
+    last_failure: Option<String>,
+    last_failure_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Snapshot served on `GET /health`
+#[derive(Debug, Serialize)]
+pub struct SyncerStatus {
+    pub running: bool,
+    pub restarts: u64,
+    pub restarting_at: Option<String>,
+    pub last_failure: Option<String>,
+    pub last_failure_at: Option<String>,
+}
+
+impl SyncerHealth {
+    pub fn status(&self) -> SyncerStatus {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        SyncerStatus {
+            running: state.restarting_at.is_none(),
+            restarts: state.restarts,
+            restarting_at: state.restarting_at.map(|t| t.to_rfc3339()),
+            last_failure: state.last_failure.clone(),
+            last_failure_at: state.last_failure_at.map(|t| t.to_rfc3339()),
+        }
+    }
+
+    fn record_failure(&self, failure: String, delay: Duration) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = chrono::Utc::now();
+        state.last_failure = Some(failure);
+        state.last_failure_at = Some(now);
+        state.restarting_at = chrono::Duration::from_std(delay)
+            .ok()
+            .map(|delay| now + delay);
+    }
+
+    fn record_restart(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.restarts += 1;
+        state.restarting_at = None;
+    }
+}
+
+/// Run a syncer built by `start` and start a new one whenever it panics,
+/// waiting twice as long after each crash in a row. A run that lasted
+/// `SYNC_RESTART_RESET_SECS` counts as healthy and resets the wait. Returns
+/// once a syncer finishes on its own, which it only does when sync is off.
+pub async fn supervise<F, Fut>(label: String, health: Arc<SyncerHealth>, start: F)
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let mut crashes = 0;
+    loop {
+        let started = Instant::now();
+        let failure = match tokio::spawn(start()).await {
+            Ok(()) => return,
+            Err(e) => e.to_string(),
+        };
+
+        if started.elapsed() >= Duration::from_secs(SYNC_RESTART_RESET_SECS) {
+            crashes = 0;
+        }
+        let delay = restart_delay(crashes);
+        crashes += 1;
+        error!(
+            "Syncer of bot '{}' stopped: {}; restarting in {}s",
+            label,
+            failure,
+            delay.as_secs()
+        );
+        health.record_failure(failure, delay);
+        tokio::time::sleep(delay).await;
+
+        health.record_restart();
+        info!("Restarting the syncer of bot '{}'", label);
+    }
+}
+
+/// Wait before the restart following `crashes` crashes in a row
+fn restart_delay(crashes: u32) -> Duration {
+    let secs = SYNC_RESTART_BASE_SECS.saturating_mul(1 << crashes.min(16));
+    Duration::from_secs(secs.min(SYNC_RESTART_MAX_SECS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_delay() {
+        assert_eq!(restart_delay(0).as_secs(), SYNC_RESTART_BASE_SECS);
+        assert_eq!(restart_delay(2).as_secs(), 4 * SYNC_RESTART_BASE_SECS);
+        assert_eq!(restart_delay(40).as_secs(), SYNC_RESTART_MAX_SECS);
+    }
+}
@@ -1,25 +1,79 @@
 use anyhow::Result;
+use futures::stream::{self, StreamExt};
 use octocrab::Octocrab;
 use regex::Regex;
 use serenity::http::Http;
-use serenity::model::channel::ChannelType;
+use serenity::model::channel::{ChannelType, GuildChannel, Message};
 use serenity::model::id::{ChannelId, GuildId};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::time::interval;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
 use tracing::{debug, error, info, warn};
 
-use crate::config::{Config, Project};
+use crate::audit::{project_key, AuditAction, AuditEntry, AuditLog};
+use crate::ci_status::CiStatusTracker;
+use crate::circuit_breaker::CircuitBreakers;
+use crate::config::{ChatPlatform, Config, Project};
+use crate::duplicate_clusters::DuplicateScans;
+use crate::rate_budget::RateBudgets;
+use crate::store::Store;
+use crate::summary::IssueSummary;
+use crate::sync_schedule::{RateLimited, SyncSchedule};
+use crate::sync_status::{CycleReport, StatusMessage};
+
+/// A forum thread whose linked issue is looked up in the next batch
+struct ClosureCheck<'a> {
+    thread: &'a GuildChannel,
+    /// The project the issue moved to, with a client for it
+    moved: Option<(Project, Octocrab)>,
+    issue_number: u64,
+    messages: Vec<Message>,
+}
 
 pub struct IssueSyncer {
     config: Arc<Config>,
     discord: Arc<Http>,
+    ci_tracker: CiStatusTracker,
+    breakers: CircuitBreakers,
+    budgets: RateBudgets,
+    schedule: SyncSchedule,
+    /// Notified by the webhook listener to run a cycle early
+    wake: Arc<Notify>,
+    store: Arc<Store>,
+    audit: Arc<AuditLog>,
+    /// Ops channel status message, when `[sync] status_channel_id` is set
+    status: Option<StatusMessage>,
+    duplicate_scans: DuplicateScans,
 }
 
 impl IssueSyncer {
-    pub fn new(config: Arc<Config>, discord: Arc<Http>) -> Self {
-        Self { config, discord }
+    pub fn new(
+        config: Arc<Config>,
+        discord: Arc<Http>,
+        store: Arc<Store>,
+        audit: Arc<AuditLog>,
+        wake: Arc<Notify>,
+    ) -> Self {
+        let interval = Duration::from_secs(config.sync_config().interval_seconds);
+        let schedule = SyncSchedule::new(&config.sync_config());
+        let status = StatusMessage::new(&config.sync_config());
+        Self {
+            config,
+            discord,
+            ci_tracker: CiStatusTracker::default(),
+            budgets: RateBudgets::new(interval),
+            schedule,
+            breakers: CircuitBreakers::new(
+                crate::constants::SYNC_BREAKER_THRESHOLD,
+                Duration::from_secs(crate::constants::SYNC_BREAKER_COOLDOWN_SECS),
+            ),
+            store,
+            audit,
+            wake,
+            status,
+            duplicate_scans: DuplicateScans::default(),
+        }
     }
 
     pub async fn start(self) {
@@ -35,83 +89,481 @@ impl IssueSyncer {
             sync_config.interval_seconds
         );
 
-        let mut interval = interval(Duration::from_secs(sync_config.interval_seconds));
+        // Converge after downtime before the fast loop takes over
+        if let Err(e) = self.reconcile_all_projects().await {
+            error!("Error during startup reconciliation: {}", e);
+        }
+
+        let mut last_start: Option<Instant> = None;
 
         loop {
-            interval.tick().await;
+            tokio::select! {
+                _ = tokio::time::sleep(self.schedule.next_delay(last_start.map(|s| s.elapsed()))) => {}
+                _ = self.wake.notified() => debug!("Sync cycle triggered by webhook"),
+            }
+            // Webhooks don't get to cut a rate limit wait short
+            if let Some(wait) = self.schedule.held_for() {
+                info!("Waiting {}s for the GitHub rate limit", wait.as_secs());
+                tokio::time::sleep(wait).await;
+            }
 
+            last_start = Some(Instant::now());
             if let Err(e) = self.sync_all_projects().await {
                 error!("Error during sync cycle: {}", e);
             }
         }
     }
 
+    async fn reconcile_all_projects(&self) -> Result<()> {
+        info!("Running startup reconciliation");
+        let mut clients = HashMap::new();
+
+        for project in self
+            .routed_projects()
+            .iter()
+            .filter(|p| p.platform() == ChatPlatform::Discord)
+        {
+            let github = self.github_for(&mut clients, project).await?;
+            let paused = project.mutations_paused(chrono::Utc::now()).is_some();
+            if let Err(e) = crate::reconcile::reconcile_project(
+                &github,
+                &self.discord,
+                &self.store,
+                &self.audit,
+                project,
+                paused,
+            )
+            .await
+            {
+                error!(
+                    "Error reconciling project {}: {:?}",
+                    project.name.as_deref().unwrap_or("unnamed"),
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+
     async fn sync_all_projects(&self) -> Result<()> {
         info!(
             "Starting sync cycle for {} projects",
             self.config.projects.len()
         );
 
-        // Create a fresh GitHub client for this sync cycle
-        let github = match crate::github_app::create_github_client().await {
-            Ok(client) => Arc::new(client),
-            Err(e) => {
-                error!("Failed to create GitHub client: {:?}", e);
-                return Err(e);
-            }
-        };
+        // Fresh GitHub clients for this sync cycle, one per account
+        let mut clients = HashMap::new();
+        let started = Instant::now();
+        let started_at = chrono::Utc::now().timestamp();
+        let mut report = CycleReport::default();
+        let projects = self.routed_projects();
 
-        for project in &self.config.projects {
-            if let Err(e) = self.sync_project(project, &github).await {
-                error!(
-                    "Error syncing project {} (owner: {}, repo: {}): {:?}",
-                    project.name.as_deref().unwrap_or("unnamed"),
-                    project.github_owner,
-                    project.github_repo,
-                    e
+        for (index, project) in projects.iter().enumerate() {
+            if index > 0 && !self.schedule.stagger().is_zero() {
+                tokio::time::sleep(self.schedule.stagger()).await;
+            }
+            let key = crate::audit::project_key(project);
+            if let Some(remaining) = self.breakers.open_for(&key, Instant::now()) {
+                debug!(
+                    "Skipping project {} for another {}s after repeated failures",
+                    key,
+                    remaining.as_secs()
                 );
+                report.skipped += 1;
+                continue;
+            }
+
+            let result = match self.github_for(&mut clients, project).await {
+                Ok(github) => self.sync_project(project, &github).await,
+                Err(e) => Err(e.context("Failed to create GitHub client")),
+            };
+
+            // Kept for `/cardibot debug` and `dump-state`
+            let error = result.as_ref().err().map(|e| format!("{e:#}"));
+            if let Err(e) = self.store.record_sync_result(&key, error.as_deref()) {
+                warn!("Failed to record sync result for {}: {}", key, e);
+            }
+
+            match result {
+                Ok(()) => {
+                    self.breakers.record_success(&key);
+                    report.synced += 1;
+                }
+                Err(e) => {
+                    // Not the project's fault; stop the cycle until GitHub allows more
+                    if let Some(RateLimited(wait)) = e.downcast_ref::<RateLimited>() {
+                        warn!(
+                            "GitHub rate limit reached while syncing {}; pausing for {}s",
+                            key,
+                            wait.as_secs()
+                        );
+                        self.schedule.hold(*wait);
+                        report.rate_limited = Some(*wait);
+                        break;
+                    }
+                    error!(
+                        "Error syncing project {} (owner: {}, repo: {}): {:?}",
+                        project.name.as_deref().unwrap_or("unnamed"),
+                        project.github_owner,
+                        project.github_repo,
+                        e
+                    );
+                    report.failed.push((key.clone(), format!("{e:#}")));
+                    if self.breakers.record_failure(&key, Instant::now()) {
+                        self.suspend_project(project, &e).await;
+                    }
+                }
+            }
+        }
+
+        crate::attachments::delete_expired(&self.config, &self.store).await;
+
+        if let Some(status) = &self.status {
+            report.duration = started.elapsed();
+            self.fill_report(&mut report, &projects, &clients, started_at)
+                .await;
+            if let Err(e) = status.publish(&self.discord, &report).await {
+                warn!("Failed to post the sync status message: {}", e);
             }
         }
         Ok(())
     }
 
+    /// Add the cycle's audited changes and each account's GitHub quota usage
+    async fn fill_report(
+        &self,
+        report: &mut CycleReport,
+        projects: &[Project],
+        clients: &HashMap<(Option<String>, Option<String>), Arc<Octocrab>>,
+        started_at: i64,
+    ) {
+        let keys: HashSet<String> = projects.iter().map(crate::audit::project_key).collect();
+        let query = crate::audit::AuditQuery {
+            since: Some(started_at),
+            limit: crate::constants::SYNC_STATUS_AUDIT_LIMIT,
+            ..Default::default()
+        };
+        match self.store.query_audit(&query) {
+            Ok(entries) => {
+                for entry in entries.iter().filter(|e| {
+                    e.actor == crate::constants::AUDIT_ACTOR_BOT && keys.contains(&e.project)
+                }) {
+                    *report.actions.entry(entry.action.clone()).or_default() += 1;
+                }
+            }
+            Err(e) => warn!("Failed to read the audit log for the sync status: {}", e),
+        }
+
+        for ((account, _), github) in clients {
+            // The rate limit endpoint doesn't count against the quota
+            match github.ratelimit().get().await {
+                Ok(limits) => report.github.push((
+                    account.clone().unwrap_or_else(|| "default".to_string()),
+                    limits.resources.core.used,
+                    limits.resources.core.limit,
+                )),
+                Err(e) => debug!("Failed to read the GitHub rate limit: {}", e),
+            }
+        }
+        report.github.sort();
+    }
+
+    /// Synced projects, once per repository their prefixes route threads to
+    fn routed_projects(&self) -> Vec<Project> {
+        self.config
+            .projects
+            .iter()
+            .filter(|p| p.sync_enabled())
+            .flat_map(|p| match p.platform() {
+                ChatPlatform::Discord => p.routes(),
+                // Threads of polled platforms are routed as they are filed
+                ChatPlatform::Matrix | ChatPlatform::Telegram => vec![p.clone()],
+            })
+            .collect()
+    }
+
+    /// Alert once when a project's breaker trips
+    async fn suspend_project(&self, project: &Project, error: &anyhow::Error) {
+        error!(
+            "Suspending sync of {} for {}s after {} consecutive failures",
+            crate::audit::project_key(project),
+            crate::constants::SYNC_BREAKER_COOLDOWN_SECS,
+            crate::constants::SYNC_BREAKER_THRESHOLD
+        );
+        self.audit
+            .record(
+                AuditEntry::new(project, AuditAction::SyncSuspended).details(format!(
+                    "{} consecutive failures, retrying in {} minutes: {}",
+                    crate::constants::SYNC_BREAKER_THRESHOLD,
+                    crate::constants::SYNC_BREAKER_COOLDOWN_SECS / 60,
+                    error
+                )),
+            )
+            .await;
+    }
+
+    /// The project's GitHub client, created once per account, server and cycle
+    async fn github_for(
+        &self,
+        clients: &mut HashMap<(Option<String>, Option<String>), Arc<Octocrab>>,
+        project: &Project,
+    ) -> Result<Arc<Octocrab>> {
+        let key = (
+            project.github_account.clone(),
+            project.github_api_url.clone(),
+        );
+        if let Some(client) = clients.get(&key) {
+            return Ok(client.clone());
+        }
+        let client =
+            Arc::new(crate::github_app::create_project_client(&self.config, project).await?);
+        clients.insert(key, client.clone());
+        Ok(client)
+    }
+
     async fn sync_project(&self, project: &Project, github: &Arc<Octocrab>) -> Result<()> {
         info!(
             "Syncing project: {}",
             project.name.as_deref().unwrap_or("unnamed")
         );
 
-        // Search for all open issues with thread IDs
-        let open_issues = self
+        // During quiet hours the sync still runs so problems show up in the logs,
+        // but nothing is changed on Discord until the window ends
+        let paused = project.mutations_paused(chrono::Utc::now());
+        if let Some(reason) = &paused {
+            info!("Mutations paused for {}; observing only", reason);
+        }
+        let paused = paused.is_some();
+
+        match project.platform() {
+            ChatPlatform::Discord => {}
+            ChatPlatform::Matrix => {
+                return crate::matrix_sync::sync_room(
+                    &self.discord,
+                    &self.config,
+                    github,
+                    &self.store,
+                    &self.audit,
+                    &self.budgets,
+                    project,
+                    paused,
+                )
+                .await;
+            }
+            ChatPlatform::Telegram => {
+                return crate::telegram_sync::sync_chat(
+                    &self.discord,
+                    &self.config,
+                    github,
+                    &self.store,
+                    &self.audit,
+                    &self.budgets,
+                    project,
+                    paused,
+                )
+                .await;
+            }
+        }
+
+        // Search for all open issues with linked threads
+        let open_issues: Vec<_> = self
             .search_issues(github, &project.github_owner, &project.github_repo, "open")
-            .await?;
+            .await?
+            .into_iter()
+            .filter_map(|issue| {
+                let thread_id = crate::mappings::thread_for(&self.store, project, &issue)?;
+                Some((thread_id, issue))
+            })
+            .collect();
 
         info!("Found {} open issues with thread IDs", open_issues.len());
 
         // Build a set of open issue thread IDs for quick lookup
-        let open_thread_ids: HashSet<u64> = open_issues
+        let open_thread_ids: HashSet<u64> = open_issues.iter().map(|(id, _)| *id).collect();
+
+        // The Discord side of each issue is checked a few threads at a time;
+        // the budgeted GitHub follow-ups below stay in order
+        let concurrency = self.config.sync_config().concurrency();
+        // Futures are built up front: a closure over borrowed items inside the
+        // stream trips the Send check of the spawned sync task
+        let refreshes: Vec<_> = open_issues
             .iter()
-            .filter_map(|issue| extract_thread_id(&issue.title))
+            .map(|(thread_id, issue)| self.refresh_open_thread(project, *thread_id, issue, paused))
             .collect();
+        let exists: Vec<bool> = stream::iter(refreshes)
+            .buffered(concurrency)
+            .collect()
+            .await;
+        let existing_threads = exists.iter().filter(|e| **e).count();
+        let missing_threads = open_issues.len() - existing_threads;
 
-        // Count how many threads exist
-        let mut existing_threads = 0;
-        let mut missing_threads = 0;
+        // Follow-up GitHub work that doesn't fit this cycle's budget is picked
+        // up first on the next one
+        let mut deferred_from = None;
+        let start = self.budgets.resume_index(project, open_issues.len());
 
-        // Sync open issues (ensure threads are unlocked)
-        for issue in &open_issues {
-            if let Some(thread_id) = extract_thread_id(&issue.title) {
-                match self.sync_open_issue(project, thread_id, issue).await {
-                    Ok(true) => existing_threads += 1,
-                    Ok(false) => missing_threads += 1,
-                    Err(e) => {
-                        warn!("Failed to sync open issue #{}: {}", issue.number, e);
-                        missing_threads += 1;
-                    }
+        // One listing of open pull requests serves every issue's CI check
+        let fixes = if project.ci_status_updates()
+            && !project.staging()
+            && !paused
+            && self.budgets.try_take(project, 1)
+        {
+            crate::ci_status::open_fixes(github, project)
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("Failed to list pull requests: {}", e);
+                    HashMap::new()
+                })
+        } else {
+            HashMap::new()
+        };
+
+        for index in crate::rate_budget::rotated(start, open_issues.len()) {
+            if !exists[index] {
+                continue;
+            }
+            let (thread_id, issue) = &open_issues[index];
+            let thread_id = *thread_id;
+
+            // Threads merged into this one always mirror their replies
+            let merged = self.store.merged_threads(thread_id).unwrap_or_default();
+            let sla_pending = crate::sla::pending(&self.store, project, issue);
+            let fixing = fixes
+                .get(&issue.number)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let ci_status = !fixing.is_empty();
+            // These post in the thread, which staging leaves to production
+            let comment_commands = project.comment_commands() && !project.staging();
+            let sla_pending = sla_pending && !project.staging();
+            let calls = [
+                ci_status,
+                project.mirror_replies(),
+                comment_commands,
+                project.stats_comment(),
+                sla_pending,
+            ]
+            .into_iter()
+            .filter(|enabled| *enabled)
+            .count() as u32
+                + merged.len() as u32;
+            if paused || calls == 0 {
+                continue;
+            }
+            if deferred_from.is_some() || !self.budgets.try_take(project, calls) {
+                deferred_from.get_or_insert(index);
+                continue;
+            }
+
+            if ci_status {
+                if let Err(e) = self
+                    .ci_tracker
+                    .check_linked_fixes(github, &self.discord, project, fixing, thread_id)
+                    .await
+                {
+                    warn!("Failed to check CI for issue #{}: {}", issue.number, e);
+                }
+            }
+
+            if project.stats_comment() {
+                if let Err(e) = crate::thread_stats::update_stats_comment(
+                    github,
+                    &self.discord,
+                    &self.store,
+                    project,
+                    issue.number,
+                    thread_id,
+                )
+                .await
+                {
+                    warn!(
+                        "Failed to update activity comment on issue #{}: {}",
+                        issue.number, e
+                    );
+                }
+            }
+
+            if project.mirror_replies() {
+                if let Err(e) = crate::mirror::mirror_replies(
+                    github,
+                    &self.discord,
+                    &self.store,
+                    project,
+                    issue.number,
+                    thread_id,
+                )
+                .await
+                {
+                    warn!("Failed to mirror replies to issue #{}: {}", issue.number, e);
+                }
+            }
+
+            for merged_thread_id in merged {
+                if let Err(e) = crate::mirror::mirror_replies(
+                    github,
+                    &self.discord,
+                    &self.store,
+                    project,
+                    issue.number,
+                    merged_thread_id,
+                )
+                .await
+                {
+                    warn!(
+                        "Failed to mirror merged thread {} to issue #{}: {}",
+                        merged_thread_id, issue.number, e
+                    );
+                }
+            }
+
+            if sla_pending {
+                if let Err(e) = crate::sla::check(
+                    github,
+                    &self.discord,
+                    &self.store,
+                    &self.audit,
+                    project,
+                    issue,
+                    thread_id,
+                )
+                .await
+                {
+                    warn!("Failed to check SLA of issue #{}: {}", issue.number, e);
+                }
+            }
+
+            if comment_commands {
+                if let Err(e) = crate::comment_commands::process_issue_comments(
+                    github,
+                    &self.discord,
+                    &self.store,
+                    &self.audit,
+                    project,
+                    issue.number,
+                    thread_id,
+                )
+                .await
+                {
+                    warn!(
+                        "Failed to process comment commands for issue #{}: {}",
+                        issue.number, e
+                    );
                 }
             }
         }
 
+        if let Some(index) = deferred_from {
+            info!(
+                "GitHub budget for {} used up; deferring {} issues to the next cycle",
+                crate::audit::project_key(project),
+                crate::rate_budget::rotated(start, open_issues.len())
+                    .skip_while(|i| *i != index)
+                    .count()
+            );
+        }
+        self.budgets
+            .set_resume_index(project, deferred_from.unwrap_or(0));
+
         info!(
             "Discord thread status: {}/{} exist ({} missing)",
             existing_threads,
@@ -121,12 +573,77 @@ impl IssueSyncer {
 
         // Check all Discord threads in the forum
         if let Err(e) = self
-            .sync_discord_threads(project, &open_thread_ids, github)
+            .sync_discord_threads(project, &open_thread_ids, github, paused)
             .await
         {
             warn!("Failed to sync Discord threads: {}", e);
         }
 
+        if let Some(reports) = &project.duplicate_reports {
+            if !paused && self.duplicate_scans.start(project, reports) {
+                if let Err(e) = crate::duplicate_clusters::report_clusters(
+                    &self.discord,
+                    &self.store,
+                    project,
+                    reports,
+                )
+                .await
+                {
+                    warn!("Failed to report duplicate threads: {}", e);
+                }
+            }
+        }
+
+        if let Err(e) = crate::reward_hooks::deliver_pending(&self.store, project).await {
+            warn!("Failed to deliver reward hooks: {}", e);
+        }
+
+        if let Some(announcements) = &project.milestone_announcements {
+            if !paused && self.budgets.try_take(project, 1) {
+                if let Err(e) = crate::milestones::check_milestones(
+                    github,
+                    &self.discord,
+                    &self.store,
+                    project,
+                    announcements,
+                )
+                .await
+                {
+                    warn!("Failed to check milestones: {}", e);
+                }
+            }
+        }
+
+        if project.release_notifications()
+            && !project.staging()
+            && !paused
+            && self.budgets.try_take(project, 1)
+        {
+            if let Err(e) =
+                crate::releases::announce_releases(github, &self.discord, &self.store, project)
+                    .await
+            {
+                warn!("Failed to announce releases: {}", e);
+            }
+        }
+
+        if let Some(label) = &project.discord_post_label {
+            if !paused && !project.staging() && self.budgets.try_take(project, 1) {
+                if let Err(e) = crate::issue_posts::post_labeled_issues(
+                    github,
+                    &self.discord,
+                    &self.store,
+                    &self.audit,
+                    project,
+                    label,
+                )
+                .await
+                {
+                    warn!("Failed to create forum posts for labeled issues: {}", e);
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -142,24 +659,48 @@ impl IssueSyncer {
         // doesn't support regex patterns for numbers in brackets
         let query = format!("repo:{owner}/{repo} is:{state} in:title");
 
-        let page = github
-            .search()
-            .issues_and_pull_requests(&query)
-            .send()
+        // Sent raw so a rate limit's Retry-After and reset headers can be read;
+        // octocrab's typed errors drop them. This is the first call of each
+        // project's cycle, so it's where limits usually show.
+        let url = reqwest::Url::parse_with_params(
+            "https://api.github.com/search/issues",
+            &[("q", &query)],
+        )?;
+        let response = github
+            ._get(format!(
+                "/search/issues?{}",
+                url.query().unwrap_or_default()
+            ))
             .await
             .map_err(|e| {
                 error!("GitHub API search failed for query '{}': {:?}", query, e);
                 e
             })?;
+        if let Some(wait) = crate::sync_schedule::retry_after(
+            response.status(),
+            response.headers(),
+            chrono::Utc::now().timestamp(),
+        ) {
+            return Err(RateLimited(wait).into());
+        }
+        let status = response.status();
+        let body = github.body_to_string(response).await?;
+        if !status.is_success() {
+            error!(
+                "GitHub API search failed for query '{}': {} {}",
+                query, status, body
+            );
+            anyhow::bail!("GitHub search failed with {status}");
+        }
+        #[derive(serde::Deserialize)]
+        struct SearchResults {
+            items: Vec<octocrab::models::issues::Issue>,
+        }
+        let page: SearchResults = serde_json::from_str(&body)?;
 
-        // Filter to only issues with thread IDs
-        let issues_with_thread_ids: Vec<_> = page
-            .items
-            .into_iter()
-            .filter(|issue| extract_thread_id(&issue.title).is_some())
-            .collect();
-
-        Ok(issues_with_thread_ids)
+        // Callers keep only issues with linked threads, which depends on the
+        // project's mapping mode
+        Ok(page.items)
     }
 
     async fn sync_open_issue(
@@ -167,13 +708,16 @@ impl IssueSyncer {
         project: &Project,
         thread_id: u64,
         issue: &octocrab::models::issues::Issue,
+        paused: bool,
     ) -> Result<bool> {
         let channel_id = ChannelId::new(thread_id);
         let _guild_id = GuildId::new(project.discord_guild_id.parse()?);
 
         // Get thread info
+        let drift = crate::drift::DriftCondition::missing_thread(thread_id, issue.number);
         match self.discord.get_channel(channel_id).await {
             Ok(channel) => {
+                crate::drift::clear(&self.store, &self.audit, project, &drift).await?;
                 if let Some(thread) = channel.guild() {
                     if thread.kind == ChannelType::PublicThread {
                         // Check if thread is locked or archived
@@ -181,7 +725,28 @@ impl IssueSyncer {
                         let is_locked = metadata.map(|m| m.locked).unwrap_or(false);
                         let is_archived = metadata.map(|m| m.archived).unwrap_or(false);
 
-                        if is_locked || is_archived {
+                        let moderator = if is_locked || is_archived {
+                            self.store.moderator_action(thread_id)?
+                        } else {
+                            None
+                        };
+
+                        if let Some(action) = moderator {
+                            debug!(
+                                "Leaving thread {} {} by {} for open issue #{}",
+                                thread_id, action.action, action.moderator, issue.number
+                            );
+                        } else if (is_locked || is_archived) && paused {
+                            info!(
+                                "Would unlock thread {} for reopened issue #{} (paused)",
+                                thread_id, issue.number
+                            );
+                        } else if (is_locked || is_archived) && project.staging() {
+                            debug!(
+                                "Would unlock thread {} for reopened issue #{} (staging)",
+                                thread_id, issue.number
+                            );
+                        } else if is_locked || is_archived {
                             // Post update message first (before unlocking)
                             channel_id
                                 .send_message(
@@ -205,19 +770,126 @@ impl IssueSyncer {
                                 "Unlocked and unarchived thread {} for reopened issue #{}",
                                 thread_id, issue.number
                             );
+                            self.audit
+                                .record(
+                                    AuditEntry::new(project, AuditAction::ThreadUnlocked)
+                                        .thread(thread_id)
+                                        .issue(issue.number)
+                                        .details("issue reopened on GitHub"),
+                                )
+                                .await;
                         }
                     }
                 }
                 Ok(true) // Thread exists
             }
             Err(e) => {
+                // Logged once, not every cycle; see drift.rs
+                crate::drift::report(
+                    &self.store,
+                    &self.audit,
+                    project,
+                    &drift,
+                    &format!("thread not found ({e}); GitHub issue: {}", issue.html_url),
+                )
+                .await?;
+                Ok(false) // Thread doesn't exist
+            }
+        }
+    }
+
+    /// Bring an open issue's thread up to date on Discord; false when the
+    /// thread is gone
+    async fn refresh_open_thread(
+        &self,
+        project: &Project,
+        thread_id: u64,
+        issue: &octocrab::models::issues::Issue,
+        paused: bool,
+    ) -> bool {
+        match self
+            .sync_open_issue(project, thread_id, issue, paused)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => return false,
+            Err(e) => {
+                warn!("Failed to sync open issue #{}: {}", issue.number, e);
+                return false;
+            }
+        }
+
+        if let Err(e) = self.store.upsert_thread_issue(
+            thread_id,
+            &crate::audit::project_key(project),
+            issue.number,
+        ) {
+            warn!(
+                "Failed to record mapping for issue #{}: {}",
+                issue.number, e
+            );
+        }
+
+        // Production keeps the shared threads up to date
+        if project.staging() {
+            return true;
+        }
+
+        let summary = crate::summary::IssueSummary::from_issue(issue);
+        if project.pinned_summary() && !paused {
+            if let Err(e) =
+                crate::summary::update_summary(&self.discord, &self.store, thread_id, &summary)
+                    .await
+            {
                 warn!(
-                    "Thread {} not found: {} - GitHub issue: https://github.com/{}/{}/issues/{}",
-                    thread_id, e, project.github_owner, project.github_repo, issue.number
+                    "Failed to update summary for issue #{}: {}",
+                    issue.number, e
                 );
-                Ok(false) // Thread doesn't exist
             }
         }
+
+        if let Some(tags) = project.status_tags.as_ref().filter(|_| !paused) {
+            if let Err(e) =
+                crate::status_tags::update_tags(&self.discord, project, tags, thread_id, &summary)
+                    .await
+            {
+                warn!(
+                    "Failed to update status tag for issue #{}: {}",
+                    issue.number, e
+                );
+            }
+        }
+
+        if !paused {
+            if let Err(e) = crate::needs_info::check_label(
+                &self.discord,
+                &self.store,
+                project,
+                issue,
+                thread_id,
+            )
+            .await
+            {
+                warn!(
+                    "Failed to post needs-info questions for issue #{}: {}",
+                    issue.number, e
+                );
+            }
+        }
+
+        if project.assignment_updates() && !paused {
+            if let Err(e) =
+                crate::assignments::check_assignees(&self.discord, &self.store, issue, thread_id)
+                    .await
+            {
+                warn!(
+                    "Failed to post assignment update for issue #{}: {}",
+                    issue.number, e
+                );
+            }
+        }
+
+        true
     }
 
     async fn sync_discord_threads(
@@ -225,142 +897,284 @@ impl IssueSyncer {
         project: &Project,
         open_thread_ids: &HashSet<u64>,
         github: &Arc<Octocrab>,
+        paused: bool,
     ) -> Result<()> {
         let guild_id = GuildId::new(project.discord_guild_id.parse()?);
         let forum_id = ChannelId::new(project.discord_forum_id.parse()?);
 
+        let base = self.config.find_project(guild_id.get(), forum_id.get());
+        let base_key = base.map(project_key);
+        let base_key = base_key.as_deref();
+        // Every route of the forum sees all its threads; each is checked by
+        // the one route it belongs to
+        let route_keys: Vec<String> = base
+            .map(|b| b.routes().iter().map(project_key).collect())
+            .unwrap_or_default();
+        let route_keys = &route_keys;
+
         // Get all active threads in the guild
         let active_threads = guild_id.get_active_threads(&self.discord).await?;
 
-        // Process active threads to find ones that might need to be locked
-        for thread in active_threads.threads {
-            // Only process threads in our forum
-            if thread.parent_id != Some(forum_id) {
-                continue;
+        // Threads are independent, so a few are checked at once; serenity
+        // queues each request behind its rate limit bucket
+        let concurrency = self.config.sync_config().concurrency();
+        let scans: Vec<_> = active_threads
+            .threads
+            .iter()
+            .filter(|thread| {
+                thread.parent_id == Some(forum_id)
+                    || (project.text_channel_threads()
+                        && self.is_filed_channel_thread(thread, base_key))
+            })
+            .filter(|thread| {
+                base.is_none_or(|base| {
+                    self.thread_route(base, route_keys, thread) == project_key(project)
+                })
+            })
+            .map(|thread| async move {
+                match self
+                    .check_forum_thread(project, thread, open_thread_ids, paused, base_key)
+                    .await
+                {
+                    Ok(check) => check,
+                    Err(e) => {
+                        warn!("Failed to check thread {}: {}", thread.id, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        let checks: Vec<ClosureCheck> = stream::iter(scans)
+            .buffer_unordered(concurrency)
+            .filter_map(|check| async move { check })
+            .collect()
+            .await;
+
+        // Linked issues are looked up a batch per request, by repository
+        let mut by_repo: BTreeMap<String, Vec<ClosureCheck>> = BTreeMap::new();
+        for check in checks {
+            let key = match &check.moved {
+                Some((target, _)) => project_key(target),
+                None => project_key(project),
+            };
+            by_repo.entry(key).or_default().push(check);
+        }
+        let mut closed = Vec::new();
+        for checks in by_repo.values() {
+            let (repo_project, client) = match &checks[0].moved {
+                Some((target, client)) => (target, client),
+                None => (project, &**github),
+            };
+            for batch in checks.chunks(crate::constants::SYNC_ISSUE_BATCH_SIZE) {
+                if !self.budgets.try_take(repo_project, 1) {
+                    debug!(
+                        "Deferring closure checks of {} issues to the next cycle",
+                        batch.len()
+                    );
+                    continue;
+                }
+                let numbers: Vec<u64> = batch.iter().map(|c| c.issue_number).collect();
+                let issues =
+                    match crate::graphql::issue_summaries(client, repo_project, &numbers).await {
+                        Ok(issues) => issues,
+                        Err(e) => {
+                            warn!("Failed to check status of {} issues: {}", numbers.len(), e);
+                            continue;
+                        }
+                    };
+                for check in batch {
+                    match issues.get(&check.issue_number) {
+                        Some(issue) if !issue.open => {
+                            closed.push((repo_project, check, issue.clone()))
+                        }
+                        Some(_) => {}
+                        None => warn!(
+                            "Issue #{} of thread {} not found",
+                            check.issue_number, check.thread.id
+                        ),
+                    }
+                }
             }
+        }
 
-            // Only check threads with valid prefixes
-            let thread_name = &thread.name;
-            let has_valid_prefix = crate::constants::THREAD_PREFIXES
-                .iter()
-                .any(|prefix| thread_name.starts_with(prefix));
+        let closures: Vec<_> = closed
+            .iter()
+            .map(|(repo_project, check, issue)| async move {
+                if let Err(e) = self
+                    .close_resolved_thread(repo_project, check, issue, paused)
+                    .await
+                {
+                    warn!("Failed to archive thread {}: {}", check.thread.id, e);
+                }
+            })
+            .collect();
+        stream::iter(closures)
+            .buffer_unordered(concurrency)
+            .collect::<Vec<()>>()
+            .await;
 
-            if !has_valid_prefix {
-                continue;
-            }
+        Ok(())
+    }
 
-            // Skip already archived/locked threads
-            let metadata = thread.thread_metadata.as_ref();
-            let is_archived = metadata.map(|m| m.archived).unwrap_or(false);
-            let is_locked = metadata.map(|m| m.locked).unwrap_or(false);
+    /// A text channel thread filed by the project whose forum project key is
+    /// `base_key`. Unfiled ones are left to the channel they were opened in.
+    fn is_filed_channel_thread(&self, thread: &GuildChannel, base_key: Option<&str>) -> bool {
+        let Some(parent_id) = thread.parent_id else {
+            return false;
+        };
+        let guild_id = thread.guild_id.get();
+        if thread.kind != ChannelType::PublicThread
+            || self
+                .config
+                .find_project(guild_id, parent_id.get())
+                .is_some()
+        {
+            return false;
+        }
+        let owner = self
+            .config
+            .find_thread_project(guild_id, parent_id.get())
+            .map(project_key);
+        owner.is_some()
+            && owner.as_deref() == base_key
+            && matches!(self.store.thread_issue(thread.id.get()), Ok(Some(_)))
+    }
 
-            if is_archived || is_locked {
-                continue;
+    /// Key of the route that checks a thread: the repository its issue is
+    /// linked in, or for unlinked threads the one its prefix routes to.
+    /// Threads moved out of the forum's repositories stay with the forum's
+    /// own project.
+    fn thread_route(&self, base: &Project, route_keys: &[String], thread: &GuildChannel) -> String {
+        let linked = match self.store.thread_issue(thread.id.get()) {
+            Ok(linked) => linked,
+            Err(e) => {
+                warn!("Failed to look up the issue of thread {}: {}", thread.id, e);
+                None
             }
+        };
+        match linked {
+            Some(linked) => route_keys
+                .iter()
+                .find(|key| key.eq_ignore_ascii_case(&linked.project))
+                .cloned()
+                .unwrap_or_else(|| project_key(base)),
+            None => project_key(&base.routed(&thread.name)),
+        }
+    }
 
-            let thread_id = thread.id.get();
+    /// Find the issue a forum thread links to, for the batched closure
+    /// check, or give an unlinked thread to auto-create and the janitor
+    async fn check_forum_thread<'a>(
+        &self,
+        project: &Project,
+        thread: &'a GuildChannel,
+        open_thread_ids: &HashSet<u64>,
+        paused: bool,
+        base_key: Option<&str>,
+    ) -> Result<Option<ClosureCheck<'a>>> {
+        // Only check threads with valid prefixes
+        let thread_name = &thread.name;
+        let has_valid_prefix = project.has_valid_prefix(thread_name);
 
-            // If this thread has an open issue, skip it (it should stay unlocked)
-            if open_thread_ids.contains(&thread_id) {
-                continue;
+        if !has_valid_prefix {
+            return Ok(None);
+        }
+        if !paused {
+            if let Err(e) =
+                crate::prefix::normalize_thread_name(&self.discord, project, thread, &self.audit)
+                    .await
+            {
+                warn!("Failed to rename thread {}: {}", thread.id, e);
             }
+        }
 
-            debug!(
-                "Checking thread {} ({}) for closure",
-                thread_id, thread_name
-            );
+        // Skip already archived/locked threads
+        let metadata = thread.thread_metadata.as_ref();
+        let is_archived = metadata.map(|m| m.archived).unwrap_or(false);
+        let is_locked = metadata.map(|m| m.locked).unwrap_or(false);
 
-            // Check if CardiBot created an issue for this thread
-            let messages = thread
-                .id
-                .messages(
-                    &self.discord,
-                    serenity::builder::GetMessages::new()
-                        .limit(crate::constants::DISCORD_MESSAGE_FETCH_LIMIT),
-                )
-                .await?;
+        if is_archived || is_locked {
+            return Ok(None);
+        }
 
-            // Look for CardiBot's issue creation message (in embeds)
-            let mut github_issue_url = None;
-            for msg in &messages {
-                if msg.author.bot {
-                    for embed in &msg.embeds {
-                        if embed.title.as_deref() == Some(crate::constants::MSG_ISSUE_CREATED)
-                            || embed.title.as_deref() == Some(crate::constants::MSG_ISSUE_UPDATED)
-                        {
-                            // Extract issue URL from embed description
-                            if let Some(desc) = &embed.description {
-                                if let Some(url_start) = desc.find("https://github.com/") {
-                                    let url_part = &desc[url_start..];
-                                    if let Some(url_end) =
-                                        url_part.find(|c: char| c.is_whitespace())
-                                    {
-                                        github_issue_url = Some(url_part[..url_end].to_string());
-                                    } else {
-                                        github_issue_url = Some(url_part.to_string());
-                                    }
-                                    info!(
-                                        "Found GitHub issue URL in thread {}: {}",
-                                        thread_id,
-                                        github_issue_url.as_ref().unwrap()
-                                    );
-                                    break;
-                                }
-                            }
-                        }
+        let thread_id = thread.id.get();
+
+        // If this thread has an open issue, skip it (it should stay unlocked)
+        if open_thread_ids.contains(&thread_id) {
+            return Ok(None);
+        }
+
+        // A thread whose issue lives in another repository is checked
+        // there: by that route when it serves this forum too, otherwise by
+        // the forum's own project alone
+        let moved = crate::move_issue::moved_to(&self.config, &self.store, project, thread_id)?;
+        let moved = match moved {
+            Some(target) if target.discord_forum_id == project.discord_forum_id => return Ok(None),
+            Some(_) if base_key != Some(project_key(project).as_str()) => return Ok(None),
+            moved => moved,
+        };
+        let moved_github = match &moved {
+            Some(target) => {
+                match crate::github_app::create_project_client(&self.config, target).await {
+                    Ok(client) => Some(client),
+                    Err(e) => {
+                        warn!("Failed to check moved thread {}: {}", thread_id, e);
+                        return Ok(None);
                     }
                 }
             }
+            None => None,
+        };
+        let project = moved.as_ref().unwrap_or(project);
+
+        debug!(
+            "Checking thread {} ({}) for closure",
+            thread_id, thread_name
+        );
+
+        // Check if CardiBot created an issue for this thread
+        let messages = thread
+            .id
+            .messages(
+                &self.discord,
+                serenity::builder::GetMessages::new()
+                    .limit(crate::constants::DISCORD_MESSAGE_FETCH_LIMIT),
+            )
+            .await?;
 
-            if let Some(issue_url) = github_issue_url {
-                // Extract issue number from URL
-                if let Some(issue_num_str) = issue_url.split('/').next_back() {
-                    if let Ok(issue_number) = issue_num_str.parse::<u64>() {
-                        // Check if this issue is still open
-                        match github
-                            .issues(&project.github_owner, &project.github_repo)
-                            .get(issue_number)
-                            .await
-                        {
-                            Ok(issue) => {
-                                if matches!(issue.state, octocrab::models::IssueState::Closed) {
-                                    info!(
-                                        "Thread {} has closed issue #{}, archiving",
-                                        thread_id, issue_number
-                                    );
-
-                                    // Post closure message
-                                    thread
-                                        .id
-                                        .send_message(
-                                            &self.discord,
-                                            serenity::builder::CreateMessage::new()
-                                                .content(crate::constants::MSG_ISSUE_CLOSED),
-                                        )
-                                        .await?;
-
-                                    // Lock and archive the thread
-                                    thread
-                                        .id
-                                        .edit_thread(
-                                            &self.discord,
-                                            serenity::builder::EditThread::new()
-                                                .locked(true)
-                                                .archived(true),
-                                        )
-                                        .await?;
-
-                                    info!(
-                                        "Locked and archived thread {} - issue #{} is closed",
-                                        thread_id, issue_number
-                                    );
+        // Look for CardiBot's issue creation message (in embeds)
+        let mut github_issue_url = None;
+        for msg in &messages {
+            if msg.author.bot {
+                for embed in &msg.embeds {
+                    if embed.title.as_deref() == Some(crate::constants::MSG_ISSUE_CREATED)
+                        || embed.title.as_deref() == Some(crate::constants::MSG_ISSUE_UPDATED)
+                        || embed.title.as_deref() == Some(crate::constants::MSG_ISSUE_LINKED)
+                        || embed.title.as_deref() == Some(crate::constants::MSG_ISSUE_MOVED)
+                    {
+                        // Extract issue URL from embed description; links
+                        // into other repositories belong to other routes
+                        if let Some(desc) = &embed.description {
+                            let repo_url = format!(
+                                "{}/{}/{}/",
+                                project.github_web_url(),
+                                project.github_owner,
+                                project.github_repo
+                            )
+                            .to_ascii_lowercase();
+                            if let Some(url_start) = desc.to_ascii_lowercase().find(&repo_url) {
+                                let url_part = &desc[url_start..];
+                                if let Some(url_end) = url_part.find(|c: char| c.is_whitespace()) {
+                                    github_issue_url = Some(url_part[..url_end].to_string());
+                                } else {
+                                    github_issue_url = Some(url_part.to_string());
                                 }
-                            }
-                            Err(e) => {
-                                warn!(
-                                    "Failed to check issue status for thread {}: {}",
-                                    thread_id, e
+                                info!(
+                                    "Found GitHub issue URL in thread {}: {}",
+                                    thread_id,
+                                    github_issue_url.as_ref().unwrap()
                                 );
+                                break;
                             }
                         }
                     }
@@ -368,14 +1182,215 @@ impl IssueSyncer {
             }
         }
 
+        if let Some(issue_url) = github_issue_url {
+            // Extract issue number from URL
+            if let Some(issue_num_str) = issue_url.split('/').next_back() {
+                if let Ok(issue_number) = issue_num_str.parse::<u64>() {
+                    return Ok(Some(ClosureCheck {
+                        thread,
+                        moved: moved.zip(moved_github),
+                        issue_number,
+                        messages,
+                    }));
+                }
+            }
+        } else {
+            if let Err(e) = crate::auto_create::handle_unlinked_thread(
+                &self.discord,
+                &self.config,
+                project,
+                thread,
+                &messages,
+                &self.store,
+                &self.audit,
+                paused,
+            )
+            .await
+            {
+                warn!(
+                    "Failed to auto-create issue for thread {}: {}",
+                    thread_id, e
+                );
+            }
+            if let Err(e) = crate::janitor::archive_if_abandoned(
+                &self.discord,
+                project,
+                thread,
+                &messages,
+                &self.store,
+                &self.audit,
+                paused,
+            )
+            .await
+            {
+                warn!("Failed to archive abandoned thread {}: {}", thread_id, e);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Archive a forum thread whose linked issue was closed, unless a fix
+    /// still waits for the reporter to confirm it
+    async fn close_resolved_thread(
+        &self,
+        project: &Project,
+        check: &ClosureCheck<'_>,
+        issue: &IssueSummary,
+        paused: bool,
+    ) -> Result<()> {
+        let thread_id = check.thread.id.get();
+        let issue_number = check.issue_number;
+
+        if paused {
+            info!(
+                "Would archive thread {} for closed issue #{} (paused)",
+                thread_id, issue_number
+            );
+            return Ok(());
+        }
+        // Threads stay open, so closing would repeat every cycle
+        if project.staging() {
+            debug!(
+                "Would archive thread {} for closed issue #{} (staging)",
+                thread_id, issue_number
+            );
+            return Ok(());
+        }
+
+        if issue.completed {
+            if let Err(e) = self.store.mark_contribution_fixed(thread_id) {
+                warn!("Failed to record the fix of issue #{}: {}", issue_number, e);
+            }
+            if let Err(e) =
+                crate::reward_hooks::queue_completed(&self.store, project, check.thread, issue)
+            {
+                warn!(
+                    "Failed to queue the reward hook for issue #{}: {}",
+                    issue_number, e
+                );
+            }
+        }
+
+        // Last refresh before the thread is archived
+        if let Some(tags) = &project.status_tags {
+            if let Err(e) =
+                crate::status_tags::update_tags(&self.discord, project, tags, thread_id, issue)
+                    .await
+            {
+                warn!(
+                    "Failed to update status tag for issue #{}: {}",
+                    issue_number, e
+                );
+            }
+        }
+        if project.pinned_summary() {
+            if let Err(e) =
+                crate::summary::update_summary(&self.discord, &self.store, thread_id, issue).await
+            {
+                warn!(
+                    "Failed to update summary for issue #{}: {}",
+                    issue_number, e
+                );
+            }
+        }
+
+        // Only fixes need verifying, not "not planned" or duplicates
+        if project.verify_fix()
+            && issue.completed
+            && !crate::verify_fix::should_close(
+                &self.discord,
+                project,
+                check.thread.id,
+                issue_number,
+                &check.messages,
+            )
+            .await?
+        {
+            return Ok(());
+        }
+
+        info!(
+            "Thread {} has closed issue #{}, archiving",
+            thread_id, issue_number
+        );
+
+        close_thread(&self.discord, project, check.thread.id).await?;
+
+        info!(
+            "Locked and archived thread {} - issue #{} is closed",
+            thread_id, issue_number
+        );
+        self.audit
+            .record(
+                AuditEntry::new(project, AuditAction::ThreadLocked)
+                    .thread(thread_id)
+                    .issue(issue_number)
+                    .details("issue closed on GitHub"),
+            )
+            .await;
+
         Ok(())
     }
 }
 
+/// Post the closure message, then lock and archive the thread. Threads of a
+/// staging project are left as they are.
+pub async fn close_thread(discord: &Http, project: &Project, thread_id: ChannelId) -> Result<()> {
+    if project.staging() {
+        info!("Left thread {} open (staging)", thread_id);
+        return Ok(());
+    }
+    thread_id
+        .send_message(
+            discord,
+            serenity::builder::CreateMessage::new().content(crate::constants::MSG_ISSUE_CLOSED),
+        )
+        .await?;
+
+    thread_id
+        .edit_thread(
+            discord,
+            serenity::builder::EditThread::new()
+                .locked(true)
+                .archived(true),
+        )
+        .await?;
+    crate::discord_cache::invalidate_channel(thread_id);
+
+    Ok(())
+}
+
+/// Thread ID an issue was created for: the hidden body marker, falling back
+/// to the title suffix used by older issues
+pub fn issue_thread_id(issue: &octocrab::models::issues::Issue) -> Option<u64> {
+    issue
+        .body
+        .as_deref()
+        .and_then(extract_marker_thread_id)
+        .or_else(|| extract_thread_id(&issue.title))
+}
+
+/// Hidden marker embedded in issue bodies to link them to their thread
+pub fn thread_marker(thread_id: u64) -> String {
+    format!("<!-- cardibot:thread={thread_id} -->")
+}
+
+pub fn extract_marker_thread_id(body: &str) -> Option<u64> {
+    let re = Regex::new(r"<!--\s*cardibot:thread=(\d+)\s*-->").ok()?;
+    re.captures(body)?.get(1)?.as_str().parse::<u64>().ok()
+}
+
 pub fn extract_thread_id(title: &str) -> Option<u64> {
-    // Extract thread ID from title format: "Title [1234567890]"
+    // Extract thread ID from title format: "Title [1234567890]". The bot appends
+    // the ID last, so prefer the trailing bracket over tags maintainers add.
     let re = Regex::new(r"\[(\d+)\]").ok()?;
-    re.captures(title)?.get(1)?.as_str().parse::<u64>().ok()
+    re.captures_iter(title)
+        .last()?
+        .get(1)?
+        .as_str()
+        .parse::<u64>()
+        .ok()
 }
 
 #[cfg(test)]
@@ -394,5 +1409,16 @@ mod tests {
         );
         assert_eq!(extract_thread_id("No thread ID here"), None);
         assert_eq!(extract_thread_id("[not-a-number]"), None);
+        assert_eq!(
+            extract_thread_id("[2024] [v3] Crash on start [1234567890]"),
+            Some(1234567890)
+        );
+    }
+
+    #[test]
+    fn test_extract_marker_thread_id() {
+        let body = format!("Some report\n\n{}", thread_marker(1234567890));
+        assert_eq!(extract_marker_thread_id(&body), Some(1234567890));
+        assert_eq!(extract_marker_thread_id("No marker [42]"), None);
     }
 }
@@ -0,0 +1,177 @@
+use hyper::{HeaderMap, StatusCode};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::SyncConfig;
+
+/// GitHub asked us to slow down; nothing more should be sent for the duration
+#[derive(Debug, thiserror::Error)]
+#[error("GitHub rate limit reached; retrying in {}s", .0.as_secs())]
+pub struct RateLimited(pub Duration);
+
+/// When the next sync cycle and each project within it may start
+pub struct SyncSchedule {
+    interval: Duration,
+    jitter: Duration,
+    stagger: Duration,
+    hold_until: Mutex<Option<Instant>>,
+}
+
+impl SyncSchedule {
+    pub fn new(config: &SyncConfig) -> Self {
+        Self {
+            interval: Duration::from_secs(config.interval_seconds),
+            jitter: Duration::from_secs(config.jitter_seconds.unwrap_or(0)),
+            stagger: Duration::from_secs(config.stagger_seconds.unwrap_or(0)),
+            hold_until: Mutex::new(None),
+        }
+    }
+
+    /// Wait before the next cycle: the rest of the interval since the last one
+    /// started plus random jitter, so instances started together drift apart.
+    /// The first cycle only waits for the jitter.
+    pub fn next_delay(&self, since_last_start: Option<Duration>) -> Duration {
+        let rest = since_last_start.map_or(Duration::ZERO, |elapsed| {
+            self.interval.saturating_sub(elapsed)
+        });
+        rest + random_up_to(self.jitter)
+    }
+
+    /// Pause between two projects of the same cycle
+    pub fn stagger(&self) -> Duration {
+        self.stagger
+    }
+
+    /// Stop syncing until `wait` has passed
+    pub fn hold(&self, wait: Duration) {
+        let until = Instant::now() + wait;
+        let mut hold = self.hold_until.lock().unwrap_or_else(|e| e.into_inner());
+        if hold.is_none_or(|current| current < until) {
+            *hold = Some(until);
+        }
+    }
+
+    /// Time left on a rate limit hold, if any
+    pub fn held_for(&self) -> Option<Duration> {
+        let mut hold = self.hold_until.lock().unwrap_or_else(|e| e.into_inner());
+        let remaining = hold.map(|until| until.saturating_duration_since(Instant::now()));
+        match remaining {
+            Some(remaining) if !remaining.is_zero() => Some(remaining),
+            _ => {
+                *hold = None;
+                None
+            }
+        }
+    }
+}
+
+fn random_up_to(max: Duration) -> Duration {
+    if max.is_zero() {
+        return Duration::ZERO;
+    }
+    let mut bytes = [0u8; 8];
+    if SystemRandom::new().fill(&mut bytes).is_err() {
+        return Duration::ZERO;
+    }
+    Duration::from_millis(u64::from_le_bytes(bytes) % (max.as_millis() as u64 + 1))
+}
+
+/// How long GitHub wants us to wait after a rate-limited response: its
+/// `Retry-After`, the primary limit's reset time, or a minute for secondary
+/// limits that give neither
+pub fn retry_after(status: StatusCode, headers: &HeaderMap, now_unix: i64) -> Option<Duration> {
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<i64>().ok())
+    };
+
+    if let Some(seconds) = header("retry-after") {
+        return Some(Duration::from_secs(seconds.max(0) as u64));
+    }
+    if header("x-ratelimit-remaining") == Some(0) {
+        if let Some(reset) = header("x-ratelimit-reset") {
+            return Some(Duration::from_secs((reset - now_unix).max(0) as u64));
+        }
+    }
+    // A plain 403 is a permission problem, not a limit
+    (status == StatusCode::TOO_MANY_REQUESTS)
+        .then(|| Duration::from_secs(crate::constants::GITHUB_SECONDARY_LIMIT_WAIT_SECS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, value.parse().unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let now = 1_700_000_000;
+        assert_eq!(
+            retry_after(
+                StatusCode::FORBIDDEN,
+                &headers(&[("retry-after", "30")]),
+                now
+            ),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            retry_after(
+                StatusCode::FORBIDDEN,
+                &headers(&[
+                    ("x-ratelimit-remaining", "0"),
+                    ("x-ratelimit-reset", "1700000090")
+                ]),
+                now
+            ),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(
+            retry_after(StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new(), now),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            retry_after(StatusCode::FORBIDDEN, &HeaderMap::new(), now),
+            None
+        );
+        assert_eq!(
+            retry_after(StatusCode::OK, &headers(&[("retry-after", "5")]), now),
+            None
+        );
+    }
+
+    #[test]
+    fn test_schedule() {
+        let schedule = SyncSchedule::new(&SyncConfig {
+            enabled: true,
+            interval_seconds: 60,
+            jitter_seconds: Some(10),
+            stagger_seconds: None,
+            status_channel_id: None,
+            concurrency: None,
+        });
+        for _ in 0..20 {
+            let delay = schedule.next_delay(Some(Duration::from_secs(15)));
+            assert!(delay >= Duration::from_secs(45) && delay <= Duration::from_secs(55));
+        }
+        assert!(schedule.next_delay(Some(Duration::from_secs(90))) <= Duration::from_secs(10));
+        assert!(schedule.next_delay(None) <= Duration::from_secs(10));
+
+        assert!(schedule.held_for().is_none());
+        schedule.hold(Duration::from_secs(30));
+        schedule.hold(Duration::from_secs(5));
+        assert!(schedule.held_for().unwrap() > Duration::from_secs(20));
+    }
+}
@@ -0,0 +1,197 @@
+use anyhow::Result;
+use serenity::builder::{
+    CreateAllowedMentions, CreateEmbed, CreateMessage, EditMessage, GetMessages,
+};
+use serenity::http::Http;
+use serenity::model::id::{ChannelId, MessageId};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::config::SyncConfig;
+use crate::embeds::Embed;
+
+// Thread changes listed on their own line; other audited actions are summed up
+const THREAD_ACTIONS: [(&str, &str); 3] = [
+    ("thread_locked", "locked"),
+    ("thread_unlocked", "unlocked"),
+    ("thread_archived", "archived"),
+];
+
+/// What one sync cycle did, for the ops channel
+#[derive(Debug, Default)]
+pub struct CycleReport {
+    pub synced: usize,
+    /// Project and error of each failed project
+    pub failed: Vec<(String, String)>,
+    /// Projects skipped while their circuit breaker is open
+    pub skipped: usize,
+    /// Set when the cycle stopped early for the GitHub rate limit
+    pub rate_limited: Option<Duration>,
+    /// Audited bot changes during the cycle, by action
+    pub actions: BTreeMap<String, usize>,
+    /// GitHub account, calls used and limit of its hourly core quota
+    pub github: Vec<(String, usize, usize)>,
+    pub duration: Duration,
+}
+
+impl CycleReport {
+    fn description(&self) -> String {
+        let mut lines = vec![format!(
+            "**Projects**: {} synced, {} failed, {} skipped",
+            self.synced,
+            self.failed.len(),
+            self.skipped
+        )];
+
+        let count = |action: &str| self.actions.get(action).copied().unwrap_or(0);
+        let threads: Vec<String> = THREAD_ACTIONS
+            .iter()
+            .map(|(action, label)| format!("{} {label}", count(action)))
+            .collect();
+        lines.push(format!("**Threads**: {}", threads.join(", ")));
+
+        let other: Vec<String> = self
+            .actions
+            .iter()
+            .filter(|(action, _)| !THREAD_ACTIONS.iter().any(|(a, _)| a == action))
+            .map(|(action, n)| format!("{} {n}", action.replace('_', " ")))
+            .collect();
+        if !other.is_empty() {
+            lines.push(format!("**Other changes**: {}", other.join(", ")));
+        }
+
+        if !self.github.is_empty() {
+            let usage: Vec<String> = self
+                .github
+                .iter()
+                .map(|(account, used, limit)| format!("{account} {used}/{limit}"))
+                .collect();
+            lines.push(format!("**GitHub API this hour**: {}", usage.join(", ")));
+        }
+        if let Some(wait) = self.rate_limited {
+            lines.push(format!(
+                "⏳ Rate limited; sync resumes in {}s",
+                wait.as_secs()
+            ));
+        }
+
+        if !self.failed.is_empty() {
+            lines.push("**Errors**".to_string());
+            for (project, error) in &self.failed {
+                let error: String = error.chars().take(150).collect();
+                lines.push(format!("• `{project}`: {error}"));
+            }
+        }
+        lines.join("\n")
+    }
+
+    pub fn embed(&self) -> CreateEmbed {
+        let color = if self.failed.is_empty() && self.rate_limited.is_none() {
+            crate::constants::COLOR_SUCCESS
+        } else {
+            crate::constants::COLOR_FAILURE
+        };
+        Embed::new()
+            .title(crate::constants::MSG_SYNC_STATUS)
+            .description(self.description())
+            .footer(format!("Cycle took {}s", self.duration.as_secs()))
+            .timestamp(serenity::model::Timestamp::now())
+            .color(color)
+            .build()
+    }
+}
+
+/// The status message in `[sync] status_channel_id`, edited after every cycle
+pub struct StatusMessage {
+    channel: ChannelId,
+    message: Mutex<Option<MessageId>>,
+}
+
+impl StatusMessage {
+    pub fn new(config: &SyncConfig) -> Option<Self> {
+        let channel = config.status_channel_id.as_deref()?.parse().ok()?;
+        Some(Self {
+            channel: ChannelId::new(channel),
+            message: Mutex::new(None),
+        })
+    }
+
+    pub async fn publish(&self, discord: &Http, report: &CycleReport) -> Result<()> {
+        let known = *self.message.lock().unwrap_or_else(|e| e.into_inner());
+        // After a restart, keep editing the message the last run left behind
+        let existing = match known {
+            Some(id) => Some(id),
+            None => self.find_previous(discord).await?,
+        };
+
+        if let Some(id) = existing {
+            let edited = self
+                .channel
+                .edit_message(discord, id, EditMessage::new().embed(report.embed()))
+                .await;
+            if edited.is_ok() {
+                *self.message.lock().unwrap_or_else(|e| e.into_inner()) = Some(id);
+                return Ok(());
+            }
+        }
+
+        let message = self
+            .channel
+            .send_message(
+                discord,
+                CreateMessage::new()
+                    .embed(report.embed())
+                    .allowed_mentions(CreateAllowedMentions::new()),
+            )
+            .await?;
+        *self.message.lock().unwrap_or_else(|e| e.into_inner()) = Some(message.id);
+        Ok(())
+    }
+
+    async fn find_previous(&self, discord: &Http) -> Result<Option<MessageId>> {
+        let messages = self
+            .channel
+            .messages(discord, GetMessages::new().limit(20))
+            .await?;
+        Ok(messages
+            .iter()
+            .find(|m| {
+                m.author.bot
+                    && m.embeds
+                        .iter()
+                        .any(|e| e.title.as_deref() == Some(crate::constants::MSG_SYNC_STATUS))
+            })
+            .map(|m| m.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_description() {
+        let report = CycleReport {
+            synced: 2,
+            failed: vec![("org/web".to_string(), "GitHub search failed".to_string())],
+            skipped: 1,
+            rate_limited: None,
+            actions: BTreeMap::from([
+                ("thread_locked".to_string(), 3),
+                ("issue_closed".to_string(), 1),
+            ]),
+            github: vec![("default".to_string(), 120, 5000)],
+            duration: Duration::from_secs(4),
+        };
+        assert_eq!(
+            report.description(),
+            "**Projects**: 2 synced, 1 failed, 1 skipped\n\
+             **Threads**: 3 locked, 0 unlocked, 0 archived\n\
+             **Other changes**: issue closed 1\n\
+             **GitHub API this hour**: default 120/5000\n\
+             **Errors**\n\
+             • `org/web`: GitHub search failed"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use serenity::http::Http;
+use serenity::model::channel::GuildChannel;
+use std::collections::HashMap;
+
+use crate::config::Project;
+
+/// `cc @org/frontend` line for the GitHub teams mapped to the thread's forum
+/// tags or title prefix, or None when nothing maps
+pub async fn thread_teams_line(
+    discord: &Http,
+    project: &Project,
+    thread: &GuildChannel,
+) -> Option<String> {
+    let mapping = project.team_mentions.as_ref()?;
+
+    let mut components = Vec::new();
+    if let Some(prefix) = project.prefix_for_title(&thread.name) {
+        components.push(prefix.tag_name());
+        components.push(prefix.prefix);
+    }
+    if !thread.applied_tags.is_empty() {
+        if let Some(forum) = thread.parent_id {
+            match crate::discord_cache::channel(discord, forum).await {
+                Ok(channel) => {
+                    if let Some(forum) = channel.guild() {
+                        components.extend(
+                            forum
+                                .available_tags
+                                .iter()
+                                .filter(|t| thread.applied_tags.contains(&t.id))
+                                .map(|t| t.name.clone()),
+                        );
+                    }
+                }
+                Err(e) => tracing::debug!("Could not fetch forum tags for {}: {}", thread.id, e),
+            }
+        }
+    }
+
+    let teams = teams_for(mapping, &components);
+    (!teams.is_empty()).then(|| format!("cc {}", teams.join(" ")))
+}
+
+/// Teams for any of the components (tag names or prefixes), matched without
+/// regard to case, each listed once
+fn teams_for(mapping: &HashMap<String, String>, components: &[String]) -> Vec<String> {
+    let mut teams: Vec<String> = Vec::new();
+    for component in components {
+        let team = mapping
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(component))
+            .map(|(_, team)| {
+                if team.starts_with('@') {
+                    team.clone()
+                } else {
+                    format!("@{team}")
+                }
+            });
+        if let Some(team) = team {
+            if !teams.contains(&team) {
+                teams.push(team);
+            }
+        }
+    }
+    teams
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_teams_for() {
+        let mapping = HashMap::from([
+            ("Frontend".to_string(), "@acme/frontend".to_string()),
+            ("BUG".to_string(), "acme/triage".to_string()),
+            ("Wallet".to_string(), "@acme/frontend".to_string()),
+        ]);
+        let components = ["BUG", "[BUG]", "frontend", "wallet", "Docs"].map(String::from);
+        assert_eq!(
+            teams_for(&mapping, &components),
+            vec!["@acme/triage", "@acme/frontend"]
+        );
+        assert!(teams_for(&mapping, &["Docs".to_string()]).is_empty());
+    }
+}
@@ -0,0 +1,187 @@
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[derive(Debug, Deserialize)]
+pub struct Update {
+    pub update_id: i64,
+    pub message: Option<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Message {
+    pub message_id: i64,
+    /// The forum topic of the message when `is_topic_message` is set;
+    /// otherwise it may be a reply thread
+    pub message_thread_id: Option<i64>,
+    #[serde(default)]
+    pub is_topic_message: bool,
+    pub from: Option<User>,
+    pub chat: Chat,
+    pub text: Option<String>,
+    pub caption: Option<String>,
+    pub forum_topic_created: Option<ForumTopicCreated>,
+    pub forum_topic_edited: Option<ForumTopicEdited>,
+}
+
+impl Message {
+    /// Text of a message, or the caption of a photo or file
+    pub fn content(&self) -> Option<&str> {
+        self.text.as_deref().or(self.caption.as_deref())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct User {
+    pub is_bot: bool,
+    pub first_name: String,
+    pub username: Option<String>,
+}
+
+impl User {
+    pub fn display_name(&self) -> &str {
+        self.username.as_deref().unwrap_or(&self.first_name)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Chat {
+    pub id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForumTopicCreated {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForumTopicEdited {
+    /// None when only the icon changed
+    pub name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    ok: bool,
+    result: Option<T>,
+    description: Option<String>,
+}
+
+/// Bot API client for the bot behind `TELEGRAM_BOT_TOKEN`
+pub struct TelegramClient {
+    token: String,
+    client: reqwest::Client,
+}
+
+impl TelegramClient {
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            token: std::env::var("TELEGRAM_BOT_TOKEN")
+                .context("TELEGRAM_BOT_TOKEN must be set for Telegram projects")?,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// The bot's username
+    pub async fn username(&self) -> Result<String> {
+        let me: Value = self.call("getMe", json!({})).await?;
+        me["username"]
+            .as_str()
+            .map(str::to_string)
+            .context("getMe response has no username")
+    }
+
+    /// Messages since `offset` without waiting for new ones. Updates are
+    /// only kept by Telegram for a day, and only until a later offset is
+    /// requested.
+    pub async fn updates(&self, offset: i64) -> Result<Vec<Update>> {
+        self.call(
+            "getUpdates",
+            json!({
+                "offset": offset,
+                "limit": crate::constants::TELEGRAM_UPDATE_LIMIT,
+                "timeout": 0,
+                "allowed_updates": ["message"],
+            }),
+        )
+        .await
+    }
+
+    pub async fn send_to_topic(&self, chat_id: i64, topic_id: i64, text: &str) -> Result<()> {
+        let _: Value = self
+            .call(
+                "sendMessage",
+                json!({
+                    "chat_id": chat_id,
+                    "message_thread_id": topic_id,
+                    "text": text,
+                }),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Close a topic to new messages; needs the `can_manage_topics` right
+    pub async fn close_topic(&self, chat_id: i64, topic_id: i64) -> Result<()> {
+        let _: bool = self
+            .call(
+                "closeForumTopic",
+                json!({ "chat_id": chat_id, "message_thread_id": topic_id }),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let url = format!(
+            "{}/bot{}/{}",
+            crate::constants::TELEGRAM_API_URL,
+            self.token,
+            method
+        );
+        // Errors come back as JSON with a description, whatever the status.
+        // The URL holds the token, so it is left out of errors.
+        let response: Response<T> = async {
+            self.client
+                .post(url)
+                .json(&params)
+                .send()
+                .await?
+                .json()
+                .await
+        }
+        .await
+        .map_err(|e: reqwest::Error| {
+            anyhow::anyhow!("Telegram {method} failed: {}", e.without_url())
+        })?;
+        match response.result.filter(|_| response.ok) {
+            Some(result) => Ok(result),
+            None => anyhow::bail!(
+                "Telegram {method} failed: {}",
+                response.description.as_deref().unwrap_or("no description")
+            ),
+        }
+    }
+}
+
+/// Link to a topic of a supergroup, which opens for its members
+pub fn topic_url(chat_id: i64, topic_id: i64) -> String {
+    // Supergroup IDs are the internal channel ID with a -100 prefix
+    let channel = chat_id.to_string();
+    let channel = channel.strip_prefix("-100").unwrap_or(&channel);
+    format!("https://t.me/c/{channel}/{topic_id}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topic_url() {
+        assert_eq!(
+            topic_url(-1001234567890, 42),
+            "https://t.me/c/1234567890/42"
+        );
+    }
+}
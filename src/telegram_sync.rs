@@ -0,0 +1,259 @@
+use anyhow::{Context, Result};
+use octocrab::Octocrab;
+use serenity::all::Http;
+use tracing::{info, warn};
+
+use crate::audit::{project_key, AuditAction, AuditEntry, AuditLog};
+use crate::config::{ChatPlatform, Config, Project};
+use crate::rate_budget::RateBudgets;
+use crate::store::{Store, TelegramTopic};
+use crate::telegram::{Message, TelegramClient};
+
+/// Sync a Telegram project's forum topics: file topics named with a prefix
+/// once they have a message, then post the issue's closure in the topic and
+/// close it. Topics are filed into the repository their prefix routes to.
+#[allow(clippy::too_many_arguments)]
+pub async fn sync_chat(
+    discord: &Http,
+    config: &Config,
+    github: &Octocrab,
+    store: &Store,
+    audit: &AuditLog,
+    budgets: &RateBudgets,
+    project: &Project,
+    paused: bool,
+) -> Result<()> {
+    let chat_id = project
+        .telegram_chat_id
+        .context("Project has no telegram_chat_id")?;
+    let telegram = TelegramClient::from_env()?;
+    read_updates(config, store, &telegram).await?;
+
+    let prefixes = project.prefixes();
+    for topic in store.unfiled_telegram_topics(chat_id)? {
+        let Some((_, title)) = crate::prefix::match_prefix(&topic.name, &prefixes) else {
+            continue;
+        };
+        // Topics open with just a title; wait for the report itself
+        let messages = store.telegram_messages(chat_id, topic.topic_id)?;
+        if messages.is_empty() {
+            continue;
+        }
+        if paused {
+            info!("Would file Telegram topic {} (paused)", topic.topic_id);
+            continue;
+        }
+        let route = project.routed(&title);
+        if !budgets.try_take(&route, 1) {
+            info!(
+                "GitHub budget for {} used up; filing the remaining Telegram topics next cycle",
+                project_key(&route)
+            );
+            break;
+        }
+        if let Err(e) = file_topic(
+            discord, &telegram, github, store, audit, &route, chat_id, &topic, &title, &messages,
+        )
+        .await
+        {
+            warn!("Failed to file Telegram topic {}: {}", topic.topic_id, e);
+        }
+    }
+
+    announce_closed_issues(
+        &telegram, github, store, audit, budgets, project, chat_id, paused,
+    )
+    .await
+}
+
+/// Store what the bot received since the last poll. The Bot API can't read
+/// a chat's history, so topics and their messages are kept until filed.
+async fn read_updates(config: &Config, store: &Store, telegram: &TelegramClient) -> Result<()> {
+    loop {
+        let updates = telegram.updates(store.telegram_update_offset()?).await?;
+        let Some(last) = updates.last().map(|u| u.update_id) else {
+            return Ok(());
+        };
+        for message in updates.iter().filter_map(|u| u.message.as_ref()) {
+            if let Err(e) = record_message(config, store, message) {
+                warn!(
+                    "Failed to record Telegram message {}: {}",
+                    message.message_id, e
+                );
+            }
+        }
+        // Confirms the batch; Telegram drops updates before the new offset
+        store.set_telegram_update_offset(last + 1)?;
+        if updates.len() < crate::constants::TELEGRAM_UPDATE_LIMIT {
+            return Ok(());
+        }
+    }
+}
+
+/// Keep topic openings and renames in bridged chats, and messages in topics
+/// named with a prefix that aren't filed yet
+fn record_message(config: &Config, store: &Store, message: &Message) -> Result<()> {
+    let chat_id = message.chat.id;
+    let Some(project) = config
+        .projects
+        .iter()
+        .find(|p| p.platform() == ChatPlatform::Telegram && p.telegram_chat_id == Some(chat_id))
+    else {
+        return Ok(());
+    };
+
+    if let Some(created) = &message.forum_topic_created {
+        let creator = message
+            .from
+            .as_ref()
+            .map_or("unknown", |u| u.display_name());
+        return store.record_telegram_topic(chat_id, message.message_id, &created.name, creator);
+    }
+    let Some(topic_id) = message
+        .message_thread_id
+        .filter(|_| message.is_topic_message)
+    else {
+        return Ok(());
+    };
+    if let Some(name) = message
+        .forum_topic_edited
+        .as_ref()
+        .and_then(|e| e.name.as_deref())
+    {
+        return store.rename_telegram_topic(chat_id, topic_id, name);
+    }
+
+    let (Some(author), Some(text)) = (message.from.as_ref(), message.content()) else {
+        return Ok(());
+    };
+    if author.is_bot {
+        return Ok(());
+    }
+    match store.telegram_topic(chat_id, topic_id)? {
+        Some(topic) if topic.issue_number.is_none() && project.has_valid_prefix(&topic.name) => {
+            store.record_telegram_message(
+                chat_id,
+                message.message_id,
+                topic_id,
+                author.display_name(),
+                text,
+            )
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Create the issue for a topic, then link to it from the topic
+#[allow(clippy::too_many_arguments)]
+async fn file_topic(
+    discord: &Http,
+    telegram: &TelegramClient,
+    github: &Octocrab,
+    store: &Store,
+    audit: &AuditLog,
+    project: &Project,
+    chat_id: i64,
+    topic: &TelegramTopic,
+    title: &str,
+    messages: &[(String, String)],
+) -> Result<()> {
+    let messages: Vec<(&str, &str)> = messages
+        .iter()
+        .map(|(author, text)| (author.as_str(), text.as_str()))
+        .collect();
+    let topic_url = crate::telegram::topic_url(chat_id, topic.topic_id);
+    let issue = crate::bridge::create_issue(
+        discord,
+        github,
+        store,
+        project,
+        title,
+        &topic.creator,
+        &topic_url,
+        &messages,
+    )
+    .await?;
+
+    // Linked before announcing, so a failed message can't file it twice
+    store.link_telegram_topic(chat_id, topic.topic_id, &project_key(project), issue.number)?;
+    info!(
+        "Created issue #{} from Telegram topic {}",
+        issue.number, topic.topic_id
+    );
+    audit
+        .record(
+            AuditEntry::new(project, AuditAction::IssueCreated)
+                .issue(issue.number)
+                .actor(&topic.creator)
+                .details(format!("from Telegram topic {topic_url}")),
+        )
+        .await;
+
+    telegram
+        .send_to_topic(
+            chat_id,
+            topic.topic_id,
+            &format!(
+                "{}: {}",
+                crate::constants::MSG_ISSUE_CREATED,
+                issue.html_url
+            ),
+        )
+        .await
+}
+
+/// Post the closure in each topic whose issue was closed, then close the
+/// topic
+#[allow(clippy::too_many_arguments)]
+async fn announce_closed_issues(
+    telegram: &TelegramClient,
+    github: &Octocrab,
+    store: &Store,
+    audit: &AuditLog,
+    budgets: &RateBudgets,
+    project: &Project,
+    chat_id: i64,
+    paused: bool,
+) -> Result<()> {
+    let links = store.open_telegram_topics(chat_id)?;
+    for (topic_id, route, issue) in
+        crate::bridge::closed_issues(github, budgets, project, links).await?
+    {
+        if paused {
+            info!(
+                "Would close Telegram topic {} for closed issue #{} (paused)",
+                topic_id, issue.number
+            );
+            continue;
+        }
+
+        let text = format!("{}: {}", crate::constants::MSG_ISSUE_CLOSED, issue.url);
+        if let Err(e) = telegram.send_to_topic(chat_id, topic_id, &text).await {
+            warn!(
+                "Failed to announce closed issue #{} in Telegram topic {}: {}",
+                issue.number, topic_id, e
+            );
+            continue;
+        }
+        // The closure message is enough when the bot can't manage topics
+        if let Err(e) = telegram.close_topic(chat_id, topic_id).await {
+            warn!("Failed to close Telegram topic {}: {}", topic_id, e);
+        }
+        store.close_telegram_topic(chat_id, topic_id, chrono::Utc::now().timestamp())?;
+        info!(
+            "Closed Telegram topic {} - issue #{} is closed",
+            topic_id, issue.number
+        );
+        audit
+            .record(
+                AuditEntry::new(&route, AuditAction::ThreadLocked)
+                    .issue(issue.number)
+                    .details(format!(
+                        "issue closed on GitHub; Telegram topic {}",
+                        crate::telegram::topic_url(chat_id, topic_id)
+                    )),
+            )
+            .await;
+    }
+    Ok(())
+}
@@ -0,0 +1,178 @@
+use anyhow::Result;
+use serenity::http::Http;
+use serenity::model::channel::{Channel, ChannelType, GuildChannel};
+use serenity::model::id::ChannelId;
+use tracing::{debug, info, warn};
+
+use crate::audit::{AuditAction, AuditEntry, AuditLog};
+use crate::config::{Config, Project};
+use crate::store::Store;
+
+/// Find the project whose forum owns this thread, or that takes the guild's
+/// text channel threads; archived projects are left alone
+pub fn project_for_thread<'a>(config: &'a Config, thread: &GuildChannel) -> Option<&'a Project> {
+    let parent_id = thread.parent_id?;
+    let project = match thread.kind {
+        ChannelType::PublicThread => {
+            config.find_thread_project(thread.guild_id.get(), parent_id.get())
+        }
+        _ => config.find_project(thread.guild_id.get(), parent_id.get()),
+    };
+    project.filter(|p| !p.archived())
+}
+
+/// Load an interaction's thread and the project that owns it, routed to the
+/// repository of the thread's prefix or, once its issue was moved, to the
+/// repository it was moved to
+pub async fn resolve_thread(
+    http: &Http,
+    channel_id: ChannelId,
+    config: &Config,
+    store: &Store,
+) -> Result<Option<(GuildChannel, Project)>> {
+    let Channel::Guild(thread) = crate::discord_cache::channel(http, channel_id).await? else {
+        return Ok(None);
+    };
+
+    let Some(project) = project_for_thread(config, &thread).map(|p| p.routed(&thread.name)) else {
+        return Ok(None);
+    };
+    let moved = crate::move_issue::moved_to(config, store, &project, thread.id.get())?;
+    Ok(Some((thread, moved.unwrap_or(project))))
+}
+
+pub async fn handle_thread_create(
+    http: &Http,
+    config: &Config,
+    store: &Store,
+    audit: &AuditLog,
+    thread: &GuildChannel,
+) -> Result<()> {
+    let Some(project) = project_for_thread(config, thread) else {
+        return Ok(());
+    };
+
+    if project.has_valid_prefix(&thread.name) {
+        info!(
+            "New thread {} ({}) in project '{}'",
+            thread.id,
+            thread.name,
+            project.name.as_deref().unwrap_or("unnamed")
+        );
+        if project.mutations_paused(chrono::Utc::now()).is_none() {
+            crate::prefix::normalize_thread_name(http, project, thread, audit).await?;
+        }
+        if let Err(e) = crate::onboarding::welcome_reporter(http, store, project, thread).await {
+            warn!(
+                "Failed to send the onboarding message for {}: {}",
+                thread.id, e
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// React to moderator changes on a managed thread without waiting for the next sync cycle
+pub async fn handle_thread_update(
+    http: &Http,
+    config: &Config,
+    store: &Store,
+    audit: &AuditLog,
+    thread_id: ChannelId,
+) -> Result<()> {
+    // Routed, so a prefix filed in another repository or a moved issue is
+    // looked up where it lives
+    let Some((thread, project)) = resolve_thread(http, thread_id, config, store).await? else {
+        return Ok(());
+    };
+    let (thread, project) = (&thread, &project);
+
+    if !project.has_valid_prefix(&thread.name) {
+        return Ok(());
+    }
+
+    let Some(mapping) = store.thread_issue(thread.id.get())? else {
+        debug!("Updated thread {} has no linked issue", thread.id);
+        return Ok(());
+    };
+
+    let github = crate::github_app::create_project_client(config, project).await?;
+    let issues = github.issues(&project.github_owner, &project.github_repo);
+    let issue = issues.get(mapping.issue_number).await?;
+
+    // Keep the issue title in step with thread renames
+    let expected_title = crate::github::issue_title(project, &thread.name, thread.id.get());
+    if issue.title != expected_title {
+        crate::redact::report_title(http, store, project, thread.id.get(), &thread.name).await;
+        issues
+            .update(issue.number)
+            .title(&expected_title)
+            .send()
+            .await?;
+        info!(
+            "Renamed issue #{} to match thread {}",
+            issue.number, thread.id
+        );
+        audit
+            .record(
+                AuditEntry::new(project, AuditAction::IssueRenamed)
+                    .thread(thread.id.get())
+                    .issue(issue.number)
+                    .details(format!("title set to '{expected_title}'")),
+            )
+            .await;
+    }
+
+    // A moderator locking the thread closes the linked issue
+    let is_locked = thread
+        .thread_metadata
+        .as_ref()
+        .map(|m| m.locked)
+        .unwrap_or(false);
+    let is_archived = thread
+        .thread_metadata
+        .as_ref()
+        .map(|m| m.archived)
+        .unwrap_or(false);
+    let is_open = matches!(issue.state, octocrab::models::IssueState::Open);
+
+    // Hand-made locks and archives are remembered so the syncer doesn't undo
+    // them, until someone opens the thread again
+    let moderator = if is_locked || is_archived {
+        crate::moderator_actions::attribute(
+            http, store, audit, project, &github, &issue, thread, is_locked,
+        )
+        .await
+    } else {
+        if store.clear_moderator_action(thread.id.get())? {
+            info!("Thread {} was reopened on Discord", thread.id);
+        }
+        None
+    };
+
+    if is_locked && is_open {
+        issues
+            .create_comment(issue.number, crate::constants::MSG_THREAD_LOCKED_COMMENT)
+            .await?;
+        issues
+            .update(issue.number)
+            .state(octocrab::models::IssueState::Closed)
+            .send()
+            .await?;
+        info!(
+            "Closed issue #{} because thread {} was locked",
+            issue.number, thread.id
+        );
+        let mut entry = AuditEntry::new(project, AuditAction::IssueClosed)
+            .thread(thread.id.get())
+            .issue(issue.number)
+            .details("thread locked on Discord");
+        if let Some(moderator) = &moderator {
+            entry = entry.actor(moderator);
+        }
+        audit.record(entry).await;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,220 @@
+use anyhow::Result;
+use octocrab::models::CommentId;
+use octocrab::Octocrab;
+use serenity::builder::GetMessages;
+use serenity::http::Http;
+use serenity::model::channel::{Channel, Message, ReactionType};
+use serenity::model::id::ChannelId;
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, info};
+
+use crate::config::Project;
+use crate::store::Store;
+
+// Tells the bot's comment apart from what maintainers write
+const STATS_MARKER: &str = "<!-- cardibot:stats -->";
+const TOP_REACTIONS: usize = 5;
+
+/// How busy a thread is, from its most recent messages
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreadStats {
+    pub messages: usize,
+    /// Whether there were more messages than could be fetched
+    pub more: bool,
+    pub participants: usize,
+    /// Total reactions per emoji, most used first
+    pub reactions: Vec<(String, u64)>,
+    /// Unix timestamp of the newest message
+    pub last_activity: Option<i64>,
+}
+
+impl ThreadStats {
+    pub fn from_messages(messages: &[Message], more: bool) -> Self {
+        let human: Vec<&Message> = messages.iter().filter(|m| !m.author.bot).collect();
+        let participants: HashSet<_> = human.iter().map(|m| m.author.id).collect();
+
+        let mut reactions: HashMap<String, u64> = HashMap::new();
+        for reaction in messages.iter().flat_map(|m| &m.reactions) {
+            let emoji = match &reaction.reaction_type {
+                ReactionType::Unicode(emoji) => emoji.clone(),
+                ReactionType::Custom { name, .. } => {
+                    format!(":{}:", name.as_deref().unwrap_or("emoji"))
+                }
+                _ => continue,
+            };
+            *reactions.entry(emoji).or_default() += reaction.count;
+        }
+        let mut reactions: Vec<(String, u64)> = reactions.into_iter().collect();
+        reactions.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        Self {
+            messages: human.len(),
+            more,
+            participants: participants.len(),
+            reactions,
+            last_activity: human.iter().map(|m| m.timestamp.unix_timestamp()).max(),
+        }
+    }
+
+    pub fn comment_body(&self, thread_url: &str) -> String {
+        let reactions = if self.reactions.is_empty() {
+            "none".to_string()
+        } else {
+            self.reactions
+                .iter()
+                .take(TOP_REACTIONS)
+                .map(|(emoji, count)| format!("{emoji} {count}"))
+                .collect::<Vec<_>>()
+                .join(" · ")
+        };
+        let last_activity = self
+            .last_activity
+            .and_then(|at| chrono::DateTime::from_timestamp(at, 0))
+            .map(|at| at.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_else(|| "none".to_string());
+
+        format!(
+            "{STATS_MARKER}\n### 💬 Discord activity\n\n| | |\n|---|---|\n| Participants | {} |\n| Messages | {}{} |\n| Reactions | {} |\n| Last activity | {} |\n\n[Open the thread]({})",
+            self.participants,
+            self.messages,
+            if self.more { "+" } else { "" },
+            reactions,
+            last_activity,
+            thread_url
+        )
+    }
+}
+
+/// Create or refresh the bot's activity comment on a linked issue. Messages
+/// are only counted again once the thread has a new one, and the comment is
+/// only edited when the numbers changed since the last run.
+pub async fn update_stats_comment(
+    github: &Octocrab,
+    discord: &Http,
+    store: &Store,
+    project: &Project,
+    issue_number: u64,
+    thread_id: u64,
+) -> Result<()> {
+    let Channel::Guild(thread) =
+        crate::discord_cache::channel(discord, ChannelId::new(thread_id)).await?
+    else {
+        return Ok(());
+    };
+    // Without replies, the opening post is the last message
+    let last_message = thread
+        .last_message_id
+        .unwrap_or_else(|| crate::discord_cache::starter_id(thread.id))
+        .get();
+    let existing = store.stats_comment(thread_id)?;
+    if existing.is_some() && store.stats_cursor(thread_id)? == Some(last_message) {
+        return Ok(());
+    }
+
+    let limit = crate::constants::DISCORD_MESSAGE_FETCH_LIMIT;
+    let messages = thread
+        .id
+        .messages(discord, GetMessages::new().limit(limit))
+        .await?;
+    let stats = ThreadStats::from_messages(&messages, messages.len() >= limit as usize);
+    let guild_id = project.discord_guild_id.as_str();
+    let body = stats.comment_body(&format!(
+        "https://discord.com/channels/{guild_id}/{thread_id}"
+    ));
+
+    let issues = github.issues(&project.github_owner, &project.github_repo);
+    if let Some((comment_id, previous)) = existing {
+        if previous != body {
+            match issues
+                .update_comment(CommentId(comment_id), body.clone())
+                .await
+            {
+                Ok(_) => {
+                    store.set_stats_comment(thread_id, comment_id, &body)?;
+                    debug!("Refreshed activity comment on issue #{}", issue_number);
+                }
+                // Deleted by a maintainer: post a fresh one
+                Err(e) if crate::error::is_github_not_found(&e) => {
+                    debug!("Activity comment on issue #{} is gone", issue_number);
+                    post_comment(&issues, store, issue_number, thread_id, &body).await?;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    } else {
+        post_comment(&issues, store, issue_number, thread_id, &body).await?;
+    }
+    store.set_stats_cursor(thread_id, last_message)?;
+    Ok(())
+}
+
+async fn post_comment(
+    issues: &octocrab::issues::IssueHandler<'_>,
+    store: &Store,
+    issue_number: u64,
+    thread_id: u64,
+    body: &str,
+) -> Result<()> {
+    let comment = issues.create_comment(issue_number, body).await?;
+    store.set_stats_comment(thread_id, comment.id.0, body)?;
+    info!("Posted activity comment on issue #{}", issue_number);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(author: u64, at: i64, reactions: &[(&str, u64)]) -> Message {
+        serde_json::from_value(serde_json::json!({
+            "id": (at as u64).to_string(),
+            "channel_id": "1",
+            "author": { "id": author.to_string(), "username": "user", "discriminator": "0000", "avatar": null },
+            "content": "",
+            "timestamp": chrono::DateTime::from_timestamp(at, 0).unwrap().to_rfc3339(),
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": false,
+            "mentions": [],
+            "mention_roles": [],
+            "attachments": [],
+            "embeds": [],
+            "pinned": false,
+            "type": 0,
+            "reactions": reactions.iter().map(|(emoji, count)| serde_json::json!({
+                "count": count,
+                "count_details": { "burst": 0, "normal": count },
+                "me": false,
+                "me_burst": false,
+                "emoji": { "id": null, "name": emoji },
+                "burst_colors": []
+            })).collect::<Vec<_>>()
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_thread_stats() {
+        let messages = vec![
+            message(2, 1_700_000_300, &[("👍", 1)]),
+            message(1, 1_700_000_200, &[]),
+            message(1, 1_700_000_100, &[("👍", 4), ("🔥", 2)]),
+        ];
+        let stats = ThreadStats::from_messages(&messages, false);
+        assert_eq!(stats.messages, 3);
+        assert_eq!(stats.participants, 2);
+        assert_eq!(
+            stats.reactions,
+            vec![("👍".to_string(), 5), ("🔥".to_string(), 2)]
+        );
+        assert_eq!(stats.last_activity, Some(1_700_000_300));
+
+        let body = stats.comment_body("https://discord.com/channels/1/2");
+        assert!(body.starts_with(STATS_MARKER));
+        assert!(body.contains("| Reactions | 👍 5 · 🔥 2 |"));
+        assert!(body.contains("| Last activity | 2023-11-14 22:18 UTC |"));
+
+        let more = ThreadStats::from_messages(&messages, true);
+        assert!(more.comment_body("").contains("| Messages | 3+ |"));
+    }
+}
@@ -0,0 +1,313 @@
+use anyhow::{Context, Result};
+use octocrab::Octocrab;
+use serenity::http::Http;
+use serenity::model::channel::GuildChannel;
+use serenity::model::id::{ChannelId, GuildId};
+use tracing::info;
+
+use crate::audit::{AuditAction, AuditEntry, AuditLog};
+use crate::config::{Config, Project};
+use crate::sync::issue_thread_id;
+
+/// Lock forum threads whose last activity is older than the given age
+pub async fn lock_threads(
+    older_than: &str,
+    prefix: Option<&str>,
+    project_filter: Option<&str>,
+    dry_run: bool,
+) -> Result<()> {
+    let max_age = parse_age(older_than)?;
+    let max_age = i64::try_from(max_age.as_secs())
+        .with_context(|| format!("Age '{older_than}' is too large"))?;
+    let cutoff = chrono::Utc::now().timestamp() - max_age;
+
+    println!("🔒 Locking threads inactive for more than {older_than}...");
+    if dry_run {
+        println!("   (dry run - no changes will be made)");
+    }
+    println!();
+
+    let config = Config::load()?;
+    let clients = crate::clients::Clients::new_standalone().await?;
+    let audit = cli_audit_log(&config, &clients)?;
+
+    for (idx, project) in selected_projects(&config, project_filter)
+        .into_iter()
+        .enumerate()
+    {
+        println!(
+            "Project {}: {}",
+            idx + 1,
+            project.name.as_deref().unwrap_or("unnamed")
+        );
+        if project.staging() {
+            println!("  ⏭️  Skipped: staging projects never lock threads\n");
+            continue;
+        }
+
+        let discord = match clients.discord_for(&config, project) {
+            Ok(discord) => discord,
+            Err(e) => {
+                eprintln!("  ❌ Error: {e}\n");
+                continue;
+            }
+        };
+        match lock_project_threads(&discord, &audit, project, prefix, cutoff, dry_run).await {
+            Ok(count) => {
+                let verb = if dry_run { "Would lock" } else { "Locked" };
+                println!("  ✅ {verb} {count} threads");
+            }
+            Err(e) => {
+                eprintln!("  ❌ Error: {e}");
+            }
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Close open GitHub issues carrying a label that were created from Discord threads
+pub async fn close_issues(label: &str, project_filter: Option<&str>, dry_run: bool) -> Result<()> {
+    println!("📕 Closing issues labeled '{label}'...");
+    if dry_run {
+        println!("   (dry run - no changes will be made)");
+    }
+    println!();
+
+    let config = Config::load()?;
+    let clients = crate::clients::Clients::new_standalone().await?;
+    let audit = cli_audit_log(&config, &clients)?;
+
+    for (idx, project) in selected_projects(&config, project_filter)
+        .into_iter()
+        .enumerate()
+    {
+        println!(
+            "Project {}: {}",
+            idx + 1,
+            project.name.as_deref().unwrap_or("unnamed")
+        );
+
+        // Every repository the project's prefixes route threads to
+        for route in &project.routes() {
+            println!("  - GitHub: {}/{}", route.github_owner, route.github_repo);
+
+            let github = match clients.github_for(&config, route).await {
+                Ok(github) => github,
+                Err(e) => {
+                    eprintln!("  ❌ Error: {e}");
+                    continue;
+                }
+            };
+            match close_project_issues(&github, &audit, route, label, dry_run).await {
+                Ok(count) => {
+                    let verb = if dry_run { "Would close" } else { "Closed" };
+                    println!("  ✅ {verb} {count} issues");
+                }
+                Err(e) => {
+                    eprintln!("  ❌ Error: {e}");
+                }
+            }
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Projects matching a name or repository filter, or all projects
+pub fn selected_projects<'a>(config: &'a Config, project_filter: Option<&str>) -> Vec<&'a Project> {
+    config
+        .projects
+        .iter()
+        .filter(|p| match project_filter {
+            Some(name) => p.name.as_deref() == Some(name) || p.github_repo == name,
+            None => true,
+        })
+        .collect()
+}
+
+/// Audit log for CLI-initiated changes
+pub fn cli_audit_log(config: &Config, clients: &crate::clients::Clients) -> Result<AuditLog> {
+    let store = crate::store::Store::open(config)?;
+    Ok(AuditLog::new(
+        std::sync::Arc::new(store),
+        Some(clients.discord_http.clone()),
+        config,
+    ))
+}
+
+async fn lock_project_threads(
+    discord: &Http,
+    audit: &AuditLog,
+    project: &Project,
+    prefix: Option<&str>,
+    cutoff: i64,
+    dry_run: bool,
+) -> Result<usize> {
+    let guild_id = GuildId::new(project.discord_guild_id.parse()?);
+    let forum_id = ChannelId::new(project.discord_forum_id.parse()?);
+
+    let threads = guild_id.get_active_threads(discord).await?;
+    let mut locked_count = 0;
+
+    for thread in threads.threads {
+        if thread.parent_id != Some(forum_id) {
+            continue;
+        }
+
+        let thread_name = &thread.name;
+        let matches_prefix = match prefix {
+            Some(prefix) => thread_name.starts_with(prefix),
+            None => project.has_valid_prefix(thread_name),
+        };
+
+        if !matches_prefix {
+            continue;
+        }
+
+        let is_locked = thread
+            .thread_metadata
+            .as_ref()
+            .map(|m| m.locked)
+            .unwrap_or(false);
+
+        if is_locked || last_activity(&thread) > cutoff {
+            continue;
+        }
+
+        if dry_run {
+            println!("  - Would lock: {} ({})", thread_name, thread.id);
+        } else {
+            println!("  - Locking: {} ({})", thread_name, thread.id);
+            thread
+                .id
+                .edit_thread(
+                    discord,
+                    serenity::builder::EditThread::new()
+                        .locked(true)
+                        .archived(true),
+                )
+                .await?;
+            info!("Locked inactive thread {} ({})", thread.id, thread_name);
+            audit
+                .record(
+                    AuditEntry::new(project, AuditAction::ThreadLocked)
+                        .thread(thread.id.get())
+                        .actor(crate::constants::AUDIT_ACTOR_CLI)
+                        .details("inactive thread"),
+                )
+                .await;
+        }
+
+        locked_count += 1;
+    }
+
+    Ok(locked_count)
+}
+
+async fn close_project_issues(
+    github: &Octocrab,
+    audit: &AuditLog,
+    project: &Project,
+    label: &str,
+    dry_run: bool,
+) -> Result<usize> {
+    let query = format!(
+        "repo:{}/{} is:issue is:open label:\"{}\"",
+        project.github_owner, project.github_repo, label
+    );
+
+    // Every page is read before closing, since closed issues leave the results
+    let first = github
+        .search()
+        .issues_and_pull_requests(&query)
+        .per_page(100)
+        .send()
+        .await?;
+    let found = github.all_pages(first).await?;
+
+    let mut closed_count = 0;
+
+    // Only touch issues that CardiBot created from a Discord thread
+    for issue in found
+        .iter()
+        .filter(|issue| issue_thread_id(issue).is_some())
+    {
+        if dry_run {
+            println!("  - Would close: #{} {}", issue.number, issue.title);
+        } else {
+            println!("  - Closing: #{} {}", issue.number, issue.title);
+            github
+                .issues(&project.github_owner, &project.github_repo)
+                .update(issue.number)
+                .state(octocrab::models::IssueState::Closed)
+                .send()
+                .await?;
+            info!("Closed issue #{} labeled '{}'", issue.number, label);
+            let mut entry = AuditEntry::new(project, AuditAction::IssueClosed)
+                .issue(issue.number)
+                .actor(crate::constants::AUDIT_ACTOR_CLI)
+                .details(format!("labeled '{label}'"));
+            if let Some(thread_id) = issue_thread_id(issue) {
+                entry = entry.thread(thread_id);
+            }
+            audit.record(entry).await;
+        }
+
+        closed_count += 1;
+    }
+
+    Ok(closed_count)
+}
+
+/// Unix timestamp of the last message in a thread, falling back to its creation time
+pub fn last_activity(thread: &GuildChannel) -> i64 {
+    thread
+        .last_message_id
+        .map(|id| id.created_at())
+        .unwrap_or_else(|| thread.id.created_at())
+        .unix_timestamp()
+}
+
+/// Parse an age like "90d", "12h", "30m" or "2w" into a duration
+pub fn parse_age(age: &str) -> Result<std::time::Duration> {
+    let age = age.trim();
+    // The unit may be any character, so split at its boundary
+    let split = age.char_indices().last().map_or(0, |(i, _)| i);
+    let (value, unit) = age.split_at(split);
+    let value: u64 = value
+        .parse()
+        .with_context(|| format!("Invalid age '{age}', expected e.g. 90d, 12h, 2w"))?;
+
+    let unit_secs: u64 = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        "w" => 60 * 60 * 24 * 7,
+        _ => anyhow::bail!("Invalid age unit in '{age}', expected one of m, h, d, w"),
+    };
+    let seconds = value
+        .checked_mul(unit_secs)
+        .with_context(|| format!("Age '{age}' is too large"))?;
+
+    Ok(std::time::Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("90d").unwrap().as_secs(), 90 * 86400);
+        assert_eq!(parse_age("12h").unwrap().as_secs(), 12 * 3600);
+        assert_eq!(parse_age("2w").unwrap().as_secs(), 14 * 86400);
+        assert!(parse_age("90").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("5y").is_err());
+        assert!(parse_age("5é").is_err());
+        assert!(parse_age("99999999999999999w").is_err());
+    }
+}
@@ -0,0 +1,363 @@
+use anyhow::Result;
+use regex::Regex;
+use serenity::all::*;
+use std::sync::LazyLock;
+use tracing::info;
+
+use crate::audit::{AuditAction, AuditEntry, AuditLog};
+use crate::config::{Config, Project};
+use crate::progress::Progress;
+use crate::selection::MessageSelection;
+use crate::store::Store;
+
+const APPLY_PREFIX: &str = "title_suggestion:apply:";
+const KEEP_PREFIX: &str = "title_suggestion:keep:";
+const MAX_SUGGESTED_WORDS: usize = 12;
+
+// Words that say nothing about what went wrong
+const FILLER_WORDS: &[&str] = &[
+    "a",
+    "an",
+    "the",
+    "i",
+    "my",
+    "me",
+    "it",
+    "is",
+    "this",
+    "that",
+    "with",
+    "on",
+    "in",
+    "hi",
+    "hello",
+    "hey",
+    "help",
+    "pls",
+    "plz",
+    "please",
+    "urgent",
+    "asap",
+    "anyone",
+    "someone",
+    "something",
+    "bug",
+    "bugs",
+    "issue",
+    "issues",
+    "problem",
+    "problems",
+    "error",
+    "errors",
+    "question",
+    "feature",
+    "request",
+    "feedback",
+    "broken",
+    "not",
+    "working",
+    "work",
+    "works",
+    "doesnt",
+    "doesn",
+    "dont",
+    "don",
+    "cant",
+    "t",
+    "wrong",
+    "need",
+    "fix",
+];
+
+// Mentions, channel links and URLs don't belong in a title
+static NOISE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<[@#][!&]?\d+>|<a?:\w+:\d+>|https?://\S+").expect("valid regex"));
+
+/// Ask the thread owner to pick a better title before filing when the current
+/// one says too little. Returns whether the prompt was shown; `/issue create`
+/// carries on as usual otherwise.
+pub async fn offer(
+    http: &Http,
+    command: &CommandInteraction,
+    project: &Project,
+    thread: &GuildChannel,
+    selection: &MessageSelection,
+    anonymous: bool,
+    preview: bool,
+) -> Result<bool> {
+    if !project.title_suggestions() || thread.owner_id != Some(command.user.id) {
+        return Ok(false);
+    }
+    let Some(suggested) = suggestion_for_thread(http, project, thread).await? else {
+        return Ok(false);
+    };
+
+    // The choices made with the command come back with the button
+    let encoded = format!(
+        "{}{}:{}",
+        u8::from(anonymous),
+        u8::from(preview),
+        selection.encode()
+    );
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{APPLY_PREFIX}{encoded}"))
+            .label("Use this title")
+            .style(ButtonStyle::Success),
+        CreateButton::new(format!("{KEEP_PREFIX}{encoded}"))
+            .label("Keep my title")
+            .style(ButtonStyle::Secondary),
+    ]);
+    command
+        .edit_response(
+            http,
+            EditInteractionResponse::new()
+                .content(format!(
+                    "A more descriptive title helps maintainers find and triage this. How about:\n> {suggested}"
+                ))
+                .components(vec![buttons]),
+        )
+        .await?;
+    Ok(true)
+}
+
+pub fn is_title_suggestion_component(custom_id: &str) -> bool {
+    custom_id.starts_with(APPLY_PREFIX) || custom_id.starts_with(KEEP_PREFIX)
+}
+
+/// Rename the thread if asked, then file it with the selection the command
+/// was run with
+pub async fn handle_component(
+    http: &Http,
+    component: &ComponentInteraction,
+    config: &Config,
+    store: &Store,
+    audit: &AuditLog,
+) -> Result<()> {
+    let custom_id = component.data.custom_id.as_str();
+    let (apply, encoded) = match custom_id.strip_prefix(APPLY_PREFIX) {
+        Some(rest) => (true, rest),
+        None => (
+            false,
+            custom_id.strip_prefix(KEEP_PREFIX).unwrap_or_default(),
+        ),
+    };
+    let (flags, encoded) = encoded.split_once(':').unwrap_or(("00", encoded));
+    let anonymous = flags.starts_with('1');
+    let preview = flags.get(1..2) == Some("1");
+    let selection = MessageSelection::decode(encoded);
+
+    let Some((mut thread, project)) =
+        crate::thread_events::resolve_thread(http, component.channel_id, config, store).await?
+    else {
+        return Ok(());
+    };
+    // Only the owner who ran `/issue create` is shown the prompt
+    if thread.owner_id != Some(component.user.id) {
+        return Ok(());
+    }
+
+    component
+        .create_response(
+            http,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content("⏳ Creating the issue...")
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+
+    if apply {
+        if let Some(suggested) = suggestion_for_thread(http, &project, &thread).await? {
+            let previous = thread.name.clone();
+            thread = thread
+                .id
+                .edit_thread(http, EditThread::new().name(&suggested))
+                .await?;
+            crate::discord_cache::invalidate_channel(thread.id);
+            info!("Renamed thread {} to '{}'", thread.id, suggested);
+            audit
+                .record(
+                    AuditEntry::new(&project, AuditAction::ThreadRenamed)
+                        .thread(thread.id.get())
+                        .actor(&component.user.name)
+                        .details(format!("'{previous}' -> '{suggested}'")),
+                )
+                .await;
+        }
+    }
+
+    if preview {
+        let response = crate::preview::response(
+            http,
+            config,
+            &project,
+            &thread,
+            store,
+            &selection,
+            anonymous,
+            Progress::Component(http, component),
+        )
+        .await?;
+        component.edit_response(http, response).await?;
+        return Ok(());
+    }
+
+    if !store.try_lock_thread(thread.id.get(), crate::constants::THREAD_LOCK_TTL_SECS)? {
+        component
+            .edit_response(
+                http,
+                EditInteractionResponse::new().content(crate::constants::MSG_THREAD_BUSY),
+            )
+            .await?;
+        return Ok(());
+    }
+    if anonymous {
+        store.set_anonymous_reporter(
+            thread.id.get(),
+            component.user.id.get(),
+            &component.user.name,
+        )?;
+    }
+    let result = crate::filing::file_thread(
+        http,
+        config,
+        &project,
+        &thread,
+        store,
+        audit,
+        &component.user.name,
+        &selection,
+        Progress::Component(http, component),
+    )
+    .await;
+    store.unlock_thread(thread.id.get())?;
+
+    let (content, result) = match result {
+        Ok(filed) => (format!("✅ {}", filed.summary()), Ok(())),
+        Err(e) => {
+            let e = crate::error::BotError::from(e);
+            (format!("❌ {}", e.user_message()), Err(e.into()))
+        }
+    };
+    component
+        .edit_response(http, EditInteractionResponse::new().content(content))
+        .await?;
+    result
+}
+
+/// A better title built from the opening post, when the current one is too
+/// vague and the post has something more specific to say
+async fn suggestion_for_thread(
+    http: &Http,
+    project: &Project,
+    thread: &GuildChannel,
+) -> Result<Option<String>> {
+    let (prefix, rest) = split_prefix(project, &thread.name);
+    if is_informative(&rest) {
+        return Ok(None);
+    }
+    let starter = crate::discord_cache::starter_message(http, thread.id).await?;
+    Ok(suggest_title(prefix.as_deref(), &starter.content))
+}
+
+/// The configured prefix of a title, if any, and the words after it
+fn split_prefix(project: &Project, title: &str) -> (Option<String>, String) {
+    let title = project
+        .canonical_title(title)
+        .unwrap_or_else(|| title.to_string());
+    match project.prefix_for_title(&title) {
+        Some(prefix) => {
+            let rest = title.replacen(&prefix.prefix, "", 1).trim().to_string();
+            (Some(prefix.prefix), rest)
+        }
+        None => (None, title.trim().to_string()),
+    }
+}
+
+/// At least two words that aren't filler like "help" or "bug"
+fn is_informative(text: &str) -> bool {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .filter(|word| !FILLER_WORDS.contains(&word.to_lowercase().as_str()))
+        .count()
+        >= 2
+}
+
+/// Title from the first telling sentence of the opening post, behind the prefix
+pub fn suggest_title(prefix: Option<&str>, content: &str) -> Option<String> {
+    let mut in_code = false;
+    let sentence = content
+        .lines()
+        .filter(|line| {
+            if line.trim().starts_with("```") {
+                in_code = !in_code;
+                return false;
+            }
+            !in_code
+        })
+        .map(first_sentence)
+        .find(|sentence| is_informative(sentence))?;
+
+    let mut chars = sentence.chars();
+    let first = chars.next()?;
+    let sentence = format!("{}{}", first.to_uppercase(), chars.as_str());
+    let title = match prefix {
+        Some(prefix) => format!("{prefix} {sentence}"),
+        None => sentence,
+    };
+    Some(crate::sanitize::truncate_for_discord(
+        &title,
+        crate::constants::DISCORD_THREAD_NAME_LIMIT,
+    ))
+}
+
+/// A line's first sentence without mentions, links or markdown, cut to a
+/// title's length in words
+fn first_sentence(line: &str) -> String {
+    let line = NOISE.replace_all(line, "");
+    let line: String = line
+        .chars()
+        .filter(|c| !matches!(c, '*' | '_' | '`' | '~' | '>' | '#' | '|'))
+        .collect();
+    let sentence = line
+        .split_inclusive(['.', '!', '?'])
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches(['.', '!', '?']);
+    sentence
+        .split_whitespace()
+        .take(MAX_SUGGESTED_WORDS)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_informative() {
+        assert!(!is_informative("help!!"));
+        assert!(!is_informative("bug"));
+        assert!(!is_informative("Please help, not working"));
+        assert!(is_informative("Ledger signing fails"));
+    }
+
+    #[test]
+    fn test_suggest_title() {
+        assert_eq!(
+            suggest_title(
+                Some("[BUG]"),
+                "hey <@123>\n```\nwallet sync failed\n```\nstaking rewards don't show after epoch change. I tried restarting"
+            )
+            .as_deref(),
+            Some("[BUG] Staking rewards don't show after epoch change")
+        );
+        assert_eq!(
+            suggest_title(None, "**hardware wallet** disconnects during signing!"),
+            Some("Hardware wallet disconnects during signing".to_string())
+        );
+        assert_eq!(suggest_title(None, "help pls"), None);
+    }
+}
@@ -0,0 +1,139 @@
+use serde::Serialize;
+
+use crate::config::TranscriptFormat;
+
+/// Bumped whenever the JSON layout changes in a way parsers would notice
+const JSON_VERSION: u32 = 1;
+
+/// One message as it goes into the issue body
+#[derive(Debug, Clone, Serialize)]
+pub struct Entry<'a> {
+    /// Discord message ID, as a string since it overflows JavaScript numbers
+    #[serde(serialize_with = "as_string")]
+    pub id: u64,
+    pub author: &'a str,
+    /// RFC 3339, UTC
+    #[serde(serialize_with = "as_rfc3339")]
+    pub timestamp: i64,
+    pub content: &'a str,
+}
+
+fn as_string<S: serde::Serializer>(id: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&id.to_string())
+}
+
+fn as_rfc3339<S: serde::Serializer>(timestamp: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+    let time = chrono::DateTime::from_timestamp(*timestamp, 0).unwrap_or_default();
+    serializer.serialize_str(&time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+}
+
+#[derive(Serialize)]
+struct JsonTranscript<'a> {
+    version: u32,
+    messages: &'a [Entry<'a>],
+}
+
+/// The transcript for the issue body in the project's format
+pub fn render(format: TranscriptFormat, entries: &[Entry]) -> String {
+    match format {
+        TranscriptFormat::Text => entries
+            .iter()
+            .map(|e| crate::code_blocks::transcript_entry(e.author, e.content))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        TranscriptFormat::Table => table(entries),
+        TranscriptFormat::Json => json(entries),
+    }
+}
+
+fn table(entries: &[Entry]) -> String {
+    let mut table = String::from("| Time (UTC) | Author | Message |\n| --- | --- | --- |");
+    for entry in entries {
+        let time = chrono::DateTime::from_timestamp(entry.timestamp, 0)
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        table.push_str(&format!(
+            "\n| {time} | @{} | {} |",
+            table_cell(entry.author),
+            table_cell(entry.content)
+        ));
+    }
+    table
+}
+
+/// Keep a message on its row: pipes would start a new cell and line breaks
+/// a new paragraph
+fn table_cell(text: &str) -> String {
+    text.trim()
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+fn json(entries: &[Entry]) -> String {
+    let transcript = JsonTranscript {
+        version: JSON_VERSION,
+        messages: entries,
+    };
+    let json = serde_json::to_string_pretty(&transcript).unwrap_or_default();
+    format!("<details>\n<summary>Transcript (JSON)</summary>\n\n```json\n{json}\n```\n\n</details>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<Entry<'static>> {
+        vec![
+            Entry {
+                id: 1_200_000_000_000_000_001,
+                author: "ana",
+                timestamp: 1_735_732_800,
+                content: "Crashes | every time\non save",
+            },
+            Entry {
+                id: 1_200_000_000_000_000_002,
+                author: "bo",
+                timestamp: 1_735_732_860,
+                content: "Same here",
+            },
+        ]
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(
+            render(TranscriptFormat::Text, &entries()[1..]),
+            "**@bo**: Same here"
+        );
+    }
+
+    #[test]
+    fn test_table() {
+        let table = render(TranscriptFormat::Table, &entries());
+        let rows: Vec<&str> = table.lines().collect();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(
+            rows[2],
+            "| 2025-01-01 12:00 | @ana | Crashes \\| every time<br>on save |"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let rendered = render(TranscriptFormat::Json, &entries());
+        let json = rendered
+            .split_once("```json\n")
+            .and_then(|(_, rest)| rest.split_once("\n```"))
+            .map(|(json, _)| json)
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(value["version"], 1);
+        assert_eq!(value["messages"][0]["id"], "1200000000000000001");
+        assert_eq!(value["messages"][0]["timestamp"], "2025-01-01T12:00:00Z");
+        assert_eq!(
+            value["messages"][0]["content"],
+            "Crashes | every time\non save"
+        );
+    }
+}
@@ -0,0 +1,368 @@
+use anyhow::{Context, Result};
+use ring::digest;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::config::{TranslationConfig, TranslationProvider};
+
+/// Shared by every translation so connections are reused
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+
+/// Words common in running text of each language, English first
+const STOP_WORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "it", "to", "of", "in", "this", "that", "with", "not", "when",
+            "have", "for", "on", "my", "i", "you", "but", "can",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "es", "y", "en", "un", "una", "con", "por", "pero", "cuando",
+            "tengo", "mi", "está", "puedo", "hay", "funciona", "del",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "os", "um", "uma", "com", "não", "mas", "quando", "tenho", "meu", "minha", "está",
+            "posso", "funciona", "do", "da", "isso", "em",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le",
+            "les",
+            "et",
+            "est",
+            "un",
+            "une",
+            "avec",
+            "pas",
+            "mais",
+            "quand",
+            "je",
+            "mon",
+            "ma",
+            "ne",
+            "du",
+            "des",
+            "ça",
+            "fonctionne",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der",
+            "die",
+            "das",
+            "und",
+            "ist",
+            "nicht",
+            "ein",
+            "eine",
+            "mit",
+            "aber",
+            "wenn",
+            "ich",
+            "mein",
+            "es",
+            "funktioniert",
+            "auf",
+            "bei",
+        ],
+    ),
+];
+
+/// Display names of the languages providers may report
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("ar", "Arabic"),
+    ("de", "German"),
+    ("es", "Spanish"),
+    ("fr", "French"),
+    ("id", "Indonesian"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("pt", "Portuguese"),
+    ("ru", "Russian"),
+    ("tr", "Turkish"),
+    ("uk", "Ukrainian"),
+    ("vi", "Vietnamese"),
+    ("zh", "Chinese"),
+];
+
+/// Guess the language of a transcript, as an ISO 639-1 code, when it isn't
+/// English. Scripts decide for CJK, Korean and Cyrillic text; Latin text is
+/// judged by its most common words. None for English or too little text.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    // Links are mostly ASCII whatever the language around them
+    let words: Vec<&str> = text
+        .split_whitespace()
+        .filter(|w| !w.contains("://"))
+        .collect();
+
+    let (mut kana, mut han, mut hangul, mut cyrillic, mut letters) = (0, 0, 0, 0, 0);
+    for c in words
+        .iter()
+        .flat_map(|w| w.chars())
+        .filter(|c| c.is_alphabetic())
+    {
+        letters += 1;
+        match c {
+            '\u{3040}'..='\u{30FF}' => kana += 1,
+            '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' => han += 1,
+            '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' => hangul += 1,
+            '\u{0400}'..='\u{04FF}' => cyrillic += 1,
+            _ => {}
+        }
+    }
+    if letters < crate::constants::TRANSLATION_MIN_LETTERS {
+        return None;
+    }
+    // A fifth of the letters is plenty: usernames and code are Latin
+    let notable = |count: usize| count * 5 >= letters;
+    if kana > 0 && notable(kana + han) {
+        return Some("ja");
+    }
+    if notable(hangul) {
+        return Some("ko");
+    }
+    if notable(han) {
+        return Some("zh");
+    }
+    if notable(cyrillic) {
+        return Some("ru");
+    }
+
+    let hits = |list: &[&str]| {
+        words
+            .iter()
+            .map(|w| w.trim_matches(|c: char| !c.is_alphabetic()).to_lowercase())
+            .filter(|w| list.contains(&w.as_str()))
+            .count()
+    };
+    let english = hits(STOP_WORDS[0].1);
+    STOP_WORDS[1..]
+        .iter()
+        .map(|(code, list)| (*code, hits(list)))
+        .filter(|(_, count)| *count >= 3 && *count > english)
+        .max_by_key(|(_, count)| *count)
+        .map(|(code, _)| code)
+}
+
+pub fn language_name(code: &str) -> String {
+    let base = code.split(['-', '_']).next().unwrap_or(code).to_lowercase();
+    LANGUAGE_NAMES
+        .iter()
+        .find(|(c, _)| *c == base)
+        .map_or_else(|| code.to_uppercase(), |(_, name)| name.to_string())
+}
+
+/// A collapsible section with the transcript translated to English, or None
+/// when the transcript is English or the service fails. The transcript is
+/// expected to be filtered and redacted already.
+pub async fn section(
+    service: &TranslationConfig,
+    transcript: &str,
+    detected: &str,
+) -> Option<String> {
+    let text =
+        crate::sanitize::truncate_for_discord(transcript, crate::constants::TRANSLATION_MAX_CHARS);
+
+    let (translated, source) = match translate(service, &text).await {
+        Ok(result) => result,
+        Err(e) => {
+            warn!(
+                "Translation with {} failed: {:#}",
+                service.provider.name(),
+                e
+            );
+            return None;
+        }
+    };
+    let source = source.unwrap_or_else(|| detected.to_string());
+    if source.to_lowercase().starts_with("en") {
+        debug!(
+            "{} found the transcript to be English",
+            service.provider.name()
+        );
+        return None;
+    }
+    info!(
+        "Translated a {} transcript with {}",
+        language_name(&source),
+        service.provider.name()
+    );
+    Some(format!(
+        "<details open>\n<summary>Machine translation from {} ({})</summary>\n\n{}\n\n</details>",
+        language_name(&source),
+        service.provider.name(),
+        translated.trim()
+    ))
+}
+
+/// Fingerprint of a transcript, to tell whether a cached translation of it
+/// is still current
+pub fn source_hash(transcript: &str) -> String {
+    crate::webhook::encode_hex(digest::digest(&digest::SHA256, transcript.as_bytes()).as_ref())
+}
+
+/// The text in English and the source language the provider detected
+async fn translate(service: &TranslationConfig, text: &str) -> Result<(String, Option<String>)> {
+    let key = match &service.api_key_env {
+        Some(name) => Some(
+            std::env::var(name).with_context(|| format!("{name} must be set for translation"))?,
+        ),
+        None => None,
+    };
+    let client = &*CLIENT;
+    let request = match service.provider {
+        TranslationProvider::Deepl => {
+            let base = service
+                .endpoint
+                .as_deref()
+                .unwrap_or(crate::constants::DEFAULT_DEEPL_URL);
+            let mut request = client
+                .post(format!("{}/v2/translate", base.trim_end_matches('/')))
+                .json(&serde_json::json!({ "text": [text], "target_lang": "EN-US" }));
+            if let Some(key) = &key {
+                request = request.header("Authorization", format!("DeepL-Auth-Key {key}"));
+            }
+            request
+        }
+        TranslationProvider::Google => {
+            let base = service
+                .endpoint
+                .as_deref()
+                .unwrap_or(crate::constants::DEFAULT_GOOGLE_TRANSLATE_URL);
+            let mut request = client
+                .post(format!(
+                    "{}/language/translate/v2",
+                    base.trim_end_matches('/')
+                ))
+                .json(&serde_json::json!({ "q": text, "target": "en", "format": "text" }));
+            if let Some(key) = &key {
+                request = request.query(&[("key", key)]);
+            }
+            request
+        }
+        TranslationProvider::LibreTranslate => {
+            let base = service
+                .endpoint
+                .as_deref()
+                .context("LibreTranslate needs an endpoint")?;
+            let mut body = serde_json::json!({
+                "q": text,
+                "source": "auto",
+                "target": "en",
+                "format": "text",
+            });
+            if let Some(key) = &key {
+                body["api_key"] = serde_json::Value::from(key.as_str());
+            }
+            client
+                .post(format!("{}/translate", base.trim_end_matches('/')))
+                .json(&body)
+        }
+    };
+
+    // Keys can be in the query string, so errors leave the URL out
+    let response: serde_json::Value = request
+        .timeout(Duration::from_secs(
+            crate::constants::TRANSLATION_TIMEOUT_SECS,
+        ))
+        .send()
+        .await
+        .map_err(|e| e.without_url())?
+        .error_for_status()
+        .map_err(|e| e.without_url())?
+        .json()
+        .await
+        .map_err(|e| e.without_url())?;
+    parse_response(service.provider, &response).context("Unexpected translation response")
+}
+
+fn parse_response(
+    provider: TranslationProvider,
+    response: &serde_json::Value,
+) -> Option<(String, Option<String>)> {
+    let (text, source) = match provider {
+        TranslationProvider::Deepl => {
+            let translation = &response["translations"][0];
+            (
+                translation["text"].as_str(),
+                translation["detected_source_language"].as_str(),
+            )
+        }
+        TranslationProvider::Google => {
+            let translation = &response["data"]["translations"][0];
+            (
+                translation["translatedText"].as_str(),
+                translation["detectedSourceLanguage"].as_str(),
+            )
+        }
+        TranslationProvider::LibreTranslate => (
+            response["translatedText"].as_str(),
+            response["detectedLanguage"]["language"].as_str(),
+        ),
+    };
+    Some((text?.to_string(), source.map(str::to_lowercase)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(
+            detect_language(
+                "**kenji**: ログインボタンを押すとアプリが落ちます。再起動しても同じです。"
+            ),
+            Some("ja")
+        );
+        assert_eq!(
+            detect_language(
+                "**maria**: Cuando abro la app en el móvil, la pantalla se queda en blanco y no hay forma de volver"
+            ),
+            Some("es")
+        );
+        assert_eq!(
+            detect_language(
+                "**sam**: When I open the app on my phone the screen is blank and I can't go back"
+            ),
+            None
+        );
+        assert_eq!(detect_language("ok"), None);
+        assert_eq!(
+            detect_language("Приложение вылетает при входе в аккаунт"),
+            Some("ru")
+        );
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let deepl = serde_json::json!({
+            "translations": [{ "detected_source_language": "JA", "text": "It crashes" }]
+        });
+        assert_eq!(
+            parse_response(TranslationProvider::Deepl, &deepl),
+            Some(("It crashes".to_string(), Some("ja".to_string())))
+        );
+        let libre = serde_json::json!({
+            "translatedText": "It crashes",
+            "detectedLanguage": { "confidence": 90, "language": "es" }
+        });
+        assert_eq!(
+            parse_response(TranslationProvider::LibreTranslate, &libre),
+            Some(("It crashes".to_string(), Some("es".to_string())))
+        );
+        assert_eq!(language_name("pt-BR"), "Portuguese");
+    }
+}
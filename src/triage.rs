@@ -0,0 +1,324 @@
+use anyhow::Result;
+use serenity::all::*;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::audit::{project_key, AuditAction, AuditEntry, AuditLog};
+use crate::auto_create::ThreadActivity;
+use crate::config::{Config, Project};
+use crate::store::Store;
+
+const TRIAGE_PREFIX: &str = "triage:";
+const DUPLICATE_MODAL_PREFIX: &str = "triage_duplicate:";
+const ISSUE_INPUT_ID: &str = "issue";
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_APPROVED: &str = "approved";
+pub const STATUS_REJECTED: &str = "rejected";
+pub const STATUS_DUPLICATE: &str = "duplicate";
+
+/// Post a candidate thread to the triage channel unless it was queued before
+pub async fn queue_candidate(
+    discord: &Http,
+    project: &Project,
+    thread: &GuildChannel,
+    activity: &ThreadActivity,
+    store: &Store,
+) -> Result<()> {
+    if store.triage_status(thread.id.get())?.is_some() {
+        return Ok(());
+    }
+    let Some(channel_id) = project
+        .triage_channel_id
+        .as_deref()
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(ChannelId::new)
+    else {
+        anyhow::bail!("triage_queue is enabled but triage_channel_id is not set");
+    };
+
+    let thread_id = thread.id.get();
+    let embed = CreateEmbed::new()
+        .title(crate::constants::MSG_TRIAGE_CANDIDATE)
+        .description(format!("**{}**\n<#{}>", thread.name, thread_id))
+        .field("Messages", activity.messages.to_string(), true)
+        .field("Participants", activity.participants.to_string(), true)
+        .field("👍", activity.reactions.to_string(), true)
+        .color(crate::constants::COLOR_SUCCESS);
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{TRIAGE_PREFIX}approve:{thread_id}"))
+            .label("Approve")
+            .style(ButtonStyle::Success),
+        CreateButton::new(format!("{TRIAGE_PREFIX}reject:{thread_id}"))
+            .label("Reject")
+            .style(ButtonStyle::Danger),
+        CreateButton::new(format!("{TRIAGE_PREFIX}duplicate:{thread_id}"))
+            .label("Merge as duplicate")
+            .style(ButtonStyle::Secondary),
+    ]);
+
+    channel_id
+        .send_message(
+            discord,
+            CreateMessage::new().embed(embed).components(vec![buttons]),
+        )
+        .await?;
+    store.set_triage_status(thread_id, &project_key(project), STATUS_PENDING)?;
+
+    info!("Queued thread {} for triage", thread_id);
+    Ok(())
+}
+
+pub fn is_triage_component(custom_id: &str) -> bool {
+    custom_id.starts_with(TRIAGE_PREFIX)
+}
+
+pub fn is_triage_modal(custom_id: &str) -> bool {
+    custom_id.starts_with(DUPLICATE_MODAL_PREFIX)
+}
+
+pub async fn handle_component(
+    http: &Http,
+    component: &ComponentInteraction,
+    config: &Arc<Config>,
+    store: &Store,
+    audit: &AuditLog,
+) -> Result<()> {
+    let Some((decision, thread_id)) = component
+        .data
+        .custom_id
+        .strip_prefix(TRIAGE_PREFIX)
+        .and_then(|rest| rest.split_once(':'))
+        .and_then(|(decision, id)| Some((decision, id.parse::<u64>().ok()?)))
+    else {
+        return Ok(());
+    };
+
+    let Some((thread, project)) =
+        crate::thread_events::resolve_thread(http, ChannelId::new(thread_id), config, store)
+            .await?
+    else {
+        return Ok(());
+    };
+
+    if !crate::priority::has_allowed_role(&project, component.member.as_ref()) {
+        component
+            .create_response(
+                http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content("You don't have permission to triage threads")
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    match decision {
+        "approve" => {
+            component.defer(http).await?;
+            if !store.try_lock_thread(thread_id, crate::constants::THREAD_LOCK_TTL_SECS)? {
+                return notice(http, component, crate::constants::MSG_THREAD_BUSY).await;
+            }
+            // Two moderators pressing approve file the thread once
+            if !store.claim_triage(thread_id, STATUS_PENDING, STATUS_APPROVED)? {
+                store.unlock_thread(thread_id)?;
+                return notice(http, component, "This thread was already triaged").await;
+            }
+            let filed = crate::filing::file_thread(
+                http,
+                config,
+                &project,
+                &thread,
+                store,
+                audit,
+                &component.user.name,
+                &Default::default(),
+                crate::progress::Progress::Silent,
+            )
+            .await;
+            store.unlock_thread(thread_id)?;
+            let filed = match filed {
+                Ok(filed) => filed,
+                Err(e) => {
+                    // Back in the queue for another try
+                    store.set_triage_status(thread_id, &project_key(&project), STATUS_PENDING)?;
+                    return Err(e);
+                }
+            };
+            resolve_message(
+                http,
+                component,
+                format!(
+                    "✅ Approved by <@{}>: {}",
+                    component.user.id,
+                    filed.summary()
+                ),
+            )
+            .await?;
+            record_decision(audit, &project, thread_id, &component.user.name, "approved").await;
+        }
+        "reject" => {
+            store.set_triage_status(thread_id, &project_key(&project), STATUS_REJECTED)?;
+            component
+                .create_response(
+                    http,
+                    CreateInteractionResponse::UpdateMessage(
+                        CreateInteractionResponseMessage::new()
+                            .content(format!("🚫 Rejected by <@{}>", component.user.id))
+                            .components(vec![]),
+                    ),
+                )
+                .await?;
+            record_decision(audit, &project, thread_id, &component.user.name, "rejected").await;
+        }
+        "duplicate" => {
+            let modal = CreateModal::new(
+                format!("{DUPLICATE_MODAL_PREFIX}{thread_id}"),
+                "Merge as duplicate",
+            )
+            .components(vec![CreateActionRow::InputText(
+                CreateInputText::new(
+                    InputTextStyle::Short,
+                    "Existing issue number",
+                    ISSUE_INPUT_ID,
+                )
+                .placeholder("123")
+                .required(true),
+            )]);
+            component
+                .create_response(http, CreateInteractionResponse::Modal(modal))
+                .await?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Link the thread to an existing issue instead of filing a new one
+pub async fn handle_modal(
+    http: &Http,
+    modal: &ModalInteraction,
+    config: &Arc<Config>,
+    store: &Store,
+    audit: &AuditLog,
+) -> Result<()> {
+    let Some(thread_id) = modal
+        .data
+        .custom_id
+        .strip_prefix(DUPLICATE_MODAL_PREFIX)
+        .and_then(|id| id.parse::<u64>().ok())
+    else {
+        return Ok(());
+    };
+    let Some((thread, project)) =
+        crate::thread_events::resolve_thread(http, ChannelId::new(thread_id), config, store)
+            .await?
+    else {
+        return Ok(());
+    };
+
+    let input = modal
+        .data
+        .components
+        .iter()
+        .flat_map(|row| row.components.iter())
+        .find_map(|component| match component {
+            ActionRowComponent::InputText(input) if input.custom_id == ISSUE_INPUT_ID => {
+                input.value.clone()
+            }
+            _ => None,
+        })
+        .unwrap_or_default();
+    let Ok(issue_number) = input.trim().trim_start_matches('#').parse::<u64>() else {
+        modal
+            .create_response(
+                http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content(format!("'{input}' is not an issue number"))
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+        return Ok(());
+    };
+
+    modal.defer(http).await?;
+    let github = crate::github_app::create_project_client(config, &project).await?;
+    let issue =
+        crate::filing::link_duplicate_thread(&github, http, &project, &thread, issue_number)
+            .await?;
+    store.upsert_thread_issue(thread_id, &project_key(&project), issue.number)?;
+    store.set_triage_status(thread_id, &project_key(&project), STATUS_DUPLICATE)?;
+
+    modal
+        .edit_response(
+            http,
+            EditInteractionResponse::new()
+                .content(format!(
+                    "🔁 Merged by <@{}> into issue #{}",
+                    modal.user.id, issue.number
+                ))
+                .components(vec![]),
+        )
+        .await?;
+    record_decision(
+        audit,
+        &project,
+        thread_id,
+        &modal.user.name,
+        &format!("duplicate of #{}", issue.number),
+    )
+    .await;
+
+    Ok(())
+}
+
+async fn resolve_message(
+    http: &Http,
+    component: &ComponentInteraction,
+    content: String,
+) -> Result<()> {
+    component
+        .edit_response(
+            http,
+            EditInteractionResponse::new()
+                .content(content)
+                .components(vec![]),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Tell only the moderator who pressed the button
+async fn notice(http: &Http, component: &ComponentInteraction, content: &str) -> Result<()> {
+    component
+        .create_followup(
+            http,
+            CreateInteractionResponseFollowup::new()
+                .content(content)
+                .ephemeral(true),
+        )
+        .await?;
+    Ok(())
+}
+
+async fn record_decision(
+    audit: &AuditLog,
+    project: &Project,
+    thread_id: u64,
+    actor: &str,
+    decision: &str,
+) {
+    audit
+        .record(
+            AuditEntry::new(project, AuditAction::TriageDecision)
+                .thread(thread_id)
+                .actor(actor)
+                .details(decision),
+        )
+        .await;
+}
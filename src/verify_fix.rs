@@ -0,0 +1,275 @@
+use anyhow::Result;
+use serenity::all::*;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::audit::{AuditAction, AuditEntry, AuditLog};
+use crate::config::{Config, Project};
+use crate::store::Store;
+
+const CONFIRM_PREFIX: &str = "verify_fix:confirm:";
+const REJECT_PREFIX: &str = "verify_fix:reject:";
+const FEEDBACK_PREFIX: &str = "verify_fix_feedback:";
+const FEEDBACK_INPUT_ID: &str = "feedback";
+
+/// Decide whether a thread with a closed issue can be locked, asking the reporter first.
+///
+/// Returns true once the reporter prompt has gone unanswered past the project's timeout.
+pub async fn should_close(
+    discord: &Http,
+    project: &Project,
+    thread_id: ChannelId,
+    issue_number: u64,
+    messages: &[Message],
+) -> Result<bool> {
+    let pending_prompt = messages.iter().find(|msg| {
+        msg.author.bot
+            && !msg.components.is_empty()
+            && msg
+                .embeds
+                .iter()
+                .any(|e| e.title.as_deref() == Some(crate::constants::MSG_VERIFY_FIX))
+    });
+
+    let Some(prompt) = pending_prompt else {
+        post_prompt(discord, thread_id, issue_number).await?;
+        info!(
+            "Asked reporter to verify fix for issue #{} in thread {}",
+            issue_number, thread_id
+        );
+        return Ok(false);
+    };
+
+    let age_secs = chrono::Utc::now().timestamp() - prompt.timestamp.unix_timestamp();
+    if age_secs < (project.verify_fix_timeout_hours() * 3600) as i64 {
+        return Ok(false);
+    }
+
+    // No answer in time: retire the buttons and let the thread close
+    thread_id
+        .edit_message(discord, prompt.id, EditMessage::new().components(vec![]))
+        .await?;
+
+    Ok(true)
+}
+
+async fn post_prompt(discord: &Http, thread_id: ChannelId, issue_number: u64) -> Result<()> {
+    let embed = CreateEmbed::new()
+        .title(crate::constants::MSG_VERIFY_FIX)
+        .description(format!(
+            "Issue #{issue_number} was closed on GitHub. Can you confirm the problem is fixed?"
+        ))
+        .color(crate::constants::COLOR_SUCCESS);
+
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{CONFIRM_PREFIX}{issue_number}"))
+            .label("Fixed")
+            .emoji('✅')
+            .style(ButtonStyle::Success),
+        CreateButton::new(format!("{REJECT_PREFIX}{issue_number}"))
+            .label("Still broken")
+            .emoji('❌')
+            .style(ButtonStyle::Danger),
+    ]);
+
+    thread_id
+        .send_message(
+            discord,
+            CreateMessage::new().embed(embed).components(vec![buttons]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub fn is_verify_fix_component(custom_id: &str) -> bool {
+    custom_id.starts_with(CONFIRM_PREFIX) || custom_id.starts_with(REJECT_PREFIX)
+}
+
+pub fn is_verify_fix_modal(custom_id: &str) -> bool {
+    custom_id.starts_with(FEEDBACK_PREFIX)
+}
+
+pub async fn handle_component(
+    http: &Http,
+    component: &ComponentInteraction,
+    config: &Arc<Config>,
+    store: &Store,
+    audit: &AuditLog,
+) -> Result<()> {
+    let custom_id = component.data.custom_id.as_str();
+    let (thread, project) =
+        match crate::thread_events::resolve_thread(http, component.channel_id, config, store)
+            .await?
+        {
+            Some(found) => found,
+            None => return Ok(()),
+        };
+
+    if !may_answer(
+        &thread,
+        component.user.id,
+        component.member.as_ref(),
+        &project,
+    ) {
+        component
+            .create_response(
+                http,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content("Only the reporter can verify this fix")
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    if let Some(issue_number) = custom_id.strip_prefix(CONFIRM_PREFIX) {
+        component
+            .create_response(
+                http,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .content(format!("✅ Fix confirmed by <@{}>", component.user.id))
+                        .components(vec![]),
+                ),
+            )
+            .await?;
+
+        crate::sync::close_thread(http, &project, thread.id).await?;
+        info!(
+            "Reporter confirmed fix for issue #{} - locked thread {}",
+            issue_number, thread.id
+        );
+        let mut entry = AuditEntry::new(&project, AuditAction::ThreadLocked)
+            .thread(thread.id.get())
+            .actor(component.user.name.clone())
+            .details("reporter confirmed fix");
+        if let Ok(number) = issue_number.parse() {
+            entry = entry.issue(number);
+        }
+        audit.record(entry).await;
+    } else if let Some(issue_number) = custom_id.strip_prefix(REJECT_PREFIX) {
+        let modal = CreateModal::new(
+            format!("{FEEDBACK_PREFIX}{issue_number}"),
+            "What is still broken?",
+        )
+        .components(vec![CreateActionRow::InputText(
+            CreateInputText::new(InputTextStyle::Paragraph, "Details", FEEDBACK_INPUT_ID)
+                .placeholder("Describe what still happens after the fix")
+                .required(true),
+        )]);
+
+        component
+            .create_response(http, CreateInteractionResponse::Modal(modal))
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn handle_modal(
+    http: &Http,
+    modal: &ModalInteraction,
+    config: &Arc<Config>,
+    store: &Store,
+    audit: &AuditLog,
+) -> Result<()> {
+    let Some(issue_number) = modal
+        .data
+        .custom_id
+        .strip_prefix(FEEDBACK_PREFIX)
+        .and_then(|n| n.parse::<u64>().ok())
+    else {
+        return Ok(());
+    };
+
+    let (thread, project) =
+        match crate::thread_events::resolve_thread(http, modal.channel_id, config, store).await? {
+            Some(found) => found,
+            None => return Ok(()),
+        };
+
+    let feedback = modal
+        .data
+        .components
+        .iter()
+        .flat_map(|row| row.components.iter())
+        .find_map(|component| match component {
+            ActionRowComponent::InputText(input) if input.custom_id == FEEDBACK_INPUT_ID => {
+                input.value.clone()
+            }
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    modal
+        .create_response(
+            http,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(format!(
+                        "❌ <@{}> reported the problem persists, reopening the issue",
+                        modal.user.id
+                    ))
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+
+    let quoted = feedback
+        .lines()
+        .map(|line| format!("> {line}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let comment = format!(
+        "❌ **{}** reports the fix did not resolve this on Discord:\n\n{}",
+        modal.user.name, quoted
+    );
+
+    let github = crate::github_app::create_project_client(config, &project).await?;
+    let issues = github.issues(&project.github_owner, &project.github_repo);
+    issues.create_comment(issue_number, comment).await?;
+    issues
+        .update(issue_number)
+        .state(octocrab::models::IssueState::Open)
+        .send()
+        .await?;
+
+    info!(
+        "Reopened issue #{} after reporter feedback in thread {}",
+        issue_number, thread.id
+    );
+    audit
+        .record(
+            AuditEntry::new(&project, AuditAction::IssueReopened)
+                .thread(thread.id.get())
+                .issue(issue_number)
+                .actor(modal.user.name.clone())
+                .details("reporter says fix did not work"),
+        )
+        .await;
+
+    Ok(())
+}
+
+/// The thread owner, or members holding the project's allowed role, may answer
+fn may_answer(
+    thread: &GuildChannel,
+    user_id: UserId,
+    member: Option<&Member>,
+    project: &Project,
+) -> bool {
+    if thread.owner_id == Some(user_id) {
+        return true;
+    }
+
+    match (&project.allowed_role_id, member) {
+        (Some(role_id), Some(member)) => member
+            .roles
+            .iter()
+            .any(|role| role.get().to_string() == *role_id),
+        _ => false,
+    }
+}